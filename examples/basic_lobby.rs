@@ -199,7 +199,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     // ── Disconnect ───────────────────────────────────
                     SignalFishEvent::Disconnected { reason, .. } => {
-                        tracing::warn!("Disconnected: {}", reason.as_deref().unwrap_or("unknown"));
+                        tracing::warn!("Disconnected: {reason}");
                         break;
                     }
 
@@ -167,10 +167,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 tracing::info!("Event: Authenticated — app_name={app_name}");
             }
             SignalFishEvent::Disconnected { reason, .. } => {
-                tracing::info!(
-                    "Event: Disconnected — {}",
-                    reason.as_deref().unwrap_or("clean")
-                );
+                tracing::info!("Event: Disconnected — {reason}");
                 break;
             }
             other => {
@@ -0,0 +1,92 @@
+//! Micro-benchmark for game-data decoding.
+//!
+//! Times [`SignalFishEvent::decode`] against [`SignalFishEvent::try_decode`]
+//! on a valid payload and on the two common failure paths (a type mismatch
+//! and malformed binary JSON). Produces CSV on stdout for analysis.
+//!
+//! ```text
+//! cargo run --release --example decode_bench -- [iters=200000]
+//! ```
+//!
+//! Neither form formats a message on failure — the error is a kind plus the
+//! `serde_json` error, rendered only if displayed — so the two columns should
+//! track each other closely; a gap means the failure path grew extra work.
+
+use std::error::Error;
+use std::hint::black_box;
+use std::time::Instant;
+
+use serde::Deserialize;
+use signal_fish_client::protocol::GameDataEncoding;
+use signal_fish_client::SignalFishEvent;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Move {
+    x: i32,
+    y: i32,
+    facing: f32,
+}
+
+fn game_data(data: serde_json::Value) -> SignalFishEvent {
+    SignalFishEvent::GameData {
+        from_player: uuid::Uuid::nil(),
+        data,
+        seq: None,
+        epoch: None,
+        class: None,
+        key: None,
+    }
+}
+
+fn binary_json(payload: &[u8]) -> SignalFishEvent {
+    SignalFishEvent::GameDataBinary {
+        from_player: uuid::Uuid::nil(),
+        encoding: GameDataEncoding::Json,
+        payload: payload.to_vec(),
+        seq: None,
+        epoch: None,
+    }
+}
+
+/// Mean nanoseconds per call of `f` over `iters` calls.
+fn time_ns(iters: u32, mut f: impl FnMut() -> bool) -> f64 {
+    let start = Instant::now();
+    let mut ok = 0_u32;
+    for _ in 0..iters {
+        ok += u32::from(black_box(f()));
+    }
+    black_box(ok);
+    start.elapsed().as_nanos() as f64 / f64::from(iters)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut iters: u32 = 200_000;
+    for arg in std::env::args().skip(1) {
+        match arg.split_once('=') {
+            Some(("iters", value)) => iters = value.parse()?,
+            _ => return Err(format!("unknown option `{arg}`").into()),
+        }
+    }
+
+    let cases = [
+        (
+            "valid",
+            game_data(serde_json::json!({ "x": 3, "y": -1, "facing": 0.5 })),
+        ),
+        (
+            "mismatch",
+            game_data(serde_json::json!({ "x": "three", "y": -1, "facing": 0.5 })),
+        ),
+        ("syntax", binary_json(br#"{"x": 3, "y": -1, facing}"#)),
+    ];
+
+    println!("case,iters,decode_ns,try_decode_ns");
+    for (name, event) in &cases {
+        let event = black_box(event);
+        let decode = time_ns(iters, || event.decode::<Move>().is_ok());
+        let try_decode = time_ns(iters, || event.try_decode::<Move>().is_some());
+        println!("{name},{iters},{decode:.1},{try_decode:.1}");
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! Heartbeat latency while a large frame is parsed, with and without
+//! `SignalFishConfig::decode_pipeline`.
+//!
+//! An in-process server authenticates a client that pings every
+//! `interval_ms`, then sends one `GameData` frame of about `mb` megabytes. The
+//! bench records when each `Ping` leaves the client and prints, as CSV, how
+//! long the frame took to surface as an event and the longest gap between
+//! consecutive pings around it.
+//!
+//! ```text
+//! cargo run --release --example pipeline_bench -- [mb=5] [interval_ms=10] [runs=5]
+//! ```
+//!
+//! Inline, the transport loop parses the frame itself, so the longest gap
+//! grows with the parse time; with the pipeline it should stay near
+//! `interval_ms`. The runtime is single-threaded, the worst case for inline
+//! parsing.
+
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use signal_fish_client::protocol::{ClientMessage, RateLimitInfo, ServerMessage};
+use signal_fish_client::transport::TransportFrame;
+use signal_fish_client::{
+    SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent, Transport,
+};
+use tokio::sync::mpsc;
+
+/// Feeds server frames from a channel and timestamps outgoing pings.
+struct BenchTransport {
+    incoming: mpsc::UnboundedReceiver<String>,
+    pings: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl Transport for BenchTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if let Some(TransportFrame::Text(message)) = frame.take() {
+            if matches!(serde_json::from_str(&message), Ok(ClientMessage::Ping)) {
+                if let Ok(mut pings) = self.pings.lock() {
+                    pings.push(Instant::now());
+                }
+            }
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.incoming
+            .poll_recv(cx)
+            .map(|message| message.map(|message| Ok(TransportFrame::Text(message))))
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// A `GameData` frame of roughly `bytes` bytes of small JSON objects.
+fn large_frame(bytes: usize) -> Result<String, serde_json::Error> {
+    let units: Vec<_> = (0..bytes / 40)
+        .map(|i| serde_json::json!({ "x": i, "y": -1, "name": format!("unit-{i}") }))
+        .collect();
+    serde_json::to_string(&ServerMessage::GameData {
+        from_player: uuid::Uuid::nil(),
+        data: serde_json::Value::Array(units),
+        seq: None,
+        epoch: None,
+        class: None,
+        key: None,
+    })
+}
+
+/// Milliseconds until the frame surfaced, and the longest gap between pings
+/// spanning it.
+async fn run(
+    pipeline: bool,
+    frame: &str,
+    interval: Duration,
+) -> Result<(f64, f64), Box<dyn Error>> {
+    let (server, incoming) = mpsc::unbounded_channel();
+    let pings = Arc::new(Mutex::new(Vec::new()));
+    let transport = BenchTransport {
+        incoming,
+        pings: Arc::clone(&pings),
+    };
+    let mut config = SignalFishConfig::new("mb_bench").with_ping_interval(interval);
+    if pipeline {
+        config = config.with_decode_pipeline(4);
+    }
+    let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+    server.send(serde_json::to_string(&ServerMessage::Authenticated {
+        app_name: "bench".into(),
+        organization: None,
+        rate_limits: RateLimitInfo {
+            per_minute: 60,
+            per_hour: 1000,
+            per_day: 10000,
+        },
+    })?)?;
+    while !matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. }) | None
+    ) {}
+    tokio::time::sleep(interval * 3).await;
+
+    let sent_at = Instant::now();
+    server.send(frame.to_owned())?;
+    // Held until the end: dropping a large value blocks the thread too.
+    let received = loop {
+        match events.recv().await {
+            Some(event @ SignalFishEvent::GameData { .. }) => break event,
+            Some(_) => {}
+            None => return Err("client disconnected".into()),
+        }
+    };
+    let surfaced = sent_at.elapsed();
+    tokio::time::sleep(interval * 3).await;
+    client.shutdown().await;
+    drop(received);
+
+    let pings = pings.lock().map_err(|_| "ping log poisoned")?.clone();
+    let max_gap = pings
+        .iter()
+        .zip(pings.iter().skip(1))
+        .filter(|(_, later)| **later >= sent_at)
+        .map(|(earlier, later)| *later - *earlier)
+        .max()
+        .unwrap_or_default();
+    Ok((surfaced.as_secs_f64() * 1e3, max_gap.as_secs_f64() * 1e3))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let (mut mb, mut interval_ms, mut runs) = (5_usize, 10_u64, 5_u32);
+    for arg in std::env::args().skip(1) {
+        match arg.split_once('=') {
+            Some(("mb", value)) => mb = value.parse()?,
+            Some(("interval_ms", value)) => interval_ms = value.parse()?,
+            Some(("runs", value)) => runs = value.parse()?,
+            _ => return Err(format!("unknown option `{arg}`").into()),
+        }
+    }
+
+    let frame = large_frame(mb * 1024 * 1024)?;
+    let interval = Duration::from_millis(interval_ms);
+    println!("mode,run,frame_bytes,surfaced_ms,max_ping_gap_ms");
+    for run_index in 0..runs {
+        for (mode, pipeline) in [("inline", false), ("pipeline", true)] {
+            let (surfaced, gap) = run(pipeline, &frame, interval).await?;
+            println!("{mode},{run_index},{},{surfaced:.1},{gap:.1}", frame.len());
+        }
+    }
+    Ok(())
+}
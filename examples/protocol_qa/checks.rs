@@ -0,0 +1,1073 @@
+//! Composable protocol checks and the runner that reports on them.
+//!
+//! A [`Check`] is one named step of the walkthrough. It runs against a shared
+//! [`QaContext`] holding the connections and the room built so far, and names
+//! the checks it requires: a check whose prerequisite did not pass is skipped
+//! instead of being run against a half-built session. [`run`] executes checks
+//! in order under a per-check deadline and collects a [`Report`].
+//!
+//! Connections come from a [`Connector`], so the same suite runs against a
+//! real deployment and against the in-process fake server the tests use.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use signal_fish_client::protocol::{GameDataEncoding, PlayerId, RoomId};
+use signal_fish_client::{
+    ErrorCode, JoinRoomParams, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent,
+};
+use tokio::sync::mpsc;
+
+/// Events a [`Peer`] keeps for later checks before dropping the oldest.
+const BACKLOG_LIMIT: usize = 256;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// A started client and its event receiver.
+pub type Started = (SignalFishClient, mpsc::Receiver<SignalFishEvent>);
+
+/// Opens client connections to the server under test.
+pub trait Connector {
+    /// Connect a new transport and start a client on it with `config`.
+    fn open(&mut self, config: SignalFishConfig)
+        -> BoxFuture<'_, Result<Started, SignalFishError>>;
+}
+
+/// Knobs shared by every check.
+#[derive(Debug, Clone)]
+pub struct QaOptions {
+    pub app_id: String,
+    pub game_name: String,
+    /// How long one expected event may take to arrive.
+    pub event_timeout: Duration,
+    /// How long one whole check may take.
+    pub check_timeout: Duration,
+}
+
+impl QaOptions {
+    pub fn new(app_id: impl Into<String>) -> Self {
+        Self {
+            app_id: app_id.into(),
+            game_name: "protocol-qa".to_string(),
+            event_timeout: Duration::from_secs(5),
+            check_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// One client connection and the events no check has claimed yet.
+pub struct Peer {
+    pub client: SignalFishClient,
+    events: mpsc::Receiver<SignalFishEvent>,
+    backlog: VecDeque<SignalFishEvent>,
+    timeout: Duration,
+}
+
+impl Peer {
+    fn new(started: Started, timeout: Duration) -> Self {
+        let (client, events) = started;
+        Self {
+            client,
+            events,
+            backlog: VecDeque::new(),
+            timeout,
+        }
+    }
+
+    /// Wait for the first event `pick` accepts, looking at unclaimed events
+    /// first. Events it passes over stay queued for later checks, so the
+    /// order in which two checks wait does not have to match arrival order.
+    pub async fn wait_for<T>(
+        &mut self,
+        what: &str,
+        mut pick: impl FnMut(&SignalFishEvent) -> Option<T>,
+    ) -> Result<T, String> {
+        let claimed = self
+            .backlog
+            .iter()
+            .enumerate()
+            .find_map(|(index, event)| pick(event).map(|found| (index, found)));
+        if let Some((index, found)) = claimed {
+            self.backlog.remove(index);
+            return Ok(found);
+        }
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        loop {
+            let event = match tokio::time::timeout_at(deadline, self.events.recv()).await {
+                Ok(Some(event)) => event,
+                Ok(None) => return Err(format!("event stream ended before {what}")),
+                Err(_) => {
+                    return Err(format!(
+                        "no {what} within {:?}{}",
+                        self.timeout,
+                        self.unclaimed()
+                    ))
+                }
+            };
+            if let Some(found) = pick(&event) {
+                return Ok(found);
+            }
+            if let SignalFishEvent::Disconnected { reason, .. } = &event {
+                return Err(format!("disconnected before {what}: {reason}"));
+            }
+            if self.backlog.len() == BACKLOG_LIMIT {
+                self.backlog.pop_front();
+            }
+            self.backlog.push_back(event);
+        }
+    }
+
+    async fn wait_for_authenticated(&mut self) -> Result<String, String> {
+        self.wait_for("Authenticated", |event| match event {
+            SignalFishEvent::Authenticated { app_name, .. } => Some(Ok(app_name.clone())),
+            SignalFishEvent::AuthenticationError { error, error_code } => Some(Err(format!(
+                "authentication rejected ({}): {error}",
+                error_code.key()
+            ))),
+            _ => None,
+        })
+        .await?
+    }
+
+    /// The newest unclaimed event kinds, for timeout messages.
+    fn unclaimed(&self) -> String {
+        if self.backlog.is_empty() {
+            return String::new();
+        }
+        // `Debug` on an event prints only its variant name.
+        let names: Vec<String> = self
+            .backlog
+            .iter()
+            .rev()
+            .take(8)
+            .map(|event| format!("{event:?}"))
+            .collect();
+        format!("; unclaimed: {}", names.join(", "))
+    }
+}
+
+/// The connections and room facts the checks build up.
+pub struct QaContext {
+    connector: Box<dyn Connector>,
+    options: QaOptions,
+    host: Option<Peer>,
+    guest: Option<Peer>,
+    spectator: Option<Peer>,
+    room_id: Option<RoomId>,
+    room_code: Option<String>,
+    host_id: Option<PlayerId>,
+    guest_id: Option<PlayerId>,
+    guest_token: Option<String>,
+}
+
+impl QaContext {
+    pub fn new(connector: impl Connector + 'static, options: QaOptions) -> Self {
+        Self {
+            connector: Box::new(connector),
+            options,
+            host: None,
+            guest: None,
+            spectator: None,
+            room_id: None,
+            room_code: None,
+            host_id: None,
+            guest_id: None,
+            guest_token: None,
+        }
+    }
+
+    /// Connect a new client. Each one asks for protocol v3 and MessagePack so
+    /// the binary check can run; a server that offers less negotiates down.
+    async fn open(&mut self, label: &str) -> Result<Peer, String> {
+        let mut config = SignalFishConfig::new(self.options.app_id.clone())
+            .enable_v3()
+            .with_label(label);
+        config.game_data_format = Some(GameDataEncoding::MessagePack);
+        let started = self
+            .connector
+            .open(config)
+            .await
+            .map_err(|error| format!("{label} could not connect: {error}"))?;
+        Ok(Peer::new(started, self.options.event_timeout))
+    }
+
+    async fn open_authenticated(&mut self, label: &str) -> Result<Peer, String> {
+        let mut peer = self.open(label).await?;
+        peer.wait_for_authenticated()
+            .await
+            .map_err(|reason| format!("{label}: {reason}"))?;
+        Ok(peer)
+    }
+
+    /// Shut every remaining connection down gracefully.
+    pub async fn close(&mut self) {
+        for peer in [&mut self.host, &mut self.guest, &mut self.spectator] {
+            if let Some(mut peer) = peer.take() {
+                peer.client.shutdown().await;
+            }
+        }
+    }
+}
+
+/// The verdict of one check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed(String),
+    Skipped(String),
+    Failed(String),
+}
+
+/// A check body. `Err` is shorthand for [`Outcome::Failed`].
+pub type CheckFn = for<'a> fn(&'a mut QaContext) -> BoxFuture<'a, Result<Outcome, String>>;
+
+/// One named step of the walkthrough.
+#[derive(Clone, Copy)]
+pub struct Check {
+    pub name: &'static str,
+    /// Checks that must have passed for this one to run.
+    pub requires: &'static [&'static str],
+    pub run: CheckFn,
+}
+
+/// The outcome of one check and how long it took.
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Every check's outcome, in run order.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub checks: Vec<CheckReport>,
+}
+
+impl Report {
+    /// The outcome of the check called `name`, if it ran.
+    pub fn outcome(&self, name: &str) -> Option<&Outcome> {
+        self.checks
+            .iter()
+            .find(|check| check.name == name)
+            .map(|check| &check.outcome)
+    }
+
+    fn count(&self, matches: fn(&Outcome) -> bool) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| matches(&check.outcome))
+            .count()
+    }
+
+    /// Whether no check failed. Skipped checks do not fail the run.
+    pub fn is_success(&self) -> bool {
+        self.count(|outcome| matches!(outcome, Outcome::Failed(_))) == 0
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        for check in &self.checks {
+            let (label, detail) = match &check.outcome {
+                Outcome::Passed(detail) => ("PASS", detail),
+                Outcome::Skipped(detail) => ("SKIP", detail),
+                Outcome::Failed(detail) => ("FAIL", detail),
+            };
+            writeln!(
+                f,
+                "{label}  {:<width$}  {:>6} ms  {detail}",
+                check.name,
+                check.elapsed.as_millis()
+            )?;
+        }
+        write!(
+            f,
+            "{} passed, {} failed, {} skipped",
+            self.count(|outcome| matches!(outcome, Outcome::Passed(_))),
+            self.count(|outcome| matches!(outcome, Outcome::Failed(_))),
+            self.count(|outcome| matches!(outcome, Outcome::Skipped(_))),
+        )
+    }
+}
+
+/// Run `checks` in order against `cx`.
+pub async fn run(checks: &[Check], cx: &mut QaContext) -> Report {
+    let mut report = Report::default();
+    for check in checks {
+        let started = Instant::now();
+        let missing = check
+            .requires
+            .iter()
+            .find(|name| !matches!(report.outcome(name), Some(Outcome::Passed(_))));
+        let outcome = match missing {
+            Some(name) => Outcome::Skipped(format!("requires `{name}`")),
+            None => {
+                let deadline = cx.options.check_timeout;
+                match tokio::time::timeout(deadline, (check.run)(cx)).await {
+                    Ok(Ok(outcome)) => outcome,
+                    Ok(Err(reason)) => Outcome::Failed(reason),
+                    Err(_) => Outcome::Failed(format!("did not finish within {deadline:?}")),
+                }
+            }
+        };
+        tracing::info!(check = check.name, ?outcome, "check finished");
+        report.checks.push(CheckReport {
+            name: check.name,
+            outcome,
+            elapsed: started.elapsed(),
+        });
+    }
+    report
+}
+
+// ── The standard suite ──────────────────────────────────────────────
+
+/// The full walkthrough: a host, a second player, and a spectator in one room.
+pub const STANDARD_SUITE: &[Check] = &[
+    Check {
+        name: "connect",
+        requires: &[],
+        run: connect,
+    },
+    Check {
+        name: "authenticate",
+        requires: &["connect"],
+        run: authenticate,
+    },
+    Check {
+        name: "protocol_info",
+        requires: &["authenticate"],
+        run: protocol_info,
+    },
+    Check {
+        name: "ping",
+        requires: &["authenticate"],
+        run: ping,
+    },
+    Check {
+        name: "time_sync",
+        requires: &["authenticate"],
+        run: time_sync,
+    },
+    Check {
+        name: "create_room",
+        requires: &["authenticate"],
+        run: create_room,
+    },
+    Check {
+        name: "join_room",
+        requires: &["create_room"],
+        run: join_room,
+    },
+    Check {
+        name: "ready",
+        requires: &["join_room"],
+        run: ready,
+    },
+    Check {
+        name: "authority",
+        requires: &["join_room"],
+        run: authority,
+    },
+    Check {
+        name: "json_game_data",
+        requires: &["join_room"],
+        run: json_game_data,
+    },
+    Check {
+        name: "binary_game_data",
+        requires: &["join_room"],
+        run: binary_game_data,
+    },
+    Check {
+        name: "spectate",
+        requires: &["create_room"],
+        run: spectate,
+    },
+    Check {
+        name: "leave_spectator",
+        requires: &["spectate"],
+        run: leave_spectator,
+    },
+    Check {
+        name: "intentional_error",
+        requires: &["join_room"],
+        run: intentional_error,
+    },
+    Check {
+        name: "reconnect",
+        requires: &["join_room"],
+        run: reconnect,
+    },
+    Check {
+        name: "leave_room",
+        requires: &["join_room"],
+        run: leave_room,
+    },
+];
+
+/// The room facts `create_room` recorded.
+fn room(cx: &QaContext) -> Result<(RoomId, String, PlayerId), String> {
+    match (cx.room_id, &cx.room_code, cx.host_id) {
+        (Some(room_id), Some(code), Some(host_id)) => Ok((room_id, code.clone(), host_id)),
+        _ => Err("no room was created".to_string()),
+    }
+}
+
+fn peer<'a>(peer: &'a mut Option<Peer>, role: &str) -> Result<&'a mut Peer, String> {
+    peer.as_mut().ok_or_else(|| format!("no {role} connection"))
+}
+
+fn connect(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let mut host = cx.open("qa-host").await?;
+        host.wait_for("Connected", |event| {
+            matches!(event, SignalFishEvent::Connected).then_some(())
+        })
+        .await?;
+        cx.host = Some(host);
+        Ok(Outcome::Passed("transport connected".to_string()))
+    })
+}
+
+fn authenticate(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let app_name = peer(&mut cx.host, "host")?.wait_for_authenticated().await?;
+        Ok(Outcome::Passed(format!("app `{app_name}`")))
+    })
+}
+
+fn protocol_info(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let host = peer(&mut cx.host, "host")?;
+        let formats = host
+            .wait_for("ProtocolInfo", |event| match event {
+                SignalFishEvent::ProtocolInfo(info) => Some(info.game_data_formats.clone()),
+                _ => None,
+            })
+            .await?;
+        let version = host
+            .client
+            .negotiated_protocol_version()
+            .map_or_else(|| "v2".to_string(), |version| format!("v{version}"));
+        Ok(Outcome::Passed(format!(
+            "negotiated {version}, game data formats {formats:?}"
+        )))
+    })
+}
+
+fn ping(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let host = peer(&mut cx.host, "host")?;
+        let sent = Instant::now();
+        host.client
+            .ping()
+            .map_err(|error| format!("ping: {error}"))?;
+        host.wait_for("Pong", |event| {
+            matches!(event, SignalFishEvent::Pong).then_some(())
+        })
+        .await?;
+        Ok(Outcome::Passed(format!(
+            "round trip {} ms",
+            sent.elapsed().as_millis()
+        )))
+    })
+}
+
+fn time_sync(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let timeout = cx.options.event_timeout;
+        let host = peer(&mut cx.host, "host")?;
+        let sample = tokio::time::timeout(timeout, host.client.sync_time())
+            .await
+            .map_err(|_| format!("no TimeSyncResponse within {timeout:?}"))?
+            .map_err(|error| format!("sync_time: {error}"))?;
+        Ok(Outcome::Passed(format!(
+            "offset {} ms, round trip {} ms",
+            sample.offset,
+            sample.rtt.as_millis()
+        )))
+    })
+}
+
+fn create_room(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let params = JoinRoomParams::new(cx.options.game_name.clone(), "qa-host")
+            .with_max_players(4)
+            .with_supports_authority(true);
+        let host = peer(&mut cx.host, "host")?;
+        host.client
+            .join_room(params)
+            .map_err(|error| format!("join_room: {error}"))?;
+        let (room_id, room_code, host_id) = host
+            .wait_for("RoomJoined", |event| match event {
+                SignalFishEvent::RoomJoined {
+                    room_id,
+                    room_code,
+                    player_id,
+                    ..
+                } => Some(Ok((*room_id, room_code.clone(), *player_id))),
+                SignalFishEvent::RoomJoinFailed { reason, .. } => {
+                    Some(Err(format!("room creation failed: {reason}")))
+                }
+                _ => None,
+            })
+            .await??;
+        cx.room_id = Some(room_id);
+        cx.room_code = Some(room_code.clone());
+        cx.host_id = Some(host_id);
+        Ok(Outcome::Passed(format!("room {room_code}")))
+    })
+}
+
+fn join_room(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (room_id, room_code, _) = room(cx)?;
+        let mut guest = cx.open_authenticated("qa-guest").await?;
+        let params = JoinRoomParams::new(cx.options.game_name.clone(), "qa-guest")
+            .with_room_code(room_code.clone());
+        guest
+            .client
+            .join_room(params)
+            .map_err(|error| format!("join_room: {error}"))?;
+        let (guest_id, token) = guest
+            .wait_for("RoomJoined", |event| match event {
+                SignalFishEvent::RoomJoined {
+                    room_id: joined,
+                    player_id,
+                    reconnection_token,
+                    ..
+                } if *joined == room_id => Some(Ok((*player_id, reconnection_token.clone()))),
+                SignalFishEvent::RoomJoinFailed { reason, .. } => {
+                    Some(Err(format!("joining {room_code} failed: {reason}")))
+                }
+                _ => None,
+            })
+            .await??;
+        cx.guest = Some(guest);
+        cx.guest_id = Some(guest_id);
+        cx.guest_token = token;
+        peer(&mut cx.host, "host")?
+            .wait_for("PlayerJoined for the guest", |event| match event {
+                SignalFishEvent::PlayerJoined { player } => (player.id == guest_id).then_some(()),
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed(format!("guest {guest_id} joined")))
+    })
+}
+
+fn ready(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (_, _, host_id) = room(cx)?;
+        let host_ready = |event: &SignalFishEvent| match event {
+            SignalFishEvent::LobbyStateChanged {
+                lobby_state,
+                ready_players,
+                ..
+            } if ready_players.contains(&host_id) => Some(lobby_state.clone()),
+            _ => None,
+        };
+        let host = peer(&mut cx.host, "host")?;
+        host.client
+            .set_ready()
+            .map_err(|error| format!("set_ready: {error}"))?;
+        let lobby_state = host.wait_for("LobbyStateChanged", host_ready).await?;
+        peer(&mut cx.guest, "guest")?
+            .wait_for("LobbyStateChanged on the guest", host_ready)
+            .await?;
+        Ok(Outcome::Passed(format!(
+            "host ready, lobby {lobby_state:?}"
+        )))
+    })
+}
+
+fn authority(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (_, _, host_id) = room(cx)?;
+        let host = peer(&mut cx.host, "host")?;
+        host.client
+            .request_authority(true)
+            .map_err(|error| format!("request_authority: {error}"))?;
+        host.wait_for("AuthorityResponse", |event| match event {
+            SignalFishEvent::AuthorityResponse { granted: true, .. } => Some(Ok(())),
+            SignalFishEvent::AuthorityResponse {
+                reason, error_code, ..
+            } => Some(Err(format!(
+                "authority denied ({}): {}",
+                error_code.as_ref().map_or("no code", ErrorCode::key),
+                reason.as_deref().unwrap_or("no reason given")
+            ))),
+            _ => None,
+        })
+        .await??;
+        peer(&mut cx.guest, "guest")?
+            .wait_for("AuthorityChanged on the guest", |event| match event {
+                SignalFishEvent::AuthorityChanged {
+                    authority_player, ..
+                } => (*authority_player == Some(host_id)).then_some(()),
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed("host granted authority".to_string()))
+    })
+}
+
+fn json_game_data(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (_, _, host_id) = room(cx)?;
+        let data = serde_json::json!({ "qa": "json_game_data", "values": [1, 2, 3] });
+        peer(&mut cx.host, "host")?
+            .client
+            .send_game_data(data.clone())
+            .map_err(|error| format!("send_game_data: {error}"))?;
+        // A guest that negotiated MessagePack may receive the payload
+        // transcoded, so compare the decoded value rather than the variant.
+        peer(&mut cx.guest, "guest")?
+            .wait_for("GameData from the host", |event| match event {
+                SignalFishEvent::GameData { from_player, .. }
+                | SignalFishEvent::GameDataBinary { from_player, .. }
+                    if *from_player == host_id =>
+                {
+                    event
+                        .decode::<serde_json::Value>()
+                        .ok()
+                        .filter(|received| *received == data)
+                }
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed(
+            "guest received the host's JSON".to_string(),
+        ))
+    })
+}
+
+fn binary_game_data(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (_, _, host_id) = room(cx)?;
+        let payload = b"protocol-qa binary payload".to_vec();
+        let sent = peer(&mut cx.host, "host")?
+            .client
+            .send_binary_game_data(payload.clone());
+        match sent {
+            Ok(()) => {}
+            Err(
+                error @ (SignalFishError::ProtocolUnsupported { .. }
+                | SignalFishError::BinaryFormatNotNegotiated),
+            ) => return Ok(Outcome::Skipped(error.to_string())),
+            Err(error) => return Err(format!("send_binary_game_data: {error}")),
+        }
+        peer(&mut cx.guest, "guest")?
+            .wait_for("GameDataBinary from the host", |event| match event {
+                SignalFishEvent::GameDataBinary {
+                    from_player,
+                    payload: received,
+                    ..
+                } if *from_player == host_id && *received == payload => Some(()),
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed(format!(
+            "guest received {} bytes",
+            payload.len()
+        )))
+    })
+}
+
+fn spectate(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (room_id, room_code, _) = room(cx)?;
+        let mut spectator = cx.open_authenticated("qa-spectator").await?;
+        spectator
+            .client
+            .join_as_spectator(
+                cx.options.game_name.clone(),
+                room_code.clone(),
+                "qa-spectator".to_string(),
+            )
+            .map_err(|error| format!("join_as_spectator: {error}"))?;
+        let spectator_id = spectator
+            .wait_for("SpectatorJoined", |event| match event {
+                SignalFishEvent::SpectatorJoined {
+                    room_id: joined,
+                    spectator_id,
+                    ..
+                } if *joined == room_id => Some(Ok(*spectator_id)),
+                SignalFishEvent::SpectatorJoinFailed { reason, .. } => {
+                    Some(Err(format!("spectating {room_code} failed: {reason}")))
+                }
+                _ => None,
+            })
+            .await??;
+        cx.spectator = Some(spectator);
+        peer(&mut cx.host, "host")?
+            .wait_for("NewSpectatorJoined", |event| match event {
+                SignalFishEvent::NewSpectatorJoined { spectator, .. } => {
+                    (spectator.id == spectator_id).then_some(())
+                }
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed(format!(
+            "spectator {spectator_id} watching"
+        )))
+    })
+}
+
+fn leave_spectator(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let spectator = peer(&mut cx.spectator, "spectator")?;
+        spectator
+            .client
+            .leave_spectator()
+            .map_err(|error| format!("leave_spectator: {error}"))?;
+        spectator
+            .wait_for("SpectatorLeft", |event| {
+                matches!(event, SignalFishEvent::SpectatorLeft { .. }).then_some(())
+            })
+            .await?;
+        Ok(Outcome::Passed("spectator left".to_string()))
+    })
+}
+
+/// Join the room a second time from the guest, which the server must refuse
+/// with `ALREADY_IN_ROOM`.
+fn intentional_error(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (_, room_code, _) = room(cx)?;
+        let params =
+            JoinRoomParams::new(cx.options.game_name.clone(), "qa-guest").with_room_code(room_code);
+        let guest = peer(&mut cx.guest, "guest")?;
+        guest
+            .client
+            .join_room(params)
+            .map_err(|error| format!("join_room: {error}"))?;
+        let code = guest
+            .wait_for("an ALREADY_IN_ROOM rejection", |event| match event {
+                SignalFishEvent::RoomJoinFailed { error_code, .. } => Some(error_code.clone()),
+                // A generic error may be a leftover from an earlier step, so
+                // only the expected code answers this join.
+                SignalFishEvent::Error {
+                    error_code: Some(ErrorCode::AlreadyInRoom),
+                    ..
+                } => Some(Some(ErrorCode::AlreadyInRoom)),
+                SignalFishEvent::RoomJoined { .. } => Some(None),
+                _ => None,
+            })
+            .await?;
+        match code {
+            Some(ErrorCode::AlreadyInRoom) => {
+                Ok(Outcome::Passed("rejected with ALREADY_IN_ROOM".to_string()))
+            }
+            Some(other) => Err(format!(
+                "rejected with {}, expected ALREADY_IN_ROOM",
+                other.key()
+            )),
+            None => Err("duplicate join was not rejected with an error code".to_string()),
+        }
+    })
+}
+
+/// Drop the guest without a close handshake and resume its seat on a new
+/// connection with the token from its `RoomJoined`.
+fn reconnect(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let (room_id, _, _) = room(cx)?;
+        let Some(token) = cx.guest_token.clone() else {
+            return Ok(Outcome::Skipped(
+                "server issued no reconnection token".to_string(),
+            ));
+        };
+        let guest_id = cx
+            .guest_id
+            .ok_or_else(|| "no guest player id".to_string())?;
+        // Dropping the client aborts its task, which drops the transport
+        // without a close frame: an unexpected disconnect to the server.
+        drop(cx.guest.take());
+        let mut guest = cx.open_authenticated("qa-guest-reconnect").await?;
+        guest
+            .client
+            .reconnect(guest_id, room_id, token)
+            .map_err(|error| format!("reconnect: {error}"))?;
+        let token = guest
+            .wait_for("Reconnected", |event| match event {
+                SignalFishEvent::Reconnected {
+                    room_id: resumed,
+                    reconnection_token,
+                    ..
+                } if *resumed == room_id => Some(Ok(reconnection_token.clone())),
+                SignalFishEvent::Reconnected { room_id: other, .. } => Some(Err(format!(
+                    "reconnected to room {other}, expected {room_id}"
+                ))),
+                SignalFishEvent::ReconnectionFailed { reason, error_code } => Some(Err(format!(
+                    "reconnection failed ({}): {reason}",
+                    error_code.key()
+                ))),
+                _ => None,
+            })
+            .await??;
+        cx.guest = Some(guest);
+        if token.is_some() {
+            cx.guest_token = token;
+        }
+        peer(&mut cx.host, "host")?
+            .wait_for("PlayerReconnected", |event| match event {
+                SignalFishEvent::PlayerReconnected { player_id, .. } => {
+                    (*player_id == guest_id).then_some(())
+                }
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed("guest resumed its seat".to_string()))
+    })
+}
+
+fn leave_room(cx: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+    Box::pin(async move {
+        let guest_id = cx
+            .guest_id
+            .ok_or_else(|| "no guest player id".to_string())?;
+        let guest = peer(&mut cx.guest, "guest")?;
+        guest
+            .client
+            .leave_room()
+            .map_err(|error| format!("leave_room: {error}"))?;
+        guest
+            .wait_for("RoomLeft", |event| {
+                matches!(event, SignalFishEvent::RoomLeft).then_some(())
+            })
+            .await?;
+        peer(&mut cx.host, "host")?
+            .wait_for("PlayerLeft for the guest", |event| match event {
+                SignalFishEvent::PlayerLeft { player_id, .. } => {
+                    (*player_id == guest_id).then_some(())
+                }
+                _ => None,
+            })
+            .await?;
+        Ok(Outcome::Passed("guest left, host notified".to_string()))
+    })
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+mod tests {
+    use super::*;
+    use crate::fake_server::{Behavior, FakeServer};
+
+    fn options() -> QaOptions {
+        let mut options = QaOptions::new("qa-test-app");
+        options.event_timeout = Duration::from_millis(500);
+        options
+    }
+
+    async fn run_against(behavior: Behavior) -> Report {
+        let mut cx = QaContext::new(FakeServer::new(behavior), options());
+        let report = run(STANDARD_SUITE, &mut cx).await;
+        cx.close().await;
+        report
+    }
+
+    fn failure(report: &Report, name: &str) -> String {
+        match report.outcome(name) {
+            Some(Outcome::Failed(reason)) => reason.clone(),
+            other => panic!("expected `{name}` to fail, got {other:?}\n{report}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn standard_suite_passes_against_a_conforming_server() {
+        let report = run_against(Behavior::default()).await;
+
+        assert!(report.is_success(), "{report}");
+        assert_eq!(report.checks.len(), STANDARD_SUITE.len());
+        // The fake server negotiates protocol v2, which has no binary relay.
+        assert!(
+            matches!(
+                report.outcome("binary_game_data"),
+                Some(Outcome::Skipped(_))
+            ),
+            "{report}"
+        );
+        let not_passed: Vec<_> = report
+            .checks
+            .iter()
+            .filter(|check| !matches!(check.outcome, Outcome::Passed(_)))
+            .map(|check| check.name)
+            .collect();
+        assert_eq!(not_passed, ["binary_game_data"], "{report}");
+    }
+
+    #[tokio::test]
+    async fn a_failed_check_skips_everything_that_requires_it() {
+        let report = run_against(Behavior {
+            reject_authentication: true,
+            ..Behavior::default()
+        })
+        .await;
+
+        assert!(!report.is_success());
+        assert!(matches!(
+            report.outcome("connect"),
+            Some(Outcome::Passed(_))
+        ));
+        assert!(failure(&report, "authenticate").contains("INVALID_APP_ID"));
+        for (check, reported) in STANDARD_SUITE.iter().zip(&report.checks).skip(2) {
+            assert_eq!(
+                reported.outcome,
+                Outcome::Skipped(format!("requires `{}`", check.requires[0])),
+                "{report}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn a_missing_reply_fails_only_the_check_waiting_for_it() {
+        let report = run_against(Behavior {
+            answer_pings: false,
+            ..Behavior::default()
+        })
+        .await;
+
+        assert!(
+            failure(&report, "ping").starts_with("no Pong within 500ms"),
+            "{report}"
+        );
+        let failed: Vec<_> = report
+            .checks
+            .iter()
+            .filter(|check| matches!(check.outcome, Outcome::Failed(_)))
+            .map(|check| check.name)
+            .collect();
+        assert_eq!(failed, ["ping"], "{report}");
+    }
+
+    #[tokio::test]
+    async fn a_denial_is_reported_with_the_server_code_and_reason() {
+        let report = run_against(Behavior {
+            grant_authority: false,
+            ..Behavior::default()
+        })
+        .await;
+
+        assert_eq!(
+            failure(&report, "authority"),
+            "authority denied (AUTHORITY_DENIED): authority is pinned"
+        );
+        assert!(matches!(
+            report.outcome("json_game_data"),
+            Some(Outcome::Passed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reconnect_is_skipped_when_the_server_issues_no_token() {
+        let report = run_against(Behavior {
+            issue_tokens: false,
+            ..Behavior::default()
+        })
+        .await;
+
+        assert_eq!(
+            report.outcome("reconnect"),
+            Some(&Outcome::Skipped(
+                "server issued no reconnection token".to_string()
+            ))
+        );
+        assert!(matches!(
+            report.outcome("leave_room"),
+            Some(Outcome::Passed(_))
+        ));
+    }
+
+    fn stall(_: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+        Box::pin(std::future::pending())
+    }
+
+    fn pass(_: &mut QaContext) -> BoxFuture<'_, Result<Outcome, String>> {
+        Box::pin(async { Ok(Outcome::Passed("fine".to_string())) })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_check_that_overruns_its_deadline_fails_and_the_run_continues() {
+        let mut options = options();
+        options.check_timeout = Duration::from_secs(2);
+        let mut cx = QaContext::new(FakeServer::new(Behavior::default()), options);
+        let checks = [
+            Check {
+                name: "stall",
+                requires: &[],
+                run: stall,
+            },
+            Check {
+                name: "after_stall",
+                requires: &["stall"],
+                run: pass,
+            },
+            Check {
+                name: "independent",
+                requires: &[],
+                run: pass,
+            },
+        ];
+
+        let report = run(&checks, &mut cx).await;
+
+        assert_eq!(failure(&report, "stall"), "did not finish within 2s");
+        assert_eq!(
+            report.outcome("after_stall"),
+            Some(&Outcome::Skipped("requires `stall`".to_string()))
+        );
+        assert_eq!(
+            report.outcome("independent"),
+            Some(&Outcome::Passed("fine".to_string()))
+        );
+    }
+
+    #[test]
+    fn report_renders_one_aligned_line_per_check_and_a_summary() {
+        let report = Report {
+            checks: vec![
+                CheckReport {
+                    name: "connect",
+                    outcome: Outcome::Passed("transport connected".to_string()),
+                    elapsed: Duration::from_millis(12),
+                },
+                CheckReport {
+                    name: "binary_game_data",
+                    outcome: Outcome::Skipped("relay-only".to_string()),
+                    elapsed: Duration::from_millis(0),
+                },
+                CheckReport {
+                    name: "ping",
+                    outcome: Outcome::Failed("no Pong".to_string()),
+                    elapsed: Duration::from_millis(5000),
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "PASS  connect               12 ms  transport connected\n\
+             SKIP  binary_game_data       0 ms  relay-only\n\
+             FAIL  ping                5000 ms  no Pong\n\
+             1 passed, 1 failed, 1 skipped"
+        );
+        assert!(!report.is_success());
+    }
+}
@@ -0,0 +1,585 @@
+//! An in-process stand-in for a Signal Fish server, for testing the checks.
+//!
+//! It speaks just enough of protocol v2 for the standard suite: rooms by
+//! code, readiness, authority, JSON relay, spectators, and token reconnects.
+//! [`Behavior`] switches individual answers off to exercise the failure
+//! paths.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use signal_fish_client::protocol::{
+    ClientMessage, GameDataEncoding, LobbyState, PlayerId, PlayerInfo, ProtocolInfoPayload,
+    RateLimitInfo, ReconnectedPayload, RoomId, ServerMessage, SpectatorInfo,
+    SpectatorJoinedPayload,
+};
+use signal_fish_client::testing;
+use signal_fish_client::transport::TransportFrame;
+use signal_fish_client::{
+    ErrorCode, SignalFishClient, SignalFishConfig, SignalFishError, Transport,
+};
+use tokio::sync::mpsc;
+
+use crate::checks::{BoxFuture, Connector, Started};
+
+type Outbox = mpsc::UnboundedSender<String>;
+
+/// Which answers the fake server gives.
+#[derive(Debug, Clone, Copy)]
+pub struct Behavior {
+    pub reject_authentication: bool,
+    pub answer_pings: bool,
+    pub grant_authority: bool,
+    pub issue_tokens: bool,
+}
+
+impl Default for Behavior {
+    fn default() -> Self {
+        Self {
+            reject_authentication: false,
+            answer_pings: true,
+            grant_authority: true,
+            issue_tokens: true,
+        }
+    }
+}
+
+/// A [`Connector`] whose connections all reach one shared fake server.
+pub struct FakeServer {
+    world: Arc<Mutex<World>>,
+}
+
+impl FakeServer {
+    pub fn new(behavior: Behavior) -> Self {
+        Self {
+            world: Arc::new(Mutex::new(World {
+                behavior,
+                rooms: HashMap::new(),
+                next_id: 0,
+            })),
+        }
+    }
+}
+
+impl Connector for FakeServer {
+    fn open(
+        &mut self,
+        config: SignalFishConfig,
+    ) -> BoxFuture<'_, Result<Started, SignalFishError>> {
+        let (to_server, from_client) = mpsc::unbounded_channel();
+        let (to_client, from_server) = mpsc::unbounded_channel();
+        tokio::spawn(serve(Arc::clone(&self.world), from_client, to_client));
+        let transport = Loopback {
+            to_server: Some(to_server),
+            from_server,
+        };
+        Box::pin(async move { Ok(SignalFishClient::start(transport, config)) })
+    }
+}
+
+/// The client half of one connection.
+struct Loopback {
+    to_server: Option<Outbox>,
+    from_server: mpsc::UnboundedReceiver<String>,
+}
+
+impl Transport for Loopback {
+    fn poll_send(
+        &mut self,
+        _cx: &mut Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> Poll<Result<(), SignalFishError>> {
+        let result = match (frame.take(), &self.to_server) {
+            (Some(TransportFrame::Text(text)), Some(to_server)) => to_server
+                .send(text)
+                .map_err(|error| SignalFishError::TransportSend(error.to_string())),
+            (Some(TransportFrame::Binary(_)), _) => Err(SignalFishError::TransportSend(
+                "the fake server speaks text frames only".into(),
+            )),
+            (Some(_), None) => Err(SignalFishError::TransportSend("closed".into())),
+            (None, _) => Ok(()),
+        };
+        Poll::Ready(result)
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.from_server
+            .poll_recv(cx)
+            .map(|text| text.map(|text| Ok(TransportFrame::Text(text))))
+    }
+
+    fn poll_close(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), SignalFishError>> {
+        self.to_server = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+struct Member {
+    info: PlayerInfo,
+    token: Option<String>,
+    /// `None` while the player is disconnected but keeps its seat.
+    outbox: Option<Outbox>,
+}
+
+struct Room {
+    id: RoomId,
+    game_name: String,
+    max_players: u8,
+    authority: Option<PlayerId>,
+    players: Vec<Member>,
+    spectators: Vec<SpectatorInfo>,
+}
+
+impl Room {
+    fn broadcast(&self, except: Option<PlayerId>, message: &ServerMessage) {
+        for member in &self.players {
+            if Some(member.info.id) != except {
+                if let Some(outbox) = &member.outbox {
+                    send(outbox, message);
+                }
+            }
+        }
+    }
+
+    fn ready_players(&self) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .filter(|member| member.info.is_ready)
+            .map(|member| member.info.id)
+            .collect()
+    }
+
+    fn player_infos(&self) -> Vec<PlayerInfo> {
+        self.players
+            .iter()
+            .map(|member| member.info.clone())
+            .collect()
+    }
+
+    fn spectator_infos(&self) -> Vec<SpectatorInfo> {
+        self.spectators.clone()
+    }
+}
+
+struct World {
+    behavior: Behavior,
+    rooms: HashMap<String, Room>,
+    next_id: u128,
+}
+
+impl World {
+    fn next_id(&mut self) -> uuid::Uuid {
+        self.next_id += 1;
+        uuid::Uuid::from_u128(self.next_id)
+    }
+}
+
+/// Where one connection sits.
+enum Seat {
+    Lobby,
+    Player { code: String, id: PlayerId },
+    Spectator { code: String, id: PlayerId },
+}
+
+fn send(outbox: &Outbox, message: &ServerMessage) {
+    if let Ok(text) = serde_json::to_string(message) {
+        let _ = outbox.send(text);
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+        })
+}
+
+async fn serve(
+    world: Arc<Mutex<World>>,
+    mut inbox: mpsc::UnboundedReceiver<String>,
+    outbox: Outbox,
+) {
+    let mut seat = Seat::Lobby;
+    while let Some(text) = inbox.recv().await {
+        let Ok(message) = serde_json::from_str::<ClientMessage>(&text) else {
+            continue;
+        };
+        let Ok(mut world) = world.lock() else {
+            return;
+        };
+        handle(&mut world, &mut seat, &outbox, message);
+    }
+    // The connection dropped. A player keeps its seat for a reconnect; a
+    // spectator is gone.
+    let Ok(mut world) = world.lock() else {
+        return;
+    };
+    match seat {
+        Seat::Player { code, id } => {
+            if let Some(member) = world
+                .rooms
+                .get_mut(&code)
+                .and_then(|room| room.players.iter_mut().find(|member| member.info.id == id))
+            {
+                member.outbox = None;
+            }
+        }
+        Seat::Spectator { code, id } => {
+            if let Some(room) = world.rooms.get_mut(&code) {
+                room.spectators.retain(|spectator| spectator.id != id);
+            }
+        }
+        Seat::Lobby => {}
+    }
+}
+
+fn handle(world: &mut World, seat: &mut Seat, outbox: &Outbox, message: ClientMessage) {
+    let behavior = world.behavior;
+    match message {
+        ClientMessage::Authenticate {
+            app_id,
+            game_data_format,
+            ..
+        } => {
+            if behavior.reject_authentication {
+                send(
+                    outbox,
+                    &ServerMessage::AuthenticationError {
+                        error: format!("unknown app `{app_id}`"),
+                        error_code: ErrorCode::InvalidAppId,
+                    },
+                );
+                return;
+            }
+            send(
+                outbox,
+                &ServerMessage::Authenticated {
+                    app_name: app_id,
+                    organization: None,
+                    rate_limits: RateLimitInfo {
+                        per_minute: 600,
+                        per_hour: 36_000,
+                        per_day: 864_000,
+                    },
+                },
+            );
+            send(
+                outbox,
+                &ServerMessage::ProtocolInfo(ProtocolInfoPayload {
+                    platform: None,
+                    sdk_version: None,
+                    minimum_version: None,
+                    recommended_version: None,
+                    capabilities: vec![],
+                    notes: None,
+                    game_data_formats: vec![],
+                    player_name_rules: None,
+                    protocol_version: None,
+                    min_protocol_version: None,
+                    max_protocol_version: None,
+                    transports: None,
+                    limits: None,
+                }),
+            );
+            // Text frames only: refuse a binary encoding the way a server
+            // without it does, so the client falls back to JSON.
+            if game_data_format.is_some_and(|format| format != GameDataEncoding::Json) {
+                send(
+                    outbox,
+                    &ServerMessage::Error {
+                        message: "binary game data is not supported".into(),
+                        error_code: Some(ErrorCode::UnsupportedGameDataFormat),
+                    },
+                );
+            }
+        }
+        ClientMessage::Ping => {
+            if behavior.answer_pings {
+                send(outbox, &ServerMessage::Pong);
+            }
+        }
+        ClientMessage::TimeSyncRequest { client_sent_at_ms } => send(
+            outbox,
+            &ServerMessage::TimeSyncResponse {
+                client_sent_at_ms,
+                server_time_ms: unix_millis(),
+            },
+        ),
+        ClientMessage::JoinRoom {
+            game_name,
+            room_code,
+            player_name,
+            max_players,
+            ..
+        } => {
+            if !matches!(seat, Seat::Lobby) {
+                send(
+                    outbox,
+                    &ServerMessage::RoomJoinFailed {
+                        reason: "already in a room".into(),
+                        error_code: Some(ErrorCode::AlreadyInRoom),
+                    },
+                );
+                return;
+            }
+            let id = world.next_id();
+            let code = room_code.unwrap_or_else(|| format!("QA{:04}", id.as_u128() % 10_000));
+            let room_id = world.next_id();
+            let room = world.rooms.entry(code.clone()).or_insert_with(|| Room {
+                id: room_id,
+                game_name,
+                max_players: max_players.unwrap_or(4),
+                authority: None,
+                players: Vec::new(),
+                spectators: Vec::new(),
+            });
+            let info = testing::player(player_name).id(id).build();
+            let token = behavior.issue_tokens.then(|| format!("token-{id}"));
+            room.players.push(Member {
+                info: info.clone(),
+                token: token.clone(),
+                outbox: Some(outbox.clone()),
+            });
+            let mut joined = testing::room_joined(code.clone())
+                .room_id(room.id)
+                .player_id(id)
+                .game_name(room.game_name.clone())
+                .max_players(room.max_players)
+                .lobby_state(LobbyState::Lobby)
+                .players(room.player_infos())
+                .spectators(room.spectator_infos());
+            if let Some(token) = token {
+                joined = joined.reconnection_token(token);
+            }
+            send(outbox, &joined.message());
+            room.broadcast(Some(id), &ServerMessage::PlayerJoined { player: info });
+            *seat = Seat::Player { code, id };
+        }
+        ClientMessage::PlayerReady => {
+            let Some((room, id)) = player_room(world, seat) else {
+                return;
+            };
+            if let Some(member) = room.players.iter_mut().find(|member| member.info.id == id) {
+                member.info.is_ready = true;
+            }
+            let ready_players = room.ready_players();
+            let all_ready = ready_players.len() == room.players.len();
+            room.broadcast(
+                None,
+                &ServerMessage::LobbyStateChanged {
+                    lobby_state: LobbyState::Lobby,
+                    ready_players,
+                    all_ready,
+                },
+            );
+        }
+        ClientMessage::AuthorityRequest { become_authority } => {
+            let Some((room, id)) = player_room(world, seat) else {
+                return;
+            };
+            if !behavior.grant_authority {
+                send(
+                    outbox,
+                    &ServerMessage::AuthorityResponse {
+                        granted: false,
+                        reason: Some("authority is pinned".into()),
+                        error_code: Some(ErrorCode::AuthorityDenied),
+                    },
+                );
+                return;
+            }
+            room.authority = become_authority.then_some(id);
+            send(
+                outbox,
+                &ServerMessage::AuthorityResponse {
+                    granted: true,
+                    reason: None,
+                    error_code: None,
+                },
+            );
+            for member in &room.players {
+                if let Some(outbox) = &member.outbox {
+                    send(
+                        outbox,
+                        &ServerMessage::AuthorityChanged {
+                            authority_player: room.authority,
+                            you_are_authority: room.authority == Some(member.info.id),
+                        },
+                    );
+                }
+            }
+        }
+        ClientMessage::GameData { data, .. } => {
+            let Some((room, id)) = player_room(world, seat) else {
+                return;
+            };
+            room.broadcast(
+                Some(id),
+                &ServerMessage::GameData {
+                    from_player: id,
+                    data,
+                    seq: None,
+                    epoch: None,
+                    class: None,
+                    key: None,
+                },
+            );
+        }
+        ClientMessage::JoinAsSpectator {
+            room_code,
+            spectator_name,
+            ..
+        } => {
+            let id = world.next_id();
+            let Some(room) = world.rooms.get_mut(&room_code) else {
+                send(
+                    outbox,
+                    &ServerMessage::SpectatorJoinFailed {
+                        reason: format!("no room `{room_code}`"),
+                        error_code: Some(ErrorCode::RoomNotFound),
+                    },
+                );
+                return;
+            };
+            let info = testing::spectator(spectator_name).id(id).build();
+            room.spectators.push(info.clone());
+            send(
+                outbox,
+                &ServerMessage::SpectatorJoined(Box::new(SpectatorJoinedPayload {
+                    room_id: room.id,
+                    room_code: room_code.clone(),
+                    spectator_id: id,
+                    game_name: room.game_name.clone(),
+                    current_players: room.player_infos(),
+                    current_spectators: room.spectator_infos(),
+                    lobby_state: LobbyState::Lobby,
+                    reason: None,
+                })),
+            );
+            room.broadcast(
+                None,
+                &ServerMessage::NewSpectatorJoined {
+                    spectator: info,
+                    current_spectators: room.spectator_infos(),
+                    reason: None,
+                },
+            );
+            *seat = Seat::Spectator {
+                code: room_code,
+                id,
+            };
+        }
+        ClientMessage::LeaveSpectator => {
+            let Seat::Spectator { code, id } = std::mem::replace(seat, Seat::Lobby) else {
+                return;
+            };
+            let Some(room) = world.rooms.get_mut(&code) else {
+                return;
+            };
+            room.spectators.retain(|spectator| spectator.id != id);
+            send(
+                outbox,
+                &ServerMessage::SpectatorLeft {
+                    room_id: Some(room.id),
+                    room_code: Some(code),
+                    reason: None,
+                    current_spectators: room.spectator_infos(),
+                },
+            );
+        }
+        ClientMessage::Reconnect {
+            player_id,
+            room_id,
+            auth_token,
+        } => {
+            let resumed = world.rooms.iter_mut().find_map(|(code, room)| {
+                let member = room.players.iter_mut().find(|member| {
+                    member.info.id == player_id && member.token.as_ref() == Some(&auth_token)
+                })?;
+                (room.id == room_id).then_some(())?;
+                member.outbox = Some(outbox.clone());
+                Some((code.clone(), room))
+            });
+            let Some((code, room)) = resumed else {
+                send(
+                    outbox,
+                    &ServerMessage::ReconnectionFailed {
+                        reason: "unknown seat or token".into(),
+                        error_code: ErrorCode::ReconnectionTokenInvalid,
+                    },
+                );
+                return;
+            };
+            send(
+                outbox,
+                &ServerMessage::Reconnected(Box::new(ReconnectedPayload {
+                    room_id: room.id,
+                    room_code: code.clone(),
+                    player_id,
+                    game_name: room.game_name.clone(),
+                    max_players: room.max_players,
+                    supports_authority: true,
+                    current_players: room.player_infos(),
+                    is_authority: room.authority == Some(player_id),
+                    lobby_state: LobbyState::Lobby,
+                    ready_players: room.ready_players(),
+                    relay_type: "auto".into(),
+                    current_spectators: room.spectator_infos(),
+                    ice_servers: vec![],
+                    missed_events: vec![],
+                    replay: None,
+                    sender_watermarks: vec![],
+                    reconnection_token: None,
+                })),
+            );
+            room.broadcast(
+                Some(player_id),
+                &ServerMessage::PlayerReconnected {
+                    player_id,
+                    epoch: None,
+                },
+            );
+            *seat = Seat::Player {
+                code,
+                id: player_id,
+            };
+        }
+        ClientMessage::LeaveRoom => {
+            let Some((room, id)) = player_room(world, seat) else {
+                return;
+            };
+            room.players.retain(|member| member.info.id != id);
+            send(outbox, &ServerMessage::RoomLeft);
+            room.broadcast(
+                None,
+                &ServerMessage::PlayerLeft {
+                    player_id: id,
+                    epoch: None,
+                    final_seq: None,
+                },
+            );
+            *seat = Seat::Lobby;
+        }
+        _ => send(
+            outbox,
+            &ServerMessage::Error {
+                message: "not supported by the fake server".into(),
+                error_code: None,
+            },
+        ),
+    }
+}
+
+/// The room and id of a connection seated as a player.
+fn player_room<'a>(world: &'a mut World, seat: &Seat) -> Option<(&'a mut Room, PlayerId)> {
+    let Seat::Player { code, id } = seat else {
+        return None;
+    };
+    world.rooms.get_mut(code).map(|room| (room, *id))
+}
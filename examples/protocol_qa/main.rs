@@ -0,0 +1,92 @@
+//! Protocol QA walkthrough against a **real** Signal Fish deployment.
+//!
+//! Walks the whole client-facing protocol the way a game would: authenticate,
+//! create and join a room, ready up, request authority, relay JSON and binary
+//! game data, spectate from a third connection, trigger an intentional
+//! `ALREADY_IN_ROOM` error, and reconnect with the server-issued token. Every
+//! expected event is verified and the run ends with a pass/fail report; the
+//! exit status is non-zero when any check failed.
+//!
+//! ```text
+//! cargo run --example protocol_qa -- <url> <app_id> [key=value ...]
+//!
+//! options (defaults)
+//!   game=protocol-qa   event_timeout_ms=5000   check_timeout_ms=30000
+//! ```
+//!
+//! `wss://` URLs need `--features tls`. The checks live in `checks.rs` as a
+//! reusable module; its tests run them against an in-process fake server
+//! (`cargo test --example protocol_qa`).
+
+mod checks;
+#[cfg(test)]
+mod fake_server;
+
+use std::error::Error;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use signal_fish_client::{SignalFishClient, SignalFishConfig, SignalFishError, WebSocketTransport};
+
+use checks::{BoxFuture, Connector, QaContext, QaOptions, Started, STANDARD_SUITE};
+
+/// Opens each connection as a fresh WebSocket to the deployment.
+struct Deployment {
+    url: String,
+}
+
+impl Connector for Deployment {
+    fn open(
+        &mut self,
+        config: SignalFishConfig,
+    ) -> BoxFuture<'_, Result<Started, SignalFishError>> {
+        Box::pin(async move {
+            let transport = WebSocketTransport::connect(&self.url).await?;
+            Ok(SignalFishClient::start(transport, config))
+        })
+    }
+}
+
+fn parse(args: &[String]) -> Result<(String, QaOptions), Box<dyn Error>> {
+    let [url, app_id, rest @ ..] = args else {
+        return Err("usage: protocol_qa <url> <app_id> [key=value ...]".into());
+    };
+    let mut options = QaOptions::new(app_id.clone());
+    for arg in rest {
+        let (key, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got `{arg}`"))?;
+        match key {
+            "game" => options.game_name = value.to_string(),
+            "event_timeout_ms" => options.event_timeout = Duration::from_millis(value.parse()?),
+            "check_timeout_ms" => options.check_timeout = Duration::from_millis(value.parse()?),
+            _ => return Err(format!("unknown option `{key}`").into()),
+        }
+    }
+    Ok((url.clone(), options))
+}
+
+#[tokio::main]
+async fn main() -> Result<ExitCode, Box<dyn Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (url, options) = parse(&args)?;
+    println!("protocol QA against {url} (app `{}`)", options.app_id);
+
+    let mut cx = QaContext::new(Deployment { url }, options);
+    let report = checks::run(STANDARD_SUITE, &mut cx).await;
+    cx.close().await;
+
+    println!("{report}");
+    Ok(if report.is_success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
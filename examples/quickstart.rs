@@ -0,0 +1,134 @@
+//! # Quick Start Example
+//!
+//! The README quick start, runnable without a server: an in-process stand-in
+//! answers `Authenticate` and `JoinRoom`, and the client walks the documented
+//! flow — start, wait for `Authenticated`, `join_room`, wait for `RoomJoined`,
+//! shut down.
+//!
+//! `tests/quickstart_flow.rs` pins the same scenario; keep the two in step.
+//!
+//! ## Running
+//!
+//! ```sh
+//! cargo run --example quickstart
+//! ```
+
+use signal_fish_client::protocol::{ClientMessage, RateLimitInfo, ServerMessage};
+use signal_fish_client::testing;
+use signal_fish_client::transport::TransportFrame;
+use signal_fish_client::{
+    JoinRoomParams, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent, Transport,
+};
+use tokio::sync::mpsc;
+
+/// The client half of an in-process connection.
+struct LoopbackTransport {
+    to_server: Option<mpsc::UnboundedSender<String>>,
+    from_server: mpsc::UnboundedReceiver<String>,
+}
+
+impl Transport for LoopbackTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        let sent = match (frame.take(), &self.to_server) {
+            (Some(TransportFrame::Text(message)), Some(server)) => server.send(message).is_ok(),
+            _ => false,
+        };
+        std::task::Poll::Ready(if sent {
+            Ok(())
+        } else {
+            Err(SignalFishError::TransportClosed)
+        })
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.from_server
+            .poll_recv(cx)
+            .map(|message| message.map(|message| Ok(TransportFrame::Text(message))))
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.to_server = None;
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// Stand-in server: authenticate every client and put it in room `QS1234`.
+async fn serve(
+    mut from_client: mpsc::UnboundedReceiver<String>,
+    to_client: mpsc::UnboundedSender<String>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    while let Some(frame) = from_client.recv().await {
+        let reply = match serde_json::from_str(&frame)? {
+            ClientMessage::Authenticate { .. } => {
+                serde_json::to_string(&ServerMessage::Authenticated {
+                    app_name: "quickstart".into(),
+                    organization: None,
+                    rate_limits: RateLimitInfo {
+                        per_minute: 60,
+                        per_hour: 1000,
+                        per_day: 10000,
+                    },
+                })?
+            }
+            ClientMessage::JoinRoom { game_name, .. } => {
+                testing::room_joined("QS1234").game_name(game_name).json()
+            }
+            _ => continue,
+        };
+        // The client may already have gone; nothing left to answer.
+        let _ = to_client.send(reply);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (to_server, from_client) = mpsc::unbounded_channel();
+    let (to_client, from_server) = mpsc::unbounded_channel();
+    let server = tokio::spawn(serve(from_client, to_client));
+
+    // 1. Connect a transport (here, to the stand-in server).
+    let transport = LoopbackTransport {
+        to_server: Some(to_server),
+        from_server,
+    };
+
+    // 2. Build a client config with your application ID.
+    let config = SignalFishConfig::new("mb_app_abc123");
+
+    // 3. Start the client — it sends Authenticate on its own.
+    let (mut client, mut event_rx) = SignalFishClient::start(transport, config);
+
+    // 4. Wait for Authenticated before joining a room.
+    while let Some(event) = event_rx.recv().await {
+        match event {
+            SignalFishEvent::Authenticated { app_name, .. } => {
+                println!("Authenticated as {app_name}");
+                client.join_room(JoinRoomParams::new("my-game", "Alice"))?;
+            }
+            SignalFishEvent::RoomJoined { room_code, .. } => {
+                println!("Joined room {room_code}");
+                // 5. Shut down gracefully; the loop ends on Disconnected.
+                client.shutdown().await;
+            }
+            SignalFishEvent::Disconnected { reason, .. } => {
+                println!("Disconnected: {reason}");
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    server.await??;
+    Ok(())
+}
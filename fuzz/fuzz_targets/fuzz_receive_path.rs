@@ -0,0 +1,12 @@
+#![no_main]
+
+// The harness lives with the integration tests so `cargo test` can run it
+// without nightly; see `tests/receive_path_fuzz_smoke.rs`.
+#[path = "../../tests/receive_path/mod.rs"]
+mod receive_path;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &[u8]| {
+    receive_path::run(input);
+});
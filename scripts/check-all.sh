@@ -140,7 +140,8 @@ require_cmd cargo-clippy "rustup component add clippy"
 echo -e "${YELLOW}Preflight: Checking fuzz seeds for forbidden '\"data\":null'...${NC}"
 if grep -R -n -E '"data"[[:space:]]*:[[:space:]]*null' \
     fuzz/seeds/fuzz_client_message \
-    fuzz/seeds/fuzz_server_message; then
+    fuzz/seeds/fuzz_server_message \
+    fuzz/seeds/fuzz_receive_path; then
     echo -e "${RED}ERROR: Found forbidden '\"data\":null' in fuzz seed files.${NC}" >&2
     echo "  Unit variants must serialize without a data field (e.g., {\"type\":\"Ping\"})." >&2
     exit 1
@@ -581,6 +582,12 @@ else
             echo -e "${RED}  fuzz_client_message: FAIL${NC}"
             PHASE16_FAIL=true
         fi
+        if (cd "$FUZZ_DIR" && cargo +nightly fuzz run fuzz_receive_path seeds/fuzz_receive_path -- -max_total_time=10) 2>&1; then
+            echo -e "${GREEN}  fuzz_receive_path: PASS${NC}"
+        else
+            echo -e "${RED}  fuzz_receive_path: FAIL${NC}"
+            PHASE16_FAIL=true
+        fi
     else
         echo -e "${YELLOW}SKIP: fuzz/ directory not found.${NC}"
         PHASE_RESULTS[16]="SKIP"
@@ -14,14 +14,18 @@
 //!   has room — backpressure propagates to the server instead of losing
 //!   events. Inbound frames that fail to decode are surfaced as
 //!   [`DecodeFailed`](SignalFishEvent::DecodeFailed) events (and counted in
-//!   [`ClientStats::messages_undecodable`]) rather than dropped. An event can
+//!   [`ClientStats::messages_undecodable`]) rather than dropped; only blank
+//!   keepalive frames are skipped, and those are counted in
+//!   [`ClientStats::blank_frames_skipped`]. A frame carrying several
+//!   concatenated messages yields an event for each. An event can
 //!   only be missed when the loop stops delivering entirely: the receiver was
 //!   dropped, the client handle was dropped without calling
 //!   [`shutdown`](SignalFishClient::shutdown) (which aborts immediately), or
 //!   `shutdown` was requested — a shutdown abandons at most the one event
-//!   delivery it interrupted, closes the transport gracefully, and delivers
-//!   the terminal `Disconnected` best-effort (a receiver that outlives the
-//!   loop also observes the event channel closing).
+//!   delivery it interrupted and closes the transport gracefully. Every
+//!   receiver still ends with exactly one terminal `Disconnected` followed by
+//!   `None`, including after a shutdown-timeout abort or a handle dropped
+//!   inside the runtime.
 //! - **Commands** go through a bounded queue and queue admission is never
 //!   silent: the synchronous send methods fail fast with
 //!   [`SignalFishError::SendBufferFull`] when it is full, and the
@@ -66,33 +70,41 @@
 //! }
 //! ```
 
-#[cfg(all(test, feature = "tokio-runtime"))]
-use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "debug-server")]
+pub mod debug_server;
+
+use std::collections::BTreeMap;
+#[cfg(feature = "tokio-runtime")]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 #[cfg(feature = "tokio-runtime")]
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use serde::Serialize;
 #[cfg(feature = "tokio-runtime")]
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 #[cfg(feature = "tokio-runtime")]
-use tracing::{debug, error, warn};
+use tracing::{debug, error, warn, Instrument};
 
 #[cfg(feature = "tokio-runtime")]
-use crate::client_core::{ClientCore, ClientOperation, CoreCommand as ClientCommand};
+use crate::client_core::{
+    ClientCore, ClientOperation, CoreCommand as ClientCommand, DecodedFrame, FrameDecoder,
+    WaiterKind,
+};
 #[cfg(feature = "tokio-runtime")]
 use crate::error::{Result, SignalFishError};
 #[cfg(feature = "tokio-runtime")]
-use crate::event::SignalFishEvent;
-#[cfg(feature = "tokio-runtime")]
-use crate::protocol::ClientMessage;
-#[cfg(feature = "tokio-runtime")]
-use crate::protocol::ConnectionInfo;
+use crate::event::{DisconnectReason, EventEnvelope, SignalFishEvent};
 #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
 use crate::protocol::ServerMessage;
 use crate::protocol::{
-    GameDataEncoding, PlayerId, RelayTransport, RoomId, Topology, TransportKind,
+    ClientMessageKind, GameDataEncoding, PlayerId, RelayTransport, RoomId, Topology, TransportKind,
 };
 #[cfg(feature = "tokio-runtime")]
+use crate::protocol::{ConnectionInfo, LobbyState, PlayerInfo};
+#[cfg(feature = "tokio-runtime")]
+use crate::reconnect::TransportFactory;
+#[cfg(feature = "tokio-runtime")]
 use crate::signal::PeerSignal;
 #[cfg(feature = "tokio-runtime")]
 use crate::transport::{close_transport, recv_frame, send_frame, Transport, TransportFrame};
@@ -106,6 +118,21 @@ const DEFAULT_COMMAND_CHANNEL_CAPACITY: usize = 1024;
 /// Default timeout for the graceful shutdown.
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// Default [`SignalFishConfig::label`].
+#[cfg(feature = "tokio-runtime")]
+const DEFAULT_LABEL: &str = "signal-fish-client";
+
+/// Default grace period the self-removal policy waits for `RoomLeft`.
+const DEFAULT_SELF_REMOVAL_GRACE: Duration = Duration::from_millis(500);
+
+/// Default [`SignalFishConfig::reconnect_answer_timeout`].
+const DEFAULT_RECONNECT_ANSWER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the transport loop looks for dropped event receivers under an
+/// [`OnReceiverDropped`] policy other than `KeepRunning`.
+#[cfg(feature = "tokio-runtime")]
+const RECEIVER_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
 #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
 pub(crate) fn bounded_binary_preview(bytes: &[u8]) -> String {
     use std::fmt::Write as _;
@@ -175,7 +202,14 @@ pub struct SignalFishConfig {
     pub app_id: String,
     /// SDK version string sent during authentication.
     /// Defaults to the crate version at compile time.
+    ///
+    /// The server expects a SemVer 2.0.0 version; build one with
+    /// [`SdkVersion`](crate::SdkVersion) to add CI build metadata. Other
+    /// strings are handled per [`sdk_version_policy`](Self::sdk_version_policy).
     pub sdk_version: Option<String>,
+    /// What the client does when `sdk_version` is not a SemVer 2.0.0
+    /// version. Defaults to [`SdkVersionPolicy::Warn`](crate::SdkVersionPolicy::Warn).
+    pub sdk_version_policy: crate::SdkVersionPolicy,
     /// Platform identifier (e.g. `"unity"`, `"godot"`, `"rust"`).
     pub platform: Option<String>,
     /// Preferred game data encoding format.
@@ -203,11 +237,25 @@ pub struct SignalFishConfig {
     /// the consumer gets before that backpressure kicks in. An event can only
     /// be missed when delivery stops entirely: the receiver is dropped, the
     /// client handle is dropped without calling [`SignalFishClient::shutdown`],
-    /// or on `shutdown` — which abandons at most one in-flight event and
-    /// delivers the terminal `Disconnected` best-effort.
+    /// or on `shutdown` — which abandons at most one in-flight event. The
+    /// terminal `Disconnected` is never dropped: a full channel receives it
+    /// once a slot frees up, and the channel closes only after it.
     ///
     /// Defaults to **256**. Values below 1 are clamped to 1.
     pub event_channel_capacity: usize,
+    /// Capacity of an additional [`tokio::sync::broadcast`] channel the
+    /// async client publishes every event to, or `None` (the default) for
+    /// none.
+    ///
+    /// Unlike the bounded receivers, broadcast receivers from
+    /// [`SignalFishClient::subscribe_broadcast`] never hold the transport
+    /// loop up: one that falls more than `capacity` events behind loses the
+    /// oldest and is told how many with
+    /// [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged).
+    /// The loop treats the channel as a receiver that is always listening,
+    /// so [`on_receiver_dropped`](Self::on_receiver_dropped) never fires
+    /// while it is enabled. The polling client ignores it.
+    pub broadcast_events: Option<usize>,
     /// Capacity of the bounded outgoing command queue.
     ///
     /// Queue admission is **never silent**. When the queue is full, the
@@ -226,16 +274,353 @@ pub struct SignalFishConfig {
     /// When [`SignalFishClient::shutdown`] is called, the background transport
     /// loop is given this much time to close the transport and emit a final
     /// `Disconnected` event. If the timeout expires the task is aborted and
-    /// the `Disconnected` event may not be delivered. The polling client uses
-    /// the same duration to bound queued-work flushing and its transport close
+    /// `shutdown` emits the `Disconnected` itself. The same bound applies to
+    /// the transport close when the connection ends on its own (server close,
+    /// transport error); expiry there invokes [`Transport::abort`] and the
+    /// loop still exits with `Disconnected`. The polling client uses the same
+    /// duration to bound queued-work flushing and its transport close
     /// handshake; expiry invokes [`Transport::abort`].
     ///
     /// Defaults to **1 second**. A zero timeout aborts the transport loop
-    /// immediately without waiting for graceful shutdown, meaning the
-    /// `Disconnected` event will likely not be emitted.
+    /// immediately without waiting for graceful shutdown.
     pub shutdown_timeout: Duration,
+    /// Whether [`SignalFishClient::shutdown`] sends the commands still queued
+    /// for the transport loop before closing.
+    ///
+    /// When `true` (the default), a `leave_room()` or `send_game_data()` made
+    /// just before `shutdown()` still reaches the server: the loop sends what
+    /// is queued, within [`shutdown_timeout`](Self::shutdown_timeout), and
+    /// then closes the transport within another, so `shutdown()` may take up
+    /// to twice the timeout. Set it to `false` to drop the queue and close at
+    /// once. The `Drop` fallback never flushes.
+    pub flush_on_shutdown: bool,
+    /// Whether [`SignalFishClient::shutdown`] leaves the room before closing.
+    ///
+    /// When `true` and the client is in a room, the loop sends `LeaveRoom`
+    /// (`LeaveSpectator` for a spectator) and waits for the server's
+    /// confirmation before closing the transport, so other players see a
+    /// clean departure instead of a disconnect with a reconnection window.
+    /// The leave shares the [`shutdown_timeout`](Self::shutdown_timeout)
+    /// window with the queued-command flush; an unanswered leave still closes
+    /// once it expires, so `shutdown()` may take up to twice the timeout. The
+    /// polling client and the `Drop` fallback ignore it. Defaults to
+    /// `false`.
+    pub leave_room_on_shutdown: bool,
     /// Response to a protocol-v3 delivery-accountability violation.
     pub protocol_violation_policy: ProtocolViolationPolicy,
+    /// Whether an `AuthenticationError` invalidates the local room session.
+    ///
+    /// When `true` (the default), an authentication failure clears the
+    /// player/room/spectator state and the reconnection token, and a
+    /// [`SessionInvalidated`](crate::SignalFishEvent::SessionInvalidated) event
+    /// follows the `AuthenticationError` if a session was cleared. Set it to
+    /// `false` to keep that state (including the token) for an application
+    /// that still wants to attempt a token-based `reconnect`. The
+    /// authenticated flag is cleared either way.
+    pub clear_session_on_auth_failure: bool,
+    /// Whether a repeated `Authenticated` or `Connected` within the same
+    /// logical session is surfaced as an event.
+    ///
+    /// A server may re-confirm authentication without the session having
+    /// reset (for example after it restores the room with `Reconnected`),
+    /// and a client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// connects and authenticates again after each outage it recovers from.
+    /// Consumers that treat `Authenticated` as "fresh session" can set this to
+    /// `false` to receive `Connected` and `Authenticated` only once per
+    /// [`ClientSnapshot::session_epoch`]. Defaults to `true`.
+    pub reemit_session_events: bool,
+    /// Whether an empty or whitespace-only text frame is a decode failure.
+    ///
+    /// By default such frames (keepalives injected by some reverse proxies)
+    /// are skipped silently and counted in
+    /// [`ClientStats::blank_frames_skipped`]. Set this to `true` to treat
+    /// them like any other undecodable frame — a warning, a
+    /// [`DecodeFailed`](crate::SignalFishEvent::DecodeFailed) event, and a
+    /// `messages_undecodable` count — when debugging a proxy. Defaults to
+    /// `false`.
+    pub strict_blank_frames: bool,
+    /// Whether a text frame must hold exactly one JSON message.
+    ///
+    /// Some proxies coalesce consecutive messages into one frame. By default
+    /// each concatenated message is processed in order and the frame is
+    /// counted in [`ClientStats::multi_message_frames`]; a malformed or
+    /// truncated remainder is reported once as a
+    /// [`DecodeFailed`](crate::SignalFishEvent::DecodeFailed) after the
+    /// messages before it. Set this to `true` to report the whole frame as
+    /// one decode failure instead. Defaults to `false`.
+    pub strict_message_framing: bool,
+    /// Whether a room payload with a malformed part is a decode failure.
+    ///
+    /// By default a `RoomJoined` or `Reconnected` that fails to decode is
+    /// retried with the malformed collection entries dropped and malformed
+    /// optional fields defaulted, each reported by a
+    /// [`PayloadSalvaged`](crate::SignalFishEvent::PayloadSalvaged) before the
+    /// message's own event. Set this to `true` to report such a message as a
+    /// [`DecodeFailed`](crate::SignalFishEvent::DecodeFailed) instead.
+    /// Defaults to `false`.
+    pub strict_payloads: bool,
+    /// Whether an undecodable message that is well-formed JSON with a `type`
+    /// tag arrives whole as
+    /// [`UnknownMessage`](crate::SignalFishEvent::UnknownMessage) instead of
+    /// [`DecodeFailed`](crate::SignalFishEvent::DecodeFailed).
+    ///
+    /// For handling messages from a server newer than this SDK. Off by
+    /// default because the event carries the complete raw message, which
+    /// may be large or hold data the application should not log. Defaults
+    /// to `false`.
+    pub forward_unknown_messages: bool,
+    /// Whether server [`Announcement`](crate::SignalFishEvent::Announcement)
+    /// events are dropped instead of delivered.
+    ///
+    /// For clients with no place to show a message of the day. Client state
+    /// is unaffected; announcements carry none. Defaults to `false`.
+    pub suppress_announcements: bool,
+    /// Sender id of the system player that legacy servers use to relay
+    /// announcements as `GameData`.
+    ///
+    /// When set, `GameData` from this player whose payload is an object with
+    /// a string `"message"` (plus optional string `"severity"` and any
+    /// `"metadata"`) is surfaced as an
+    /// [`Announcement`](crate::SignalFishEvent::Announcement) instead. Any
+    /// other payload from that player still arrives as `GameData`. A
+    /// migration aid: leave it `None` (the default) once the server sends
+    /// native announcements.
+    pub legacy_announcement_sender: Option<PlayerId>,
+    /// Whether room-scoped events processed before a room change are dropped
+    /// instead of delivered after it.
+    ///
+    /// Events are delivered in the order the server sent them, but one frame
+    /// (or one polling-client `poll()` batch) can carry a previous room's
+    /// `PlayerJoined`, `PlayerLeft`, `GameData`, `GameDataBinary` or
+    /// `LobbyStateChanged` together with a quick leave and rejoin. When `true`, such events are
+    /// dropped if the room changed between processing them and handing them
+    /// over, and counted in [`ClientStats::cross_epoch_events_dropped`].
+    /// Lifecycle events are always delivered. Defaults to `false`.
+    pub drop_cross_epoch_events: bool,
+    /// Limits used to grade `health()` on either client.
+    pub health_thresholds: crate::HealthThresholds,
+    /// Override for error-code text rendered by `describe_error_code()`;
+    /// `None` uses the built-in English descriptions.
+    pub error_code_formatter: Option<crate::ErrorCodeFormatter>,
+    /// Window over which [`SignalFishClient`] coalesces consecutive
+    /// `LobbyStateChanged` events into the latest one.
+    ///
+    /// A peer toggling ready rapidly otherwise produces one event (and one
+    /// round of UI work) per toggle. The first event of a burst starts the
+    /// window; when it elapses only the newest state is emitted. Client state
+    /// still updates on every message, an `all_ready: true` change is emitted
+    /// at once (replacing any held state), and any other event flushes the held
+    /// state first so ordering is preserved.
+    ///
+    /// `None` (the default) or a zero window emits every change immediately.
+    /// The polling client ignores this; it returns each change from `poll()`.
+    pub lobby_state_debounce: Option<Duration>,
+    /// What to do when the server announces our own departure with a
+    /// `PlayerLeft` naming this client's `player_id` (for example an idle
+    /// kick).
+    ///
+    /// Depending on the server version a `RoomLeft` may or may not follow. The
+    /// default, [`SelfRemovalPolicy::ClearAfter`] with a **500 ms** grace,
+    /// waits briefly for it and otherwise clears the room state itself.
+    pub self_removal_policy: SelfRemovalPolicy,
+    /// What to do with a server message scoped to a different room than the
+    /// client's session, reported as
+    /// [`SessionInconsistency`](crate::SignalFishEvent::SessionInconsistency).
+    ///
+    /// The default, [`SessionInconsistencyPolicy::Apply`], applies it anyway.
+    pub session_inconsistency_policy: SessionInconsistencyPolicy,
+    /// The organization this app should authenticate under. When set, an
+    /// `Authenticated` naming a different organization (or none) is reported
+    /// as [`OrganizationMismatch`](crate::SignalFishEvent::OrganizationMismatch),
+    /// which usually means a misconfigured `app_id`. `None` (the default)
+    /// checks nothing.
+    pub expected_organization: Option<String>,
+    /// What to do after an
+    /// [`OrganizationMismatch`](crate::SignalFishEvent::OrganizationMismatch).
+    ///
+    /// The default, [`OrganizationMismatchPolicy::Warn`], authenticates anyway.
+    pub organization_mismatch_policy: OrganizationMismatchPolicy,
+    /// Telemetry callback for failures in `decode_game_data()` /
+    /// `try_decode_game_data()` on either client; `None` reports nothing.
+    pub decode_failure_hook: Option<crate::DecodeFailureHook>,
+    /// Test observer of the async client's event fan-out; `None` (the
+    /// default) observes nothing. The polling client ignores it.
+    #[cfg(feature = "testing")]
+    pub emission_hook: Option<crate::testing::EmissionHook>,
+    /// Name for this client in tokio task names and tracing spans.
+    ///
+    /// The async transport loop runs inside a `signal_fish_client` span
+    /// carrying this label, plus the `organization` from `Authenticated` once
+    /// the server names one. When built with `--cfg tokio_unstable` and the
+    /// `task-names` feature, its tasks are also named `{label}`,
+    /// `{label}/fanout`, `{label}/terminal`, `{label}/decode`, and
+    /// `{label}/handler` for `tokio-console`.
+    ///
+    /// `None` (the default) uses `"signal-fish-client"`.
+    pub label: Option<String>,
+    /// How often [`SignalFishClient`] sends a heartbeat `Ping` on its own.
+    ///
+    /// Pings go out only while authenticated, one per interval; each keeps
+    /// proxies from timing out an idle connection and draws a `Pong` that
+    /// resets the inbound silence [`health`](SignalFishClient::health)
+    /// grades. `None` (the default) or a zero interval sends none. Tunable
+    /// while connected with
+    /// [`update_runtime_config`](SignalFishClient::update_runtime_config).
+    /// A ping is skipped while the transport's
+    /// [`last_activity_hint`](crate::Transport::last_activity_hint) is more
+    /// recent than one interval; that activity then counts as inbound for
+    /// `health`. An application `ping()` likewise pushes the next heartbeat
+    /// back a full interval.
+    /// The polling client ignores this; call `ping()` from the game loop.
+    pub ping_interval: Option<Duration>,
+    /// Longest [`SignalFishClient`] waits for a `Pong` after a `Ping` before
+    /// it treats the connection as dead: it closes the transport and ends
+    /// with [`DisconnectReason::HeartbeatTimeout`].
+    ///
+    /// Catches connections a NAT or proxy dropped silently, which otherwise
+    /// look connected until the next send. Pings from `ping()` count as well
+    /// as the [`ping_interval`](Self::ping_interval) heartbeat; the clock
+    /// stops while parsing is paused by
+    /// [`OnReceiverDropped::PauseParsing`]. A client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// redials instead. `None` (the default) or zero never times out. The
+    /// polling client ignores this.
+    pub pong_timeout: Option<Duration>,
+    /// How often [`SignalFishClient`] sends a time-sync request on its own
+    /// to keep [`server_time_estimate`](SignalFishClient::server_time_estimate)
+    /// fresh.
+    ///
+    /// Requests go out only while authenticated; the first follows the first
+    /// tick after authentication. `None` (the default) or a zero interval
+    /// sends none; explicit `sync_time()` calls still feed the estimate.
+    /// Tunable while connected. The polling client ignores this; call `request_time_sync()` from the game
+    /// loop instead.
+    pub time_sync_interval: Option<Duration>,
+    /// How many recent time-sync samples the server-time estimate chooses
+    /// from; the one with the smallest round trip wins. Defaults to
+    /// [`DEFAULT_TIME_SYNC_SAMPLES`](crate::DEFAULT_TIME_SYNC_SAMPLES). Values
+    /// below 1 are clamped to 1.
+    pub time_sync_samples: usize,
+    /// Move JSON parsing of received frames off [`SignalFishClient`]'s
+    /// transport loop, with up to this many frames queued for it.
+    ///
+    /// Inline parsing (`None`, the default) is cheapest for small messages,
+    /// but a multi-megabyte frame (a `Reconnected` with a long
+    /// `missed_events` backlog, bulky `GameData`) stalls the loop — and with
+    /// it shutdown, pings, and queued commands — until it is parsed. With a
+    /// pipeline a worker parses frames on tokio's blocking pool while the
+    /// loop keeps serving everything else; state updates and events still
+    /// happen on the loop in arrival order. When the queue is full the loop
+    /// stops reading the transport until the worker catches up. On shutdown
+    /// frames still queued are dropped; on a server close or transport error
+    /// they are applied first. Values below 1 are clamped to 1. The polling
+    /// client ignores this.
+    pub decode_pipeline: Option<usize>,
+    /// What [`SignalFishClient`] does once every event receiver has been
+    /// dropped while the client handle lives on.
+    ///
+    /// By default ([`OnReceiverDropped::KeepRunning`]) the loop carries on,
+    /// applying and discarding every event. Under the other policies the
+    /// loop notices the drop on the next received frame or within a quarter
+    /// second. The polling client ignores this.
+    pub on_receiver_dropped: OnReceiverDropped,
+    /// Longest [`SignalFishClient::begin_drain`] waits for the current room
+    /// to end before shutting down anyway.
+    ///
+    /// `None` (the default) waits for the room to end however long it takes.
+    /// The polling client ignores this.
+    pub drain_timeout: Option<Duration>,
+    /// Keep a [`RoomSessionRecord`](crate::RoomSessionRecord) for each ended
+    /// room session, up to this many (the oldest is dropped first), readable
+    /// from `room_history()` on either client.
+    ///
+    /// `None` (the default) or `0` keeps none.
+    pub room_history: Option<usize>,
+    /// Backoff and attempt limit for a client started with
+    /// [`SignalFishClient::start_with_reconnect`]; other clients ignore it.
+    pub reconnect_policy: crate::ReconnectPolicy,
+    /// Whether a client started with
+    /// [`SignalFishClient::start_with_reconnect`] returns to its room after
+    /// an outage. Defaults to [`ResumePolicy::Never`](crate::ResumePolicy::Never);
+    /// other clients use it only for a ticket loaded from the
+    /// [`session_store`](Self::session_store).
+    pub resume_policy: crate::ResumePolicy,
+    /// Commands a client started with
+    /// [`SignalFishClient::start_with_reconnect`] holds while its connection
+    /// is down, sent in order once the next connection authenticates.
+    /// Defaults to **64**.
+    ///
+    /// A full queue evicts its oldest command, counted in
+    /// [`ClientStats::offline_commands_evicted`]. Pings are never held. `0`
+    /// holds nothing: sends during an outage fail with
+    /// [`SignalFishError::NotConnected`]. Other clients ignore it.
+    pub offline_queue_capacity: usize,
+    /// How long a `Reconnect` may go unanswered before another may be sent.
+    /// Defaults to **10 seconds**.
+    ///
+    /// Until [`Reconnected`](SignalFishEvent::Reconnected) or
+    /// [`ReconnectionFailed`](SignalFishEvent::ReconnectionFailed) arrives,
+    /// or the connection ends, a second
+    /// [`reconnect`](SignalFishClient::reconnect) fails with
+    /// [`SignalFishError::ReconnectInFlight`], so two answers cannot race to
+    /// set the room. Past this timeout the attempt is taken as lost and a new
+    /// one may go out.
+    pub reconnect_answer_timeout: Duration,
+    /// Watch [`SignalFishClient`]'s transport loop and report it as stalled
+    /// once it has spent this long in one step.
+    ///
+    /// The loop updates a heartbeat at the top of every iteration, and wakes
+    /// at least every half threshold while idle to do so. A watchdog task
+    /// checks it every quarter threshold; when it is older than the
+    /// threshold it logs a warning and emits
+    /// [`ClientStalled`](SignalFishEvent::ClientStalled), once per stall.
+    /// Waiting for a receiver to make room and closing the transport are not
+    /// stalls; both are bounded elsewhere. Pick a threshold well above the
+    /// longest send the transport may take. `None` (the default) spawns no
+    /// watchdog. The polling client ignores it.
+    pub loop_watchdog: Option<Duration>,
+    /// Whether a stall reported under
+    /// [`loop_watchdog`](Self::loop_watchdog) also abandons the stalled
+    /// connection as lost, with
+    /// [`DisconnectReason::LoopStalled`]. Defaults to `false`.
+    ///
+    /// A client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect) then
+    /// dials a new transport as after any other loss; other clients end. Only
+    /// a stall on an `await` can be abandoned: code that blocks the thread
+    /// is reported but never returns control to the loop.
+    pub restart_stalled_loop: bool,
+    /// How long a message of each kind may wait in the outgoing queue before
+    /// it is no longer worth sending. Empty (the default) expires nothing.
+    ///
+    /// The deadline runs from when the message is queued. The transport loop
+    /// checks it as it takes the message off the queue — including the
+    /// offline queue of a reconnecting client — and drops an expired message
+    /// instead of sending it, emitting
+    /// [`MessageExpired`](SignalFishEvent::MessageExpired) and counting it in
+    /// [`ClientStats::messages_expired`].
+    /// [`send_game_data_with_deadline`](SignalFishClient::send_game_data_with_deadline)
+    /// sets a deadline for one message instead. `Authenticate`, `Ping`,
+    /// `LeaveRoom` and `LeaveSpectator` never expire; entries for them are
+    /// ignored. The polling client ignores it.
+    pub message_deadlines: BTreeMap<ClientMessageKind, Duration>,
+    /// Where the async client keeps its
+    /// [`SessionTicket`](crate::SessionTicket) so a restarted process can
+    /// return to its room. `None` (the default) keeps nothing.
+    ///
+    /// The ticket is written whenever the server issues a reconnection
+    /// token and removed when the room ends for good — a leave, a removal, a
+    /// `ReconnectionFailed` — but not when the transport drops. Unless
+    /// [`resume_policy`](Self::resume_policy) is
+    /// [`Never`](crate::ResumePolicy::Never), the client loads it on start:
+    /// [`resume`](SignalFishClient::resume) then works on the first
+    /// connection, and under [`Auto`](crate::ResumePolicy::Auto) the
+    /// `Reconnect` follows the first `Authenticate`, for clients started
+    /// with a connected transport too. A ticket that does not parse is
+    /// logged and removed. Writes happen in the background and never hold up
+    /// the transport loop. The polling client ignores it.
+    pub session_store: Option<std::sync::Arc<dyn crate::SessionStore>>,
 }
 
 impl SignalFishConfig {
@@ -244,18 +629,87 @@ impl SignalFishConfig {
         Self {
             app_id: app_id.into(),
             sdk_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            sdk_version_policy: crate::SdkVersionPolicy::Warn,
             platform: None,
             game_data_format: None,
             protocol_version: None,
             supported_transports: None,
             supported_topologies: None,
             event_channel_capacity: DEFAULT_EVENT_CHANNEL_CAPACITY,
+            broadcast_events: None,
             command_channel_capacity: DEFAULT_COMMAND_CHANNEL_CAPACITY,
             shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            flush_on_shutdown: true,
+            leave_room_on_shutdown: false,
             protocol_violation_policy: ProtocolViolationPolicy::Quarantine,
+            clear_session_on_auth_failure: true,
+            reemit_session_events: true,
+            strict_blank_frames: false,
+            strict_message_framing: false,
+            strict_payloads: false,
+            forward_unknown_messages: false,
+            suppress_announcements: false,
+            legacy_announcement_sender: None,
+            drop_cross_epoch_events: false,
+            health_thresholds: crate::HealthThresholds::default(),
+            error_code_formatter: None,
+            lobby_state_debounce: None,
+            self_removal_policy: SelfRemovalPolicy::default(),
+            session_inconsistency_policy: SessionInconsistencyPolicy::default(),
+            expected_organization: None,
+            organization_mismatch_policy: OrganizationMismatchPolicy::default(),
+            decode_failure_hook: None,
+            #[cfg(feature = "testing")]
+            emission_hook: None,
+            label: None,
+            ping_interval: None,
+            pong_timeout: None,
+            time_sync_interval: None,
+            time_sync_samples: crate::DEFAULT_TIME_SYNC_SAMPLES,
+            decode_pipeline: None,
+            on_receiver_dropped: OnReceiverDropped::KeepRunning,
+            drain_timeout: None,
+            room_history: None,
+            reconnect_policy: crate::ReconnectPolicy::default(),
+            resume_policy: crate::ResumePolicy::Never,
+            offline_queue_capacity: 64,
+            reconnect_answer_timeout: DEFAULT_RECONNECT_ANSWER_TIMEOUT,
+            loop_watchdog: None,
+            restart_stalled_loop: false,
+            message_deadlines: BTreeMap::new(),
+            session_store: None,
         }
     }
 
+    /// Set the SDK version sent during authentication.
+    ///
+    /// Accepts an [`SdkVersion`](crate::SdkVersion), which is valid by
+    /// construction, or a plain string, which the client checks when it
+    /// starts (see [`sdk_version_policy`](Self::sdk_version_policy)).
+    ///
+    /// ```
+    /// use signal_fish_client::{SdkVersion, SignalFishConfig};
+    ///
+    /// let version = SdkVersion::crate_default()
+    ///     .with_build_metadata("gabc123")?
+    ///     .with_channel("beta")?;
+    /// let config = SignalFishConfig::new("mb_app_abc123").with_sdk_version(version);
+    /// assert!(config.sdk_version.unwrap().ends_with("+beta.gabc123"));
+    /// # Ok::<(), signal_fish_client::InvalidSdkVersion>(())
+    /// ```
+    #[must_use]
+    pub fn with_sdk_version(mut self, version: impl Into<String>) -> Self {
+        self.sdk_version = Some(version.into());
+        self
+    }
+
+    /// Set how a non-SemVer [`sdk_version`](Self::sdk_version) is handled.
+    #[must_use]
+    pub fn with_sdk_version_policy(mut self, policy: crate::SdkVersionPolicy) -> Self {
+        self.sdk_version_policy = policy;
+        self
+    }
+
     /// Set the capacity of the bounded event channel.
     ///
     /// Defaults to **256**. Values below 1 are clamped to 1.
@@ -265,6 +719,17 @@ impl SignalFishConfig {
         self
     }
 
+    /// Also publish every event to a broadcast channel of `capacity`, for
+    /// [`SignalFishClient::subscribe_broadcast`].
+    ///
+    /// See [`broadcast_events`](Self::broadcast_events) for how lagging
+    /// receivers are handled. Values below 1 are clamped to 1.
+    #[must_use]
+    pub fn with_broadcast_events(mut self, capacity: usize) -> Self {
+        self.broadcast_events = Some(capacity.max(1));
+        self
+    }
+
     /// Set the capacity of the bounded outgoing command queue.
     ///
     /// See [`command_channel_capacity`](Self::command_channel_capacity) for
@@ -287,6 +752,26 @@ impl SignalFishConfig {
         self
     }
 
+    /// Select whether `shutdown()` sends queued commands before closing.
+    ///
+    /// Defaults to **`true`**. See
+    /// [`flush_on_shutdown`](Self::flush_on_shutdown).
+    #[must_use]
+    pub fn with_flush_on_shutdown(mut self, flush: bool) -> Self {
+        self.flush_on_shutdown = flush;
+        self
+    }
+
+    /// Select whether `shutdown()` leaves the current room before closing.
+    ///
+    /// Defaults to **`false`**. See
+    /// [`leave_room_on_shutdown`](Self::leave_room_on_shutdown).
+    #[must_use]
+    pub fn with_leave_room_on_shutdown(mut self, leave: bool) -> Self {
+        self.leave_room_on_shutdown = leave;
+        self
+    }
+
     /// Select how delivery-accountability violations affect the connection.
     #[must_use]
     pub fn with_protocol_violation_policy(mut self, policy: ProtocolViolationPolicy) -> Self {
@@ -294,6 +779,351 @@ impl SignalFishConfig {
         self
     }
 
+    /// Select whether an `AuthenticationError` clears the local room session.
+    ///
+    /// Defaults to **`true`**. See
+    /// [`clear_session_on_auth_failure`](Self::clear_session_on_auth_failure).
+    #[must_use]
+    pub fn with_clear_session_on_auth_failure(mut self, clear: bool) -> Self {
+        self.clear_session_on_auth_failure = clear;
+        self
+    }
+
+    /// Select whether a repeated `Authenticated` in the same session is
+    /// surfaced as an event.
+    ///
+    /// Defaults to **`true`**. See
+    /// [`reemit_session_events`](Self::reemit_session_events).
+    #[must_use]
+    pub fn with_reemit_session_events(mut self, reemit: bool) -> Self {
+        self.reemit_session_events = reemit;
+        self
+    }
+
+    /// Select whether blank text frames are reported as decode failures.
+    ///
+    /// Defaults to **`false`**. See
+    /// [`strict_blank_frames`](Self::strict_blank_frames).
+    #[must_use]
+    pub fn with_strict_blank_frames(mut self, strict: bool) -> Self {
+        self.strict_blank_frames = strict;
+        self
+    }
+
+    /// Select whether a text frame may carry concatenated messages.
+    ///
+    /// Defaults to **`false`** (concatenated messages are split). See
+    /// [`strict_message_framing`](Self::strict_message_framing).
+    #[must_use]
+    pub fn with_strict_message_framing(mut self, strict: bool) -> Self {
+        self.strict_message_framing = strict;
+        self
+    }
+
+    /// Select whether malformed parts of room payloads fail the message.
+    ///
+    /// Defaults to **`false`** (they are salvaged). See
+    /// [`strict_payloads`](Self::strict_payloads).
+    #[must_use]
+    pub fn with_strict_payloads(mut self, strict: bool) -> Self {
+        self.strict_payloads = strict;
+        self
+    }
+
+    /// Select whether undecodable messages with a `type` tag arrive whole.
+    ///
+    /// Defaults to **`false`**. See
+    /// [`forward_unknown_messages`](Self::forward_unknown_messages).
+    #[must_use]
+    pub fn with_forward_unknown_messages(mut self, forward: bool) -> Self {
+        self.forward_unknown_messages = forward;
+        self
+    }
+
+    /// Select whether server announcements are dropped.
+    ///
+    /// Defaults to **`false`**. See
+    /// [`suppress_announcements`](Self::suppress_announcements).
+    #[must_use]
+    pub fn with_suppress_announcements(mut self, suppress: bool) -> Self {
+        self.suppress_announcements = suppress;
+        self
+    }
+
+    /// Map legacy announcements, relayed as `GameData` from the system player
+    /// `sender`, to `Announcement` events.
+    ///
+    /// See [`legacy_announcement_sender`](Self::legacy_announcement_sender).
+    #[must_use]
+    pub fn with_legacy_announcement_sender(mut self, sender: PlayerId) -> Self {
+        self.legacy_announcement_sender = Some(sender);
+        self
+    }
+
+    /// Select whether room-scoped events from a room the client has since
+    /// left are dropped.
+    ///
+    /// Defaults to **`false`**. See
+    /// [`drop_cross_epoch_events`](Self::drop_cross_epoch_events).
+    #[must_use]
+    pub fn with_drop_cross_epoch_events(mut self, drop: bool) -> Self {
+        self.drop_cross_epoch_events = drop;
+        self
+    }
+
+    /// Set the limits used to grade `health()`.
+    ///
+    /// See [`HealthThresholds`](crate::HealthThresholds) for the defaults.
+    #[must_use]
+    pub fn with_health_thresholds(mut self, thresholds: crate::HealthThresholds) -> Self {
+        self.health_thresholds = thresholds;
+        self
+    }
+
+    /// Install a formatter that supplies (for example localized) text for
+    /// error codes rendered by `describe_error_code()`.
+    #[must_use]
+    pub fn with_error_code_formatter(mut self, formatter: crate::ErrorCodeFormatter) -> Self {
+        self.error_code_formatter = Some(formatter);
+        self
+    }
+
+    /// Install a hook told about every failed `decode_game_data()` /
+    /// `try_decode_game_data()` call, e.g. to count bad payloads per sender.
+    #[must_use]
+    pub fn with_decode_failure_hook(mut self, hook: crate::DecodeFailureHook) -> Self {
+        self.decode_failure_hook = Some(hook);
+        self
+    }
+
+    /// Install a hook told about each event enqueued for each subscriber,
+    /// to record one global emission sequence in tests.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn with_emission_hook(mut self, hook: crate::testing::EmissionHook) -> Self {
+        self.emission_hook = Some(hook);
+        self
+    }
+
+    /// Name this client's tasks and tracing span.
+    ///
+    /// See [`label`](Self::label).
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Coalesce bursts of `LobbyStateChanged` events within `window`.
+    ///
+    /// See [`lobby_state_debounce`](Self::lobby_state_debounce).
+    #[must_use]
+    pub fn with_lobby_state_debounce(mut self, window: Duration) -> Self {
+        self.lobby_state_debounce = Some(window);
+        self
+    }
+
+    /// Send a heartbeat `Ping` every `interval` while authenticated.
+    ///
+    /// See [`ping_interval`](Self::ping_interval).
+    #[must_use]
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Send a heartbeat `Ping` every `interval` while authenticated, and
+    /// close the connection when a `Pong` does not follow within `timeout`.
+    ///
+    /// Sets [`ping_interval`](Self::ping_interval) and
+    /// [`pong_timeout`](Self::pong_timeout).
+    #[must_use]
+    pub fn with_heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self.pong_timeout = Some(timeout);
+        self
+    }
+
+    /// Send a time-sync request every `interval` while authenticated.
+    ///
+    /// See [`time_sync_interval`](Self::time_sync_interval).
+    #[must_use]
+    pub fn with_time_sync_interval(mut self, interval: Duration) -> Self {
+        self.time_sync_interval = Some(interval);
+        self
+    }
+
+    /// Set how many recent time-sync samples the server-time estimate keeps.
+    ///
+    /// See [`time_sync_samples`](Self::time_sync_samples).
+    #[must_use]
+    pub fn with_time_sync_samples(mut self, samples: usize) -> Self {
+        self.time_sync_samples = samples;
+        self
+    }
+
+    /// Parse received frames off the transport loop, queueing up to `depth`.
+    ///
+    /// See [`decode_pipeline`](Self::decode_pipeline).
+    #[must_use]
+    pub fn with_decode_pipeline(mut self, depth: usize) -> Self {
+        self.decode_pipeline = Some(depth);
+        self
+    }
+
+    /// Select what happens once every event receiver is dropped.
+    ///
+    /// See [`on_receiver_dropped`](Self::on_receiver_dropped).
+    #[must_use]
+    pub fn with_on_receiver_dropped(mut self, policy: OnReceiverDropped) -> Self {
+        self.on_receiver_dropped = policy;
+        self
+    }
+
+    /// Bound how long a drain waits for the current room to end.
+    ///
+    /// See [`drain_timeout`](Self::drain_timeout).
+    #[must_use]
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep up to `limit` records of ended room sessions.
+    ///
+    /// See [`room_history`](Self::room_history).
+    #[must_use]
+    pub fn with_room_history(mut self, limit: usize) -> Self {
+        self.room_history = Some(limit);
+        self
+    }
+
+    /// Set how a client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// retries a lost connection.
+    ///
+    /// See [`ReconnectPolicy`](crate::ReconnectPolicy) for the defaults.
+    #[must_use]
+    pub fn with_reconnect_policy(mut self, policy: crate::ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Set whether a client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// returns to its room after an outage.
+    ///
+    /// See [`ResumePolicy`](crate::ResumePolicy).
+    #[must_use]
+    pub fn with_resume_policy(mut self, policy: crate::ResumePolicy) -> Self {
+        self.resume_policy = policy;
+        self
+    }
+
+    /// Set how many commands a client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// holds while its connection is down.
+    ///
+    /// See [`offline_queue_capacity`](Self::offline_queue_capacity).
+    #[must_use]
+    pub fn with_offline_queue_capacity(mut self, capacity: usize) -> Self {
+        self.offline_queue_capacity = capacity;
+        self
+    }
+
+    /// Set how long a `Reconnect` may go unanswered before another may be
+    /// sent.
+    ///
+    /// See [`reconnect_answer_timeout`](Self::reconnect_answer_timeout).
+    #[must_use]
+    pub fn with_reconnect_answer_timeout(mut self, timeout: Duration) -> Self {
+        self.reconnect_answer_timeout = timeout;
+        self
+    }
+
+    /// Report the transport loop as stalled after `threshold` in one step.
+    ///
+    /// See [`loop_watchdog`](Self::loop_watchdog).
+    #[must_use]
+    pub fn with_loop_watchdog(mut self, threshold: Duration) -> Self {
+        self.loop_watchdog = Some(threshold);
+        self
+    }
+
+    /// Set whether a stalled connection is abandoned as lost.
+    ///
+    /// See [`restart_stalled_loop`](Self::restart_stalled_loop).
+    #[must_use]
+    pub fn with_restart_stalled_loop(mut self, restart: bool) -> Self {
+        self.restart_stalled_loop = restart;
+        self
+    }
+
+    /// Drop queued messages of `kind` that have waited longer than
+    /// `deadline` to be sent.
+    ///
+    /// See [`message_deadlines`](Self::message_deadlines).
+    #[must_use]
+    pub fn with_message_deadline(mut self, kind: ClientMessageKind, deadline: Duration) -> Self {
+        self.message_deadlines.insert(kind, deadline);
+        self
+    }
+
+    /// Keep the client's session ticket in `store`.
+    ///
+    /// See [`session_store`](Self::session_store).
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use signal_fish_client::{MemorySessionStore, ResumePolicy, SignalFishConfig};
+    ///
+    /// let config = SignalFishConfig::new("mb_app_abc123")
+    ///     .with_session_store(Arc::new(MemorySessionStore::new()))
+    ///     .with_resume_policy(ResumePolicy::Auto);
+    /// # drop(config);
+    /// ```
+    #[must_use]
+    pub fn with_session_store(mut self, store: std::sync::Arc<dyn crate::SessionStore>) -> Self {
+        self.session_store = Some(store);
+        self
+    }
+
+    /// Select how a `PlayerLeft` naming this client is handled.
+    ///
+    /// See [`self_removal_policy`](Self::self_removal_policy).
+    #[must_use]
+    pub fn with_self_removal_policy(mut self, policy: SelfRemovalPolicy) -> Self {
+        self.self_removal_policy = policy;
+        self
+    }
+
+    /// Select how a message scoped to another room is handled.
+    ///
+    /// See [`session_inconsistency_policy`](Self::session_inconsistency_policy).
+    #[must_use]
+    pub fn with_session_inconsistency_policy(mut self, policy: SessionInconsistencyPolicy) -> Self {
+        self.session_inconsistency_policy = policy;
+        self
+    }
+
+    /// Check that the server authenticates this app under `organization`.
+    ///
+    /// See [`expected_organization`](Self::expected_organization).
+    #[must_use]
+    pub fn with_expected_organization(mut self, organization: impl Into<String>) -> Self {
+        self.expected_organization = Some(organization.into());
+        self
+    }
+
+    /// Select how an organization mismatch is handled.
+    ///
+    /// See [`organization_mismatch_policy`](Self::organization_mismatch_policy).
+    #[must_use]
+    pub fn with_organization_mismatch_policy(mut self, policy: OrganizationMismatchPolicy) -> Self {
+        self.organization_mismatch_policy = policy;
+        self
+    }
+
     /// Opt into the protocol v3 P2P mesh.
     ///
     /// This is the one-liner for "I have a WebRTC stack — give me mesh with relay
@@ -393,9 +1223,92 @@ pub enum ProtocolViolationPolicy {
     Observe,
 }
 
-// ── JoinRoomParams ──────────────────────────────────────────────────
+/// Handling of a server-initiated removal announced by a `PlayerLeft` that
+/// names this client.
+///
+/// Some server versions follow such a `PlayerLeft` with `RoomLeft`, others do
+/// not. Either way a `RoomLeft` that does arrive clears the room as usual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfRemovalPolicy {
+    /// Wait up to `grace` for `RoomLeft`; if it does not arrive, clear the
+    /// room state and emit
+    /// [`RemovedFromRoom`](crate::SignalFishEvent::RemovedFromRoom). A zero
+    /// grace clears immediately.
+    ClearAfter {
+        /// How long to wait for the server's own `RoomLeft`.
+        grace: Duration,
+    },
+    /// Keep the room state, emit
+    /// [`SelfRemovalPending`](crate::SignalFishEvent::SelfRemovalPending), and
+    /// rely on the server's `RoomLeft` (or the application) to leave.
+    AwaitRoomLeft,
+}
 
-/// Parameters for joining (or creating) a room.
+impl Default for SelfRemovalPolicy {
+    fn default() -> Self {
+        Self::ClearAfter {
+            grace: DEFAULT_SELF_REMOVAL_GRACE,
+        }
+    }
+}
+
+/// Handling of a server message that names a different room than the
+/// client's session.
+///
+/// Every policy first emits
+/// [`SessionInconsistency`](crate::SignalFishEvent::SessionInconsistency).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionInconsistencyPolicy {
+    /// Apply the message as sent: the server's view wins.
+    #[default]
+    Apply,
+    /// Drop the message; local state is unchanged and no event for it is
+    /// emitted.
+    Ignore,
+    /// Drop the message and close the connection, so the application
+    /// reconnects and takes the server's state afresh.
+    Resync,
+}
+
+/// Handling of an `Authenticated` naming a different organization than
+/// [`SignalFishConfig::expected_organization`].
+///
+/// Every policy first emits
+/// [`OrganizationMismatch`](crate::SignalFishEvent::OrganizationMismatch).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrganizationMismatchPolicy {
+    /// Log a warning and authenticate as usual.
+    #[default]
+    Warn,
+    /// Treat authentication as failed: no `Authenticated` is emitted or
+    /// applied, and the connection closes.
+    Disconnect,
+}
+
+/// What the async client does when the application drops every event
+/// receiver but keeps the client handle.
+///
+/// Without a receiver nothing observes the session, yet by default the loop
+/// still parses every frame and updates state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum OnReceiverDropped {
+    /// Carry on as if a receiver were listening.
+    #[default]
+    KeepRunning,
+    /// Close the transport and stop the loop, as
+    /// [`shutdown`](SignalFishClient::shutdown) would. Later operations fail
+    /// with [`SignalFishError::NotConnected`].
+    ShutdownClient,
+    /// Keep the connection and the automatic pings, but discard received
+    /// frames unparsed, counted in [`ClientStats::paused_frames_skipped`],
+    /// until [`subscribe`](SignalFishClient::subscribe) attaches a receiver.
+    /// Room state is stale after a pause; rejoin or reconnect if it matters.
+    PauseParsing,
+}
+
+// ── JoinRoomParams ──────────────────────────────────────────────────
+
+/// Parameters for joining (or creating) a room.
 ///
 /// Only `game_name` and `player_name` are required. Leave `room_code` as
 /// `None` for quick-match / auto-create behavior.
@@ -507,6 +1420,79 @@ pub struct ClientStats {
     /// growth means protocol drift (a server newer than this SDK) or a
     /// corrupting middlebox.
     pub messages_undecodable: u64,
+    /// Empty or whitespace-only text frames skipped without decoding.
+    ///
+    /// Reverse proxies inject these as keepalives. They are not counted in
+    /// `messages_undecodable` unless
+    /// [`SignalFishConfig::strict_blank_frames`] is set.
+    pub blank_frames_skipped: u64,
+    /// Text frames that carried more than one message.
+    ///
+    /// Non-zero means something on the path coalesces frames; it is never
+    /// counted under [`SignalFishConfig::strict_message_framing`].
+    pub multi_message_frames: u64,
+    /// Frames discarded unparsed while no event receiver was attached, under
+    /// [`OnReceiverDropped::PauseParsing`].
+    pub paused_frames_skipped: u64,
+    /// Room-scoped events dropped under
+    /// [`SignalFishConfig::drop_cross_epoch_events`] because the room changed
+    /// before they were delivered.
+    pub cross_epoch_events_dropped: u64,
+    /// Commands evicted from a full
+    /// [`offline_queue_capacity`](SignalFishConfig::offline_queue_capacity)
+    /// queue while a reconnecting client was disconnected.
+    pub offline_commands_evicted: u64,
+    /// Queued messages dropped unsent because their deadline passed, per
+    /// [`SignalFishConfig::message_deadlines`] or
+    /// [`SignalFishClient::send_game_data_with_deadline`].
+    pub messages_expired: u64,
+}
+
+/// Outgoing messages counted per [`ClientMessageKind`], for seeing which
+/// kinds spend the app's server-side rate limits.
+///
+/// Each message is counted once when it enters the outgoing queue and again
+/// when the transport accepts its frame, so `enqueued - sent` per kind is
+/// what was queued but never went out (dropped at shutdown, lost to a failed
+/// send, or still queued). Messages the queue refused with
+/// [`SendBufferFull`](crate::SignalFishError::SendBufferFull) are not counted.
+/// Counters are cumulative for the lifetime of the client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutgoingBreakdown {
+    /// Messages accepted into the outgoing queue, by kind.
+    pub enqueued: BTreeMap<ClientMessageKind, u64>,
+    /// Messages whose frames the transport accepted, by kind.
+    pub sent: BTreeMap<ClientMessageKind, u64>,
+}
+
+impl OutgoingBreakdown {
+    /// Messages of `kind` accepted into the outgoing queue.
+    #[must_use]
+    pub fn enqueued(&self, kind: ClientMessageKind) -> u64 {
+        self.enqueued.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Messages of `kind` whose frames the transport accepted.
+    #[must_use]
+    pub fn sent(&self, kind: ClientMessageKind) -> u64 {
+        self.sent.get(&kind).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl OutgoingBreakdown {
+    pub(crate) fn record_enqueued(&mut self, kind: ClientMessageKind) {
+        Self::bump(&mut self.enqueued, kind);
+    }
+
+    pub(crate) fn record_sent(&mut self, kind: ClientMessageKind) {
+        Self::bump(&mut self.sent, kind);
+    }
+
+    fn bump(counts: &mut BTreeMap<ClientMessageKind, u64>, kind: ClientMessageKind) {
+        let count = counts.entry(kind).or_default();
+        *count = count.saturating_add(1);
+    }
 }
 
 /// Coherent synchronous view of client/session state.
@@ -522,6 +1508,35 @@ pub struct ClientSnapshot {
     pub reconnection_token: Option<String>,
     /// Whether accountability policy currently suppresses room game data.
     pub quarantined: bool,
+    /// Logical session counter, starting at `0`.
+    ///
+    /// Incremented only when the logical session resets: an
+    /// `AuthenticationError` that invalidates the session, or a disconnect.
+    /// A server re-confirming authentication or restoring the room via
+    /// `Reconnected` keeps the epoch, so game state keyed on it survives.
+    ///
+    /// An outage that a client started with
+    /// [`start_with_reconnect`](SignalFishClient::start_with_reconnect)
+    /// recovers from is not a disconnect: the epoch is kept while the room it
+    /// held can still be resumed, and advances once that resume is abandoned
+    /// — the room had no resume ticket, the server answered
+    /// `ReconnectionFailed`, the client joined another room, or reconnecting
+    /// gave up.
+    pub session_epoch: u64,
+    /// Whether the connection ended because the very first outbound frame
+    /// (the automatic `Authenticate`) failed to send.
+    ///
+    /// The server never saw this client, so treat it as a connect failure —
+    /// retry the whole connect — rather than the loss of an established
+    /// session. Stays set after the accompanying `Disconnected`.
+    pub handshake_failed: bool,
+    /// Whether [`SignalFishClient::begin_drain`] was called: no new rooms are
+    /// taken and the client shuts down once its current room ends. Stays
+    /// set after the accompanying `Disconnected`.
+    pub draining: bool,
+    /// The most recent `Ping`/`Pong` round trip, as
+    /// [`LatencyStats::last`](crate::LatencyStats::last) reports it.
+    pub last_rtt: Option<Duration>,
 }
 
 impl std::fmt::Debug for ClientSnapshot {
@@ -541,6 +1556,10 @@ impl std::fmt::Debug for ClientSnapshot {
                 &self.reconnection_token.as_ref().map(|_| "<redacted>"),
             )
             .field("quarantined", &self.quarantined)
+            .field("session_epoch", &self.session_epoch)
+            .field("handshake_failed", &self.handshake_failed)
+            .field("draining", &self.draining)
+            .field("last_rtt", &self.last_rtt)
             .finish()
     }
 }
@@ -552,8 +1571,9 @@ impl std::fmt::Debug for ClientSnapshot {
 /// Created via [`SignalFishClient::start`], which spawns a background transport
 /// loop and returns this handle together with an event receiver.
 ///
-/// All synchronous public methods serialize a [`ClientMessage`] and queue it
-/// to the transport loop over a **bounded** channel, returning immediately
+/// All synchronous public methods serialize a
+/// [`ClientMessage`](crate::protocol::ClientMessage) and queue it to the
+/// transport loop over a **bounded** channel, returning immediately
 /// once the message is queued (no round-trip await). When the queue is full
 /// they fail fast with [`SignalFishError::SendBufferFull`]; the waiting
 /// variants ([`send_game_data_reliable`](Self::send_game_data_reliable),
@@ -562,7 +1582,7 @@ impl std::fmt::Debug for ClientSnapshot {
 #[cfg(feature = "tokio-runtime")]
 pub struct SignalFishClient {
     /// Sender half of the bounded command channel to the transport loop.
-    cmd_tx: mpsc::Sender<ClientCommand>,
+    cmd_tx: mpsc::Sender<LoopCommand>,
     /// Shared state updated by the transport loop.
     state: Arc<Mutex<ClientCore>>,
     /// Handle to the background transport loop task.
@@ -574,6 +1594,34 @@ pub struct SignalFishClient {
     /// Timeout for the graceful shutdown.
     #[cfg(feature = "tokio-runtime")]
     shutdown_timeout: Duration,
+    /// Delivers the terminal `Disconnected` when the loop is aborted first.
+    #[cfg(feature = "tokio-runtime")]
+    terminal: TerminalFallback,
+    /// Closes when the transport loop finishes or is aborted; never sent on.
+    #[cfg(feature = "tokio-runtime")]
+    loop_ended: tokio::sync::watch::Receiver<()>,
+    /// The loop's broadcast channel, under
+    /// [`SignalFishConfig::broadcast_events`].
+    #[cfg(feature = "tokio-runtime")]
+    broadcast: Option<broadcast::WeakSender<SignalFishEvent>>,
+}
+
+/// Registration of an [`on_event`](SignalFishClient::on_event) handler.
+///
+/// Dropping the guard stops the handler's dispatch task; an event it is
+/// handling at that moment still completes.
+#[cfg(feature = "tokio-runtime")]
+#[must_use = "dropping the guard removes the handler"]
+#[derive(Debug)]
+pub struct HandlerGuard {
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl Drop for HandlerGuard {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
 
 /// Async client handle unavailable without the `tokio-runtime` feature.
@@ -586,7 +1634,7 @@ pub struct SignalFishClient {
 impl SignalFishClient {
     /// Start the client transport loop and return a handle plus event receiver.
     ///
-    /// The transport loop immediately sends an [`Authenticate`](ClientMessage::Authenticate)
+    /// The transport loop immediately sends an [`Authenticate`](crate::protocol::ClientMessage::Authenticate)
     /// message using the provided [`SignalFishConfig`].
     ///
     /// The loop is spawned with [`tokio::spawn`] and therefore only makes
@@ -604,55 +1652,437 @@ impl SignalFishClient {
     /// # Returns
     ///
     /// A tuple of `(client_handle, event_receiver)`. The event receiver yields
-    /// [`SignalFishEvent`]s until the transport closes or the client shuts down.
+    /// [`SignalFishEvent`]s, in the order the server's messages produced them,
+    /// until the transport closes or the client shuts down. See
+    /// [`start_with_subscribers`](Self::start_with_subscribers) for ordering
+    /// across several receivers.
     #[must_use = "the event receiver must be used to receive events"]
     pub fn start(
         transport: impl Transport + Send + 'static,
         config: SignalFishConfig,
     ) -> (Self, mpsc::Receiver<SignalFishEvent>) {
-        // Clamp capacities to at least 1 (tokio panics on 0).
-        let cmd_capacity = config.command_channel_capacity.max(1);
-        let (cmd_tx, cmd_rx) = mpsc::channel::<ClientCommand>(cmd_capacity);
-        let capacity = config.event_channel_capacity.max(1);
+        let capacity = start_capacity(&config);
         let (event_tx, event_rx) = mpsc::channel::<SignalFishEvent>(capacity);
-        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let client = Self::spawn(transport, config, vec![EventSender::Plain(event_tx)]);
+        (client, event_rx)
+    }
+
+    /// Like [`start`](Self::start), but the receiver yields each event in an
+    /// [`EventEnvelope`] carrying its sequence number and emission time, for
+    /// ordering events after they leave the receiver (a game loop's buffer,
+    /// a log).
+    ///
+    /// Delivery, ordering, and backpressure are those of `start`.
+    #[must_use = "the event receiver must be used to receive events"]
+    pub fn start_enveloped(
+        transport: impl Transport + Send + 'static,
+        config: SignalFishConfig,
+    ) -> (Self, mpsc::Receiver<EventEnvelope>) {
+        let capacity = start_capacity(&config);
+        let (event_tx, event_rx) = mpsc::channel::<EventEnvelope>(capacity);
+        let client = Self::spawn(transport, config, vec![EventSender::Enveloped(event_tx)]);
+        (client, event_rx)
+    }
 
-        let requested_game_data_encoding = config.game_data_format.unwrap_or_default();
-        let mesh_enabled = config
-            .supported_transports
+    /// Like [`start`](Self::start), but returns `subscribers` independent
+    /// event receivers (at least one).
+    ///
+    /// Each receiver has its own channel of
+    /// [`event_channel_capacity`](SignalFishConfig::event_channel_capacity)
+    /// and sees every event in the same order, with the same lossless
+    /// backpressure as a single receiver: a full receiver pauses the transport
+    /// loop until it is drained. The terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected) goes to every receiver.
+    ///
+    /// Dropping a receiver unsubscribes it; the loop keeps serving the others.
+    /// [`subscribe`](Self::subscribe) adds one later.
+    ///
+    /// # Ordering across receivers
+    ///
+    /// Each event is handed to the receivers in index order, and to every
+    /// live receiver before any receiver is handed the next event. So once
+    /// receiver `i` yields an event, every receiver has already been handed
+    /// every earlier event, and receivers `0..i` this one too; a consumer
+    /// that forwards work to another can rely on the other having seen the
+    /// cause. The one exception is the terminal `Disconnected` after a
+    /// shutdown: a receiver whose channel is full gets it from a background
+    /// task, possibly after receivers with a higher index.
+    #[must_use = "the event receivers must be used to receive events"]
+    pub fn start_with_subscribers(
+        transport: impl Transport + Send + 'static,
+        config: SignalFishConfig,
+        subscribers: usize,
+    ) -> (Self, Vec<mpsc::Receiver<SignalFishEvent>>) {
+        let capacity = start_capacity(&config);
+        let (senders, receivers) = (0..subscribers.max(1))
+            .map(|_| {
+                let (tx, rx) = mpsc::channel::<SignalFishEvent>(capacity);
+                (EventSender::Plain(tx), rx)
+            })
+            .unzip();
+        let client = Self::spawn(transport, config, senders);
+        (client, receivers)
+    }
+
+    /// Like [`start`](Self::start), but dials its own transports through
+    /// `factory` and reconnects when one fails.
+    ///
+    /// The first transport is dialed in the background. When a transport
+    /// fails (a receive or send error, or the server closing the connection)
+    /// the client emits [`Reconnecting`](SignalFishEvent::Reconnecting), waits
+    /// out the backoff of [`reconnect_policy`](SignalFishConfig::reconnect_policy),
+    /// and dials again, sending a fresh `Authenticate` on each new transport;
+    /// `Connected` and `Authenticated` then arrive as after `start`. Sends
+    /// made while the connection is down are held in a queue of
+    /// [`offline_queue_capacity`](SignalFishConfig::offline_queue_capacity)
+    /// and go out in order once the new connection authenticates; a full
+    /// queue evicts its oldest command, and pings are dropped rather than
+    /// held. Room membership does not survive
+    /// the outage on its own;
+    /// [`resume_policy`](SignalFishConfig::resume_policy) decides whether the
+    /// client returns to the room with `Reconnect`.
+    ///
+    /// The client ends with the terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected) as `start` does when it
+    /// shuts down, drains, or the server rejects authentication, and also
+    /// once [`max_attempts`](crate::ReconnectPolicy::max_attempts) dials of
+    /// one outage have failed. A dialed transport that an earlier session
+    /// already claimed (see [`Transport::claim_session`]) ends it too, with
+    /// [`DisconnectReason::HandshakeRefused`] naming
+    /// [`SignalFishError::TransportReused`]; nothing is sent on it.
+    #[must_use = "the event receiver must be used to receive events"]
+    pub fn start_with_reconnect(
+        factory: impl TransportFactory,
+        config: SignalFishConfig,
+    ) -> (Self, mpsc::Receiver<SignalFishEvent>) {
+        let capacity = start_capacity(&config);
+        let (event_tx, event_rx) = mpsc::channel::<SignalFishEvent>(capacity);
+        let policy = config.reconnect_policy;
+        let authenticate = ClientCore::authenticate(&config);
+        let client = Self::spawn_loop(config, vec![EventSender::Plain(event_tx)], |context| {
+            reconnect_loop(factory, policy, authenticate, context)
+        });
+        (client, event_rx)
+    }
+
+    /// Attach another event receiver to the running client.
+    ///
+    /// The request is queued behind any commands already sent. The receiver
+    /// gets every event emitted after the transport loop handles it, none
+    /// from before, with the ordering and backpressure of the receivers from
+    /// [`start_with_subscribers`](Self::start_with_subscribers), and ends
+    /// with the terminal [`Disconnected`](SignalFishEvent::Disconnected).
+    /// Read [`snapshot`](Self::snapshot) for the state so far. Under
+    /// [`OnReceiverDropped::PauseParsing`] this resumes a paused client.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
+    /// is full.
+    pub fn subscribe(&mut self) -> Result<mpsc::Receiver<SignalFishEvent>> {
+        let (events, receiver) = mpsc::channel(self.event_channel_capacity());
+        self.attach(EventSender::Plain(events))?;
+        Ok(receiver)
+    }
+
+    /// Like [`subscribe`](Self::subscribe), but the receiver yields
+    /// [`EventEnvelope`]s. Its first envelope's
+    /// [`seq`](EventEnvelope::seq) is one past the events emitted before it
+    /// joined.
+    ///
+    /// # Errors
+    ///
+    /// As for [`subscribe`](Self::subscribe).
+    pub fn subscribe_enveloped(&mut self) -> Result<mpsc::Receiver<EventEnvelope>> {
+        let (events, receiver) = mpsc::channel(self.event_channel_capacity());
+        self.attach(EventSender::Enveloped(events))?;
+        Ok(receiver)
+    }
+
+    /// A receiver of the broadcast channel enabled with
+    /// [`with_broadcast_events`](SignalFishConfig::with_broadcast_events),
+    /// or `None` without it or once the transport loop has ended.
+    ///
+    /// The receiver gets every event published after this call, in the same
+    /// order as the other receivers, ending with the terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected) and then
+    /// [`RecvError::Closed`](broadcast::error::RecvError::Closed). Broadcast
+    /// delivery never waits for a receiver: one that falls
+    /// [`broadcast_events`](SignalFishConfig::broadcast_events) events
+    /// behind loses the oldest, and its next `recv` returns
+    /// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged) with how
+    /// many it missed before resuming at the oldest event still held. The
+    /// receivers from [`start`](Self::start) and
+    /// [`subscribe`](Self::subscribe) keep their lossless backpressure.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(client: &signal_fish_client::SignalFishClient) {
+    /// use tokio::sync::broadcast::error::RecvError;
+    ///
+    /// let mut lobby = client.subscribe_broadcast().expect("broadcast enabled");
+    /// loop {
+    ///     match lobby.recv().await {
+    ///         Ok(event) => println!("{event:?}"),
+    ///         Err(RecvError::Lagged(missed)) => println!("skipped {missed} events"),
+    ///         Err(RecvError::Closed) => break,
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn subscribe_broadcast(&self) -> Option<broadcast::Receiver<SignalFishEvent>> {
+        self.broadcast
             .as_ref()
-            .is_some_and(|transports| transports.contains(&TransportKind::WebRtc));
-        let state = Arc::new(Mutex::new(ClientCore::new(
-            requested_game_data_encoding,
-            config.protocol_violation_policy,
-            mesh_enabled,
-        )));
-        let loop_state = Arc::clone(&state);
-
-        // Send the Authenticate message through the command channel so the
-        // transport loop picks it up as the very first outgoing message.
-        let auth_msg = ClientCore::authenticate(&config);
-        // This cannot fail: the channel was just created empty and its
-        // capacity is clamped to at least 1.
-        let _ = cmd_tx.try_send(auth_msg);
-
-        let task = tokio::spawn(transport_loop(
-            transport,
+            .and_then(broadcast::WeakSender::upgrade)
+            .map(|tx| tx.subscribe())
+    }
+
+    /// Sequence number of the last event the client emitted, or `0` before
+    /// the first.
+    ///
+    /// Every event counts, whether or not a receiver took it, so comparing
+    /// this with the last [`EventEnvelope::seq`] read shows how many events
+    /// are still queued or were never delivered to that receiver.
+    pub fn last_event_seq(&self) -> u64 {
+        self.terminal.sequence.last()
+    }
+
+    fn event_channel_capacity(&self) -> usize {
+        lock_core(&self.state)
+            .effective_config()
+            .event_channel_capacity
+            .value
+    }
+
+    fn attach(&mut self, events: EventSender) -> Result<()> {
+        let handed_off = self.terminal.register(&events);
+        self.send_command(LoopCommand::Subscribe { events, handed_off })
+    }
+
+    /// Call `handler` with every event emitted from now on, for integrations
+    /// that cannot own an event receiver.
+    ///
+    /// Each handler reads its own [`subscribe`](Self::subscribe) receiver on
+    /// a dispatch task, so handlers never run on the transport loop and any
+    /// number can be registered. A handler that falls behind applies the same
+    /// backpressure as a slow receiver; keep handlers short and hand longer
+    /// work to another task. The last call gets the terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected). Dropping the returned
+    /// [`HandlerGuard`] removes the handler.
+    ///
+    /// # Errors
+    ///
+    /// As for [`subscribe`](Self::subscribe).
+    pub fn on_event(
+        &mut self,
+        handler: impl Fn(&SignalFishEvent) + Send + Sync + 'static,
+    ) -> Result<HandlerGuard> {
+        let mut events = self.subscribe()?;
+        let name = format!("{}/handler", self.terminal.label);
+        let task = spawn_named(&self.terminal.runtime, &name, async move {
+            while let Some(event) = events.recv().await {
+                handler(&event);
+            }
+        });
+        Ok(HandlerGuard { task })
+    }
+
+    /// Wait until `matcher` returns `Some` for an event, and return its value.
+    ///
+    /// The events are read from an internal receiver of their own, so every
+    /// event, matched or not, still reaches the receivers from
+    /// [`start`](Self::start) and [`subscribe`](Self::subscribe) unchanged,
+    /// and before `matcher` sees it.
+    ///
+    /// The receiver is registered when `wait_for` is called, not when the
+    /// future is first polled, and the returned future does not borrow the
+    /// client. It sees every event emitted after the commands already queued,
+    /// so calling it before the action whose answer it waits for cannot miss
+    /// that answer. While the wait lasts the receiver applies the same
+    /// backpressure as any other; it is dropped when the wait ends.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     client: &mut signal_fish_client::SignalFishClient,
+    /// # ) -> signal_fish_client::error::Result<()> {
+    /// use std::time::Duration;
+    /// use signal_fish_client::SignalFishEvent;
+    ///
+    /// let starting = client.wait_for(Duration::from_secs(30), |event| match event {
+    ///     SignalFishEvent::GameStarting { peer_connections } => Some(peer_connections.len()),
+    ///     _ => None,
+    /// });
+    /// client.set_ready()?;
+    /// println!("starting with {} peers", starting.await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// or once a [`Disconnected`](SignalFishEvent::Disconnected) that
+    /// `matcher` does not match arrives;
+    /// [`SignalFishError::Timeout`] if nothing matches within `timeout`,
+    /// counted from the first poll; or [`SignalFishError::SendBufferFull`]
+    /// if the outgoing command queue is full.
+    ///
+    /// # Cancel safety
+    ///
+    /// Dropping the future ends the wait and consumes nothing.
+    pub fn wait_for<F, T>(
+        &self,
+        timeout: Duration,
+        mut matcher: F,
+    ) -> impl std::future::Future<Output = Result<T>>
+    where
+        F: FnMut(&SignalFishEvent) -> Option<T>,
+    {
+        let (waiter, events) = mpsc::channel(self.event_channel_capacity());
+        let registered = self
+            .send_command(LoopCommand::Watch(waiter))
+            .map(|()| events);
+        async move {
+            let mut events = registered?;
+            let matched = async {
+                while let Some(event) = events.recv().await {
+                    if let Some(value) = matcher(&event) {
+                        return Ok(value);
+                    }
+                    if matches!(event, SignalFishEvent::Disconnected { .. }) {
+                        break;
+                    }
+                }
+                Err(SignalFishError::NotConnected)
+            };
+            tokio::time::timeout(timeout, matched)
+                .await
+                .unwrap_or(Err(SignalFishError::Timeout))
+        }
+    }
+
+    fn spawn(
+        transport: impl Transport + Send + 'static,
+        config: SignalFishConfig,
+        event_senders: Vec<EventSender>,
+    ) -> Self {
+        // The transport loop sends Authenticate ahead of every command.
+        let authenticate = ClientCore::authenticate(&config);
+        Self::spawn_loop(config, event_senders, |context| {
+            transport_loop(transport, authenticate, context)
+        })
+    }
+
+    /// Spawn the task running `session` with the loop's half of the
+    /// channels and state.
+    fn spawn_loop<S>(
+        config: SignalFishConfig,
+        mut event_senders: Vec<EventSender>,
+        session: impl FnOnce(LoopContext) -> S,
+    ) -> Self
+    where
+        S: std::future::Future<Output = ()> + Send + 'static,
+    {
+        // The loop holds the only strong sender, so broadcast receivers close
+        // when it ends.
+        let broadcast = config.broadcast_events.map(|capacity| {
+            let (tx, _) = broadcast::channel(capacity.max(1));
+            let weak = tx.downgrade();
+            event_senders.push(EventSender::Broadcast(tx));
+            weak
+        });
+        let cmd_capacity = config.command_channel_capacity.max(1);
+        let (cmd_tx, cmd_rx) = mpsc::channel::<LoopCommand>(cmd_capacity);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let (loop_alive, loop_ended) = tokio::sync::watch::channel(());
+
+        let state = Arc::new(Mutex::new(
+            ClientCore::new(&config).with_clock(|| tokio::time::Instant::now().into_std()),
+        ));
+
+        let label: Arc<str> = config.label.as_deref().unwrap_or(DEFAULT_LABEL).into();
+        let observer = EmissionObserver::new(&config);
+        let heartbeat = LoopHeartbeat::new(&config, &event_senders);
+        let terminal = TerminalFallback::new(
+            &event_senders,
+            Arc::clone(&label),
+            observer.clone(),
+            heartbeat.clone(),
+        );
+        let span = tracing::debug_span!(
+            "signal_fish_client",
+            label = %label,
+            organization = tracing::field::Empty,
+        );
+        if let Some(heartbeat) = &heartbeat {
+            spawn_named(
+                &terminal.runtime,
+                &format!("{label}/watchdog"),
+                watch_loop(
+                    Arc::clone(heartbeat),
+                    terminal.sequence.clone(),
+                    observer.clone(),
+                    loop_ended.clone(),
+                )
+                .in_current_span(),
+            );
+        }
+        let session = session(LoopContext {
             cmd_rx,
-            event_tx,
-            loop_state,
+            events: EventFanout {
+                senders: event_senders,
+                handed_off: terminal.handed_off.clone(),
+                waiters: Vec::new(),
+                sequence: terminal.sequence.clone(),
+                label: Arc::clone(&label),
+                observer,
+                heartbeat,
+            },
+            lobby_debounce: LobbyDebounce::new(config.lobby_state_debounce),
+            state: Arc::clone(&state),
             shutdown_rx,
-        ));
+            close_timeout: config.shutdown_timeout,
+            flush_on_shutdown: config.flush_on_shutdown,
+            leave_room_on_shutdown: config.leave_room_on_shutdown,
+            offline: OfflineQueue::new(config.offline_queue_capacity),
+            session_store: config.session_store.clone(),
+        });
+        if let Some(store) = config.session_store.clone() {
+            let tickets = lock_core(&state).session_ticket_watch();
+            spawn_named(
+                &terminal.runtime,
+                &format!("{label}/session-store"),
+                persist_session_tickets(store, tickets, loop_ended.clone()).in_current_span(),
+            );
+        }
+        let task = spawn_named(
+            &terminal.runtime,
+            &label,
+            async move {
+                // Dropped when the loop finishes or is aborted, which
+                // resolves `drained()`.
+                let _loop_alive = loop_alive;
+                session.await;
+            }
+            .instrument(span),
+        );
 
-        let client = Self {
+        Self {
             cmd_tx,
             state,
             task: Some(task),
             shutdown_tx: Some(shutdown_tx),
-            shutdown_timeout: config.shutdown_timeout,
-        };
-
-        (client, event_rx)
+            // A flush or leave on shutdown gets its own window before the
+            // close, so the outer deadline covers both.
+            shutdown_timeout: if config.flush_on_shutdown || config.leave_room_on_shutdown {
+                config.shutdown_timeout.saturating_mul(2)
+            } else {
+                config.shutdown_timeout
+            },
+            terminal,
+            loop_ended,
+            broadcast,
+        }
     }
 
     /// Shut down the client, closing the transport and stopping the background task.
@@ -660,13 +2090,33 @@ impl SignalFishClient {
     /// The shutdown signal preempts even a transport loop blocked on a full
     /// event channel (a consumer that stopped draining): the loop abandons at
     /// most the one event delivery it was waiting on, closes the transport
-    /// gracefully, and delivers a terminal
-    /// [`Disconnected`](SignalFishEvent::Disconnected) best-effort. The loop
-    /// is given [`shutdown_timeout`](SignalFishConfig::shutdown_timeout) to
-    /// finish; if the timeout expires (e.g. a transport whose `close()`
-    /// hangs), the task is aborted. After shutdown completes, the event
-    /// receiver yields the remaining buffered events and then `None` — treat
-    /// the channel closing as the authoritative end-of-stream signal.
+    /// gracefully, and emits the terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected). The loop is given
+    /// [`shutdown_timeout`](SignalFishConfig::shutdown_timeout) to finish; if
+    /// the timeout expires (e.g. a transport whose `close()` hangs), the task
+    /// is aborted and `shutdown` emits the `Disconnected` itself.
+    ///
+    /// Commands queued before the call, such as a final `leave_room()`, are
+    /// sent before the transport closes unless
+    /// [`flush_on_shutdown`](SignalFishConfig::flush_on_shutdown) is off. With
+    /// [`leave_room_on_shutdown`](SignalFishConfig::leave_room_on_shutdown)
+    /// set, the loop then leaves the room held and waits for the server to
+    /// confirm. The flush and the leave get one timeout between them, and
+    /// whatever is still queued when it expires is dropped; the close gets
+    /// another, so a slow flush still ends with a graceful close and
+    /// `shutdown` may take up to twice the timeout. With neither, the close
+    /// alone gets the one timeout.
+    ///
+    /// Either way every event receiver yields its remaining buffered events,
+    /// then exactly one `Disconnected`, then `None`. A receiver whose channel
+    /// is full gets the `Disconnected` once it drains a slot; `shutdown` does
+    /// not wait for that.
+    ///
+    /// # Cancel safety
+    ///
+    /// The shutdown signal goes out before the first await, so a cancelled
+    /// `shutdown` still ends the client: the loop winds down on its own, but
+    /// it is detached, and nothing aborts it if its close then hangs.
     pub async fn shutdown(&mut self) {
         debug!("SignalFishClient: shutdown requested");
 
@@ -685,17 +2135,73 @@ impl SignalFishClient {
                 }
                 Err(_) => {
                     warn!("transport loop did not exit within timeout; aborting task");
+                    let retained = self.terminal.retain();
                     task.abort();
                     if let Err(join_err) = task.await {
                         debug!("transport loop aborted: {join_err}");
                     }
+                    let event = aborted_disconnect(&self.state);
+                    self.terminal.deliver(retained, &event);
                 }
             }
         }
 
         let mut core = lock_core(&self.state);
         if core.is_connected() {
-            let _ = core.disconnect(Some("client shut down".into()));
+            let _ = core.disconnect(DisconnectReason::ClientShutdown);
+        }
+    }
+
+    /// Stop taking new rooms and shut down once the current one ends.
+    ///
+    /// For rolling restarts: from this call on, [`join_room`](Self::join_room)
+    /// and [`join_as_spectator`](Self::join_as_spectator) fail with
+    /// [`SignalFishError::Draining`], while the room the client is already in
+    /// keeps working — game data, ready, authority, and
+    /// [`leave_room`](Self::leave_room) are unaffected. Once the client holds
+    /// no room and has no join or reconnect awaiting an answer, the loop
+    /// closes the transport gracefully and emits
+    /// [`Disconnected`](SignalFishEvent::Disconnected) with
+    /// [`DisconnectReason::Drained`]; a client already idle shuts down right away.
+    /// [`SignalFishConfig::drain_timeout`] bounds the wait, ending with the
+    /// [`DisconnectReason::DrainTimedOut`]. [`ClientSnapshot::draining`] reports the
+    /// drain and [`drained`](Self::drained) resolves when it completes.
+    ///
+    /// Calling it again while draining has no further effect. Queued
+    /// commands ahead of the drain are still sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has already
+    /// closed. New rooms are refused either way.
+    ///
+    /// # Cancel safety
+    ///
+    /// New rooms are refused from the first poll on. Cancelled while the
+    /// command queue is full, the drain itself is never queued; call again to
+    /// start it.
+    pub async fn begin_drain(&self) -> Result<()> {
+        lock_core(&self.state).begin_drain();
+        self.cmd_tx
+            .send(LoopCommand::Drain)
+            .await
+            .map_err(|_| SignalFishError::NotConnected)
+    }
+
+    /// Resolve once the transport loop has finished.
+    ///
+    /// After [`begin_drain`](Self::begin_drain) this is the drain completing;
+    /// it also resolves on any other end of the connection, and immediately
+    /// when the loop already finished. The terminal
+    /// [`Disconnected`](SignalFishEvent::Disconnected) has been handed to the
+    /// receivers by then. The future does not borrow the client, so it can
+    /// be awaited alongside the room's remaining work.
+    pub fn drained(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut loop_ended = self.loop_ended.clone();
+        async move {
+            // Nothing is ever sent: `changed` fails once the loop drops the
+            // sender.
+            let _ = loop_ended.changed().await;
         }
     }
 }
@@ -711,10 +2217,68 @@ impl SignalFishClient {
     /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
     /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
     /// is full (the message is **not** queued; nothing is silently dropped).
+    /// Returns [`SignalFishError::MaxPlayersExceeded`] if `max_players` is
+    /// above the server's advertised
+    /// [`max_players_per_room`](crate::protocol::ServerLimits::max_players_per_room).
     pub fn join_room(&mut self, params: JoinRoomParams) -> Result<()> {
         self.send_operation(ClientOperation::JoinRoom(params))
     }
 
+    /// Join or create a room and wait for the server's answer.
+    ///
+    /// Sends `JoinRoom` like [`join_room`](Self::join_room) and resolves with
+    /// the [`RoomJoined`](SignalFishEvent::RoomJoined) payload. The answer is
+    /// still delivered as an event, so keep draining events while awaiting.
+    /// Only one call waits at a time; starting another stops the earlier one
+    /// waiting, which then fails with [`SignalFishError::NotConnected`].
+    ///
+    /// ```rust,no_run
+    /// # async fn example(client: &signal_fish_client::SignalFishClient) {
+    /// use std::time::Duration;
+    /// use signal_fish_client::{ErrorCode, JoinRoomParams, SignalFishError};
+    ///
+    /// let params = JoinRoomParams::new("my-game", "Alice").with_room_code("ABC123");
+    /// match client.join_room_and_wait(params, Duration::from_secs(5)).await {
+    ///     Ok(room) => println!("joined {} as {}", room.room_code, room.player_id),
+    ///     Err(SignalFishError::RoomJoinFailed { error_code: Some(ErrorCode::RoomFull), .. }) => {
+    ///         println!("room is full")
+    ///     }
+    ///     Err(error) => println!("{error}"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::RoomJoinFailed`] when the server refuses
+    /// the join, [`SignalFishError::ServerError`] when it answers with an
+    /// `Error`, and [`SignalFishError::NotConnected`] when the connection
+    /// ends first. Returns [`SignalFishError::Timeout`] when no answer
+    /// arrives within `timeout`; the join stays in flight, so a late
+    /// `RoomJoined` still puts the client in the room and is delivered as an
+    /// event. Sending fails as for [`join_room`](Self::join_room).
+    ///
+    /// # Cancel safety
+    ///
+    /// `JoinRoom` is queued on the first poll, so dropping the future after
+    /// that leaves the join in flight, as with a timeout: its answer is still
+    /// applied and delivered as an event. The waiter is removed when the
+    /// future drops, and a later call waits for its own answer.
+    pub async fn join_room_and_wait(
+        &self,
+        params: JoinRoomParams,
+        timeout: Duration,
+    ) -> Result<crate::protocol::RoomJoinedPayload> {
+        let (waiter, joined) = tokio::sync::oneshot::channel();
+        lock_core(&self.state).set_join_waiter(waiter);
+        let joined = PendingReply::new(&self.state, WaiterKind::Join, joined);
+        self.send_operation(ClientOperation::JoinRoom(params))?;
+        match tokio::time::timeout(timeout, joined).await {
+            Ok(answer) => answer.unwrap_or(Err(SignalFishError::NotConnected)),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
     /// Leave the current room.
     ///
     /// # Errors
@@ -726,6 +2290,65 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::LeaveRoom)
     }
 
+    /// Leave the current room and wait until the server confirms it.
+    ///
+    /// Resolves once [`RoomLeft`](SignalFishEvent::RoomLeft) arrives, or an
+    /// [`Error`](SignalFishEvent::Error) with
+    /// [`ErrorCode::NotInRoom`](crate::ErrorCode::NotInRoom): the server
+    /// holds no room for this client either way. After that the room is
+    /// cleared from [`snapshot`](Self::snapshot), so gameplay state can be
+    /// torn down and another room joined. Returns `Ok` at once, sending
+    /// nothing, when the client is not in a room.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     client: &signal_fish_client::SignalFishClient,
+    /// # ) -> signal_fish_client::error::Result<()> {
+    /// use std::time::Duration;
+    /// use signal_fish_client::JoinRoomParams;
+    ///
+    /// client.leave_room_and_wait(Duration::from_secs(5)).await?;
+    /// client
+    ///     .join_room_and_wait(JoinRoomParams::new("my-game", "Alice"), Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed
+    /// or closes before the confirmation, [`SignalFishError::Timeout`] if no
+    /// confirmation arrives within `timeout`, or
+    /// [`SignalFishError::SendBufferFull`] if the outgoing command queue is
+    /// full.
+    ///
+    /// # Cancel safety
+    ///
+    /// `LeaveRoom` is queued on the first poll; dropping the future after
+    /// that only stops waiting for the confirmation, which still applies. The
+    /// waiter is removed when the future drops.
+    pub async fn leave_room_and_wait(&self, timeout: Duration) -> Result<()> {
+        let (waiter, left) = tokio::sync::oneshot::channel();
+        {
+            let mut core = lock_core(&self.state);
+            if !core.is_connected() {
+                return Err(SignalFishError::NotConnected);
+            }
+            if !core.in_room() {
+                return Ok(());
+            }
+            let command = core.prepare(ClientOperation::LeaveRoom)?;
+            self.send_command_locked(&mut core, command.into())?;
+            core.await_room_left(waiter);
+        }
+        let left = PendingReply::new(&self.state, WaiterKind::Leave, left);
+        match tokio::time::timeout(timeout, left).await {
+            Ok(left) => left.map_err(|_| SignalFishError::NotConnected),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
     /// Send arbitrary JSON game data to other players in the room.
     ///
     /// Returns as soon as the message is queued. For high-rate payloads
@@ -742,6 +2365,22 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::GameData(data, GameDataDelivery::Reliable))
     }
 
+    /// Serialize `value` to JSON and send it as game data.
+    ///
+    /// Shorthand for `serde_json::to_value` followed by
+    /// [`send_game_data`](Self::send_game_data); the receiving side reads it
+    /// back with [`SignalFishEvent::game_data_as`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Serialization`] if `value` does not
+    /// serialize (nothing is queued), otherwise as
+    /// [`send_game_data`](Self::send_game_data).
+    pub fn send_game_data_as<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let data = serde_json::to_value(value)?;
+        self.send_game_data(data)
+    }
+
     /// Send JSON game data with an explicit protocol-v3 delivery policy.
     pub fn send_game_data_with_delivery(
         &mut self,
@@ -751,6 +2390,34 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::GameData(data, delivery))
     }
 
+    /// Send JSON game data that is dropped instead of sent if it is still
+    /// queued `deadline` after this call.
+    ///
+    /// Overrides the [`GameData`](ClientMessageKind::GameData) default in
+    /// [`SignalFishConfig::message_deadlines`] for this one message. A
+    /// message that expires in the outgoing queue (or in the offline queue
+    /// while a reconnecting client waits out an outage) is reported with
+    /// [`SignalFishEvent::MessageExpired`] and counted in
+    /// [`ClientStats::messages_expired`]. Once the transport loop has taken
+    /// it off the queue, a message is sent regardless.
+    ///
+    /// # Errors
+    ///
+    /// As [`send_game_data`](Self::send_game_data).
+    pub fn send_game_data_with_deadline(
+        &mut self,
+        data: serde_json::Value,
+        deadline: Duration,
+    ) -> Result<()> {
+        let mut core = lock_core(&self.state);
+        let command = core.prepare(ClientOperation::GameData(data, GameDataDelivery::Reliable))?;
+        let outgoing = Outgoing {
+            command,
+            expires_at: Some(tokio::time::Instant::now() + deadline),
+        };
+        self.send_command_locked(&mut core, LoopCommand::Send(outgoing))
+    }
+
     /// Send arbitrary JSON game data, waiting for space in the outgoing
     /// command queue when it is full.
     ///
@@ -776,6 +2443,12 @@ impl SignalFishClient {
     /// # Errors
     ///
     /// Returns [`SignalFishError::NotConnected`] if the transport has closed.
+    ///
+    /// # Cancel safety
+    ///
+    /// Dropping the future while it waits for capacity discards `data`:
+    /// nothing is queued. Once it resolves, the message is queued. The other
+    /// `*_reliable` sends behave the same way.
     pub async fn send_game_data_reliable(&self, data: serde_json::Value) -> Result<()> {
         self.send_operation_reliable(ClientOperation::GameData(data, GameDataDelivery::Reliable))
             .await
@@ -792,6 +2465,12 @@ impl SignalFishClient {
     }
 
     /// Send opaque binary game data over the negotiated protocol-v3 relay.
+    ///
+    /// `payload` goes out verbatim as one binary WebSocket frame, already
+    /// encoded in the [`game_data_format`](SignalFishConfig::game_data_format)
+    /// the server accepted; there is no per-message encoding. Returns
+    /// [`BinaryFormatNotNegotiated`](SignalFishError::BinaryFormatNotNegotiated)
+    /// while that format is JSON.
     pub fn send_binary_game_data(&mut self, payload: Vec<u8>) -> Result<()> {
         self.send_operation(ClientOperation::Binary(payload))
     }
@@ -824,6 +2503,66 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::RequestAuthority(become_authority))
     }
 
+    /// Request to become (or relinquish) authority and wait for the server's
+    /// answer.
+    ///
+    /// Resolves with the [`AuthorityResponse`](SignalFishEvent::AuthorityResponse)
+    /// that answers this request. Responses carry no request id, so they are
+    /// matched to requests in the order the requests were queued, including
+    /// ones sent with [`request_authority`](Self::request_authority). The
+    /// response is still delivered as an event, so keep draining events
+    /// while awaiting.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     client: &signal_fish_client::SignalFishClient,
+    /// # ) -> signal_fish_client::error::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let grant = client
+    ///     .request_authority_and_wait(true, Duration::from_secs(5))
+    ///     .await?;
+    /// if !grant.granted {
+    ///     println!("denied: {:?}", grant.reason);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed
+    /// or closes before the answer arrives, [`SignalFishError::Timeout`] if
+    /// no answer arrives within `timeout`, or
+    /// [`SignalFishError::SendBufferFull`] if the outgoing command queue is
+    /// full. A denial is not an error: it resolves with
+    /// [`granted`](crate::AuthorityGrant::granted) set to `false`.
+    ///
+    /// # Cancel safety
+    ///
+    /// `AuthorityRequest` is queued on the first poll. Dropping the future
+    /// after that leaves the request in flight; its response is delivered
+    /// only as an event and still consumes that request's place in line, so
+    /// later calls are matched with their own responses.
+    pub async fn request_authority_and_wait(
+        &self,
+        become_authority: bool,
+        timeout: Duration,
+    ) -> Result<crate::AuthorityGrant> {
+        let (waiter, response) = tokio::sync::oneshot::channel();
+        {
+            let mut core = lock_core(&self.state);
+            let command = core.prepare(ClientOperation::RequestAuthority(become_authority))?;
+            self.send_command_locked(&mut core, command.into())?;
+            core.await_authority_response(waiter);
+        }
+        let response = PendingReply::new(&self.state, WaiterKind::Authority, response);
+        match tokio::time::timeout(timeout, response).await {
+            Ok(grant) => grant.map_err(|_| SignalFishError::NotConnected),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
     /// Provide connection information for P2P establishment.
     ///
     /// # Errors
@@ -837,9 +2576,18 @@ impl SignalFishClient {
 
     /// Reconnect to a room after a disconnection.
     ///
+    /// One `Reconnect` is in flight at a time: until the server answers with
+    /// [`Reconnected`](SignalFishEvent::Reconnected) or
+    /// [`ReconnectionFailed`](SignalFishEvent::ReconnectionFailed), the
+    /// connection ends, or
+    /// [`reconnect_answer_timeout`](SignalFishConfig::reconnect_answer_timeout)
+    /// passes, further calls are refused.
+    ///
     /// # Errors
     ///
-    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// Returns [`SignalFishError::ReconnectInFlight`] if an earlier
+    /// `Reconnect` is still awaiting its answer,
+    /// [`SignalFishError::NotConnected`] if the transport has closed,
     /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
     /// is full (the message is **not** queued; nothing is silently dropped).
     pub fn reconnect(
@@ -848,14 +2596,112 @@ impl SignalFishClient {
         room_id: RoomId,
         auth_token: String,
     ) -> Result<()> {
-        self.send_operation(ClientOperation::Reconnect(player_id, room_id, auth_token))
+        let mut core = lock_core(&self.state);
+        let command = core.prepare(ClientOperation::Reconnect(player_id, room_id, auth_token))?;
+        self.send_reconnect_locked(&mut core, command)
     }
 
-    /// Join a room as a read-only spectator.
+    /// Reconnect to a room and wait for the server's answer.
     ///
-    /// # Errors
+    /// Sends `Reconnect` like [`reconnect`](Self::reconnect) and resolves with
+    /// the [`Reconnected`](SignalFishEvent::Reconnected) payload. If a
+    /// `Reconnect` to `room_id` is already in flight — from an earlier call
+    /// of either method, [`resume`](Self::resume), or automatic resumption —
+    /// nothing is sent and this call waits on that attempt's answer, so any
+    /// number of callers share one request. The answer is still delivered as
+    /// an event, so keep draining events while awaiting.
     ///
-    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     client: &signal_fish_client::SignalFishClient,
+    /// #     player_id: signal_fish_client::protocol::PlayerId,
+    /// #     room_id: signal_fish_client::protocol::RoomId,
+    /// #     token: String,
+    /// # ) {
+    /// use std::time::Duration;
+    ///
+    /// match client
+    ///     .reconnect_and_wait(player_id, room_id, token, Duration::from_secs(5))
+    ///     .await
+    /// {
+    ///     Ok(room) => println!("back in {}", room.room_code),
+    ///     Err(error) => println!("{error}"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::ServerError`] carrying the reason and code
+    /// of a [`ReconnectionFailed`](SignalFishEvent::ReconnectionFailed),
+    /// [`SignalFishError::NotConnected`] when the connection ends first, and
+    /// [`SignalFishError::ReconnectInFlight`] if the attempt in flight names
+    /// another room. Returns [`SignalFishError::Timeout`] when no answer
+    /// arrives within `timeout`; the attempt stays in flight, so a late
+    /// `Reconnected` still puts the client in the room and is delivered as
+    /// an event. Sending fails as for [`reconnect`](Self::reconnect).
+    ///
+    /// # Cancel safety
+    ///
+    /// `Reconnect` is queued on the first poll. Dropping the future after
+    /// that leaves the attempt in flight, as with a timeout; the waiter is
+    /// removed when the future drops.
+    pub async fn reconnect_and_wait(
+        &self,
+        player_id: PlayerId,
+        room_id: RoomId,
+        auth_token: String,
+        timeout: Duration,
+    ) -> Result<crate::protocol::ReconnectedPayload> {
+        let (waiter, answer) = tokio::sync::oneshot::channel();
+        {
+            let mut core = lock_core(&self.state);
+            match core.reconnect_in_flight() {
+                Some(pending) if pending == room_id => {}
+                Some(_) => return Err(SignalFishError::ReconnectInFlight),
+                None => {
+                    let command =
+                        core.prepare(ClientOperation::Reconnect(player_id, room_id, auth_token))?;
+                    self.send_reconnect_locked(&mut core, command)?;
+                }
+            }
+            core.await_reconnected(waiter);
+        }
+        let answer = PendingReply::new(&self.state, WaiterKind::Reconnect, answer);
+        match tokio::time::timeout(timeout, answer).await {
+            Ok(answer) => answer.unwrap_or(Err(SignalFishError::NotConnected)),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
+    /// Send `Reconnect` for the room this client held when its transport
+    /// last dropped, under [`ResumePolicy::Manual`](crate::ResumePolicy::Manual).
+    ///
+    /// Called during the outage, the `Reconnect` is held with other sends and
+    /// goes out once the next connection authenticates. The room stays resumable until the
+    /// server answers with [`Reconnected`](SignalFishEvent::Reconnected) or
+    /// [`ReconnectionFailed`](SignalFishEvent::ReconnectionFailed), or the
+    /// client joins another room.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotInRoom`] if there is no room to resume:
+    /// the client was not started with
+    /// [`start_with_reconnect`](Self::start_with_reconnect), the resume
+    /// policy is [`Never`](crate::ResumePolicy::Never), or the dropped
+    /// connection held no room with a reconnection token. Otherwise as
+    /// [`reconnect`](Self::reconnect).
+    pub fn resume(&mut self) -> Result<()> {
+        let mut core = lock_core(&self.state);
+        let command = core.resume()?;
+        self.send_reconnect_locked(&mut core, command)
+    }
+
+    /// Join a room as a read-only spectator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
     /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
     /// is full (the message is **not** queued; nothing is silently dropped).
     pub fn join_as_spectator(
@@ -882,6 +2728,85 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::LeaveSpectator)
     }
 
+    /// Turn this spectator into a player in the same room.
+    ///
+    /// Sends `LeaveSpectator`, waits for the server's `SpectatorLeft`, and
+    /// only then sends `JoinRoom` — joining earlier is rejected with
+    /// `ALREADY_IN_ROOM`. The join names the spectated game and room code;
+    /// `overrides` adjusts the rest of the parameters (`|params| params` for
+    /// none), and a game name or room code it sets is replaced. The events of
+    /// both steps are delivered as usual, so keep draining events while
+    /// awaiting. There is no built-in deadline; wrap the call in
+    /// [`tokio::time::timeout`] if the server may not answer.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(client: &mut signal_fish_client::SignalFishClient) {
+    /// use signal_fish_client::{ErrorCode, PromotionError, SignalFishError};
+    ///
+    /// match client.promote_to_player("Alice", |params| params.with_supports_authority(true)).await {
+    ///     Ok(room) => println!("playing in {}", room.room_code),
+    ///     Err(PromotionError::Join {
+    ///         error: SignalFishError::ServerError { error_code: Some(ErrorCode::RoomFull), .. },
+    ///         ..
+    ///     }) => println!("the slot was taken"),
+    ///     Err(error) => println!("{error}"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PromotionError::NotSpectating`] when there is no spectator
+    /// session, [`PromotionError::Leave`] when leaving fails (the client is
+    /// still spectating), and [`PromotionError::Join`] when the join fails
+    /// after the spectator seat was given up. A server `Error` while a step
+    /// is waiting fails that step, and a disconnect fails it with
+    /// [`SignalFishError::NotConnected`].
+    ///
+    /// [`PromotionError::NotSpectating`]: crate::PromotionError::NotSpectating
+    /// [`PromotionError::Leave`]: crate::PromotionError::Leave
+    /// [`PromotionError::Join`]: crate::PromotionError::Join
+    ///
+    /// # Cancel safety
+    ///
+    /// Not cancel safe. Dropped while waiting for `SpectatorLeft`, the
+    /// client leaves spectator mode without joining; dropped while waiting
+    /// for the join's answer, the join stays in flight and its answer arrives
+    /// as an event. Check [`session_state`](Self::session_state) before
+    /// retrying.
+    pub async fn promote_to_player(
+        &mut self,
+        player_name: impl Into<String>,
+        overrides: impl FnOnce(JoinRoomParams) -> JoinRoomParams,
+    ) -> std::result::Result<crate::protocol::RoomJoinedPayload, crate::PromotionError> {
+        use crate::client_core::PromotionWaiter;
+        use crate::PromotionError;
+
+        let (game_name, room_code) = lock_core(&self.state)
+            .spectated_room()
+            .ok_or(PromotionError::NotSpectating)?;
+
+        let (waiter, left) = tokio::sync::oneshot::channel();
+        lock_core(&self.state).set_promotion_waiter(PromotionWaiter::Leave(waiter));
+        let left = PendingReply::new(&self.state, WaiterKind::Promotion, left);
+        self.leave_spectator().map_err(PromotionError::Leave)?;
+        left.await
+            .unwrap_or(Err(SignalFishError::NotConnected))
+            .map_err(PromotionError::Leave)?;
+
+        let mut params = overrides(JoinRoomParams::new(game_name.clone(), player_name));
+        params.game_name = game_name;
+        params.room_code = Some(room_code.clone());
+        let (waiter, joined) = tokio::sync::oneshot::channel();
+        lock_core(&self.state).set_promotion_waiter(PromotionWaiter::Join(waiter));
+        let joined = PendingReply::new(&self.state, WaiterKind::Promotion, joined);
+        let joined = match self.join_room(params) {
+            Ok(()) => joined.await.unwrap_or(Err(SignalFishError::NotConnected)),
+            Err(error) => Err(error),
+        };
+        joined.map_err(|error| PromotionError::Join { room_code, error })
+    }
+
     /// Send a heartbeat ping to the server.
     ///
     /// # Errors
@@ -893,6 +2818,116 @@ impl SignalFishClient {
         self.send_operation(ClientOperation::Ping)
     }
 
+    /// Send a `Ping` and return the time until its `Pong` arrives.
+    ///
+    /// The round trip runs from the transport accepting the ping to the
+    /// pong being received, the same measure
+    /// [`latency_stats`](Self::latency_stats) records. The server answers
+    /// pings in order, so concurrent callers — and heartbeat pings in between
+    /// — each get the round trip of their own ping. The
+    /// [`Pong`](SignalFishEvent::Pong) event is still emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Timeout`] if no `Pong` arrives within
+    /// `timeout`, [`SignalFishError::NotConnected`] if the client is not
+    /// connected or the transport closes first, or
+    /// [`SignalFishError::SendBufferFull`] if the outgoing command queue is
+    /// full.
+    ///
+    /// # Cancel safety
+    ///
+    /// Dropping the future after the ping is queued only stops the wait: the
+    /// ping is still sent and its pong still feeds
+    /// [`latency_stats`](Self::latency_stats).
+    pub async fn ping_rtt(&self, timeout: Duration) -> Result<Duration> {
+        let (waiter, rtt) = tokio::sync::oneshot::channel();
+        {
+            let mut core = lock_core(&self.state);
+            if !core.is_connected() {
+                return Err(SignalFishError::NotConnected);
+            }
+            let command = core.prepare(ClientOperation::Ping)?;
+            self.send_command_locked(&mut core, LoopCommand::TimedPing(command, waiter))?;
+        }
+        let rtt = PendingReply::new(&self.state, WaiterKind::Ping, rtt);
+        match tokio::time::timeout(timeout, rtt).await {
+            Ok(Ok(rtt)) => Ok(rtt),
+            Ok(Err(_)) => Err(SignalFishError::NotConnected),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
+    /// Measure the server clock offset with one time-sync round trip.
+    ///
+    /// Sends a [`TimeSyncRequest`](crate::protocol::ClientMessage::TimeSyncRequest) and waits
+    /// for the matching response, whose sample also feeds
+    /// [`server_time_estimate`](Self::server_time_estimate). The response is
+    /// delivered as a [`TimeSyncResponse`](SignalFishEvent::TimeSyncResponse)
+    /// event as well, so keep draining events while awaiting. There is no
+    /// built-in deadline; wrap the call in [`tokio::time::timeout`] if the
+    /// server may not answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed
+    /// or closes before the response arrives, or
+    /// [`SignalFishError::SendBufferFull`] if the outgoing command queue is
+    /// full.
+    ///
+    /// # Cancel safety
+    ///
+    /// Dropping the future after the request is queued only stops the wait:
+    /// the response still feeds
+    /// [`server_time_estimate`](Self::server_time_estimate).
+    pub async fn sync_time(&self) -> Result<crate::TimeSyncResult> {
+        let (waiter, response) = tokio::sync::oneshot::channel();
+        let command = lock_core(&self.state).prepare_time_sync(waiter)?;
+        let response = PendingReply::new(&self.state, WaiterKind::TimeSync, response);
+        self.send_command(command)?;
+        response.await.map_err(|_| SignalFishError::NotConnected)
+    }
+
+    /// The best recent time-sync sample: of the last
+    /// [`time_sync_samples`](SignalFishConfig::time_sync_samples) exchanges,
+    /// the one with the smallest round trip. `None` until a response arrives.
+    pub fn server_time_estimate(&self) -> Option<crate::TimeSyncResult> {
+        lock_core(&self.state).server_time_estimate()
+    }
+
+    /// Round-trip percentiles and jitter over the last
+    /// [`LATENCY_SAMPLES`](crate::LATENCY_SAMPLES) `Ping`/`Pong` exchanges,
+    /// from [`ping()`](Self::ping) and the automatic
+    /// [`ping_interval`](SignalFishConfig::ping_interval) heartbeat alike.
+    /// `None` until a `Pong` answers a ping.
+    pub fn latency_stats(&self) -> Option<crate::LatencyStats> {
+        lock_core(&self.state).latency_stats()
+    }
+
+    // ── Runtime configuration ───────────────────────────────────────
+
+    /// Change runtime-tunable settings without reconnecting.
+    ///
+    /// The patch is queued behind any commands already sent and applied by
+    /// the transport loop between iterations: changed ping and time-sync
+    /// intervals restart from the moment it applies, and a lobby state held
+    /// by the old debounce window is emitted. Once applied,
+    /// [`effective_config`](Self::effective_config) reports the new values
+    /// with [`ConfigSource::Runtime`](crate::ConfigSource::Runtime).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotRuntimeTunable`] listing the offending
+    /// fields if the patch changes settings outside
+    /// [`RuntimeConfigPatch::TUNABLE_FIELDS`](crate::RuntimeConfigPatch::TUNABLE_FIELDS)
+    /// (nothing is applied), [`SignalFishError::NotConnected`] if the
+    /// transport has closed, or [`SignalFishError::SendBufferFull`] if the
+    /// outgoing command queue is full.
+    pub fn update_runtime_config(&mut self, patch: crate::RuntimeConfigPatch) -> Result<()> {
+        patch.check()?;
+        self.send_command(LoopCommand::RuntimeConfig(patch))
+    }
+
     // ── Game start (protocol v2) ────────────────────────────────────
 
     /// Request that the server start the game (protocol v2).
@@ -1058,11 +3093,79 @@ impl SignalFishClient {
         lock_core(&self.state).is_connected()
     }
 
+    /// The tokio task id of the transport loop, for matching this client to
+    /// its task in `tokio-console` or `tokio::task::id()` logs.
+    ///
+    /// `None` once [`shutdown`](Self::shutdown) has reaped the task.
+    pub fn task_id(&self) -> Option<tokio::task::Id> {
+        self.task.as_ref().map(tokio::task::JoinHandle::id)
+    }
+
     /// Returns `true` if the server has confirmed authentication.
     pub fn is_authenticated(&self) -> bool {
         lock_core(&self.state).is_authenticated()
     }
 
+    /// Returns `true` while the local player holds room authority.
+    ///
+    /// Set from `RoomJoined` and `Reconnected`, kept current by
+    /// `AuthorityChanged` and granted `AuthorityResponse`s, and reset when
+    /// the room is left or the connection ends.
+    pub fn is_authority(&self) -> bool {
+        lock_core(&self.state).is_authority()
+    }
+
+    /// Wait until the server confirms authentication.
+    ///
+    /// The client sends `Authenticate` on start; this resolves with what the
+    /// server's [`Authenticated`](SignalFishEvent::Authenticated) confirmed,
+    /// at once if that already happened. The answer is still delivered as an
+    /// event, so keep draining events while awaiting.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(client: &signal_fish_client::SignalFishClient) {
+    /// use std::time::Duration;
+    /// use signal_fish_client::{ErrorCode, SignalFishError};
+    ///
+    /// match client.wait_authenticated(Duration::from_secs(5)).await {
+    ///     Ok(info) => println!("authenticated as {}", info.app_name),
+    ///     Err(SignalFishError::AuthenticationFailed { error_code: ErrorCode::InvalidAppId, .. }) => {
+    ///         println!("check the app ID")
+    ///     }
+    ///     Err(error) => println!("{error}"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::AuthenticationFailed`] when the server
+    /// answers with `AuthenticationError`,
+    /// [`SignalFishError::NotConnected`] when the connection is closed or
+    /// closes first, and [`SignalFishError::Timeout`] when no answer arrives
+    /// within `timeout`.
+    ///
+    /// # Cancel safety
+    ///
+    /// Cancel safe: it only waits. The waiter is removed when the future
+    /// drops.
+    pub async fn wait_authenticated(&self, timeout: Duration) -> Result<crate::AuthenticatedInfo> {
+        let (waiter, outcome) = tokio::sync::oneshot::channel();
+        lock_core(&self.state).wait_authenticated(waiter);
+        let outcome = PendingReply::new(&self.state, WaiterKind::Authenticated, outcome);
+        match tokio::time::timeout(timeout, outcome).await {
+            Ok(outcome) => outcome.unwrap_or(Err(SignalFishError::NotConnected)),
+            Err(_) => Err(SignalFishError::Timeout),
+        }
+    }
+
+    /// Returns `true` if the connection ended because the initial
+    /// `Authenticate` could not be sent (see
+    /// [`ClientSnapshot::handshake_failed`]).
+    pub fn handshake_failed(&self) -> bool {
+        lock_core(&self.state).snapshot().handshake_failed
+    }
+
     /// Returns the current room ID, if the client is in a room.
     pub async fn current_room_id(&self) -> Option<RoomId> {
         lock_core(&self.state).snapshot().room_id
@@ -1078,6 +3181,43 @@ impl SignalFishClient {
         lock_core(&self.state).snapshot().room_code
     }
 
+    /// Returns the players in the current room, including the local player.
+    ///
+    /// Seeded from `RoomJoined`, `Reconnected` or `SpectatorJoined` and kept
+    /// current by joins, leaves, `LobbyStateChanged` (`is_ready`) and
+    /// `AuthorityChanged` (`is_authority`). Empty outside a room.
+    pub async fn current_players(&self) -> Vec<PlayerInfo> {
+        lock_core(&self.state).current_players().to_vec()
+    }
+
+    /// Returns the current room's lobby state.
+    ///
+    /// Taken from `RoomJoined`, `Reconnected`, `SpectatorJoined` and every
+    /// `LobbyStateChanged`; [`LobbyState::Waiting`] outside a room.
+    pub fn lobby_state(&self) -> LobbyState {
+        lock_core(&self.state).lobby_state().clone()
+    }
+
+    /// Subscribe to [`lobby_state`](Self::lobby_state) transitions.
+    ///
+    /// The receiver starts at the current state and is notified only when the
+    /// state actually changes, so `changed().await` resolves once per
+    /// transition, including the reset to [`LobbyState::Waiting`] when the
+    /// room is left or the connection ends.
+    pub fn lobby_state_watch(&self) -> tokio::sync::watch::Receiver<LobbyState> {
+        lock_core(&self.state).lobby_state_watch()
+    }
+
+    /// Returns the current room as one consistent [`RoomSnapshot`](crate::RoomSnapshot),
+    /// or `None` outside a room.
+    ///
+    /// Reading `current_room_id()`, `current_room_code()` and
+    /// `current_players()` one after another can straddle a `RoomLeft`; this
+    /// reads every field under a single lock.
+    pub fn room_snapshot(&self) -> Option<crate::RoomSnapshot> {
+        lock_core(&self.state).room_snapshot()
+    }
+
     /// Number of messages that can currently be queued before the synchronous
     /// send methods return [`SignalFishError::SendBufferFull`].
     ///
@@ -1099,11 +3239,120 @@ impl SignalFishClient {
         lock_core(&self.state).stats()
     }
 
+    /// Outgoing messages counted per kind, once when queued and again when
+    /// sent (see [`OutgoingBreakdown`]).
+    ///
+    /// Pair it with the rate limits from
+    /// [`Authenticated`](SignalFishEvent::Authenticated) to see which
+    /// message kinds spend the app's budget.
+    pub fn outgoing_breakdown(&self) -> OutgoingBreakdown {
+        lock_core(&self.state).outgoing_breakdown()
+    }
+
     /// Return a coherent synchronous snapshot of connection and room state.
     pub fn snapshot(&self) -> ClientSnapshot {
         lock_core(&self.state).snapshot()
     }
 
+    /// Synchronous health summary for liveness probes (see
+    /// [`ClientHealth`](crate::ClientHealth)).
+    ///
+    /// Never awaits; safe to call from a synchronous HTTP handler.
+    pub fn health(&self) -> crate::ClientHealth {
+        let max = self.cmd_tx.max_capacity();
+        let used = max.saturating_sub(self.cmd_tx.capacity());
+        lock_core(&self.state).health(crate::health::utilization(used, max))
+    }
+
+    /// Time since the transport last sent and received a frame (see
+    /// [`ActivityInfo`](crate::ActivityInfo)).
+    pub fn activity(&self) -> crate::ActivityInfo {
+        lock_core(&self.state).activity()
+    }
+
+    /// The settings this client is running with and where each came from
+    /// (see [`EffectiveConfig`](crate::EffectiveConfig)).
+    pub fn effective_config(&self) -> crate::EffectiveConfig {
+        lock_core(&self.state).effective_config()
+    }
+
+    /// Current session lifecycle state (see [`SessionState`](crate::SessionState)).
+    pub fn session_state(&self) -> crate::SessionState {
+        lock_core(&self.state).session_state()
+    }
+
+    /// Caps the server advertised in this session's `ProtocolInfo`, or
+    /// `None` before it arrives or when the server sends none.
+    ///
+    /// A known `max_players_per_room` is checked by `join_room()`.
+    pub fn server_limits(&self) -> Option<crate::protocol::ServerLimits> {
+        lock_core(&self.state).server_limits()
+    }
+
+    /// What the server confirmed in this connection's `Authenticated`: the
+    /// app name, the organization that scopes its quotas, and its rate
+    /// limits. `None` before authentication and after the connection drops.
+    pub fn authenticated_info(&self) -> Option<crate::AuthenticatedInfo> {
+        lock_core(&self.state).authenticated_info().cloned()
+    }
+
+    /// The application's rate limits from this connection's `Authenticated`,
+    /// for pacing sends. `None` before authentication and after the
+    /// connection drops, since a new connection may report different limits.
+    pub fn rate_limits(&self) -> Option<crate::protocol::RateLimitInfo> {
+        lock_core(&self.state).rate_limits().cloned()
+    }
+
+    /// Text for `code` from the configured
+    /// [`ErrorCodeFormatter`](crate::ErrorCodeFormatter), falling back to
+    /// [`ErrorCode::description`](crate::ErrorCode::description). Limit
+    /// errors name the matching [`server_limits`](Self::server_limits) cap
+    /// when the server advertised one.
+    pub fn describe_error_code(&self, code: &crate::ErrorCode) -> std::borrow::Cow<'static, str> {
+        lock_core(&self.state).describe_error_code(code)
+    }
+
+    /// Decode a game-data event's payload as `T` (see
+    /// [`SignalFishEvent::decode`]), reporting a failure to the configured
+    /// [`DecodeFailureHook`](crate::DecodeFailureHook).
+    ///
+    /// The happy path takes no lock and formats nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`GameDataDecodeError`](crate::GameDataDecodeError) from
+    /// [`SignalFishEvent::decode`].
+    pub fn decode_game_data<'a, T: serde::Deserialize<'a>>(
+        &self,
+        event: &'a SignalFishEvent,
+    ) -> std::result::Result<T, crate::GameDataDecodeError> {
+        event.decode().inspect_err(|error| {
+            lock_core(&self.state).report_decode_failure(event, error.kind());
+        })
+    }
+
+    /// Like [`decode_game_data`](Self::decode_game_data), returning `None`
+    /// on failure (the hook still sees it).
+    pub fn try_decode_game_data<'a, T: serde::Deserialize<'a>>(
+        &self,
+        event: &'a SignalFishEvent,
+    ) -> Option<T> {
+        self.decode_game_data(event).ok()
+    }
+
+    /// Oldest-first log of the most recent session transitions, bounded by
+    /// [`STATE_HISTORY_CAPACITY`](crate::STATE_HISTORY_CAPACITY). Intended for
+    /// debugging and bug reports.
+    pub fn state_history(&self) -> Vec<crate::StateTransition> {
+        lock_core(&self.state).state_history()
+    }
+
+    /// Oldest-first records of the room sessions that have ended, bounded by
+    /// [`SignalFishConfig::room_history`]; empty when that is unset.
+    pub fn room_history(&self) -> Vec<crate::RoomSessionRecord> {
+        lock_core(&self.state).room_history()
+    }
+
     // ── Internal helpers ────────────────────────────────────────────
 
     fn send_operation(&self, operation: ClientOperation) -> Result<()> {
@@ -1111,12 +3360,30 @@ impl SignalFishClient {
         self.send_command(command)
     }
 
-    fn send_command(&self, command: ClientCommand) -> Result<()> {
-        if !lock_core(&self.state).is_connected() {
-            return Err(SignalFishError::NotConnected);
+    fn send_command(&self, command: impl Into<LoopCommand>) -> Result<()> {
+        self.send_command_locked(&mut lock_core(&self.state), command.into())
+    }
+
+    /// Queue `command` under the core lock, which is held across the
+    /// non-blocking send so the loop cannot count the message as sent (or
+    /// answered) before it is counted as enqueued.
+    fn send_command_locked(&self, core: &mut ClientCore, mut command: LoopCommand) -> Result<()> {
+        command.stamp(core);
+        let kind = command.message_kind();
+        match kind {
+            Some(_) => core.ensure_sendable()?,
+            None if !core.is_connected() && !core.reconnecting() => {
+                return Err(SignalFishError::NotConnected);
+            }
+            None => {}
         }
         match self.cmd_tx.try_send(command) {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                if let Some(kind) = kind {
+                    core.record_enqueued(kind);
+                }
+                Ok(())
+            }
             Err(mpsc::error::TrySendError::Full(_)) => Err(SignalFishError::SendBufferFull {
                 capacity: self.cmd_tx.max_capacity(),
             }),
@@ -1124,19 +3391,37 @@ impl SignalFishClient {
         }
     }
 
+    /// Queue a prepared `Reconnect`, which stops counting as in flight if
+    /// it cannot be queued.
+    fn send_reconnect_locked(&self, core: &mut ClientCore, command: ClientCommand) -> Result<()> {
+        let sent = self.send_command_locked(core, command.into());
+        if sent.is_err() {
+            core.reconnect_not_sent();
+        }
+        sent
+    }
+
     async fn send_operation_reliable(&self, operation: ClientOperation) -> Result<()> {
         let command = lock_core(&self.state).prepare(operation)?;
         self.send_command_reliable(command).await
     }
 
     async fn send_command_reliable(&self, command: ClientCommand) -> Result<()> {
-        if !lock_core(&self.state).is_connected() {
-            return Err(SignalFishError::NotConnected);
+        let mut outgoing = Outgoing::from(command);
+        {
+            let core = lock_core(&self.state);
+            core.ensure_sendable()?;
+            outgoing.stamp(&core);
         }
-        self.cmd_tx
-            .send(command)
+        let permit = self
+            .cmd_tx
+            .reserve()
             .await
-            .map_err(|_| SignalFishError::NotConnected)
+            .map_err(|_| SignalFishError::NotConnected)?;
+        let mut core = lock_core(&self.state);
+        core.record_enqueued(outgoing.command.kind());
+        permit.send(LoopCommand::Send(outgoing));
+        Ok(())
     }
 }
 
@@ -1262,12 +3547,79 @@ impl Drop for SignalFishClient {
         // `shutdown_tx` oneshot is intentionally *not* sent here: sending
         // it would trigger a graceful path that calls async `transport.close()`,
         // but there is no executor context to drive it inside `Drop`.
+        //
+        // The terminal `Disconnected` is still owed to every receiver. Keep
+        // the channels open past the abort and emit it on the client's
+        // runtime once the aborted loop has fully stopped, so it cannot
+        // overtake an event the loop was still sending.
         if let Some(task) = self.task.take() {
+            let retained = self.terminal.retain();
             task.abort();
+            if retained.iter().any(Option::is_some) {
+                let terminal = self.terminal.clone();
+                let state = Arc::clone(&self.state);
+                let name = format!("{}/terminal", self.terminal.label);
+                spawn_named(&self.terminal.runtime, &name, async move {
+                    let _ = task.await;
+                    let event = aborted_disconnect(&state);
+                    terminal.deliver(retained, &event);
+                });
+            }
+        }
+    }
+}
+
+/// The answer an awaitable method is waiting on, registered with the core.
+///
+/// Dropping it deregisters the waiter, so a caller whose future is cancelled
+/// mid-wait — by a `select!`, a `timeout`, or an aborted task — leaves
+/// nothing behind for a later answer to find.
+#[cfg(feature = "tokio-runtime")]
+struct PendingReply<T> {
+    reply: Option<tokio::sync::oneshot::Receiver<T>>,
+    state: Arc<Mutex<ClientCore>>,
+    kind: WaiterKind,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<T> PendingReply<T> {
+    fn new(
+        state: &Arc<Mutex<ClientCore>>,
+        kind: WaiterKind,
+        reply: tokio::sync::oneshot::Receiver<T>,
+    ) -> Self {
+        Self {
+            reply: Some(reply),
+            state: Arc::clone(state),
+            kind,
         }
     }
 }
 
+#[cfg(feature = "tokio-runtime")]
+impl<T> std::future::Future for PendingReply<T> {
+    type Output = std::result::Result<T, tokio::sync::oneshot::error::RecvError>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        match self.reply.as_mut() {
+            Some(reply) => std::pin::Pin::new(reply).poll(cx),
+            None => std::task::Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<T> Drop for PendingReply<T> {
+    fn drop(&mut self) {
+        // Closing the receiver first is what marks the waiter abandoned.
+        drop(self.reply.take());
+        lock_core(&self.state).forget_abandoned(self.kind);
+    }
+}
+
 // ── Transport loop ──────────────────────────────────────────────────
 
 #[cfg(feature = "tokio-runtime")]
@@ -1278,174 +3630,2023 @@ fn lock_core(state: &Arc<Mutex<ClientCore>>) -> std::sync::MutexGuard<'_, Client
     }
 }
 
+/// Spawn a client-owned task on `runtime`.
+///
+/// Built with `--cfg tokio_unstable` and the `task-names` feature, the task
+/// carries `name` for `tokio-console`; otherwise the name is unused.
 #[cfg(feature = "tokio-runtime")]
-async fn finish_core_shutdown(
-    transport: &mut impl Transport,
-    event_tx: &mpsc::Sender<SignalFishEvent>,
-    state: &Arc<Mutex<ClientCore>>,
-) {
-    let _ = close_transport(transport).await;
-    let event = lock_core(state).disconnect(Some("client shut down".into()));
-    let _ = event_tx.try_send(event);
+fn spawn_named<F>(
+    runtime: &tokio::runtime::Handle,
+    name: &str,
+    future: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "task-names"))]
+    {
+        // The builder consumes the future even on error, so hand it over
+        // through a slot that the unnamed fallback can reclaim.
+        let slot = Arc::new(Mutex::new(Some(future)));
+        let named = Arc::clone(&slot);
+        let spawned = tokio::task::Builder::new().name(name).spawn_on(
+            async move {
+                let future = named.lock().ok().and_then(|mut slot| slot.take());
+                if let Some(future) = future {
+                    future.await;
+                }
+            },
+            runtime,
+        );
+        match spawned {
+            Ok(handle) => handle,
+            Err(error) => {
+                debug!("could not name task {name}: {error}");
+                let future = slot.lock().ok().and_then(|mut slot| slot.take());
+                runtime.spawn(async move {
+                    if let Some(future) = future {
+                        future.await;
+                    }
+                })
+            }
+        }
+    }
+    #[cfg(not(all(tokio_unstable, feature = "task-names")))]
+    {
+        let _ = name;
+        runtime.spawn(future)
+    }
 }
 
+/// The terminal event for a loop that was aborted before emitting its own.
 #[cfg(feature = "tokio-runtime")]
-async fn emit_core_disconnected_or_shutdown(
-    transport: &mut impl Transport,
-    event_tx: &mpsc::Sender<SignalFishEvent>,
-    shutdown_rx: &mut tokio::sync::oneshot::Receiver<()>,
-    state: &Arc<Mutex<ClientCore>>,
-    reason: Option<String>,
-) {
-    let _ = close_transport(transport).await;
-    let event = lock_core(state).disconnect(reason);
-    tokio::select! {
-        biased;
-        result = event_tx.send(event.clone()) => {
-            if result.is_err() {
-                debug!("event channel closed, receiver dropped");
-            }
-        }
-        _ = &mut *shutdown_rx => {
-            let _ = event_tx.try_send(event);
+fn aborted_disconnect(state: &Arc<Mutex<ClientCore>>) -> SignalFishEvent {
+    let mut core = lock_core(state);
+    if core.is_connected() {
+        core.disconnect(DisconnectReason::ClientShutdown)
+    } else {
+        SignalFishEvent::Disconnected {
+            reason: DisconnectReason::ClientShutdown,
+            last_server_error: None,
         }
     }
 }
 
-/// Background transport loop that multiplexes send/receive via `tokio::select!`.
+/// The client handle's path to the event receivers when the transport loop
+/// is aborted (shutdown timeout or `Drop`) before it emitted the terminal
+/// `Disconnected`.
 ///
-/// Exits when:
-/// - The command channel closes (client handle dropped or shutdown called)
-/// - The transport returns `None` (server closed connection)
-/// - A transport error occurs
+/// It holds weak senders, so receivers still close when the loop ends on its
+/// own; the abort paths upgrade them *before* aborting so no receiver can
+/// observe `None` ahead of the fallback event.
 #[cfg(feature = "tokio-runtime")]
-async fn transport_loop(
-    mut transport: impl Transport + Send + 'static,
-    mut cmd_rx: mpsc::Receiver<ClientCommand>,
-    event_tx: mpsc::Sender<SignalFishEvent>,
-    state: Arc<Mutex<ClientCore>>,
-    mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
-) {
-    debug!("transport loop started");
+#[derive(Clone)]
+struct TerminalFallback {
+    senders: Vec<WeakEventSender>,
+    /// Per subscriber: the terminal `Disconnected` was sent or is owned by a
+    /// task that will send it. Shared with the loop's [`EventFanout`].
+    handed_off: Vec<Arc<AtomicBool>>,
+    /// Shared with [`EventFanout::sequence`].
+    sequence: EventSequence,
+    /// Runtime that spawned the loop, for delivering after a synchronous `Drop`.
+    runtime: tokio::runtime::Handle,
+    /// [`SignalFishConfig::label`], for naming the delivery tasks.
+    label: Arc<str>,
+    observer: EmissionObserver,
+    /// Shared with [`EventFanout::heartbeat`].
+    heartbeat: Option<Arc<LoopHeartbeat>>,
+}
 
-    if matches!(
-        emit_event_or_shutdown(&event_tx, &mut shutdown_rx, SignalFishEvent::Connected).await,
-        EmitOutcome::ShutdownRequested
-    ) {
-        finish_core_shutdown(&mut transport, &event_tx, &state).await;
-        debug!("transport loop exited");
-        return;
+#[cfg(feature = "tokio-runtime")]
+impl TerminalFallback {
+    fn new(
+        senders: &[EventSender],
+        label: Arc<str>,
+        observer: EmissionObserver,
+        heartbeat: Option<Arc<LoopHeartbeat>>,
+    ) -> Self {
+        Self {
+            senders: senders.iter().map(EventSender::downgrade).collect(),
+            handed_off: senders.iter().map(|_| Arc::default()).collect(),
+            sequence: EventSequence::default(),
+            runtime: tokio::runtime::Handle::current(),
+            label,
+            observer,
+            heartbeat,
+        }
     }
 
-    loop {
-        tokio::select! {
-            command = cmd_rx.recv() => {
-                let Some(command) = command else {
-                    emit_core_disconnected_or_shutdown(
-                        &mut transport,
-                        &event_tx,
-                        &mut shutdown_rx,
-                        &state,
-                        Some("client shut down".into()),
-                    ).await;
-                    break;
-                };
-                let (frame, is_game_data) = match command {
-                    ClientCommand::Message(message) => match serde_json::to_string(&message) {
-                        Ok(json) => (
-                            Some(TransportFrame::Text(json)),
-                            matches!(message, ClientMessage::GameData { .. }),
-                        ),
-                        Err(error) => {
-                            error!("failed to serialize ClientMessage: {error}");
-                            (None, false)
-                        }
-                    },
-                    ClientCommand::Binary(payload) => {
-                        (Some(TransportFrame::Binary(payload)), true)
-                    }
-                };
-                if let Some(frame) = frame {
-                    if let Err(error) = send_frame(&mut transport, frame).await {
-                        emit_core_disconnected_or_shutdown(
-                            &mut transport,
-                            &event_tx,
-                            &mut shutdown_rx,
-                            &state,
-                            Some(format!("transport send error: {error}")),
-                        ).await;
-                        break;
-                    }
-                    if is_game_data {
-                        lock_core(&state).record_game_data_sent();
-                    }
-                }
-            }
-            _ = &mut shutdown_rx => {
-                finish_core_shutdown(&mut transport, &event_tx, &state).await;
-                break;
+    /// Cover a subscriber added after start; the returned flag goes to the
+    /// loop with its sender.
+    fn register(&mut self, tx: &EventSender) -> Arc<AtomicBool> {
+        let handed_off = Arc::new(AtomicBool::new(false));
+        self.senders.push(tx.downgrade());
+        self.handed_off.push(Arc::clone(&handed_off));
+        handed_off
+    }
+
+    /// Keep every still-open event channel alive across an abort.
+    fn retain(&self) -> Vec<Option<EventSender>> {
+        self.senders.iter().map(WeakEventSender::upgrade).collect()
+    }
+
+    /// Emit `event` to each retained subscriber the stopped loop never
+    /// reached. A full channel gets it from a task that waits for capacity,
+    /// so that receiver still closes only after the event.
+    fn deliver(&self, retained: Vec<Option<EventSender>>, event: &SignalFishEvent) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.finish();
+        }
+        let name = format!("{}/terminal", self.label);
+        // Numbered only if some subscriber still needs it.
+        let mut envelope = None;
+        for (subscriber, (tx, handed_off)) in
+            retained.into_iter().zip(self.handed_off.iter()).enumerate()
+        {
+            let Some(tx) = tx else {
+                continue;
+            };
+            if handed_off.swap(true, Ordering::AcqRel) {
+                continue;
             }
-            incoming = recv_frame(&mut transport) => {
-                match incoming {
-                    Some(Ok(frame)) => {
-                        let outcome = lock_core(&state).process_frame(frame);
-                        let disconnect = outcome.disconnect;
-                        let mut shutdown_requested = false;
-                        for event in outcome.events {
-                            if matches!(
-                                emit_event_or_shutdown(&event_tx, &mut shutdown_rx, event).await,
-                                EmitOutcome::ShutdownRequested
-                            ) {
-                                shutdown_requested = true;
-                                break;
-                            }
-                        }
-                        if shutdown_requested {
-                            finish_core_shutdown(&mut transport, &event_tx, &state).await;
-                            break;
-                        }
-                        if disconnect {
-                            emit_core_disconnected_or_shutdown(
-                                &mut transport,
-                                &event_tx,
-                                &mut shutdown_rx,
-                                &state,
-                                Some("protocol accountability violation".into()),
-                            ).await;
-                            break;
-                        }
-                    }
-                    Some(Err(error)) => {
-                        emit_core_disconnected_or_shutdown(
-                            &mut transport,
-                            &event_tx,
-                            &mut shutdown_rx,
-                            &state,
-                            Some(format!("transport receive error: {error}")),
-                        ).await;
-                        break;
-                    }
-                    None => {
-                        let reason = transport.close_info().map(|info| {
-                            format!(
-                                "closed by server: code={:?}, reason={:?}",
-                                info.code, info.reason
-                            )
-                        });
-                        emit_core_disconnected_or_shutdown(
-                            &mut transport,
-                            &event_tx,
-                            &mut shutdown_rx,
-                            &state,
-                            reason,
-                        ).await;
-                        break;
+            let envelope = envelope.get_or_insert_with(|| self.sequence.stamp(event.clone()));
+            self.observer
+                .send_or_spawn(&self.runtime, &name, subscriber, tx, envelope);
+        }
+    }
+}
+
+/// The transport loop's event subscribers: one bounded channel per receiver
+/// handed out by [`SignalFishClient::start`],
+/// [`SignalFishClient::start_with_subscribers`], or
+/// [`SignalFishClient::subscribe`].
+#[cfg(feature = "tokio-runtime")]
+struct EventFanout {
+    senders: Vec<EventSender>,
+    /// Shared with [`TerminalFallback::handed_off`].
+    handed_off: Vec<Arc<AtomicBool>>,
+    /// Receivers of pending [`SignalFishClient::wait_for`] calls, served
+    /// after `senders`. They keep no client running and are not observed.
+    waiters: Vec<mpsc::Sender<SignalFishEvent>>,
+    /// Numbers each event once, before it goes to any subscriber.
+    sequence: EventSequence,
+    /// [`SignalFishConfig::label`], for naming the hand-off tasks.
+    label: Arc<str>,
+    observer: EmissionObserver,
+    /// The loop's heartbeat, under [`SignalFishConfig::loop_watchdog`].
+    heartbeat: Option<Arc<LoopHeartbeat>>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl EventFanout {
+    /// Record that the loop is making progress.
+    fn tick(&self) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.tick();
+        }
+    }
+
+    /// Record that the loop is waiting on something bounded elsewhere, which
+    /// the watchdog does not count as a stall.
+    fn park(&self) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.park();
+        }
+    }
+
+    /// How often an idle loop wakes to tick, if it is watched.
+    fn pulse(&self) -> Option<Duration> {
+        self.heartbeat.as_deref().map(LoopHeartbeat::pulse)
+    }
+
+    /// Add a subscriber from [`SignalFishClient::subscribe`]. It gets events
+    /// from the next delivery on.
+    fn attach(&mut self, tx: EventSender, handed_off: Arc<AtomicBool>) {
+        self.senders.push(tx);
+        self.handed_off.push(handed_off);
+    }
+
+    /// Add the receiver of a [`SignalFishClient::wait_for`], forgetting
+    /// those of waits already over.
+    fn watch(&mut self, waiter: mpsc::Sender<SignalFishEvent>) {
+        self.waiters.retain(|tx| !tx.is_closed());
+        self.waiters.push(waiter);
+    }
+
+    /// Whether every receiver has been dropped.
+    fn unsubscribed(&self) -> bool {
+        self.senders.iter().all(EventSender::is_closed)
+    }
+
+    /// Deliver `event` to every live subscriber in index order, waiting for
+    /// capacity on each, while letting `shutdown_rx` preempt a blocked
+    /// delivery.
+    ///
+    /// The loop awaits this before producing the next event, which is what
+    /// orders events across subscribers: every live subscriber holds an event
+    /// before any is handed the next. On preemption the subscribers not yet
+    /// served lose the event. Dropped receivers are skipped.
+    async fn deliver(
+        &self,
+        shutdown_rx: &mut tokio::sync::oneshot::Receiver<()>,
+        event: SignalFishEvent,
+    ) -> EmitOutcome {
+        let live: Vec<_> = self
+            .senders
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| !tx.is_closed())
+            .collect();
+        if live.is_empty() {
+            debug!("event channel closed, receiver dropped");
+        }
+        // A slow receiver holds the loop up by design.
+        self.park();
+        let watched = (!self.waiters.is_empty()).then(|| event.clone());
+        // Clone for all but the last subscriber so the common single-receiver
+        // case moves the event without copying it.
+        let mut pending = Some(self.sequence.stamp(event));
+        for (index, (subscriber, tx)) in live.iter().enumerate() {
+            let event = if index + 1 == live.len() {
+                pending.take()
+            } else {
+                pending.clone()
+            };
+            let Some(event) = event else {
+                break;
+            };
+            tokio::select! {
+                biased;
+                permit = tx.reserve() => {
+                    if let Ok(permit) = permit {
+                        self.observer.observe(*subscriber, &event.event);
+                        permit.send(event);
+                    } else {
+                        debug!("event channel closed, receiver dropped");
+                    }
+                }
+                _ = &mut *shutdown_rx => {
+                    return EmitOutcome::ShutdownRequested;
+                }
+            }
+        }
+        // Waiters come last, so a matched event is already with every
+        // subscriber.
+        if let Some(event) = watched {
+            for tx in &self.waiters {
+                tokio::select! {
+                    biased;
+                    permit = tx.reserve() => {
+                        if let Ok(permit) = permit {
+                            permit.send(event.clone());
+                        }
+                    }
+                    _ = &mut *shutdown_rx => {
+                        return EmitOutcome::ShutdownRequested;
+                    }
+                }
+            }
+        }
+        self.tick();
+        EmitOutcome::Delivered
+    }
+
+    /// Emit the terminal `Disconnected` to every subscriber, then drop the
+    /// senders so each receiver closes strictly after it.
+    ///
+    /// Waits for capacity like any other event while `shutdown_rx` is given
+    /// and has not fired. Once shutdown is requested, a subscriber whose
+    /// channel is still full is handed to a detached task that holds its
+    /// sender until the event fits (or the receiver is dropped), so shutdown
+    /// is never pinned by a stalled consumer.
+    async fn finish(
+        self,
+        event: SignalFishEvent,
+        mut shutdown_rx: Option<&mut tokio::sync::oneshot::Receiver<()>>,
+    ) {
+        let name = format!("{}/fanout", self.label);
+        let runtime = tokio::runtime::Handle::current();
+        for waiter in &self.waiters {
+            // A full waiter sees its channel close instead.
+            let _ = waiter.try_send(event.clone());
+        }
+        let envelope = self.sequence.stamp(event);
+        for (subscriber, (tx, handed_off)) in self
+            .senders
+            .into_iter()
+            .zip(self.handed_off.iter())
+            .enumerate()
+        {
+            let sent = match shutdown_rx.as_deref_mut() {
+                Some(rx) => tokio::select! {
+                    biased;
+                    permit = tx.reserve() => {
+                        if let Ok(permit) = permit {
+                            self.observer.observe(subscriber, &envelope.event);
+                            permit.send(envelope.clone());
+                        }
+                        true
+                    }
+                    _ = rx => {
+                        shutdown_rx = None;
+                        false
+                    }
+                },
+                None => false,
+            };
+            if !sent {
+                self.observer
+                    .send_or_spawn(&runtime, &name, subscriber, tx, &envelope);
+            }
+            handed_off.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// Forwards deliveries to [`SignalFishConfig::emission_hook`]; without the
+/// `testing` feature it observes nothing.
+#[cfg(feature = "tokio-runtime")]
+#[derive(Clone)]
+struct EmissionObserver {
+    #[cfg(feature = "testing")]
+    hook: Option<crate::testing::EmissionHook>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl EmissionObserver {
+    fn new(config: &SignalFishConfig) -> Self {
+        #[cfg(not(feature = "testing"))]
+        let _ = config;
+        Self {
+            #[cfg(feature = "testing")]
+            hook: config.emission_hook.clone(),
+        }
+    }
+
+    fn observe(&self, subscriber: usize, event: &SignalFishEvent) {
+        #[cfg(feature = "testing")]
+        if let Some(hook) = &self.hook {
+            hook.observe(subscriber, event);
+        }
+        #[cfg(not(feature = "testing"))]
+        let _ = (subscriber, event);
+    }
+
+    /// Hand `envelope` to `tx` now if it has room, else from a task named
+    /// `name` that waits for capacity, so a full channel still closes only
+    /// after it.
+    fn send_or_spawn(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        name: &str,
+        subscriber: usize,
+        tx: EventSender,
+        envelope: &EventEnvelope,
+    ) {
+        let full = match tx.try_reserve() {
+            Ok(permit) => {
+                self.observe(subscriber, &envelope.event);
+                permit.send(envelope.clone());
+                false
+            }
+            Err(mpsc::error::TrySendError::Full(())) => true,
+            Err(mpsc::error::TrySendError::Closed(())) => false,
+        };
+        if full {
+            let observer = self.clone();
+            let envelope = envelope.clone();
+            spawn_named(
+                runtime,
+                name,
+                async move {
+                    if let Ok(permit) = tx.reserve().await {
+                        observer.observe(subscriber, &envelope.event);
+                        permit.send(envelope);
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+    }
+}
+
+/// Numbers every event the client emits, for [`EventEnvelope::seq`] and
+/// [`SignalFishClient::last_event_seq`].
+#[cfg(feature = "tokio-runtime")]
+#[derive(Clone, Default)]
+struct EventSequence(Arc<AtomicU64>);
+
+#[cfg(feature = "tokio-runtime")]
+impl EventSequence {
+    fn stamp(&self, event: SignalFishEvent) -> EventEnvelope {
+        EventEnvelope {
+            seq: self.0.fetch_add(1, Ordering::Relaxed).saturating_add(1),
+            received_at: tokio::time::Instant::now().into_std(),
+            event,
+        }
+    }
+
+    fn last(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// One subscriber's channel: bare events for [`SignalFishClient::start`] and
+/// [`SignalFishClient::subscribe`], envelopes for their `_enveloped` forms.
+#[cfg(feature = "tokio-runtime")]
+#[derive(Clone)]
+enum EventSender {
+    Plain(mpsc::Sender<SignalFishEvent>),
+    Enveloped(mpsc::Sender<EventEnvelope>),
+    /// [`SignalFishConfig::broadcast_events`]: always has room, and counts
+    /// as open while the loop holds it, since receivers come and go
+    /// without the loop's knowledge.
+    Broadcast(broadcast::Sender<SignalFishEvent>),
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl EventSender {
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Plain(tx) => tx.is_closed(),
+            Self::Enveloped(tx) => tx.is_closed(),
+            Self::Broadcast(_) => false,
+        }
+    }
+
+    fn downgrade(&self) -> WeakEventSender {
+        match self {
+            Self::Plain(tx) => WeakEventSender::Plain(tx.downgrade()),
+            Self::Enveloped(tx) => WeakEventSender::Enveloped(tx.downgrade()),
+            Self::Broadcast(tx) => WeakEventSender::Broadcast(tx.downgrade()),
+        }
+    }
+
+    async fn reserve(&self) -> std::result::Result<EventPermit<'_>, mpsc::error::SendError<()>> {
+        match self {
+            Self::Plain(tx) => tx.reserve().await.map(EventPermit::Plain),
+            Self::Enveloped(tx) => tx.reserve().await.map(EventPermit::Enveloped),
+            Self::Broadcast(tx) => Ok(EventPermit::Broadcast(tx)),
+        }
+    }
+
+    fn try_reserve(&self) -> std::result::Result<EventPermit<'_>, mpsc::error::TrySendError<()>> {
+        match self {
+            Self::Plain(tx) => tx.try_reserve().map(EventPermit::Plain),
+            Self::Enveloped(tx) => tx.try_reserve().map(EventPermit::Enveloped),
+            Self::Broadcast(tx) => Ok(EventPermit::Broadcast(tx)),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+#[derive(Clone)]
+enum WeakEventSender {
+    Plain(mpsc::WeakSender<SignalFishEvent>),
+    Enveloped(mpsc::WeakSender<EventEnvelope>),
+    Broadcast(broadcast::WeakSender<SignalFishEvent>),
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl WeakEventSender {
+    fn upgrade(&self) -> Option<EventSender> {
+        match self {
+            Self::Plain(tx) => tx.upgrade().map(EventSender::Plain),
+            Self::Enveloped(tx) => tx.upgrade().map(EventSender::Enveloped),
+            Self::Broadcast(tx) => tx.upgrade().map(EventSender::Broadcast),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+enum EventPermit<'a> {
+    Plain(mpsc::Permit<'a, SignalFishEvent>),
+    Enveloped(mpsc::Permit<'a, EventEnvelope>),
+    Broadcast(&'a broadcast::Sender<SignalFishEvent>),
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl EventPermit<'_> {
+    /// Send `envelope`, unwrapped for a plain subscriber.
+    fn send(self, envelope: EventEnvelope) {
+        match self {
+            Self::Plain(permit) => permit.send(envelope.event),
+            Self::Enveloped(permit) => permit.send(envelope),
+            // Fails only while no receiver is subscribed.
+            Self::Broadcast(tx) => {
+                let _ = tx.send(envelope.event);
+            }
+        }
+    }
+}
+
+/// Capacity of an event channel the client starts with: the configured one,
+/// clamped to at least 1 (tokio panics on 0), plus the slot the loop
+/// watchdog keeps reserved.
+#[cfg(feature = "tokio-runtime")]
+fn start_capacity(config: &SignalFishConfig) -> usize {
+    config.event_channel_capacity.max(1) + usize::from(config.loop_watchdog.is_some())
+}
+
+/// The transport loop's heartbeat, read by the watchdog task of
+/// [`SignalFishConfig::loop_watchdog`].
+#[cfg(feature = "tokio-runtime")]
+struct LoopHeartbeat {
+    origin: tokio::time::Instant,
+    /// Milliseconds from `origin` to the last tick, plus one, or
+    /// [`Self::PARKED`].
+    beat: AtomicU64,
+    threshold: Duration,
+    /// Wakes [`run_watched_session`] to abandon a stalled session, under
+    /// [`SignalFishConfig::restart_stalled_loop`].
+    restart: Option<tokio::sync::Notify>,
+    /// One slot per receiver the client started with, for
+    /// [`ClientStalled`](SignalFishEvent::ClientStalled). `None` once the
+    /// terminal `Disconnected` is on its way, so no report can follow it.
+    slots: Mutex<Option<Vec<StallSlot>>>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl LoopHeartbeat {
+    /// The loop is waiting on something bounded elsewhere.
+    const PARKED: u64 = 0;
+
+    fn new(config: &SignalFishConfig, senders: &[EventSender]) -> Option<Arc<Self>> {
+        let threshold = config.loop_watchdog?;
+        let heartbeat = Self {
+            origin: tokio::time::Instant::now(),
+            beat: AtomicU64::new(Self::PARKED),
+            threshold,
+            restart: config.restart_stalled_loop.then(tokio::sync::Notify::new),
+            slots: Mutex::new(Some(
+                senders.iter().cloned().map(StallSlot::reserve).collect(),
+            )),
+        };
+        heartbeat.tick();
+        Some(Arc::new(heartbeat))
+    }
+
+    fn tick(&self) {
+        let elapsed = u64::try_from(self.origin.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.beat
+            .store(elapsed.saturating_add(1), Ordering::Relaxed);
+    }
+
+    fn park(&self) {
+        self.beat.store(Self::PARKED, Ordering::Relaxed);
+    }
+
+    /// When the loop last ticked, or `None` while it is parked.
+    fn last_tick(&self) -> Option<tokio::time::Instant> {
+        match self.beat.load(Ordering::Relaxed) {
+            Self::PARKED => None,
+            beat => Some(self.origin + Duration::from_millis(beat - 1)),
+        }
+    }
+
+    fn pulse(&self) -> Duration {
+        (self.threshold / 2).max(Duration::from_millis(1))
+    }
+
+    /// Stop reporting, releasing the reserved slots.
+    fn finish(&self) {
+        *self.slots() = None;
+    }
+
+    /// Resolves when the watchdog abandons the running session; never
+    /// without [`SignalFishConfig::restart_stalled_loop`].
+    async fn abandoned(heartbeat: Option<&Self>) {
+        match heartbeat.and_then(|heartbeat| heartbeat.restart.as_ref()) {
+            Some(restart) => restart.notified().await,
+            // Nothing restarts the session: this pending future never
+            // completes.
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Reserve again every slot whose receiver has made room.
+    fn refill(&self) {
+        if let Some(slots) = self.slots().as_mut() {
+            for slot in slots {
+                *slot = std::mem::replace(slot, StallSlot::Closed).refill();
+            }
+        }
+    }
+
+    /// Emit `ClientStalled` through every reserved slot, unless the loop has
+    /// finished.
+    fn report(
+        &self,
+        since: tokio::time::Instant,
+        sequence: &EventSequence,
+        observer: &EmissionObserver,
+    ) {
+        let mut slots = self.slots();
+        let Some(slots) = slots.as_mut() else {
+            return;
+        };
+        let envelope = sequence.stamp(SignalFishEvent::ClientStalled {
+            since: since.into_std(),
+        });
+        for (subscriber, slot) in slots.iter_mut().enumerate() {
+            *slot = match std::mem::replace(slot, StallSlot::Closed).refill() {
+                StallSlot::Plain(permit) => {
+                    observer.observe(subscriber, &envelope.event);
+                    StallSlot::Spent(EventSender::Plain(permit.send(envelope.event.clone())))
+                }
+                StallSlot::Enveloped(permit) => {
+                    observer.observe(subscriber, &envelope.event);
+                    StallSlot::Spent(EventSender::Enveloped(permit.send(envelope.clone())))
+                }
+                StallSlot::Broadcast(tx) => {
+                    observer.observe(subscriber, &envelope.event);
+                    let _ = tx.send(envelope.event.clone());
+                    StallSlot::Broadcast(tx)
+                }
+                slot => slot,
+            };
+        }
+    }
+
+    fn slots(&self) -> std::sync::MutexGuard<'_, Option<Vec<StallSlot>>> {
+        match self.slots.lock() {
+            Ok(slots) => slots,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// A receiver's slot for [`ClientStalled`](SignalFishEvent::ClientStalled),
+/// taken from the capacity [`start_capacity`] adds.
+#[cfg(feature = "tokio-runtime")]
+enum StallSlot {
+    Plain(mpsc::OwnedPermit<SignalFishEvent>),
+    Enveloped(mpsc::OwnedPermit<EventEnvelope>),
+    /// A broadcast channel, which always has room.
+    Broadcast(broadcast::Sender<SignalFishEvent>),
+    /// Used, or never free; reserved again once the receiver makes room.
+    Spent(EventSender),
+    /// The receiver is gone.
+    Closed,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl StallSlot {
+    fn reserve(tx: EventSender) -> Self {
+        match tx {
+            EventSender::Plain(tx) => match tx.try_reserve_owned() {
+                Ok(permit) => Self::Plain(permit),
+                Err(mpsc::error::TrySendError::Full(tx)) => Self::Spent(EventSender::Plain(tx)),
+                Err(mpsc::error::TrySendError::Closed(_)) => Self::Closed,
+            },
+            EventSender::Enveloped(tx) => match tx.try_reserve_owned() {
+                Ok(permit) => Self::Enveloped(permit),
+                Err(mpsc::error::TrySendError::Full(tx)) => Self::Spent(EventSender::Enveloped(tx)),
+                Err(mpsc::error::TrySendError::Closed(_)) => Self::Closed,
+            },
+            EventSender::Broadcast(tx) => Self::Broadcast(tx),
+        }
+    }
+
+    fn refill(self) -> Self {
+        match self {
+            Self::Spent(tx) => Self::reserve(tx),
+            slot => slot,
+        }
+    }
+}
+
+/// Check `heartbeat` every quarter threshold until the loop ends, reporting
+/// each stall once and, under [`SignalFishConfig::restart_stalled_loop`],
+/// abandoning the stalled session.
+#[cfg(feature = "tokio-runtime")]
+async fn watch_loop(
+    heartbeat: Arc<LoopHeartbeat>,
+    sequence: EventSequence,
+    observer: EmissionObserver,
+    mut loop_ended: tokio::sync::watch::Receiver<()>,
+) {
+    let period = (heartbeat.threshold / 4).max(Duration::from_millis(1));
+    let mut reported = None;
+    loop {
+        tokio::select! {
+            _ = loop_ended.changed() => break,
+            () = tokio::time::sleep(period) => {}
+        }
+        heartbeat.refill();
+        let Some(since) = heartbeat.last_tick() else {
+            continue;
+        };
+        let stalled_for = since.elapsed();
+        if stalled_for < heartbeat.threshold || reported == Some(since) {
+            continue;
+        }
+        reported = Some(since);
+        warn!(?stalled_for, "transport loop stalled");
+        heartbeat.report(since, &sequence, &observer);
+        if let Some(restart) = &heartbeat.restart {
+            restart.notify_waiters();
+        }
+    }
+    // The receivers close once the loop's senders and these slots are gone.
+    heartbeat.finish();
+}
+
+/// A frame queued for the transport loop and when it stops being worth
+/// sending, per [`SignalFishConfig::message_deadlines`].
+#[cfg(feature = "tokio-runtime")]
+struct Outgoing {
+    command: ClientCommand,
+    expires_at: Option<tokio::time::Instant>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl Outgoing {
+    /// Give an unstamped message its kind's default deadline, from now.
+    fn stamp(&mut self, core: &ClientCore) {
+        if self.expires_at.is_none() {
+            self.expires_at = core
+                .message_deadline(self.command.kind())
+                .map(|deadline| tokio::time::Instant::now() + deadline);
+        }
+    }
+
+    /// The command to send, or `Err` with its kind when it is past its
+    /// deadline, counted in [`ClientStats::messages_expired`].
+    fn live(
+        self,
+        state: &Arc<Mutex<ClientCore>>,
+    ) -> std::result::Result<ClientCommand, ClientMessageKind> {
+        match self.expires_at {
+            Some(expires_at) if tokio::time::Instant::now() >= expires_at => {
+                let kind = self.command.kind();
+                debug!(?kind, "dropping a message past its deadline");
+                lock_core(state).record_message_expired();
+                Err(kind)
+            }
+            _ => Ok(self.command),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl From<ClientCommand> for Outgoing {
+    fn from(command: ClientCommand) -> Self {
+        Self {
+            command,
+            expires_at: None,
+        }
+    }
+}
+
+/// What the client handle queues for the transport loop.
+#[cfg(feature = "tokio-runtime")]
+enum LoopCommand {
+    /// A frame to send.
+    Send(Outgoing),
+    /// A `Ping` whose round trip [`SignalFishClient::ping_rtt`] awaits.
+    TimedPing(ClientCommand, crate::client_core::PingWaiter),
+    /// A checked [`RuntimeConfigPatch`](crate::RuntimeConfigPatch) to apply.
+    RuntimeConfig(crate::RuntimeConfigPatch),
+    /// A receiver added by [`SignalFishClient::subscribe`].
+    Subscribe {
+        events: EventSender,
+        handed_off: Arc<AtomicBool>,
+    },
+    /// The internal receiver of a [`SignalFishClient::wait_for`].
+    Watch(mpsc::Sender<SignalFishEvent>),
+    /// [`SignalFishClient::begin_drain`] was called; the core already
+    /// refuses new rooms.
+    Drain,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl LoopCommand {
+    /// Kind of the message this command sends, if it sends one.
+    fn message_kind(&self) -> Option<ClientMessageKind> {
+        match self {
+            Self::Send(outgoing) => Some(outgoing.command.kind()),
+            Self::TimedPing(command, _) => Some(command.kind()),
+            _ => None,
+        }
+    }
+
+    /// Stamp a send with its kind's default deadline.
+    fn stamp(&mut self, core: &ClientCore) {
+        if let Self::Send(outgoing) = self {
+            outgoing.stamp(core);
+        }
+    }
+
+    /// This command as the loop handles it: a timed `Ping` arms its waiter
+    /// for the next `Ping` sent and becomes a plain send.
+    fn armed(self, state: &Arc<Mutex<ClientCore>>) -> Self {
+        match self {
+            Self::TimedPing(command, waiter) => {
+                lock_core(state).arm_ping(waiter);
+                Self::Send(command.into())
+            }
+            command => command,
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl From<ClientCommand> for LoopCommand {
+    fn from(command: ClientCommand) -> Self {
+        Self::Send(command.into())
+    }
+}
+
+/// A repeating request the transport loop sends on its own while
+/// authenticated.
+#[cfg(feature = "tokio-runtime")]
+struct Cadence {
+    interval: Option<Duration>,
+    next: Option<tokio::time::Instant>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl Cadence {
+    fn new(interval: Option<Duration>) -> Self {
+        let mut cadence = Self {
+            interval: None,
+            next: None,
+        };
+        cadence.retune(interval);
+        cadence
+    }
+
+    /// Switch to `interval`, counting the next tick from now.
+    fn retune(&mut self, interval: Option<Duration>) {
+        self.interval = interval.filter(|interval| !interval.is_zero());
+        self.rearm();
+    }
+
+    fn rearm(&mut self) {
+        self.next = self
+            .interval
+            .map(|interval| tokio::time::Instant::now() + interval);
+    }
+
+    /// Schedule the next tick and prepare this tick's `operation`; `None`
+    /// when not authenticated.
+    fn tick(
+        &mut self,
+        state: &Arc<Mutex<ClientCore>>,
+        operation: ClientOperation,
+    ) -> Option<ClientCommand> {
+        self.rearm();
+        let mut core = lock_core(state);
+        let command = core
+            .is_authenticated()
+            .then(|| core.prepare(operation).ok())
+            .flatten()?;
+        core.record_enqueued(command.kind());
+        Some(command)
+    }
+
+    /// [`tick`](Self::tick) for the heartbeat `Ping`. When the transport saw
+    /// the connection alive within the last interval, the ping is skipped and
+    /// the next tick falls one interval after that sighting.
+    fn heartbeat(
+        &mut self,
+        state: &Arc<Mutex<ClientCore>>,
+        liveness: Option<std::time::Instant>,
+    ) -> Option<ClientCommand> {
+        if let (Some(interval), Some(seen)) = (self.interval, liveness) {
+            let seen = tokio::time::Instant::from_std(seen);
+            if tokio::time::Instant::now().saturating_duration_since(seen) < interval {
+                debug!("transport reported recent activity; skipping heartbeat Ping");
+                self.next = Some(seen + interval);
+                lock_core(state).observe_transport_activity(seen.into_std());
+                return None;
+            }
+        }
+        self.tick(state, ClientOperation::Ping)
+    }
+}
+
+/// Enforcement of [`SignalFishConfig::on_receiver_dropped`].
+#[cfg(feature = "tokio-runtime")]
+struct ReceiverWatch {
+    policy: OnReceiverDropped,
+    /// The next periodic check; `None` under `KeepRunning` and while paused.
+    next: Option<tokio::time::Instant>,
+    paused: bool,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl ReceiverWatch {
+    fn new(policy: OnReceiverDropped) -> Self {
+        let mut watch = Self {
+            policy,
+            next: None,
+            paused: false,
+        };
+        watch.rearm();
+        watch
+    }
+
+    fn rearm(&mut self) {
+        self.next = (self.policy != OnReceiverDropped::KeepRunning && !self.paused)
+            .then(|| tokio::time::Instant::now() + RECEIVER_CHECK_INTERVAL);
+    }
+
+    /// Apply the policy if every receiver is gone; `true` ends the session.
+    fn check(&mut self, fanout: &EventFanout) -> bool {
+        if self.policy == OnReceiverDropped::KeepRunning || self.paused || !fanout.unsubscribed() {
+            return false;
+        }
+        match self.policy {
+            OnReceiverDropped::KeepRunning => false,
+            OnReceiverDropped::ShutdownClient => {
+                debug!("every event receiver dropped; shutting down");
+                true
+            }
+            OnReceiverDropped::PauseParsing => {
+                debug!("every event receiver dropped; pausing frame parsing");
+                self.paused = true;
+                self.next = None;
+                false
+            }
+        }
+    }
+
+    /// A receiver was attached.
+    fn resume(&mut self) {
+        if self.paused {
+            debug!("event receiver attached; resuming frame parsing");
+        }
+        self.paused = false;
+        self.rearm();
+    }
+}
+
+/// Deferred emission of `LobbyStateChanged` per
+/// [`SignalFishConfig::lobby_state_debounce`].
+#[cfg(feature = "tokio-runtime")]
+struct LobbyDebounce {
+    window: Option<Duration>,
+    held: Option<SignalFishEvent>,
+    deadline: Option<tokio::time::Instant>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl LobbyDebounce {
+    fn new(window: Option<Duration>) -> Self {
+        Self {
+            window: window.filter(|window| !window.is_zero()),
+            held: None,
+            deadline: None,
+        }
+    }
+
+    /// Route `event` through the debounce. Returns what to emit now, in order:
+    /// a held lobby state flushed ahead of `event`, then `event` itself unless
+    /// it was held back.
+    fn admit(&mut self, event: SignalFishEvent) -> [Option<SignalFishEvent>; 2] {
+        let Some(window) = self.window else {
+            return [None, Some(event)];
+        };
+        match event {
+            SignalFishEvent::LobbyStateChanged {
+                all_ready: false, ..
+            } => {
+                self.deadline
+                    .get_or_insert_with(|| tokio::time::Instant::now() + window);
+                self.held = Some(event);
+                [None, None]
+            }
+            // Readiness gates game start: never delay it. It supersedes any
+            // held state, which it already reflects.
+            SignalFishEvent::LobbyStateChanged { .. } => {
+                self.take();
+                [None, Some(event)]
+            }
+            event => [self.take(), Some(event)],
+        }
+    }
+
+    /// Release the held lobby state, if any, and stop the timer.
+    fn take(&mut self) -> Option<SignalFishEvent> {
+        self.deadline = None;
+        self.held.take()
+    }
+
+    /// Switch to `window`, releasing any state held under the old one.
+    fn retune(&mut self, window: Option<Duration>) -> Option<SignalFishEvent> {
+        self.window = window.filter(|window| !window.is_zero());
+        self.take()
+    }
+}
+
+/// The transport loop's half of a client: everything it owns besides the
+/// transport, which a reconnecting loop replaces.
+#[cfg(feature = "tokio-runtime")]
+struct LoopContext {
+    cmd_rx: mpsc::Receiver<LoopCommand>,
+    events: EventFanout,
+    lobby_debounce: LobbyDebounce,
+    state: Arc<Mutex<ClientCore>>,
+    shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+    close_timeout: Duration,
+    flush_on_shutdown: bool,
+    leave_room_on_shutdown: bool,
+    offline: OfflineQueue,
+    session_store: Option<Arc<dyn crate::SessionStore>>,
+}
+
+/// Commands a reconnecting client holds for its next authenticated
+/// connection, oldest first, per
+/// [`SignalFishConfig::offline_queue_capacity`].
+#[cfg(feature = "tokio-runtime")]
+struct OfflineQueue {
+    capacity: usize,
+    held: std::collections::VecDeque<Outgoing>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl OfflineQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            held: std::collections::VecDeque::new(),
+        }
+    }
+
+    /// Hold `command`, evicting the oldest when full. A `Ping` is dropped:
+    /// by the time it went out it would only time the outage.
+    fn hold(&mut self, command: Outgoing, state: &Arc<Mutex<ClientCore>>) {
+        if command.command.kind() == ClientMessageKind::Ping || self.capacity == 0 {
+            return;
+        }
+        if self.held.len() >= self.capacity {
+            self.held.pop_front();
+            lock_core(state).record_offline_eviction();
+        }
+        self.held.push_back(command);
+    }
+
+    /// Send everything held, in order, once `state` has authenticated,
+    /// adding the kind of each message dropped past its deadline to
+    /// `expired`. `Err` carries the `Disconnected` reason of a failed send;
+    /// what was not yet sent stays held.
+    async fn flush(
+        &mut self,
+        transport: &mut (impl Transport + Send),
+        state: &Arc<Mutex<ClientCore>>,
+        expired: &mut Vec<ClientMessageKind>,
+    ) -> std::result::Result<(), DisconnectReason> {
+        if self.held.is_empty() || !lock_core(state).is_authenticated() {
+            return Ok(());
+        }
+        while let Some(outgoing) = self.held.pop_front() {
+            match outgoing.live(state) {
+                Ok(command) => send_command_frame(transport, state, command).await?,
+                Err(kind) => expired.push(kind),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Background transport loop that multiplexes send/receive via `tokio::select!`.
+///
+/// Exits when:
+/// - The command channel closes (client handle dropped or shutdown called)
+/// - The transport returns `None` (server closed connection)
+/// - A transport error occurs
+///
+/// Every exit closes the transport and emits the terminal `Disconnected`
+/// before the event senders drop, so receivers see it strictly before `None`.
+/// The close is bounded by `close_timeout`; a transport still closing after
+/// that is aborted, so a hung close cannot keep the loop alive.
+#[cfg(feature = "tokio-runtime")]
+async fn transport_loop(
+    mut transport: impl Transport + Send + 'static,
+    authenticate: ClientCommand,
+    mut context: LoopContext,
+) {
+    debug!("transport loop started");
+    context.restore_ticket().await;
+    let exit = if transport.claim_session() {
+        let mut opening = vec![authenticate];
+        {
+            let mut core = lock_core(&context.state);
+            core.record_enqueued(ClientMessageKind::Authenticate);
+            opening.extend(core.auto_resume());
+        }
+        let exit = run_watched_session(&mut transport, &mut context, opening).await;
+        close_session(&mut transport, &mut context, exit).await
+    } else {
+        // Another session owns the connection: leave it untouched.
+        LoopExit::Disconnected(lock_core(&context.state).transport_reused())
+    };
+    context.finish(exit).await;
+    debug!("transport loop exited");
+}
+
+/// [`transport_loop`] for a client that dials its own transports through
+/// `factory`, reconnecting per `policy` while a transport-originated end
+/// leaves anything to reconnect for. Every transport first sends
+/// `authenticate`; the commands held while none was open follow once it
+/// authenticates.
+#[cfg(feature = "tokio-runtime")]
+async fn reconnect_loop<F: TransportFactory>(
+    mut factory: F,
+    policy: crate::ReconnectPolicy,
+    authenticate: ClientCommand,
+    mut context: LoopContext,
+) {
+    debug!("reconnecting transport loop started");
+    context.restore_ticket().await;
+    let mut attempt: u32 = 0;
+    let exit = loop {
+        let dialed = match context.outage(factory.connect()).await {
+            Ok(dialed) => dialed,
+            Err(exit) => break exit,
+        };
+        let failure = match dialed {
+            Ok(mut transport) => {
+                if !transport.claim_session() {
+                    // A factory handing back a used transport will not
+                    // improve on a retry; leave the connection untouched.
+                    break LoopExit::Disconnected(lock_core(&context.state).transport_reused());
+                }
+                let mut opening = vec![authenticate.clone()];
+                {
+                    let mut core = lock_core(&context.state);
+                    core.transport_reopened();
+                    core.record_enqueued(authenticate.kind());
+                    opening.extend(core.auto_resume());
+                }
+                let exit = run_watched_session(&mut transport, &mut context, opening).await;
+                let reason = match close_session(&mut transport, &mut context, exit).await {
+                    LoopExit::Lost(reason) => reason,
+                    exit => break exit,
+                };
+                let core = lock_core(&context.state);
+                if core.authentication_rejected() {
+                    break LoopExit::Disconnected(reason);
+                }
+                if core.is_authenticated() {
+                    attempt = 0;
+                }
+                reason
+            }
+            Err(error) => DisconnectReason::TransportError {
+                detail: format!("connect failed: {error}"),
+            },
+        };
+        lock_core(&context.state).begin_outage();
+        if policy.max_attempts.is_some_and(|max| attempt >= max) {
+            break LoopExit::Disconnected(DisconnectReason::ReconnectExhausted {
+                attempts: attempt,
+                last_failure: Box::new(failure),
+            });
+        }
+        attempt = attempt.saturating_add(1);
+        let (unit, _) = uuid::Uuid::new_v4().as_u64_pair();
+        #[allow(clippy::cast_precision_loss)]
+        let delay = policy.delay(attempt, unit as f64 / u64::MAX as f64);
+        warn!(attempt, ?delay, %failure, "transport lost; reconnecting");
+        let reconnecting = SignalFishEvent::Reconnecting { attempt, delay };
+        if matches!(
+            emit_event_or_shutdown(&context.events, &mut context.shutdown_rx, reconnecting).await,
+            EmitOutcome::ShutdownRequested
+        ) {
+            break LoopExit::Shutdown;
+        }
+        if let Err(exit) = context.outage(tokio::time::sleep(delay)).await {
+            break exit;
+        }
+    };
+    context.finish(exit).await;
+    debug!("reconnecting transport loop exited");
+}
+
+/// [`run_session`], abandoned as lost when the loop watchdog gives up on it
+/// under [`SignalFishConfig::restart_stalled_loop`].
+#[cfg(feature = "tokio-runtime")]
+async fn run_watched_session(
+    transport: &mut (impl Transport + Send),
+    context: &mut LoopContext,
+    opening: Vec<ClientCommand>,
+) -> LoopExit {
+    let heartbeat = context.events.heartbeat.clone();
+    tokio::select! {
+        exit = run_session(transport, context, opening) => exit,
+        () = LoopHeartbeat::abandoned(heartbeat.as_deref()) => {
+            warn!("abandoning the stalled connection");
+            LoopExit::Lost(DisconnectReason::LoopStalled)
+        }
+    }
+}
+
+/// Run one connection over `transport` until it ends: emit `Connected`, send
+/// `opening`, then serve commands, timers, and received frames. Commands in
+/// the offline queue go out once the connection authenticates, and later
+/// sends wait behind them.
+#[cfg(feature = "tokio-runtime")]
+async fn run_session(
+    transport: &mut (impl Transport + Send),
+    context: &mut LoopContext,
+    opening: Vec<ClientCommand>,
+) -> LoopExit {
+    let LoopContext {
+        cmd_rx,
+        events: event_tx,
+        lobby_debounce,
+        state,
+        shutdown_rx,
+        offline,
+        ..
+    } = context;
+    let decoder = lock_core(state).frame_decoder();
+    let mut pipeline = lock_core(state)
+        .effective_config()
+        .decode_pipeline
+        .value
+        .map(|depth| DecodePipeline::spawn(depth, &format!("{}/decode", event_tx.label), decoder));
+
+    let exit = 'session: {
+        let announce = lock_core(state).announce_connected();
+        if announce
+            && matches!(
+                emit_event_or_shutdown(event_tx, shutdown_rx, SignalFishEvent::Connected).await,
+                EmitOutcome::ShutdownRequested
+            )
+        {
+            break 'session LoopExit::Shutdown;
+        }
+        if let Some(reason) = lock_core(state).take_handshake_refusal() {
+            break 'session LoopExit::Disconnected(reason);
+        }
+        for command in opening {
+            if let Err(reason) = send_command_frame(transport, state, command).await {
+                break 'session LoopExit::Lost(reason);
+            }
+        }
+
+        let mut self_removal_deadline: Option<tokio::time::Instant> = None;
+        let mut transport_end: Option<DisconnectReason> = None;
+        let configured = lock_core(state).effective_config();
+        let mut ping = Cadence::new(configured.ping_interval.value);
+        let pong_timeout = configured.pong_timeout.value;
+        let mut time_sync = Cadence::new(configured.time_sync_interval.value);
+        let mut receivers = ReceiverWatch::new(configured.on_receiver_dropped.value);
+        let mut draining = false;
+        let mut drain_deadline: Option<tokio::time::Instant> = None;
+        let pulse = event_tx.pulse();
+        loop {
+            event_tx.tick();
+            if draining && lock_core(state).drained() {
+                break LoopExit::Disconnected(DisconnectReason::Drained);
+            }
+            let mut expired = Vec::new();
+            let flushed = offline.flush(transport, state, &mut expired).await;
+            let mut shutdown_requested = false;
+            for kind in expired {
+                let event = SignalFishEvent::MessageExpired { kind };
+                if matches!(
+                    emit_event_or_shutdown(event_tx, shutdown_rx, event).await,
+                    EmitOutcome::ShutdownRequested
+                ) {
+                    shutdown_requested = true;
+                    break;
+                }
+            }
+            if shutdown_requested {
+                break LoopExit::Shutdown;
+            }
+            if let Err(reason) = flushed {
+                break LoopExit::Lost(reason);
+            }
+            let pong_deadline = pong_timeout
+                .filter(|_| !receivers.paused)
+                .and_then(|timeout| {
+                    let sent = lock_core(state).oldest_unanswered_ping()?;
+                    Some(tokio::time::Instant::from_std(sent) + timeout)
+                });
+            // Cancel safety: whenever one arm wins, the others' futures are
+            // dropped, so each must lose nothing when dropped mid-wait. They
+            // are `mpsc` receives (commands, the decode pipeline), timers,
+            // the shutdown `oneshot`, and `recv_frame`, whose `poll_recv`
+            // yields a frame only in the poll that completes. What must not
+            // be cut short — sends and event deliveries — runs in the arm
+            // bodies, after the select has chosen.
+            tokio::select! {
+                command = cmd_rx.recv() => {
+                    match command.map(|command| command.armed(state)) {
+                        None => break LoopExit::Disconnected(DisconnectReason::ClientShutdown),
+                        Some(LoopCommand::Send(command)) if !offline.held.is_empty() => {
+                            offline.hold(command, state);
+                        }
+                        Some(LoopCommand::Send(outgoing)) => {
+                            let command = match outgoing.live(state) {
+                                Ok(command) => command,
+                                Err(kind) => {
+                                    let event = SignalFishEvent::MessageExpired { kind };
+                                    if matches!(
+                                        emit_event_or_shutdown(event_tx, shutdown_rx, event).await,
+                                        EmitOutcome::ShutdownRequested
+                                    ) {
+                                        break LoopExit::Shutdown;
+                                    }
+                                    continue;
+                                }
+                            };
+                            let pinged = command.kind() == ClientMessageKind::Ping;
+                            if let Err(reason) = send_command_frame(transport, state, command).await {
+                                break LoopExit::Lost(reason);
+                            }
+                            // An application ping already shows the
+                            // connection alive; the heartbeat waits a full
+                            // interval from it.
+                            if pinged {
+                                ping.rearm();
+                            }
+                        }
+                        Some(LoopCommand::RuntimeConfig(patch)) => {
+                            debug!(?patch, "applying runtime config");
+                            lock_core(state).apply_runtime_config(&patch);
+                            if let Some(interval) = patch.ping_interval {
+                                ping.retune(interval);
+                            }
+                            if let Some(interval) = patch.time_sync_interval {
+                                time_sync.retune(interval);
+                            }
+                            let released = patch
+                                .lobby_state_debounce
+                                .and_then(|window| lobby_debounce.retune(window));
+                            if let Some(held) = released {
+                                if matches!(
+                                    emit_event_or_shutdown(event_tx, shutdown_rx, held).await,
+                                    EmitOutcome::ShutdownRequested
+                                ) {
+                                    break LoopExit::Shutdown;
+                                }
+                            }
+                        }
+                        Some(LoopCommand::Subscribe { events, handed_off }) => {
+                            event_tx.attach(events, handed_off);
+                            receivers.resume();
+                        }
+                        Some(LoopCommand::Watch(waiter)) => event_tx.watch(waiter),
+                        // Armed into a `Send` above.
+                        Some(LoopCommand::TimedPing(..)) => {}
+                        Some(LoopCommand::Drain) => {
+                            if !draining {
+                                debug!("draining");
+                                draining = true;
+                                drain_deadline = lock_core(state)
+                                    .effective_config()
+                                    .drain_timeout
+                                    .value
+                                    .map(|timeout| tokio::time::Instant::now() + timeout);
+                            }
+                        }
+                    }
+                }
+                () = tokio::time::sleep_until(
+                    drain_deadline.unwrap_or_else(tokio::time::Instant::now),
+                ), if drain_deadline.is_some() => {
+                    break LoopExit::Disconnected(DisconnectReason::DrainTimedOut);
+                }
+                // Wakes an idle loop to tick the watchdog's heartbeat.
+                () = tokio::time::sleep(pulse.unwrap_or_default()), if pulse.is_some() => {}
+                () = tokio::time::sleep_until(
+                    receivers.next.unwrap_or_else(tokio::time::Instant::now),
+                ), if receivers.next.is_some() => {
+                    receivers.rearm();
+                    if receivers.check(event_tx) {
+                        break LoopExit::Disconnected(DisconnectReason::ReceiversDropped);
+                    }
+                }
+                () = tokio::time::sleep_until(
+                    ping.next.unwrap_or_else(tokio::time::Instant::now),
+                ), if ping.next.is_some() => {
+                    if let Some(command) = ping.heartbeat(state, transport.last_activity_hint()) {
+                        if let Err(reason) = send_command_frame(transport, state, command).await {
+                            break LoopExit::Lost(reason);
+                        }
+                    }
+                }
+                () = tokio::time::sleep_until(
+                    pong_deadline.unwrap_or_else(tokio::time::Instant::now),
+                ), if pong_deadline.is_some() => {
+                    warn!(?pong_timeout, "no Pong within the heartbeat timeout; closing the transport");
+                    break LoopExit::Lost(DisconnectReason::HeartbeatTimeout);
+                }
+                () = tokio::time::sleep_until(
+                    time_sync.next.unwrap_or_else(tokio::time::Instant::now),
+                ), if time_sync.next.is_some() => {
+                    if let Some(command) = time_sync.tick(state, ClientOperation::TimeSync) {
+                        if let Err(reason) = send_command_frame(transport, state, command).await {
+                            break LoopExit::Lost(reason);
+                        }
+                    }
+                }
+                _ = &mut *shutdown_rx => {
+                    let ended = match transport_end.take() {
+                        Some(reason) => Some(reason),
+                        None => transport_ended(transport, state).await,
+                    };
+                    break match ended {
+                        Some(reason) => LoopExit::EndedBeforeShutdown(reason),
+                        None => LoopExit::Shutdown,
+                    };
+                }
+                () = tokio::time::sleep_until(
+                    lobby_debounce.deadline.unwrap_or_else(tokio::time::Instant::now),
+                ), if lobby_debounce.deadline.is_some() => {
+                    if let Some(held) = lobby_debounce.take() {
+                        if matches!(
+                            emit_event_or_shutdown(event_tx, shutdown_rx, held).await,
+                            EmitOutcome::ShutdownRequested
+                        ) {
+                            break LoopExit::Shutdown;
+                        }
+                    }
+                }
+                () = tokio::time::sleep_until(
+                    self_removal_deadline.unwrap_or_else(tokio::time::Instant::now),
+                ), if self_removal_deadline.is_some() => {
+                    self_removal_deadline = None;
+                    let removed = lock_core(state).expire_self_removal();
+                    let mut shutdown_requested = false;
+                    for event in removed.into_iter().flat_map(|event| lobby_debounce.admit(event)).flatten() {
+                        if matches!(
+                            emit_event_or_shutdown(event_tx, shutdown_rx, event).await,
+                            EmitOutcome::ShutdownRequested
+                        ) {
+                            shutdown_requested = true;
+                            break;
+                        }
+                    }
+                    if shutdown_requested {
+                        break LoopExit::Shutdown;
+                    }
+                }
+                incoming = recv_frame(transport),
+                    if transport_end.is_none() && pipeline.as_ref().is_none_or(DecodePipeline::accepting) =>
+                {
+                    let reason = match incoming {
+                        Some(Ok(frame)) => {
+                            if receivers.check(event_tx) {
+                                break LoopExit::Disconnected(DisconnectReason::ReceiversDropped);
+                            }
+                            if receivers.paused {
+                                lock_core(state).skip_frame();
+                                continue;
+                            }
+                            if let Some(pipeline) = &pipeline {
+                                pipeline.push(frame);
+                                continue;
+                            }
+                            let frame = decoder.decode(frame);
+                            if let Some(exit) = apply_frame(
+                                frame,
+                                state,
+                                event_tx,
+                                shutdown_rx,
+                                lobby_debounce,
+                                &mut self_removal_deadline,
+                            )
+                            .await
+                            {
+                                break exit;
+                            }
+                            continue;
+                        }
+                        Some(Err(error)) => lock_core(state).receive_failure_reason(&error),
+                        None => lock_core(state).server_close_reason(transport.close_info()),
+                    };
+                    match &mut pipeline {
+                        // Frames still in the pipeline precede the end.
+                        Some(pipeline) => {
+                            pipeline.close();
+                            transport_end = Some(reason);
+                        }
+                        None => break LoopExit::Lost(reason),
+                    }
+                }
+                decoded = DecodePipeline::next(pipeline.as_mut()), if pipeline.is_some() => {
+                    let Some(frame) = decoded else {
+                        break match transport_end.take() {
+                            Some(reason) => LoopExit::Lost(reason),
+                            None => LoopExit::Disconnected(DisconnectReason::TransportError {
+                                detail: "decode worker stopped".into(),
+                            }),
+                        };
+                    };
+                    if let Some(exit) = apply_frame(
+                        frame,
+                        state,
+                        event_tx,
+                        shutdown_rx,
+                        lobby_debounce,
+                        &mut self_removal_deadline,
+                    )
+                    .await
+                    {
+                        break exit;
+                    }
+                }
+            }
+        }
+    };
+
+    // Frames still queued for decoding are abandoned with the session.
+    if let Some(pipeline) = pipeline.take() {
+        pipeline.stop().await;
+    }
+    exit
+}
+
+/// Wind down a session's connection: a held lobby state still precedes
+/// whatever follows, a requested shutdown sends the commands still queued and
+/// leaves the room as configured, then the transport closes within the close
+/// timeout.
+#[cfg(feature = "tokio-runtime")]
+async fn close_session(
+    transport: &mut (impl Transport + Send),
+    context: &mut LoopContext,
+    exit: LoopExit,
+) -> LoopExit {
+    // Every step here is bounded by the close timeout.
+    context.events.park();
+    let mut exit = exit;
+    if !exit.shutdown_requested() {
+        if let Some(held) = context.lobby_debounce.take() {
+            if matches!(
+                emit_event_or_shutdown(&context.events, &mut context.shutdown_rx, held).await,
+                EmitOutcome::ShutdownRequested
+            ) {
+                exit = LoopExit::Shutdown;
+            }
+        }
+    }
+    let close_timeout = context.close_timeout;
+    if matches!(exit, LoopExit::Shutdown) {
+        match tokio::time::timeout(close_timeout, context.before_close(transport)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(reason)) => exit = LoopExit::EndedBeforeShutdown(reason),
+            Err(_) => warn!("shutdown flush and leave did not finish within {close_timeout:?}"),
+        }
+    }
+    if tokio::time::timeout(close_timeout, close_transport(transport))
+        .await
+        .is_err()
+    {
+        warn!("transport close did not finish within {close_timeout:?}; aborting transport");
+        transport.abort();
+    }
+    exit
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl LoopContext {
+    /// A requested shutdown's work on the open transport, per
+    /// [`SignalFishConfig::flush_on_shutdown`] and
+    /// [`SignalFishConfig::leave_room_on_shutdown`].
+    async fn before_close(
+        &mut self,
+        transport: &mut (impl Transport + Send),
+    ) -> std::result::Result<(), DisconnectReason> {
+        if self.flush_on_shutdown {
+            self.flush_queued(transport).await?;
+        }
+        if self.leave_room_on_shutdown {
+            self.leave_held_room(transport).await?;
+        }
+        Ok(())
+    }
+
+    /// Leave the room held, unless a leave already went out, and apply
+    /// received frames until the server confirms it.
+    async fn leave_held_room(
+        &mut self,
+        transport: &mut (impl Transport + Send),
+    ) -> std::result::Result<(), DisconnectReason> {
+        let leave = {
+            let mut core = lock_core(&self.state);
+            if !core.holds_room() {
+                return Ok(());
+            }
+            core.prepare_shutdown_leave()
+        };
+        if let Some(command) = leave {
+            send_command_frame(transport, &self.state, command).await?;
+        }
+        let decoder = lock_core(&self.state).frame_decoder();
+        // The shutdown signal has already fired; only the caller's deadline
+        // cuts these deliveries short.
+        let (_unfired, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let mut self_removal_deadline = None;
+        while lock_core(&self.state).holds_room() {
+            let frame = match recv_frame(transport).await {
+                Some(Ok(frame)) => decoder.decode(frame),
+                Some(Err(error)) => {
+                    return Err(lock_core(&self.state).receive_failure_reason(&error));
+                }
+                None => {
+                    return Err(lock_core(&self.state).server_close_reason(transport.close_info()));
+                }
+            };
+            match apply_frame(
+                frame,
+                &self.state,
+                &self.events,
+                &mut shutdown_rx,
+                &mut self.lobby_debounce,
+                &mut self_removal_deadline,
+            )
+            .await
+            {
+                None | Some(LoopExit::Shutdown) => {}
+                Some(
+                    LoopExit::Disconnected(reason)
+                    | LoopExit::Lost(reason)
+                    | LoopExit::EndedBeforeShutdown(reason),
+                ) => return Err(reason),
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the commands held offline and then those still queued, in
+    /// order, reporting those past their deadline as expired. Late
+    /// subscribers are attached; other commands no longer matter. `Err`
+    /// carries the `Disconnected` reason of a failed send.
+    async fn flush_queued(
+        &mut self,
+        transport: &mut (impl Transport + Send),
+    ) -> std::result::Result<(), DisconnectReason> {
+        let mut expired = Vec::new();
+        let flushed = self
+            .offline
+            .flush(transport, &self.state, &mut expired)
+            .await;
+        self.report_expired(expired).await;
+        flushed?;
+        while let Ok(command) = self.cmd_rx.try_recv() {
+            match command.armed(&self.state) {
+                LoopCommand::Send(outgoing) => match outgoing.live(&self.state) {
+                    Ok(command) => send_command_frame(transport, &self.state, command).await?,
+                    Err(kind) => self.report_expired(vec![kind]).await,
+                },
+                LoopCommand::Subscribe { events, handed_off } => {
+                    self.events.attach(events, handed_off);
+                }
+                LoopCommand::Watch(waiter) => self.events.watch(waiter),
+                LoopCommand::RuntimeConfig(_) | LoopCommand::Drain | LoopCommand::TimedPing(..) => {
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit [`SignalFishEvent::MessageExpired`] for each of `expired`. The
+    /// shutdown signal has already fired; only the caller's deadline cuts
+    /// these deliveries short.
+    async fn report_expired(&self, expired: Vec<ClientMessageKind>) {
+        let (_unfired, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        for kind in expired {
+            let event = SignalFishEvent::MessageExpired { kind };
+            self.events.deliver(&mut shutdown_rx, event).await;
+        }
+    }
+
+    /// Run `work` while no transport is open. Sent commands are held in the
+    /// offline queue for the next connection and the others apply at once;
+    /// `Err` ends the client instead.
+    async fn outage<T>(
+        &mut self,
+        work: impl std::future::Future<Output = T>,
+    ) -> std::result::Result<T, LoopExit> {
+        tokio::pin!(work);
+        let pulse = self.events.pulse();
+        loop {
+            self.events.tick();
+            tokio::select! {
+                done = &mut work => return Ok(done),
+                () = tokio::time::sleep(pulse.unwrap_or_default()), if pulse.is_some() => {}
+                command = self.cmd_rx.recv() => match command.map(|command| command.armed(&self.state)) {
+                    None => return Err(LoopExit::Disconnected(DisconnectReason::ClientShutdown)),
+                    Some(LoopCommand::Send(command)) => self.offline.hold(command, &self.state),
+                    Some(LoopCommand::RuntimeConfig(patch)) => {
+                        debug!(?patch, "applying runtime config");
+                        lock_core(&self.state).apply_runtime_config(&patch);
+                        // Nothing is held between sessions.
+                        if let Some(window) = patch.lobby_state_debounce {
+                            let _ = self.lobby_debounce.retune(window);
+                        }
+                    }
+                    Some(LoopCommand::Subscribe { events, handed_off }) => {
+                        self.events.attach(events, handed_off);
+                    }
+                    Some(LoopCommand::Watch(waiter)) => self.events.watch(waiter),
+                    Some(LoopCommand::Drain) => {
+                        return Err(LoopExit::Disconnected(DisconnectReason::Drained));
+                    }
+                    // Armed into a `Send` above.
+                    Some(LoopCommand::TimedPing(..)) => {}
+                },
+                _ = &mut self.shutdown_rx => return Err(LoopExit::Shutdown),
+            }
+        }
+    }
+
+    /// Take the ticket kept in the session store, if any, as the room to
+    /// resume. A ticket that does not parse is logged and removed.
+    async fn restore_ticket(&self) {
+        let Some(store) = &self.session_store else {
+            return;
+        };
+        let stored = match store.get(crate::SESSION_TICKET_KEY).await {
+            Ok(Some(stored)) => stored,
+            Ok(None) => return,
+            Err(error) => {
+                warn!("could not load the stored session ticket: {error}");
+                return;
+            }
+        };
+        match serde_json::from_slice(&stored) {
+            Ok(ticket) => {
+                debug!("resuming from the stored session ticket");
+                lock_core(&self.state).restore_ticket(ticket);
+            }
+            Err(error) => {
+                warn!("discarding a corrupt stored session ticket: {error}");
+                store_ticket(store.as_ref(), None).await;
+            }
+        }
+    }
+
+    /// Emit the terminal `Disconnected` for `exit`, to late subscribers too.
+    async fn finish(mut self, exit: LoopExit) {
+        let (reason, shutdown_requested) = match exit {
+            LoopExit::Shutdown => (DisconnectReason::ClientShutdown, true),
+            LoopExit::EndedBeforeShutdown(reason) => (reason, true),
+            LoopExit::Disconnected(reason) | LoopExit::Lost(reason) => (reason, false),
+        };
+        // Receivers subscribed too late to see the session still get its end.
+        self.cmd_rx.close();
+        while let Ok(command) = self.cmd_rx.try_recv() {
+            match command {
+                LoopCommand::Subscribe { events, handed_off } => {
+                    self.events.attach(events, handed_off);
+                }
+                LoopCommand::Watch(waiter) => self.events.watch(waiter),
+                _ => {}
+            }
+        }
+        if let Some(heartbeat) = &self.events.heartbeat {
+            heartbeat.finish();
+        }
+        let event = lock_core(&self.state).disconnect(reason);
+        self.events
+            .finish(
+                event,
+                (!shutdown_requested).then_some(&mut self.shutdown_rx),
+            )
+            .await;
+    }
+}
+
+/// Keep `store` in step with the client's session ticket until the loop
+/// ends, then write the last change, if any.
+#[cfg(feature = "tokio-runtime")]
+async fn persist_session_tickets(
+    store: Arc<dyn crate::SessionStore>,
+    mut tickets: tokio::sync::watch::Receiver<Option<crate::SessionTicket>>,
+    mut loop_ended: tokio::sync::watch::Receiver<()>,
+) {
+    loop {
+        let ended = tokio::select! {
+            changed = tickets.changed() => changed.is_err(),
+            _ = loop_ended.changed() => true,
+        };
+        if ended && !tickets.has_changed().unwrap_or(false) {
+            return;
+        }
+        let ticket = tickets.borrow_and_update().clone();
+        store_ticket(store.as_ref(), ticket.as_ref()).await;
+        if ended {
+            return;
+        }
+    }
+}
+
+/// Write `ticket` to `store`, or remove the stored one for `None`; a failure
+/// is logged.
+#[cfg(feature = "tokio-runtime")]
+async fn store_ticket(store: &dyn crate::SessionStore, ticket: Option<&crate::SessionTicket>) {
+    let result = match ticket.map(serde_json::to_vec) {
+        Some(Ok(ticket)) => store.set(crate::SESSION_TICKET_KEY, ticket).await,
+        Some(Err(error)) => Err(error.into()),
+        None => store.remove(crate::SESSION_TICKET_KEY).await,
+    };
+    if let Err(error) = result {
+        warn!("could not update the stored session ticket: {error}");
+    }
+}
+
+/// Serialize `command` and hand it to the transport. `Err` carries the
+/// `Disconnected` reason for a failed send; a message that fails to serialize
+/// is logged and skipped.
+#[cfg(feature = "tokio-runtime")]
+async fn send_command_frame(
+    transport: &mut (impl Transport + Send),
+    state: &Arc<Mutex<ClientCore>>,
+    command: ClientCommand,
+) -> std::result::Result<(), DisconnectReason> {
+    let kind = command.kind();
+    let frame = match command {
+        ClientCommand::Message(message) => match serde_json::to_string(&message) {
+            Ok(json) => TransportFrame::Text(json),
+            Err(error) => {
+                error!("failed to serialize ClientMessage: {error}");
+                return Ok(());
+            }
+        },
+        ClientCommand::Binary(payload) => TransportFrame::Binary(payload),
+    };
+    if let Err(error) = send_frame(transport, frame).await {
+        return Err(lock_core(state).send_failure_reason(&error));
+    }
+    lock_core(state).record_frame_sent(kind);
+    Ok(())
+}
+
+/// Apply one received frame to the core and emit its events. `Some` ends the
+/// session.
+#[cfg(feature = "tokio-runtime")]
+async fn apply_frame(
+    frame: DecodedFrame,
+    state: &Arc<Mutex<ClientCore>>,
+    event_tx: &EventFanout,
+    shutdown_rx: &mut tokio::sync::oneshot::Receiver<()>,
+    lobby_debounce: &mut LobbyDebounce,
+    self_removal_deadline: &mut Option<tokio::time::Instant>,
+) -> Option<LoopExit> {
+    let outcome = lock_core(state).apply_frame(frame);
+    for event in &outcome.events {
+        if let SignalFishEvent::Authenticated {
+            organization: Some(organization),
+            ..
+        } = event
+        {
+            tracing::Span::current().record("organization", organization.as_str());
+        }
+    }
+    if let Some(grace) = outcome.self_removal_grace {
+        *self_removal_deadline = Some(tokio::time::Instant::now() + grace);
+    }
+    let ready = outcome
+        .events
+        .into_iter()
+        .flat_map(|event| lobby_debounce.admit(event))
+        .flatten();
+    for event in ready {
+        if matches!(
+            emit_event_or_shutdown(event_tx, shutdown_rx, event).await,
+            EmitOutcome::ShutdownRequested
+        ) {
+            return Some(LoopExit::Shutdown);
+        }
+    }
+    outcome.disconnect.map(LoopExit::Disconnected)
+}
+
+/// Off-loop decoding per [`SignalFishConfig::decode_pipeline`]: a worker
+/// parses received frames on the blocking pool, one at a time, and hands them
+/// back in arrival order.
+///
+/// The worker never touches client state, so abandoning it is safe: a parse
+/// already running finishes on the blocking pool and is discarded.
+#[cfg(feature = "tokio-runtime")]
+struct DecodePipeline {
+    /// `None` once the transport has ended.
+    frames: Option<mpsc::Sender<TransportFrame>>,
+    decoded: mpsc::Receiver<DecodedFrame>,
+    worker: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl DecodePipeline {
+    /// Start a worker with up to `depth` frames queued on each side of it.
+    fn spawn(depth: usize, name: &str, decoder: FrameDecoder) -> Self {
+        let (frames, mut queued) = mpsc::channel::<TransportFrame>(depth.max(1));
+        let (done, decoded) = mpsc::channel(depth.max(1));
+        let worker = spawn_named(
+            &tokio::runtime::Handle::current(),
+            name,
+            async move {
+                while let Some(frame) = queued.recv().await {
+                    let Ok(frame) =
+                        tokio::task::spawn_blocking(move || decoder.decode(frame)).await
+                    else {
+                        return;
+                    };
+                    if done.send(frame).await.is_err() {
+                        return;
                     }
                 }
             }
+            .in_current_span(),
+        );
+        Self {
+            frames: Some(frames),
+            decoded,
+            worker,
         }
     }
-    debug!("transport loop exited");
+
+    /// Whether a received frame can be queued without waiting. The loop
+    /// stops reading the transport while the queue is full.
+    fn accepting(&self) -> bool {
+        self.frames
+            .as_ref()
+            .is_some_and(|frames| frames.capacity() > 0)
+    }
+
+    /// Queue `frame`; call only while [`accepting`](Self::accepting). A
+    /// stopped worker drops it and [`next`](Self::next) then ends the session.
+    fn push(&self, frame: TransportFrame) {
+        if let Some(frames) = &self.frames {
+            let _ = frames.try_send(frame);
+        }
+    }
+
+    /// The next decoded frame in arrival order; `None` once the pipeline is
+    /// closed and drained, or the worker stopped.
+    async fn next(pipeline: Option<&mut Self>) -> Option<DecodedFrame> {
+        match pipeline {
+            Some(pipeline) => pipeline.decoded.recv().await,
+            // Inline decoding: the branch is disabled and this pending
+            // future is never polled.
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Accept no more frames; the queued ones still come out of `next`.
+    fn close(&mut self) {
+        self.frames = None;
+    }
+
+    /// Abandon queued frames and wait for the worker to stop.
+    async fn stop(self) {
+        self.worker.abort();
+        let _ = self.worker.await;
+    }
+}
+
+/// Why the transport loop's session ended; every exit shares one epilogue
+/// that emits the terminal `Disconnected`.
+#[cfg(feature = "tokio-runtime")]
+enum LoopExit {
+    /// The client asked to shut down.
+    Shutdown,
+    /// The client asked to shut down, but the transport had already ended;
+    /// the connection's own `Disconnected` reason wins.
+    EndedBeforeShutdown(DisconnectReason),
+    /// The connection ended, with the `Disconnected` reason.
+    Disconnected(DisconnectReason),
+    /// The transport failed or the server closed it, with the
+    /// `Disconnected` reason; a reconnecting client dials again.
+    Lost(DisconnectReason),
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl LoopExit {
+    fn shutdown_requested(&self) -> bool {
+        matches!(self, Self::Shutdown | Self::EndedBeforeShutdown(_))
+    }
+}
+
+/// When shutdown fires, check without waiting whether the transport had
+/// already ended (server close or receive error) — `select!` picks among
+/// ready branches at random, so the shutdown branch can win a race the server
+/// started first. Returns that end's `Disconnected` reason; a frame that was
+/// ready instead is abandoned with the rest of the session.
+#[cfg(feature = "tokio-runtime")]
+async fn transport_ended(
+    transport: &mut (impl Transport + Send),
+    state: &Arc<Mutex<ClientCore>>,
+) -> Option<DisconnectReason> {
+    let ready = std::future::poll_fn(|cx| std::task::Poll::Ready(transport.poll_recv(cx))).await;
+    match ready {
+        std::task::Poll::Ready(None) => {
+            Some(lock_core(state).server_close_reason(transport.close_info()))
+        }
+        std::task::Poll::Ready(Some(Err(error))) => {
+            Some(lock_core(state).receive_failure_reason(&error))
+        }
+        std::task::Poll::Ready(Some(Ok(_))) | std::task::Poll::Pending => None,
+    }
 }
 
 /// Result of racing an event delivery against the shutdown signal.
@@ -1465,25 +5666,17 @@ enum EmitOutcome {
 /// `biased` polls the delivery arm first, so when both are ready the event is
 /// still delivered; only a genuinely blocked delivery (consumer not draining)
 /// lets shutdown win. On [`EmitOutcome::ShutdownRequested`] exactly the one
-/// in-flight event is abandoned — the caller must then run
-/// [`finish_core_shutdown`] and exit the loop **without polling `shutdown_rx`
-/// again** (a completed `oneshot::Receiver` panics if re-polled).
+/// in-flight event is abandoned (for the subscribers it had not reached yet)
+/// — the caller must then exit with [`LoopExit::Shutdown`] **without polling
+/// `shutdown_rx` again** (a completed `oneshot::Receiver`
+/// panics if re-polled).
 #[cfg(feature = "tokio-runtime")]
 async fn emit_event_or_shutdown(
-    event_tx: &mpsc::Sender<SignalFishEvent>,
+    event_tx: &EventFanout,
     shutdown_rx: &mut tokio::sync::oneshot::Receiver<()>,
     event: SignalFishEvent,
 ) -> EmitOutcome {
-    tokio::select! {
-        biased;
-        res = event_tx.send(event) => {
-            if res.is_err() {
-                debug!("event channel closed, receiver dropped");
-            }
-            EmitOutcome::Delivered
-        }
-        _ = &mut *shutdown_rx => EmitOutcome::ShutdownRequested,
-    }
+    event_tx.deliver(shutdown_rx, event).await
 }
 
 // ── Tests ───────────────────────────────────────────────────────────
@@ -1499,7 +5692,7 @@ async fn emit_event_or_shutdown(
 )]
 mod tests {
     use super::*;
-    use crate::protocol::{LobbyState, RateLimitInfo, RoomJoinedPayload};
+    use crate::protocol::{ClientMessage, LobbyState, RateLimitInfo, RoomJoinedPayload};
     use std::collections::VecDeque;
     use std::future::Future;
     use std::pin::Pin;
@@ -1859,6 +6052,7 @@ mod tests {
         assert_eq!(config.event_channel_capacity, 256);
         assert_eq!(config.command_channel_capacity, 1024);
         assert_eq!(config.shutdown_timeout, std::time::Duration::from_secs(1));
+        assert!(config.label.is_none());
     }
 
     #[tokio::test]
@@ -2103,6 +6297,12 @@ mod tests {
                 game_data_sent: 3,
                 game_data_received: 2,
                 messages_undecodable: 0,
+                blank_frames_skipped: 0,
+                multi_message_frames: 0,
+                paused_frames_skipped: 0,
+                cross_epoch_events_dropped: 0,
+                offline_commands_evicted: 0,
+                messages_expired: 0,
             }
         );
 
@@ -2335,6 +6535,7 @@ mod tests {
             min_protocol_version: Some(2),
             max_protocol_version: Some(3),
             transports: None,
+            limits: None,
         }))
         .unwrap()
     }
@@ -2598,6 +6799,133 @@ mod tests {
         assert!(!client.is_connected());
     }
 
+    /// Each client exposes its own loop's task id until shutdown reaps it.
+    /// Without `tokio_unstable` the label only names the tracing span, and the
+    /// client runs exactly as an unlabeled one.
+    #[tokio::test]
+    async fn task_id_identifies_each_transport_loop() {
+        let (mut first, mut first_events) = SignalFishClient::start(
+            MockTransport::new(vec![Some(Ok(authenticated_json()))]).0,
+            SignalFishConfig::new("mb_test").with_label("lobby-7"),
+        );
+        let (mut second, _second_events) = SignalFishClient::start(
+            MockTransport::new(vec![]).0,
+            SignalFishConfig::new("mb_test"),
+        );
+
+        let first_id = first.task_id().unwrap();
+        assert_ne!(Some(first_id), second.task_id());
+        assert!(matches!(
+            first_events.recv().await,
+            Some(SignalFishEvent::Connected)
+        ));
+        assert!(matches!(
+            first_events.recv().await,
+            Some(SignalFishEvent::Authenticated { .. })
+        ));
+
+        first.shutdown().await;
+        second.shutdown().await;
+        assert!(first.task_id().is_none());
+    }
+
+    /// With `--cfg tokio_unstable` and `task-names`, the loop task carries
+    /// the configured label (observed through tokio's spawn span).
+    #[cfg(all(tokio_unstable, feature = "task-names"))]
+    #[tokio::test]
+    async fn transport_loop_task_is_named_after_the_label() {
+        struct TaskNames(Arc<Mutex<Vec<String>>>);
+
+        struct NameVisitor<'a>(&'a Mutex<Vec<String>>);
+
+        impl tracing::field::Visit for NameVisitor<'_> {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "task.name" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            }
+        }
+
+        impl tracing::Subscriber for TaskNames {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                span.record(&mut NameVisitor(&self.0));
+                tracing::span::Id::from_u64(1)
+            }
+            fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+            fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+            fn event(&self, _: &tracing::Event<'_>) {}
+            fn enter(&self, _: &tracing::span::Id) {}
+            fn exit(&self, _: &tracing::span::Id) {}
+        }
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let guard = tracing::subscriber::set_default(TaskNames(Arc::clone(&names)));
+        let (mut client, _events) = SignalFishClient::start(
+            MockTransport::new(vec![]).0,
+            SignalFishConfig::new("mb_test").with_label("lobby-7"),
+        );
+        drop(guard);
+
+        assert!(names.lock().unwrap().iter().any(|name| name == "lobby-7"));
+        client.shutdown().await;
+    }
+
+    /// A server close followed by a hanging `close()` must not pin the loop:
+    /// nobody calls `shutdown()`, yet the stream still ends within the bound.
+    #[tokio::test]
+    async fn hanging_close_after_server_close_is_bounded() {
+        let (transport, close_called, dropped) =
+            HangingCloseTransport::with_incoming(vec![Some(Ok(authenticated_json())), None]);
+        let config = SignalFishConfig::new("mb_test")
+            .with_shutdown_timeout(std::time::Duration::from_millis(20));
+        let (client, mut events) = SignalFishClient::start(transport, config);
+
+        let drained = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            let mut last = None;
+            while let Some(event) = events.recv().await {
+                last = Some(event);
+            }
+            last
+        })
+        .await
+        .expect("event stream should close once the close timeout elapses");
+
+        assert!(matches!(
+            drained,
+            Some(SignalFishEvent::Disconnected { .. })
+        ));
+        assert!(close_called.load(Ordering::Acquire));
+        assert!(dropped.load(Ordering::Acquire));
+        assert!(!client.is_connected());
+    }
+
+    /// Dropping the handle with a transport whose `close()` hangs still ends
+    /// the stream with `Disconnected`.
+    #[tokio::test]
+    async fn dropping_client_with_hanging_close_ends_the_stream() {
+        let (transport, _close_called, dropped) = HangingCloseTransport::new();
+        let config = SignalFishConfig::new("mb_test")
+            .with_shutdown_timeout(std::time::Duration::from_millis(20));
+        let (client, mut events) = SignalFishClient::start(transport, config);
+        let event = events.recv().await.unwrap();
+        assert!(matches!(event, SignalFishEvent::Connected));
+
+        drop(client);
+
+        let next = tokio::time::timeout(std::time::Duration::from_secs(1), events.recv())
+            .await
+            .expect("Disconnected should follow the drop");
+        assert!(matches!(next, Some(SignalFishEvent::Disconnected { .. })));
+        let end = tokio::time::timeout(std::time::Duration::from_secs(1), events.recv())
+            .await
+            .expect("receiver should close after Disconnected");
+        assert!(end.is_none());
+        assert!(dropped.load(Ordering::Acquire));
+    }
+
     #[tokio::test]
     async fn join_room_params_default() {
         let params = JoinRoomParams::new("g", "p");
@@ -2644,7 +6972,10 @@ mod tests {
         let event = events.recv().await.unwrap();
         assert!(matches!(event, SignalFishEvent::Disconnected { .. }));
         if let SignalFishEvent::Disconnected { reason, .. } = event {
-            assert!(reason.unwrap().contains("boom"));
+            assert!(
+                matches!(&reason, DisconnectReason::TransportError { detail } if detail.contains("boom")),
+                "{reason:?}"
+            );
         }
 
         client.shutdown().await;
@@ -2817,13 +7148,262 @@ mod tests {
         let event = events.recv().await.unwrap();
         assert!(matches!(event, SignalFishEvent::Disconnected { .. }));
         if let SignalFishEvent::Disconnected { reason, .. } = event {
-            assert_eq!(reason.as_deref(), Some("client shut down"));
+            assert_eq!(reason, DisconnectReason::ClientShutdown);
         }
 
         // The transport should have been closed.
         assert!(closed.load(Ordering::Relaxed));
     }
 
+    #[tokio::test]
+    async fn shutdown_sends_commands_queued_before_it() {
+        let (transport, sent, closed) = MockTransport::new(vec![Some(Ok(authenticated_json()))]);
+
+        let config = SignalFishConfig::new("mb_test");
+        let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+        let _ = events.recv().await; // Connected
+        let _ = events.recv().await; // Authenticated
+        client.leave_room().unwrap();
+        client.shutdown().await;
+
+        {
+            let messages = sent.lock().unwrap();
+            let last: ClientMessage = serde_json::from_str(messages.last().unwrap()).unwrap();
+            assert!(matches!(last, ClientMessage::LeaveRoom));
+        }
+        assert!(closed.load(Ordering::Relaxed));
+        let event = events.recv().await.unwrap();
+        assert!(matches!(
+            event,
+            SignalFishEvent::Disconnected {
+                reason: DisconnectReason::ClientShutdown,
+                ..
+            }
+        ));
+    }
+
+    /// Replays scripted messages, answers a `LeaveRoom` or `LeaveSpectator`
+    /// with `answer` (or never, when it is `None`), and records what is sent.
+    struct LeaveAnsweringTransport {
+        incoming: VecDeque<String>,
+        answer: Option<String>,
+        sent: Arc<StdMutex<Vec<String>>>,
+        closed: Arc<AtomicBool>,
+        waker: Option<std::task::Waker>,
+    }
+
+    impl LeaveAnsweringTransport {
+        #[allow(clippy::type_complexity)]
+        fn new(
+            incoming: Vec<String>,
+            answer: Option<String>,
+        ) -> (Self, Arc<StdMutex<Vec<String>>>, Arc<AtomicBool>) {
+            let sent = Arc::new(StdMutex::new(Vec::new()));
+            let closed = Arc::new(AtomicBool::new(false));
+            let transport = Self {
+                incoming: VecDeque::from(incoming),
+                answer,
+                sent: Arc::clone(&sent),
+                closed: Arc::clone(&closed),
+                waker: None,
+            };
+            (transport, sent, closed)
+        }
+    }
+
+    impl Transport for LeaveAnsweringTransport {
+        fn poll_send(
+            &mut self,
+            _cx: &mut Context<'_>,
+            frame: &mut Option<TransportFrame>,
+        ) -> Poll<std::result::Result<(), SignalFishError>> {
+            if let Some(TransportFrame::Text(message)) = frame.take() {
+                let parsed: ClientMessage = serde_json::from_str(&message).unwrap();
+                if matches!(
+                    parsed,
+                    ClientMessage::LeaveRoom | ClientMessage::LeaveSpectator
+                ) {
+                    if let Some(answer) = self.answer.take() {
+                        self.incoming.push_back(answer);
+                        if let Some(waker) = self.waker.take() {
+                            waker.wake();
+                        }
+                    }
+                }
+                self.sent.lock().unwrap().push(message);
+            }
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_recv(
+            &mut self,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<std::result::Result<TransportFrame, SignalFishError>>> {
+            match self.incoming.pop_front() {
+                Some(message) => Poll::Ready(Some(Ok(TransportFrame::Text(message)))),
+                None => {
+                    self.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+
+        fn poll_close(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), SignalFishError>> {
+            self.closed.store(true, Ordering::Relaxed);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn sent_messages(sent: &Arc<StdMutex<Vec<String>>>) -> Vec<ClientMessage> {
+        sent.lock()
+            .unwrap()
+            .iter()
+            .map(|message| serde_json::from_str(message).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn shutdown_leaves_the_room_when_configured() {
+        let room_left = serde_json::to_string(&ServerMessage::RoomLeft).unwrap();
+        let (transport, sent, closed) = LeaveAnsweringTransport::new(
+            vec![authenticated_json(), room_joined_json()],
+            Some(room_left),
+        );
+
+        let config = SignalFishConfig::new("mb_test").with_leave_room_on_shutdown(true);
+        let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+        let _ = events.recv().await; // Connected
+        let _ = events.recv().await; // Authenticated
+        let _ = events.recv().await; // RoomJoined
+        client.shutdown().await;
+
+        assert!(matches!(
+            sent_messages(&sent).last(),
+            Some(ClientMessage::LeaveRoom)
+        ));
+        assert!(closed.load(Ordering::Relaxed));
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::RoomLeft)
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::Disconnected {
+                reason: DisconnectReason::ClientShutdown,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn shutdown_leaves_as_a_spectator_when_spectating() {
+        use crate::protocol::SpectatorJoinedPayload;
+
+        let spectator_joined = serde_json::to_string(&ServerMessage::SpectatorJoined(Box::new(
+            SpectatorJoinedPayload {
+                room_id: uuid::Uuid::from_u128(300),
+                room_code: "SPEC1".into(),
+                spectator_id: uuid::Uuid::from_u128(400),
+                game_name: "spec-game".into(),
+                current_players: vec![],
+                current_spectators: vec![],
+                lobby_state: LobbyState::Waiting,
+                reason: None,
+            },
+        )))
+        .unwrap();
+        let spectator_left = serde_json::to_string(&ServerMessage::SpectatorLeft {
+            room_id: Some(uuid::Uuid::from_u128(300)),
+            room_code: Some("SPEC1".into()),
+            reason: None,
+            current_spectators: vec![],
+        })
+        .unwrap();
+        let (transport, sent, _closed) = LeaveAnsweringTransport::new(
+            vec![authenticated_json(), spectator_joined],
+            Some(spectator_left),
+        );
+
+        let config = SignalFishConfig::new("mb_test").with_leave_room_on_shutdown(true);
+        let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+        let _ = events.recv().await; // Connected
+        let _ = events.recv().await; // Authenticated
+        let _ = events.recv().await; // SpectatorJoined
+        client.shutdown().await;
+
+        let messages = sent_messages(&sent);
+        assert!(matches!(
+            messages.last(),
+            Some(ClientMessage::LeaveSpectator)
+        ));
+        assert!(!messages
+            .iter()
+            .any(|message| matches!(message, ClientMessage::LeaveRoom)));
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::SpectatorLeft { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_no_leave_outside_a_room() {
+        let (transport, sent, closed) =
+            LeaveAnsweringTransport::new(vec![authenticated_json()], None);
+
+        let config = SignalFishConfig::new("mb_test").with_leave_room_on_shutdown(true);
+        let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+        let _ = events.recv().await; // Connected
+        let _ = events.recv().await; // Authenticated
+        client.shutdown().await;
+
+        assert!(matches!(
+            sent_messages(&sent)[..],
+            [ClientMessage::Authenticate { .. }]
+        ));
+        assert!(closed.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_closes_after_an_unanswered_leave() {
+        let (transport, sent, closed) =
+            LeaveAnsweringTransport::new(vec![authenticated_json(), room_joined_json()], None);
+
+        let config = SignalFishConfig::new("mb_test")
+            .with_leave_room_on_shutdown(true)
+            .with_shutdown_timeout(std::time::Duration::from_millis(200));
+        let (mut client, mut events) = SignalFishClient::start(transport, config);
+
+        let _ = events.recv().await; // Connected
+        let _ = events.recv().await; // Authenticated
+        let _ = events.recv().await; // RoomJoined
+        let started = tokio::time::Instant::now();
+        client.shutdown().await;
+
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+        assert!(matches!(
+            sent_messages(&sent).last(),
+            Some(ClientMessage::LeaveRoom)
+        ));
+        assert!(
+            closed.load(Ordering::Relaxed),
+            "the transport still closes gracefully"
+        );
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::Disconnected {
+                reason: DisconnectReason::ClientShutdown,
+                ..
+            })
+        ));
+    }
+
     #[tokio::test]
     async fn join_room_params_builder() {
         let params = JoinRoomParams::new("my-game", "Alice")
@@ -3045,12 +7625,10 @@ mod tests {
         client.shutdown().await;
     }
 
-    /// Validates the documented best-effort delivery guarantee: when `shutdown()`
-    /// times out and aborts the transport task, the `Disconnected` event may NOT
-    /// be delivered because the transport loop is forcibly cancelled before it can
-    /// emit the event. Both outcomes (event received or not) are acceptable.
+    /// When `shutdown()` times out and aborts the transport task, the client
+    /// emits the terminal `Disconnected` itself before the channel closes.
     #[tokio::test]
-    async fn shutdown_abort_may_skip_disconnected_event() {
+    async fn shutdown_abort_still_delivers_disconnected() {
         let (transport, _close_called, _dropped) = HangingCloseTransport::new();
         let config = SignalFishConfig::new("mb_test")
             .with_shutdown_timeout(std::time::Duration::from_millis(1));
@@ -3063,26 +7641,13 @@ mod tests {
         // Shutdown will timeout (close() hangs) and abort the transport task.
         client.shutdown().await;
 
-        // The transport loop was aborted, so `emit_disconnected` may never have
-        // executed. Try to receive with a short timeout — either outcome is valid.
-        let result =
-            tokio::time::timeout(std::time::Duration::from_millis(50), events.recv()).await;
-
-        match result {
-            Ok(Some(SignalFishEvent::Disconnected { .. })) => {
-                // Disconnected was delivered before the abort took effect — acceptable.
-            }
-            Ok(None) => {
-                // Channel closed without a Disconnected event — acceptable.
-            }
-            Err(_) => {
-                // Timed out waiting; no Disconnected event was delivered — acceptable.
-            }
-            Ok(Some(other)) => {
-                panic!("unexpected event after shutdown abort: {other:?}");
+        match events.recv().await {
+            Some(SignalFishEvent::Disconnected { reason, .. }) => {
+                assert_eq!(reason, DisconnectReason::ClientShutdown);
             }
+            other => panic!("expected Disconnected after abort, got {other:?}"),
         }
-
+        assert!(events.recv().await.is_none());
         assert!(!client.is_connected());
     }
 
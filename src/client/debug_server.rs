@@ -0,0 +1,328 @@
+//! Read-only debug endpoint for a running [`SignalFishClient`].
+//!
+//! [`SignalFishClient::serve_debug`] listens on a TCP address and speaks
+//! newline-delimited JSON: each request is one line holding an object with
+//! the server's `token` and a `route`, and each answer is one line holding
+//! either `{"route": ..., "data": ...}` or `{"error": ...}`. A connection may
+//! send any number of requests.
+//!
+//! | Route | Data |
+//! |-------|------|
+//! | `snapshot` | [`SignalFishClient::snapshot`] and the session state |
+//! | `roster` | [`SignalFishClient::room_snapshot`], or `in_room: false` |
+//! | `stats` | [`SignalFishClient::stats`] and [`SignalFishClient::outgoing_breakdown`] |
+//! | `history` | [`SignalFishClient::state_history`] and [`SignalFishClient::room_history`] |
+//! | `traffic` | [`SignalFishClient::activity`], [`SignalFishClient::latency_stats`], and the game data counts |
+//! | `config` | [`SignalFishClient::effective_config`] |
+//!
+//! Nothing can be changed through it. The reconnection token is reported only
+//! as present or absent.
+//!
+//! ```text
+//! $ nc 127.0.0.1 7777
+//! {"token":"6f1c…","route":"stats"}
+//! {"route":"stats","data":{"game_data_sent":12,…}}
+//! ```
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tracing::{debug, info};
+
+use super::{lock_core, spawn_named, SignalFishClient};
+use crate::client_core::ClientCore;
+use crate::error::{Result, SignalFishError};
+
+/// Longest request line read, in bytes; a longer one ends the connection.
+const MAX_REQUEST_LINE: u64 = 4096;
+
+/// Pause after a failed `accept`, so a full descriptor table does not spin
+/// the task.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The routes a request may name.
+const ROUTES: [&str; 6] = [
+    "snapshot", "roster", "stats", "history", "traffic", "config",
+];
+
+/// A running debug endpoint from [`SignalFishClient::serve_debug`].
+///
+/// It stops on its own when the client's transport loop ends. Dropping it
+/// stops it at once.
+#[derive(Debug)]
+pub struct DebugServer {
+    local_addr: SocketAddr,
+    token: Arc<str>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl DebugServer {
+    /// The address the endpoint is listening on, with the port the system
+    /// chose if the requested one was `0`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The token every request must carry. It is never logged, so hand it
+    /// to whoever will query the endpoint.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl Drop for DebugServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl SignalFishClient {
+    /// Serve this client's state read-only on `addr`, for live debugging.
+    ///
+    /// A fresh random token is generated for the returned
+    /// [`DebugServer::token`]; requests without it are refused. See the
+    /// [`debug_server`](crate::client::debug_server) module for the wire
+    /// format and routes. Bind to a loopback address: the traffic is not
+    /// encrypted.
+    ///
+    /// The endpoint, and every connection to it, closes when the transport
+    /// loop ends, whether by [`shutdown`](Self::shutdown), the connection
+    /// ending, or the handle being dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport loop has
+    /// already ended, or [`SignalFishError::Io`] if `addr` cannot be bound.
+    pub async fn serve_debug(&self, addr: impl ToSocketAddrs) -> Result<DebugServer> {
+        if self.loop_ended.has_changed().is_err() {
+            return Err(SignalFishError::NotConnected);
+        }
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let token: Arc<str> = uuid::Uuid::new_v4().simple().to_string().into();
+        info!(%local_addr, "debug server listening");
+
+        let served = Served {
+            state: Arc::clone(&self.state),
+            token: Arc::clone(&token),
+        };
+        let mut loop_ended = self.loop_ended.clone();
+        let name = format!("{}/debug", self.terminal.label);
+        let task = spawn_named(&self.terminal.runtime, &name, async move {
+            // Dropping the set at the end aborts the open connections.
+            let mut connections = tokio::task::JoinSet::new();
+            loop {
+                tokio::select! {
+                    _ = loop_ended.changed() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, peer)) => {
+                            debug!(%peer, "debug connection");
+                            connections.spawn(served.clone().connection(stream));
+                        }
+                        Err(error) => {
+                            debug!("debug server accept failed: {error}");
+                            tokio::time::sleep(ACCEPT_BACKOFF).await;
+                        }
+                    },
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                }
+            }
+            debug!("debug server stopped with the client");
+        });
+        Ok(DebugServer {
+            local_addr,
+            token,
+            task,
+        })
+    }
+}
+
+/// What each debug connection reads from.
+#[derive(Clone)]
+struct Served {
+    state: Arc<Mutex<ClientCore>>,
+    token: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct Request {
+    token: String,
+    route: String,
+}
+
+impl Served {
+    /// Answer request lines until the peer hangs up or sends a line longer
+    /// than [`MAX_REQUEST_LINE`].
+    async fn connection(self, stream: TcpStream) {
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = (&mut reader)
+                .take(MAX_REQUEST_LINE + 1)
+                .read_until(b'\n', &mut line)
+                .await;
+            let answer = match read {
+                Ok(0) | Err(_) => break,
+                Ok(_) if !line.ends_with(b"\n") && line.len() as u64 > MAX_REQUEST_LINE => {
+                    let answer = json!({ "error": "request line too long" });
+                    let _ = write_line(&mut write, &answer).await;
+                    break;
+                }
+                Ok(_) if line.trim_ascii().is_empty() => continue,
+                Ok(_) => self.answer(&line),
+            };
+            if write_line(&mut write, &answer).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    fn answer(&self, line: &[u8]) -> Value {
+        let request: Request = match serde_json::from_slice(line) {
+            Ok(request) => request,
+            Err(error) => return json!({ "error": format!("malformed request: {error}") }),
+        };
+        if !same_token(request.token.as_bytes(), self.token.as_bytes()) {
+            return json!({ "error": "unauthorized" });
+        }
+        match route(&request.route, &lock_core(&self.state)) {
+            Some(data) => json!({ "route": request.route, "data": data }),
+            None => json!({
+                "error": format!("unknown route {:?}", request.route),
+                "routes": ROUTES,
+            }),
+        }
+    }
+}
+
+async fn write_line(
+    write: &mut tokio::net::tcp::OwnedWriteHalf,
+    answer: &Value,
+) -> std::io::Result<()> {
+    let mut line = answer.to_string();
+    line.push('\n');
+    write.write_all(line.as_bytes()).await
+}
+
+/// Compare tokens without stopping at the first difference.
+fn same_token(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// The data of `route`, read under the one core lock.
+fn route(route: &str, core: &ClientCore) -> Option<Value> {
+    let data = match route {
+        "snapshot" => {
+            let snapshot = core.snapshot();
+            json!({
+                "session_state": format!("{:?}", core.session_state()),
+                "connected": snapshot.connected,
+                "authenticated": snapshot.authenticated,
+                "negotiated_protocol_version": snapshot.negotiated_protocol_version,
+                "player_id": snapshot.player_id,
+                "room_id": snapshot.room_id,
+                "room_code": snapshot.room_code,
+                "has_reconnection_token": snapshot.reconnection_token.is_some(),
+                "quarantined": snapshot.quarantined,
+                "session_epoch": snapshot.session_epoch,
+                "handshake_failed": snapshot.handshake_failed,
+                "draining": snapshot.draining,
+                "last_rtt_ms": snapshot.last_rtt.map(millis),
+            })
+        }
+        "roster" => match core.room_snapshot() {
+            Some(room) if room.is_in_room() => json!({
+                "in_room": true,
+                "room_id": room.room_id,
+                "room_code": room.room_code,
+                "game_name": room.game_name,
+                "player_id": room.player_id,
+                "max_players": room.max_players,
+                "lobby_state": room.lobby_state,
+                "authority": room.authority,
+                "is_authority": room.is_authority,
+                "players": room.players,
+                "spectators": room.spectators,
+            }),
+            _ => json!({ "in_room": false }),
+        },
+        "stats" => {
+            let stats = core.stats();
+            let outgoing = core.outgoing_breakdown();
+            let by_kind = |counts: &std::collections::BTreeMap<_, u64>| {
+                counts
+                    .iter()
+                    .map(|(kind, count): (&crate::protocol::ClientMessageKind, _)| {
+                        (kind.as_str().to_owned(), Value::from(*count))
+                    })
+                    .collect::<Map<_, _>>()
+            };
+            json!({
+                "game_data_sent": stats.game_data_sent,
+                "game_data_received": stats.game_data_received,
+                "messages_undecodable": stats.messages_undecodable,
+                "blank_frames_skipped": stats.blank_frames_skipped,
+                "multi_message_frames": stats.multi_message_frames,
+                "paused_frames_skipped": stats.paused_frames_skipped,
+                "cross_epoch_events_dropped": stats.cross_epoch_events_dropped,
+                "offline_commands_evicted": stats.offline_commands_evicted,
+                "messages_expired": stats.messages_expired,
+                "enqueued": by_kind(&outgoing.enqueued),
+                "sent": by_kind(&outgoing.sent),
+            })
+        }
+        "history" => {
+            let transitions: Vec<Value> = core
+                .state_history()
+                .iter()
+                .map(|transition| {
+                    json!({
+                        "from": format!("{:?}", transition.from),
+                        "to": format!("{:?}", transition.to),
+                        "trigger": format!("{:?}", transition.trigger),
+                    })
+                })
+                .collect();
+            json!({ "transitions": transitions, "rooms": core.room_history() })
+        }
+        "traffic" => {
+            let activity = core.activity();
+            let stats = core.stats();
+            json!({
+                "since_last_send_ms": activity.since_last_send.map(millis),
+                "since_last_recv_ms": activity.since_last_recv.map(millis),
+                "latency": core.latency_stats().map(|latency| json!({
+                    "samples": latency.samples,
+                    "last_ms": millis(latency.last),
+                    "min_ms": millis(latency.min),
+                    "p50_ms": millis(latency.p50),
+                    "p95_ms": millis(latency.p95),
+                    "max_ms": millis(latency.max),
+                    "jitter_ms": millis(latency.jitter),
+                })),
+                "game_data_sent": stats.game_data_sent,
+                "game_data_received": stats.game_data_received,
+            })
+        }
+        "config" => serde_json::to_value(core.effective_config()).unwrap_or(Value::Null),
+        _ => return None,
+    };
+    Some(data)
+}
+
+/// A duration as fractional milliseconds, the unit every `_ms` field uses.
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -74,6 +74,12 @@ pub trait SignalFishClientApi {
         self.snapshot().authenticated
     }
 
+    /// Whether the connection ended before the initial `Authenticate` could
+    /// be sent.
+    fn handshake_failed(&self) -> bool {
+        self.snapshot().handshake_failed
+    }
+
     /// Negotiated v3-or-newer protocol version.
     fn negotiated_protocol_version(&self) -> Option<u16> {
         self.snapshot().negotiated_protocol_version
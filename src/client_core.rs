@@ -7,28 +7,134 @@
 use crate::accountability::{self, DeliveryAccountability, GameDataDisposition};
 use crate::client::{
     bounded_binary_preview, decode_binary_server_message, ClientSnapshot, ClientStats,
-    GameDataDelivery, JoinRoomParams, ProtocolViolationPolicy, SignalFishConfig,
+    GameDataDelivery, JoinRoomParams, OrganizationMismatchPolicy, OutgoingBreakdown,
+    ProtocolViolationPolicy, SelfRemovalPolicy, SessionInconsistencyPolicy, SignalFishConfig,
 };
-use crate::event::{ProtocolViolationKind, ServerErrorInfo, SignalFishEvent};
+use crate::effective_config::EffectiveConfig;
+#[cfg(feature = "tokio-runtime")]
+use crate::event::AuthorityGrant;
+use crate::event::{
+    AuthenticatedInfo, DisconnectReason, ProtocolViolationKind, RoomRef, ServerErrorInfo,
+    SignalFishEvent,
+};
+use crate::latency::{LatencyStats, LatencyTracker};
 use crate::protocol::{
-    ClientMessage, ConnectionInfo, DeliveryClass, GameDataEncoding, PlayerId, RoomId,
-    ServerMessage, TransportKind,
+    lenient, ClientMessage, ClientMessageKind, ConnectionInfo, DeliveryClass, GameDataEncoding,
+    LobbyState, PlayerId, PlayerInfo, RateLimitInfo, RoomId, ServerLimits, ServerMessage,
+    TransportKind,
 };
+#[cfg(feature = "tokio-runtime")]
+use crate::reconnect::ResumePolicy;
+use crate::room_diff::RoomSnapshot;
+use crate::room_history::{RoomHistory, RoomLeaveReason, RoomSessionRecord};
+use crate::session::{SessionState, SessionStateMachine, SessionTrigger, StateTransition};
+#[cfg(feature = "tokio-runtime")]
+use crate::session_store::SessionTicket;
 use crate::signal::PeerSignal;
+use crate::time::Instant;
+use crate::time_sync::{unix_time_ms, TimeSyncResult, TimeSyncSampler};
 use crate::transport::TransportFrame;
+use std::time::Duration;
+
+/// A text frame parsed as a `ServerMessage`, or the text and the parse error.
+/// A decoded message with the `FieldOutOfRange` warnings its decode raised,
+/// or the unparsed text and why it failed.
+type ParsedText =
+    std::result::Result<(ServerMessage, Vec<SignalFishEvent>), (String, serde_json::Error)>;
+
+/// A received frame after the part of decoding that needs no client state,
+/// ready for [`ClientCore::apply_frame`].
+pub(crate) enum DecodedFrame {
+    /// Each message of a text frame in order, ending at the first that
+    /// failed to parse.
+    Text(Vec<ParsedText>),
+    /// Binary frames decode against the negotiated protocol, so they pass
+    /// through untouched.
+    Binary(Vec<u8>),
+}
+
+/// The stateless half of frame decoding, detached from the core so a driver
+/// can run it elsewhere.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameDecoder {
+    strict_message_framing: bool,
+    strict_payloads: bool,
+}
+
+impl FrameDecoder {
+    pub(crate) fn decode(self, frame: TransportFrame) -> DecodedFrame {
+        match frame {
+            TransportFrame::Text(text) => DecodedFrame::Text(self.parse_text(text)),
+            TransportFrame::Binary(bytes) => DecodedFrame::Binary(bytes),
+        }
+    }
+
+    /// Parse the messages in `text`. Unless framing is strict, top-level
+    /// messages concatenated by a proxy are split; parsing stops at the first
+    /// failure, which carries the rest of the frame.
+    fn parse_text(self, text: String) -> Vec<ParsedText> {
+        if self.strict_message_framing || text.trim().is_empty() {
+            let (message, adjusted) = lenient::collecting(|| serde_json::from_str(&text));
+            return vec![match message {
+                Ok(message) => Ok((message, adjusted)),
+                Err(error) => self.salvage(text, error),
+            }];
+        }
+        let mut parsed = Vec::with_capacity(1);
+        let mut stream = serde_json::Deserializer::from_str(&text).into_iter::<ServerMessage>();
+        loop {
+            let start = stream.byte_offset();
+            let (next, adjusted) = lenient::collecting(|| stream.next());
+            match next {
+                None => break,
+                Some(Ok(message)) => parsed.push(Ok((message, adjusted))),
+                Some(Err(error)) => {
+                    let rest = text.get(start..).unwrap_or_default().to_owned();
+                    parsed.push(self.salvage(rest, error));
+                    break;
+                }
+            }
+        }
+        parsed
+    }
+
+    /// Recover the room payload in `text` that `error` failed, unless
+    /// payloads are strict. Only a message that is the whole of `text` is
+    /// salvaged.
+    fn salvage(self, text: String, error: serde_json::Error) -> ParsedText {
+        if self.strict_payloads {
+            return Err((text, error));
+        }
+        crate::protocol::salvage::salvage(&text).ok_or((text, error))
+    }
+}
 
 /// Result of processing one physical server frame.
 pub(crate) struct FrameOutcome {
     pub(crate) events: Vec<SignalFishEvent>,
-    pub(crate) disconnect: bool,
+    /// Why the driver must close the connection, if it must.
+    pub(crate) disconnect: Option<DisconnectReason>,
+    /// A `PlayerLeft` naming this client started the self-removal grace
+    /// period; the driver calls [`ClientCore::expire_self_removal`] once it
+    /// elapses.
+    pub(crate) self_removal_grace: Option<Duration>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum CoreCommand {
     Message(ClientMessage),
     Binary(Vec<u8>),
 }
 
+impl CoreCommand {
+    pub(crate) fn kind(&self) -> ClientMessageKind {
+        match self {
+            Self::Message(message) => message.kind(),
+            Self::Binary(_) => ClientMessageKind::GameDataBinary,
+        }
+    }
+}
+
 pub(crate) enum ClientOperation {
     JoinRoom(JoinRoomParams),
     LeaveRoom,
@@ -45,27 +151,226 @@ pub(crate) enum ClientOperation {
     Signal(PlayerId, PeerSignal),
     RawSignal(PlayerId, serde_json::Value),
     TransportStatus(TransportKind, bool),
+    TimeSync,
 }
 
 impl FrameOutcome {
     fn new() -> Self {
         Self {
             events: Vec::new(),
-            disconnect: false,
+            disconnect: None,
+            self_removal_grace: None,
         }
     }
 }
 
+/// The step a `promote_to_player()` caller is waiting on.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) enum PromotionWaiter {
+    /// `LeaveSpectator` is out; resolved by `SpectatorLeft`.
+    Leave(tokio::sync::oneshot::Sender<crate::error::Result<()>>),
+    /// `JoinRoom` is out; resolved by `RoomJoined` or `RoomJoinFailed`.
+    Join(JoinWaiter),
+}
+
+/// Resolved by the `RoomJoined`, `RoomJoinFailed`, or `Error` answering a
+/// `JoinRoom`.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type JoinWaiter =
+    tokio::sync::oneshot::Sender<crate::error::Result<crate::protocol::RoomJoinedPayload>>;
+
+/// Resolved by the `Authenticated` or `AuthenticationError` answering
+/// `Authenticate`.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type AuthWaiter = tokio::sync::oneshot::Sender<crate::error::Result<AuthenticatedInfo>>;
+
+/// Resolved by the `RoomLeft` (or `NOT_IN_ROOM` error) answering a
+/// `LeaveRoom`.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type LeaveWaiter = tokio::sync::oneshot::Sender<()>;
+
+/// Resolved by the `Reconnected` or `ReconnectionFailed` answering a
+/// `Reconnect`.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type ReconnectWaiter =
+    tokio::sync::oneshot::Sender<crate::error::Result<crate::protocol::ReconnectedPayload>>;
+
+/// Resolved by the `AuthorityResponse` answering an `AuthorityRequest`.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type AuthorityWaiter = tokio::sync::oneshot::Sender<AuthorityGrant>;
+
+/// Resolved with the round trip of the `Ping` it was armed for.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) type PingWaiter = tokio::sync::oneshot::Sender<std::time::Duration>;
+
+/// Which registry an awaitable method's waiter sits in, for
+/// [`ClientCore::forget_abandoned`].
+#[cfg(feature = "tokio-runtime")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WaiterKind {
+    Join,
+    Leave,
+    Authenticated,
+    Authority,
+    Reconnect,
+    TimeSync,
+    Promotion,
+    Ping,
+}
+
 /// Shared protocol state and behavior used by both public client drivers.
 pub(crate) struct ClientCore {
+    session: SessionStateMachine,
     snapshot: ClientSnapshot,
     protocol_info_seen: bool,
     mesh_enabled: bool,
     game_data_encoding: GameDataEncoding,
     stats: ClientStats,
+    outgoing: OutgoingBreakdown,
     last_server_error: Option<ServerErrorInfo>,
     violation_policy: ProtocolViolationPolicy,
+    clear_session_on_auth_failure: bool,
+    reemit_session_events: bool,
+    /// The [`ClientSnapshot::session_epoch`] whose `Authenticated` has been
+    /// processed, so a repeat in the same epoch can be suppressed.
+    authenticated_epoch: Option<u64>,
+    strict_blank_frames: bool,
+    forward_unknown_messages: bool,
+    decoder: FrameDecoder,
+    suppress_announcements: bool,
+    legacy_announcement_sender: Option<PlayerId>,
+    health_thresholds: crate::HealthThresholds,
+    drop_cross_epoch_events: bool,
+    /// Advances on every room transition (join, reconnect, leave, removal),
+    /// so events can be traced to the room they were processed in.
+    room_epoch: u64,
+    /// Time source for activity tracking; the async driver swaps in Tokio's
+    /// clock so paused-time tests observe it.
+    clock: fn() -> Instant,
+    started: Instant,
+    last_recv: Option<Instant>,
+    last_sent: Option<Instant>,
+    /// Room code of the most recent `JoinRoom` still awaiting an answer;
+    /// `Some(None)` for a quick-match join.
+    pending_join: Option<Option<String>>,
+    error_code_formatter: Option<crate::ErrorCodeFormatter>,
+    decode_failure_hook: Option<crate::DecodeFailureHook>,
     accountability: DeliveryAccountability,
+    self_removal_policy: SelfRemovalPolicy,
+    /// A `PlayerLeft` naming this client is waiting for `RoomLeft`.
+    self_removal_pending: bool,
+    /// The room was cleared without `RoomLeft`; a late one is not re-emitted.
+    suppress_late_room_left: bool,
+    session_inconsistency_policy: SessionInconsistencyPolicy,
+    expected_organization: Option<String>,
+    organization_mismatch_policy: OrganizationMismatchPolicy,
+    /// Room named by the most recent `Reconnect` still awaiting an answer,
+    /// and when it was queued.
+    pending_reconnect: Option<(RoomId, Instant)>,
+    /// [`SignalFishConfig::reconnect_answer_timeout`].
+    reconnect_answer_timeout: std::time::Duration,
+    /// The transport has accepted at least one outbound frame.
+    frame_sent: bool,
+    /// A reconnecting client lost its transport and has not ended.
+    outage: bool,
+    /// [`SignalFishConfig::offline_queue_capacity`].
+    offline_queue_capacity: usize,
+    /// [`SignalFishConfig::message_deadlines`].
+    #[cfg(feature = "tokio-runtime")]
+    message_deadlines: std::collections::BTreeMap<ClientMessageKind, std::time::Duration>,
+    /// Code of the `AuthenticationError` the server answered this
+    /// connection with; redialing would only be rejected again.
+    authentication_rejected: Option<crate::ErrorCode>,
+    /// [`SignalFishConfig::resume_policy`].
+    #[cfg(feature = "tokio-runtime")]
+    resume_policy: ResumePolicy,
+    /// Player, room, and reconnection token of the room a reconnecting
+    /// client held when its transport dropped; kept until `Reconnected`,
+    /// `ReconnectionFailed`, or `RoomJoined` settles it.
+    #[cfg(feature = "tokio-runtime")]
+    resume_ticket: Option<(PlayerId, RoomId, String)>,
+    /// The session epoch was kept across an outage for the room in
+    /// `resume_ticket`; giving up on that room ends the session.
+    #[cfg(feature = "tokio-runtime")]
+    resume_held: bool,
+    /// The session epoch whose `Connected` has been emitted.
+    #[cfg(feature = "tokio-runtime")]
+    connected_epoch: Option<u64>,
+    /// Unix-epoch milliseconds at `started`; `clock` advances it, so
+    /// time-sync stamps follow paused test time.
+    started_unix_ms: u64,
+    time_sync: TimeSyncSampler,
+    latency: LatencyTracker,
+    room_history: RoomHistory,
+    /// Whether a `LeaveRoom` or `LeaveSpectator` went out since the room was
+    /// entered, so its `RoomLeft` ends the session as a voluntary leave.
+    leave_requested: bool,
+    /// `sync_time()` callers awaiting the response to their request, keyed by
+    /// its `client_sent_at_ms`.
+    #[cfg(feature = "tokio-runtime")]
+    time_sync_waiters: Vec<(u64, tokio::sync::oneshot::Sender<TimeSyncResult>)>,
+    /// Game of the room this client spectates, for `promote_to_player()`.
+    #[cfg(feature = "tokio-runtime")]
+    spectated_game: Option<String>,
+    #[cfg(feature = "tokio-runtime")]
+    promotion: Option<PromotionWaiter>,
+    /// `join_room_and_wait()` caller awaiting the answer to its `JoinRoom`.
+    #[cfg(feature = "tokio-runtime")]
+    join_waiter: Option<JoinWaiter>,
+    /// `wait_authenticated()` callers awaiting the server's answer to
+    /// `Authenticate`.
+    #[cfg(feature = "tokio-runtime")]
+    auth_waiters: Vec<AuthWaiter>,
+    /// `leave_room_and_wait()` callers awaiting confirmation of the leave.
+    #[cfg(feature = "tokio-runtime")]
+    leave_waiters: Vec<LeaveWaiter>,
+    /// `reconnect_and_wait()` callers awaiting the answer to the pending
+    /// `Reconnect`.
+    #[cfg(feature = "tokio-runtime")]
+    reconnect_waiters: Vec<ReconnectWaiter>,
+    /// One entry per queued `AuthorityRequest`, oldest first, holding the
+    /// `request_authority_and_wait()` caller awaiting its answer, if any.
+    /// The server answers in order and the response carries no request id.
+    #[cfg(feature = "tokio-runtime")]
+    authority_requests: std::collections::VecDeque<Option<AuthorityWaiter>>,
+    /// What the latest `AuthorityRequest` asked for; a granted
+    /// `AuthorityResponse` applies it, since the response does not say.
+    authority_asked: bool,
+    /// `ping_rtt()` caller for the next `Ping` the transport loop sends.
+    #[cfg(feature = "tokio-runtime")]
+    armed_ping: Option<PingWaiter>,
+    /// `Ping`s sent and `Pong`s received on this transport. The server
+    /// answers in order, so the n-th `Pong` answers the n-th `Ping`.
+    #[cfg(feature = "tokio-runtime")]
+    pings_sent: u64,
+    #[cfg(feature = "tokio-runtime")]
+    pongs_received: u64,
+    /// `ping_rtt()` callers, oldest first, with the number and send time of
+    /// the `Ping` each awaits the answer to.
+    #[cfg(feature = "tokio-runtime")]
+    ping_waiters: std::collections::VecDeque<(u64, Instant, PingWaiter)>,
+    /// Default, configured and runtime layers of
+    /// [`effective_config`](Self::effective_config).
+    configured: EffectiveConfig,
+    /// What the latest `Authenticated` confirmed.
+    authenticated: Option<AuthenticatedInfo>,
+    /// The current room, kept whole so `room_snapshot()` reads it under one
+    /// lock; the default snapshot outside a room.
+    room: RoomSnapshot,
+    /// Publishes every `room.lobby_state` change to `lobby_state_watch()`.
+    #[cfg(feature = "tokio-runtime")]
+    lobby_watch: tokio::sync::watch::Sender<LobbyState>,
+    /// The ticket for [`SignalFishConfig::session_store`], republished when
+    /// the room it names changes.
+    #[cfg(feature = "tokio-runtime")]
+    ticket_watch: tokio::sync::watch::Sender<Option<SessionTicket>>,
+    /// Caps from the session's `ProtocolInfo`, when the server sends them.
+    server_limits: Option<ServerLimits>,
+    /// The player-name rules the server advertised in `ProtocolInfo`.
+    player_name_rules: Option<crate::protocol::PlayerNameRulesPayload>,
+    /// Why the configuration forbids sending `Authenticate`; the driver ends
+    /// the connection before its first send.
+    handshake_refusal: Option<crate::InvalidSdkVersion>,
 }
 
 impl ClientCore {
@@ -81,40 +386,707 @@ impl ClientCore {
         })
     }
 
-    pub(crate) fn new(
-        game_data_encoding: GameDataEncoding,
-        violation_policy: ProtocolViolationPolicy,
-        mesh_enabled: bool,
-    ) -> Self {
+    /// Check `sdk_version` against SemVer: a warning under
+    /// [`SdkVersionPolicy::Warn`](crate::SdkVersionPolicy::Warn), the error
+    /// that stops `Authenticate` under `Reject`.
+    fn refuse_sdk_version(config: &SignalFishConfig) -> Option<crate::InvalidSdkVersion> {
+        let error = crate::SdkVersion::parse(config.sdk_version.as_deref()?).err()?;
+        match config.sdk_version_policy {
+            crate::SdkVersionPolicy::Warn => {
+                tracing::warn!("sending sdk_version as configured: {error}");
+                None
+            }
+            crate::SdkVersionPolicy::Reject => Some(error),
+        }
+    }
+
+    pub(crate) fn new(config: &SignalFishConfig) -> Self {
+        let mesh_enabled = config
+            .supported_transports
+            .as_ref()
+            .is_some_and(|transports| transports.contains(&TransportKind::WebRtc));
         Self {
-            snapshot: ClientSnapshot {
-                connected: true,
-                ..ClientSnapshot::default()
-            },
+            session: SessionStateMachine::connected(),
+            snapshot: ClientSnapshot::default(),
             protocol_info_seen: false,
             mesh_enabled,
-            game_data_encoding,
+            game_data_encoding: config.game_data_format.unwrap_or_default(),
             stats: ClientStats::default(),
+            outgoing: OutgoingBreakdown::default(),
             last_server_error: None,
-            violation_policy,
+            violation_policy: config.protocol_violation_policy,
+            clear_session_on_auth_failure: config.clear_session_on_auth_failure,
+            reemit_session_events: config.reemit_session_events,
+            authenticated_epoch: None,
+            strict_blank_frames: config.strict_blank_frames,
+            forward_unknown_messages: config.forward_unknown_messages,
+            decoder: FrameDecoder {
+                strict_message_framing: config.strict_message_framing,
+                strict_payloads: config.strict_payloads,
+            },
+            suppress_announcements: config.suppress_announcements,
+            legacy_announcement_sender: config.legacy_announcement_sender,
+            health_thresholds: config.health_thresholds,
+            drop_cross_epoch_events: config.drop_cross_epoch_events,
+            room_epoch: 0,
+            clock: Instant::now,
+            started: Instant::now(),
+            last_recv: None,
+            last_sent: None,
+            pending_join: None,
+            error_code_formatter: config.error_code_formatter.clone(),
+            decode_failure_hook: config.decode_failure_hook.clone(),
             accountability: DeliveryAccountability::new(false),
+            self_removal_policy: config.self_removal_policy,
+            self_removal_pending: false,
+            suppress_late_room_left: false,
+            session_inconsistency_policy: config.session_inconsistency_policy,
+            expected_organization: config.expected_organization.clone(),
+            organization_mismatch_policy: config.organization_mismatch_policy,
+            pending_reconnect: None,
+            reconnect_answer_timeout: config.reconnect_answer_timeout,
+            frame_sent: false,
+            outage: false,
+            offline_queue_capacity: config.offline_queue_capacity,
+            #[cfg(feature = "tokio-runtime")]
+            message_deadlines: config.message_deadlines.clone(),
+            authentication_rejected: None,
+            #[cfg(feature = "tokio-runtime")]
+            resume_policy: config.resume_policy,
+            #[cfg(feature = "tokio-runtime")]
+            resume_ticket: None,
+            #[cfg(feature = "tokio-runtime")]
+            resume_held: false,
+            #[cfg(feature = "tokio-runtime")]
+            connected_epoch: None,
+            started_unix_ms: unix_time_ms(),
+            time_sync: TimeSyncSampler::new(config.time_sync_samples),
+            latency: LatencyTracker::new(),
+            room_history: RoomHistory::new(config.room_history.unwrap_or(0)),
+            leave_requested: false,
+            #[cfg(feature = "tokio-runtime")]
+            time_sync_waiters: Vec::new(),
+            #[cfg(feature = "tokio-runtime")]
+            spectated_game: None,
+            #[cfg(feature = "tokio-runtime")]
+            promotion: None,
+            #[cfg(feature = "tokio-runtime")]
+            join_waiter: None,
+            #[cfg(feature = "tokio-runtime")]
+            auth_waiters: Vec::new(),
+            #[cfg(feature = "tokio-runtime")]
+            leave_waiters: Vec::new(),
+            #[cfg(feature = "tokio-runtime")]
+            reconnect_waiters: Vec::new(),
+            #[cfg(feature = "tokio-runtime")]
+            authority_requests: std::collections::VecDeque::new(),
+            authority_asked: true,
+            #[cfg(feature = "tokio-runtime")]
+            armed_ping: None,
+            #[cfg(feature = "tokio-runtime")]
+            pings_sent: 0,
+            #[cfg(feature = "tokio-runtime")]
+            pongs_received: 0,
+            #[cfg(feature = "tokio-runtime")]
+            ping_waiters: std::collections::VecDeque::new(),
+            configured: EffectiveConfig::from_config(config),
+            authenticated: None,
+            room: RoomSnapshot::default(),
+            #[cfg(feature = "tokio-runtime")]
+            lobby_watch: tokio::sync::watch::Sender::new(LobbyState::Waiting),
+            #[cfg(feature = "tokio-runtime")]
+            ticket_watch: tokio::sync::watch::Sender::new(None),
+            server_limits: None,
+            player_name_rules: None,
+            handshake_refusal: Self::refuse_sdk_version(config),
         }
     }
 
+    /// Replace the activity time source. Call before any frame moves.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn with_clock(mut self, clock: fn() -> Instant) -> Self {
+        self.clock = clock;
+        self.started = clock();
+        self
+    }
+
     pub(crate) fn snapshot(&self) -> ClientSnapshot {
-        self.snapshot.clone()
+        let state = self.session.state();
+        ClientSnapshot {
+            connected: state.is_connected(),
+            authenticated: state.is_authenticated(),
+            last_rtt: self.latency.stats().map(|stats| stats.last),
+            ..self.snapshot.clone()
+        }
+    }
+
+    pub(crate) fn health(&self, channel_utilization: f64) -> crate::ClientHealth {
+        crate::ClientHealth::evaluate(
+            self.is_connected(),
+            self.is_authenticated(),
+            self.activity()
+                .since_last_recv
+                .unwrap_or_else(|| (self.clock)().saturating_duration_since(self.started)),
+            channel_utilization,
+            &self.health_thresholds,
+        )
+        .with_rtt_p95(
+            self.latency.stats().map(|stats| stats.p95),
+            &self.health_thresholds,
+        )
+    }
+
+    pub(crate) fn activity(&self) -> crate::ActivityInfo {
+        let now = (self.clock)();
+        crate::ActivityInfo {
+            since_last_send: self.last_sent.map(|at| now.saturating_duration_since(at)),
+            since_last_recv: self.last_recv.map(|at| now.saturating_duration_since(at)),
+        }
+    }
+
+    /// Disconnect reason for the server ending the connection, carrying the
+    /// per-direction activity that explains idle closes.
+    pub(crate) fn server_close_reason(
+        &self,
+        close: Option<crate::transport::TransportCloseInfo>,
+    ) -> DisconnectReason {
+        DisconnectReason::ServerClosed {
+            close,
+            activity: self.activity(),
+        }
+    }
+
+    /// Disconnect reason for a failed transport receive.
+    pub(crate) fn receive_failure_reason(
+        &self,
+        error: &crate::SignalFishError,
+    ) -> DisconnectReason {
+        DisconnectReason::TransportError {
+            detail: format!("transport receive error: {error} ({})", self.activity()),
+        }
+    }
+
+    pub(crate) fn session_state(&self) -> SessionState {
+        self.session.state()
+    }
+
+    pub(crate) fn state_history(&self) -> Vec<StateTransition> {
+        self.session.history()
+    }
+
+    /// Text for `code`, with the server's advertised cap appended when one
+    /// explains the error.
+    pub(crate) fn describe_error_code(
+        &self,
+        code: &crate::ErrorCode,
+    ) -> std::borrow::Cow<'static, str> {
+        let description = match &self.error_code_formatter {
+            Some(formatter) => formatter.describe(code),
+            None => code.description().into(),
+        };
+        match self.known_limit(code) {
+            Some(limit) => format!("{description} ({limit})").into(),
+            None => description,
+        }
+    }
+
+    /// The advertised cap behind a limit error code, as text.
+    fn known_limit(&self, code: &crate::ErrorCode) -> Option<String> {
+        let limits = self.server_limits.as_ref()?;
+        let (limit, unit) = match code {
+            crate::ErrorCode::MaxRoomsPerGameExceeded => {
+                (limits.max_rooms_per_game?, "rooms per game")
+            }
+            crate::ErrorCode::InvalidMaxPlayers => {
+                (limits.max_players_per_room?, "players per room")
+            }
+            crate::ErrorCode::TooManySpectators => (limits.max_spectators?, "spectators per room"),
+            _ => return None,
+        };
+        Some(format!("server limit: {limit} {unit}"))
+    }
+
+    pub(crate) fn server_limits(&self) -> Option<ServerLimits> {
+        self.server_limits
+    }
+
+    pub(crate) fn authenticated_info(&self) -> Option<&AuthenticatedInfo> {
+        self.authenticated.as_ref()
+    }
+
+    pub(crate) fn rate_limits(&self) -> Option<&RateLimitInfo> {
+        self.authenticated.as_ref().map(|info| &info.rate_limits)
+    }
+
+    pub(crate) fn report_decode_failure(
+        &self,
+        event: &SignalFishEvent,
+        kind: crate::DecodeErrorKind,
+    ) {
+        if let Some(hook) = &self.decode_failure_hook {
+            hook.report(event, kind);
+        }
     }
 
     pub(crate) fn stats(&self) -> ClientStats {
         self.stats
     }
 
+    /// The current room epoch; see
+    /// [`drop_cross_epoch`](Self::drop_cross_epoch).
+    #[cfg(feature = "polling-client")]
+    pub(crate) fn room_epoch(&self) -> u64 {
+        self.room_epoch
+    }
+
+    /// Whether room-scoped events processed in room epoch `epoch` are now
+    /// dropped under [`SignalFishConfig::drop_cross_epoch_events`].
+    pub(crate) fn is_cross_epoch(&self, epoch: u64) -> bool {
+        self.drop_cross_epoch_events && epoch != self.room_epoch
+    }
+
+    /// Drop the room-scoped events among `events`, processed in room epoch
+    /// `epoch`, if [`is_cross_epoch`](Self::is_cross_epoch).
+    pub(crate) fn drop_cross_epoch(&mut self, epoch: u64, events: &mut Vec<SignalFishEvent>) {
+        if !self.is_cross_epoch(epoch) {
+            return;
+        }
+        let before = events.len();
+        events.retain(|event| !is_room_scoped(event));
+        let dropped = u64::try_from(before - events.len()).unwrap_or(u64::MAX);
+        if dropped > 0 {
+            tracing::debug!(dropped, epoch, "dropped events from a previous room");
+            self.stats.cross_epoch_events_dropped = self
+                .stats
+                .cross_epoch_events_dropped
+                .saturating_add(dropped);
+        }
+    }
+
+    pub(crate) fn outgoing_breakdown(&self) -> OutgoingBreakdown {
+        self.outgoing.clone()
+    }
+
+    /// The driver's outgoing queue accepted a message of `kind`.
+    pub(crate) fn record_enqueued(&mut self, kind: ClientMessageKind) {
+        self.outgoing.record_enqueued(kind);
+        #[cfg(feature = "tokio-runtime")]
+        if kind == ClientMessageKind::AuthorityRequest {
+            self.authority_requests.push_back(None);
+        }
+    }
+
+    /// Local Unix-epoch milliseconds, advanced by the activity clock.
+    fn local_time_ms(&self) -> u64 {
+        let elapsed = (self.clock)().saturating_duration_since(self.started);
+        self.started_unix_ms
+            .saturating_add(u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+    }
+
+    pub(crate) fn server_time_estimate(&self) -> Option<TimeSyncResult> {
+        self.time_sync.best()
+    }
+
+    pub(crate) fn latency_stats(&self) -> Option<LatencyStats> {
+        self.latency.stats()
+    }
+
+    /// When the oldest `Ping` still awaiting its `Pong` went out.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn oldest_unanswered_ping(&self) -> Option<Instant> {
+        self.latency.oldest_in_flight()
+    }
+
+    /// Hand the round trip of the next `Ping` the transport sends to
+    /// `waiter`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn arm_ping(&mut self, waiter: PingWaiter) {
+        self.armed_ping = Some(waiter);
+    }
+
+    /// Resolve the `ping_rtt()` caller the `Pong` received at `at` answers.
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_pong(&mut self, at: Instant) {
+        self.pongs_received = self.pongs_received.saturating_add(1);
+        while let Some((ping, _, _)) = self.ping_waiters.front() {
+            if *ping > self.pongs_received {
+                break;
+            }
+            let Some((ping, sent, waiter)) = self.ping_waiters.pop_front() else {
+                break;
+            };
+            if ping == self.pongs_received {
+                let _ = waiter.send(at.saturating_duration_since(sent));
+            }
+        }
+    }
+
+    /// Prepare a time-sync request whose result is delivered to `waiter`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn prepare_time_sync(
+        &mut self,
+        waiter: tokio::sync::oneshot::Sender<TimeSyncResult>,
+    ) -> crate::error::Result<CoreCommand> {
+        let command = self.prepare(ClientOperation::TimeSync)?;
+        if let CoreCommand::Message(ClientMessage::TimeSyncRequest { client_sent_at_ms }) = &command
+        {
+            self.time_sync_waiters
+                .retain(|(_, waiter)| !waiter.is_closed());
+            self.time_sync_waiters.push((*client_sent_at_ms, waiter));
+        }
+        Ok(command)
+    }
+
+    /// The game name and room code of the room this client spectates.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn spectated_room(&self) -> Option<(String, String)> {
+        if self.session.state() != SessionState::Spectating {
+            return None;
+        }
+        Some((
+            self.spectated_game.clone()?,
+            self.snapshot.room_code.clone()?,
+        ))
+    }
+
+    /// Wait on the next promotion step.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn set_promotion_waiter(&mut self, waiter: PromotionWaiter) {
+        self.promotion = Some(waiter);
+    }
+
+    /// Resolve the promotion step `message` answers. A server `Error` fails
+    /// whichever step is waiting.
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_promotion(&mut self, message: &ServerMessage) {
+        let Some(waiter) = self.promotion.take() else {
+            return;
+        };
+        let server_error = |message: &str, error_code: &Option<crate::ErrorCode>| {
+            crate::SignalFishError::ServerError {
+                message: message.to_owned(),
+                error_code: error_code.clone(),
+            }
+        };
+        match (waiter, message) {
+            (PromotionWaiter::Leave(waiter), ServerMessage::SpectatorLeft { .. }) => {
+                let _ = waiter.send(Ok(()));
+            }
+            (
+                PromotionWaiter::Leave(waiter),
+                ServerMessage::Error {
+                    message,
+                    error_code,
+                },
+            ) => {
+                let _ = waiter.send(Err(server_error(message, error_code)));
+            }
+            (PromotionWaiter::Join(waiter), ServerMessage::RoomJoined(payload)) => {
+                let _ = waiter.send(Ok(payload.as_ref().clone()));
+            }
+            (
+                PromotionWaiter::Join(waiter),
+                ServerMessage::RoomJoinFailed { reason, error_code }
+                | ServerMessage::Error {
+                    message: reason,
+                    error_code,
+                },
+            ) => {
+                let _ = waiter.send(Err(server_error(reason, error_code)));
+            }
+            (waiter, _) => self.promotion = Some(waiter),
+        }
+    }
+
+    /// Wait on the answer to the next `JoinRoom`, replacing any earlier
+    /// waiter.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn set_join_waiter(&mut self, waiter: JoinWaiter) {
+        self.join_waiter = Some(waiter);
+    }
+
+    /// Forget the waiters of `kind` whose callers stopped waiting, leaving
+    /// live ones in place. An abandoned `AuthorityRequest` keeps its slot, so
+    /// the responses still line up with the requests in flight.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn forget_abandoned(&mut self, kind: WaiterKind) {
+        match kind {
+            WaiterKind::Join => {
+                self.join_waiter.take_if(|waiter| waiter.is_closed());
+            }
+            WaiterKind::Leave => self.leave_waiters.retain(|waiter| !waiter.is_closed()),
+            WaiterKind::Authenticated => self.auth_waiters.retain(|waiter| !waiter.is_closed()),
+            WaiterKind::Reconnect => self.reconnect_waiters.retain(|waiter| !waiter.is_closed()),
+            WaiterKind::Authority => {
+                for slot in &mut self.authority_requests {
+                    slot.take_if(|waiter| waiter.is_closed());
+                }
+            }
+            WaiterKind::TimeSync => self
+                .time_sync_waiters
+                .retain(|(_, waiter)| !waiter.is_closed()),
+            WaiterKind::Promotion => {
+                self.promotion.take_if(|waiter| match waiter {
+                    PromotionWaiter::Leave(waiter) => waiter.is_closed(),
+                    PromotionWaiter::Join(waiter) => waiter.is_closed(),
+                });
+            }
+            WaiterKind::Ping => {
+                self.armed_ping.take_if(|waiter| waiter.is_closed());
+                self.ping_waiters
+                    .retain(|(_, _, waiter)| !waiter.is_closed());
+            }
+        }
+    }
+
+    /// Resolve the `join_room_and_wait()` caller `message` answers.
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_join(&mut self, message: &ServerMessage) {
+        let answer = match message {
+            ServerMessage::RoomJoined(payload) => Ok(payload.as_ref().clone()),
+            ServerMessage::RoomJoinFailed { reason, error_code } => {
+                Err(crate::SignalFishError::RoomJoinFailed {
+                    reason: reason.clone(),
+                    error_code: error_code.clone(),
+                })
+            }
+            ServerMessage::Error {
+                message,
+                error_code,
+            } => Err(crate::SignalFishError::ServerError {
+                message: message.clone(),
+                error_code: error_code.clone(),
+            }),
+            _ => return,
+        };
+        if let Some(waiter) = self.join_waiter.take() {
+            let _ = waiter.send(answer);
+        }
+    }
+
+    /// Hand `waiter` the outcome of authentication: at once if it already
+    /// completed, otherwise when the server answers. A closed connection
+    /// drops the waiter.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn wait_authenticated(&mut self, waiter: AuthWaiter) {
+        match &self.authenticated {
+            Some(info) if self.is_authenticated() => {
+                let _ = waiter.send(Ok(info.clone()));
+            }
+            _ if self.is_connected() || self.reconnecting() => {
+                self.auth_waiters.retain(|waiter| !waiter.is_closed());
+                self.auth_waiters.push(waiter);
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_authentication(&mut self, message: &ServerMessage) {
+        let outcome = match message {
+            ServerMessage::Authenticated {
+                app_name,
+                organization,
+                rate_limits,
+            } => Ok(AuthenticatedInfo {
+                app_name: app_name.clone(),
+                organization: organization.clone(),
+                rate_limits: rate_limits.clone(),
+            }),
+            ServerMessage::AuthenticationError { error, error_code } => Err((error, error_code)),
+            _ => return,
+        };
+        for waiter in self.auth_waiters.drain(..) {
+            let _ = waiter.send(match &outcome {
+                Ok(info) => Ok(info.clone()),
+                Err((error, error_code)) => Err(crate::SignalFishError::AuthenticationFailed {
+                    error: (*error).clone(),
+                    error_code: (*error_code).clone(),
+                }),
+            });
+        }
+    }
+
+    /// Whether the client is a player in a room it could leave.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn in_room(&self) -> bool {
+        self.session.state() == SessionState::InRoom
+    }
+
+    /// Whether the client is in a room, as a player or a spectator.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn holds_room(&self) -> bool {
+        matches!(
+            self.session.state(),
+            SessionState::InRoom | SessionState::Spectating
+        )
+    }
+
+    /// The `LeaveRoom` or `LeaveSpectator` a shutdown sends for the room
+    /// held, unless a leave already went out.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn prepare_shutdown_leave(&mut self) -> Option<CoreCommand> {
+        let operation = match self.session.state() {
+            SessionState::InRoom => ClientOperation::LeaveRoom,
+            SessionState::Spectating => ClientOperation::LeaveSpectator,
+            _ => return None,
+        };
+        if self.leave_requested {
+            return None;
+        }
+        self.prepare(operation).ok()
+    }
+
+    /// Resolve `waiter` once the server confirms the queued `LeaveRoom`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn await_room_left(&mut self, waiter: LeaveWaiter) {
+        self.leave_waiters.retain(|waiter| !waiter.is_closed());
+        self.leave_waiters.push(waiter);
+    }
+
+    /// `RoomLeft` confirms a leave; so does a `NOT_IN_ROOM` error, which
+    /// means the server already holds no room for this client. The room is
+    /// cleared here only in that second case: `update_state` handled
+    /// `RoomLeft`, and a late one is then ignored.
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_leave(&mut self, message: &ServerMessage) {
+        if self.leave_waiters.is_empty() {
+            return;
+        }
+        match message {
+            ServerMessage::RoomLeft => {}
+            ServerMessage::Error {
+                error_code: Some(crate::ErrorCode::NotInRoom),
+                ..
+            } => {
+                if self.in_room() {
+                    self.apply_trigger(SessionTrigger::LeftRoom);
+                    self.clear_room(RoomLeaveReason::Left);
+                    self.suppress_late_room_left = true;
+                }
+            }
+            _ => return,
+        }
+        for waiter in self.leave_waiters.drain(..) {
+            let _ = waiter.send(());
+        }
+    }
+
+    /// Hand the answer to the pending `Reconnect` to `waiter`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn await_reconnected(&mut self, waiter: ReconnectWaiter) {
+        self.reconnect_waiters.retain(|waiter| !waiter.is_closed());
+        self.reconnect_waiters.push(waiter);
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_reconnect(&mut self, message: &ServerMessage) {
+        let answer = match message {
+            ServerMessage::Reconnected(payload) => Ok(payload.as_ref()),
+            ServerMessage::ReconnectionFailed { reason, error_code } => Err((reason, error_code)),
+            _ => return,
+        };
+        for waiter in self.reconnect_waiters.drain(..) {
+            let _ = waiter.send(match answer {
+                Ok(payload) => Ok(payload.clone()),
+                Err((reason, error_code)) => Err(crate::SignalFishError::ServerError {
+                    message: reason.clone(),
+                    error_code: Some(error_code.clone()),
+                }),
+            });
+        }
+    }
+
+    /// Hand the answer to the `AuthorityRequest` just queued to `waiter`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn await_authority_response(&mut self, waiter: AuthorityWaiter) {
+        if let Some(slot) = self.authority_requests.back_mut() {
+            *slot = Some(waiter);
+        }
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    fn observe_authority_response(&mut self, message: &ServerMessage) {
+        let ServerMessage::AuthorityResponse {
+            granted,
+            reason,
+            error_code,
+        } = message
+        else {
+            return;
+        };
+        if let Some(Some(waiter)) = self.authority_requests.pop_front() {
+            let _ = waiter.send(AuthorityGrant {
+                granted: *granted,
+                reason: reason.clone(),
+                error_code: error_code.clone(),
+            });
+        }
+    }
+
     pub(crate) fn is_connected(&self) -> bool {
-        self.snapshot.connected
+        self.session.state().is_connected()
+    }
+
+    /// A reconnecting client lost its transport and is dialing another.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn reconnecting(&self) -> bool {
+        self.outage
+    }
+
+    /// Whether a message may be queued now: always while connected, and
+    /// while reconnecting if the offline queue holds anything.
+    pub(crate) fn ensure_sendable(&self) -> crate::error::Result<()> {
+        if self.is_connected() || (self.outage && self.offline_queue_capacity > 0) {
+            Ok(())
+        } else {
+            Err(crate::SignalFishError::NotConnected)
+        }
+    }
+
+    /// A full offline queue evicted its oldest command.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn record_offline_eviction(&mut self) {
+        self.stats.offline_commands_evicted = self.stats.offline_commands_evicted.saturating_add(1);
+    }
+
+    /// How long a queued message of `kind` stays worth sending, per
+    /// [`SignalFishConfig::message_deadlines`]; `None` for the kinds that
+    /// never expire.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn message_deadline(&self, kind: ClientMessageKind) -> Option<std::time::Duration> {
+        if never_expires(kind) {
+            return None;
+        }
+        self.message_deadlines.get(&kind).copied()
+    }
+
+    /// A queued message passed its deadline and was dropped unsent.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn record_message_expired(&mut self) {
+        self.stats.messages_expired = self.stats.messages_expired.saturating_add(1);
     }
 
     pub(crate) fn is_authenticated(&self) -> bool {
-        self.snapshot.authenticated
+        self.session.state().is_authenticated()
+    }
+
+    pub(crate) fn effective_config(&self) -> EffectiveConfig {
+        self.configured.clone().with_server(
+            self.game_data_encoding,
+            self.protocol_info_seen
+                .then_some(self.snapshot.negotiated_protocol_version),
+            self.rate_limits(),
+        )
+    }
+
+    /// Apply the core's share of a checked runtime patch; scheduling
+    /// changes are the driver's.
+    pub(crate) fn apply_runtime_config(&mut self, patch: &crate::RuntimeConfigPatch) {
+        if let Some(suppress) = patch.suppress_announcements {
+            self.suppress_announcements = suppress;
+        }
+        self.configured.apply_runtime(patch);
     }
 
     pub(crate) fn negotiated_protocol_version(&self) -> Option<u16> {
@@ -143,10 +1115,65 @@ impl ClientCore {
         self.snapshot.room_code.as_deref()
     }
 
-    pub(crate) fn prepare(&self, operation: ClientOperation) -> crate::error::Result<CoreCommand> {
-        if !self.is_connected() {
-            return Err(crate::SignalFishError::NotConnected);
+    pub(crate) fn current_players(&self) -> &[PlayerInfo] {
+        &self.room.players
+    }
+
+    pub(crate) fn lobby_state(&self) -> &LobbyState {
+        &self.room.lobby_state
+    }
+
+    pub(crate) fn is_authority(&self) -> bool {
+        self.room.is_authority
+    }
+
+    pub(crate) fn room_snapshot(&self) -> Option<RoomSnapshot> {
+        self.room.is_in_room().then(|| self.room.clone())
+    }
+
+    pub(crate) fn room_history(&self) -> Vec<RoomSessionRecord> {
+        self.room_history.records()
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn lobby_state_watch(&self) -> tokio::sync::watch::Receiver<LobbyState> {
+        self.lobby_watch.subscribe()
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn session_ticket_watch(
+        &self,
+    ) -> tokio::sync::watch::Receiver<Option<SessionTicket>> {
+        self.ticket_watch.subscribe()
+    }
+
+    /// The ticket for the room held, once the server issued a reconnection
+    /// token for it.
+    #[cfg(feature = "tokio-runtime")]
+    fn room_ticket(&self) -> Option<SessionTicket> {
+        Some(SessionTicket {
+            player_id: self.snapshot.player_id?,
+            room_id: self.snapshot.room_id?,
+            room_code: self.snapshot.room_code.clone()?,
+            reconnection_token: self.snapshot.reconnection_token.clone()?,
+        })
+    }
+
+    /// Make a stored `ticket` the room to resume, unless the resume policy
+    /// is `Never`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn restore_ticket(&mut self, ticket: SessionTicket) {
+        if self.resume_policy != ResumePolicy::Never {
+            self.resume_ticket =
+                Some((ticket.player_id, ticket.room_id, ticket.reconnection_token));
         }
+    }
+
+    pub(crate) fn prepare(
+        &mut self,
+        operation: ClientOperation,
+    ) -> crate::error::Result<CoreCommand> {
+        self.ensure_sendable()?;
         match &operation {
             ClientOperation::GameData(_, GameDataDelivery::Latest { .. })
             | ClientOperation::GameData(_, GameDataDelivery::Volatile)
@@ -161,15 +1188,34 @@ impl ClientCore {
         {
             return Err(crate::SignalFishError::BinaryFormatNotNegotiated);
         }
+        if self.snapshot.draining
+            && matches!(
+                &operation,
+                ClientOperation::JoinRoom(_) | ClientOperation::JoinAsSpectator(..)
+            )
+        {
+            return Err(crate::SignalFishError::Draining);
+        }
+        if let ClientOperation::JoinRoom(params) = &operation {
+            self.check_join_limits(params)?;
+        }
+        if matches!(&operation, ClientOperation::Reconnect(..))
+            && self.reconnect_in_flight().is_some()
+        {
+            return Err(crate::SignalFishError::ReconnectInFlight);
+        }
         let message = match operation {
-            ClientOperation::JoinRoom(params) => ClientMessage::JoinRoom {
-                game_name: params.game_name,
-                room_code: params.room_code,
-                player_name: params.player_name,
-                max_players: params.max_players,
-                supports_authority: params.supports_authority,
-                relay_transport: params.relay_transport,
-            },
+            ClientOperation::JoinRoom(params) => {
+                self.pending_join = Some(params.room_code.clone());
+                ClientMessage::JoinRoom {
+                    game_name: params.game_name,
+                    room_code: params.room_code,
+                    player_name: params.player_name,
+                    max_players: params.max_players,
+                    supports_authority: params.supports_authority,
+                    relay_transport: params.relay_transport,
+                }
+            }
             ClientOperation::LeaveRoom => ClientMessage::LeaveRoom,
             ClientOperation::GameData(data, delivery) => {
                 let (class, key) = match delivery {
@@ -185,12 +1231,14 @@ impl ClientCore {
             ClientOperation::SetReady => ClientMessage::PlayerReady,
             ClientOperation::StartGame => ClientMessage::StartGame,
             ClientOperation::RequestAuthority(become_authority) => {
+                self.authority_asked = become_authority;
                 ClientMessage::AuthorityRequest { become_authority }
             }
             ClientOperation::ProvideConnectionInfo(connection_info) => {
                 ClientMessage::ProvideConnectionInfo { connection_info }
             }
             ClientOperation::Reconnect(player_id, room_id, auth_token) => {
+                self.pending_reconnect = Some((room_id, (self.clock)()));
                 ClientMessage::Reconnect {
                     player_id,
                     room_id,
@@ -217,6 +1265,9 @@ impl ClientCore {
                     connected,
                 }
             }
+            ClientOperation::TimeSync => ClientMessage::TimeSyncRequest {
+                client_sent_at_ms: self.local_time_ms(),
+            },
         };
         Ok(CoreCommand::Message(message))
     }
@@ -236,53 +1287,393 @@ impl ClientCore {
         Err(crate::SignalFishError::ProtocolUnsupported { mode })
     }
 
-    pub(crate) fn record_game_data_sent(&mut self) {
-        self.stats.game_data_sent = self.stats.game_data_sent.saturating_add(1);
+    /// Refuse a `JoinRoom` the server's advertised caps or name rules would
+    /// reject.
+    fn check_join_limits(&self, params: &JoinRoomParams) -> crate::error::Result<()> {
+        if let Some(rules) = &self.player_name_rules {
+            rules
+                .validate(&params.player_name)
+                .map_err(crate::SignalFishError::InvalidPlayerName)?;
+        }
+        let limit = self
+            .server_limits
+            .as_ref()
+            .and_then(|limits| limits.max_players_per_room);
+        match (params.max_players, limit) {
+            (Some(requested), Some(limit)) if u32::from(requested) > limit => {
+                Err(crate::SignalFishError::MaxPlayersExceeded { requested, limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The transport accepted an outbound frame carrying a message of
+    /// `kind`.
+    pub(crate) fn record_frame_sent(&mut self, kind: ClientMessageKind) {
+        self.frame_sent = true;
+        self.last_sent = Some((self.clock)());
+        self.outgoing.record_sent(kind);
+        if kind == ClientMessageKind::Ping {
+            let now = (self.clock)();
+            self.latency.ping_sent(now);
+            #[cfg(feature = "tokio-runtime")]
+            {
+                self.pings_sent = self.pings_sent.saturating_add(1);
+                if let Some(waiter) = self.armed_ping.take() {
+                    self.ping_waiters.push_back((self.pings_sent, now, waiter));
+                }
+            }
+        }
+        if matches!(
+            kind,
+            ClientMessageKind::LeaveRoom | ClientMessageKind::LeaveSpectator
+        ) {
+            self.leave_requested = true;
+        }
+        if kind.is_game_data() {
+            self.stats.game_data_sent = self.stats.game_data_sent.saturating_add(1);
+        }
+    }
+
+    /// Disconnect reason for a failed transport send. A failure before any
+    /// frame went out means `Authenticate` never left the client, which is
+    /// recorded as [`ClientSnapshot::handshake_failed`].
+    pub(crate) fn send_failure_reason(
+        &mut self,
+        error: &crate::SignalFishError,
+    ) -> DisconnectReason {
+        let detail = if self.frame_sent {
+            format!("transport send error: {error}")
+        } else {
+            self.snapshot.handshake_failed = true;
+            format!("transport send error during handshake: {error}")
+        };
+        DisconnectReason::TransportError { detail }
+    }
+
+    /// Disconnect reason when the configuration refuses to send
+    /// `Authenticate`, recorded as [`ClientSnapshot::handshake_failed`].
+    pub(crate) fn take_handshake_refusal(&mut self) -> Option<DisconnectReason> {
+        let error = self.handshake_refusal.take()?;
+        tracing::error!(%error, "refusing to authenticate");
+        self.snapshot.handshake_failed = true;
+        Some(DisconnectReason::HandshakeRefused {
+            detail: error.to_string(),
+        })
+    }
+
+    /// Disconnect reason when the transport was already claimed by another
+    /// session, recorded as [`ClientSnapshot::handshake_failed`].
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn transport_reused(&mut self) -> DisconnectReason {
+        let error = crate::SignalFishError::TransportReused;
+        tracing::error!(%error, "refusing to authenticate on a reused transport");
+        self.snapshot.handshake_failed = true;
+        DisconnectReason::HandshakeRefused {
+            detail: error.to_string(),
+        }
     }
 
     pub(crate) fn clear_session(&mut self) {
-        self.snapshot.authenticated = false;
         self.snapshot.negotiated_protocol_version = None;
         self.snapshot.player_id = None;
         self.snapshot.room_id = None;
         self.snapshot.room_code = None;
         self.snapshot.reconnection_token = None;
         self.snapshot.quarantined = false;
+        self.room_history
+            .ended(RoomLeaveReason::Disconnected, self.local_time_ms());
+        self.leave_requested = false;
+        self.set_room_snapshot(RoomSnapshot::default());
         self.protocol_info_seen = false;
+        self.server_limits = None;
+        self.player_name_rules = None;
+        self.authenticated = None;
+        self.pending_join = None;
+        self.self_removal_pending = false;
+        self.suppress_late_room_left = false;
+        self.pending_reconnect = None;
     }
 
-    pub(crate) fn disconnect(&mut self, reason: Option<String>) -> SignalFishEvent {
-        self.accountability.observe_terminal();
-        self.snapshot.connected = false;
-        self.clear_session();
+    /// Refuse new rooms from now on; the driver shuts down once
+    /// [`drained`](Self::drained).
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn begin_drain(&mut self) {
+        self.snapshot.draining = true;
+    }
+
+    /// Draining with no room held or on its way: nothing is left to finish.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn drained(&self) -> bool {
+        self.snapshot.draining
+            && !matches!(
+                self.session.state(),
+                SessionState::InRoom | SessionState::Spectating
+            )
+            && self.pending_join.is_none()
+            && self.pending_reconnect.is_none()
+    }
+
+    /// The room of the `Reconnect` still awaiting an answer, unless it has
+    /// waited past [`SignalFishConfig::reconnect_answer_timeout`].
+    pub(crate) fn reconnect_in_flight(&self) -> Option<RoomId> {
+        let (room_id, sent) = self.pending_reconnect?;
+        ((self.clock)().saturating_duration_since(sent) < self.reconnect_answer_timeout)
+            .then_some(room_id)
+    }
+
+    /// The `Reconnect` just prepared could not be queued: it is not in flight.
+    pub(crate) fn reconnect_not_sent(&mut self) {
+        self.pending_reconnect = None;
+    }
+
+    pub(crate) fn disconnect(&mut self, reason: DisconnectReason) -> SignalFishEvent {
+        // A connection that ends after the server rejected `Authenticate`
+        // ended because of it.
+        let reason = match (reason, &self.authentication_rejected) {
+            (
+                DisconnectReason::ServerClosed { .. } | DisconnectReason::TransportError { .. },
+                Some(code),
+            ) => DisconnectReason::AuthenticationFailed { code: code.clone() },
+            (reason, _) => reason,
+        };
+        // A reconnecting client already ended the connection when the outage
+        // began, but kept the session for the next one.
+        let ended = self.is_connected() || self.outage;
+        if !std::mem::take(&mut self.outage) {
+            self.end_connection();
+        }
+        if ended {
+            self.advance_session_epoch();
+        }
+        #[cfg(feature = "tokio-runtime")]
+        self.auth_waiters.clear();
         SignalFishEvent::Disconnected {
             reason,
             last_server_error: self.last_server_error.take(),
         }
     }
 
+    /// Clear everything tied to the current transport.
+    fn end_connection(&mut self) {
+        self.accountability.observe_terminal();
+        self.apply_trigger(SessionTrigger::TransportClosed);
+        self.clear_session();
+        self.latency.clear_in_flight();
+        #[cfg(feature = "tokio-runtime")]
+        {
+            self.time_sync_waiters.clear();
+            self.promotion = None;
+            self.join_waiter = None;
+            self.leave_waiters.clear();
+            self.reconnect_waiters.clear();
+            self.authority_requests.clear();
+            self.armed_ping = None;
+            self.ping_waiters.clear();
+            self.pings_sent = 0;
+            self.pongs_received = 0;
+        }
+    }
+
+    /// A reconnecting client lost its transport: end the connection as
+    /// [`disconnect`](Self::disconnect) does, without a terminal event, and
+    /// start buffering sends. `wait_authenticated` callers keep waiting for
+    /// the next connection.
+    ///
+    /// The session epoch carries over to the next connection unless a room
+    /// was held that cannot be resumed.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn begin_outage(&mut self) {
+        if self.reconnecting() {
+            return;
+        }
+        let mut room_lost = self.snapshot.room_id.is_some();
+        if self.resume_policy != ResumePolicy::Never {
+            if let (Some(player_id), Some(room_id), Some(token)) = (
+                self.snapshot.player_id,
+                self.snapshot.room_id,
+                self.snapshot.reconnection_token.clone(),
+            ) {
+                self.resume_ticket = Some((player_id, room_id, token));
+                self.resume_held = true;
+                room_lost = false;
+            }
+        }
+        self.end_connection();
+        self.outage = true;
+        if room_lost {
+            self.advance_session_epoch();
+        }
+    }
+
+    /// The logical session reset: see [`ClientSnapshot::session_epoch`].
+    fn advance_session_epoch(&mut self) {
+        self.snapshot.session_epoch = self.snapshot.session_epoch.saturating_add(1);
+        #[cfg(feature = "tokio-runtime")]
+        {
+            self.resume_held = false;
+        }
+    }
+
+    /// The room kept across an outage will not be resumed: the session it
+    /// belonged to has ended.
+    #[cfg(feature = "tokio-runtime")]
+    fn abandon_resume(&mut self) {
+        if self.resume_held {
+            self.advance_session_epoch();
+        }
+    }
+
+    /// Whether a connection just opened should emit `Connected`: always
+    /// under [`SignalFishConfig::reemit_session_events`], otherwise once per
+    /// session epoch.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn announce_connected(&mut self) -> bool {
+        let epoch = self.snapshot.session_epoch;
+        let repeated = self.connected_epoch.replace(epoch) == Some(epoch);
+        !repeated || self.reemit_session_events
+    }
+
+    /// `Reconnect` to the room held before the last outage.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn resume(&mut self) -> crate::error::Result<CoreCommand> {
+        let (player_id, room_id, token) = self
+            .resume_ticket
+            .clone()
+            .ok_or(crate::SignalFishError::NotInRoom)?;
+        self.prepare(ClientOperation::Reconnect(player_id, room_id, token))
+    }
+
+    /// Under [`ResumePolicy::Auto`], the `Reconnect` to open a new transport
+    /// with, already counted as enqueued.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn auto_resume(&mut self) -> Option<CoreCommand> {
+        if self.resume_policy != ResumePolicy::Auto {
+            return None;
+        }
+        let command = self.resume().ok()?;
+        self.record_enqueued(command.kind());
+        Some(command)
+    }
+
+    /// A reconnecting client dialed a new transport.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn transport_reopened(&mut self) {
+        if std::mem::take(&mut self.outage) {
+            self.apply_trigger(SessionTrigger::TransportReopened);
+        }
+        self.frame_sent = false;
+        self.snapshot.handshake_failed = false;
+        self.authentication_rejected = None;
+    }
+
+    /// The connection that just ended was refused by the server's
+    /// `AuthenticationError`.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn authentication_rejected(&self) -> bool {
+        self.authentication_rejected.is_some()
+    }
+
+    #[cfg(feature = "polling-client")]
     pub(crate) fn process_frame(&mut self, frame: TransportFrame) -> FrameOutcome {
+        self.apply_frame(self.decoder.decode(frame))
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn frame_decoder(&self) -> FrameDecoder {
+        self.decoder
+    }
+
+    /// Liveness the transport saw without delivering a frame, such as a
+    /// WebSocket pong; counts as inbound activity.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn observe_transport_activity(&mut self, at: Instant) {
+        self.last_recv = self.last_recv.max(Some(at));
+    }
+
+    /// [`process_frame`](Self::process_frame) for a frame whose stateless
+    /// decoding already ran, possibly on another thread.
+    /// Discard a frame unread while no one is listening; it still counts as
+    /// inbound activity.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn skip_frame(&mut self) {
+        self.last_recv = Some((self.clock)());
+        self.stats.paused_frames_skipped = self.stats.paused_frames_skipped.saturating_add(1);
+    }
+
+    pub(crate) fn apply_frame(&mut self, frame: DecodedFrame) -> FrameOutcome {
+        #[cfg(feature = "tokio-runtime")]
+        let ticket = self.room_ticket();
+        let outcome = self.apply_decoded(frame);
+        // Only server messages move the ticket: a dropped transport leaves
+        // it for the next connection to resume with.
+        #[cfg(feature = "tokio-runtime")]
+        {
+            let current = self.room_ticket();
+            if current != ticket {
+                self.ticket_watch.send_replace(current);
+            }
+        }
+        outcome
+    }
+
+    fn apply_decoded(&mut self, frame: DecodedFrame) -> FrameOutcome {
+        self.last_recv = Some((self.clock)());
         match frame {
-            TransportFrame::Text(text) => self.process_text(text),
-            TransportFrame::Binary(bytes) => self.process_binary(bytes),
+            DecodedFrame::Text(messages) => {
+                if messages.len() > 1 {
+                    self.stats.multi_message_frames =
+                        self.stats.multi_message_frames.saturating_add(1);
+                }
+                let mut outcome = FrameOutcome::new();
+                let mut processed = Vec::with_capacity(messages.len());
+                for parsed in messages {
+                    let next = self.process_text(parsed);
+                    processed.push((self.room_epoch, next.events));
+                    outcome.self_removal_grace =
+                        next.self_removal_grace.or(outcome.self_removal_grace);
+                    if next.disconnect.is_some() {
+                        outcome.disconnect = next.disconnect;
+                        break;
+                    }
+                }
+                for (epoch, mut events) in processed {
+                    self.drop_cross_epoch(epoch, &mut events);
+                    outcome.events.extend(events);
+                }
+                outcome
+            }
+            DecodedFrame::Binary(bytes) => self.process_binary(bytes),
         }
     }
 
-    fn process_text(&mut self, text: String) -> FrameOutcome {
+    fn process_text(&mut self, parsed: ParsedText) -> FrameOutcome {
         let mut outcome = FrameOutcome::new();
-        let server_msg = match serde_json::from_str::<ServerMessage>(&text) {
-            Ok(message) => message,
-            Err(error) => {
+        let server_msg = match parsed {
+            Ok((message, adjusted)) => {
+                outcome.events.extend(adjusted);
+                message
+            }
+            // Proxies inject empty frames as keepalives; they carry no message.
+            Err((text, _)) if !self.strict_blank_frames && text.trim().is_empty() => {
+                self.stats.blank_frames_skipped = self.stats.blank_frames_skipped.saturating_add(1);
+                tracing::trace!("skipped blank text frame ({} bytes)", text.len());
+                return outcome;
+            }
+            Err((text, error)) => {
                 tracing::warn!(
                     "failed to deserialize server message ({} bytes): {error}",
                     text.len()
                 );
                 let disconnect = self.observe_undecodable(&mut outcome.events);
                 self.stats.messages_undecodable = self.stats.messages_undecodable.saturating_add(1);
-                outcome
-                    .events
-                    .push(SignalFishEvent::decode_failed(&text, &error));
-                outcome.disconnect = disconnect;
+                let event = self
+                    .forward_unknown_messages
+                    .then(|| SignalFishEvent::unknown_message(&text, &error))
+                    .flatten()
+                    .unwrap_or_else(|| SignalFishEvent::decode_failed(&text, &error));
+                outcome.events.push(event);
+                outcome.disconnect = disconnect.then_some(DisconnectReason::ProtocolViolation);
                 return outcome;
             }
         };
@@ -326,7 +1717,7 @@ impl ClientCore {
             Err(diagnostic) => {
                 self.push_violation(&mut outcome.events, diagnostic);
                 if self.violation_policy == ProtocolViolationPolicy::Disconnect {
-                    outcome.disconnect = true;
+                    outcome.disconnect = Some(DisconnectReason::ProtocolViolation);
                     return outcome;
                 }
                 let disposition = if self.violation_policy == ProtocolViolationPolicy::Observe {
@@ -356,11 +1747,193 @@ impl ClientCore {
             return outcome;
         }
 
+        let auth_failure = match &server_msg {
+            ServerMessage::AuthenticationError { error, .. } => Some(error.clone()),
+            _ => None,
+        };
+        if matches!(server_msg, ServerMessage::RoomLeft) && self.suppress_late_room_left {
+            self.suppress_late_room_left = false;
+            tracing::debug!("ignoring RoomLeft for a room already cleared by self-removal");
+            return outcome;
+        }
+        if let Some(warning) = self.room_inconsistency(&server_msg) {
+            outcome.events.push(warning);
+            match self.session_inconsistency_policy {
+                SessionInconsistencyPolicy::Apply => {}
+                SessionInconsistencyPolicy::Ignore => return outcome,
+                SessionInconsistencyPolicy::Resync => {
+                    outcome.disconnect = Some(DisconnectReason::SessionInconsistency);
+                    return outcome;
+                }
+            }
+        }
+        if let Some(warning) = self.organization_mismatch(&server_msg) {
+            outcome.events.push(warning);
+            if self.organization_mismatch_policy == OrganizationMismatchPolicy::Disconnect {
+                outcome.disconnect = Some(DisconnectReason::OrganizationMismatch);
+                return outcome;
+            }
+        }
+        let self_removed = match &server_msg {
+            ServerMessage::PlayerLeft { player_id, .. } => {
+                self.snapshot.player_id == Some(*player_id) && self.snapshot.room_id.is_some()
+            }
+            _ => false,
+        };
+        let repeated_auth = matches!(server_msg, ServerMessage::Authenticated { .. })
+            && self.authenticated_epoch == Some(self.snapshot.session_epoch);
         self.update_state(&server_msg);
-        outcome.events.push(SignalFishEvent::from(server_msg));
+        if repeated_auth && !self.reemit_session_events {
+            tracing::debug!("suppressing repeated Authenticated within the same session");
+            #[cfg(feature = "tokio-runtime")]
+            self.observe_authentication(&server_msg);
+        } else {
+            self.push_server_events(&mut outcome.events, server_msg);
+        }
+        if let Some(reason) = auth_failure {
+            outcome
+                .events
+                .extend(self.invalidate_session_on_auth_failure(reason));
+        }
+        if self_removed {
+            outcome.self_removal_grace = self.observe_self_removal(&mut outcome.events);
+        }
         outcome
     }
 
+    /// A [`SignalFishEvent::SessionInconsistency`] when `message` is scoped to
+    /// a room other than the one this session is in (or reconnecting to).
+    fn room_inconsistency(&self, message: &ServerMessage) -> Option<SignalFishEvent> {
+        let (got, message_kind) = match message {
+            ServerMessage::SpectatorLeft {
+                room_id, room_code, ..
+            } => (
+                RoomRef {
+                    room_id: *room_id,
+                    room_code: room_code.clone(),
+                },
+                "SpectatorLeft",
+            ),
+            ServerMessage::Reconnected(payload) => (
+                RoomRef {
+                    room_id: Some(payload.room_id),
+                    room_code: Some(payload.room_code.clone()),
+                },
+                "Reconnected",
+            ),
+            _ => return None,
+        };
+        let expected = match (message, self.pending_reconnect) {
+            (ServerMessage::Reconnected(_), Some((room_id, _))) => RoomRef {
+                room_id: Some(room_id),
+                room_code: None,
+            },
+            _ => RoomRef {
+                room_id: self.snapshot.room_id,
+                room_code: self.snapshot.room_code.clone(),
+            },
+        };
+        if !expected.conflicts_with(&got) {
+            return None;
+        }
+        tracing::warn!(
+            message_kind,
+            ?expected,
+            ?got,
+            policy = ?self.session_inconsistency_policy,
+            "server message names a room this session is not in"
+        );
+        Some(SignalFishEvent::SessionInconsistency {
+            expected,
+            got,
+            message_kind,
+        })
+    }
+
+    /// A [`SignalFishEvent::OrganizationMismatch`] when `message` is an
+    /// `Authenticated` naming an organization other than the expected one.
+    fn organization_mismatch(&self, message: &ServerMessage) -> Option<SignalFishEvent> {
+        let expected = self.expected_organization.as_ref()?;
+        let ServerMessage::Authenticated { organization, .. } = message else {
+            return None;
+        };
+        if organization.as_ref() == Some(expected) {
+            return None;
+        }
+        tracing::warn!(
+            expected,
+            actual = ?organization,
+            policy = ?self.organization_mismatch_policy,
+            "server authenticated this app under an unexpected organization"
+        );
+        Some(SignalFishEvent::OrganizationMismatch {
+            expected: expected.clone(),
+            actual: organization.clone(),
+        })
+    }
+
+    /// Apply [`SignalFishConfig::self_removal_policy`] to a `PlayerLeft` that
+    /// names this client. Returns the grace period the driver must wait
+    /// before calling [`expire_self_removal`](Self::expire_self_removal).
+    fn observe_self_removal(&mut self, events: &mut Vec<SignalFishEvent>) -> Option<Duration> {
+        if self.self_removal_pending {
+            return None;
+        }
+        match self.self_removal_policy {
+            SelfRemovalPolicy::ClearAfter { grace } if grace.is_zero() => {
+                events.extend(self.remove_self_from_room());
+                None
+            }
+            SelfRemovalPolicy::ClearAfter { grace } => {
+                self.self_removal_pending = true;
+                Some(grace)
+            }
+            SelfRemovalPolicy::AwaitRoomLeft => {
+                tracing::warn!("server announced our own PlayerLeft; awaiting RoomLeft");
+                events.extend(
+                    self.snapshot
+                        .room_id
+                        .map(|room_id| SignalFishEvent::SelfRemovalPending { room_id }),
+                );
+                None
+            }
+        }
+    }
+
+    /// The self-removal grace period elapsed. Clears the room unless a
+    /// `RoomLeft` (or a fresh join) already resolved it.
+    pub(crate) fn expire_self_removal(&mut self) -> Option<SignalFishEvent> {
+        if !std::mem::take(&mut self.self_removal_pending) {
+            return None;
+        }
+        self.remove_self_from_room()
+    }
+
+    fn remove_self_from_room(&mut self) -> Option<SignalFishEvent> {
+        let room_id = self.snapshot.room_id?;
+        let room_code = self.snapshot.room_code.clone().unwrap_or_default();
+        self.apply_trigger(SessionTrigger::LeftRoom);
+        self.clear_room(RoomLeaveReason::Removed);
+        self.suppress_late_room_left = true;
+        Some(SignalFishEvent::RemovedFromRoom { room_id, room_code })
+    }
+
+    /// Apply [`SignalFishConfig::clear_session_on_auth_failure`] after an
+    /// `AuthenticationError`, returning the distilled event when a room
+    /// session was actually discarded.
+    fn invalidate_session_on_auth_failure(&mut self, reason: String) -> Option<SignalFishEvent> {
+        if !self.clear_session_on_auth_failure {
+            return None;
+        }
+        let had_session = self.snapshot.player_id.is_some()
+            || self.snapshot.room_id.is_some()
+            || self.snapshot.reconnection_token.is_some();
+        self.snapshot.player_id = None;
+        self.clear_room(RoomLeaveReason::Disconnected);
+        self.advance_session_epoch();
+        had_session.then_some(SignalFishEvent::SessionInvalidated { reason })
+    }
+
     fn process_binary(&mut self, bytes: Vec<u8>) -> FrameOutcome {
         let mut outcome = FrameOutcome::new();
         let mut observe_representation_violation = false;
@@ -372,7 +1945,7 @@ impl ClientCore {
             match self.violation_policy {
                 ProtocolViolationPolicy::Quarantine => return outcome,
                 ProtocolViolationPolicy::Disconnect => {
-                    outcome.disconnect = true;
+                    outcome.disconnect = Some(DisconnectReason::ProtocolViolation);
                     return outcome;
                 }
                 ProtocolViolationPolicy::Observe => observe_representation_violation = true,
@@ -393,7 +1966,7 @@ impl ClientCore {
                     error,
                     raw_prefix: bounded_binary_preview(&bytes),
                 });
-                outcome.disconnect = disconnect;
+                outcome.disconnect = disconnect.then_some(DisconnectReason::ProtocolViolation);
                 return outcome;
             }
         };
@@ -413,7 +1986,7 @@ impl ClientCore {
             Err(diagnostic) => {
                 self.push_violation(&mut outcome.events, diagnostic);
                 if self.violation_policy == ProtocolViolationPolicy::Disconnect {
-                    outcome.disconnect = true;
+                    outcome.disconnect = Some(DisconnectReason::ProtocolViolation);
                     return outcome;
                 }
                 if self.violation_policy == ProtocolViolationPolicy::Observe {
@@ -432,10 +2005,83 @@ impl ClientCore {
         }
 
         self.update_state(&server_msg);
-        outcome.events.push(SignalFishEvent::from(server_msg));
+        self.push_server_events(&mut outcome.events, server_msg);
         outcome
     }
 
+    /// Distill `message` into its public event, attaching the client-side
+    /// context the wire message does not carry, followed by any events derived
+    /// from it.
+    fn push_server_events(&mut self, events: &mut Vec<SignalFishEvent>, message: ServerMessage) {
+        let gaps = match &message {
+            ServerMessage::Reconnected(payload) => payload.missing_ranges(),
+            _ => Vec::new(),
+        };
+        #[cfg(feature = "tokio-runtime")]
+        {
+            self.observe_promotion(&message);
+            self.observe_join(&message);
+            self.observe_authentication(&message);
+            self.observe_authority_response(&message);
+            self.observe_reconnect(&message);
+            self.observe_leave(&message);
+        }
+        let mut event = SignalFishEvent::from(message);
+        if let SignalFishEvent::GameData {
+            from_player, data, ..
+        } = &event
+        {
+            if self.legacy_announcement_sender == Some(*from_player) {
+                if let Some(announcement) = legacy_announcement(data) {
+                    event = announcement;
+                }
+            }
+        }
+        if self.suppress_announcements && matches!(event, SignalFishEvent::Announcement { .. }) {
+            tracing::trace!("suppressed server announcement");
+            return;
+        }
+        if let SignalFishEvent::RoomJoinFailed {
+            requested_room_code,
+            ..
+        } = &mut event
+        {
+            *requested_room_code = self.pending_join.take().flatten();
+        }
+        if let SignalFishEvent::TimeSyncResponse {
+            client_sent_at_ms,
+            server_time_ms,
+            sample,
+        } = &mut event
+        {
+            *sample = Some(self.observe_time_sync(*client_sent_at_ms, *server_time_ms));
+        }
+        events.push(event);
+        events.extend(
+            gaps.into_iter()
+                .map(|gap| SignalFishEvent::MissedEventsGap {
+                    from: *gap.start(),
+                    to: *gap.end(),
+                }),
+        );
+    }
+
+    /// Record the sample a `TimeSyncResponse` yields and hand it to the
+    /// `sync_time()` callers waiting on that request.
+    fn observe_time_sync(&mut self, client_sent_at_ms: u64, server_time_ms: u64) -> TimeSyncResult {
+        let sample =
+            TimeSyncResult::from_exchange(client_sent_at_ms, server_time_ms, self.local_time_ms());
+        self.time_sync.record(sample);
+        #[cfg(feature = "tokio-runtime")]
+        for (_, waiter) in self
+            .time_sync_waiters
+            .extract_if(.., |(sent_at, _)| *sent_at == client_sent_at_ms)
+        {
+            let _ = waiter.send(sample);
+        }
+        sample
+    }
+
     fn observe_undecodable(&mut self, events: &mut Vec<SignalFishEvent>) -> bool {
         if let Err(diagnostic) = self.accountability.observe_server_message(false) {
             self.push_violation(events, diagnostic);
@@ -454,9 +2100,32 @@ impl ClientCore {
         }
     }
 
+    /// Feed `trigger` to the session machine. Rejected transitions leave the
+    /// state untouched and are only logged: the server is not allowed to panic
+    /// the client.
+    fn apply_trigger(&mut self, trigger: SessionTrigger) {
+        if let Err(error) = self.session.transition(trigger) {
+            tracing::warn!("ignoring unexpected session transition: {error}");
+        }
+    }
+
     fn update_state(&mut self, message: &ServerMessage) {
+        if let Some(trigger) = SessionTrigger::from_server_message(message) {
+            self.apply_trigger(trigger);
+        }
         match message {
-            ServerMessage::Authenticated { .. } => self.snapshot.authenticated = true,
+            ServerMessage::Authenticated {
+                app_name,
+                organization,
+                rate_limits,
+            } => {
+                self.authenticated = Some(AuthenticatedInfo {
+                    app_name: app_name.clone(),
+                    organization: organization.clone(),
+                    rate_limits: rate_limits.clone(),
+                });
+                self.authenticated_epoch = Some(self.snapshot.session_epoch);
+            }
             ServerMessage::Error {
                 message,
                 error_code,
@@ -470,6 +2139,8 @@ impl ClientCore {
                 });
             }
             ServerMessage::AuthenticationError { error, error_code } => {
+                self.authentication_rejected = Some(error_code.clone());
+                self.authenticated_epoch = None;
                 self.last_server_error = Some(ServerErrorInfo {
                     message: error.clone(),
                     error_code: Some(error_code.clone()),
@@ -479,23 +2150,85 @@ impl ClientCore {
                 self.snapshot.negotiated_protocol_version =
                     payload.protocol_version.filter(|version| *version >= 3);
                 self.protocol_info_seen = true;
+                self.server_limits = payload.limits;
+                self.player_name_rules = payload.player_name_rules.clone();
             }
             ServerMessage::RoomJoined(payload) => {
+                self.pending_join = None;
+                #[cfg(feature = "tokio-runtime")]
+                self.abandon_resume();
                 self.set_room(
                     payload.player_id,
                     payload.room_id,
                     payload.room_code.clone(),
                     payload.reconnection_token.clone(),
                 );
+                self.enter_room(RoomSnapshot {
+                    player_id: Some(payload.player_id),
+                    game_name: Some(payload.game_name.clone()),
+                    is_authority: payload.is_authority,
+                    spectators: payload.current_spectators.clone(),
+                    ..RoomSnapshot::from_room(
+                        payload.room_id,
+                        &payload.room_code,
+                        payload.max_players,
+                        &payload.lobby_state,
+                        &payload.current_players,
+                        &payload.ready_players,
+                    )
+                });
+            }
+            ServerMessage::RoomJoinFailed { .. } => {
+                // A failed join must never leave a half-applied room behind.
+                // Outside a room the room fields are already empty; enforce it
+                // rather than trust every earlier transition got it right.
+                let in_room = matches!(
+                    self.session.state(),
+                    SessionState::InRoom | SessionState::Spectating
+                );
+                if !in_room
+                    && (self.snapshot.room_id.is_some() || self.snapshot.room_code.is_some())
+                {
+                    tracing::warn!("clearing stale room state after RoomJoinFailed");
+                    self.clear_room(RoomLeaveReason::Removed);
+                }
+            }
+            ServerMessage::RoomLeft => self.clear_room(self.leave_reason()),
+            ServerMessage::ReconnectionFailed { .. } => {
+                self.pending_reconnect = None;
+                #[cfg(feature = "tokio-runtime")]
+                {
+                    self.abandon_resume();
+                    self.resume_ticket = None;
+                    self.ticket_watch.send_replace(None);
+                }
             }
-            ServerMessage::RoomLeft => self.clear_room(),
             ServerMessage::Reconnected(payload) => {
+                self.pending_reconnect = None;
+                #[cfg(feature = "tokio-runtime")]
+                {
+                    self.resume_held = false;
+                }
                 self.set_room(
                     payload.player_id,
                     payload.room_id,
                     payload.room_code.clone(),
                     payload.reconnection_token.clone(),
                 );
+                self.enter_room(RoomSnapshot {
+                    player_id: Some(payload.player_id),
+                    game_name: Some(payload.game_name.clone()),
+                    is_authority: payload.is_authority,
+                    spectators: payload.current_spectators.clone(),
+                    ..RoomSnapshot::from_room(
+                        payload.room_id,
+                        &payload.room_code,
+                        payload.max_players,
+                        &payload.lobby_state,
+                        &payload.current_players,
+                        &payload.ready_players,
+                    )
+                });
                 if let Some(version) =
                     crate::protocol::replayed_negotiated_version(&payload.missed_events)
                 {
@@ -510,8 +2243,86 @@ impl ClientCore {
                     payload.room_code.clone(),
                     None,
                 );
+                self.enter_room(RoomSnapshot {
+                    player_id: Some(payload.spectator_id),
+                    game_name: Some(payload.game_name.clone()),
+                    spectators: payload.current_spectators.clone(),
+                    ..RoomSnapshot::from_room(
+                        payload.room_id,
+                        &payload.room_code,
+                        0,
+                        &payload.lobby_state,
+                        &payload.current_players,
+                        &[],
+                    )
+                });
+                #[cfg(feature = "tokio-runtime")]
+                {
+                    self.spectated_game = Some(payload.game_name.clone());
+                }
+            }
+            ServerMessage::SpectatorLeft { .. } => self.clear_room(self.leave_reason()),
+            ServerMessage::PlayerJoined { player } => {
+                match self.room.player_mut(&player.id) {
+                    Some(existing) => *existing = player.clone(),
+                    None => self.room.players.push(player.clone()),
+                }
+                self.room_history.occupancy(self.room.players.len());
+            }
+            ServerMessage::PlayerLeft { player_id, .. } => {
+                self.room.players.retain(|p| p.id != *player_id);
+                if self.room.authority == Some(*player_id) {
+                    self.room.authority = None;
+                }
+            }
+            ServerMessage::PlayerReconnected {
+                player_id,
+                epoch: Some(epoch),
+            } => {
+                if let Some(player) = self.room.player_mut(player_id) {
+                    player.epoch = Some(*epoch);
+                }
+            }
+            ServerMessage::AuthorityChanged {
+                authority_player,
+                you_are_authority,
+            } => {
+                self.set_authority(*authority_player, *you_are_authority);
+            }
+            ServerMessage::AuthorityResponse { granted: true, .. } if self.room.is_in_room() => {
+                let local = self.room.player_id;
+                if self.authority_asked {
+                    self.set_authority(local, true);
+                } else {
+                    let holder = self.room.authority.filter(|holder| Some(*holder) != local);
+                    self.set_authority(holder, false);
+                }
+            }
+            ServerMessage::LobbyStateChanged {
+                lobby_state,
+                ready_players,
+                ..
+            } => {
+                for player in &mut self.room.players {
+                    player.is_ready = ready_players.contains(&player.id);
+                }
+                self.room.lobby_state = lobby_state.clone();
+                self.publish_lobby_state();
+            }
+            ServerMessage::NewSpectatorJoined {
+                spectator,
+                current_spectators,
+                ..
+            } => self.room.spectator_joined(spectator, current_spectators),
+            ServerMessage::SpectatorDisconnected { spectator_id, .. } => {
+                self.room.spectators.retain(|s| s.id != *spectator_id);
+            }
+            ServerMessage::Pong => {
+                let now = (self.clock)();
+                self.latency.pong_received(now);
+                #[cfg(feature = "tokio-runtime")]
+                self.observe_pong(now);
             }
-            ServerMessage::SpectatorLeft { .. } => self.clear_room(),
             ServerMessage::GameData { .. } | ServerMessage::GameDataBinary { .. } => {
                 self.stats.game_data_received = self.stats.game_data_received.saturating_add(1);
             }
@@ -531,12 +2342,123 @@ impl ClientCore {
         self.snapshot.room_code = Some(room_code);
         self.snapshot.reconnection_token = reconnection_token;
         self.snapshot.quarantined = false;
+        self.self_removal_pending = false;
+        self.suppress_late_room_left = false;
+        self.room_epoch = self.room_epoch.wrapping_add(1);
+        #[cfg(feature = "tokio-runtime")]
+        {
+            self.resume_ticket = None;
+        }
+    }
+
+    /// Install the snapshot of a room just entered and open its
+    /// [`RoomHistory`] session.
+    fn enter_room(&mut self, room: RoomSnapshot) {
+        if let (Some(room_id), Some(room_code)) = (room.room_id, room.room_code.as_deref()) {
+            let spectator = matches!(self.session.state(), SessionState::Spectating);
+            self.room_history.entered(
+                room_id,
+                room_code,
+                room.game_name.as_deref().unwrap_or_default(),
+                spectator,
+                self.local_time_ms(),
+            );
+            self.room_history.occupancy(room.players.len());
+        }
+        self.leave_requested = false;
+        self.set_room_snapshot(room);
+    }
+
+    /// How a server-confirmed departure ends the room session.
+    fn leave_reason(&self) -> RoomLeaveReason {
+        if self.leave_requested {
+            RoomLeaveReason::Left
+        } else {
+            RoomLeaveReason::Removed
+        }
+    }
+
+    fn set_authority(&mut self, authority_player: Option<PlayerId>, is_authority: bool) {
+        self.room.authority = authority_player;
+        self.room.is_authority = is_authority;
+        for player in &mut self.room.players {
+            player.is_authority = Some(player.id) == authority_player;
+        }
     }
 
-    fn clear_room(&mut self) {
+    fn set_room_snapshot(&mut self, room: RoomSnapshot) {
+        self.room = room;
+        self.publish_lobby_state();
+    }
+
+    fn publish_lobby_state(&self) {
+        #[cfg(feature = "tokio-runtime")]
+        self.lobby_watch.send_if_modified(|current| {
+            let changed = *current != self.room.lobby_state;
+            if changed {
+                *current = self.room.lobby_state.clone();
+            }
+            changed
+        });
+    }
+
+    fn clear_room(&mut self, reason: RoomLeaveReason) {
+        self.room_history.ended(reason, self.local_time_ms());
+        self.leave_requested = false;
+        #[cfg(feature = "tokio-runtime")]
+        {
+            self.spectated_game = None;
+        }
         self.snapshot.room_id = None;
         self.snapshot.room_code = None;
         self.snapshot.reconnection_token = None;
         self.snapshot.quarantined = false;
+        self.self_removal_pending = false;
+        self.set_room_snapshot(RoomSnapshot::default());
+        self.room_epoch = self.room_epoch.wrapping_add(1);
     }
 }
+
+/// Messages a deadline never drops: the handshake, liveness checks, and
+/// leaving, which matters however late it goes out.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) fn never_expires(kind: ClientMessageKind) -> bool {
+    matches!(
+        kind,
+        ClientMessageKind::Authenticate
+            | ClientMessageKind::Ping
+            | ClientMessageKind::LeaveRoom
+            | ClientMessageKind::LeaveSpectator
+    )
+}
+
+/// Events that only make sense in the room they arrived in.
+fn is_room_scoped(event: &SignalFishEvent) -> bool {
+    matches!(
+        event,
+        SignalFishEvent::PlayerJoined { .. }
+            | SignalFishEvent::PlayerLeft { .. }
+            | SignalFishEvent::GameData { .. }
+            | SignalFishEvent::GameDataBinary { .. }
+            | SignalFishEvent::LobbyStateChanged { .. }
+    )
+}
+
+/// The `Announcement` a legacy server relayed as `GameData` from its system
+/// player, or `None` if `data` does not have the legacy shape.
+fn legacy_announcement(data: &serde_json::Value) -> Option<SignalFishEvent> {
+    let message = data.get("message")?.as_str()?.to_owned();
+    let severity = match data.get("severity") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(severity) => Some(severity.as_str()?.to_owned()),
+    };
+    let metadata = data
+        .get("metadata")
+        .filter(|value| !value.is_null())
+        .cloned();
+    Some(SignalFishEvent::Announcement {
+        message,
+        severity,
+        metadata,
+    })
+}
@@ -0,0 +1,213 @@
+//! The configuration a client is actually running with.
+//!
+//! A client's behavior comes from several layers: built-in defaults, the
+//! [`SignalFishConfig`] it was started with, and what the server advertised or
+//! forced after connecting (the negotiated protocol version, rate limits, a
+//! game-data encoding downgrade), and any
+//! [`RuntimeConfigPatch`](crate::RuntimeConfigPatch) applied since. [`EffectiveConfig`] resolves them into one
+//! serializable snapshot in which every value records its [`ConfigSource`],
+//! ready to paste into a bug report.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::protocol::{GameDataEncoding, RateLimitInfo};
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+use crate::SignalFishConfig;
+
+/// Where an [`EffectiveConfig`] value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ConfigSource {
+    /// The built-in default; the configuration left it alone or set it to
+    /// the default value.
+    Default,
+    /// The [`SignalFishConfig`] the client was started with.
+    Config,
+    /// Advertised or imposed by the server after connecting.
+    Server,
+    /// Changed on the running client by a
+    /// [`RuntimeConfigPatch`](crate::RuntimeConfigPatch).
+    Runtime,
+}
+
+/// A resolved value and its provenance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Sourced<T> {
+    /// The value in effect.
+    pub value: T,
+    /// The layer that supplied it.
+    pub source: ConfigSource,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl<T: PartialEq> Sourced<T> {
+    /// `configured`, attributed to [`ConfigSource::Default`] when it equals
+    /// `default` and to [`ConfigSource::Config`] otherwise.
+    fn layered(configured: T, default: &T) -> Self {
+        let source = if configured == *default {
+            ConfigSource::Default
+        } else {
+            ConfigSource::Config
+        };
+        Self {
+            value: configured,
+            source,
+        }
+    }
+
+    fn server(value: T) -> Self {
+        Self {
+            value,
+            source: ConfigSource::Server,
+        }
+    }
+
+    fn runtime(value: T) -> Self {
+        Self {
+            value,
+            source: ConfigSource::Runtime,
+        }
+    }
+}
+
+/// Snapshot of the resolved settings, returned by `effective_config()` on
+/// either client.
+///
+/// Driver-specific settings are reported as configured even on the driver
+/// that ignores them: the polling client has no event channel and never
+/// debounces or schedules time syncs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveConfig {
+    /// Capacity of each async event channel. Events are never dropped on
+    /// overflow; a full channel pauses the transport loop.
+    pub event_channel_capacity: Sourced<usize>,
+    /// Capacity of the outgoing command queue.
+    pub command_channel_capacity: Sourced<usize>,
+    /// Deadline for graceful shutdown and transport close.
+    pub shutdown_timeout: Sourced<Duration>,
+    /// Whether `PlayerAnnouncement`s are dropped before emission.
+    pub suppress_announcements: Sourced<bool>,
+    /// `LobbyStateChanged` coalescing window, if any.
+    pub lobby_state_debounce: Sourced<Option<Duration>>,
+    /// Automatic ping cadence, if any.
+    pub ping_interval: Sourced<Option<Duration>>,
+    /// Longest wait for a `Pong` before the connection is closed, if bounded.
+    pub pong_timeout: Sourced<Option<Duration>>,
+    /// Automatic time-sync cadence, if any.
+    pub time_sync_interval: Sourced<Option<Duration>>,
+    /// Samples kept for `server_time_estimate()`.
+    pub time_sync_samples: Sourced<usize>,
+    /// Depth of the off-loop decode queue; `None` parses frames inline.
+    pub decode_pipeline: Sourced<Option<usize>>,
+    /// Response to every event receiver being dropped.
+    pub on_receiver_dropped: Sourced<crate::OnReceiverDropped>,
+    /// Longest a drain waits for the current room to end, if bounded.
+    pub drain_timeout: Sourced<Option<Duration>>,
+    /// Game-data encoding in use. [`ConfigSource::Server`] means the server
+    /// rejected the configured format and the client fell back to JSON.
+    pub game_data_encoding: Sourced<GameDataEncoding>,
+    /// Protocol version in effect: the negotiated v3-or-newer version once
+    /// the server has answered ([`ConfigSource::Server`]), otherwise the
+    /// highest version advertised. `None` is the v2 relay floor.
+    pub protocol_version: Sourced<Option<u16>>,
+    /// The application's rate limits, once authentication has reported them.
+    pub rate_limits: Sourced<Option<RateLimitInfo>>,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl EffectiveConfig {
+    /// The configuration-only layers, before any server input.
+    pub(crate) fn from_config(config: &SignalFishConfig) -> Self {
+        let defaults = SignalFishConfig::new(config.app_id.clone());
+        Self {
+            event_channel_capacity: Sourced::layered(
+                config.event_channel_capacity.max(1),
+                &defaults.event_channel_capacity,
+            ),
+            command_channel_capacity: Sourced::layered(
+                config.command_channel_capacity.max(1),
+                &defaults.command_channel_capacity,
+            ),
+            shutdown_timeout: Sourced::layered(config.shutdown_timeout, &defaults.shutdown_timeout),
+            suppress_announcements: Sourced::layered(
+                config.suppress_announcements,
+                &defaults.suppress_announcements,
+            ),
+            lobby_state_debounce: Sourced::layered(
+                config
+                    .lobby_state_debounce
+                    .filter(|window| !window.is_zero()),
+                &defaults.lobby_state_debounce,
+            ),
+            ping_interval: Sourced::layered(
+                config.ping_interval.filter(|interval| !interval.is_zero()),
+                &defaults.ping_interval,
+            ),
+            pong_timeout: Sourced::layered(
+                config.pong_timeout.filter(|timeout| !timeout.is_zero()),
+                &defaults.pong_timeout,
+            ),
+            time_sync_interval: Sourced::layered(
+                config
+                    .time_sync_interval
+                    .filter(|interval| !interval.is_zero()),
+                &defaults.time_sync_interval,
+            ),
+            time_sync_samples: Sourced::layered(
+                config.time_sync_samples.max(1),
+                &defaults.time_sync_samples,
+            ),
+            decode_pipeline: Sourced::layered(
+                config.decode_pipeline.map(|depth| depth.max(1)),
+                &defaults.decode_pipeline,
+            ),
+            on_receiver_dropped: Sourced::layered(
+                config.on_receiver_dropped,
+                &defaults.on_receiver_dropped,
+            ),
+            drain_timeout: Sourced::layered(config.drain_timeout, &defaults.drain_timeout),
+            game_data_encoding: Sourced::layered(
+                config.game_data_format.unwrap_or_default(),
+                &defaults.game_data_format.unwrap_or_default(),
+            ),
+            protocol_version: Sourced::layered(config.protocol_version, &defaults.protocol_version),
+            rate_limits: Sourced::layered(None, &None),
+        }
+    }
+
+    /// Overlay what the server has said so far.
+    pub(crate) fn with_server(
+        mut self,
+        game_data_encoding: GameDataEncoding,
+        negotiated_protocol_version: Option<Option<u16>>,
+        rate_limits: Option<&RateLimitInfo>,
+    ) -> Self {
+        if game_data_encoding != self.game_data_encoding.value {
+            self.game_data_encoding = Sourced::server(game_data_encoding);
+        }
+        if let Some(version) = negotiated_protocol_version {
+            self.protocol_version = Sourced::server(version);
+        }
+        if let Some(rate_limits) = rate_limits {
+            self.rate_limits = Sourced::server(Some(rate_limits.clone()));
+        }
+        self
+    }
+
+    /// Record the settings a runtime patch changed.
+    pub(crate) fn apply_runtime(&mut self, patch: &crate::RuntimeConfigPatch) {
+        if let Some(suppress) = patch.suppress_announcements {
+            self.suppress_announcements = Sourced::runtime(suppress);
+        }
+        if let Some(window) = patch.lobby_state_debounce {
+            self.lobby_state_debounce = Sourced::runtime(window);
+        }
+        if let Some(interval) = patch.ping_interval {
+            self.ping_interval = Sourced::runtime(interval);
+        }
+        if let Some(interval) = patch.time_sync_interval {
+            self.time_sync_interval = Sourced::runtime(interval);
+        }
+    }
+}
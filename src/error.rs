@@ -4,7 +4,23 @@ use crate::error_codes::ErrorCode;
 use thiserror::Error;
 
 /// Errors that can occur when using the Signal Fish client.
+///
+/// The enum is `#[non_exhaustive]`: handle the variants you act on and give
+/// the rest a wildcard arm.
+///
+/// ```rust
+/// use signal_fish_client::SignalFishError;
+///
+/// fn retryable(error: &SignalFishError) -> bool {
+///     match error {
+///         SignalFishError::Timeout | SignalFishError::TransportClosed => true,
+///         _ => false,
+///     }
+/// }
+/// assert!(retryable(&SignalFishError::Timeout));
+/// ```
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum SignalFishError {
     /// Failed to send a message through the transport.
     #[error("transport send error: {0}")]
@@ -18,7 +34,9 @@ pub enum SignalFishError {
     #[error("transport connection closed")]
     TransportClosed,
 
-    /// Failed to serialize or deserialize a protocol message.
+    /// Failed to serialize or deserialize a protocol message, including a
+    /// [`send_game_data_as`](crate::SignalFishClient::send_game_data_as) value
+    /// that does not serialize.
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -88,6 +106,128 @@ pub enum SignalFishError {
     )]
     BinaryFormatNotNegotiated,
 
+    /// A [`RuntimeConfigPatch`](crate::RuntimeConfigPatch) changes settings
+    /// that only take effect on a new client; nothing was applied.
+    #[error("not tunable on a running client: {}", fields.join(", "))]
+    NotRuntimeTunable {
+        /// The changed [`SignalFishConfig`](crate::SignalFishConfig) fields.
+        fields: Vec<&'static str>,
+    },
+
+    /// [`JoinRoomParams::max_players`](crate::JoinRoomParams::max_players)
+    /// is above the server's advertised
+    /// [`ServerLimits::max_players_per_room`](crate::protocol::ServerLimits::max_players_per_room);
+    /// nothing was sent.
+    #[error("max_players {requested} exceeds this server's limit of {limit} players per room")]
+    MaxPlayersExceeded {
+        /// The requested `max_players`.
+        requested: u8,
+        /// The server's cap.
+        limit: u32,
+    },
+
+    /// [`JoinRoomParams::player_name`](crate::JoinRoomParams::player_name)
+    /// breaks the server's advertised
+    /// [`player_name_rules`](crate::protocol::ProtocolInfoPayload::player_name_rules);
+    /// nothing was sent.
+    #[error("player name rejected by this server's rules: {0}")]
+    InvalidPlayerName(crate::protocol::PlayerNameViolation),
+
+    /// The client is draining after
+    /// [`begin_drain`](crate::SignalFishClient::begin_drain) and takes no new
+    /// rooms; nothing was sent.
+    #[error("client is draining and accepts no new rooms")]
+    Draining,
+
+    /// A `Reconnect` sent earlier is still waiting for the server's
+    /// [`Reconnected`](crate::SignalFishEvent::Reconnected) or
+    /// [`ReconnectionFailed`](crate::SignalFishEvent::ReconnectionFailed);
+    /// nothing was sent.
+    ///
+    /// The attempt stops counting once it is answered, the connection ends,
+    /// or
+    /// [`reconnect_answer_timeout`](crate::SignalFishConfig::reconnect_answer_timeout)
+    /// passes.
+    /// [`reconnect_and_wait`](crate::SignalFishClient::reconnect_and_wait)
+    /// for the same room waits on that attempt instead.
+    #[error("a reconnect is already waiting for the server's answer")]
+    ReconnectInFlight,
+
+    /// A transport, typically from a
+    /// [`TransportFactory`](crate::TransportFactory), had already carried a
+    /// client session: its
+    /// [`claim_session`](crate::Transport::claim_session) returned `false`.
+    /// Nothing was sent on it. Reported as the detail of
+    /// [`DisconnectReason::HandshakeRefused`](crate::DisconnectReason::HandshakeRefused).
+    #[error("the transport already carried a client session")]
+    TransportReused,
+
+    /// The server refused a
+    /// [`join_room_and_wait`](crate::SignalFishClient::join_room_and_wait)
+    /// with `RoomJoinFailed`.
+    #[error("joining the room failed: {reason}")]
+    RoomJoinFailed {
+        /// The server's explanation.
+        reason: String,
+        /// Structured reason, such as [`ErrorCode::RoomFull`], if the
+        /// server sent one.
+        error_code: Option<ErrorCode>,
+    },
+
+    /// The server rejected the app ID while
+    /// [`wait_authenticated`](crate::SignalFishClient::wait_authenticated)
+    /// was waiting.
+    #[error("authentication failed: {error}")]
+    AuthenticationFailed {
+        /// The server's explanation.
+        error: String,
+        /// Structured reason, such as [`ErrorCode::InvalidAppId`].
+        error_code: ErrorCode,
+    },
+
+    /// A message is longer than the transport allows, inbound or outbound.
+    ///
+    /// Raised by [`TcpTransport`](crate::TcpTransport), whose
+    /// [`with_max_line_length`](crate::TcpTransport::with_max_line_length)
+    /// sets the limit so a peer cannot grow the receive buffer without bound.
+    /// An inbound oversized line ends the connection; an outbound frame is
+    /// refused and the connection stays up.
+    #[error("message exceeds the {limit}-byte limit")]
+    MessageTooLarge {
+        /// The limit, in bytes, excluding the line terminator.
+        limit: usize,
+    },
+
+    /// A WebSocket handshake request could not be built from the URL,
+    /// headers, and query parameters given to a
+    /// [`WebSocketTransportBuilder`](crate::WebSocketTransportBuilder);
+    /// nothing was dialed.
+    #[error("invalid WebSocket handshake request: {0}")]
+    InvalidRequest(String),
+
+    /// The TLS handshake of a `wss://` connection failed: the server's
+    /// certificate is not trusted or does not name the host, the peer does
+    /// not speak TLS, or the negotiation broke off.
+    ///
+    /// Distinct from [`Io`](Self::Io) so an untrusted certificate is not
+    /// retried like an unreachable host. The source is the handshake's I/O
+    /// error; for a certificate rejection it wraps the `rustls::Error`.
+    #[error("TLS handshake failed: {0}")]
+    TlsHandshake(#[source] std::io::Error),
+
+    /// Opening a transport did not finish within its connect timeout, set
+    /// with
+    /// [`WebSocketTransport::connect_with_timeout`](crate::WebSocketTransport::connect_with_timeout)
+    /// or
+    /// [`WebSocketTransportBuilder::with_connect_timeout`](crate::WebSocketTransportBuilder::with_connect_timeout).
+    ///
+    /// The attempt was abandoned: nothing is left dialing in the background.
+    #[error("connecting timed out after {elapsed:?}")]
+    ConnectTimeout {
+        /// How long the attempt ran before it was abandoned.
+        elapsed: std::time::Duration,
+    },
+
     /// An operation timed out.
     #[error("operation timed out")]
     Timeout,
@@ -97,6 +237,38 @@ pub enum SignalFishError {
     Io(#[from] std::io::Error),
 }
 
+/// Why `SignalFishClient::promote_to_player` did not end with the client in
+/// the room as a player.
+///
+/// Each variant names the step that failed, and so where the client was
+/// left.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PromotionError {
+    /// The client was not spectating a room; nothing was sent.
+    #[error("cannot promote to player: not spectating a room")]
+    NotSpectating,
+
+    /// `LeaveSpectator` could not be sent or the server answered it with an
+    /// error. The client is still spectating, unless the error is
+    /// [`SignalFishError::NotConnected`].
+    #[error("leaving spectator mode failed: {0}")]
+    Leave(#[source] SignalFishError),
+
+    /// The spectator seat was given up but `JoinRoom` failed — for example
+    /// [`ErrorCode::RoomFull`] when the room filled in the gap. The client is
+    /// in no room.
+    #[error("joining room {room_code} as a player failed: {error}")]
+    Join {
+        /// The room the client was spectating.
+        room_code: String,
+        /// Why the join failed; a server rejection is
+        /// [`SignalFishError::ServerError`].
+        #[source]
+        error: SignalFishError,
+    },
+}
+
 /// A specialized [`Result`] type for Signal Fish client operations.
 pub type Result<T> = std::result::Result<T, SignalFishError>;
 
@@ -2,18 +2,22 @@
 //!
 //! These codes are wire-compatible with the server's `ErrorCode` enum and
 //! serialize using `SCREAMING_SNAKE_CASE` to match the server's JSON format.
+//! A token this SDK does not know decodes as [`ErrorCode::Unknown`], so a
+//! newer server's codes never fail the enclosing message.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
 use std::fmt;
+use std::sync::Arc;
 
 /// Structured error codes returned by the Signal Fish server.
 ///
 /// Each variant corresponds to a specific error condition. The server sends these
 /// as `"SCREAMING_SNAKE_CASE"` strings (e.g., `"ROOM_NOT_FOUND"`).
 ///
-/// Use [`description()`](ErrorCode::description) for a human-readable explanation.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// Use [`description()`](ErrorCode::description) for a human-readable explanation,
+/// or [`ErrorCodeFormatter`] to substitute localized text.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ErrorCode {
     // Authentication errors
     Unauthorized,
@@ -103,9 +107,139 @@ pub enum ErrorCode {
     ServerDraining,
     /// The requested protocol-v3 delivery class/key combination is invalid.
     InvalidDeliveryClass,
+
+    /// A code this SDK does not recognize, sent by a newer server; holds the
+    /// wire token as received and serializes back to it.
+    Unknown(String),
 }
 
+/// Every known [`ErrorCode`] variant, in declaration order.
+const ALL: &[ErrorCode] = &[
+    ErrorCode::Unauthorized,
+    ErrorCode::InvalidToken,
+    ErrorCode::AuthenticationRequired,
+    ErrorCode::InvalidAppId,
+    ErrorCode::AppIdExpired,
+    ErrorCode::AppIdRevoked,
+    ErrorCode::AppIdSuspended,
+    ErrorCode::MissingAppId,
+    ErrorCode::AuthenticationTimeout,
+    ErrorCode::SdkVersionUnsupported,
+    ErrorCode::UnsupportedGameDataFormat,
+    ErrorCode::InvalidInput,
+    ErrorCode::InvalidGameName,
+    ErrorCode::InvalidRoomCode,
+    ErrorCode::InvalidPlayerName,
+    ErrorCode::InvalidMaxPlayers,
+    ErrorCode::MessageTooLarge,
+    ErrorCode::RoomNotFound,
+    ErrorCode::RoomFull,
+    ErrorCode::AlreadyInRoom,
+    ErrorCode::NotInRoom,
+    ErrorCode::RoomCreationFailed,
+    ErrorCode::MaxRoomsPerGameExceeded,
+    ErrorCode::InvalidRoomState,
+    ErrorCode::AuthorityNotSupported,
+    ErrorCode::AuthorityConflict,
+    ErrorCode::AuthorityDenied,
+    ErrorCode::RateLimitExceeded,
+    ErrorCode::TooManyConnections,
+    ErrorCode::ReconnectionFailed,
+    ErrorCode::ReconnectionTokenInvalid,
+    ErrorCode::ReconnectionExpired,
+    ErrorCode::PlayerAlreadyConnected,
+    ErrorCode::SpectatorNotAllowed,
+    ErrorCode::TooManySpectators,
+    ErrorCode::NotASpectator,
+    ErrorCode::SpectatorJoinFailed,
+    ErrorCode::InternalError,
+    ErrorCode::StorageError,
+    ErrorCode::ServiceUnavailable,
+    ErrorCode::GameStartNotReady,
+    ErrorCode::GameStartForbidden,
+    ErrorCode::CrossRoomSignal,
+    ErrorCode::UnsupportedTransport,
+    ErrorCode::SignalTargetNotFound,
+    ErrorCode::SignalRateLimited,
+    ErrorCode::SignalTooLarge,
+    ErrorCode::ConnectionIdleTimeout,
+    ErrorCode::SlowConsumer,
+    ErrorCode::ActivityTimeout,
+    ErrorCode::ServerDraining,
+    ErrorCode::InvalidDeliveryClass,
+];
+
 impl ErrorCode {
+    /// Every known variant, in declaration order; [`Unknown`](Self::Unknown)
+    /// is not listed.
+    ///
+    /// Lets applications validate an external table keyed by error code (for
+    /// example a localization catalog) against the full set at test time.
+    pub fn all() -> &'static [ErrorCode] {
+        ALL
+    }
+
+    /// Stable lookup key for this code: its `SCREAMING_SNAKE_CASE` wire token
+    /// (e.g. `"ROOM_NOT_FOUND"`), or the received token for
+    /// [`Unknown`](Self::Unknown).
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Unauthorized => "UNAUTHORIZED",
+            Self::InvalidToken => "INVALID_TOKEN",
+            Self::AuthenticationRequired => "AUTHENTICATION_REQUIRED",
+            Self::InvalidAppId => "INVALID_APP_ID",
+            Self::AppIdExpired => "APP_ID_EXPIRED",
+            Self::AppIdRevoked => "APP_ID_REVOKED",
+            Self::AppIdSuspended => "APP_ID_SUSPENDED",
+            Self::MissingAppId => "MISSING_APP_ID",
+            Self::AuthenticationTimeout => "AUTHENTICATION_TIMEOUT",
+            Self::SdkVersionUnsupported => "SDK_VERSION_UNSUPPORTED",
+            Self::UnsupportedGameDataFormat => "UNSUPPORTED_GAME_DATA_FORMAT",
+            Self::InvalidInput => "INVALID_INPUT",
+            Self::InvalidGameName => "INVALID_GAME_NAME",
+            Self::InvalidRoomCode => "INVALID_ROOM_CODE",
+            Self::InvalidPlayerName => "INVALID_PLAYER_NAME",
+            Self::InvalidMaxPlayers => "INVALID_MAX_PLAYERS",
+            Self::MessageTooLarge => "MESSAGE_TOO_LARGE",
+            Self::RoomNotFound => "ROOM_NOT_FOUND",
+            Self::RoomFull => "ROOM_FULL",
+            Self::AlreadyInRoom => "ALREADY_IN_ROOM",
+            Self::NotInRoom => "NOT_IN_ROOM",
+            Self::RoomCreationFailed => "ROOM_CREATION_FAILED",
+            Self::MaxRoomsPerGameExceeded => "MAX_ROOMS_PER_GAME_EXCEEDED",
+            Self::InvalidRoomState => "INVALID_ROOM_STATE",
+            Self::AuthorityNotSupported => "AUTHORITY_NOT_SUPPORTED",
+            Self::AuthorityConflict => "AUTHORITY_CONFLICT",
+            Self::AuthorityDenied => "AUTHORITY_DENIED",
+            Self::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            Self::TooManyConnections => "TOO_MANY_CONNECTIONS",
+            Self::ReconnectionFailed => "RECONNECTION_FAILED",
+            Self::ReconnectionTokenInvalid => "RECONNECTION_TOKEN_INVALID",
+            Self::ReconnectionExpired => "RECONNECTION_EXPIRED",
+            Self::PlayerAlreadyConnected => "PLAYER_ALREADY_CONNECTED",
+            Self::SpectatorNotAllowed => "SPECTATOR_NOT_ALLOWED",
+            Self::TooManySpectators => "TOO_MANY_SPECTATORS",
+            Self::NotASpectator => "NOT_A_SPECTATOR",
+            Self::SpectatorJoinFailed => "SPECTATOR_JOIN_FAILED",
+            Self::InternalError => "INTERNAL_ERROR",
+            Self::StorageError => "STORAGE_ERROR",
+            Self::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            Self::GameStartNotReady => "GAME_START_NOT_READY",
+            Self::GameStartForbidden => "GAME_START_FORBIDDEN",
+            Self::CrossRoomSignal => "CROSS_ROOM_SIGNAL",
+            Self::UnsupportedTransport => "UNSUPPORTED_TRANSPORT",
+            Self::SignalTargetNotFound => "SIGNAL_TARGET_NOT_FOUND",
+            Self::SignalRateLimited => "SIGNAL_RATE_LIMITED",
+            Self::SignalTooLarge => "SIGNAL_TOO_LARGE",
+            Self::ConnectionIdleTimeout => "CONNECTION_IDLE_TIMEOUT",
+            Self::SlowConsumer => "SLOW_CONSUMER",
+            Self::ActivityTimeout => "ACTIVITY_TIMEOUT",
+            Self::ServerDraining => "SERVER_DRAINING",
+            Self::InvalidDeliveryClass => "INVALID_DELIVERY_CLASS",
+            Self::Unknown(token) => token,
+        }
+    }
+
     /// Returns a human-readable description of this error code.
     ///
     /// This method provides actionable error messages that SDK developers
@@ -291,12 +425,82 @@ impl ErrorCode {
             Self::InvalidDeliveryClass => {
                 "The requested game-data delivery class and key combination is invalid. Latest requires a key; reliable and volatile forbid one."
             }
+
+            Self::Unknown(_) => {
+                "The server reported an error this SDK version does not recognize. Check the error message for details."
+            }
         }
     }
 }
 
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = String::deserialize(deserializer)?;
+        Ok(ALL
+            .iter()
+            .find(|code| code.key() == token)
+            .cloned()
+            .unwrap_or(Self::Unknown(token)))
+    }
+}
+
 impl fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.description())
     }
 }
+
+/// Host-supplied override for error-code descriptions, e.g. localized text.
+///
+/// Install it with
+/// [`SignalFishConfig::with_error_code_formatter`](crate::SignalFishConfig::with_error_code_formatter)
+/// and render codes through `describe_error_code()` on either client. The
+/// closure returns `None` to fall back to the built-in English
+/// [`ErrorCode::description`]. [`Display`](fmt::Display) for `ErrorCode` is
+/// global and always uses the built-in text.
+///
+/// ```
+/// use signal_fish_client::{ErrorCode, ErrorCodeFormatter};
+///
+/// let formatter = ErrorCodeFormatter::new(|code| match code.key() {
+///     "ROOM_FULL" => Some("La salle est pleine.".to_string()),
+///     _ => None,
+/// });
+/// assert_eq!(formatter.describe(&ErrorCode::RoomFull), "La salle est pleine.");
+/// assert_eq!(
+///     formatter.describe(&ErrorCode::RoomNotFound),
+///     ErrorCode::RoomNotFound.description()
+/// );
+/// ```
+#[derive(Clone)]
+pub struct ErrorCodeFormatter {
+    format: Arc<FormatFn>,
+}
+
+type FormatFn = dyn Fn(&ErrorCode) -> Option<String> + Send + Sync;
+
+impl ErrorCodeFormatter {
+    /// Wrap a closure mapping a code to its override text.
+    pub fn new(format: impl Fn(&ErrorCode) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self {
+            format: Arc::new(format),
+        }
+    }
+
+    /// The override for `code`, or its built-in description.
+    pub fn describe(&self, code: &ErrorCode) -> Cow<'static, str> {
+        (self.format)(code).map_or(Cow::Borrowed(code.description()), Cow::Owned)
+    }
+}
+
+impl fmt::Debug for ErrorCodeFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorCodeFormatter").finish_non_exhaustive()
+    }
+}
@@ -1,8 +1,8 @@
 //! High-level events emitted by the Signal Fish client.
 //!
 //! [`SignalFishEvent`] provides a 1:1 mapping from every [`ServerMessage`] variant
-//! plus three synthetic events (`Connected`, `Disconnected`, and `DecodeFailed`)
-//! that originate from the transport layer rather than the server.
+//! plus synthetic events (`Connected`, `Disconnected`, `DecodeFailed`, and
+//! friends) that originate in the client rather than directly from the server.
 //!
 //! Boxed payload types ([`RoomJoinedPayload`], [`ReconnectedPayload`],
 //! [`SpectatorJoinedPayload`]) are flattened into inline fields so callers can
@@ -15,10 +15,10 @@
 
 use crate::error_codes::ErrorCode;
 use crate::protocol::{
-    DeliveryClass, DeliveryReportPayload, GameDataEncoding, IceServer, LobbyState,
-    PeerConnectionInfo, PlayerId, PlayerInfo, ProtocolInfoPayload, RateLimitInfo, ReplayStatus,
-    RoomId, SenderWatermark, ServerMessage, SessionPeer, SpectatorInfo, SpectatorStateChangeReason,
-    Topology, TransportKind,
+    ClientMessageKind, DeliveryClass, DeliveryReportPayload, GameDataEncoding, IceServer,
+    LobbyState, PeerConnectionInfo, PlayerId, PlayerInfo, ProtocolInfoPayload, RateLimitInfo,
+    ReplayStatus, RoomId, SenderWatermark, ServerMessage, SessionPeer, SpectatorInfo,
+    SpectatorStateChangeReason, Topology, TransportKind,
 };
 
 /// Events emitted by the Signal Fish client.
@@ -32,20 +32,39 @@ use crate::protocol::{
 /// |---|---|
 /// | [`Connected`](Self::Connected) | Transport layer opened successfully |
 /// | [`Disconnected`](Self::Disconnected) | Transport layer closed or errored |
+/// | [`Reconnecting`](Self::Reconnecting) | A reconnecting client lost its transport and will dial another |
+/// | [`ClientStalled`](Self::ClientStalled) | The async client's transport loop stopped making progress |
+/// | [`MessageExpired`](Self::MessageExpired) | A queued message passed its deadline and was dropped unsent |
 /// | [`DecodeFailed`](Self::DecodeFailed) | An inbound frame could not be decoded |
+/// | [`UnknownMessage`](Self::UnknownMessage) | An undecodable message had a `type` tag, with `forward_unknown_messages` set |
+/// | [`SessionInvalidated`](Self::SessionInvalidated) | An authentication failure discarded the room session |
+/// | [`RemovedFromRoom`](Self::RemovedFromRoom) | The server removed this client and no `RoomLeft` followed |
+/// | [`SelfRemovalPending`](Self::SelfRemovalPending) | The server announced this client's removal; awaiting `RoomLeft` |
+/// | [`SessionInconsistency`](Self::SessionInconsistency) | A server message named a different room than the session |
+/// | [`OrganizationMismatch`](Self::OrganizationMismatch) | `Authenticated` named an organization other than the expected one |
+/// | [`FieldOutOfRange`](Self::FieldOutOfRange) | A server message carried a number its field cannot hold |
+/// | [`PayloadSalvaged`](Self::PayloadSalvaged) | A malformed part of a room payload was dropped so the rest applied |
+/// | [`MissedEventsGap`](Self::MissedEventsGap) | A `Reconnected` replay skipped sequence numbers |
 ///
 /// # Example
 ///
-/// ```text
-/// // Assuming `events` is an async receiver of SignalFishEvent:
-/// match event {
-///     SignalFishEvent::RoomJoined { room_code, current_players, .. } => { /* … */ }
-///     SignalFishEvent::PlayerJoined { player } => { /* … */ }
-///     SignalFishEvent::Disconnected { reason, .. } => { /* … */ }
-///     _ => {}
+/// ```rust
+/// use signal_fish_client::SignalFishEvent;
+///
+/// fn handle(event: SignalFishEvent) {
+///     match event {
+///         SignalFishEvent::RoomJoined { room_code, current_players, .. } => { /* … */ }
+///         SignalFishEvent::PlayerJoined { player } => { /* … */ }
+///         SignalFishEvent::Disconnected { reason, .. } => { /* … */ }
+///         _ => {}
+///     }
 /// }
 /// ```
+///
+/// The enum is `#[non_exhaustive]`: new events arrive in minor releases, so a
+/// `match` outside this crate needs a wildcard arm.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum SignalFishEvent {
     // ── Synthetic events ────────────────────────────────────────────
     /// The client has started and will begin communicating with the server.
@@ -54,7 +73,9 @@ pub enum SignalFishEvent {
     ///
     /// - **`SignalFishClient`** (async): emitted at the start of the transport
     ///   loop, after the transport has already been connected via
-    ///   `.connect().await`.
+    ///   `.connect().await`. A client started with
+    ///   [`start_with_reconnect`](crate::SignalFishClient::start_with_reconnect)
+    ///   emits it again for every new transport.
     /// - **`SignalFishPollingClient`**: emitted once
     ///   [`Transport::is_ready()`](crate::Transport::is_ready) returns `true`
     ///   during a [`poll()`](crate::SignalFishPollingClient::poll) cycle. For
@@ -66,12 +87,8 @@ pub enum SignalFishEvent {
 
     /// The transport connection was closed.
     Disconnected {
-        /// Human-readable reason for the disconnection, if available.
-        ///
-        /// When the transport captured a WebSocket Close frame with a reason
-        /// (see [`Transport::close_info`](crate::Transport::close_info)),
-        /// it is included here as `"closed by server: …"`.
-        reason: Option<String>,
+        /// Why the connection ended; `Display` gives the text for logs.
+        reason: DisconnectReason,
         /// The most recent `Error`/`AuthenticationError` received on this
         /// connection, if any.
         ///
@@ -84,14 +101,67 @@ pub enum SignalFishEvent {
         last_server_error: Option<ServerErrorInfo>,
     },
 
+    /// A client started with
+    /// [`start_with_reconnect`](crate::SignalFishClient::start_with_reconnect)
+    /// lost its transport and will dial a new one after `delay`.
+    ///
+    /// This is a **synthetic event**. The session ended with the transport
+    /// (room, authentication, and pending requests are cleared as for
+    /// [`Disconnected`](Self::Disconnected)); `Connected` and, once the
+    /// server accepts the new `Authenticate`, `Authenticated` follow a
+    /// successful dial. A failed dial emits the next `Reconnecting`. When
+    /// [`ReconnectPolicy::max_attempts`](crate::ReconnectPolicy::max_attempts)
+    /// runs out, `Disconnected` ends the client instead.
+    Reconnecting {
+        /// Attempt number within this outage, starting at 1.
+        attempt: u32,
+        /// Backoff before the attempt dials.
+        delay: std::time::Duration,
+    },
+
+    /// The async client's transport loop has been stuck in one step for
+    /// longer than
+    /// [`loop_watchdog`](crate::SignalFishConfig::loop_watchdog) — an
+    /// `await` that never completes, such as a transport send that never
+    /// returns.
+    ///
+    /// This is a **synthetic event** emitted by the watchdog task, once per
+    /// stall, through a slot it keeps reserved in each receiver the client
+    /// started with, so a full channel cannot hold it back; receivers added
+    /// with `subscribe` do not get it. With
+    /// [`restart_stalled_loop`](crate::SignalFishConfig::restart_stalled_loop)
+    /// the stalled connection is abandoned next, ending with
+    /// [`DisconnectReason::LoopStalled`]. A loop waiting on a receiver with
+    /// no capacity is not stalled.
+    ClientStalled {
+        /// When the loop last made progress.
+        since: crate::time::Instant,
+    },
+
+    /// A queued message waited past its deadline and was dropped instead of
+    /// sent.
+    ///
+    /// This is a **synthetic event** from the async client, emitted when the
+    /// transport loop takes the expired message off its queue, for a
+    /// deadline from
+    /// [`message_deadlines`](crate::SignalFishConfig::message_deadlines) or
+    /// [`send_game_data_with_deadline`](crate::SignalFishClient::send_game_data_with_deadline).
+    /// Each one is also counted in
+    /// [`ClientStats::messages_expired`](crate::ClientStats::messages_expired).
+    MessageExpired {
+        /// Kind of the dropped message.
+        kind: ClientMessageKind,
+    },
+
     /// An inbound server frame could not be decoded into a
     /// [`ServerMessage`].
     ///
     /// This is a **synthetic event**. The connection stays open and later
     /// frames are unaffected. Typical causes: a server newer than this SDK
-    /// (an unknown message `type`, or an unknown `error_code` string inside
-    /// an otherwise-known message), a proxy injecting non-protocol frames,
-    /// or payload corruption.
+    /// (an unknown message `type`, or a new field shape inside an
+    /// otherwise-known message), a proxy injecting non-protocol frames, or
+    /// payload corruption. Unknown `error_code` strings and unknown values
+    /// of the catch-all protocol enums decode without failing.
     ///
     /// Every undecodable frame processed during normal operation produces
     /// exactly one `DecodeFailed` event (no coalescing) and increments the
@@ -104,10 +174,8 @@ pub enum SignalFishEvent {
     DecodeFailed {
         /// The wire `type` tag, when the frame was valid JSON with one.
         ///
-        /// `Some("Error")` plus a decode failure strongly implies an
-        /// `error_code` string this SDK does not know; any other
-        /// `Some(...)` implies an unknown or malformed message type;
-        /// `None` implies the frame was not valid JSON at all.
+        /// `Some(...)` implies an unknown or malformed message of that
+        /// type; `None` implies the frame was not valid JSON at all.
         message_type: Option<String>,
         /// The deserialization error text.
         error: String,
@@ -116,6 +184,25 @@ pub enum SignalFishEvent {
         raw_prefix: String,
     },
 
+    /// A server message this SDK could not decode, though it is well-formed
+    /// JSON with a `type` tag: usually a message type, or a field value,
+    /// added by a newer server.
+    ///
+    /// This is a **synthetic event**, emitted in place of
+    /// [`DecodeFailed`](Self::DecodeFailed) only with
+    /// [`SignalFishConfig::forward_unknown_messages`](crate::SignalFishConfig::forward_unknown_messages)
+    /// set, so the application can handle the message itself. It counts
+    /// toward `messages_undecodable` like `DecodeFailed`, and the connection
+    /// stays open. Client state is unaffected.
+    UnknownMessage {
+        /// The wire `type` tag.
+        message_type: String,
+        /// The complete message text.
+        raw: String,
+        /// The deserialization error text.
+        error: String,
+    },
+
     /// The server violated protocol-v3 delivery-accountability invariants.
     ProtocolViolation {
         /// Stable category suitable for metrics and policy handling.
@@ -124,6 +211,123 @@ pub enum SignalFishEvent {
         diagnostic: String,
     },
 
+    /// An authentication failure discarded the local room session.
+    ///
+    /// This is a **synthetic event** emitted immediately after the
+    /// [`AuthenticationError`](Self::AuthenticationError) that caused it, and
+    /// only when player/room/spectator state or a reconnection token was
+    /// actually held. The room accessors and
+    /// [`ClientSnapshot`](crate::ClientSnapshot) are already cleared when it
+    /// arrives; treat it like an implicit [`RoomLeft`](Self::RoomLeft). Not
+    /// emitted when
+    /// [`SignalFishConfig::clear_session_on_auth_failure`](crate::SignalFishConfig::clear_session_on_auth_failure)
+    /// is `false`.
+    SessionInvalidated {
+        /// The server's authentication error text.
+        reason: String,
+    },
+
+    /// The server removed this client from its room (for example an idle
+    /// kick) and did not confirm it with `RoomLeft`.
+    ///
+    /// This is a **synthetic event** emitted under
+    /// [`SelfRemovalPolicy::ClearAfter`](crate::SelfRemovalPolicy::ClearAfter)
+    /// (the default) when a [`PlayerLeft`](Self::PlayerLeft) naming this
+    /// client is not followed by `RoomLeft` within the grace period. The room
+    /// accessors and [`ClientSnapshot`](crate::ClientSnapshot) are already
+    /// cleared when it arrives; treat it like an implicit
+    /// [`RoomLeft`](Self::RoomLeft). A `RoomLeft` arriving afterwards is not
+    /// surfaced again.
+    RemovedFromRoom {
+        /// The room this client was removed from.
+        room_id: RoomId,
+        /// Code of that room.
+        room_code: String,
+    },
+
+    /// The server announced this client's own departure with a
+    /// [`PlayerLeft`](Self::PlayerLeft), and room state is being kept until
+    /// `RoomLeft` arrives.
+    ///
+    /// This is a **synthetic warning** emitted right after that `PlayerLeft`
+    /// under
+    /// [`SelfRemovalPolicy::AwaitRoomLeft`](crate::SelfRemovalPolicy::AwaitRoomLeft).
+    /// If the server never sends `RoomLeft`, the application decides whether
+    /// to call `leave_room()` or reconnect.
+    SelfRemovalPending {
+        /// The room the server says this client left.
+        room_id: RoomId,
+    },
+
+    /// A server message named a different room than the one the client's
+    /// session is in (or asked to reconnect to).
+    ///
+    /// This is a **synthetic warning** emitted just before the message's own
+    /// event.
+    /// [`SignalFishConfig::session_inconsistency_policy`](crate::SignalFishConfig::session_inconsistency_policy)
+    /// decides whether the message is then applied, dropped, or answered by
+    /// closing the connection.
+    SessionInconsistency {
+        /// The room the client's session expects.
+        expected: RoomRef,
+        /// The room the message names.
+        got: RoomRef,
+        /// Wire `type` of the message, such as `"SpectatorLeft"`.
+        message_kind: &'static str,
+    },
+
+    /// `Authenticated` named an organization other than
+    /// [`SignalFishConfig::expected_organization`](crate::SignalFishConfig::expected_organization),
+    /// which usually means the client was built with the wrong `app_id`.
+    ///
+    /// This is a **synthetic warning** emitted just before `Authenticated`.
+    /// [`SignalFishConfig::organization_mismatch_policy`](crate::SignalFishConfig::organization_mismatch_policy)
+    /// decides whether authentication then proceeds or the connection closes.
+    OrganizationMismatch {
+        /// The configured organization.
+        expected: String,
+        /// The organization the server named, if any.
+        actual: Option<String>,
+    },
+
+    /// A server message carried a number outside the range of its field,
+    /// which was adjusted so the rest of the message could still be applied.
+    ///
+    /// This is a **synthetic warning** emitted just before the message's own
+    /// event. The per-field policy is documented on the protocol types:
+    /// `max_players` is clamped, rate-limit counts become
+    /// [`RateLimitInfo::UNLIMITED`], and a `connection_info` with an unusable
+    /// port is dropped.
+    FieldOutOfRange {
+        /// Path of the field, such as `"max_players"` or
+        /// `"rate_limits.per_minute"`.
+        field: &'static str,
+        /// The number as the server sent it.
+        value: String,
+        /// What the client did with it.
+        action: OutOfRangeAction,
+    },
+
+    /// A `RoomJoined` or `Reconnected` that failed to decode was recovered
+    /// by dropping the part that did not parse.
+    ///
+    /// This is a **synthetic warning** emitted just before the message's own
+    /// event, which lacks the dropped part: a malformed entry of a collection
+    /// such as `current_players` is removed, and an optional field such as
+    /// `reconnection_token` falls back to its default. Set
+    /// [`SignalFishConfig::strict_payloads`](crate::SignalFishConfig::strict_payloads)
+    /// to report such messages as
+    /// [`DecodeFailed`](Self::DecodeFailed) instead.
+    PayloadSalvaged {
+        /// The message's `type`, `"RoomJoined"` or `"Reconnected"`.
+        message_type: &'static str,
+        /// Path of what was dropped, such as `"current_players[2]"` or
+        /// `"reconnection_token"`.
+        field: String,
+        /// Why it did not parse.
+        error: String,
+    },
+
     // ── Authentication ──────────────────────────────────────────────
     /// Authentication succeeded.
     Authenticated {
@@ -187,11 +391,18 @@ pub enum SignalFishEvent {
     },
 
     /// Failed to join a room.
+    ///
+    /// The client's room state is untouched by a failed join: `room_id` and
+    /// `room_code` stay `None` unless the client was already in a room.
     RoomJoinFailed {
         /// Human-readable failure reason.
         reason: String,
         /// Structured error code, if provided.
         error_code: Option<ErrorCode>,
+        /// Room code of the `join_room` request this failure answers, when
+        /// that request named one. `None` for quick-match joins and when no
+        /// join was pending.
+        requested_room_code: Option<String>,
     },
 
     /// Successfully left the current room.
@@ -362,6 +573,20 @@ pub enum SignalFishEvent {
     // ── Heartbeat ───────────────────────────────────────────────────
     /// Pong response to a ping.
     Pong,
+    /// The server's answer to a time-sync request.
+    ///
+    /// `sample` holds the offset and round trip the client computed from the
+    /// local receive time; it is `None` only for a response replayed inside
+    /// [`Reconnected::missed_events`](Self::Reconnected), whose receive time
+    /// says nothing about the original exchange.
+    TimeSyncResponse {
+        /// The request's send time, echoed by the server.
+        client_sent_at_ms: u64,
+        /// The server clock when it handled the request.
+        server_time_ms: u64,
+        /// The estimate this exchange produced.
+        sample: Option<crate::TimeSyncResult>,
+    },
 
     // ── Reconnection ────────────────────────────────────────────────
     /// Reconnection succeeded. Fields are flattened from [`ReconnectedPayload`].
@@ -396,7 +621,8 @@ pub enum SignalFishEvent {
         /// (protocol v3 "pre-gather"). Empty unless the server opted this
         /// connection into ICE pre-gather.
         ice_servers: Vec<IceServer>,
-        /// Events that occurred while the client was disconnected.
+        /// Events that occurred while the client was disconnected. Reported
+        /// only; the room fields above are the state after all of them.
         missed_events: Vec<SignalFishEvent>,
         /// Completeness of the replayed control-event suffix.
         replay: Option<ReplayStatus>,
@@ -406,6 +632,17 @@ pub enum SignalFishEvent {
         reconnection_token: Option<String>,
     },
 
+    /// The preceding `Reconnected` replay is missing the server sequence
+    /// numbers `from..=to`. One event is emitted per gap, directly after
+    /// `Reconnected`. See
+    /// [`ReconnectedPayload::missing_ranges`](crate::protocol::ReconnectedPayload::missing_ranges).
+    MissedEventsGap {
+        /// First missing sequence number.
+        from: u64,
+        /// Last missing sequence number (inclusive).
+        to: u64,
+    },
+
     /// Reconnection failed.
     ReconnectionFailed {
         /// Human-readable failure reason.
@@ -486,6 +723,24 @@ pub enum SignalFishEvent {
         current_spectators: Vec<SpectatorInfo>,
     },
 
+    // ── Announcements ───────────────────────────────────────────────
+    /// A room-wide announcement from the server (MOTD, tournament notices).
+    ///
+    /// Dropped when [`SignalFishConfig::suppress_announcements`] is set. With
+    /// [`SignalFishConfig::legacy_announcement_sender`], legacy announcements
+    /// relayed as `GameData` from a system player arrive here too.
+    ///
+    /// [`SignalFishConfig::suppress_announcements`]: crate::SignalFishConfig::suppress_announcements
+    /// [`SignalFishConfig::legacy_announcement_sender`]: crate::SignalFishConfig::legacy_announcement_sender
+    Announcement {
+        /// Human-readable announcement text.
+        message: String,
+        /// Server-defined severity (e.g. `"info"`, `"warning"`), if provided.
+        severity: Option<String>,
+        /// Arbitrary structured context, if provided.
+        metadata: Option<serde_json::Value>,
+    },
+
     // ── Errors ──────────────────────────────────────────────────────
     /// A generic server error.
     Error {
@@ -504,8 +759,19 @@ impl std::fmt::Debug for SignalFishEvent {
         f.write_str(match self {
             Self::Connected => "Connected",
             Self::Disconnected { .. } => "Disconnected",
+            Self::Reconnecting { .. } => "Reconnecting",
+            Self::ClientStalled { .. } => "ClientStalled",
+            Self::MessageExpired { .. } => "MessageExpired",
             Self::DecodeFailed { .. } => "DecodeFailed",
+            Self::UnknownMessage { .. } => "UnknownMessage",
             Self::ProtocolViolation { .. } => "ProtocolViolation",
+            Self::SessionInvalidated { .. } => "SessionInvalidated",
+            Self::RemovedFromRoom { .. } => "RemovedFromRoom",
+            Self::SelfRemovalPending { .. } => "SelfRemovalPending",
+            Self::SessionInconsistency { .. } => "SessionInconsistency",
+            Self::OrganizationMismatch { .. } => "OrganizationMismatch",
+            Self::FieldOutOfRange { .. } => "FieldOutOfRange",
+            Self::PayloadSalvaged { .. } => "PayloadSalvaged",
             Self::Authenticated { .. } => "Authenticated",
             Self::ProtocolInfo(_) => "ProtocolInfo",
             Self::AuthenticationError { .. } => "AuthenticationError",
@@ -528,7 +794,9 @@ impl std::fmt::Debug for SignalFishEvent {
             Self::GoingAway { .. } => "GoingAway",
             Self::DeliveryReport(_) => "DeliveryReport",
             Self::Pong => "Pong",
+            Self::TimeSyncResponse { .. } => "TimeSyncResponse",
             Self::Reconnected { .. } => "Reconnected",
+            Self::MissedEventsGap { .. } => "MissedEventsGap",
             Self::ReconnectionFailed { .. } => "ReconnectionFailed",
             Self::PlayerReconnected { .. } => "PlayerReconnected",
             Self::SpectatorJoined { .. } => "SpectatorJoined",
@@ -536,6 +804,7 @@ impl std::fmt::Debug for SignalFishEvent {
             Self::SpectatorLeft { .. } => "SpectatorLeft",
             Self::NewSpectatorJoined { .. } => "NewSpectatorJoined",
             Self::SpectatorDisconnected { .. } => "SpectatorDisconnected",
+            Self::Announcement { .. } => "Announcement",
             Self::Error { .. } => "Error",
         })
     }
@@ -580,6 +849,23 @@ impl ProtocolViolationKind {
     }
 }
 
+/// How the client handled a number outside its field's range; carried by
+/// [`SignalFishEvent::FieldOutOfRange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfRangeAction {
+    /// The nearest value the field can hold was used instead.
+    Clamped {
+        /// The value applied.
+        to: u64,
+    },
+    /// A negative count was read as "no limit" and stored as
+    /// [`RateLimitInfo::UNLIMITED`].
+    Unlimited,
+    /// The value was unusable, so the optional structure holding it was
+    /// dropped.
+    Dropped,
+}
+
 /// Maximum number of bytes of raw frame text preserved in
 /// [`SignalFishEvent::DecodeFailed::raw_prefix`].
 ///
@@ -587,6 +873,124 @@ impl ProtocolViolationKind {
 /// be a few bytes shorter than this cap.
 pub const DECODE_FAILED_RAW_PREFIX_MAX: usize = 512;
 
+/// Why the connection ended, carried by [`SignalFishEvent::Disconnected`].
+///
+/// Match on the variant to decide what to do next — reconnect after a
+/// [`ServerClosed`](Self::ServerClosed) or
+/// [`TransportError`](Self::TransportError), stop after
+/// [`ClientShutdown`](Self::ClientShutdown) or
+/// [`AuthenticationFailed`](Self::AuthenticationFailed). `Display` renders
+/// the human-readable text for logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The application ended the client: `shutdown()`, dropping every
+    /// handle, or the polling client's `close()`.
+    ClientShutdown,
+    /// The server closed the connection.
+    ServerClosed {
+        /// The Close frame's code and reason, when the transport captured
+        /// one (see [`Transport::close_info`](crate::Transport::close_info)).
+        close: Option<crate::transport::TransportCloseInfo>,
+        /// Time since the last send and receive, which explains idle closes.
+        activity: crate::ActivityInfo,
+    },
+    /// Sending or receiving failed, or the receive path stopped.
+    TransportError {
+        /// What failed, with the transport's error and, for receive
+        /// failures, the time since the last send and receive.
+        detail: String,
+    },
+    /// A `Ping` went unanswered for
+    /// [`pong_timeout`](crate::SignalFishConfig::pong_timeout).
+    HeartbeatTimeout,
+    /// The server rejected `Authenticate` with `AuthenticationError`, then
+    /// the connection ended.
+    AuthenticationFailed {
+        /// The `AuthenticationError`'s code.
+        code: ErrorCode,
+    },
+    /// The configuration refused to send `Authenticate`, e.g. under
+    /// [`SdkVersionPolicy::Reject`](crate::SdkVersionPolicy::Reject).
+    HandshakeRefused {
+        /// Why it was refused.
+        detail: String,
+    },
+    /// A drain finished: the client left its last room.
+    Drained,
+    /// [`drain_timeout`](crate::SignalFishConfig::drain_timeout) cut a drain
+    /// short.
+    DrainTimedOut,
+    /// Every event receiver was dropped under
+    /// [`OnReceiverDropped::ShutdownClient`](crate::OnReceiverDropped::ShutdownClient).
+    ReceiversDropped,
+    /// The client closed the connection under
+    /// [`ProtocolViolationPolicy::Disconnect`](crate::ProtocolViolationPolicy::Disconnect).
+    ProtocolViolation,
+    /// The client closed the connection under
+    /// [`SessionInconsistencyPolicy::Resync`](crate::SessionInconsistencyPolicy::Resync).
+    SessionInconsistency,
+    /// The client closed the connection under
+    /// [`OrganizationMismatchPolicy::Disconnect`](crate::OrganizationMismatchPolicy::Disconnect).
+    OrganizationMismatch,
+    /// A client started with
+    /// [`start_with_reconnect`](crate::SignalFishClient::start_with_reconnect)
+    /// used up [`max_attempts`](crate::ReconnectPolicy::max_attempts).
+    ReconnectExhausted {
+        /// Attempts made in the final outage.
+        attempts: u32,
+        /// Why the last connection or dial failed.
+        last_failure: Box<DisconnectReason>,
+    },
+    /// The transport loop stalled for
+    /// [`loop_watchdog`](crate::SignalFishConfig::loop_watchdog) and
+    /// [`restart_stalled_loop`](crate::SignalFishConfig::restart_stalled_loop)
+    /// abandoned the connection.
+    LoopStalled,
+}
+
+impl std::fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ClientShutdown => f.write_str("client shut down"),
+            Self::ServerClosed {
+                close: Some(close),
+                activity,
+            } => write!(
+                f,
+                "closed by server: code={:?}, reason={:?} ({activity})",
+                close.code, close.reason
+            ),
+            Self::ServerClosed {
+                close: None,
+                activity,
+            } => write!(f, "closed by server ({activity})"),
+            Self::TransportError { detail } => f.write_str(detail),
+            Self::HeartbeatTimeout => f.write_str("heartbeat timeout"),
+            Self::AuthenticationFailed { code } => {
+                write!(f, "authentication failed: {}", code.key())
+            }
+            Self::HandshakeRefused { detail } => write!(f, "handshake refused: {detail}"),
+            Self::Drained => f.write_str("client drained"),
+            Self::DrainTimedOut => f.write_str("drain timed out"),
+            Self::ReceiversDropped => f.write_str("event receivers dropped"),
+            Self::ProtocolViolation => f.write_str("protocol accountability violation"),
+            Self::SessionInconsistency => f.write_str("server named a room this session is not in"),
+            Self::OrganizationMismatch => {
+                f.write_str("authenticated under an unexpected organization")
+            }
+            Self::ReconnectExhausted {
+                attempts,
+                last_failure,
+            } => write!(
+                f,
+                "gave up reconnecting after {attempts} attempts: {last_failure}"
+            ),
+            Self::LoopStalled => f.write_str("transport loop stalled"),
+        }
+    }
+}
+
 /// A server-sent error remembered for disconnect attribution.
 ///
 /// Carried by [`SignalFishEvent::Disconnected::last_server_error`]: the most
@@ -599,6 +1003,79 @@ pub struct ServerErrorInfo {
     pub error_code: Option<ErrorCode>,
 }
 
+/// What the server confirmed in `Authenticated`.
+///
+/// Returned by `SignalFishClient::wait_authenticated`; the same fields
+/// arrive in [`SignalFishEvent::Authenticated`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedInfo {
+    /// App name, for confirmation.
+    pub app_name: String,
+    /// Organization name, if any.
+    pub organization: Option<String>,
+    /// Rate limits for this app.
+    pub rate_limits: RateLimitInfo,
+}
+
+/// The server's answer to an `AuthorityRequest`.
+///
+/// Returned by `SignalFishClient::request_authority_and_wait`; the same
+/// fields arrive in [`SignalFishEvent::AuthorityResponse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityGrant {
+    /// Whether the request was granted.
+    pub granted: bool,
+    /// The server's explanation, if any.
+    pub reason: Option<String>,
+    /// Structured reason for a denial, if the server sent one.
+    pub error_code: Option<ErrorCode>,
+}
+
+/// A room as one side of a
+/// [`SessionInconsistency`](SignalFishEvent::SessionInconsistency) names it.
+///
+/// Either identifier may be missing: some messages carry only one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomRef {
+    /// The room's id, if known.
+    pub room_id: Option<RoomId>,
+    /// The room's code, if known.
+    pub room_code: Option<String>,
+}
+
+impl RoomRef {
+    /// Whether `self` and `other` name different rooms: some identifier
+    /// present on both sides differs.
+    pub fn conflicts_with(&self, other: &RoomRef) -> bool {
+        let ids = matches!((self.room_id, other.room_id), (Some(a), Some(b)) if a != b);
+        let codes = matches!(
+            (&self.room_code, &other.room_code),
+            (Some(a), Some(b)) if a != b
+        );
+        ids || codes
+    }
+}
+
+/// An event with its place in the client's emission order, from the
+/// enveloped receivers: `SignalFishClient::start_enveloped` and
+/// `subscribe_enveloped`, or `SignalFishPollingClient::poll_enveloped`.
+///
+/// Every event the client emits takes the next sequence number, whichever
+/// receivers it reaches, so a jump between consecutive envelopes counts the
+/// events that receiver did not see (it subscribed late, or a shutdown
+/// preempted a delivery). `last_event_seq()` on either client reads the
+/// counter.
+#[derive(Debug, Clone)]
+pub struct EventEnvelope {
+    /// Position in emission order, starting at 1.
+    pub seq: u64,
+    /// When the client emitted the event: as the transport loop handed it
+    /// out, or the `poll_enveloped()` call that produced it.
+    pub received_at: crate::time::Instant,
+    /// The event.
+    pub event: SignalFishEvent,
+}
+
 impl SignalFishEvent {
     /// Builds the [`DecodeFailed`](Self::DecodeFailed) event for a frame that
     /// failed to deserialize.
@@ -632,6 +1109,23 @@ impl SignalFishEvent {
             raw_prefix: prefix.to_string(),
         }
     }
+
+    /// Builds the [`UnknownMessage`](Self::UnknownMessage) event for a message
+    /// that failed to decode, or `None` unless it is well-formed JSON with a
+    /// string `type` tag.
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    pub(crate) fn unknown_message(raw: &str, error: &serde_json::Error) -> Option<Self> {
+        if error.classify() != serde_json::error::Category::Data {
+            return None;
+        }
+        let value = serde_json::from_str::<serde_json::Value>(raw).ok()?;
+        let message_type = value.get("type")?.as_str()?.to_owned();
+        Some(Self::UnknownMessage {
+            message_type,
+            raw: raw.to_owned(),
+            error: error.to_string(),
+        })
+    }
 }
 
 /// Returns the longest prefix of `s` that is at most `max_bytes` long and
@@ -685,9 +1179,11 @@ impl From<ServerMessage> for SignalFishEvent {
                     reconnection_token: p.reconnection_token,
                 }
             }
-            ServerMessage::RoomJoinFailed { reason, error_code } => {
-                Self::RoomJoinFailed { reason, error_code }
-            }
+            ServerMessage::RoomJoinFailed { reason, error_code } => Self::RoomJoinFailed {
+                reason,
+                error_code,
+                requested_room_code: None,
+            },
             ServerMessage::RoomLeft => Self::RoomLeft,
             ServerMessage::PlayerJoined { player } => Self::PlayerJoined { player },
             ServerMessage::PlayerLeft {
@@ -756,6 +1252,14 @@ impl From<ServerMessage> for SignalFishEvent {
                 Self::GameStarting { peer_connections }
             }
             ServerMessage::Pong => Self::Pong,
+            ServerMessage::TimeSyncResponse {
+                client_sent_at_ms,
+                server_time_ms,
+            } => Self::TimeSyncResponse {
+                client_sent_at_ms,
+                server_time_ms,
+                sample: None,
+            },
             ServerMessage::Reconnected(payload) => {
                 let p = *payload;
                 Self::Reconnected {
@@ -775,7 +1279,7 @@ impl From<ServerMessage> for SignalFishEvent {
                     missed_events: p
                         .missed_events
                         .into_iter()
-                        .map(SignalFishEvent::from)
+                        .map(|missed| SignalFishEvent::from(missed.message))
                         .collect(),
                     replay: p.replay,
                     sender_watermarks: p.sender_watermarks,
@@ -840,6 +1344,15 @@ impl From<ServerMessage> for SignalFishEvent {
                 message,
                 error_code,
             },
+            ServerMessage::Announcement {
+                message,
+                severity,
+                metadata,
+            } => Self::Announcement {
+                message,
+                severity,
+                metadata,
+            },
             ServerMessage::Signal { from, signal } => Self::SignalReceived { from, signal },
             ServerMessage::NewPeer {
                 peer_id,
@@ -970,16 +1483,57 @@ mod tests {
     #[test]
     fn disconnected_event_contains_reason() {
         let event = SignalFishEvent::Disconnected {
-            reason: Some("server shutdown".into()),
+            reason: DisconnectReason::HeartbeatTimeout,
             last_server_error: None,
         };
         if let SignalFishEvent::Disconnected { reason, .. } = event {
-            assert_eq!(reason.as_deref(), Some("server shutdown"));
+            assert_eq!(reason, DisconnectReason::HeartbeatTimeout);
         } else {
             panic!("expected Disconnected variant");
         }
     }
 
+    #[test]
+    fn disconnect_reason_display_keeps_the_log_text() {
+        let activity = crate::ActivityInfo {
+            since_last_send: None,
+            since_last_recv: None,
+        };
+        let closed = DisconnectReason::ServerClosed {
+            close: Some(crate::transport::TransportCloseInfo {
+                code: Some(1001),
+                reason: Some("going away".into()),
+                ..Default::default()
+            }),
+            activity,
+        };
+        assert_eq!(
+            closed.to_string(),
+            format!("closed by server: code=Some(1001), reason=Some(\"going away\") ({activity})")
+        );
+        assert_eq!(
+            DisconnectReason::ClientShutdown.to_string(),
+            "client shut down"
+        );
+        assert_eq!(
+            DisconnectReason::AuthenticationFailed {
+                code: ErrorCode::InvalidAppId
+            }
+            .to_string(),
+            "authentication failed: INVALID_APP_ID"
+        );
+        let exhausted = DisconnectReason::ReconnectExhausted {
+            attempts: 3,
+            last_failure: Box::new(DisconnectReason::TransportError {
+                detail: "connect failed: refused".into(),
+            }),
+        };
+        assert_eq!(
+            exhausted.to_string(),
+            "gave up reconnecting after 3 attempts: connect failed: refused"
+        );
+    }
+
     #[test]
     fn from_server_message_pong() {
         let event = SignalFishEvent::from(ServerMessage::Pong);
@@ -1069,7 +1623,7 @@ mod tests {
             relay_type: "tcp".into(),
             current_spectators: vec![],
             ice_servers: vec![],
-            missed_events: vec![ServerMessage::Pong],
+            missed_events: vec![ServerMessage::Pong.into()],
             replay: None,
             sender_watermarks: vec![],
             reconnection_token: None,
@@ -1229,4 +1783,31 @@ mod tests {
             other => panic!("expected DecodeFailed, got {other:?}"),
         }
     }
+
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    #[test]
+    fn unknown_message_keeps_the_whole_frame_of_any_size() {
+        let big = format!(
+            r#"{{"type":"SomeFutureMessage","data":{{"pad":"{}"}}}}"#,
+            "x".repeat(DECODE_FAILED_RAW_PREFIX_MAX)
+        );
+        let err = serde_json::from_str::<ServerMessage>(&big).unwrap_err();
+        match SignalFishEvent::unknown_message(&big, &err) {
+            Some(SignalFishEvent::UnknownMessage {
+                message_type, raw, ..
+            }) => {
+                assert_eq!(message_type, "SomeFutureMessage");
+                assert_eq!(raw, big);
+            }
+            other => panic!("expected UnknownMessage, got {other:?}"),
+        }
+
+        for raw in [r#"{"data":{}}"#, r#"{"type":7}"#, "{{not json"] {
+            let err = serde_json::from_str::<ServerMessage>(raw).unwrap_err();
+            assert!(
+                SignalFishEvent::unknown_message(raw, &err).is_none(),
+                "{raw} has no string type tag"
+            );
+        }
+    }
 }
@@ -0,0 +1,660 @@
+//! Typed decoding of game-data events.
+//!
+//! [`SignalFishEvent::decode`] and [`SignalFishEvent::try_decode`] deserialize
+//! a `GameData` payload (or a JSON-encoded `GameDataBinary` payload) straight
+//! from the event without cloning it, so a successful decode costs only what
+//! the target type itself allocates. Failures are described by a compact
+//! [`GameDataDecodeError`] whose [`DecodeErrorKind`] can be counted without
+//! formatting anything.
+//!
+//! The `decode_game_data` / `try_decode_game_data` methods on both clients do
+//! the same and additionally report each failure to the configured
+//! [`DecodeFailureHook`], for release-build telemetry.
+//!
+//! With the `rkyv` feature, `validate_rkyv` and `SignalFishEvent::decode_rkyv`
+//! read rkyv payloads. Payload bytes come from the network and are never
+//! trusted: every archive is checked with bytecheck before any of it is read,
+//! and nothing here offers unchecked access.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::event::SignalFishEvent;
+use crate::protocol::{GameDataEncoding, PlayerId};
+
+/// Why a game-data payload could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeErrorKind {
+    /// The event is not `GameData` or `GameDataBinary`.
+    NotGameData,
+    /// A `GameDataBinary` payload in an encoding this crate does not decode
+    /// (MessagePack or rkyv).
+    UnsupportedEncoding,
+    /// A binary JSON payload that is not valid JSON.
+    Syntax,
+    /// A binary JSON payload that ended early.
+    Eof,
+    /// Valid JSON whose shape does not match the requested type.
+    Mismatch,
+    /// An rkyv payload that failed validation: truncated, misaligned, or
+    /// holding bytes that are not a valid archive of the requested type.
+    InvalidArchive,
+}
+
+impl DecodeErrorKind {
+    /// Short static description, usable as a metrics label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NotGameData => "not game data",
+            Self::UnsupportedEncoding => "unsupported encoding",
+            Self::Syntax => "malformed JSON",
+            Self::Eof => "truncated JSON",
+            Self::Mismatch => "type mismatch",
+            Self::InvalidArchive => "invalid rkyv archive",
+        }
+    }
+
+    fn from_json(error: &serde_json::Error) -> Self {
+        match error.classify() {
+            serde_json::error::Category::Syntax => Self::Syntax,
+            serde_json::error::Category::Eof => Self::Eof,
+            serde_json::error::Category::Data | serde_json::error::Category::Io => Self::Mismatch,
+        }
+    }
+}
+
+impl fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Failure returned by [`SignalFishEvent::decode`].
+///
+/// Carries the [`DecodeErrorKind`] and, for JSON and rkyv failures, the
+/// underlying error as its [`source`](std::error::Error::source). Nothing is
+/// formatted until the error is displayed.
+#[derive(Debug)]
+pub struct GameDataDecodeError {
+    kind: DecodeErrorKind,
+    source: Option<DecodeSource>,
+}
+
+#[derive(Debug)]
+enum DecodeSource {
+    Json(serde_json::Error),
+    #[cfg(feature = "rkyv")]
+    Rkyv(rkyv::rancor::Error),
+}
+
+impl GameDataDecodeError {
+    fn new(kind: DecodeErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+
+    fn json(source: serde_json::Error) -> Self {
+        Self {
+            kind: DecodeErrorKind::from_json(&source),
+            source: Some(DecodeSource::Json(source)),
+        }
+    }
+
+    #[cfg(feature = "rkyv")]
+    fn rkyv(source: rkyv::rancor::Error) -> Self {
+        Self {
+            kind: DecodeErrorKind::InvalidArchive,
+            source: Some(DecodeSource::Rkyv(source)),
+        }
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> DecodeErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for GameDataDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "game data decode failed: {}", self.kind)?;
+        match &self.source {
+            Some(DecodeSource::Json(source)) => write!(f, ": {source}"),
+            #[cfg(feature = "rkyv")]
+            Some(DecodeSource::Rkyv(source)) => write!(f, ": {source}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for GameDataDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.source.as_ref()? {
+            DecodeSource::Json(source) => Some(source),
+            #[cfg(feature = "rkyv")]
+            DecodeSource::Rkyv(source) => Some(source),
+        }
+    }
+}
+
+/// Check that `payload` is a valid rkyv archive of `T` and return its
+/// archived root for zero-copy reads.
+///
+/// This is the only way this crate reads an archive: the bytes came from the
+/// network, so every pointer, length, and discriminant is validated before
+/// the reference is handed out. Never read a payload with rkyv's unchecked
+/// `access_unchecked`; a peer, a relay bug, or a truncated frame turns that
+/// into undefined behavior.
+///
+/// `payload` must start at the archive's alignment, which a payload copied
+/// out of a frame does not promise; copy it into an
+/// [`rkyv::util::AlignedVec`] first, or use
+/// [`SignalFishEvent::decode_rkyv`], which does.
+///
+/// # Errors
+///
+/// A [`GameDataDecodeError`] of kind [`InvalidArchive`](DecodeErrorKind::InvalidArchive)
+/// for a truncated, misaligned, or corrupt payload.
+///
+/// ```
+/// use signal_fish_client::validate_rkyv;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// struct Move {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&Move { x: 3, y: -1 }).unwrap();
+/// let archived = validate_rkyv::<Move>(&bytes).unwrap();
+/// assert_eq!((archived.x.to_native(), archived.y.to_native()), (3, -1));
+/// assert!(validate_rkyv::<Move>(&bytes[..bytes.len() - 1]).is_err());
+/// ```
+#[cfg(feature = "rkyv")]
+pub fn validate_rkyv<T>(payload: &[u8]) -> Result<&T::Archived, GameDataDecodeError>
+where
+    T: rkyv::Archive,
+    T::Archived: rkyv::Portable
+        + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+{
+    rkyv::access::<T::Archived, rkyv::rancor::Error>(payload).map_err(GameDataDecodeError::rkyv)
+}
+
+/// Telemetry callback for failed game-data decodes.
+///
+/// Install it with
+/// [`SignalFishConfig::with_decode_failure_hook`](crate::SignalFishConfig::with_decode_failure_hook).
+/// It runs synchronously inside `decode_game_data()` / `try_decode_game_data()`
+/// on either client, once per failed decode of a game-data event, with the
+/// sending player and the failure kind. Calling `decode` on the event directly
+/// bypasses it unless you pass the result to [`report`](Self::report)
+/// yourself; a non-game-data event is never reported.
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+/// use signal_fish_client::DecodeFailureHook;
+///
+/// let failures = Arc::new(AtomicU64::new(0));
+/// let counter = Arc::clone(&failures);
+/// let hook = DecodeFailureHook::new(move |_from_player, _kind| {
+///     counter.fetch_add(1, Ordering::Relaxed);
+/// });
+/// # let _ = hook;
+/// ```
+#[derive(Clone)]
+pub struct DecodeFailureHook {
+    hook: Arc<HookFn>,
+}
+
+type HookFn = dyn Fn(PlayerId, DecodeErrorKind) + Send + Sync;
+
+impl DecodeFailureHook {
+    /// Wrap a closure called with the sender and kind of each failure.
+    pub fn new(hook: impl Fn(PlayerId, DecodeErrorKind) + Send + Sync + 'static) -> Self {
+        Self {
+            hook: Arc::new(hook),
+        }
+    }
+
+    /// Report a failed decode of `event`; non-game-data events are ignored.
+    pub fn report(&self, event: &SignalFishEvent, kind: DecodeErrorKind) {
+        if let Some(from_player) = sender(event) {
+            (self.hook)(from_player, kind);
+        }
+    }
+}
+
+impl fmt::Debug for DecodeFailureHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeFailureHook").finish_non_exhaustive()
+    }
+}
+
+fn sender(event: &SignalFishEvent) -> Option<PlayerId> {
+    match event {
+        SignalFishEvent::GameData { from_player, .. }
+        | SignalFishEvent::GameDataBinary { from_player, .. } => Some(*from_player),
+        _ => None,
+    }
+}
+
+impl SignalFishEvent {
+    /// Deserialize this event's game-data payload as `T`.
+    ///
+    /// `GameData` is read straight from its JSON value; `GameDataBinary` is
+    /// parsed when its encoding is JSON. The payload is borrowed, so `T` may
+    /// borrow from the event too.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GameDataDecodeError`] whose [`kind`](GameDataDecodeError::kind)
+    /// says why: not a game-data event, an unsupported binary encoding,
+    /// malformed or truncated binary JSON, or a payload that does not match `T`.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use signal_fish_client::{DecodeErrorKind, SignalFishEvent};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Move {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let event = SignalFishEvent::GameData {
+    ///     from_player: uuid::Uuid::nil(),
+    ///     data: serde_json::json!({ "x": 3, "y": -1 }),
+    ///     seq: None,
+    ///     epoch: None,
+    ///     class: None,
+    ///     key: None,
+    /// };
+    /// let step: Move = event.decode().unwrap();
+    /// assert_eq!((step.x, step.y), (3, -1));
+    /// assert_eq!(
+    ///     event.decode::<String>().unwrap_err().kind(),
+    ///     DecodeErrorKind::Mismatch
+    /// );
+    /// ```
+    pub fn decode<'a, T: Deserialize<'a>>(&'a self) -> Result<T, GameDataDecodeError> {
+        match self {
+            Self::GameData { data, .. } => T::deserialize(data).map_err(GameDataDecodeError::json),
+            Self::GameDataBinary {
+                encoding: GameDataEncoding::Json,
+                payload,
+                ..
+            } => serde_json::from_slice(payload).map_err(GameDataDecodeError::json),
+            Self::GameDataBinary { .. } => Err(GameDataDecodeError::new(
+                DecodeErrorKind::UnsupportedEncoding,
+            )),
+            _ => Err(GameDataDecodeError::new(DecodeErrorKind::NotGameData)),
+        }
+    }
+
+    /// Like [`decode`](Self::decode), discarding the error.
+    pub fn try_decode<'a, T: Deserialize<'a>>(&'a self) -> Option<T> {
+        self.decode().ok()
+    }
+
+    /// Validate and deserialize this event's rkyv `GameDataBinary` payload
+    /// as an owned `T`.
+    ///
+    /// The payload goes through [`validate_rkyv`] first, copied into an
+    /// aligned buffer when it is not already aligned, so a hostile or
+    /// damaged payload is an error rather than undefined behavior.
+    ///
+    /// # Errors
+    ///
+    /// [`InvalidArchive`](DecodeErrorKind::InvalidArchive) for a payload that
+    /// fails validation, [`UnsupportedEncoding`](DecodeErrorKind::UnsupportedEncoding)
+    /// for game data in another encoding, and
+    /// [`NotGameData`](DecodeErrorKind::NotGameData) for any other event.
+    #[cfg(feature = "rkyv")]
+    pub fn decode_rkyv<T>(&self) -> Result<T, GameDataDecodeError>
+    where
+        T: rkyv::Archive,
+        T::Archived: rkyv::Portable
+            + for<'a> rkyv::bytecheck::CheckBytes<
+                rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>,
+            > + rkyv::Deserialize<T, rkyv::api::high::HighDeserializer<rkyv::rancor::Error>>,
+    {
+        let payload = match self {
+            Self::GameDataBinary {
+                encoding: GameDataEncoding::Rkyv,
+                payload,
+                ..
+            } => payload,
+            Self::GameData { .. } | Self::GameDataBinary { .. } => {
+                return Err(GameDataDecodeError::new(
+                    DecodeErrorKind::UnsupportedEncoding,
+                ));
+            }
+            _ => return Err(GameDataDecodeError::new(DecodeErrorKind::NotGameData)),
+        };
+        let aligned;
+        let bytes = if payload.as_ptr().cast::<T::Archived>().is_aligned() {
+            payload.as_slice()
+        } else {
+            let mut copy = rkyv::util::AlignedVec::<16>::with_capacity(payload.len());
+            copy.extend_from_slice(payload);
+            aligned = copy;
+            aligned.as_slice()
+        };
+        let archived = validate_rkyv::<T>(bytes)?;
+        rkyv::deserialize::<T, rkyv::rancor::Error>(archived).map_err(GameDataDecodeError::rkyv)
+    }
+
+    /// Deserialize a JSON game-data payload as an owned `T`: the receiving
+    /// half of [`SignalFishClient::send_game_data_as`](crate::SignalFishClient::send_game_data_as).
+    ///
+    /// `None` when this is not a `GameData` event or a JSON-encoded
+    /// `GameDataBinary` one; otherwise the `serde_json` result, so a payload
+    /// that does not match `T` is an `Err` rather than a skipped event.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use signal_fish_client::SignalFishEvent;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// enum GameMsg {
+    ///     Move { x: i32, y: i32 },
+    ///     Chat(String),
+    /// }
+    ///
+    /// let event = SignalFishEvent::GameData {
+    ///     from_player: uuid::Uuid::nil(),
+    ///     data: serde_json::json!({ "Chat": "gg" }),
+    ///     seq: None,
+    ///     epoch: None,
+    ///     class: None,
+    ///     key: None,
+    /// };
+    /// assert_eq!(
+    ///     event.game_data_as::<GameMsg>().unwrap().unwrap(),
+    ///     GameMsg::Chat("gg".into())
+    /// );
+    /// assert!(SignalFishEvent::Connected.game_data_as::<GameMsg>().is_none());
+    /// ```
+    pub fn game_data_as<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        match self {
+            Self::GameData { data, .. } => Some(T::deserialize(data)),
+            Self::GameDataBinary {
+                encoding: GameDataEncoding::Json,
+                payload,
+                ..
+            } => Some(serde_json::from_slice(payload)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Move<'a> {
+        unit: &'a str,
+        x: i32,
+    }
+
+    fn game_data(data: serde_json::Value) -> SignalFishEvent {
+        SignalFishEvent::GameData {
+            from_player: uuid::Uuid::from_u128(7),
+            data,
+            seq: None,
+            epoch: None,
+            class: None,
+            key: None,
+        }
+    }
+
+    fn binary(encoding: GameDataEncoding, payload: &[u8]) -> SignalFishEvent {
+        SignalFishEvent::GameDataBinary {
+            from_player: uuid::Uuid::from_u128(7),
+            encoding,
+            payload: payload.to_vec(),
+            seq: None,
+            epoch: None,
+        }
+    }
+
+    #[test]
+    fn valid_payloads_decode_and_borrow_from_the_event() {
+        let event = game_data(serde_json::json!({ "unit": "archer", "x": 4 }));
+        assert_eq!(
+            event.decode::<Move<'_>>().unwrap(),
+            Move {
+                unit: "archer",
+                x: 4
+            }
+        );
+        let event = binary(GameDataEncoding::Json, br#"{"unit":"scout","x":-2}"#);
+        assert_eq!(
+            event.try_decode::<Move<'_>>(),
+            Some(Move {
+                unit: "scout",
+                x: -2
+            })
+        );
+    }
+
+    #[test]
+    fn mismatched_types_report_mismatch_with_source() {
+        let event = game_data(serde_json::json!({ "unit": "archer", "x": "four" }));
+        let error = event.decode::<Move<'_>>().unwrap_err();
+        assert_eq!(error.kind(), DecodeErrorKind::Mismatch);
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(error
+            .to_string()
+            .starts_with("game data decode failed: type mismatch"));
+        assert_eq!(event.try_decode::<Move<'_>>(), None);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum GameMsg {
+        Move { x: i32 },
+        Quit,
+    }
+
+    #[test]
+    fn game_data_as_reads_json_payloads_only() {
+        let event = game_data(serde_json::json!({ "Move": { "x": 2 } }));
+        assert_eq!(
+            event.game_data_as::<GameMsg>().unwrap().unwrap(),
+            GameMsg::Move { x: 2 }
+        );
+        let event = binary(GameDataEncoding::Json, br#""Quit""#);
+        assert_eq!(
+            event.game_data_as::<GameMsg>().unwrap().unwrap(),
+            GameMsg::Quit
+        );
+        let event = game_data(serde_json::json!({ "Jump": {} }));
+        assert!(event.game_data_as::<GameMsg>().unwrap().is_err());
+
+        let packed = binary(GameDataEncoding::MessagePack, &[0xa4]);
+        assert!(packed.game_data_as::<GameMsg>().is_none());
+        assert!(SignalFishEvent::Connected
+            .game_data_as::<GameMsg>()
+            .is_none());
+    }
+
+    #[test]
+    fn malformed_binary_json_reports_syntax_and_eof() {
+        let garbage = binary(GameDataEncoding::Json, b"{unit: archer}");
+        assert_eq!(
+            garbage.decode::<Move<'_>>().unwrap_err().kind(),
+            DecodeErrorKind::Syntax
+        );
+        let truncated = binary(GameDataEncoding::Json, br#"{"unit":"arch"#);
+        assert_eq!(
+            truncated.decode::<Move<'_>>().unwrap_err().kind(),
+            DecodeErrorKind::Eof
+        );
+    }
+
+    #[test]
+    fn other_encodings_and_events_are_not_decoded() {
+        let packed = binary(GameDataEncoding::MessagePack, &[0x81, 0xa1, 0x78, 0x01]);
+        assert_eq!(
+            packed.decode::<serde_json::Value>().unwrap_err().kind(),
+            DecodeErrorKind::UnsupportedEncoding
+        );
+        let error = SignalFishEvent::Connected
+            .decode::<serde_json::Value>()
+            .unwrap_err();
+        assert_eq!(error.kind(), DecodeErrorKind::NotGameData);
+        assert!(std::error::Error::source(&error).is_none());
+    }
+
+    #[test]
+    fn hook_reports_game_data_senders_only() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let hook =
+            DecodeFailureHook::new(move |from, kind| sink.lock().unwrap().push((from, kind)));
+        hook.report(&game_data(serde_json::json!(1)), DecodeErrorKind::Mismatch);
+        hook.report(&SignalFishEvent::Connected, DecodeErrorKind::NotGameData);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(uuid::Uuid::from_u128(7), DecodeErrorKind::Mismatch)]
+        );
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[allow(clippy::indexing_slicing)]
+    mod rkyv_payloads {
+        use super::*;
+        use rkyv::util::AlignedVec;
+
+        #[derive(Debug, PartialEq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+        #[rkyv(derive(Debug))]
+        struct Unit {
+            name: String,
+            x: i32,
+            alive: bool,
+        }
+
+        fn archived() -> AlignedVec {
+            let unit = Unit {
+                name: "archer of the north".into(),
+                x: -7,
+                alive: true,
+            };
+            rkyv::to_bytes::<rkyv::rancor::Error>(&unit).unwrap()
+        }
+
+        fn invalid(result: Result<&ArchivedUnit, GameDataDecodeError>) -> GameDataDecodeError {
+            let error = result.unwrap_err();
+            assert_eq!(error.kind(), DecodeErrorKind::InvalidArchive);
+            assert!(std::error::Error::source(&error).is_some());
+            error
+        }
+
+        #[test]
+        fn valid_archives_read_in_place() {
+            let bytes = archived();
+            let unit = validate_rkyv::<Unit>(&bytes).unwrap();
+            assert_eq!(unit.name.as_str(), "archer of the north");
+            assert_eq!(unit.x, -7);
+            assert!(unit.alive);
+            // Zero copy: the name is read straight out of the payload.
+            let range = bytes.as_ptr_range();
+            assert!(range.contains(&unit.name.as_str().as_ptr()));
+        }
+
+        #[test]
+        fn truncated_archives_are_rejected() {
+            let bytes = archived();
+            invalid(validate_rkyv::<Unit>(&[]));
+            invalid(validate_rkyv::<Unit>(&bytes[..bytes.len() - 1]));
+            invalid(validate_rkyv::<Unit>(&bytes[..bytes.len() / 2]));
+            for len in 0..bytes.len() {
+                // Every prefix is handled without panicking; most are errors.
+                let _ = validate_rkyv::<Unit>(&bytes[..len]);
+            }
+        }
+
+        #[test]
+        fn misaligned_archives_are_rejected() {
+            let bytes = archived();
+            let mut shifted = AlignedVec::<16>::new();
+            shifted.push(0);
+            shifted.extend_from_slice(&bytes);
+            let error = invalid(validate_rkyv::<Unit>(&shifted[1..]));
+            assert!(error
+                .to_string()
+                .starts_with("game data decode failed: invalid rkyv archive: "));
+        }
+
+        #[test]
+        fn corrupt_archives_are_rejected() {
+            let bytes = archived();
+            let mut corrupt = AlignedVec::<16>::new();
+            corrupt.extend_from_slice(&bytes);
+            // The root ends the archive: `alive` is its last field, and a bool
+            // holds only 0 or 1.
+            let alive = std::mem::offset_of!(ArchivedUnit, alive)
+                + (bytes.len() - std::mem::size_of::<ArchivedUnit>());
+            corrupt[alive] = 2;
+            invalid(validate_rkyv::<Unit>(&corrupt));
+
+            let mut garbage = AlignedVec::<16>::new();
+            garbage.extend_from_slice(&vec![0xff; bytes.len()]);
+            invalid(validate_rkyv::<Unit>(&garbage));
+        }
+
+        #[test]
+        fn decode_rkyv_copies_misaligned_payloads_and_rejects_the_rest() {
+            let bytes = archived();
+            let event = binary(GameDataEncoding::Rkyv, &bytes);
+            let expected = Unit {
+                name: "archer of the north".into(),
+                x: -7,
+                alive: true,
+            };
+            assert_eq!(event.decode_rkyv::<Unit>().unwrap(), expected);
+
+            // A payload at an odd address still decodes, from an aligned copy.
+            let mut shifted = vec![0u8; bytes.len() + 1];
+            shifted[1..].copy_from_slice(&bytes);
+            let mut event = binary(GameDataEncoding::Rkyv, &[]);
+            if let SignalFishEvent::GameDataBinary { payload, .. } = &mut event {
+                *payload = shifted;
+                payload.remove(0);
+            }
+            assert_eq!(event.decode_rkyv::<Unit>().unwrap(), expected);
+
+            let truncated = binary(GameDataEncoding::Rkyv, &bytes[..bytes.len() - 3]);
+            assert_eq!(
+                truncated.decode_rkyv::<Unit>().unwrap_err().kind(),
+                DecodeErrorKind::InvalidArchive
+            );
+            assert_eq!(
+                binary(GameDataEncoding::MessagePack, &bytes)
+                    .decode_rkyv::<Unit>()
+                    .unwrap_err()
+                    .kind(),
+                DecodeErrorKind::UnsupportedEncoding
+            );
+            assert_eq!(
+                game_data(serde_json::json!(1))
+                    .decode_rkyv::<Unit>()
+                    .unwrap_err()
+                    .kind(),
+                DecodeErrorKind::UnsupportedEncoding
+            );
+            assert_eq!(
+                SignalFishEvent::Connected
+                    .decode_rkyv::<Unit>()
+                    .unwrap_err()
+                    .kind(),
+                DecodeErrorKind::NotGameData
+            );
+        }
+    }
+}
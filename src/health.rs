@@ -0,0 +1,276 @@
+//! Cheap synchronous health summaries for liveness probes.
+//!
+//! [`ClientHealth`] combines the connection state, inbound silence,
+//! outgoing-queue pressure, and optionally round-trip latency into one
+//! [`HealthVerdict`]. Both clients compute it
+//! without awaiting anything, so it can be called from a synchronous HTTP
+//! handler that embeds several clients. [`ActivityInfo`] reports the raw
+//! per-direction silence behind it.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Limits used to grade a [`ClientHealth`].
+///
+/// Set via
+/// [`SignalFishConfig::with_health_thresholds`](crate::SignalFishConfig::with_health_thresholds).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthThresholds {
+    /// Inbound silence after which the client is [`HealthVerdict::Degraded`].
+    ///
+    /// The server sends nothing unsolicited in a quiet room, so applications
+    /// relying on this should `ping()` more often than this interval.
+    /// Defaults to **30 seconds**.
+    pub degraded_after: Duration,
+    /// Inbound silence after which the client is [`HealthVerdict::Unhealthy`].
+    /// Defaults to **90 seconds**.
+    pub unhealthy_after: Duration,
+    /// Outgoing-queue fill ratio (`0.0..=1.0`) at or above which the client is
+    /// [`HealthVerdict::Degraded`]. Defaults to **0.8**.
+    pub degraded_utilization: f64,
+    /// 95th-percentile round trip (see
+    /// [`LatencyStats::p95`](crate::LatencyStats::p95)) at or above which the
+    /// client is [`HealthVerdict::Degraded`]. Defaults to **`None`**:
+    /// latency does not affect the verdict.
+    pub degraded_rtt: Option<Duration>,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            degraded_after: Duration::from_secs(30),
+            unhealthy_after: Duration::from_secs(90),
+            degraded_utilization: 0.8,
+            degraded_rtt: None,
+        }
+    }
+}
+
+/// Overall grade of a [`ClientHealth`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthVerdict {
+    /// Connected, authenticated, recently heard from, and not congested.
+    Healthy,
+    /// Usable but worth attention: not yet authenticated, quiet for longer
+    /// than [`HealthThresholds::degraded_after`], the outgoing queue is
+    /// near capacity, or round trips exceed
+    /// [`HealthThresholds::degraded_rtt`].
+    Degraded,
+    /// Disconnected, or silent for longer than
+    /// [`HealthThresholds::unhealthy_after`].
+    Unhealthy,
+}
+
+/// Point-in-time health summary returned by `health()` on either client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClientHealth {
+    /// Whether the transport is connected.
+    pub connected: bool,
+    /// Whether the server has confirmed authentication.
+    pub authenticated: bool,
+    /// Time since the last inbound frame, or since the client started if none
+    /// has arrived yet.
+    pub last_recv_age: Duration,
+    /// Outgoing command-queue fill ratio, `0.0` (empty) to `1.0` (full).
+    pub channel_utilization: f64,
+    /// 95th-percentile `Ping` round trip, or `None` before any `Pong`.
+    pub rtt_p95: Option<Duration>,
+    /// Overall grade derived from the fields above.
+    pub verdict: HealthVerdict,
+}
+
+impl ClientHealth {
+    /// Grade the given observations against `thresholds`.
+    #[must_use]
+    pub fn evaluate(
+        connected: bool,
+        authenticated: bool,
+        last_recv_age: Duration,
+        channel_utilization: f64,
+        thresholds: &HealthThresholds,
+    ) -> Self {
+        let verdict = if !connected || last_recv_age >= thresholds.unhealthy_after {
+            HealthVerdict::Unhealthy
+        } else if !authenticated
+            || last_recv_age >= thresholds.degraded_after
+            || channel_utilization >= thresholds.degraded_utilization
+        {
+            HealthVerdict::Degraded
+        } else {
+            HealthVerdict::Healthy
+        };
+        Self {
+            connected,
+            authenticated,
+            last_recv_age,
+            channel_utilization,
+            rtt_p95: None,
+            verdict,
+        }
+    }
+
+    /// Add the round-trip observation, downgrading a
+    /// [`HealthVerdict::Healthy`] verdict to [`HealthVerdict::Degraded`] when
+    /// it reaches [`HealthThresholds::degraded_rtt`].
+    #[must_use]
+    pub fn with_rtt_p95(
+        mut self,
+        rtt_p95: Option<Duration>,
+        thresholds: &HealthThresholds,
+    ) -> Self {
+        self.rtt_p95 = rtt_p95;
+        let slow = rtt_p95
+            .zip(thresholds.degraded_rtt)
+            .is_some_and(|(rtt, limit)| rtt >= limit);
+        if slow && self.verdict == HealthVerdict::Healthy {
+            self.verdict = HealthVerdict::Degraded;
+        }
+        self
+    }
+}
+
+/// Time since the transport last moved a frame in each direction, returned
+/// by `activity()` on either client.
+///
+/// Telling "the server stopped talking" apart from "we stopped sending" is
+/// the first question when diagnosing an idle disconnect; the same figures
+/// are appended to the `Disconnected` reason when the server sends a Close
+/// frame or the transport fails on receive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityInfo {
+    /// Time since the transport last accepted an outbound frame, or `None`
+    /// if nothing has been sent yet.
+    pub since_last_send: Option<Duration>,
+    /// Time since the last inbound frame, or `None` if nothing has arrived
+    /// yet.
+    pub since_last_recv: Option<Duration>,
+}
+
+impl fmt::Display for ActivityInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn age(f: &mut fmt::Formatter<'_>, since: Option<Duration>) -> fmt::Result {
+            match since {
+                Some(since) => write!(f, "{since:?} ago"),
+                None => f.write_str("never"),
+            }
+        }
+        f.write_str("last send ")?;
+        age(f, self.since_last_send)?;
+        f.write_str(", last receive ")?;
+        age(f, self.since_last_recv)
+    }
+}
+
+/// Fill ratio of a queue holding `used` of `capacity` slots.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn utilization(used: usize, capacity: usize) -> f64 {
+    if capacity == 0 {
+        return 1.0;
+    }
+    (used.min(capacity) as f64) / (capacity as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(connected: bool, authenticated: bool, age_secs: u64, util: f64) -> HealthVerdict {
+        ClientHealth::evaluate(
+            connected,
+            authenticated,
+            Duration::from_secs(age_secs),
+            util,
+            &HealthThresholds::default(),
+        )
+        .verdict
+    }
+
+    #[test]
+    fn connected_authenticated_recent_and_idle_is_healthy() {
+        assert_eq!(grade(true, true, 0, 0.0), HealthVerdict::Healthy);
+        assert_eq!(grade(true, true, 29, 0.79), HealthVerdict::Healthy);
+    }
+
+    #[test]
+    fn each_degraded_condition_is_detected() {
+        assert_eq!(grade(true, false, 0, 0.0), HealthVerdict::Degraded);
+        assert_eq!(grade(true, true, 30, 0.0), HealthVerdict::Degraded);
+        assert_eq!(grade(true, true, 0, 0.8), HealthVerdict::Degraded);
+    }
+
+    #[test]
+    fn disconnected_or_long_silence_is_unhealthy() {
+        assert_eq!(grade(false, true, 0, 0.0), HealthVerdict::Unhealthy);
+        assert_eq!(grade(true, true, 90, 0.0), HealthVerdict::Unhealthy);
+    }
+
+    #[test]
+    fn custom_thresholds_are_honored() {
+        let thresholds = HealthThresholds {
+            degraded_after: Duration::from_millis(10),
+            unhealthy_after: Duration::from_millis(20),
+            degraded_utilization: 0.5,
+            degraded_rtt: None,
+        };
+        let verdict = |age_ms, util| {
+            ClientHealth::evaluate(true, true, Duration::from_millis(age_ms), util, &thresholds)
+                .verdict
+        };
+        assert_eq!(verdict(5, 0.4), HealthVerdict::Healthy);
+        assert_eq!(verdict(5, 0.5), HealthVerdict::Degraded);
+        assert_eq!(verdict(15, 0.0), HealthVerdict::Degraded);
+        assert_eq!(verdict(25, 0.0), HealthVerdict::Unhealthy);
+    }
+
+    #[test]
+    fn slow_round_trips_degrade_only_when_a_limit_is_set() {
+        let healthy = ClientHealth::evaluate(
+            true,
+            true,
+            Duration::ZERO,
+            0.0,
+            &HealthThresholds::default(),
+        );
+        let rtt = Some(Duration::from_millis(250));
+        assert_eq!(
+            healthy
+                .with_rtt_p95(rtt, &HealthThresholds::default())
+                .verdict,
+            HealthVerdict::Healthy
+        );
+
+        let thresholds = HealthThresholds {
+            degraded_rtt: Some(Duration::from_millis(200)),
+            ..HealthThresholds::default()
+        };
+        let graded = healthy.with_rtt_p95(rtt, &thresholds);
+        assert_eq!(graded.verdict, HealthVerdict::Degraded);
+        assert_eq!(graded.rtt_p95, rtt);
+        assert_eq!(
+            healthy.with_rtt_p95(None, &thresholds).verdict,
+            HealthVerdict::Healthy
+        );
+    }
+
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    #[test]
+    fn utilization_is_clamped_ratio() {
+        assert!((utilization(0, 4) - 0.0).abs() < f64::EPSILON);
+        assert!((utilization(2, 4) - 0.5).abs() < f64::EPSILON);
+        assert!((utilization(9, 4) - 1.0).abs() < f64::EPSILON);
+        assert!((utilization(0, 0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn activity_info_display_reports_both_directions() {
+        let activity = ActivityInfo {
+            since_last_send: Some(Duration::from_millis(1500)),
+            since_last_recv: None,
+        };
+        assert_eq!(
+            activity.to_string(),
+            "last send 1.5s ago, last receive never"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Round-trip latency and jitter from `Ping`/`Pong` exchanges.
+//!
+//! The server answers pings in order, so each `Pong` is matched to the oldest
+//! `Ping` still unanswered and the time between the transport accepting the
+//! ping and the pong arriving is one round trip. The clients keep the last
+//! [`LATENCY_SAMPLES`] round trips in a fixed ring, so recording a sample never
+//! allocates, and derive [`LatencyStats`] from it on demand. Netcode can size
+//! an interpolation delay from [`p95`](LatencyStats::p95) plus
+//! [`jitter`](LatencyStats::jitter) rather than from a single average.
+
+use std::time::Duration;
+
+/// Round trips kept for [`LatencyStats`]; older samples are overwritten.
+pub const LATENCY_SAMPLES: usize = 32;
+
+/// Summary of the recent round trips, returned by `latency_stats()` on either
+/// client.
+///
+/// Percentiles use the nearest-rank method over the retained samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LatencyStats {
+    /// Round trips the figures are computed from, at most [`LATENCY_SAMPLES`].
+    pub samples: usize,
+    /// The most recent round trip.
+    pub last: Duration,
+    /// Shortest retained round trip.
+    pub min: Duration,
+    /// Median round trip.
+    pub p50: Duration,
+    /// 95th-percentile round trip.
+    pub p95: Duration,
+    /// Longest retained round trip.
+    pub max: Duration,
+    /// Mean absolute deviation of the round trips from their mean.
+    pub jitter: Duration,
+}
+
+/// Fixed-size ring of recent round trips plus the pings awaiting a `Pong`.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+#[derive(Debug)]
+pub(crate) struct LatencyTracker {
+    ring: [Duration; LATENCY_SAMPLES],
+    len: usize,
+    next: usize,
+    /// Send times of unanswered pings, oldest first; bounded so pings the
+    /// server never answers cannot grow it.
+    in_flight: std::collections::VecDeque<crate::time::Instant>,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl LatencyTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            ring: [Duration::ZERO; LATENCY_SAMPLES],
+            len: 0,
+            next: 0,
+            in_flight: std::collections::VecDeque::with_capacity(LATENCY_SAMPLES),
+        }
+    }
+
+    /// The transport accepted a `Ping` at `at`.
+    pub(crate) fn ping_sent(&mut self, at: crate::time::Instant) {
+        if self.in_flight.len() == LATENCY_SAMPLES {
+            self.in_flight.pop_front();
+        }
+        self.in_flight.push_back(at);
+    }
+
+    /// A `Pong` arrived at `at`; one without an outstanding ping is ignored.
+    pub(crate) fn pong_received(&mut self, at: crate::time::Instant) {
+        if let Some(sent) = self.in_flight.pop_front() {
+            self.record(at.saturating_duration_since(sent));
+        }
+    }
+
+    /// When the oldest unanswered ping went out.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn oldest_in_flight(&self) -> Option<crate::time::Instant> {
+        self.in_flight.front().copied()
+    }
+
+    /// Forget unanswered pings; their pongs can no longer arrive.
+    pub(crate) fn clear_in_flight(&mut self) {
+        self.in_flight.clear();
+    }
+
+    pub(crate) fn record(&mut self, rtt: Duration) {
+        if let Some(slot) = self.ring.get_mut(self.next) {
+            *slot = rtt;
+        }
+        self.next = (self.next + 1) % LATENCY_SAMPLES;
+        self.len = (self.len + 1).min(LATENCY_SAMPLES);
+    }
+
+    pub(crate) fn stats(&self) -> Option<LatencyStats> {
+        let last = *self
+            .ring
+            .get((self.next + LATENCY_SAMPLES - 1) % LATENCY_SAMPLES)?;
+        let mut sorted = self.ring;
+        let sorted = sorted.get_mut(..self.len).filter(|s| !s.is_empty())?;
+        sorted.sort_unstable();
+        let rank = |percent: usize| {
+            let index = (sorted.len() * percent).div_ceil(100).saturating_sub(1);
+            sorted.get(index).copied().unwrap_or_default()
+        };
+
+        let count = sorted.len() as u128;
+        let mean = sorted.iter().map(Duration::as_nanos).sum::<u128>() / count;
+        let deviation = sorted
+            .iter()
+            .map(|rtt| rtt.as_nanos().abs_diff(mean))
+            .sum::<u128>()
+            / count;
+
+        Some(LatencyStats {
+            samples: sorted.len(),
+            last,
+            min: sorted.first().copied().unwrap_or_default(),
+            p50: rank(50),
+            p95: rank(95),
+            max: sorted.last().copied().unwrap_or_default(),
+            jitter: Duration::from_nanos(u64::try_from(deviation).unwrap_or(u64::MAX)),
+        })
+    }
+}
+
+#[cfg(all(test, any(feature = "tokio-runtime", feature = "polling-client")))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn tracker_with(rtts_ms: &[u64]) -> LatencyTracker {
+        let mut tracker = LatencyTracker::new();
+        for &rtt in rtts_ms {
+            tracker.record(Duration::from_millis(rtt));
+        }
+        tracker
+    }
+
+    #[test]
+    fn no_samples_no_stats() {
+        assert_eq!(LatencyTracker::new().stats(), None);
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let stats = tracker_with(&[40, 10, 30, 20, 100]).stats().unwrap();
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.last, Duration::from_millis(100));
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.p50, Duration::from_millis(30));
+        assert_eq!(stats.p95, Duration::from_millis(100));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        // Mean 40 ms; deviations 0, 30, 10, 20, 60.
+        assert_eq!(stats.jitter, Duration::from_millis(24));
+    }
+
+    #[test]
+    fn steady_round_trips_have_no_jitter() {
+        let stats = tracker_with(&[25; 8]).stats().unwrap();
+        assert_eq!(stats.p50, Duration::from_millis(25));
+        assert_eq!(stats.p95, Duration::from_millis(25));
+        assert_eq!(stats.jitter, Duration::ZERO);
+    }
+
+    #[test]
+    fn only_the_last_window_is_kept() {
+        let rtts: Vec<u64> = (1..=LATENCY_SAMPLES as u64 + 8).collect();
+        let stats = tracker_with(&rtts).stats().unwrap();
+        assert_eq!(stats.samples, LATENCY_SAMPLES);
+        assert_eq!(stats.min, Duration::from_millis(9));
+        assert_eq!(
+            stats.last,
+            Duration::from_millis(LATENCY_SAMPLES as u64 + 8)
+        );
+    }
+
+    #[test]
+    fn pongs_answer_the_oldest_ping() {
+        let start = std::time::Instant::now();
+        let mut tracker = LatencyTracker::new();
+        tracker.pong_received(start);
+        assert_eq!(tracker.stats(), None, "an unsolicited pong is ignored");
+
+        tracker.ping_sent(start);
+        tracker.ping_sent(start + Duration::from_millis(5));
+        tracker.pong_received(start + Duration::from_millis(20));
+        tracker.pong_received(start + Duration::from_millis(40));
+
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.samples, 2);
+        assert_eq!(stats.min, Duration::from_millis(20));
+        assert_eq!(stats.last, Duration::from_millis(35));
+    }
+}
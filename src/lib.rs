@@ -60,6 +60,15 @@
 //! [`webrtc::MeshController`]) against str0m / webrtc-rs / web-sys. The highest
 //! version this SDK speaks is [`PROTOCOL_VERSION`].
 //!
+//! ## Protocol only
+//!
+//! With `default-features = false` and no `client` feature, only the protocol
+//! builds: [`protocol`], [`error_codes`], [`event`], and the payload types,
+//! with serde and no async dependency, for servers and tooling that speak the
+//! protocol without running a client. The `client` feature adds the
+//! configuration, the `Transport` trait, and the built-in transports;
+//! `polling-client` and `tokio-runtime` (on by default) enable it.
+//!
 //! ## Quick Start
 //!
 //! ```rust,ignore
@@ -109,16 +118,40 @@
 
 #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
 mod accountability;
+#[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "client")]
 pub mod client_api;
 #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
 mod client_core;
+#[cfg(feature = "client")]
+pub mod effective_config;
 pub mod error;
 pub mod error_codes;
 pub mod event;
+pub mod game_data;
+pub mod health;
+pub mod latency;
+#[cfg(feature = "tokio-runtime")]
+pub mod merge;
 pub mod protocol;
+#[cfg(feature = "client")]
+pub mod reconnect;
+pub mod room_diff;
+pub mod room_history;
+#[cfg(feature = "client")]
+pub mod runtime_config;
+pub mod sdk_version;
+pub mod session;
+#[cfg(feature = "client")]
+pub mod session_store;
 pub mod signal;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod time;
+pub mod time_sync;
 pub mod transport;
+#[cfg(feature = "client")]
 pub mod transports;
 
 /// Highest signaling protocol version this SDK speaks.
@@ -128,27 +161,71 @@ pub mod transports;
 pub const PROTOCOL_VERSION: u16 = 3;
 
 // Re-export primary types for ergonomic imports.
+#[cfg(feature = "debug-server")]
+pub use client::debug_server::DebugServer;
+#[cfg(feature = "tokio-runtime")]
+pub use client::HandlerGuard;
+#[cfg(feature = "client")]
 pub use client::{
-    ClientSnapshot, ClientStats, GameDataDelivery, JoinRoomParams, ProtocolViolationPolicy,
-    SignalFishClient, SignalFishConfig,
+    ClientSnapshot, ClientStats, GameDataDelivery, JoinRoomParams, OnReceiverDropped,
+    OrganizationMismatchPolicy, OutgoingBreakdown, ProtocolViolationPolicy, SelfRemovalPolicy,
+    SessionInconsistencyPolicy, SignalFishClient, SignalFishConfig,
 };
+#[cfg(feature = "client")]
 pub use client_api::SignalFishClientApi;
-pub use error::SignalFishError;
-pub use error_codes::ErrorCode;
+#[cfg(feature = "client")]
+pub use effective_config::{ConfigSource, EffectiveConfig, Sourced};
+pub use error::{PromotionError, SignalFishError};
+pub use error_codes::{ErrorCode, ErrorCodeFormatter};
 pub use event::{
-    ProtocolViolationKind, ServerErrorInfo, SignalFishEvent, DECODE_FAILED_RAW_PREFIX_MAX,
+    AuthenticatedInfo, AuthorityGrant, DisconnectReason, EventEnvelope, OutOfRangeAction,
+    ProtocolViolationKind, RoomRef, ServerErrorInfo, SignalFishEvent, DECODE_FAILED_RAW_PREFIX_MAX,
 };
+#[cfg(feature = "rkyv")]
+pub use game_data::validate_rkyv;
+pub use game_data::{DecodeErrorKind, DecodeFailureHook, GameDataDecodeError};
+pub use health::{ActivityInfo, ClientHealth, HealthThresholds, HealthVerdict};
+pub use latency::{LatencyStats, LATENCY_SAMPLES};
+#[cfg(feature = "tokio-runtime")]
+pub use merge::{merge_events, MERGED_EVENT_CHANNEL_CAPACITY};
 pub use protocol::{
-    decode_v3_binary_game_data, ClientMessage, DeliveryClass, DeliveryCountersByClass, DeliveryGap,
-    DeliveryGapReason, DeliveryReportPayload, IceServer, LatestDeliveryCounters, MessageTransport,
-    ReliableDeliveryCounters, ReplayStatus, SenderWatermark, ServerMessage, SessionPeer,
-    SessionPlanPayload, Topology, TransportKind, V3BinaryGameDataFrame, VolatileDeliveryCounters,
+    decode_v3_binary_game_data, ClientMessage, ClientMessageKind, DeliveryClass,
+    DeliveryCountersByClass, DeliveryGap, DeliveryGapReason, DeliveryReportPayload, IceServer,
+    LatestDeliveryCounters, MessageTransport, MissedEvent, ReliableDeliveryCounters, ReplayStatus,
+    SenderWatermark, ServerMessage, SessionPeer, SessionPlanPayload, Topology, TransportKind,
+    V3BinaryGameDataFrame, VolatileDeliveryCounters,
+};
+#[cfg(feature = "client")]
+pub use reconnect::{ConnectFuture, ReconnectPolicy, ResumePolicy, TransportFactory};
+pub use room_diff::{DiffTracker, PlayerFieldChange, PlayerUpdate, RoomDiff, RoomSnapshot};
+pub use room_history::{RoomLeaveReason, RoomSessionRecord};
+#[cfg(feature = "client")]
+pub use runtime_config::RuntimeConfigPatch;
+pub use sdk_version::{InvalidSdkVersion, SdkVersion, SdkVersionPolicy};
+pub use session::{
+    InvalidTransition, SessionState, SessionTrigger, StateTransition, STATE_HISTORY_CAPACITY,
+};
+#[cfg(feature = "store-file")]
+pub use session_store::FileSessionStore;
+#[cfg(feature = "client")]
+pub use session_store::{
+    MemorySessionStore, SessionStore, SessionTicket, StoreFuture, SESSION_TICKET_KEY,
 };
 pub use signal::PeerSignal;
-pub use transport::{Transport, TransportCloseInfo, TransportDiagnostics, TransportFrame};
+pub use time_sync::{TimeSyncResult, DEFAULT_TIME_SYNC_SAMPLES};
+pub use transport::TransportCloseInfo;
+#[cfg(feature = "client")]
+pub use transport::{Transport, TransportDiagnostics, TransportFrame};
 
+#[cfg(feature = "transport-tcp")]
+pub use transports::TcpTransport;
+#[cfg(feature = "tls")]
+pub use transports::TlsRoots;
 #[cfg(feature = "transport-websocket")]
-pub use transports::{WebSocketConnectOptions, WebSocketTransport};
+pub use transports::{
+    ConnectProgress, ConnectStage, DnsResolver, SystemResolver, WebSocketConnectOptions,
+    WebSocketConnector, WebSocketDialer, WebSocketTransport, WebSocketTransportBuilder,
+};
 
 #[cfg(feature = "polling-client")]
 pub mod polling_client;
@@ -178,3 +255,9 @@ pub use webrtc::MeshController;
 #[cfg(all(feature = "transport-websocket-emscripten", target_os = "emscripten"))]
 #[allow(deprecated)]
 pub use transports::EmscriptenWebSocketTransport;
+#[cfg(all(
+    feature = "transport-websocket-wasm",
+    target_arch = "wasm32",
+    target_os = "unknown"
+))]
+pub use transports::WasmWebSocketTransport;
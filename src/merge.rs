@@ -0,0 +1,175 @@
+//! Fan-in helper that merges several clients' event streams.
+//!
+//! Useful when one process runs a client per observed room (a spectator
+//! dashboard, a load tester) and wants a single processing pipeline.
+
+use std::future::poll_fn;
+use std::task::{Context, Poll};
+
+use tokio::sync::mpsc;
+
+use crate::event::SignalFishEvent;
+
+/// Capacity of the merged output channel returned by [`merge_events`].
+pub const MERGED_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Merge several event receivers into one, tagging each event with the index
+/// of the receiver it came from.
+///
+/// Inputs are polled round-robin: after an event is taken from input `i`, the
+/// next search starts at `i + 1`, so a saturated input can never starve the
+/// others. The merged channel closes only after **every** input has closed and
+/// been drained. Events are never dropped — when the merged channel is full,
+/// the merging task stops reading all inputs, which in turn applies each
+/// client's normal event-channel backpressure.
+///
+/// Spawns a task with [`tokio::spawn`], so it must be called from within a
+/// tokio runtime. Dropping the returned receiver stops the task at once and
+/// drops the inputs, even if none of them has an event pending.
+///
+/// ```rust,ignore
+/// let mut merged = signal_fish_client::merge_events(vec![rx_a, rx_b]);
+/// while let Some((source, event)) = merged.recv().await {
+///     println!("room #{source}: {event:?}");
+/// }
+/// ```
+pub fn merge_events(
+    receivers: Vec<mpsc::Receiver<SignalFishEvent>>,
+) -> mpsc::Receiver<(usize, SignalFishEvent)> {
+    let (tx, rx) = mpsc::channel(MERGED_EVENT_CHANNEL_CAPACITY);
+    let mut inputs: Vec<Option<mpsc::Receiver<SignalFishEvent>>> =
+        receivers.into_iter().map(Some).collect();
+    tokio::spawn(async move {
+        let mut cursor = 0;
+        loop {
+            // Watch the output too, so dropping it ends the task even while
+            // every input is idle.
+            let item = tokio::select! {
+                () = tx.closed() => break,
+                item = poll_fn(|cx| poll_fair(&mut inputs, &mut cursor, cx)) => item,
+            };
+            let Some(item) = item else { break };
+            if tx.send(item).await.is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Poll every open input once, starting at `cursor`. Closed inputs are
+/// replaced with `None`; `Ready(None)` means all inputs are closed.
+fn poll_fair<T>(
+    inputs: &mut [Option<mpsc::Receiver<T>>],
+    cursor: &mut usize,
+    cx: &mut Context<'_>,
+) -> Poll<Option<(usize, T)>> {
+    let len = inputs.len();
+    for offset in 0..len {
+        let index = (*cursor + offset) % len;
+        let Some(slot) = inputs.get_mut(index) else {
+            continue;
+        };
+        let Some(receiver) = slot.as_mut() else {
+            continue;
+        };
+        match receiver.poll_recv(cx) {
+            Poll::Ready(Some(item)) => {
+                *cursor = (index + 1) % len;
+                return Poll::Ready(Some((index, item)));
+            }
+            Poll::Ready(None) => *slot = None,
+            Poll::Pending => {}
+        }
+    }
+    if inputs.iter().all(Option::is_none) {
+        Poll::Ready(None)
+    } else {
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn filled(count: usize) -> mpsc::Receiver<SignalFishEvent> {
+        let (tx, rx) = mpsc::channel(count.max(1));
+        for _ in 0..count {
+            tx.send(SignalFishEvent::Pong).await.unwrap();
+        }
+        rx
+    }
+
+    #[tokio::test]
+    async fn saturated_input_does_not_starve_a_light_one() {
+        let heavy = filled(500).await;
+        let light = filled(3).await;
+        let mut merged = merge_events(vec![heavy, light]);
+
+        let mut sources = Vec::new();
+        for _ in 0..6 {
+            sources.push(merged.recv().await.expect("merged event").0);
+        }
+        assert_eq!(
+            sources.iter().filter(|&&source| source == 1).count(),
+            3,
+            "light input must interleave with the heavy one: {sources:?}"
+        );
+
+        let mut remaining = 0;
+        while let Some((source, _)) = merged.recv().await {
+            assert_eq!(source, 0);
+            remaining += 1;
+        }
+        assert_eq!(remaining, 497);
+    }
+
+    #[tokio::test]
+    async fn terminates_only_after_all_inputs_close() {
+        let (tx_a, rx_a) = mpsc::channel(4);
+        let (tx_b, rx_b) = mpsc::channel(4);
+        let mut merged = merge_events(vec![rx_a, rx_b]);
+
+        tx_a.send(SignalFishEvent::Connected).await.unwrap();
+        drop(tx_a);
+        let (source, event) = merged.recv().await.expect("event from a");
+        assert_eq!(source, 0);
+        assert!(matches!(event, SignalFishEvent::Connected));
+
+        // Input b is still open, so the merged stream must stay open.
+        let pending = tokio::time::timeout(Duration::from_millis(50), merged.recv()).await;
+        assert!(pending.is_err(), "merged stream closed while b was open");
+
+        tx_b.send(SignalFishEvent::Pong).await.unwrap();
+        drop(tx_b);
+        assert_eq!(merged.recv().await.expect("event from b").0, 1);
+        assert!(merged.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_output_stops_the_task_while_inputs_idle() {
+        let (tx_a, rx_a) = mpsc::channel::<SignalFishEvent>(4);
+        let (tx_b, rx_b) = mpsc::channel::<SignalFishEvent>(4);
+        let merged = merge_events(vec![rx_a, rx_b]);
+        tokio::task::yield_now().await;
+        assert!(!tx_a.is_closed(), "the task holds the inputs while running");
+
+        drop(merged);
+        // The task drops the inputs as it exits, without any event to send.
+        tokio::time::timeout(Duration::from_secs(1), async {
+            tx_a.closed().await;
+            tx_b.closed().await;
+        })
+        .await
+        .expect("the merging task must exit once the output is dropped");
+    }
+
+    #[tokio::test]
+    async fn no_inputs_closes_immediately() {
+        let mut merged = merge_events(Vec::new());
+        assert!(merged.recv().await.is_none());
+    }
+}
@@ -449,7 +449,7 @@ mod tests {
     fn reset_on_disconnect_and_room_left() {
         for terminal in [
             SignalFishEvent::Disconnected {
-                reason: None,
+                reason: crate::DisconnectReason::ClientShutdown,
                 last_server_error: None,
             },
             SignalFishEvent::RoomLeft,
@@ -676,7 +676,7 @@ mod tests {
                 plan(Topology::Mesh, None, vec![peer(1, true)], vec![]),
                 SignalFishEvent::RoomLeft,
                 SignalFishEvent::Disconnected {
-                    reason: None,
+                    reason: crate::DisconnectReason::ClientShutdown,
                     last_server_error: None,
                 },
             ],
@@ -19,18 +19,21 @@
 //! of growing without bound when the transport is congested.
 
 use std::collections::VecDeque;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use tracing::{debug, error};
 
 use crate::client::{ClientSnapshot, GameDataDelivery, JoinRoomParams, SignalFishConfig};
 use crate::client_core::{ClientCore, ClientOperation, CoreCommand as PollingCommand};
 use crate::error::{Result, SignalFishError};
-use crate::event::SignalFishEvent;
+use crate::event::{DisconnectReason, EventEnvelope, SignalFishEvent};
 #[cfg(test)]
 use crate::protocol::GameDataEncoding;
-use crate::protocol::{ClientMessage, ConnectionInfo, PlayerId, RoomId, TransportKind};
+use crate::protocol::{
+    ClientMessageKind, ConnectionInfo, LobbyState, PlayerId, PlayerInfo, RoomId, TransportKind,
+};
 use crate::signal::PeerSignal;
+use crate::time::Instant;
 use crate::transport::{Transport, TransportDiagnostics, TransportFrame};
 
 const DEFAULT_POLL_FRAMES: usize = 64;
@@ -202,16 +205,23 @@ pub struct SignalFishPollingClient<T: Transport> {
     /// The transport accepted the current frame and is still completing it.
     /// While true, poll with `None` and do not dequeue a replacement frame.
     send_in_flight: bool,
-    pending_frame_is_game_data: bool,
-    in_flight_is_game_data: bool,
+    /// Kind of the message in `pending_frame`, counted once the transport
+    /// accepts it.
+    pending_frame_kind: Option<ClientMessageKind>,
+    in_flight_kind: Option<ClientMessageKind>,
     pending_inbound: Option<TransportFrame>,
     close_phase: ClosePhase,
+    /// When the self-removal grace period started by a `PlayerLeft` naming
+    /// this client elapses.
+    self_removal_deadline: Option<Instant>,
+    /// Sequence number of the last event `poll()` returned.
+    last_event_seq: u64,
 }
 
 impl<T: Transport> SignalFishPollingClient<T> {
     /// Create a new polling client with the given transport and configuration.
     ///
-    /// Immediately queues an [`Authenticate`](ClientMessage::Authenticate) message.
+    /// Immediately queues an [`Authenticate`](crate::protocol::ClientMessage::Authenticate) message.
     /// A synthetic [`Connected`](SignalFishEvent::Connected) event will be emitted
     /// once [`poll()`](Self::poll) observes that the transport is ready (see
     /// [`Transport::is_ready()`](crate::Transport::is_ready)).
@@ -228,11 +238,6 @@ impl<T: Transport> SignalFishPollingClient<T> {
         mut options: PollingClientOptions,
     ) -> Self {
         options.work_budget = options.work_budget.clamped();
-        let requested_game_data_encoding = config.game_data_format.unwrap_or_default();
-        let mesh_enabled = config
-            .supported_transports
-            .as_ref()
-            .is_some_and(|transports| transports.contains(&TransportKind::WebRtc));
         let auth_msg = ClientCore::authenticate(&config);
 
         let now = Instant::now();
@@ -247,11 +252,7 @@ impl<T: Transport> SignalFishPollingClient<T> {
             transport,
             cmd_queue,
             command_capacity: config.command_channel_capacity.max(1),
-            core: ClientCore::new(
-                requested_game_data_encoding,
-                config.protocol_violation_policy,
-                mesh_enabled,
-            ),
+            core: ClientCore::new(&config),
             options,
             polling_stats: PollingStats {
                 current_queue_depth: 1,
@@ -264,11 +265,14 @@ impl<T: Transport> SignalFishPollingClient<T> {
             pending_frame: None,
             pending_frame_enqueued_at: None,
             send_in_flight: false,
-            pending_frame_is_game_data: false,
-            in_flight_is_game_data: false,
+            pending_frame_kind: None,
+            in_flight_kind: None,
             pending_inbound: None,
             close_phase: ClosePhase::Open,
+            self_removal_deadline: None,
+            last_event_seq: 0,
         };
+        client.core.record_enqueued(ClientMessageKind::Authenticate);
         client.refresh_queue_diagnostics_at(now);
         client
     }
@@ -307,7 +311,33 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.poll_at(Instant::now())
     }
 
+    /// Like [`poll()`](Self::poll), but each event comes in an
+    /// [`EventEnvelope`] carrying its sequence number, with this call's time
+    /// as [`received_at`](EventEnvelope::received_at).
+    ///
+    /// `poll()` and `poll_enveloped()` share one counter, so they can be
+    /// mixed.
+    pub fn poll_enveloped(&mut self) -> Vec<EventEnvelope> {
+        let now = Instant::now();
+        let first = self.last_event_seq.saturating_add(1);
+        self.poll_at(now)
+            .into_iter()
+            .zip(first..)
+            .map(|(event, seq)| EventEnvelope {
+                seq,
+                received_at: now,
+                event,
+            })
+            .collect()
+    }
+
     fn poll_at(&mut self, now: Instant) -> Vec<SignalFishEvent> {
+        let events = self.collect_events_at(now);
+        self.last_event_seq = self.last_event_seq.saturating_add(events.len() as u64);
+        events
+    }
+
+    fn collect_events_at(&mut self, now: Instant) -> Vec<SignalFishEvent> {
         let mut events = Vec::new();
         self.refresh_queue_diagnostics_at(now);
 
@@ -325,18 +355,20 @@ impl<T: Transport> SignalFishPollingClient<T> {
             return events;
         }
 
+        if let Some(reason) = self.core.take_handshake_refusal() {
+            self.handle_disconnect_at(&mut events, reason, &mut cx, now);
+            return events;
+        }
+
         if let Err(error) = self.drive_outbound(&mut cx, now) {
             error!(%error, "transport send failed");
-            self.handle_disconnect_at(
-                &mut events,
-                Some(format!("transport send error: {error}")),
-                &mut cx,
-                now,
-            );
+            let reason = self.core.send_failure_reason(&error);
+            self.handle_disconnect_at(&mut events, reason, &mut cx, now);
             return events;
         }
 
         let budget = self.options.work_budget;
+        let mut batches = Vec::new();
         let mut received_frames = 0usize;
         let mut received_bytes = 0usize;
         loop {
@@ -347,22 +379,13 @@ impl<T: Transport> SignalFishPollingClient<T> {
                     std::task::Poll::Ready(Some(Ok(frame))) => frame,
                     std::task::Poll::Ready(Some(Err(e))) => {
                         error!("transport receive error: {e}");
-                        self.handle_disconnect_at(
-                            &mut events,
-                            Some(format!("transport receive error: {e}")),
-                            &mut cx,
-                            now,
-                        );
+                        let reason = self.core.receive_failure_reason(&e);
+                        self.handle_disconnect_at(&mut events, reason, &mut cx, now);
                         break;
                     }
                     std::task::Poll::Ready(None) => {
                         debug!("transport closed by server");
-                        let reason = self.transport.close_info().map(|info| {
-                            format!(
-                                "closed by server: code={:?}, reason={:?}",
-                                info.code, info.reason
-                            )
-                        });
+                        let reason = self.core.server_close_reason(self.transport.close_info());
                         self.handle_disconnect_at(&mut events, reason, &mut cx, now);
                         break;
                     }
@@ -389,18 +412,28 @@ impl<T: Transport> SignalFishPollingClient<T> {
             received_frames = received_frames.saturating_add(1);
             received_bytes = next_bytes.unwrap_or(usize::MAX);
             let outcome = self.core.process_frame(frame);
+            if let Some(grace) = outcome.self_removal_grace {
+                self.self_removal_deadline = Some(now + grace);
+            }
+            let start = events.len();
             events.extend(outcome.events);
-            if outcome.disconnect {
-                self.handle_disconnect_at(
-                    &mut events,
-                    Some("protocol accountability violation".into()),
-                    &mut cx,
-                    now,
-                );
+            batches.push((self.core.room_epoch(), start..events.len()));
+            if let Some(reason) = outcome.disconnect {
+                self.drop_cross_epoch(&mut events, batches);
+                self.handle_disconnect_at(&mut events, reason, &mut cx, now);
                 return events;
             }
         }
 
+        if self
+            .self_removal_deadline
+            .is_some_and(|deadline| now >= deadline)
+        {
+            self.self_removal_deadline = None;
+            events.extend(self.core.expire_self_removal());
+        }
+        self.drop_cross_epoch(&mut events, batches);
+
         // Emit Connected once the transport signals readiness.
         // This is placed after the recv drain so that transports with
         // asynchronous handshakes (e.g., EmscriptenWebSocketTransport)
@@ -415,6 +448,24 @@ impl<T: Transport> SignalFishPollingClient<T> {
         events
     }
 
+    /// Apply [`SignalFishConfig::drop_cross_epoch_events`] across the
+    /// frames of one `poll()`: each range of `events` was processed in the
+    /// room epoch it is paired with.
+    fn drop_cross_epoch(
+        &mut self,
+        events: &mut Vec<SignalFishEvent>,
+        batches: Vec<(u64, std::ops::Range<usize>)>,
+    ) {
+        for (epoch, range) in batches.into_iter().rev() {
+            if !self.core.is_cross_epoch(epoch) {
+                continue;
+            }
+            let mut batch: Vec<_> = events.drain(range.clone()).collect();
+            self.core.drop_cross_epoch(epoch, &mut batch);
+            events.splice(range.start..range.start, batch);
+        }
+    }
+
     // ── Public API methods (mirror SignalFishClient) ────────────────
 
     /// Join or create a room with the given parameters.
@@ -424,6 +475,9 @@ impl<T: Transport> SignalFishPollingClient<T> {
     /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
     /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
     /// is full (the message is **not** queued; nothing is silently dropped).
+    /// Returns [`SignalFishError::MaxPlayersExceeded`] if `max_players` is
+    /// above the server's advertised
+    /// [`max_players_per_room`](crate::protocol::ServerLimits::max_players_per_room).
     pub fn join_room(&mut self, params: JoinRoomParams) -> Result<()> {
         self.queue_operation(ClientOperation::JoinRoom(params))
     }
@@ -450,6 +504,18 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.queue_operation(ClientOperation::GameData(data, GameDataDelivery::Reliable))
     }
 
+    /// Serialize `value` to JSON and send it as game data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Serialization`] if `value` does not
+    /// serialize (nothing is queued), otherwise as
+    /// [`send_game_data`](Self::send_game_data).
+    pub fn send_game_data_as<V: serde::Serialize + ?Sized>(&mut self, value: &V) -> Result<()> {
+        let data = serde_json::to_value(value)?;
+        self.send_game_data(data)
+    }
+
     /// Send JSON game data with an explicit protocol-v3 delivery policy.
     pub fn send_game_data_with_delivery(
         &mut self,
@@ -460,6 +526,12 @@ impl<T: Transport> SignalFishPollingClient<T> {
     }
 
     /// Queue opaque binary game data for the negotiated protocol-v3 relay.
+    ///
+    /// `payload` goes out verbatim as one binary WebSocket frame, already
+    /// encoded in the [`game_data_format`](SignalFishConfig::game_data_format)
+    /// the server accepted; there is no per-message encoding. Returns
+    /// [`BinaryFormatNotNegotiated`](SignalFishError::BinaryFormatNotNegotiated)
+    /// while that format is JSON.
     pub fn send_binary_game_data(&mut self, payload: Vec<u8>) -> Result<()> {
         self.queue_operation(ClientOperation::Binary(payload))
     }
@@ -499,9 +571,16 @@ impl<T: Transport> SignalFishPollingClient<T> {
 
     /// Reconnect to a room after disconnection.
     ///
+    /// As with the async client, one `Reconnect` is in flight at a time,
+    /// until the server answers it, the connection ends, or
+    /// [`reconnect_answer_timeout`](crate::SignalFishConfig::reconnect_answer_timeout)
+    /// passes.
+    ///
     /// # Errors
     ///
-    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// Returns [`SignalFishError::ReconnectInFlight`] if an earlier
+    /// `Reconnect` is still awaiting its answer,
+    /// [`SignalFishError::NotConnected`] if the transport has closed,
     /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
     /// is full (the message is **not** queued; nothing is silently dropped).
     pub fn reconnect(
@@ -510,7 +589,14 @@ impl<T: Transport> SignalFishPollingClient<T> {
         room_id: RoomId,
         auth_token: String,
     ) -> Result<()> {
-        self.queue_operation(ClientOperation::Reconnect(player_id, room_id, auth_token))
+        let command = self
+            .core
+            .prepare(ClientOperation::Reconnect(player_id, room_id, auth_token))?;
+        let queued = self.queue_command_at(command, Instant::now());
+        if queued.is_err() {
+            self.core.reconnect_not_sent();
+        }
+        queued
     }
 
     /// Join a room as a spectator (read-only observer).
@@ -555,6 +641,22 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.queue_operation(ClientOperation::Ping)
     }
 
+    /// Queue a time-sync request.
+    ///
+    /// The answer arrives from a later [`poll`](Self::poll) as a
+    /// [`TimeSyncResponse`](SignalFishEvent::TimeSyncResponse) carrying the
+    /// computed sample, which also feeds
+    /// [`server_time_estimate`](Self::server_time_estimate).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotConnected`] if the transport has closed,
+    /// or [`SignalFishError::SendBufferFull`] if the outgoing command queue
+    /// is full (the message is **not** queued; nothing is silently dropped).
+    pub fn request_time_sync(&mut self) -> Result<()> {
+        self.queue_operation(ClientOperation::TimeSync)
+    }
+
     // ── Game start (protocol v2) ────────────────────────────────────
 
     /// Request that the server start the game (protocol v2).
@@ -681,6 +783,21 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.core.is_authenticated()
     }
 
+    /// Whether the local player holds room authority.
+    ///
+    /// Set from `RoomJoined` and `Reconnected`, kept current by
+    /// `AuthorityChanged` and granted `AuthorityResponse`s, and reset when
+    /// the room is left or the connection ends.
+    pub fn is_authority(&self) -> bool {
+        self.core.is_authority()
+    }
+
+    /// Whether the connection ended because the initial `Authenticate` could
+    /// not be sent (see [`ClientSnapshot::handshake_failed`]).
+    pub fn handshake_failed(&self) -> bool {
+        self.core.snapshot().handshake_failed
+    }
+
     /// The local player's ID, set after joining a room.
     pub fn current_player_id(&self) -> Option<PlayerId> {
         self.core.current_player_id()
@@ -696,6 +813,30 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.core.current_room_code()
     }
 
+    /// The players in the current room, kept current by roster, readiness
+    /// and authority updates. Empty outside a room.
+    pub fn current_players(&self) -> &[PlayerInfo] {
+        self.core.current_players()
+    }
+
+    /// Sequence number of the last event [`poll()`](Self::poll) returned, or
+    /// `0` before the first.
+    pub fn last_event_seq(&self) -> u64 {
+        self.last_event_seq
+    }
+
+    /// The current room's lobby state; [`LobbyState::Waiting`] outside a
+    /// room.
+    pub fn lobby_state(&self) -> &LobbyState {
+        self.core.lobby_state()
+    }
+
+    /// The current room as one [`RoomSnapshot`](crate::RoomSnapshot), or
+    /// `None` outside a room.
+    pub fn room_snapshot(&self) -> Option<crate::RoomSnapshot> {
+        self.core.room_snapshot()
+    }
+
     /// Number of messages that can currently be queued before send methods
     /// return [`SignalFishError::SendBufferFull`].
     ///
@@ -717,6 +858,11 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.core.stats()
     }
 
+    /// Outgoing messages counted per kind, at enqueue and at send.
+    pub fn outgoing_breakdown(&self) -> crate::client::OutgoingBreakdown {
+        self.core.outgoing_breakdown()
+    }
+
     /// Return polling-driver queue, budget, and close diagnostics.
     pub fn polling_stats(&self) -> PollingStats {
         self.polling_stats
@@ -756,6 +902,136 @@ impl<T: Transport> SignalFishPollingClient<T> {
         self.core.snapshot()
     }
 
+    /// Synchronous health summary for liveness probes (see
+    /// [`ClientHealth`](crate::ClientHealth)).
+    pub fn health(&self) -> crate::ClientHealth {
+        self.core.health(crate::health::utilization(
+            self.cmd_queue.len(),
+            self.command_capacity,
+        ))
+    }
+
+    /// Time since the transport last sent and received a frame (see
+    /// [`ActivityInfo`](crate::ActivityInfo)).
+    pub fn activity(&self) -> crate::ActivityInfo {
+        self.core.activity()
+    }
+
+    /// The settings this client is running with and where each came from
+    /// (see [`EffectiveConfig`](crate::EffectiveConfig)).
+    pub fn effective_config(&self) -> crate::EffectiveConfig {
+        self.core.effective_config()
+    }
+
+    /// The best recent time-sync sample: of the last
+    /// [`time_sync_samples`](SignalFishConfig::time_sync_samples) exchanges,
+    /// the one with the smallest round trip. `None` until a response arrives.
+    pub fn server_time_estimate(&self) -> Option<crate::TimeSyncResult> {
+        self.core.server_time_estimate()
+    }
+
+    /// Round-trip percentiles and jitter over the last
+    /// [`LATENCY_SAMPLES`](crate::LATENCY_SAMPLES) `Ping`/`Pong` exchanges.
+    /// `None` until a `Pong` answers a [`ping()`](Self::ping).
+    pub fn latency_stats(&self) -> Option<crate::LatencyStats> {
+        self.core.latency_stats()
+    }
+
+    /// Change runtime-tunable settings without reconnecting.
+    ///
+    /// Applied immediately; [`effective_config`](Self::effective_config)
+    /// reports the new values with
+    /// [`ConfigSource::Runtime`](crate::ConfigSource::Runtime). The interval
+    /// and debounce settings are recorded but, as at startup, only the async
+    /// client acts on them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::NotRuntimeTunable`] listing the offending
+    /// fields if the patch changes settings outside
+    /// [`RuntimeConfigPatch::TUNABLE_FIELDS`](crate::RuntimeConfigPatch::TUNABLE_FIELDS);
+    /// nothing is applied.
+    pub fn update_runtime_config(&mut self, patch: crate::RuntimeConfigPatch) -> Result<()> {
+        patch.check()?;
+        self.core.apply_runtime_config(&patch);
+        Ok(())
+    }
+
+    /// Current session lifecycle state (see [`SessionState`](crate::SessionState)).
+    pub fn session_state(&self) -> crate::SessionState {
+        self.core.session_state()
+    }
+
+    /// Caps the server advertised in this session's `ProtocolInfo`, or
+    /// `None` before it arrives or when the server sends none.
+    ///
+    /// A known `max_players_per_room` is checked by `join_room()`.
+    pub fn server_limits(&self) -> Option<crate::protocol::ServerLimits> {
+        self.core.server_limits()
+    }
+
+    /// What the server confirmed in this connection's `Authenticated`: the
+    /// app name, the organization that scopes its quotas, and its rate
+    /// limits. `None` before authentication and after the connection drops.
+    pub fn authenticated_info(&self) -> Option<crate::AuthenticatedInfo> {
+        self.core.authenticated_info().cloned()
+    }
+
+    /// The application's rate limits from this connection's `Authenticated`,
+    /// for pacing sends. `None` before authentication and after the
+    /// connection drops, since a new connection may report different limits.
+    pub fn rate_limits(&self) -> Option<crate::protocol::RateLimitInfo> {
+        self.core.rate_limits().cloned()
+    }
+
+    /// Text for `code` from the configured
+    /// [`ErrorCodeFormatter`](crate::ErrorCodeFormatter), falling back to
+    /// [`ErrorCode::description`](crate::ErrorCode::description). Limit
+    /// errors name the matching [`server_limits`](Self::server_limits) cap
+    /// when the server advertised one.
+    pub fn describe_error_code(&self, code: &crate::ErrorCode) -> std::borrow::Cow<'static, str> {
+        self.core.describe_error_code(code)
+    }
+
+    /// Decode a game-data event's payload as `D` (see
+    /// [`SignalFishEvent::decode`]), reporting a failure to the configured
+    /// [`DecodeFailureHook`](crate::DecodeFailureHook).
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`GameDataDecodeError`](crate::GameDataDecodeError) from
+    /// [`SignalFishEvent::decode`].
+    pub fn decode_game_data<'a, D: serde::Deserialize<'a>>(
+        &self,
+        event: &'a SignalFishEvent,
+    ) -> std::result::Result<D, crate::GameDataDecodeError> {
+        event
+            .decode()
+            .inspect_err(|error| self.core.report_decode_failure(event, error.kind()))
+    }
+
+    /// Like [`decode_game_data`](Self::decode_game_data), returning `None`
+    /// on failure (the hook still sees it).
+    pub fn try_decode_game_data<'a, D: serde::Deserialize<'a>>(
+        &self,
+        event: &'a SignalFishEvent,
+    ) -> Option<D> {
+        self.decode_game_data(event).ok()
+    }
+
+    /// Oldest-first log of the most recent session transitions, bounded by
+    /// [`STATE_HISTORY_CAPACITY`](crate::STATE_HISTORY_CAPACITY). Intended for
+    /// debugging and bug reports.
+    pub fn state_history(&self) -> Vec<crate::StateTransition> {
+        self.core.state_history()
+    }
+
+    /// Oldest-first records of the room sessions that have ended, bounded by
+    /// [`SignalFishConfig::room_history`]; empty when that is unset.
+    pub fn room_history(&self) -> Vec<crate::RoomSessionRecord> {
+        self.core.room_history()
+    }
+
     // ── Close ───────────────────────────────────────────────────────
 
     /// Close the transport and mark the client as disconnected.
@@ -776,7 +1052,7 @@ impl<T: Transport> SignalFishPollingClient<T> {
             self.close_phase = ClosePhase::Closed;
             return;
         }
-        let _ = self.core.disconnect(Some("client closed".into()));
+        let _ = self.core.disconnect(DisconnectReason::ClientShutdown);
         self.close_phase = match self.options.close_policy {
             PollingClosePolicy::Abandon => {
                 self.abandon_client_owned(false, now);
@@ -815,6 +1091,7 @@ impl<T: Transport> SignalFishPollingClient<T> {
                 capacity: self.command_capacity,
             });
         }
+        self.core.record_enqueued(command.kind());
         self.cmd_queue.push_back(QueuedCommand {
             command,
             enqueued_at: now,
@@ -832,15 +1109,14 @@ impl<T: Transport> SignalFishPollingClient<T> {
             let mut no_frame = None;
             match self.transport.poll_send(cx, &mut no_frame) {
                 std::task::Poll::Ready(Ok(())) => {
-                    if self.in_flight_is_game_data {
-                        self.core.record_game_data_sent();
+                    if let Some(kind) = self.in_flight_kind.take() {
+                        self.core.record_frame_sent(kind);
                     }
                     self.send_in_flight = false;
-                    self.in_flight_is_game_data = false;
                 }
                 std::task::Poll::Ready(Err(error)) => {
                     self.send_in_flight = false;
-                    self.in_flight_is_game_data = false;
+                    self.in_flight_kind = None;
                     return Err(error);
                 }
                 std::task::Poll::Pending => return Ok(()),
@@ -863,12 +1139,11 @@ impl<T: Transport> SignalFishPollingClient<T> {
                         else {
                             continue;
                         };
-                        self.pending_frame_is_game_data =
-                            matches!(message, ClientMessage::GameData { .. });
+                        self.pending_frame_kind = Some(message.kind());
                         self.pending_frame = Some(TransportFrame::Text(json));
                     }
                     PollingCommand::Binary(payload) => {
-                        self.pending_frame_is_game_data = true;
+                        self.pending_frame_kind = Some(ClientMessageKind::GameDataBinary);
                         self.pending_frame = Some(TransportFrame::Binary(payload));
                     }
                 }
@@ -902,22 +1177,20 @@ impl<T: Transport> SignalFishPollingClient<T> {
                     if !transferred {
                         break;
                     }
-                    if self.pending_frame_is_game_data {
-                        self.core.record_game_data_sent();
+                    if let Some(kind) = self.pending_frame_kind.take() {
+                        self.core.record_frame_sent(kind);
                     }
-                    self.pending_frame_is_game_data = false;
                 }
                 std::task::Poll::Ready(Err(error)) => {
                     if transferred {
-                        self.pending_frame_is_game_data = false;
+                        self.pending_frame_kind = None;
                     }
                     return Err(error);
                 }
                 std::task::Poll::Pending => {
                     if transferred {
                         self.send_in_flight = true;
-                        self.in_flight_is_game_data = self.pending_frame_is_game_data;
-                        self.pending_frame_is_game_data = false;
+                        self.in_flight_kind = self.pending_frame_kind.take();
                     }
                     break;
                 }
@@ -1021,7 +1294,7 @@ impl<T: Transport> SignalFishPollingClient<T> {
     fn handle_disconnect_at(
         &mut self,
         events: &mut Vec<SignalFishEvent>,
-        reason: Option<String>,
+        reason: DisconnectReason,
         cx: &mut std::task::Context<'_>,
         now: Instant,
     ) {
@@ -1046,12 +1319,12 @@ impl<T: Transport> SignalFishPollingClient<T> {
         if include_in_flight {
             abandoned = abandoned.saturating_add(usize::from(self.send_in_flight));
             self.send_in_flight = false;
-            self.in_flight_is_game_data = false;
+            self.in_flight_kind = None;
         }
         self.cmd_queue.clear();
         self.pending_frame = None;
         self.pending_frame_enqueued_at = None;
-        self.pending_frame_is_game_data = false;
+        self.pending_frame_kind = None;
         self.polling_stats.abandoned_commands = self
             .polling_stats
             .abandoned_commands
@@ -1246,7 +1519,7 @@ mod tests {
     use proptest::{prop_assert, prop_assert_eq};
 
     use super::*;
-    use crate::protocol::ServerMessage;
+    use crate::protocol::{ClientMessage, ServerMessage};
     use crate::transport::TransportFrame;
 
     // ── Mock transport ──────────────────────────────────────────────
@@ -2061,7 +2334,7 @@ mod tests {
             relay_type: "tcp".into(),
             current_spectators: vec![],
             ice_servers: vec![],
-            missed_events: vec![ServerMessage::ProtocolInfo(protocol_info_v3())],
+            missed_events: vec![ServerMessage::ProtocolInfo(protocol_info_v3()).into()],
             replay: None,
             sender_watermarks: vec![],
             reconnection_token: None,
@@ -2118,6 +2391,7 @@ mod tests {
             min_protocol_version: Some(2),
             max_protocol_version: Some(3),
             transports: None,
+            limits: None,
         }
     }
 
@@ -2221,6 +2495,84 @@ mod tests {
         r#"{"type":"Authenticated","data":{"app_name":"test","rate_limits":{"per_minute":60,"per_hour":1000,"per_day":10000}}}"#
     }
 
+    #[test]
+    fn rate_limits_cleared_when_the_connection_drops() {
+        let transport = MockTransport::new()
+            .with_incoming(vec![Some(Ok(authenticated_json_str().to_string()))]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        assert_eq!(client.rate_limits(), None);
+
+        client.poll();
+        assert_eq!(
+            client.rate_limits().map(|limits| limits.per_minute),
+            Some(60)
+        );
+
+        client.transport.incoming.push_back(None);
+        client.poll();
+        assert!(!client.is_connected());
+        assert_eq!(client.rate_limits(), None);
+    }
+
+    #[test]
+    fn unexpected_organization_disconnects_under_the_disconnect_policy() {
+        let transport = MockTransport::new()
+            .with_incoming(vec![Some(Ok(authenticated_json_str().to_string()))]);
+        let mut client = SignalFishPollingClient::new(
+            transport,
+            default_config()
+                .with_expected_organization("acme")
+                .with_organization_mismatch_policy(crate::OrganizationMismatchPolicy::Disconnect),
+        );
+
+        let events = client.poll();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                SignalFishEvent::OrganizationMismatch { actual: None, .. },
+                SignalFishEvent::Disconnected { .. },
+            ]
+        ));
+        assert_eq!(client.authenticated_info(), None);
+    }
+
+    #[test]
+    fn poll_and_poll_enveloped_share_one_event_sequence() {
+        let options = PollingClientOptions {
+            work_budget: PollingWorkBudget {
+                receive_frames: 1,
+                ..PollingWorkBudget::default()
+            },
+            ..PollingClientOptions::default()
+        };
+        let transport = MockTransport::new().with_incoming(vec![
+            Some(Ok(authenticated_json_str().to_string())),
+            Some(Ok(r#"{"type":"Pong"}"#.to_string())),
+            Some(Ok(r#"{"type":"Pong"}"#.to_string())),
+        ]);
+        let mut client =
+            SignalFishPollingClient::new_with_options(transport, default_config(), options);
+        assert_eq!(client.last_event_seq(), 0);
+
+        let plain = client.poll();
+        assert_eq!(client.last_event_seq(), plain.len() as u64);
+
+        let enveloped = client.poll_enveloped();
+        assert!(matches!(
+            enveloped.as_slice(),
+            [EventEnvelope {
+                event: SignalFishEvent::Pong,
+                ..
+            }]
+        ));
+        assert_eq!(enveloped[0].seq, plain.len() as u64 + 1);
+
+        let _ = client.poll();
+        let enveloped = client.poll_enveloped();
+        assert!(enveloped.is_empty());
+        assert_eq!(client.last_event_seq(), plain.len() as u64 + 2);
+    }
+
     #[test]
     fn stats_count_game_data_sent_and_received() {
         let game_data_json = |seq: u64| {
@@ -2257,10 +2609,45 @@ mod tests {
                 game_data_sent: 3,
                 game_data_received: 2,
                 messages_undecodable: 0,
+                blank_frames_skipped: 0,
+                multi_message_frames: 0,
+                paused_frames_skipped: 0,
+                cross_epoch_events_dropped: 0,
+                offline_commands_evicted: 0,
+                messages_expired: 0,
             }
         );
     }
 
+    #[test]
+    fn outgoing_breakdown_counts_each_kind_when_queued_and_when_sent() {
+        use crate::protocol::ClientMessageKind as Kind;
+
+        let transport = MockTransport::new()
+            .with_incoming(vec![Some(Ok(authenticated_json_str().to_string()))]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        client.ping().unwrap();
+        for seq in 0..2 {
+            client
+                .send_game_data(serde_json::json!({ "seq": seq }))
+                .unwrap();
+        }
+
+        let queued = std::collections::BTreeMap::from([
+            (Kind::Authenticate, 1),
+            (Kind::GameData, 2),
+            (Kind::Ping, 1),
+        ]);
+        let breakdown = client.outgoing_breakdown();
+        assert_eq!(breakdown.enqueued, queued);
+        assert!(breakdown.sent.is_empty(), "{breakdown:?}");
+
+        let _ = client.poll();
+        let breakdown = client.outgoing_breakdown();
+        assert_eq!(breakdown.enqueued, queued);
+        assert_eq!(breakdown.sent, queued);
+    }
+
     #[test]
     fn poll_receives_and_deserializes_messages() {
         let authenticated_json = r#"{"type":"Authenticated","data":{"app_name":"test","rate_limits":{"per_minute":60,"per_hour":1000,"per_day":10000}}}"#;
@@ -2318,6 +2705,78 @@ mod tests {
         assert_eq!(client.current_player_id(), Some(expected_player_id));
     }
 
+    #[test]
+    fn current_players_follows_roster_updates() {
+        let alice = crate::testing::player_id("Alice");
+        let bob = crate::testing::player_id("Bob");
+        let room_joined = crate::testing::room_joined("ABC123")
+            .players([crate::testing::player("Alice")])
+            .json();
+        let player_joined = serde_json::to_string(&ServerMessage::PlayerJoined {
+            player: crate::testing::player("Bob").build(),
+        })
+        .expect("PlayerJoined must serialize");
+        let lobby = serde_json::to_string(&ServerMessage::LobbyStateChanged {
+            lobby_state: crate::protocol::LobbyState::Lobby,
+            ready_players: vec![bob],
+            all_ready: false,
+        })
+        .expect("LobbyStateChanged must serialize");
+        let authority = serde_json::to_string(&ServerMessage::AuthorityChanged {
+            authority_player: Some(alice),
+            you_are_authority: true,
+        })
+        .expect("AuthorityChanged must serialize");
+        let transport = MockTransport::new().with_incoming(vec![
+            Some(Ok(room_joined)),
+            Some(Ok(player_joined)),
+            Some(Ok(lobby)),
+            Some(Ok(authority)),
+        ]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+
+        client.poll();
+
+        let players: Vec<_> = client
+            .current_players()
+            .iter()
+            .map(|p| (p.id, p.is_ready, p.is_authority))
+            .collect();
+        assert_eq!(players, vec![(alice, false, true), (bob, true, false)]);
+    }
+
+    #[test]
+    fn lobby_state_follows_room_and_resets_on_leave() {
+        let room_joined = crate::testing::room_joined("ABC123")
+            .lobby_state(crate::protocol::LobbyState::Lobby)
+            .json();
+        let finalized = serde_json::to_string(&ServerMessage::LobbyStateChanged {
+            lobby_state: crate::protocol::LobbyState::Finalized,
+            ready_players: vec![],
+            all_ready: true,
+        })
+        .expect("LobbyStateChanged must serialize");
+        let transport =
+            MockTransport::new().with_incoming(vec![Some(Ok(room_joined)), Some(Ok(finalized))]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        assert_eq!(client.lobby_state(), &crate::protocol::LobbyState::Waiting);
+
+        client.poll();
+        assert_eq!(
+            client.lobby_state(),
+            &crate::protocol::LobbyState::Finalized
+        );
+
+        client
+            .transport
+            .incoming
+            .push_back(Some(Ok(TransportFrame::Text(
+                r#"{"type":"RoomLeft"}"#.to_string(),
+            ))));
+        client.poll();
+        assert_eq!(client.lobby_state(), &crate::protocol::LobbyState::Waiting);
+    }
+
     #[test]
     fn join_room_queues_command() {
         let transport = MockTransport::new();
@@ -2379,6 +2838,38 @@ mod tests {
         assert!(!client.is_authenticated());
     }
 
+    #[test]
+    fn activity_tracks_each_direction_and_annotates_receive_errors() {
+        let transport =
+            MockTransport::new().with_incoming(vec![Some(Ok(authenticated_json_str().into()))]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        let idle = client.activity();
+        assert_eq!(idle.since_last_send, None);
+        assert_eq!(idle.since_last_recv, None);
+
+        let _ = client.poll();
+        std::thread::sleep(Duration::from_millis(20));
+        let activity = client.activity();
+        assert!(activity.since_last_send >= Some(Duration::from_millis(20)));
+        assert!(activity.since_last_recv >= Some(Duration::from_millis(20)));
+
+        client
+            .transport
+            .incoming
+            .push_back(Some(Err(SignalFishError::TransportReceive(
+                "connection reset".into(),
+            ))));
+        let events = client.poll();
+        let reason = events.iter().find_map(|event| match event {
+            SignalFishEvent::Disconnected { reason, .. } => Some(reason.to_string()),
+            _ => None,
+        });
+        let reason = reason.expect("receive error disconnects");
+        assert!(reason.contains("connection reset ("), "{reason}");
+        assert!(reason.contains("last send "), "{reason}");
+        assert!(!reason.contains("never"), "{reason}");
+    }
+
     #[test]
     fn poll_handles_transport_error() {
         // Transport returns an error.
@@ -2603,6 +3094,148 @@ mod tests {
         assert!(client.current_player_id().is_some());
     }
 
+    /// Counts `WARN`-or-worse events emitted while it is the default subscriber.
+    struct WarnCounter(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+    impl tracing::Subscriber for WarnCounter {
+        fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+            *metadata.level() <= tracing::Level::WARN
+        }
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn blank_frames_are_skipped_quietly_and_counted() {
+        let authenticated_json = r#"{"type":"Authenticated","data":{"app_name":"test","rate_limits":{"per_minute":60,"per_hour":1000,"per_day":10000}}}"#;
+        let transport = MockTransport::new().with_incoming(vec![
+            Some(Ok(String::new())),
+            Some(Ok("  \t ".to_string())),
+            Some(Ok("\r\n".to_string())),
+            Some(Ok(format!("{authenticated_json}\n"))),
+        ]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+
+        let warnings = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let events = tracing::subscriber::with_default(
+            WarnCounter(std::sync::Arc::clone(&warnings)),
+            || client.poll(),
+        );
+
+        assert_eq!(events.len(), 2, "expected 2 events, got: {events:?}");
+        assert!(matches!(events[0], SignalFishEvent::Connected));
+        assert!(matches!(events[1], SignalFishEvent::Authenticated { .. }));
+        assert_eq!(warnings.load(std::sync::atomic::Ordering::Relaxed), 0);
+        let stats = client.stats();
+        assert_eq!(stats.blank_frames_skipped, 3);
+        assert_eq!(stats.messages_undecodable, 0);
+    }
+
+    #[test]
+    fn strict_blank_frames_reports_them_as_decode_failures() {
+        let transport = MockTransport::new().with_incoming(vec![Some(Ok(" ".to_string()))]);
+        let mut client = SignalFishPollingClient::new(
+            transport,
+            default_config().with_strict_blank_frames(true),
+        );
+
+        let warnings = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let events = tracing::subscriber::with_default(
+            WarnCounter(std::sync::Arc::clone(&warnings)),
+            || client.poll(),
+        );
+
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, SignalFishEvent::DecodeFailed { .. })));
+        assert_eq!(warnings.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert_eq!(client.stats().messages_undecodable, 1);
+        assert_eq!(client.stats().blank_frames_skipped, 0);
+    }
+
+    #[test]
+    fn poll_splits_concatenated_messages() {
+        let authenticated_json = r#"{"type":"Authenticated","data":{"app_name":"test","rate_limits":{"per_minute":60,"per_hour":1000,"per_day":10000}}}"#;
+        let transport = MockTransport::new().with_incoming(vec![Some(Ok(format!(
+            "{authenticated_json}\n{{\"type\":\"Pong\"}}"
+        )))]);
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+
+        let events = client.poll();
+
+        assert_eq!(events.len(), 3, "expected 3 events, got: {events:?}");
+        assert!(matches!(events[1], SignalFishEvent::Authenticated { .. }));
+        assert!(matches!(events[2], SignalFishEvent::Pong));
+        assert_eq!(client.stats().multi_message_frames, 1);
+    }
+
+    #[test]
+    fn drop_cross_epoch_events_spans_the_frames_of_one_poll() {
+        let game_data = serde_json::to_string(&ServerMessage::GameData {
+            from_player: crate::testing::player_id("Bob"),
+            data: serde_json::json!({ "tick": 1 }),
+            seq: None,
+            epoch: None,
+            class: None,
+            key: None,
+        })
+        .expect("GameData must serialize");
+        let player_joined = serde_json::to_string(&ServerMessage::PlayerJoined {
+            player: crate::testing::player("Cara").build(),
+        })
+        .expect("PlayerJoined must serialize");
+        let room_left =
+            serde_json::to_string(&ServerMessage::RoomLeft).expect("RoomLeft must serialize");
+        let frames = |config: SignalFishConfig| {
+            let transport = MockTransport::new().with_incoming(vec![
+                Some(Ok(crate::testing::room_joined("OLD1").json())),
+                Some(Ok(game_data.clone())),
+                Some(Ok(room_left.clone())),
+                Some(Ok(crate::testing::room_joined("NEW1").json())),
+                Some(Ok(player_joined.clone())),
+            ]);
+            let mut client = SignalFishPollingClient::new(transport, config);
+            let events = event_names(&client.poll());
+            (events, client.stats().cross_epoch_events_dropped)
+        };
+
+        let (events, dropped) = frames(default_config().with_drop_cross_epoch_events(true));
+        assert_eq!(
+            events,
+            [
+                "Connected",
+                "RoomJoined",
+                "RoomLeft",
+                "RoomJoined",
+                "PlayerJoined"
+            ],
+            "the old room's GameData is dropped, the new room's PlayerJoined is not"
+        );
+        assert_eq!(dropped, 1);
+
+        let (events, dropped) = frames(default_config());
+        assert_eq!(
+            events,
+            [
+                "Connected",
+                "RoomJoined",
+                "GameData",
+                "RoomLeft",
+                "RoomJoined",
+                "PlayerJoined"
+            ]
+        );
+        assert_eq!(dropped, 0);
+    }
+
     #[test]
     fn poll_handles_malformed_json() {
         let malformed_json = "not valid json {{{";
@@ -3058,6 +3691,58 @@ mod tests {
         assert_eq!(sent_json["data"]["auth_token"], "token123");
     }
 
+    #[test]
+    fn reconnect_is_single_flight_until_answered() {
+        let transport = MockTransport::new();
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        client.poll(); // flush auth
+        let (player_id, room_id) = (uuid::Uuid::from_u128(1), uuid::Uuid::from_u128(2));
+
+        client
+            .reconnect(player_id, room_id, "tok".into())
+            .expect("first reconnect");
+        client.poll();
+        assert!(matches!(
+            client.reconnect(player_id, room_id, "tok".into()),
+            Err(SignalFishError::ReconnectInFlight)
+        ));
+
+        client
+            .transport
+            .incoming
+            .push_back(Some(Ok(TransportFrame::Text(reconnected_to(
+                room_id, "ROOM",
+            )))));
+        client.poll();
+        client
+            .reconnect(player_id, room_id, "tok".into())
+            .expect("answered, the next reconnect is queued");
+    }
+
+    #[test]
+    fn reconnect_refused_by_a_full_queue_is_not_in_flight() {
+        let allow = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let transport = TogglePendingSendTransport {
+            allow: std::sync::Arc::clone(&allow),
+            sent: Vec::new(),
+            _sent_binary: Vec::new(),
+        };
+        let config = default_config().with_command_channel_capacity(1);
+        let mut client = SignalFishPollingClient::new(transport, config);
+        let (player_id, room_id) = (uuid::Uuid::from_u128(1), uuid::Uuid::from_u128(2));
+
+        // Authenticate fills the only slot.
+        assert!(matches!(
+            client.reconnect(player_id, room_id, "tok".into()),
+            Err(SignalFishError::SendBufferFull { capacity: 1 })
+        ));
+        allow.store(true, std::sync::atomic::Ordering::Release);
+        let _ = client.poll();
+        client
+            .reconnect(player_id, room_id, "tok".into())
+            .expect("the refused reconnect never went out");
+    }
+
     #[test]
     fn join_as_spectator_queues_command() {
         let transport = MockTransport::new();
@@ -3297,6 +3982,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn decode_game_data_reports_failures_to_the_hook() {
+        let from = uuid::Uuid::from_u128(12);
+        let json = serde_json::to_string(&ServerMessage::GameData {
+            from_player: from,
+            data: serde_json::json!({"hp": "full"}),
+            seq: None,
+            epoch: None,
+            class: None,
+            key: None,
+        })
+        .expect("GameData ServerMessage must serialize to JSON");
+        let failures = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&failures);
+        let config = default_config().with_decode_failure_hook(crate::DecodeFailureHook::new(
+            move |from, kind| sink.lock().expect("hook sink").push((from, kind)),
+        ));
+        let transport = MockTransport::new().with_incoming(vec![Some(Ok(json))]);
+        let mut client = SignalFishPollingClient::new(transport, config);
+        let events = client.poll();
+        let event = events
+            .iter()
+            .find(|e| matches!(e, SignalFishEvent::GameData { .. }))
+            .expect("GameData event");
+
+        assert_eq!(
+            client.try_decode_game_data::<serde_json::Value>(event),
+            Some(serde_json::json!({"hp": "full"}))
+        );
+        assert!(client.try_decode_game_data::<u32>(event).is_none());
+        assert_eq!(
+            *failures.lock().expect("hook sink"),
+            vec![(from, crate::DecodeErrorKind::Mismatch)]
+        );
+    }
+
     #[test]
     fn poll_receives_game_data_binary_event() {
         let from = uuid::Uuid::from_u128(13);
@@ -3563,11 +4284,15 @@ mod tests {
             rjf.is_some(),
             "expected RoomJoinFailed event, got: {events:?}"
         );
-        if let SignalFishEvent::RoomJoinFailed { reason, error_code } =
-            rjf.expect("RoomJoinFailed event must exist (verified by preceding assert)")
+        if let SignalFishEvent::RoomJoinFailed {
+            reason,
+            error_code,
+            requested_room_code,
+        } = rjf.expect("RoomJoinFailed event must exist (verified by preceding assert)")
         {
             assert_eq!(reason, "room full");
             assert_eq!(*error_code, Some(crate::error_codes::ErrorCode::RoomFull));
+            assert_eq!(*requested_room_code, None, "no join was pending");
         }
     }
 
@@ -3605,6 +4330,83 @@ mod tests {
             .any(|e| matches!(e, SignalFishEvent::ReconnectionFailed { .. })));
     }
 
+    fn reconnected_to(room_id: RoomId, room_code: &str) -> String {
+        use crate::protocol::{LobbyState, ReconnectedPayload};
+        let payload = ReconnectedPayload {
+            room_id,
+            room_code: room_code.into(),
+            player_id: uuid::Uuid::from_u128(1),
+            game_name: "g".into(),
+            max_players: 4,
+            supports_authority: false,
+            current_players: vec![],
+            is_authority: false,
+            lobby_state: LobbyState::Waiting,
+            ready_players: vec![],
+            relay_type: "tcp".into(),
+            current_spectators: vec![],
+            ice_servers: vec![],
+            missed_events: vec![],
+            replay: None,
+            sender_watermarks: vec![],
+            reconnection_token: None,
+        };
+        serde_json::to_string(&ServerMessage::Reconnected(Box::new(payload)))
+            .expect("Reconnected ServerMessage must serialize to JSON")
+    }
+
+    #[test]
+    fn reconnected_to_another_room_than_requested_warns_then_applies() {
+        let requested = uuid::Uuid::from_u128(2);
+        let other = uuid::Uuid::from_u128(3);
+        let transport = MockTransport::new();
+        let mut client = SignalFishPollingClient::new(transport, default_config());
+        client.poll(); // flush auth
+
+        client
+            .reconnect(uuid::Uuid::from_u128(1), requested, "tok".into())
+            .expect("reconnect must succeed on connected client");
+        client.poll();
+        client
+            .transport
+            .incoming
+            .push_back(Some(Ok(TransportFrame::Text(reconnected_to(
+                other, "OTHER",
+            )))));
+        let events = client.poll();
+
+        match events.as_slice() {
+            [SignalFishEvent::SessionInconsistency {
+                expected,
+                got,
+                message_kind,
+            }, SignalFishEvent::Reconnected { .. }] => {
+                assert_eq!(expected.room_id, Some(requested));
+                assert_eq!(expected.room_code, None);
+                assert_eq!(got.room_id, Some(other));
+                assert_eq!(*message_kind, "Reconnected");
+            }
+            other => panic!("expected SessionInconsistency then Reconnected, got {other:?}"),
+        }
+        assert_eq!(client.current_room_id(), Some(other));
+
+        client
+            .reconnect(uuid::Uuid::from_u128(1), other, "tok".into())
+            .expect("second reconnect");
+        client.poll();
+        client
+            .transport
+            .incoming
+            .push_back(Some(Ok(TransportFrame::Text(reconnected_to(
+                other, "OTHER",
+            )))));
+        let events = client.poll();
+        assert!(
+            matches!(events.as_slice(), [SignalFishEvent::Reconnected { .. }]),
+            "a matching Reconnected is not flagged: {events:?}"
+        );
+    }
+
     #[test]
     fn poll_receives_player_reconnected_event() {
         let player_id = uuid::Uuid::from_u128(20);
@@ -3689,6 +4491,7 @@ mod tests {
                 min_protocol_version: None,
                 max_protocol_version: None,
                 transports: None,
+                limits: None,
             },
         ))
         .expect("ProtocolInfo ServerMessage must serialize to JSON");
@@ -3717,7 +4520,7 @@ mod tests {
             matches!(
                 e,
                 SignalFishEvent::Disconnected {
-                    reason: Some(_),
+                    reason: DisconnectReason::TransportError { .. },
                     ..
                 }
             )
@@ -3727,15 +4530,41 @@ mod tests {
             "expected Disconnected event, got: {events:?}"
         );
         if let SignalFishEvent::Disconnected {
-            reason: Some(r), ..
+            reason: DisconnectReason::TransportError { detail: r },
+            ..
         } = disconnected.expect("Disconnected event must exist (verified by preceding assert)")
         {
             assert!(
                 r.contains("transport send error"),
                 "expected reason to contain 'transport send error', got: {r}"
             );
+            assert!(
+                r.contains("during handshake"),
+                "a failed first send is a handshake failure, got: {r}"
+            );
         }
         assert!(!client.is_connected());
+        assert!(client.handshake_failed());
+    }
+
+    #[test]
+    fn poll_refuses_to_authenticate_with_a_rejected_sdk_version() {
+        let config = default_config()
+            .with_sdk_version("latest")
+            .with_sdk_version_policy(crate::SdkVersionPolicy::Reject);
+        let mut client = SignalFishPollingClient::new(MockTransport::new(), config);
+
+        let events = client.poll();
+        let [SignalFishEvent::Disconnected {
+            reason: DisconnectReason::HandshakeRefused { detail },
+            ..
+        }] = events.as_slice()
+        else {
+            panic!("expected one Disconnected, got {events:?}");
+        };
+        assert!(detail.starts_with("`latest`"), "{detail}");
+        assert!(client.handshake_failed());
+        assert!(client.transport.sent.is_empty(), "nothing reached the wire");
     }
 
     /// Transport whose `send()` stays `Pending` until `allow` is set, so tests
@@ -5790,4 +6619,162 @@ mod tests {
             prop_assert!(!client.is_closing(), "driver must finish after recovery suffix");
         }
     }
+
+    fn self_removal_client(
+        policy: crate::client::SelfRemovalPolicy,
+        tail: Vec<ServerMessage>,
+    ) -> SignalFishPollingClient<MockTransport> {
+        let me = uuid::Uuid::from_u128(42);
+        let room_joined = ServerMessage::RoomJoined(Box::new(crate::protocol::RoomJoinedPayload {
+            room_id: uuid::Uuid::from_u128(7),
+            room_code: "KICKED".into(),
+            player_id: me,
+            game_name: "idle-test".into(),
+            max_players: 4,
+            supports_authority: false,
+            current_players: vec![],
+            is_authority: false,
+            lobby_state: crate::protocol::LobbyState::Waiting,
+            ready_players: vec![],
+            relay_type: "auto".into(),
+            current_spectators: vec![],
+            ice_servers: vec![],
+            reconnection_token: Some("token".into()),
+        }));
+        let self_left = ServerMessage::PlayerLeft {
+            player_id: me,
+            epoch: None,
+            final_seq: None,
+        };
+        let incoming = [room_joined, self_left]
+            .into_iter()
+            .chain(tail)
+            .map(|message| {
+                Some(Ok(
+                    serde_json::to_string(&message).expect("serialize fixture")
+                ))
+            });
+        let transport = MockTransport::new().with_incoming(
+            std::iter::once(Some(Ok(authenticated_json_str().to_string())))
+                .chain(incoming)
+                .collect(),
+        );
+        SignalFishPollingClient::new(transport, default_config().with_self_removal_policy(policy))
+    }
+
+    fn event_names(events: &[SignalFishEvent]) -> Vec<String> {
+        events.iter().map(|event| format!("{event:?}")).collect()
+    }
+
+    #[test]
+    fn own_player_left_without_room_left_clears_after_grace() {
+        let grace = Duration::from_millis(500);
+        let mut client = self_removal_client(
+            crate::client::SelfRemovalPolicy::ClearAfter { grace },
+            vec![],
+        );
+        let base = Instant::now();
+        assert_eq!(
+            event_names(&client.poll_at(base)),
+            ["Connected", "Authenticated", "RoomJoined", "PlayerLeft"]
+        );
+        assert!(client.poll_at(base + grace / 2).is_empty());
+        assert_eq!(client.current_room_code(), Some("KICKED"));
+
+        let events = client.poll_at(base + grace);
+        assert_eq!(event_names(&events), ["RemovedFromRoom"]);
+        let SignalFishEvent::RemovedFromRoom { room_id, room_code } = &events[0] else {
+            unreachable!();
+        };
+        assert_eq!(*room_id, uuid::Uuid::from_u128(7));
+        assert_eq!(room_code, "KICKED");
+        assert_eq!(client.current_room_id(), None);
+        assert_eq!(client.current_room_code(), None);
+        assert_eq!(client.snapshot().reconnection_token, None);
+        assert_eq!(client.session_state(), crate::SessionState::Authenticated);
+    }
+
+    #[test]
+    fn own_player_left_followed_by_room_left_is_not_reported_twice() {
+        let grace = Duration::from_millis(500);
+        let mut client = self_removal_client(
+            crate::client::SelfRemovalPolicy::ClearAfter { grace },
+            vec![ServerMessage::RoomLeft],
+        );
+        let base = Instant::now();
+        assert_eq!(
+            event_names(&client.poll_at(base)),
+            [
+                "Connected",
+                "Authenticated",
+                "RoomJoined",
+                "PlayerLeft",
+                "RoomLeft"
+            ]
+        );
+        assert!(client.poll_at(base + grace * 2).is_empty());
+        assert_eq!(client.current_room_id(), None);
+    }
+
+    #[test]
+    fn zero_grace_clears_at_once_and_swallows_a_late_room_left() {
+        let mut client = self_removal_client(
+            crate::client::SelfRemovalPolicy::ClearAfter {
+                grace: Duration::ZERO,
+            },
+            vec![ServerMessage::RoomLeft, ServerMessage::Pong],
+        );
+        assert_eq!(
+            event_names(&client.poll()),
+            [
+                "Connected",
+                "Authenticated",
+                "RoomJoined",
+                "PlayerLeft",
+                "RemovedFromRoom",
+                "Pong"
+            ]
+        );
+        assert_eq!(client.current_room_id(), None);
+    }
+
+    #[test]
+    fn await_room_left_policy_warns_and_keeps_room_state() {
+        let mut client =
+            self_removal_client(crate::client::SelfRemovalPolicy::AwaitRoomLeft, vec![]);
+        let base = Instant::now();
+        assert_eq!(
+            event_names(&client.poll_at(base)),
+            [
+                "Connected",
+                "Authenticated",
+                "RoomJoined",
+                "PlayerLeft",
+                "SelfRemovalPending"
+            ]
+        );
+        assert!(client.poll_at(base + Duration::from_secs(60)).is_empty());
+        assert_eq!(client.current_room_code(), Some("KICKED"));
+        assert_eq!(client.session_state(), crate::SessionState::InRoom);
+    }
+
+    #[test]
+    fn await_room_left_policy_clears_on_room_left() {
+        let mut client = self_removal_client(
+            crate::client::SelfRemovalPolicy::AwaitRoomLeft,
+            vec![ServerMessage::RoomLeft],
+        );
+        assert_eq!(
+            event_names(&client.poll()),
+            [
+                "Connected",
+                "Authenticated",
+                "RoomJoined",
+                "PlayerLeft",
+                "SelfRemovalPending",
+                "RoomLeft"
+            ]
+        );
+        assert_eq!(client.current_room_id(), None);
+    }
 }
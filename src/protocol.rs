@@ -13,6 +13,9 @@ use uuid::Uuid;
 use crate::error_codes::ErrorCode;
 
 pub mod binary;
+pub(crate) mod lenient;
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+pub(crate) mod salvage;
 pub use binary::{
     decode_v2_binary_game_data, decode_v3_binary_game_data, V2BinaryGameDataFrame,
     V3BinaryGameDataFrame,
@@ -45,6 +48,10 @@ pub enum RelayTransport {
     /// Default: UDP for 2-4 players, TCP for 5+ players, WebSocket for browser builds.
     #[default]
     Auto,
+    /// A transport this SDK does not recognize, sent by a newer server.
+    /// Received only; it serializes as `"other"`, which no server accepts.
+    #[serde(other)]
+    Other,
 }
 
 /// Encoding format for sequenced game data payloads.
@@ -65,6 +72,10 @@ pub enum GameDataEncoding {
     /// variant as reserved until the server ships rkyv negotiation.
     #[serde(rename = "rkyv")]
     Rkyv,
+    /// An encoding this SDK does not recognize, sent by a newer server.
+    /// Received only; it serializes as `"other"`, which no server accepts.
+    #[serde(other)]
+    Other,
 }
 
 /// Protocol-v3 delivery policy for relayed game data.
@@ -237,12 +248,13 @@ pub enum ConnectionInfo {
         /// Client authentication token (opaque server-issued value).
         token: String,
         /// Assigned client ID (set by server after connection).
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         client_id: Option<u16>,
     },
     /// WebRTC connection info (for Matchbox).
     #[serde(rename = "webrtc")]
     WebRTC {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         sdp: Option<String>,
         ice_candidates: Vec<String>,
     },
@@ -261,6 +273,9 @@ pub enum SpectatorStateChangeReason {
     Disconnected,
     Removed,
     RoomClosed,
+    /// A reason this SDK does not recognize, sent by a newer server.
+    #[serde(other)]
+    Other,
 }
 
 /// Lobby readiness state.
@@ -271,6 +286,9 @@ pub enum LobbyState {
     Waiting,
     Lobby,
     Finalized,
+    /// A state this SDK does not recognize, sent by a newer server.
+    #[serde(other)]
+    Other,
 }
 
 // ── Structs ─────────────────────────────────────────────────────────
@@ -284,7 +302,15 @@ pub struct PlayerInfo {
     pub is_ready: bool,
     pub connected_at: String,
     /// Connection info for P2P establishment (provided when player is ready).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    ///
+    /// `None`, with a
+    /// [`FieldOutOfRange`](crate::SignalFishEvent::FieldOutOfRange) event,
+    /// when the server sends a port outside `1..=65535`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "lenient::connection_info"
+    )]
     pub connection_info: Option<ConnectionInfo>,
     /// Current server-tracked incarnation epoch (protocol v3 only).
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -310,39 +336,60 @@ pub struct PeerConnectionInfo {
     pub is_authority: bool,
     pub relay_type: String,
     /// Connection info provided by the peer for P2P establishment.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    ///
+    /// `None`, with a
+    /// [`FieldOutOfRange`](crate::SignalFishEvent::FieldOutOfRange) event,
+    /// when the server sends a port outside `1..=65535`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "lenient::connection_info"
+    )]
     pub connection_info: Option<ConnectionInfo>,
 }
 
 /// Rate limit information for an application.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// A negative count on the wire means no limit and decodes as
+/// [`UNLIMITED`](Self::UNLIMITED); so does a count above `u32::MAX`. Either
+/// is reported with a
+/// [`FieldOutOfRange`](crate::SignalFishEvent::FieldOutOfRange) event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RateLimitInfo {
     /// Requests allowed per minute.
+    #[serde(deserialize_with = "lenient::per_minute")]
     pub per_minute: u32,
     /// Requests allowed per hour.
+    #[serde(deserialize_with = "lenient::per_hour")]
     pub per_hour: u32,
     /// Requests allowed per day.
+    #[serde(deserialize_with = "lenient::per_day")]
     pub per_day: u32,
 }
 
+impl RateLimitInfo {
+    /// A count the server does not limit.
+    pub const UNLIMITED: u32 = u32::MAX;
+}
+
 /// Describes negotiated protocol capabilities for a specific SDK.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolInfoPayload {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub platform: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sdk_version: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub minimum_version: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub recommended_version: Option<String>,
     #[serde(default)]
     pub capabilities: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     #[serde(default)]
     pub game_data_formats: Vec<GameDataEncoding>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub player_name_rules: Option<PlayerNameRulesPayload>,
     /// Protocol version negotiated for this connection (protocol v3+ only).
     ///
@@ -375,6 +422,27 @@ pub struct ProtocolInfoPayload {
         deserialize_with = "deserialize_present_optional"
     )]
     pub transports: Option<Vec<MessageTransport>>,
+    /// Per-app caps this deployment enforces; absent on servers that do not
+    /// advertise them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ServerLimits>,
+}
+
+/// Caps a deployment advertises in [`ProtocolInfoPayload::limits`].
+///
+/// Each cap is optional; a server may advertise any subset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerLimits {
+    /// Rooms one game may have open at once; a join that would open another
+    /// fails with `MAX_ROOMS_PER_GAME_EXCEEDED`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rooms_per_game: Option<u32>,
+    /// Largest `max_players` a room may be created with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_players_per_room: Option<u32>,
+    /// Spectators one room admits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_spectators: Option<u32>,
 }
 
 /// Describes the characters a deployment allows inside `player_name`.
@@ -387,10 +455,107 @@ pub struct PlayerNameRulesPayload {
     pub allow_leading_trailing_whitespace: bool,
     #[serde(default)]
     pub allowed_symbols: Vec<char>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_allowed_characters: Option<String>,
 }
 
+impl PlayerNameRulesPayload {
+    /// Check `name` against these rules.
+    ///
+    /// Lengths count Unicode scalar values (`char`s), not bytes and not
+    /// grapheme clusters: `"Åsa"` is 3 long precomposed and 4 with a
+    /// combining ring, and a ZWJ family emoji is as long as the emoji and
+    /// joiners it is built from. Every `char` must be allowed on its own,
+    /// so a combining mark or a joiner needs `allowed_symbols` or
+    /// `additional_allowed_characters` unless it is alphanumeric. Only
+    /// U+0020 counts as a space; the leading/trailing rule covers all
+    /// whitespace.
+    ///
+    /// # Errors
+    ///
+    /// The first rule `name` breaks, checking length first.
+    pub fn validate(&self, name: &str) -> Result<(), PlayerNameViolation> {
+        let length = name.chars().count();
+        if length < self.min_length {
+            return Err(PlayerNameViolation::TooShort {
+                length,
+                min: self.min_length,
+            });
+        }
+        if length > self.max_length {
+            return Err(PlayerNameViolation::TooLong {
+                length,
+                max: self.max_length,
+            });
+        }
+        if !self.allow_leading_trailing_whitespace && name.trim() != name {
+            return Err(PlayerNameViolation::SurroundingWhitespace);
+        }
+        match name.chars().find(|c| !self.allows(*c)) {
+            Some(character) => Err(PlayerNameViolation::DisallowedCharacter(character)),
+            None => Ok(()),
+        }
+    }
+
+    fn allows(&self, c: char) -> bool {
+        let alphanumeric = if self.allow_unicode_alphanumeric {
+            c.is_alphanumeric()
+        } else {
+            c.is_ascii_alphanumeric()
+        };
+        alphanumeric
+            || (self.allow_spaces && c == ' ')
+            || self.allowed_symbols.contains(&c)
+            || self
+                .additional_allowed_characters
+                .as_deref()
+                .is_some_and(|extra| extra.contains(c))
+    }
+}
+
+/// Why [`PlayerNameRulesPayload::validate`] rejected a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlayerNameViolation {
+    /// Fewer `char`s than `min_length`.
+    TooShort {
+        /// The name's length in `char`s.
+        length: usize,
+        /// The rules' minimum.
+        min: usize,
+    },
+    /// More `char`s than `max_length`.
+    TooLong {
+        /// The name's length in `char`s.
+        length: usize,
+        /// The rules' maximum.
+        max: usize,
+    },
+    /// Leading or trailing whitespace the rules do not allow.
+    SurroundingWhitespace,
+    /// A character the rules do not allow.
+    DisallowedCharacter(char),
+}
+
+impl std::fmt::Display for PlayerNameViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort { length, min } => {
+                write!(f, "name is {length} characters, under the minimum of {min}")
+            }
+            Self::TooLong { length, max } => {
+                write!(f, "name is {length} characters, over the maximum of {max}")
+            }
+            Self::SurroundingWhitespace => f.write_str("name has leading or trailing whitespace"),
+            Self::DisallowedCharacter(c) => {
+                write!(f, "name contains the disallowed character {c:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlayerNameViolation {}
+
 /// A STUN/TURN server for WebRTC ICE negotiation (protocol v3).
 ///
 /// `username`/`credential` are present only for TURN servers; bare STUN entries
@@ -400,10 +565,10 @@ pub struct IceServer {
     /// STUN/TURN URLs (e.g. `stun:stun.l.google.com:19302`).
     pub urls: Vec<String>,
     /// TURN username (omitted for credential-less STUN servers).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub username: Option<String>,
     /// TURN credential (omitted for credential-less STUN servers).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub credential: Option<String>,
 }
 
@@ -433,6 +598,10 @@ pub struct RoomJoinedPayload {
     pub room_code: String,
     pub player_id: PlayerId,
     pub game_name: String,
+    /// Clamped into `0..=255`, with a
+    /// [`FieldOutOfRange`](crate::SignalFishEvent::FieldOutOfRange) event,
+    /// when the server sends a value outside it.
+    #[serde(deserialize_with = "lenient::max_players")]
     pub max_players: u8,
     pub supports_authority: bool,
     pub current_players: Vec<PlayerInfo>,
@@ -462,6 +631,10 @@ pub struct ReconnectedPayload {
     pub room_code: String,
     pub player_id: PlayerId,
     pub game_name: String,
+    /// Clamped into `0..=255`, with a
+    /// [`FieldOutOfRange`](crate::SignalFishEvent::FieldOutOfRange) event,
+    /// when the server sends a value outside it.
+    #[serde(deserialize_with = "lenient::max_players")]
     pub max_players: u8,
     pub supports_authority: bool,
     pub current_players: Vec<PlayerInfo>,
@@ -476,8 +649,13 @@ pub struct ReconnectedPayload {
     /// v3 only). Empty — and absent from the wire — for v2 connections.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub ice_servers: Vec<IceServer>,
-    /// Events that occurred while disconnected.
-    pub missed_events: Vec<ServerMessage>,
+    /// Events that occurred while disconnected, in server order.
+    ///
+    /// The room fields above already include their effect, so the clients
+    /// report these for observability and never apply them to room state: a
+    /// `PlayerLeft` then `PlayerJoined` for the same player leaves the roster
+    /// exactly as `current_players` describes it.
+    pub missed_events: Vec<MissedEvent>,
     /// Completeness of `missed_events` (protocol v3 only).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub replay: Option<ReplayStatus>,
@@ -489,6 +667,49 @@ pub struct ReconnectedPayload {
     pub reconnection_token: Option<String>,
 }
 
+impl ReconnectedPayload {
+    /// Sequence numbers absent from `missed_events`, as inclusive ranges in
+    /// ascending order.
+    ///
+    /// Only the span between the lowest and highest sequenced event is
+    /// checked; entries without a `seq` are ignored, and duplicates or
+    /// out-of-order entries do not count as gaps.
+    #[must_use]
+    pub fn missing_ranges(&self) -> Vec<std::ops::RangeInclusive<u64>> {
+        let mut seqs: Vec<u64> = self.missed_events.iter().filter_map(|e| e.seq).collect();
+        seqs.sort_unstable();
+        seqs.dedup();
+        seqs.windows(2)
+            .filter_map(|pair| match *pair {
+                [prev, next] if next > prev.saturating_add(1) => Some(prev + 1..=next - 1),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// One entry of [`ReconnectedPayload::missed_events`].
+///
+/// On the wire the server's sequence number sits beside the message's own
+/// `type`/`data` keys (`{"type":"Pong","seq":7}`). Servers that send bare
+/// messages are accepted too and yield `seq: None`; such entries serialize
+/// back to the bare shape unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedEvent {
+    /// The replayed server message.
+    #[serde(flatten)]
+    pub message: ServerMessage,
+    /// Server-assigned sequence number, when the server provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+}
+
+impl From<ServerMessage> for MissedEvent {
+    fn from(message: ServerMessage) -> Self {
+        Self { message, seq: None }
+    }
+}
+
 /// Payload for the `SpectatorJoined` server message.
 /// Boxed in `ServerMessage` to reduce enum size.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -500,7 +721,7 @@ pub struct SpectatorJoinedPayload {
     pub current_players: Vec<PlayerInfo>,
     pub current_spectators: Vec<SpectatorInfo>,
     pub lobby_state: LobbyState,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reason: Option<SpectatorStateChangeReason>,
 }
 
@@ -518,7 +739,7 @@ pub struct SessionPlanPayload {
     /// Chosen data-path transport (`relay`, `direct`, or `webrtc`).
     pub transport: TransportKind,
     /// The elected host, present only for `host` topology.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub host: Option<PlayerId>,
     /// Peers this recipient should connect to (excludes the recipient itself).
     pub peers: Vec<SessionPeer>,
@@ -541,37 +762,40 @@ pub enum ClientMessage {
         /// Public App ID (safe to embed in game builds, e.g., "mb_app_abc123...").
         app_id: String,
         /// SDK version for debugging and analytics.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         sdk_version: Option<String>,
         /// Platform information (e.g., "unity", "godot", "unreal").
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         platform: Option<String>,
         /// Preferred game data encoding (defaults to JSON text frames).
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         game_data_format: Option<GameDataEncoding>,
         /// Highest protocol version the client speaks (protocol v3+).
         ///
         /// Omitted by default so the server treats the client as v2 relay-only
         /// and the wire bytes stay identical to v2.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         protocol_version: Option<u16>,
         /// Data-path transports the client can actually fulfill (protocol v3+).
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         supported_transports: Option<Vec<TransportKind>>,
         /// Session topologies the client can participate in (protocol v3+).
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         supported_topologies: Option<Vec<Topology>>,
     },
     /// Join or create a room for a specific game.
     JoinRoom {
         game_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         room_code: Option<String>,
         player_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         max_players: Option<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         supports_authority: Option<bool>,
         /// Preferred relay transport protocol (TCP, UDP, or Auto).
         /// If not specified, defaults to Auto.
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         relay_transport: Option<RelayTransport>,
     },
     /// Leave the current room.
@@ -647,6 +871,104 @@ pub enum ClientMessage {
         /// Whether that transport is currently connected.
         connected: bool,
     },
+    /// Ask the server for its clock reading.
+    ///
+    /// The server echoes `client_sent_at_ms` in a
+    /// [`TimeSyncResponse`](ServerMessage::TimeSyncResponse).
+    TimeSyncRequest {
+        /// Local Unix-epoch milliseconds when the request was built.
+        client_sent_at_ms: u64,
+    },
+}
+
+impl ClientMessage {
+    /// The message's variant, without its fields.
+    #[must_use]
+    pub fn kind(&self) -> ClientMessageKind {
+        match self {
+            Self::Authenticate { .. } => ClientMessageKind::Authenticate,
+            Self::JoinRoom { .. } => ClientMessageKind::JoinRoom,
+            Self::LeaveRoom => ClientMessageKind::LeaveRoom,
+            Self::GameData { .. } => ClientMessageKind::GameData,
+            Self::AuthorityRequest { .. } => ClientMessageKind::AuthorityRequest,
+            Self::PlayerReady => ClientMessageKind::PlayerReady,
+            Self::ProvideConnectionInfo { .. } => ClientMessageKind::ProvideConnectionInfo,
+            Self::Ping => ClientMessageKind::Ping,
+            Self::Reconnect { .. } => ClientMessageKind::Reconnect,
+            Self::JoinAsSpectator { .. } => ClientMessageKind::JoinAsSpectator,
+            Self::LeaveSpectator => ClientMessageKind::LeaveSpectator,
+            Self::StartGame => ClientMessageKind::StartGame,
+            Self::Signal { .. } => ClientMessageKind::Signal,
+            Self::TransportStatus { .. } => ClientMessageKind::TransportStatus,
+            Self::TimeSyncRequest { .. } => ClientMessageKind::TimeSyncRequest,
+        }
+    }
+}
+
+/// What kind of message the client sent, for per-kind counters such as
+/// `SignalFishClient::outgoing_breakdown`.
+///
+/// One variant per [`ClientMessage`] variant, plus
+/// [`GameDataBinary`](Self::GameDataBinary) for binary game-data frames,
+/// which are not JSON messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ClientMessageKind {
+    Authenticate,
+    JoinRoom,
+    LeaveRoom,
+    GameData,
+    /// A binary game-data frame (protocol v3).
+    GameDataBinary,
+    AuthorityRequest,
+    PlayerReady,
+    ProvideConnectionInfo,
+    Ping,
+    Reconnect,
+    JoinAsSpectator,
+    LeaveSpectator,
+    StartGame,
+    Signal,
+    TransportStatus,
+    TimeSyncRequest,
+}
+
+impl ClientMessageKind {
+    /// The message's wire `type`, usable as a metrics label.
+    /// [`GameDataBinary`](Self::GameDataBinary) is `"GameDataBinary"`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Authenticate => "Authenticate",
+            Self::JoinRoom => "JoinRoom",
+            Self::LeaveRoom => "LeaveRoom",
+            Self::GameData => "GameData",
+            Self::GameDataBinary => "GameDataBinary",
+            Self::AuthorityRequest => "AuthorityRequest",
+            Self::PlayerReady => "PlayerReady",
+            Self::ProvideConnectionInfo => "ProvideConnectionInfo",
+            Self::Ping => "Ping",
+            Self::Reconnect => "Reconnect",
+            Self::JoinAsSpectator => "JoinAsSpectator",
+            Self::LeaveSpectator => "LeaveSpectator",
+            Self::StartGame => "StartGame",
+            Self::Signal => "Signal",
+            Self::TransportStatus => "TransportStatus",
+            Self::TimeSyncRequest => "TimeSyncRequest",
+        }
+    }
+
+    /// Whether this kind carries game data.
+    #[must_use]
+    pub fn is_game_data(self) -> bool {
+        matches!(self, Self::GameData | Self::GameDataBinary)
+    }
+}
+
+impl std::fmt::Display for ClientMessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Message types sent from server to client.
@@ -658,7 +980,7 @@ pub enum ServerMessage {
         /// App name for confirmation.
         app_name: String,
         /// Organization name (if any).
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         organization: Option<String>,
         /// Rate limits for this app.
         rate_limits: RateLimitInfo,
@@ -677,7 +999,7 @@ pub enum ServerMessage {
     /// Failed to join room.
     RoomJoinFailed {
         reason: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         error_code: Option<ErrorCode>,
     },
     /// Successfully left room.
@@ -719,14 +1041,16 @@ pub enum ServerMessage {
     },
     /// Authority status changed.
     AuthorityChanged {
+        #[serde(default)]
         authority_player: Option<PlayerId>,
         you_are_authority: bool,
     },
     /// Authority request response.
     AuthorityResponse {
         granted: bool,
+        #[serde(default)]
         reason: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         error_code: Option<ErrorCode>,
     },
     /// Lobby state changed (room full, player readiness changed, etc.).
@@ -741,6 +1065,13 @@ pub enum ServerMessage {
     },
     /// Pong response to ping.
     Pong,
+    /// Answer to a [`TimeSyncRequest`](ClientMessage::TimeSyncRequest).
+    TimeSyncResponse {
+        /// The request's `client_sent_at_ms`, echoed verbatim.
+        client_sent_at_ms: u64,
+        /// Server Unix-epoch milliseconds when it handled the request.
+        server_time_ms: u64,
+    },
     /// Reconnection successful (boxed to reduce enum size).
     Reconnected(Box<ReconnectedPayload>),
     /// Reconnection failed.
@@ -759,16 +1090,16 @@ pub enum ServerMessage {
     /// Failed to join as spectator.
     SpectatorJoinFailed {
         reason: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         error_code: Option<ErrorCode>,
     },
     /// Successfully left spectator mode.
     SpectatorLeft {
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         room_id: Option<RoomId>,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         room_code: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         reason: Option<SpectatorStateChangeReason>,
         #[serde(default)]
         current_spectators: Vec<SpectatorInfo>,
@@ -778,13 +1109,13 @@ pub enum ServerMessage {
         spectator: SpectatorInfo,
         #[serde(default)]
         current_spectators: Vec<SpectatorInfo>,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         reason: Option<SpectatorStateChangeReason>,
     },
     /// Another spectator left the room.
     SpectatorDisconnected {
         spectator_id: PlayerId,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         reason: Option<SpectatorStateChangeReason>,
         #[serde(default)]
         current_spectators: Vec<SpectatorInfo>,
@@ -792,9 +1123,23 @@ pub enum ServerMessage {
     /// Error message.
     Error {
         message: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         error_code: Option<ErrorCode>,
     },
+    /// Room-wide announcement from the server (MOTD, tournament notices).
+    ///
+    /// Servers that predate announcements never send it; both optional
+    /// fields may be omitted.
+    Announcement {
+        /// Human-readable announcement text.
+        message: String,
+        /// Server-defined severity (e.g. `"info"`, `"warning"`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity: Option<String>,
+        /// Arbitrary structured context.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        metadata: Option<serde_json::Value>,
+    },
     /// An opaque WebRTC signal relayed from a peer.
     ///
     /// **Protocol v3 only.** Sent only on a v3-negotiated connection.
@@ -874,9 +1219,12 @@ where
 /// client needs `polling-client`) so it is not dead code in a build with neither.
 #[must_use]
 #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
-pub(crate) fn replayed_negotiated_version(missed_events: &[ServerMessage]) -> Option<u16> {
-    missed_events.iter().rev().find_map(|msg| match msg {
-        ServerMessage::ProtocolInfo(info) => info.protocol_version,
-        _ => None,
-    })
+pub(crate) fn replayed_negotiated_version(missed_events: &[MissedEvent]) -> Option<u16> {
+    missed_events
+        .iter()
+        .rev()
+        .find_map(|missed| match &missed.message {
+            ServerMessage::ProtocolInfo(info) => info.protocol_version,
+            _ => None,
+        })
 }
@@ -0,0 +1,166 @@
+//! Lenient decoding of numeric server fields.
+//!
+//! A server that sends a value outside a field's Rust type (a `max_players`
+//! of 300, a `per_minute` of -1, a port of 0) would otherwise fail the whole
+//! message. The deserializers here adjust such a value instead, log a
+//! warning, and report a [`FieldOutOfRange`](SignalFishEvent::FieldOutOfRange)
+//! to [`collecting`], so the rest of the message still arrives.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+use super::ConnectionInfo;
+use crate::event::{OutOfRangeAction, SignalFishEvent};
+
+thread_local! {
+    /// Adjustments made by the decode running on this thread, while a
+    /// [`collecting`] call is active.
+    static ADJUSTED: RefCell<Option<Vec<SignalFishEvent>>> = const { RefCell::new(None) };
+}
+
+/// Run `decode`, returning a [`FieldOutOfRange`](SignalFishEvent::FieldOutOfRange)
+/// for every field it adjusted, in decode order.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+pub(crate) fn collecting<T>(decode: impl FnOnce() -> T) -> (T, Vec<SignalFishEvent>) {
+    let outer = ADJUSTED.with(|cell| cell.replace(Some(Vec::new())));
+    let decoded = decode();
+    let adjusted = ADJUSTED
+        .with(|cell| cell.replace(outer))
+        .unwrap_or_default();
+    (decoded, adjusted)
+}
+
+fn report(field: &'static str, value: String, action: OutOfRangeAction) {
+    tracing::warn!("server sent out-of-range {field} {value}; {action:?}");
+    ADJUSTED.with(|cell| {
+        if let Some(adjusted) = cell.borrow_mut().as_mut() {
+            adjusted.push(SignalFishEvent::FieldOutOfRange {
+                field,
+                value,
+                action,
+            });
+        }
+    });
+}
+
+/// A JSON integer of any sign that fits in 64 bits.
+enum WireInt {
+    Unsigned(u64),
+    Negative(i64),
+}
+
+impl fmt::Display for WireInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(value) => value.fmt(f),
+            Self::Negative(value) => value.fmt(f),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WireInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct WireIntVisitor;
+
+        impl Visitor<'_> for WireIntVisitor {
+            type Value = WireInt;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an integer")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<WireInt, E> {
+                Ok(WireInt::Unsigned(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<WireInt, E> {
+                Ok(u64::try_from(value).map_or(WireInt::Negative(value), WireInt::Unsigned))
+            }
+        }
+
+        deserializer.deserialize_any(WireIntVisitor)
+    }
+}
+
+/// `max_players`: clamped into `0..=255`.
+pub(super) fn max_players<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let wire = WireInt::deserialize(deserializer)?;
+    let to = match wire {
+        WireInt::Unsigned(value) => match u8::try_from(value) {
+            Ok(value) => return Ok(value),
+            Err(_) => u8::MAX,
+        },
+        WireInt::Negative(_) => u8::MIN,
+    };
+    report(
+        "max_players",
+        wire.to_string(),
+        OutOfRangeAction::Clamped { to: to.into() },
+    );
+    Ok(to)
+}
+
+/// A rate-limit count: negative means unlimited, and both that and an
+/// overflow become [`RateLimitInfo::UNLIMITED`](super::RateLimitInfo::UNLIMITED).
+fn rate_limit<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    field: &'static str,
+) -> Result<u32, D::Error> {
+    let wire = WireInt::deserialize(deserializer)?;
+    match wire {
+        WireInt::Unsigned(value) => Ok(u32::try_from(value).unwrap_or_else(|_| {
+            report(
+                field,
+                wire.to_string(),
+                OutOfRangeAction::Clamped {
+                    to: u32::MAX.into(),
+                },
+            );
+            u32::MAX
+        })),
+        WireInt::Negative(_) => {
+            report(field, wire.to_string(), OutOfRangeAction::Unlimited);
+            Ok(u32::MAX)
+        }
+    }
+}
+
+pub(super) fn per_minute<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    rate_limit(deserializer, "rate_limits.per_minute")
+}
+
+pub(super) fn per_hour<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    rate_limit(deserializer, "rate_limits.per_hour")
+}
+
+pub(super) fn per_day<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    rate_limit(deserializer, "rate_limits.per_day")
+}
+
+/// A peer's `connection_info`: dropped when its `port` is a number outside
+/// `1..=65535`, which no peer could connect to.
+pub(super) fn connection_info<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<ConnectionInfo>, D::Error> {
+    let Some(value) = Option::<serde_json::Value>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Some(port) = value.get("port").filter(|port| port.is_number()) {
+        if !port
+            .as_u64()
+            .is_some_and(|port| (1..=65535).contains(&port))
+        {
+            report(
+                "connection_info.port",
+                port.to_string(),
+                OutOfRangeAction::Dropped,
+            );
+            return Ok(None);
+        }
+    }
+    ConnectionInfo::deserialize(value)
+        .map(Some)
+        .map_err(de::Error::custom)
+}
@@ -0,0 +1,126 @@
+//! Salvage of the large room payloads.
+//!
+//! One malformed entry deep inside a `RoomJoined` — a player whose
+//! `connection_info` has the wrong shape — would otherwise fail the whole
+//! message and keep the client out of the room. When the typed decode fails,
+//! [`salvage`] re-reads `RoomJoined` and `Reconnected` as JSON values, drops
+//! the collection entries that do not parse on their own, and defaults
+//! optional fields that still do not, reporting each with a
+//! [`PayloadSalvaged`](SignalFishEvent::PayloadSalvaged). Required scalar
+//! fields are never invented; a message missing one still fails.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{
+    lenient, IceServer, MissedEvent, PlayerId, PlayerInfo, ReplayStatus, SenderWatermark,
+    ServerMessage, SpectatorInfo,
+};
+use crate::event::SignalFishEvent;
+
+/// Decode `text` with whatever did not parse dropped or defaulted, returning
+/// the message after a [`PayloadSalvaged`](SignalFishEvent::PayloadSalvaged)
+/// for each repair and any
+/// [`FieldOutOfRange`](SignalFishEvent::FieldOutOfRange) the decode raised.
+/// `None` when `text` is not a salvageable message or nothing repaired it.
+pub(crate) fn salvage(text: &str) -> Option<(ServerMessage, Vec<SignalFishEvent>)> {
+    let mut message: Value = serde_json::from_str(text).ok()?;
+    let message_type = match message.get("type").and_then(Value::as_str) {
+        Some("RoomJoined") => "RoomJoined",
+        Some("Reconnected") => "Reconnected",
+        _ => return None,
+    };
+    let data = message.get_mut("data").and_then(Value::as_object_mut)?;
+    let mut repairs = Repairs {
+        message_type,
+        data,
+        events: Vec::new(),
+    };
+    repairs.entries::<PlayerInfo>("current_players");
+    repairs.entries::<PlayerId>("ready_players");
+    repairs.entries::<SpectatorInfo>("current_spectators");
+    repairs.optional::<Vec<SpectatorInfo>>("current_spectators");
+    repairs.entries::<IceServer>("ice_servers");
+    repairs.optional::<Vec<IceServer>>("ice_servers");
+    repairs.optional::<Option<String>>("reconnection_token");
+    if message_type == "Reconnected" {
+        repairs.entries::<MissedEvent>("missed_events");
+        repairs.entries::<SenderWatermark>("sender_watermarks");
+        repairs.optional::<Vec<SenderWatermark>>("sender_watermarks");
+        repairs.optional::<Option<ReplayStatus>>("replay");
+    }
+    let mut events = repairs.events;
+    if events.is_empty() {
+        return None;
+    }
+
+    let (decoded, adjusted) = lenient::collecting(|| ServerMessage::deserialize(&message));
+    let decoded = decoded.ok()?;
+    events.extend(adjusted);
+    Some((decoded, events))
+}
+
+/// The `data` object of a message under repair and what was done to it.
+struct Repairs<'a> {
+    message_type: &'static str,
+    data: &'a mut Map<String, Value>,
+    events: Vec<SignalFishEvent>,
+}
+
+impl Repairs<'_> {
+    /// Drop the entries of the array at `field` that do not parse as `T`.
+    fn entries<T: DeserializeOwned>(&mut self, field: &str) {
+        let Some(Value::Array(entries)) = self.data.get_mut(field) else {
+            return;
+        };
+        let mut index = 0;
+        entries.retain(|entry| {
+            let kept = match T::deserialize(entry) {
+                Ok(_) => true,
+                Err(error) => {
+                    report(
+                        &mut self.events,
+                        self.message_type,
+                        format!("{field}[{index}]"),
+                        &error,
+                    );
+                    false
+                }
+            };
+            index += 1;
+            kept
+        });
+    }
+
+    /// Remove `field` when it does not parse as `T`, leaving it to its
+    /// default. Only for fields the payload defaults when absent.
+    fn optional<T: DeserializeOwned>(&mut self, field: &str) {
+        let Some(value) = self.data.get(field) else {
+            return;
+        };
+        if let Err(error) = T::deserialize(value) {
+            self.data.remove(field);
+            report(
+                &mut self.events,
+                self.message_type,
+                field.to_owned(),
+                &error,
+            );
+        }
+    }
+}
+
+fn report(
+    events: &mut Vec<SignalFishEvent>,
+    message_type: &'static str,
+    field: String,
+    error: &serde_json::Error,
+) {
+    tracing::warn!("salvaged {message_type} without its malformed {field}: {error}");
+    events.push(SignalFishEvent::PayloadSalvaged {
+        message_type,
+        field,
+        error: error.to_string(),
+    });
+}
@@ -0,0 +1,189 @@
+//! Automatic reconnection for the async client.
+//!
+//! [`SignalFishClient::start_with_reconnect`](crate::SignalFishClient::start_with_reconnect)
+//! takes a [`TransportFactory`] instead of a connected transport. When the
+//! transport fails, the client emits
+//! [`Reconnecting`](crate::SignalFishEvent::Reconnecting), waits out an
+//! exponential backoff described by the config's [`ReconnectPolicy`], dials a
+//! fresh transport, and authenticates again. Commands sent while the
+//! connection is down are held, up to
+//! [`SignalFishConfig::offline_queue_capacity`](crate::SignalFishConfig::offline_queue_capacity),
+//! and go out once the new connection authenticates.
+//! The config's [`ResumePolicy`] decides whether the client also returns to
+//! the room it was in.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::error::SignalFishError;
+use crate::transport::Transport;
+
+/// Backoff for a client started with `start_with_reconnect`.
+///
+/// Set via
+/// [`SignalFishConfig::with_reconnect_policy`](crate::SignalFishConfig::with_reconnect_policy).
+/// Attempt `n` of an outage waits `base_delay * 2^(n - 1)`, capped at
+/// `max_delay`, then shortened by up to `jitter` of itself at random so a
+/// fleet of clients dropped together does not redial in lockstep. Clients
+/// started with a connected transport ignore it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Wait before the first attempt of an outage. Defaults to **500 ms**.
+    pub base_delay: Duration,
+    /// Longest wait between attempts. Defaults to **30 seconds**.
+    pub max_delay: Duration,
+    /// Fraction (`0.0..=1.0`) of each wait that is randomized away. Defaults
+    /// to **0.2**; values outside the range are clamped.
+    pub jitter: f64,
+    /// Attempts per outage before the client gives up and emits its final
+    /// [`Disconnected`](crate::SignalFishEvent::Disconnected). The count
+    /// restarts once a new connection authenticates. Defaults to **`None`**:
+    /// retry forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Wait before `attempt` (1-based), with `unit` in `0.0..1.0` choosing
+    /// how much of the jitter applies.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn delay(&self, attempt: u32, unit: f64) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay);
+        let jitter = if self.jitter.is_nan() {
+            0.0
+        } else {
+            self.jitter.clamp(0.0, 1.0)
+        };
+        delay.mul_f64(1.0 - jitter * unit.clamp(0.0, 1.0))
+    }
+}
+
+/// Whether a reconnecting client returns to the room it held when the
+/// connection dropped.
+///
+/// Set via
+/// [`SignalFishConfig::with_resume_policy`](crate::SignalFishConfig::with_resume_policy).
+/// Resuming needs the reconnection token the server issued with
+/// `RoomJoined` or `Reconnected`; without one there is nothing to resume.
+/// Clients started with a connected transport ignore it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResumePolicy {
+    /// Forget the room when the connection drops (the default).
+    #[default]
+    Never,
+    /// Send `Reconnect` on each new transport, right after `Authenticate`
+    /// and ahead of commands held during the outage. The server answers with
+    /// [`Reconnected`](crate::SignalFishEvent::Reconnected) or
+    /// [`ReconnectionFailed`](crate::SignalFishEvent::ReconnectionFailed);
+    /// after a failure the client carries on outside any room.
+    Auto,
+    /// Keep the room's credentials for
+    /// [`SignalFishClient::resume`](crate::SignalFishClient::resume) to send
+    /// when the application is ready.
+    Manual,
+}
+
+/// Future returned by [`TransportFactory::connect`].
+pub type ConnectFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, SignalFishError>> + Send + 'a>>;
+
+/// Source of fresh transports for a reconnecting client.
+///
+/// Every call must open a new connection: the client sends one
+/// `Authenticate` per transport, and a transport that already carried a
+/// session is refused with [`SignalFishError::TransportReused`] (see
+/// [`Transport::claim_session`]), which ends the client rather than counting
+/// as a failed attempt.
+///
+/// [`WebSocketDialer`](crate::WebSocketDialer) re-dials a URL. Any
+/// `FnMut() -> impl Future<Output = Result<T, SignalFishError>>` is a factory
+/// too:
+///
+/// ```rust,no_run
+/// # async fn example() {
+/// use signal_fish_client::{SignalFishClient, SignalFishConfig, WebSocketTransport};
+///
+/// let config = SignalFishConfig::new("mb_app_abc123");
+/// let (client, events) = SignalFishClient::start_with_reconnect(
+///     || WebSocketTransport::connect("ws://localhost:3536/ws"),
+///     config,
+/// );
+/// # drop((client, events));
+/// # }
+/// ```
+pub trait TransportFactory: Send + 'static {
+    /// The transport each connection uses.
+    type Transport: Transport + Send + 'static;
+
+    /// Open a new connection. An `Err` counts as a failed attempt.
+    fn connect(&mut self) -> ConnectFuture<'_, Self::Transport>;
+}
+
+impl<F, Fut, T> TransportFactory for F
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, SignalFishError>> + Send + 'static,
+    T: Transport + Send + 'static,
+{
+    type Transport = T;
+
+    fn connect(&mut self) -> ConnectFuture<'_, T> {
+        Box::pin(self())
+    }
+}
+
+#[cfg(all(test, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.5,
+            ..ReconnectPolicy::default()
+        }
+    }
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let delays: Vec<_> = (1..=6)
+            .map(|attempt| policy().delay(attempt, 0.0))
+            .collect();
+        let millis: Vec<_> = delays.iter().map(Duration::as_millis).collect();
+        assert_eq!(millis, [100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(policy().delay(u32::MAX, 0.0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_shortens_by_at_most_its_fraction() {
+        assert_eq!(policy().delay(2, 1.0), Duration::from_millis(100));
+        assert_eq!(policy().delay(2, 0.5), Duration::from_millis(150));
+
+        let wild = ReconnectPolicy {
+            jitter: 7.0,
+            ..policy()
+        };
+        assert_eq!(wild.delay(1, 1.0), Duration::ZERO);
+        let nan = ReconnectPolicy {
+            jitter: f64::NAN,
+            ..policy()
+        };
+        assert_eq!(nan.delay(1, 1.0), Duration::from_millis(100));
+    }
+}
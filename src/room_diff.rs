@@ -0,0 +1,695 @@
+//! Structured "what changed" diffs of room state for UI updates.
+//!
+//! UI frameworks render incrementally, so re-deriving the whole roster on every
+//! event is wasteful. A [`RoomSnapshot`] captures the parts of a room a lobby
+//! screen shows; [`diff`] compares two of them, and [`DiffTracker`] keeps a
+//! snapshot current from [`SignalFishEvent`]s and yields a [`RoomDiff`] for
+//! each event that changed something. The clients keep the same snapshot and
+//! return it from `room_snapshot()`.
+//!
+//! ```rust
+//! use signal_fish_client::{DiffTracker, SignalFishEvent};
+//!
+//! let mut tracker = DiffTracker::new();
+//! # let events: Vec<SignalFishEvent> = Vec::new();
+//! for event in &events {
+//!     if let Some(diff) = tracker.apply(event) {
+//!         for id in &diff.players_added {
+//!             let _player = tracker.snapshot().player(id); // add a roster row
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::event::SignalFishEvent;
+use crate::protocol::{LobbyState, PlayerId, PlayerInfo, RoomId, SpectatorInfo};
+
+/// The before and after values of one changed field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Change<T> {
+    /// Value in the earlier snapshot.
+    pub before: T,
+    /// Value in the later snapshot.
+    pub after: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    /// `Some(Change)` when `before` and `after` differ.
+    fn between(before: T, after: T) -> Option<Self> {
+        (before != after).then_some(Self { before, after })
+    }
+}
+
+/// Room state relevant to a roster or lobby UI.
+///
+/// The [`Default`] snapshot (no `room_id`, no players) means "not in a room".
+#[derive(Debug, Clone, Default)]
+pub struct RoomSnapshot {
+    /// The room, or `None` when not in one.
+    pub room_id: Option<RoomId>,
+    /// Human-readable room code, or `None` when not in a room.
+    pub room_code: Option<String>,
+    /// The local player's ID in the room (the spectator ID when spectating).
+    pub player_id: Option<PlayerId>,
+    /// The room's game, or `None` when not in a room.
+    pub game_name: Option<String>,
+    /// Room capacity; `0` when spectating, since the server does not report it.
+    pub max_players: u8,
+    /// Lobby readiness state.
+    pub lobby_state: LobbyState,
+    /// The player holding authority, if any.
+    pub authority: Option<PlayerId>,
+    /// Whether the local player holds authority.
+    pub is_authority: bool,
+    /// Current players, in server order.
+    pub players: Vec<PlayerInfo>,
+    /// Spectators watching the room, in server order.
+    pub spectators: Vec<SpectatorInfo>,
+}
+
+impl RoomSnapshot {
+    /// Whether this snapshot describes a room.
+    #[must_use]
+    pub fn is_in_room(&self) -> bool {
+        self.room_id.is_some()
+    }
+
+    /// The player with `id`, if present.
+    #[must_use]
+    pub fn player(&self, id: &PlayerId) -> Option<&PlayerInfo> {
+        self.players.iter().find(|p| p.id == *id)
+    }
+
+    /// Number of players in the room.
+    #[must_use]
+    pub fn occupancy(&self) -> usize {
+        self.players.len()
+    }
+
+    pub(crate) fn player_mut(&mut self, id: &PlayerId) -> Option<&mut PlayerInfo> {
+        self.players.iter_mut().find(|p| p.id == *id)
+    }
+
+    /// Add `spectator`, or take the server's full list when it sent one.
+    pub(crate) fn spectator_joined(&mut self, spectator: &SpectatorInfo, all: &[SpectatorInfo]) {
+        if all.is_empty() {
+            self.spectators.retain(|s| s.id != spectator.id);
+            self.spectators.push(spectator.clone());
+        } else {
+            self.spectators = all.to_vec();
+        }
+    }
+
+    pub(crate) fn from_room(
+        room_id: RoomId,
+        room_code: &str,
+        max_players: u8,
+        lobby_state: &LobbyState,
+        current_players: &[PlayerInfo],
+        ready_players: &[PlayerId],
+    ) -> Self {
+        let mut players = current_players.to_vec();
+        for player in &mut players {
+            player.is_ready |= ready_players.contains(&player.id);
+        }
+        Self {
+            room_id: Some(room_id),
+            room_code: Some(room_code.to_owned()),
+            max_players,
+            lobby_state: lobby_state.clone(),
+            authority: players.iter().find(|p| p.is_authority).map(|p| p.id),
+            players,
+            ..Self::default()
+        }
+    }
+}
+
+/// One changed field of a player present in both snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerFieldChange {
+    /// Display name changed.
+    Name(Change<String>),
+    /// Readiness changed.
+    Ready(Change<bool>),
+    /// Authority flag changed.
+    Authority(Change<bool>),
+    /// Incarnation epoch changed (protocol v3; typically after a reconnect).
+    Epoch(Change<Option<u32>>),
+}
+
+/// A player present in both snapshots whose fields changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerUpdate {
+    /// The player.
+    pub player_id: PlayerId,
+    /// Changed fields, in declaration order of [`PlayerFieldChange`].
+    pub changes: Vec<PlayerFieldChange>,
+}
+
+/// Differences between two [`RoomSnapshot`]s.
+///
+/// Player lists follow the roster order of the snapshot they come from: added
+/// and updated players in the later snapshot's order, removed players in the
+/// earlier one's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomDiff {
+    /// Entered, left, or switched rooms.
+    pub room: Option<Change<Option<RoomId>>>,
+    /// Players only in the later snapshot.
+    pub players_added: Vec<PlayerId>,
+    /// Players only in the earlier snapshot.
+    pub players_removed: Vec<PlayerId>,
+    /// Players in both snapshots with at least one changed field.
+    pub players_updated: Vec<PlayerUpdate>,
+    /// Lobby state changed.
+    pub lobby_state: Option<Change<LobbyState>>,
+    /// Authority moved, appeared, or was cleared.
+    pub authority: Option<Change<Option<PlayerId>>>,
+    /// Player count changed.
+    pub occupancy: Option<Change<usize>>,
+}
+
+impl RoomDiff {
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compare two snapshots.
+#[must_use]
+pub fn diff(before: &RoomSnapshot, after: &RoomSnapshot) -> RoomDiff {
+    let players_added = after
+        .players
+        .iter()
+        .filter(|p| before.player(&p.id).is_none())
+        .map(|p| p.id)
+        .collect();
+    let players_removed = before
+        .players
+        .iter()
+        .filter(|p| after.player(&p.id).is_none())
+        .map(|p| p.id)
+        .collect();
+    let players_updated = after
+        .players
+        .iter()
+        .filter_map(|new| {
+            let old = before.player(&new.id)?;
+            let changes: Vec<_> = [
+                Change::between(old.name.clone(), new.name.clone()).map(PlayerFieldChange::Name),
+                Change::between(old.is_ready, new.is_ready).map(PlayerFieldChange::Ready),
+                Change::between(old.is_authority, new.is_authority)
+                    .map(PlayerFieldChange::Authority),
+                Change::between(old.epoch, new.epoch).map(PlayerFieldChange::Epoch),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            (!changes.is_empty()).then_some(PlayerUpdate {
+                player_id: new.id,
+                changes,
+            })
+        })
+        .collect();
+
+    RoomDiff {
+        room: Change::between(before.room_id, after.room_id),
+        players_added,
+        players_removed,
+        players_updated,
+        lobby_state: Change::between(before.lobby_state.clone(), after.lobby_state.clone()),
+        authority: Change::between(before.authority, after.authority),
+        occupancy: Change::between(before.occupancy(), after.occupancy()),
+    }
+}
+
+/// Keeps a [`RoomSnapshot`] current from [`SignalFishEvent`]s and reports what
+/// each event changed.
+///
+/// Feed it every event the client emits, in order. Events that do not affect
+/// room state are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct DiffTracker {
+    snapshot: RoomSnapshot,
+}
+
+impl DiffTracker {
+    /// Create a tracker that starts outside any room.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current room state.
+    #[must_use]
+    pub fn snapshot(&self) -> &RoomSnapshot {
+        &self.snapshot
+    }
+
+    /// Apply `event` and return what it changed, or `None` if nothing did.
+    pub fn apply(&mut self, event: &SignalFishEvent) -> Option<RoomDiff> {
+        let before = self.snapshot.clone();
+        let next = &mut self.snapshot;
+        match event {
+            SignalFishEvent::RoomJoined {
+                room_id,
+                room_code,
+                player_id,
+                game_name,
+                max_players,
+                current_players,
+                is_authority,
+                lobby_state,
+                ready_players,
+                current_spectators,
+                ..
+            }
+            | SignalFishEvent::Reconnected {
+                room_id,
+                room_code,
+                player_id,
+                game_name,
+                max_players,
+                current_players,
+                is_authority,
+                lobby_state,
+                ready_players,
+                current_spectators,
+                ..
+            } => {
+                *next = RoomSnapshot {
+                    player_id: Some(*player_id),
+                    game_name: Some(game_name.clone()),
+                    is_authority: *is_authority,
+                    spectators: current_spectators.clone(),
+                    ..RoomSnapshot::from_room(
+                        *room_id,
+                        room_code,
+                        *max_players,
+                        lobby_state,
+                        current_players,
+                        ready_players,
+                    )
+                };
+            }
+            SignalFishEvent::RoomLeft
+            | SignalFishEvent::Disconnected { .. }
+            | SignalFishEvent::SessionInvalidated { .. }
+            | SignalFishEvent::RemovedFromRoom { .. } => *next = RoomSnapshot::default(),
+            SignalFishEvent::PlayerJoined { player } => match next.player_mut(&player.id) {
+                Some(existing) => *existing = player.clone(),
+                None => next.players.push(player.clone()),
+            },
+            SignalFishEvent::PlayerLeft { player_id, .. } => {
+                next.players.retain(|p| p.id != *player_id);
+                if next.authority == Some(*player_id) {
+                    next.authority = None;
+                }
+            }
+            SignalFishEvent::PlayerReconnected {
+                player_id,
+                epoch: Some(epoch),
+            } => {
+                if let Some(player) = next.player_mut(player_id) {
+                    player.epoch = Some(*epoch);
+                }
+            }
+            SignalFishEvent::AuthorityChanged {
+                authority_player,
+                you_are_authority,
+            } => {
+                next.authority = *authority_player;
+                next.is_authority = *you_are_authority;
+                for player in &mut next.players {
+                    player.is_authority = Some(player.id) == *authority_player;
+                }
+            }
+            SignalFishEvent::LobbyStateChanged {
+                lobby_state,
+                ready_players,
+                ..
+            } => {
+                next.lobby_state = lobby_state.clone();
+                for player in &mut next.players {
+                    player.is_ready = ready_players.contains(&player.id);
+                }
+            }
+            // Spectators are kept in the snapshot but not diffed.
+            SignalFishEvent::NewSpectatorJoined {
+                spectator,
+                current_spectators,
+                ..
+            } => next.spectator_joined(spectator, current_spectators),
+            SignalFishEvent::SpectatorDisconnected { spectator_id, .. } => {
+                next.spectators.retain(|s| s.id != *spectator_id);
+            }
+            _ => return None,
+        }
+        let changes = diff(&before, &self.snapshot);
+        (!changes.is_empty()).then_some(changes)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::indexing_slicing)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> PlayerId {
+        uuid::Uuid::from_u128(n)
+    }
+
+    fn player(n: u128, name: &str) -> PlayerInfo {
+        PlayerInfo {
+            id: id(n),
+            name: name.into(),
+            is_authority: false,
+            is_ready: false,
+            connected_at: "2026-01-01T00:00:00Z".into(),
+            connection_info: None,
+            epoch: None,
+            seq: None,
+        }
+    }
+
+    fn spectator(n: u128, name: &str) -> SpectatorInfo {
+        SpectatorInfo {
+            id: id(n),
+            name: name.into(),
+            connected_at: "2026-01-01T00:00:00Z".into(),
+        }
+    }
+
+    fn room_joined(players: Vec<PlayerInfo>) -> SignalFishEvent {
+        SignalFishEvent::RoomJoined {
+            room_id: uuid::Uuid::from_u128(100),
+            room_code: "ABC123".into(),
+            player_id: id(1),
+            game_name: "game".into(),
+            max_players: 4,
+            supports_authority: true,
+            current_players: players,
+            is_authority: false,
+            lobby_state: LobbyState::Waiting,
+            ready_players: vec![],
+            relay_type: "auto".into(),
+            current_spectators: vec![],
+            ice_servers: vec![],
+            reconnection_token: None,
+        }
+    }
+
+    fn joined_tracker() -> DiffTracker {
+        let mut tracker = DiffTracker::new();
+        tracker.apply(&room_joined(vec![player(1, "Alice"), player(2, "Bob")]));
+        tracker
+    }
+
+    #[test]
+    fn identical_snapshots_have_an_empty_diff() {
+        let tracker = joined_tracker();
+        assert!(diff(tracker.snapshot(), tracker.snapshot()).is_empty());
+    }
+
+    #[test]
+    fn joining_a_room_reports_room_and_roster() {
+        let mut tracker = DiffTracker::new();
+        let changes = tracker
+            .apply(&room_joined(vec![player(1, "Alice"), player(2, "Bob")]))
+            .unwrap();
+
+        assert_eq!(
+            changes.room,
+            Some(Change {
+                before: None,
+                after: Some(uuid::Uuid::from_u128(100)),
+            })
+        );
+        assert_eq!(changes.players_added, vec![id(1), id(2)]);
+        assert_eq!(
+            changes.occupancy,
+            Some(Change {
+                before: 0,
+                after: 2
+            })
+        );
+        assert!(changes.lobby_state.is_none());
+    }
+
+    #[test]
+    fn player_joined_and_left_report_roster_changes() {
+        let mut tracker = joined_tracker();
+
+        let joined = tracker
+            .apply(&SignalFishEvent::PlayerJoined {
+                player: player(3, "Cara"),
+            })
+            .unwrap();
+        assert_eq!(joined.players_added, vec![id(3)]);
+        assert_eq!(
+            joined.occupancy,
+            Some(Change {
+                before: 2,
+                after: 3
+            })
+        );
+
+        let left = tracker
+            .apply(&SignalFishEvent::PlayerLeft {
+                player_id: id(2),
+                epoch: None,
+                final_seq: None,
+            })
+            .unwrap();
+        assert_eq!(left.players_removed, vec![id(2)]);
+        assert_eq!(
+            left.occupancy,
+            Some(Change {
+                before: 3,
+                after: 2
+            })
+        );
+        assert!(tracker.snapshot().player(&id(2)).is_none());
+    }
+
+    #[test]
+    fn authority_change_reports_holder_and_player_flags() {
+        let mut tracker = joined_tracker();
+
+        let changes = tracker
+            .apply(&SignalFishEvent::AuthorityChanged {
+                authority_player: Some(id(2)),
+                you_are_authority: false,
+            })
+            .unwrap();
+
+        assert_eq!(
+            changes.authority,
+            Some(Change {
+                before: None,
+                after: Some(id(2)),
+            })
+        );
+        assert_eq!(
+            changes.players_updated,
+            vec![PlayerUpdate {
+                player_id: id(2),
+                changes: vec![PlayerFieldChange::Authority(Change {
+                    before: false,
+                    after: true,
+                })],
+            }]
+        );
+    }
+
+    #[test]
+    fn lobby_state_change_reports_state_and_readiness() {
+        let mut tracker = joined_tracker();
+
+        let changes = tracker
+            .apply(&SignalFishEvent::LobbyStateChanged {
+                lobby_state: LobbyState::Lobby,
+                ready_players: vec![id(1)],
+                all_ready: false,
+            })
+            .unwrap();
+
+        assert_eq!(
+            changes.lobby_state,
+            Some(Change {
+                before: LobbyState::Waiting,
+                after: LobbyState::Lobby,
+            })
+        );
+        assert_eq!(changes.players_updated.len(), 1);
+        assert_eq!(changes.players_updated[0].player_id, id(1));
+        assert_eq!(
+            changes.players_updated[0].changes,
+            vec![PlayerFieldChange::Ready(Change {
+                before: false,
+                after: true,
+            })]
+        );
+        assert!(changes.occupancy.is_none());
+    }
+
+    #[test]
+    fn reconnect_epoch_bump_reports_player_update() {
+        let mut tracker = joined_tracker();
+
+        let changes = tracker
+            .apply(&SignalFishEvent::PlayerReconnected {
+                player_id: id(2),
+                epoch: Some(2),
+            })
+            .unwrap();
+
+        assert_eq!(
+            changes.players_updated[0].changes,
+            vec![PlayerFieldChange::Epoch(Change {
+                before: None,
+                after: Some(2),
+            })]
+        );
+    }
+
+    #[test]
+    fn rejoining_player_with_new_name_is_an_update() {
+        let mut tracker = joined_tracker();
+
+        let changes = tracker
+            .apply(&SignalFishEvent::PlayerJoined {
+                player: player(2, "Robert"),
+            })
+            .unwrap();
+
+        assert!(changes.players_added.is_empty());
+        assert_eq!(
+            changes.players_updated[0].changes,
+            vec![PlayerFieldChange::Name(Change {
+                before: "Bob".into(),
+                after: "Robert".into(),
+            })]
+        );
+    }
+
+    #[test]
+    fn snapshot_keeps_the_local_seat_and_spectators() {
+        let mut tracker = DiffTracker::new();
+        let mut joined = room_joined(vec![player(1, "Alice")]);
+        if let SignalFishEvent::RoomJoined {
+            is_authority,
+            current_spectators,
+            ..
+        } = &mut joined
+        {
+            *is_authority = true;
+            current_spectators.push(spectator(7, "Sam"));
+        }
+        tracker.apply(&joined);
+
+        let changes = tracker.apply(&SignalFishEvent::NewSpectatorJoined {
+            spectator: spectator(8, "Tia"),
+            current_spectators: vec![],
+            reason: None,
+        });
+        assert!(changes.is_none(), "spectators are not diffed");
+        tracker.apply(&SignalFishEvent::SpectatorDisconnected {
+            spectator_id: id(7),
+            reason: None,
+            current_spectators: vec![],
+        });
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.player_id, Some(id(1)));
+        assert_eq!(snapshot.game_name.as_deref(), Some("game"));
+        assert!(snapshot.is_authority);
+        let spectators: Vec<_> = snapshot.spectators.iter().map(|s| s.id).collect();
+        assert_eq!(spectators, vec![id(8)]);
+    }
+
+    #[test]
+    fn leaving_the_room_clears_everything() {
+        let mut tracker = joined_tracker();
+
+        let changes = tracker.apply(&SignalFishEvent::RoomLeft).unwrap();
+
+        assert_eq!(changes.players_removed, vec![id(1), id(2)]);
+        assert_eq!(changes.room.unwrap().after, None);
+        assert!(!tracker.snapshot().is_in_room());
+    }
+
+    #[test]
+    fn unrelated_and_no_op_events_yield_nothing() {
+        let mut tracker = joined_tracker();
+
+        assert!(tracker.apply(&SignalFishEvent::Pong).is_none());
+        assert!(tracker
+            .apply(&SignalFishEvent::LobbyStateChanged {
+                lobby_state: LobbyState::Waiting,
+                ready_players: vec![],
+                all_ready: false,
+            })
+            .is_none());
+    }
+
+    #[test]
+    fn burst_of_events_diffs_against_the_start_of_the_burst() {
+        let mut tracker = joined_tracker();
+        let start = tracker.snapshot().clone();
+
+        let burst = [
+            SignalFishEvent::PlayerJoined {
+                player: player(3, "Cara"),
+            },
+            SignalFishEvent::PlayerLeft {
+                player_id: id(1),
+                epoch: None,
+                final_seq: None,
+            },
+            SignalFishEvent::AuthorityChanged {
+                authority_player: Some(id(3)),
+                you_are_authority: false,
+            },
+            SignalFishEvent::LobbyStateChanged {
+                lobby_state: LobbyState::Lobby,
+                ready_players: vec![id(2), id(3)],
+                all_ready: true,
+            },
+        ];
+        let per_event: Vec<_> = burst.iter().filter_map(|e| tracker.apply(e)).collect();
+        assert_eq!(per_event.len(), burst.len());
+
+        let combined = diff(&start, tracker.snapshot());
+        assert!(combined.room.is_none());
+        assert_eq!(combined.players_added, vec![id(3)]);
+        assert_eq!(combined.players_removed, vec![id(1)]);
+        assert_eq!(
+            combined.players_updated,
+            vec![PlayerUpdate {
+                player_id: id(2),
+                changes: vec![PlayerFieldChange::Ready(Change {
+                    before: false,
+                    after: true,
+                })],
+            }]
+        );
+        assert_eq!(
+            combined.lobby_state,
+            Some(Change {
+                before: LobbyState::Waiting,
+                after: LobbyState::Lobby,
+            })
+        );
+        assert_eq!(
+            combined.authority,
+            Some(Change {
+                before: None,
+                after: Some(id(3)),
+            })
+        );
+        assert!(combined.occupancy.is_none(), "2 players before and after");
+    }
+}
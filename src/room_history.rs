@@ -0,0 +1,183 @@
+//! A record of each room session, for analytics.
+//!
+//! With [`SignalFishConfig::room_history`](crate::SignalFishConfig::room_history)
+//! set, the clients append a [`RoomSessionRecord`] each time the client leaves
+//! a room, is removed from one, or loses the connection while in one, keeping
+//! the most recent records oldest first. The records serialize for upload.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::protocol::RoomId;
+
+/// Why a room session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomLeaveReason {
+    /// The server confirmed a `leave_room()` or `leave_spectator()`, or the
+    /// client moved straight into another room.
+    Left,
+    /// The server ended the session without being asked: a kick, an idle
+    /// timeout, or the room closing.
+    Removed,
+    /// The connection ended, or the server invalidated the session, while in
+    /// the room.
+    Disconnected,
+}
+
+/// One ended room session, returned by `room_history()` on either client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoomSessionRecord {
+    /// The room.
+    pub room_id: RoomId,
+    /// Human-readable room code.
+    pub room_code: String,
+    /// The room's game.
+    pub game_name: String,
+    /// Whether the client watched the room as a spectator.
+    pub spectator: bool,
+    /// When the client entered the room, in Unix-epoch milliseconds.
+    pub joined_at_ms: u64,
+    /// When the session ended, in Unix-epoch milliseconds.
+    pub left_at_ms: u64,
+    /// Time spent in the room.
+    pub duration: Duration,
+    /// Most players in the room at once, including this client.
+    pub peak_players: usize,
+    /// Why the session ended.
+    pub reason_left: RoomLeaveReason,
+}
+
+/// Bounded, oldest-first log of ended room sessions plus the one in progress.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+#[derive(Debug)]
+pub(crate) struct RoomHistory {
+    limit: usize,
+    records: std::collections::VecDeque<RoomSessionRecord>,
+    /// The session in progress, completed when it ends.
+    open: Option<RoomSessionRecord>,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl RoomHistory {
+    /// A log keeping up to `limit` records; `0` records nothing.
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            records: std::collections::VecDeque::new(),
+            open: None,
+        }
+    }
+
+    /// The client entered `room_id` at `now_ms`. Entering another room while a
+    /// session is open ends that one as [`RoomLeaveReason::Left`]; the same
+    /// room (a `Reconnected` restoring it) continues it.
+    pub(crate) fn entered(
+        &mut self,
+        room_id: RoomId,
+        room_code: &str,
+        game_name: &str,
+        spectator: bool,
+        now_ms: u64,
+    ) {
+        if self.limit == 0 {
+            return;
+        }
+        if self
+            .open
+            .as_ref()
+            .is_some_and(|open| open.room_id == room_id)
+        {
+            return;
+        }
+        self.ended(RoomLeaveReason::Left, now_ms);
+        self.open = Some(RoomSessionRecord {
+            room_id,
+            room_code: room_code.to_owned(),
+            game_name: game_name.to_owned(),
+            spectator,
+            joined_at_ms: now_ms,
+            left_at_ms: now_ms,
+            duration: Duration::ZERO,
+            peak_players: 0,
+            reason_left: RoomLeaveReason::Left,
+        });
+    }
+
+    /// The room now holds `players` players.
+    pub(crate) fn occupancy(&mut self, players: usize) {
+        if let Some(open) = &mut self.open {
+            open.peak_players = open.peak_players.max(players);
+        }
+    }
+
+    /// The open session, if any, ended at `now_ms` for `reason`.
+    pub(crate) fn ended(&mut self, reason: RoomLeaveReason, now_ms: u64) {
+        let Some(mut record) = self.open.take() else {
+            return;
+        };
+        record.left_at_ms = now_ms;
+        record.duration =
+            Duration::from_millis(record.left_at_ms.saturating_sub(record.joined_at_ms));
+        record.reason_left = reason;
+        if self.records.len() == self.limit {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    pub(crate) fn records(&self) -> Vec<RoomSessionRecord> {
+        self.records.iter().cloned().collect()
+    }
+}
+
+#[cfg(all(test, any(feature = "tokio-runtime", feature = "polling-client")))]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+mod tests {
+    use super::*;
+
+    fn room(n: u128) -> RoomId {
+        uuid::Uuid::from_u128(n)
+    }
+
+    #[test]
+    fn disabled_history_records_nothing() {
+        let mut history = RoomHistory::new(0);
+        history.entered(room(1), "ABC", "game", false, 1_000);
+        history.ended(RoomLeaveReason::Left, 2_000);
+        assert!(history.records().is_empty());
+    }
+
+    #[test]
+    fn records_are_bounded_oldest_first() {
+        let mut history = RoomHistory::new(2);
+        for n in 1..=3 {
+            history.entered(room(n), &format!("R{n}"), "game", false, 1_000);
+            history.occupancy(usize::try_from(n).unwrap());
+            history.ended(RoomLeaveReason::Removed, 2_000);
+        }
+
+        let records = history.records();
+        let codes: Vec<_> = records.iter().map(|r| r.room_code.as_str()).collect();
+        assert_eq!(codes, ["R2", "R3"]);
+        assert_eq!(records.last().unwrap().peak_players, 3);
+        assert_eq!(records.last().unwrap().duration, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn switching_rooms_ends_the_previous_session_as_left() {
+        let mut history = RoomHistory::new(8);
+        history.entered(room(1), "ONE", "game", false, 1_000);
+        history.entered(room(1), "ONE", "game", false, 2_000);
+        history.entered(room(2), "TWO", "game", false, 3_000);
+        history.ended(RoomLeaveReason::Disconnected, 6_000);
+
+        let records = history.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].reason_left, RoomLeaveReason::Left);
+        assert_eq!(records[0].duration, Duration::from_secs(2));
+        assert_eq!(records[1].reason_left, RoomLeaveReason::Disconnected);
+        assert_eq!(records[1].duration, Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,296 @@
+//! Settings that can change while a client is connected.
+//!
+//! Most of [`SignalFishConfig`] shapes the connection itself (the App ID,
+//! negotiated protocol, channel capacities) and only takes effect on a new
+//! client. A few knobs are safe to turn on a live session — for example a
+//! faster heartbeat while diagnosing a user's flaky connection. A
+//! [`RuntimeConfigPatch`] names those changes; `update_runtime_config()` on
+//! either client applies them without reconnecting, and `effective_config()`
+//! reports the patched values with [`ConfigSource::Runtime`](crate::ConfigSource::Runtime).
+
+use std::time::Duration;
+
+use crate::SignalFishConfig;
+
+/// A change to the runtime-tunable settings of a running client.
+///
+/// Build one setting at a time, or derive it with [`between`](Self::between)
+/// from the configuration the client was started with and an edited copy.
+/// Settings the patch leaves unset keep their current value.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use signal_fish_client::{RuntimeConfigPatch, SignalFishConfig};
+///
+/// let started = SignalFishConfig::new("mb_app");
+/// let faster = started.clone().with_ping_interval(Duration::from_secs(5));
+/// assert_eq!(
+///     RuntimeConfigPatch::between(&started, &faster),
+///     RuntimeConfigPatch::new().with_ping_interval(Some(Duration::from_secs(5))),
+/// );
+///
+/// let other_app = SignalFishConfig::new("mb_other");
+/// let patch = RuntimeConfigPatch::between(&started, &other_app);
+/// assert_eq!(patch.rejected_fields(), ["app_id"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeConfigPatch {
+    pub(crate) ping_interval: Option<Option<Duration>>,
+    pub(crate) time_sync_interval: Option<Option<Duration>>,
+    pub(crate) lobby_state_debounce: Option<Option<Duration>>,
+    pub(crate) suppress_announcements: Option<bool>,
+    /// Non-tunable fields that [`between`](Self::between) found changed.
+    rejected: Vec<&'static str>,
+}
+
+impl RuntimeConfigPatch {
+    /// The [`SignalFishConfig`] fields a patch may change.
+    pub const TUNABLE_FIELDS: &'static [&'static str] = &[
+        "ping_interval",
+        "time_sync_interval",
+        "lobby_state_debounce",
+        "suppress_announcements",
+    ];
+
+    /// A patch that changes nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The changes from `current` to `desired`.
+    ///
+    /// Changes to fields outside [`TUNABLE_FIELDS`](Self::TUNABLE_FIELDS) are
+    /// recorded in [`rejected_fields`](Self::rejected_fields), and applying
+    /// the patch fails with
+    /// [`SignalFishError::NotRuntimeTunable`](crate::SignalFishError::NotRuntimeTunable). Hooks and
+    /// formatters cannot be compared and are ignored.
+    #[must_use]
+    pub fn between(current: &SignalFishConfig, desired: &SignalFishConfig) -> Self {
+        let mut patch = Self::new();
+        if current.ping_interval != desired.ping_interval {
+            patch = patch.with_ping_interval(desired.ping_interval);
+        }
+        if current.time_sync_interval != desired.time_sync_interval {
+            patch = patch.with_time_sync_interval(desired.time_sync_interval);
+        }
+        if current.lobby_state_debounce != desired.lobby_state_debounce {
+            patch = patch.with_lobby_state_debounce(desired.lobby_state_debounce);
+        }
+        if current.suppress_announcements != desired.suppress_announcements {
+            patch = patch.with_suppress_announcements(desired.suppress_announcements);
+        }
+        let fixed = [
+            ("app_id", current.app_id != desired.app_id),
+            ("sdk_version", current.sdk_version != desired.sdk_version),
+            (
+                "sdk_version_policy",
+                current.sdk_version_policy != desired.sdk_version_policy,
+            ),
+            ("platform", current.platform != desired.platform),
+            (
+                "game_data_format",
+                current.game_data_format != desired.game_data_format,
+            ),
+            (
+                "protocol_version",
+                current.protocol_version != desired.protocol_version,
+            ),
+            (
+                "supported_transports",
+                current.supported_transports != desired.supported_transports,
+            ),
+            (
+                "supported_topologies",
+                current.supported_topologies != desired.supported_topologies,
+            ),
+            (
+                "event_channel_capacity",
+                current.event_channel_capacity != desired.event_channel_capacity,
+            ),
+            (
+                "broadcast_events",
+                current.broadcast_events != desired.broadcast_events,
+            ),
+            (
+                "command_channel_capacity",
+                current.command_channel_capacity != desired.command_channel_capacity,
+            ),
+            (
+                "shutdown_timeout",
+                current.shutdown_timeout != desired.shutdown_timeout,
+            ),
+            (
+                "flush_on_shutdown",
+                current.flush_on_shutdown != desired.flush_on_shutdown,
+            ),
+            (
+                "leave_room_on_shutdown",
+                current.leave_room_on_shutdown != desired.leave_room_on_shutdown,
+            ),
+            (
+                "protocol_violation_policy",
+                current.protocol_violation_policy != desired.protocol_violation_policy,
+            ),
+            (
+                "clear_session_on_auth_failure",
+                current.clear_session_on_auth_failure != desired.clear_session_on_auth_failure,
+            ),
+            (
+                "reemit_session_events",
+                current.reemit_session_events != desired.reemit_session_events,
+            ),
+            (
+                "strict_blank_frames",
+                current.strict_blank_frames != desired.strict_blank_frames,
+            ),
+            (
+                "strict_message_framing",
+                current.strict_message_framing != desired.strict_message_framing,
+            ),
+            (
+                "strict_payloads",
+                current.strict_payloads != desired.strict_payloads,
+            ),
+            (
+                "forward_unknown_messages",
+                current.forward_unknown_messages != desired.forward_unknown_messages,
+            ),
+            (
+                "drop_cross_epoch_events",
+                current.drop_cross_epoch_events != desired.drop_cross_epoch_events,
+            ),
+            (
+                "legacy_announcement_sender",
+                current.legacy_announcement_sender != desired.legacy_announcement_sender,
+            ),
+            (
+                "health_thresholds",
+                current.health_thresholds != desired.health_thresholds,
+            ),
+            (
+                "self_removal_policy",
+                current.self_removal_policy != desired.self_removal_policy,
+            ),
+            (
+                "session_inconsistency_policy",
+                current.session_inconsistency_policy != desired.session_inconsistency_policy,
+            ),
+            (
+                "expected_organization",
+                current.expected_organization != desired.expected_organization,
+            ),
+            (
+                "organization_mismatch_policy",
+                current.organization_mismatch_policy != desired.organization_mismatch_policy,
+            ),
+            ("label", current.label != desired.label),
+            (
+                "time_sync_samples",
+                current.time_sync_samples != desired.time_sync_samples,
+            ),
+            (
+                "decode_pipeline",
+                current.decode_pipeline != desired.decode_pipeline,
+            ),
+            (
+                "on_receiver_dropped",
+                current.on_receiver_dropped != desired.on_receiver_dropped,
+            ),
+            (
+                "drain_timeout",
+                current.drain_timeout != desired.drain_timeout,
+            ),
+            ("room_history", current.room_history != desired.room_history),
+            ("pong_timeout", current.pong_timeout != desired.pong_timeout),
+            (
+                "reconnect_policy",
+                current.reconnect_policy != desired.reconnect_policy,
+            ),
+            (
+                "resume_policy",
+                current.resume_policy != desired.resume_policy,
+            ),
+            (
+                "offline_queue_capacity",
+                current.offline_queue_capacity != desired.offline_queue_capacity,
+            ),
+            (
+                "reconnect_answer_timeout",
+                current.reconnect_answer_timeout != desired.reconnect_answer_timeout,
+            ),
+            (
+                "loop_watchdog",
+                current.loop_watchdog != desired.loop_watchdog,
+            ),
+            (
+                "restart_stalled_loop",
+                current.restart_stalled_loop != desired.restart_stalled_loop,
+            ),
+            (
+                "message_deadlines",
+                current.message_deadlines != desired.message_deadlines,
+            ),
+        ];
+        patch.rejected = fixed
+            .into_iter()
+            .filter_map(|(field, changed)| changed.then_some(field))
+            .collect();
+        patch
+    }
+
+    /// Change [`SignalFishConfig::ping_interval`]; `None` or zero stops the
+    /// automatic pings. The next ping follows one new interval after the
+    /// patch is applied.
+    #[must_use]
+    pub fn with_ping_interval(mut self, interval: Option<Duration>) -> Self {
+        self.ping_interval = Some(interval.filter(|interval| !interval.is_zero()));
+        self
+    }
+
+    /// Change [`SignalFishConfig::time_sync_interval`]; `None` or zero stops
+    /// the automatic time syncs.
+    #[must_use]
+    pub fn with_time_sync_interval(mut self, interval: Option<Duration>) -> Self {
+        self.time_sync_interval = Some(interval.filter(|interval| !interval.is_zero()));
+        self
+    }
+
+    /// Change [`SignalFishConfig::lobby_state_debounce`]. A lobby state held
+    /// by the old window is emitted when the patch is applied.
+    #[must_use]
+    pub fn with_lobby_state_debounce(mut self, window: Option<Duration>) -> Self {
+        self.lobby_state_debounce = Some(window.filter(|window| !window.is_zero()));
+        self
+    }
+
+    /// Change [`SignalFishConfig::suppress_announcements`].
+    #[must_use]
+    pub fn with_suppress_announcements(mut self, suppress: bool) -> Self {
+        self.suppress_announcements = Some(suppress);
+        self
+    }
+
+    /// Non-tunable fields this patch would change, in
+    /// [`SignalFishConfig`] declaration order.
+    pub fn rejected_fields(&self) -> &[&'static str] {
+        &self.rejected
+    }
+
+    /// Whether applying the patch would change nothing.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Refuse a patch that touches non-tunable fields.
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    pub(crate) fn check(&self) -> crate::error::Result<()> {
+        if self.rejected.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::SignalFishError::NotRuntimeTunable {
+                fields: self.rejected.clone(),
+            })
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Semver-checked `sdk_version` strings.
+//!
+//! The server reads the `sdk_version` sent in `Authenticate` as a
+//! [SemVer 2.0.0](https://semver.org) version. [`SdkVersion`] builds one from
+//! the crate version plus CI details — a build channel and a commit hash —
+//! kept in the build metadata, which version comparisons ignore, so a tagged
+//! build never sorts below the release it was cut from. Plain strings set with
+//! [`SignalFishConfig::with_sdk_version`](crate::SignalFishConfig::with_sdk_version)
+//! are checked when the client starts, per [`SdkVersionPolicy`].
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// A validated SemVer 2.0.0 SDK version.
+///
+/// ```rust
+/// use signal_fish_client::SdkVersion;
+///
+/// let version = SdkVersion::parse("1.4.0")?
+///     .with_build_metadata("gabc123")?
+///     .with_channel("beta")?;
+/// assert_eq!(version.to_string(), "1.4.0+beta.gabc123");
+///
+/// assert!(SdkVersion::parse("1.4").is_err());
+/// assert!(SdkVersion::crate_default().with_channel("nightly build").is_err());
+/// # Ok::<(), signal_fish_client::InvalidSdkVersion>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SdkVersion {
+    /// `major.minor.patch` plus any pre-release suffix.
+    release: String,
+    channel: Option<String>,
+    build: Vec<String>,
+}
+
+/// Why a string is not a valid [`SdkVersion`] or build identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("`{input}` is not a valid SDK version: {reason}")]
+pub struct InvalidSdkVersion {
+    /// The rejected input.
+    pub input: String,
+    /// What is wrong with it.
+    pub reason: &'static str,
+}
+
+/// What a client does when
+/// [`SignalFishConfig::sdk_version`](crate::SignalFishConfig::sdk_version) is
+/// not a SemVer 2.0.0 version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SdkVersionPolicy {
+    /// Log a warning and send the string as is.
+    #[default]
+    Warn,
+    /// Send nothing: the connection ends before `Authenticate` with
+    /// [`ClientSnapshot::handshake_failed`](crate::ClientSnapshot::handshake_failed)
+    /// set and the validation error as the `Disconnected` reason.
+    Reject,
+}
+
+impl SdkVersion {
+    /// This crate's own version, as sent by default.
+    pub fn crate_default() -> Self {
+        Self {
+            release: env!("CARGO_PKG_VERSION").to_owned(),
+            channel: None,
+            build: Vec::new(),
+        }
+    }
+
+    /// Parse a SemVer 2.0.0 version, build metadata included.
+    pub fn parse(input: &str) -> Result<Self, InvalidSdkVersion> {
+        let invalid = |reason| InvalidSdkVersion {
+            input: input.to_owned(),
+            reason,
+        };
+        let (release, build) = match input.split_once('+') {
+            Some((release, build)) => (release, Some(build)),
+            None => (input, None),
+        };
+        let (core, pre) = match release.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (release, None),
+        };
+        let numbers: Vec<&str> = core.split('.').collect();
+        if numbers.len() != 3 {
+            return Err(invalid("expected MAJOR.MINOR.PATCH"));
+        }
+        if !numbers.iter().all(|number| is_numeric_identifier(number)) {
+            return Err(invalid(
+                "version numbers must be digits without leading zeros",
+            ));
+        }
+        if let Some(pre) = pre {
+            for identifier in pre.split('.') {
+                check_identifier(identifier).map_err(invalid)?;
+                if identifier.bytes().all(|byte| byte.is_ascii_digit())
+                    && !is_numeric_identifier(identifier)
+                {
+                    return Err(invalid(
+                        "numeric pre-release identifiers must not have leading zeros",
+                    ));
+                }
+            }
+        }
+        let build = match build {
+            Some(build) => split_identifiers(build).map_err(invalid)?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            release: release.to_owned(),
+            channel: None,
+            build,
+        })
+    }
+
+    /// Append dot-separated build metadata, such as a commit hash.
+    ///
+    /// Each identifier must be non-empty ASCII alphanumerics and hyphens.
+    pub fn with_build_metadata(mut self, metadata: &str) -> Result<Self, InvalidSdkVersion> {
+        let identifiers = split_identifiers(metadata).map_err(|reason| InvalidSdkVersion {
+            input: metadata.to_owned(),
+            reason,
+        })?;
+        self.build.extend(identifiers);
+        Ok(self)
+    }
+
+    /// Name the build channel (`"beta"`, `"nightly"`). It renders as the
+    /// first build-metadata identifier; a later call replaces it.
+    pub fn with_channel(mut self, channel: &str) -> Result<Self, InvalidSdkVersion> {
+        check_identifier(channel).map_err(|reason| InvalidSdkVersion {
+            input: channel.to_owned(),
+            reason,
+        })?;
+        self.channel = Some(channel.to_owned());
+        Ok(self)
+    }
+}
+
+impl fmt::Display for SdkVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.release)?;
+        let mut separator = '+';
+        for identifier in self.channel.iter().chain(&self.build) {
+            write!(f, "{separator}{identifier}")?;
+            separator = '.';
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SdkVersion {
+    type Err = InvalidSdkVersion;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl From<SdkVersion> for String {
+    fn from(version: SdkVersion) -> Self {
+        version.to_string()
+    }
+}
+
+fn is_numeric_identifier(identifier: &str) -> bool {
+    !identifier.is_empty()
+        && identifier.bytes().all(|byte| byte.is_ascii_digit())
+        && (identifier == "0" || !identifier.starts_with('0'))
+}
+
+fn check_identifier(identifier: &str) -> Result<(), &'static str> {
+    if identifier.is_empty() {
+        Err("identifiers must not be empty")
+    } else if !identifier
+        .bytes()
+        .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+    {
+        Err("identifiers may only contain ASCII alphanumerics and hyphens")
+    } else {
+        Ok(())
+    }
+}
+
+fn split_identifiers(metadata: &str) -> Result<Vec<String>, &'static str> {
+    metadata
+        .split('.')
+        .map(|identifier| check_identifier(identifier).map(|()| identifier.to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crate_default_is_valid_semver() {
+        let version = SdkVersion::crate_default();
+        assert_eq!(version.to_string(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(SdkVersion::parse(&version.to_string()).unwrap(), version);
+    }
+
+    #[test]
+    fn channel_renders_before_build_metadata() {
+        let version = SdkVersion::parse("0.9.0")
+            .unwrap()
+            .with_build_metadata("gabc123.ci-42")
+            .unwrap()
+            .with_channel("beta")
+            .unwrap();
+        assert_eq!(version.to_string(), "0.9.0+beta.gabc123.ci-42");
+        assert_eq!(String::from(version), "0.9.0+beta.gabc123.ci-42");
+    }
+
+    #[test]
+    fn parse_keeps_pre_release_and_build_metadata() {
+        for input in ["1.0.0-rc.1", "1.0.0+exp.sha.5114f85", "0.0.0-alpha-1+001"] {
+            assert_eq!(SdkVersion::parse(input).unwrap().to_string(), input);
+        }
+        let extended = SdkVersion::parse("1.0.0-rc.1+sha")
+            .unwrap()
+            .with_build_metadata("ci")
+            .unwrap();
+        assert_eq!(extended.to_string(), "1.0.0-rc.1+sha.ci");
+    }
+
+    #[test]
+    fn invalid_versions_name_the_problem() {
+        for (input, reason) in [
+            ("1.2", "expected MAJOR.MINOR.PATCH"),
+            ("1.2.3.4", "expected MAJOR.MINOR.PATCH"),
+            (
+                "v1.2.3",
+                "version numbers must be digits without leading zeros",
+            ),
+            (
+                "01.2.3",
+                "version numbers must be digits without leading zeros",
+            ),
+            ("1.2.3-", "identifiers must not be empty"),
+            (
+                "1.2.3-01",
+                "numeric pre-release identifiers must not have leading zeros",
+            ),
+            ("1.2.3+a..b", "identifiers must not be empty"),
+            (
+                "1.2.3+a_b",
+                "identifiers may only contain ASCII alphanumerics and hyphens",
+            ),
+        ] {
+            let error = SdkVersion::parse(input).unwrap_err();
+            assert_eq!(error.input, input);
+            assert_eq!(error.reason, reason, "{input}");
+        }
+    }
+
+    #[test]
+    fn builders_reject_invalid_identifiers() {
+        let base = SdkVersion::crate_default();
+        let error = base.clone().with_channel("beta.1").unwrap_err();
+        assert_eq!(error.input, "beta.1");
+        assert!(base.clone().with_channel("").is_err());
+        assert!(base.clone().with_build_metadata("g abc").is_err());
+        assert!(base.with_build_metadata("gabc.").is_err());
+    }
+}
@@ -0,0 +1,675 @@
+//! Explicit session lifecycle state machine.
+//!
+//! Connection, authentication, and room membership used to be independent
+//! flags, which allowed combinations the protocol never produces
+//! (authenticated while disconnected, spectating while in a player room).
+//! [`SessionState`] collapses them into one named state, and every change goes
+//! through a single transition table so an unexpected server message is
+//! reported instead of silently corrupting the client's view.
+//!
+//! The machine itself is internal; applications observe it through
+//! `session_state()` and `state_history()` on either client.
+
+use thiserror::Error;
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+use crate::protocol::ServerMessage;
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+use std::collections::VecDeque;
+
+/// Number of transitions retained by `state_history()`.
+///
+/// Older entries are discarded first; the log is a debugging aid, not an audit
+/// trail.
+pub const STATE_HISTORY_CAPACITY: usize = 64;
+
+/// Lifecycle state of a client session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionState {
+    /// The transport is closed. Terminal for this client instance, unless it
+    /// was started with `start_with_reconnect` and is dialing a new one.
+    Disconnected,
+    /// The transport is open but authentication has not completed.
+    Connected,
+    /// The server accepted the app ID; the client is not in a room.
+    Authenticated,
+    /// The client is a player in a room.
+    InRoom,
+    /// The client is watching a room as a spectator.
+    Spectating,
+}
+
+impl SessionState {
+    /// Whether the transport is open in this state.
+    #[must_use]
+    pub fn is_connected(self) -> bool {
+        !matches!(self, Self::Disconnected)
+    }
+
+    /// Whether authentication has completed in this state.
+    #[must_use]
+    pub fn is_authenticated(self) -> bool {
+        matches!(self, Self::Authenticated | Self::InRoom | Self::Spectating)
+    }
+}
+
+/// Input that drives a [`SessionState`] transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionTrigger {
+    /// The server sent `Authenticated`.
+    Authenticated,
+    /// The server sent `AuthenticationError`.
+    AuthenticationFailed,
+    /// The server sent `RoomJoined`.
+    JoinedRoom,
+    /// The server sent `Reconnected`.
+    Reconnected,
+    /// The server sent `RoomLeft`.
+    LeftRoom,
+    /// The server sent `SpectatorJoined`.
+    JoinedAsSpectator,
+    /// The server sent `SpectatorLeft`.
+    LeftSpectator,
+    /// The transport closed, errored, or the client was shut down.
+    TransportClosed,
+    /// A reconnecting client dialed a new transport.
+    TransportReopened,
+}
+
+impl SessionTrigger {
+    /// The trigger a server message fires, or `None` for messages that do not
+    /// affect the session lifecycle.
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    pub(crate) fn from_server_message(message: &ServerMessage) -> Option<Self> {
+        // Exhaustive on purpose: a new ServerMessage kind must decide here
+        // whether it moves the session.
+        match message {
+            ServerMessage::Authenticated { .. } => Some(Self::Authenticated),
+            ServerMessage::AuthenticationError { .. } => Some(Self::AuthenticationFailed),
+            ServerMessage::RoomJoined(_) => Some(Self::JoinedRoom),
+            ServerMessage::Reconnected(_) => Some(Self::Reconnected),
+            ServerMessage::RoomLeft => Some(Self::LeftRoom),
+            ServerMessage::SpectatorJoined(_) => Some(Self::JoinedAsSpectator),
+            ServerMessage::SpectatorLeft { .. } => Some(Self::LeftSpectator),
+            ServerMessage::ProtocolInfo(_)
+            | ServerMessage::RoomJoinFailed { .. }
+            | ServerMessage::PlayerJoined { .. }
+            | ServerMessage::PlayerLeft { .. }
+            | ServerMessage::GameData { .. }
+            | ServerMessage::GameDataBinary { .. }
+            | ServerMessage::AuthorityChanged { .. }
+            | ServerMessage::AuthorityResponse { .. }
+            | ServerMessage::LobbyStateChanged { .. }
+            | ServerMessage::GameStarting { .. }
+            | ServerMessage::Pong
+            | ServerMessage::TimeSyncResponse { .. }
+            | ServerMessage::ReconnectionFailed { .. }
+            | ServerMessage::PlayerReconnected { .. }
+            | ServerMessage::SpectatorJoinFailed { .. }
+            | ServerMessage::NewSpectatorJoined { .. }
+            | ServerMessage::SpectatorDisconnected { .. }
+            | ServerMessage::Error { .. }
+            | ServerMessage::Announcement { .. }
+            | ServerMessage::Signal { .. }
+            | ServerMessage::NewPeer { .. }
+            | ServerMessage::SessionPlan(_)
+            | ServerMessage::PeerTransportStatus { .. }
+            | ServerMessage::RelayStats { .. }
+            | ServerMessage::GoingAway { .. }
+            | ServerMessage::DeliveryReport(_) => None,
+        }
+    }
+}
+
+/// One applied entry in the session transition log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTransition {
+    /// State before the trigger.
+    pub from: SessionState,
+    /// State after the trigger.
+    pub to: SessionState,
+    /// What caused the transition.
+    pub trigger: SessionTrigger,
+}
+
+/// A trigger that is not valid in the current [`SessionState`].
+///
+/// The state is left unchanged. The client logs these as warnings — a server
+/// message is never allowed to panic the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("session trigger {trigger:?} is not valid in state {from:?}")]
+pub struct InvalidTransition {
+    /// State the machine was in when the trigger arrived.
+    pub from: SessionState,
+    /// The rejected trigger.
+    pub trigger: SessionTrigger,
+}
+
+/// Session lifecycle with a bounded log of applied transitions.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+#[derive(Debug, Clone)]
+pub(crate) struct SessionStateMachine {
+    state: SessionState,
+    history: VecDeque<StateTransition>,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl SessionStateMachine {
+    /// A machine for a freshly opened transport.
+    pub(crate) fn connected() -> Self {
+        Self {
+            state: SessionState::Connected,
+            history: VecDeque::with_capacity(STATE_HISTORY_CAPACITY),
+        }
+    }
+
+    pub(crate) fn state(&self) -> SessionState {
+        self.state
+    }
+
+    /// Oldest-first copy of the retained transition log.
+    pub(crate) fn history(&self) -> Vec<StateTransition> {
+        self.history.iter().copied().collect()
+    }
+
+    /// Apply `trigger`, returning the new state or leaving the state untouched
+    /// when the transition table rejects it.
+    pub(crate) fn transition(
+        &mut self,
+        trigger: SessionTrigger,
+    ) -> Result<SessionState, InvalidTransition> {
+        let from = self.state;
+        let to = next_state(from, trigger).ok_or(InvalidTransition { from, trigger })?;
+        if self.history.len() == STATE_HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history
+            .push_back(StateTransition { from, to, trigger });
+        self.state = to;
+        Ok(to)
+    }
+}
+
+/// The transition table. `None` marks a combination the protocol never
+/// produces.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+fn next_state(from: SessionState, trigger: SessionTrigger) -> Option<SessionState> {
+    use SessionState as S;
+    use SessionTrigger as T;
+
+    match (from, trigger) {
+        (_, T::TransportClosed) => Some(S::Disconnected),
+        (S::Disconnected, T::TransportReopened) => Some(S::Connected),
+        (S::Disconnected, _) => None,
+        (S::Connected | S::Authenticated, T::Authenticated) => Some(S::Authenticated),
+        (_, T::AuthenticationFailed) => Some(S::Connected),
+        (S::Authenticated, T::JoinedRoom) | (S::Authenticated | S::InRoom, T::Reconnected) => {
+            Some(S::InRoom)
+        }
+        (S::Authenticated, T::JoinedAsSpectator) => Some(S::Spectating),
+        (S::InRoom, T::LeftRoom) | (S::Spectating, T::LeftSpectator) => Some(S::Authenticated),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, any(feature = "tokio-runtime", feature = "polling-client")))]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    const STATES: [SessionState; 5] = [
+        SessionState::Disconnected,
+        SessionState::Connected,
+        SessionState::Authenticated,
+        SessionState::InRoom,
+        SessionState::Spectating,
+    ];
+
+    const TRIGGERS: [SessionTrigger; 9] = [
+        SessionTrigger::Authenticated,
+        SessionTrigger::AuthenticationFailed,
+        SessionTrigger::JoinedRoom,
+        SessionTrigger::Reconnected,
+        SessionTrigger::LeftRoom,
+        SessionTrigger::JoinedAsSpectator,
+        SessionTrigger::LeftSpectator,
+        SessionTrigger::TransportClosed,
+        SessionTrigger::TransportReopened,
+    ];
+
+    fn machine_in(state: SessionState) -> SessionStateMachine {
+        SessionStateMachine {
+            state,
+            history: VecDeque::new(),
+        }
+    }
+
+    #[test]
+    fn transition_table_is_total_and_matches_spec() {
+        use SessionState as S;
+        use SessionTrigger as T;
+
+        let expected = |from: S, trigger: T| -> Option<S> {
+            match (from, trigger) {
+                (_, T::TransportClosed) => Some(S::Disconnected),
+                (S::Disconnected, T::TransportReopened) => Some(S::Connected),
+                (S::Connected, T::Authenticated) | (S::Authenticated, T::Authenticated) => {
+                    Some(S::Authenticated)
+                }
+                (
+                    S::Connected | S::Authenticated | S::InRoom | S::Spectating,
+                    T::AuthenticationFailed,
+                ) => Some(S::Connected),
+                (S::Authenticated, T::JoinedRoom)
+                | (S::Authenticated, T::Reconnected)
+                | (S::InRoom, T::Reconnected) => Some(S::InRoom),
+                (S::Authenticated, T::JoinedAsSpectator) => Some(S::Spectating),
+                (S::InRoom, T::LeftRoom) | (S::Spectating, T::LeftSpectator) => {
+                    Some(S::Authenticated)
+                }
+                _ => None,
+            }
+        };
+
+        for from in STATES {
+            for trigger in TRIGGERS {
+                let mut machine = machine_in(from);
+                let result = machine.transition(trigger);
+                match expected(from, trigger) {
+                    Some(to) => {
+                        assert_eq!(result, Ok(to), "{from:?} + {trigger:?}");
+                        assert_eq!(machine.state(), to);
+                        assert_eq!(
+                            machine.history(),
+                            vec![StateTransition { from, to, trigger }]
+                        );
+                    }
+                    None => {
+                        assert_eq!(
+                            result,
+                            Err(InvalidTransition { from, trigger }),
+                            "{from:?} + {trigger:?}"
+                        );
+                        assert_eq!(machine.state(), from, "rejected trigger must not move");
+                        assert!(machine.history().is_empty());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn state_predicates_never_report_authenticated_while_disconnected() {
+        for state in STATES {
+            if state.is_authenticated() {
+                assert!(state.is_connected(), "{state:?}");
+            }
+        }
+        assert!(!SessionState::Disconnected.is_connected());
+        assert!(SessionState::Connected.is_connected());
+        assert!(!SessionState::Connected.is_authenticated());
+        assert!(SessionState::Spectating.is_authenticated());
+    }
+
+    #[test]
+    fn history_is_bounded_and_drops_oldest_first() {
+        let mut machine = SessionStateMachine::connected();
+        for _ in 0..STATE_HISTORY_CAPACITY + 10 {
+            machine.transition(SessionTrigger::Authenticated).unwrap();
+        }
+        machine.transition(SessionTrigger::TransportClosed).unwrap();
+
+        let history = machine.history();
+        assert_eq!(history.len(), STATE_HISTORY_CAPACITY);
+        assert_eq!(
+            history.last().copied(),
+            Some(StateTransition {
+                from: SessionState::Authenticated,
+                to: SessionState::Disconnected,
+                trigger: SessionTrigger::TransportClosed,
+            })
+        );
+        assert!(history
+            .iter()
+            .all(|entry| entry.from != SessionState::Connected));
+    }
+
+    #[test]
+    fn invalid_transition_display_names_state_and_trigger() {
+        let error = InvalidTransition {
+            from: SessionState::Spectating,
+            trigger: SessionTrigger::LeftRoom,
+        };
+        assert_eq!(
+            error.to_string(),
+            "session trigger LeftRoom is not valid in state Spectating"
+        );
+    }
+
+    #[test]
+    fn every_server_message_kind_maps_to_its_trigger() {
+        use crate::protocol::*;
+        use crate::ErrorCode;
+
+        let id = uuid::Uuid::from_u128(1);
+        let room_joined = RoomJoinedPayload {
+            room_id: id,
+            room_code: "ROOM01".into(),
+            player_id: id,
+            game_name: "game".into(),
+            max_players: 2,
+            supports_authority: false,
+            current_players: Vec::new(),
+            is_authority: false,
+            lobby_state: LobbyState::Waiting,
+            ready_players: Vec::new(),
+            relay_type: "auto".into(),
+            current_spectators: Vec::new(),
+            ice_servers: Vec::new(),
+            reconnection_token: None,
+        };
+        let reconnected = ReconnectedPayload {
+            room_id: id,
+            room_code: "ROOM01".into(),
+            player_id: id,
+            game_name: "game".into(),
+            max_players: 2,
+            supports_authority: false,
+            current_players: Vec::new(),
+            is_authority: false,
+            lobby_state: LobbyState::Waiting,
+            ready_players: Vec::new(),
+            relay_type: "auto".into(),
+            current_spectators: Vec::new(),
+            ice_servers: Vec::new(),
+            missed_events: Vec::new(),
+            replay: None,
+            sender_watermarks: Vec::new(),
+            reconnection_token: None,
+        };
+        let spectator_joined = SpectatorJoinedPayload {
+            room_id: id,
+            room_code: "ROOM01".into(),
+            spectator_id: id,
+            game_name: "game".into(),
+            current_players: Vec::new(),
+            current_spectators: Vec::new(),
+            lobby_state: LobbyState::Waiting,
+            reason: None,
+        };
+        let spectator = SpectatorInfo {
+            id,
+            name: "watcher".into(),
+            connected_at: "now".into(),
+        };
+        let player = PlayerInfo {
+            id,
+            name: "player".into(),
+            is_authority: false,
+            is_ready: false,
+            connected_at: "now".into(),
+            connection_info: None,
+            epoch: None,
+            seq: None,
+        };
+
+        let cases: Vec<(ServerMessage, Option<SessionTrigger>)> = vec![
+            (
+                ServerMessage::Authenticated {
+                    app_name: "app".into(),
+                    organization: None,
+                    rate_limits: RateLimitInfo {
+                        per_minute: 1,
+                        per_hour: 1,
+                        per_day: 1,
+                    },
+                },
+                Some(SessionTrigger::Authenticated),
+            ),
+            (
+                ServerMessage::ProtocolInfo(ProtocolInfoPayload {
+                    platform: None,
+                    sdk_version: None,
+                    minimum_version: None,
+                    recommended_version: None,
+                    capabilities: Vec::new(),
+                    notes: None,
+                    game_data_formats: Vec::new(),
+                    player_name_rules: None,
+                    protocol_version: None,
+                    min_protocol_version: None,
+                    max_protocol_version: None,
+                    transports: None,
+                    limits: None,
+                }),
+                None,
+            ),
+            (
+                ServerMessage::AuthenticationError {
+                    error: "bad".into(),
+                    error_code: ErrorCode::InvalidAppId,
+                },
+                Some(SessionTrigger::AuthenticationFailed),
+            ),
+            (
+                ServerMessage::RoomJoined(Box::new(room_joined)),
+                Some(SessionTrigger::JoinedRoom),
+            ),
+            (
+                ServerMessage::RoomJoinFailed {
+                    reason: "full".into(),
+                    error_code: None,
+                },
+                None,
+            ),
+            (ServerMessage::RoomLeft, Some(SessionTrigger::LeftRoom)),
+            (ServerMessage::PlayerJoined { player }, None),
+            (
+                ServerMessage::PlayerLeft {
+                    player_id: id,
+                    epoch: None,
+                    final_seq: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::GameData {
+                    from_player: id,
+                    data: serde_json::json!({}),
+                    seq: None,
+                    epoch: None,
+                    class: None,
+                    key: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::GameDataBinary {
+                    from_player: id,
+                    encoding: GameDataEncoding::MessagePack,
+                    payload: Vec::new(),
+                    seq: None,
+                    epoch: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::AuthorityChanged {
+                    authority_player: None,
+                    you_are_authority: false,
+                },
+                None,
+            ),
+            (
+                ServerMessage::AuthorityResponse {
+                    granted: true,
+                    reason: None,
+                    error_code: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::LobbyStateChanged {
+                    lobby_state: LobbyState::Lobby,
+                    ready_players: Vec::new(),
+                    all_ready: false,
+                },
+                None,
+            ),
+            (
+                ServerMessage::GameStarting {
+                    peer_connections: Vec::new(),
+                },
+                None,
+            ),
+            (ServerMessage::Pong, None),
+            (
+                ServerMessage::TimeSyncResponse {
+                    client_sent_at_ms: 1,
+                    server_time_ms: 2,
+                },
+                None,
+            ),
+            (
+                ServerMessage::Reconnected(Box::new(reconnected)),
+                Some(SessionTrigger::Reconnected),
+            ),
+            (
+                ServerMessage::ReconnectionFailed {
+                    reason: "expired".into(),
+                    error_code: ErrorCode::ReconnectionFailed,
+                },
+                None,
+            ),
+            (
+                ServerMessage::PlayerReconnected {
+                    player_id: id,
+                    epoch: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::SpectatorJoined(Box::new(spectator_joined)),
+                Some(SessionTrigger::JoinedAsSpectator),
+            ),
+            (
+                ServerMessage::SpectatorJoinFailed {
+                    reason: "closed".into(),
+                    error_code: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::SpectatorLeft {
+                    room_id: None,
+                    room_code: None,
+                    reason: None,
+                    current_spectators: Vec::new(),
+                },
+                Some(SessionTrigger::LeftSpectator),
+            ),
+            (
+                ServerMessage::NewSpectatorJoined {
+                    spectator,
+                    current_spectators: Vec::new(),
+                    reason: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::SpectatorDisconnected {
+                    spectator_id: id,
+                    reason: None,
+                    current_spectators: Vec::new(),
+                },
+                None,
+            ),
+            (
+                ServerMessage::Error {
+                    message: "oops".into(),
+                    error_code: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::Announcement {
+                    message: "maintenance at noon".into(),
+                    severity: None,
+                    metadata: None,
+                },
+                None,
+            ),
+            (
+                ServerMessage::Signal {
+                    from: id,
+                    signal: serde_json::json!({}),
+                },
+                None,
+            ),
+            (
+                ServerMessage::NewPeer {
+                    peer_id: id,
+                    you_initiate: true,
+                },
+                None,
+            ),
+            (
+                ServerMessage::SessionPlan(Box::new(SessionPlanPayload {
+                    topology: Topology::Relay,
+                    transport: TransportKind::Relay,
+                    host: None,
+                    peers: Vec::new(),
+                    ice_servers: Vec::new(),
+                    fallback: TransportKind::Relay,
+                })),
+                None,
+            ),
+            (
+                ServerMessage::PeerTransportStatus {
+                    peer_id: id,
+                    transport: TransportKind::WebRtc,
+                    connected: true,
+                },
+                None,
+            ),
+            (
+                ServerMessage::RelayStats {
+                    interval_ms: 1,
+                    sent_to_you: 0,
+                    dropped_for_you: 0,
+                    backpressure_events: 0,
+                },
+                None,
+            ),
+            (
+                ServerMessage::GoingAway {
+                    deadline_ms: 1,
+                    retry_after_secs: None,
+                },
+                None,
+            ),
+            (ServerMessage::DeliveryReport(Box::default()), None),
+        ];
+
+        for (message, expected) in &cases {
+            assert_eq!(
+                SessionTrigger::from_server_message(message),
+                *expected,
+                "{message:?}"
+            );
+        }
+
+        // Every lifecycle-moving message kind, from every state, either moves
+        // the machine per the table or is rejected without panicking.
+        for (message, _) in &cases {
+            let Some(trigger) = SessionTrigger::from_server_message(message) else {
+                continue;
+            };
+            for from in STATES {
+                let mut machine = machine_in(from);
+                let outcome = machine.transition(trigger);
+                assert_eq!(outcome.is_ok(), next_state(from, trigger).is_some());
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Durable storage for what a client needs to return to its room.
+//!
+//! A [`SessionStore`] is a small async key-value interface. Set one with
+//! [`SignalFishConfig::with_session_store`](crate::SignalFishConfig::with_session_store)
+//! and the async client keeps a [`SessionTicket`] under
+//! [`SESSION_TICKET_KEY`]: written when the server hands out a reconnection
+//! token, removed when the room ends for good, and loaded on start so a
+//! restarted process can `Reconnect` to the room it was in. The transport
+//! dropping does not remove the ticket; that is what it is for.
+//!
+//! [`MemorySessionStore`] keeps entries for the life of the process, and
+//! `FileSessionStore` (feature `store-file`) in one JSON file on disk.
+//! Anything else — a browser's local storage, a platform keychain — only
+//! needs the three methods of the trait.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SignalFishError;
+use crate::protocol::{PlayerId, RoomId};
+
+/// The key the client keeps its [`SessionTicket`] under.
+pub const SESSION_TICKET_KEY: &str = "session-ticket";
+
+/// Future returned by the [`SessionStore`] methods.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SignalFishError>> + Send + 'a>>;
+
+/// Async key-value storage for small blobs.
+///
+/// Values are opaque bytes; the client stores JSON. A store shared by
+/// several clients should give each its own namespace, since they all use
+/// [`SESSION_TICKET_KEY`].
+pub trait SessionStore: std::fmt::Debug + Send + Sync + 'static {
+    /// The value stored under `key`, if any.
+    fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<Vec<u8>>>;
+
+    /// Store `value` under `key`, replacing what was there.
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>) -> StoreFuture<'a, ()>;
+
+    /// Remove `key`. Removing a missing key succeeds.
+    fn remove<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()>;
+}
+
+/// What a client needs to `Reconnect` to the room it was in.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTicket {
+    /// This client's player in the room.
+    pub player_id: PlayerId,
+    /// The room.
+    pub room_id: RoomId,
+    /// The room's join code.
+    pub room_code: String,
+    /// The token the server issued for reconnecting.
+    pub reconnection_token: String,
+}
+
+impl std::fmt::Debug for SessionTicket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionTicket")
+            .field("player_id", &self.player_id)
+            .field("room_id", &self.room_id)
+            .field("room_code", &self.room_code)
+            .field("reconnection_token", &"<redacted>")
+            .finish()
+    }
+}
+
+/// A [`SessionStore`] in memory, for tests and for clients that only need
+/// to survive a reconnect within one process.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemorySessionStore {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<Vec<u8>>> {
+        let value = self.entries().get(key).cloned();
+        Box::pin(std::future::ready(Ok(value)))
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>) -> StoreFuture<'a, ()> {
+        self.entries().insert(key.to_owned(), value);
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+        self.entries().remove(key);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+#[cfg(feature = "store-file")]
+pub use file::FileSessionStore;
+
+#[cfg(feature = "store-file")]
+mod file {
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::{SessionStore, StoreFuture};
+    use crate::error::SignalFishError;
+
+    /// A [`SessionStore`] kept in one JSON file.
+    ///
+    /// Every write replaces the file atomically: the new contents go to a
+    /// temporary file beside it, which is then renamed over it, so a crash
+    /// mid-write leaves the old contents. A file that does not parse is
+    /// logged and treated as empty; the next write replaces it. File I/O runs
+    /// on tokio's blocking pool.
+    #[derive(Debug, Clone)]
+    pub struct FileSessionStore {
+        path: Arc<PathBuf>,
+        /// Serializes read-modify-write cycles.
+        lock: Arc<tokio::sync::Mutex<()>>,
+    }
+
+    impl FileSessionStore {
+        /// A store backed by the file at `path`, created on the first write.
+        pub fn new(path: impl Into<PathBuf>) -> Self {
+            Self {
+                path: Arc::new(path.into()),
+                lock: Arc::new(tokio::sync::Mutex::new(())),
+            }
+        }
+
+        /// The file backing this store.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        async fn update(
+            &self,
+            change: impl FnOnce(&mut BTreeMap<String, Vec<u8>>) + Send + 'static,
+        ) -> Result<(), SignalFishError> {
+            let _held = self.lock.lock().await;
+            let path = Arc::clone(&self.path);
+            blocking(move || {
+                let mut entries = read_entries(&path)?;
+                change(&mut entries);
+                write_entries(&path, &entries)
+            })
+            .await
+        }
+    }
+
+    impl SessionStore for FileSessionStore {
+        fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<Vec<u8>>> {
+            Box::pin(async move {
+                let _held = self.lock.lock().await;
+                let path = Arc::clone(&self.path);
+                let key = key.to_owned();
+                blocking(move || Ok(read_entries(&path)?.remove(&key))).await
+            })
+        }
+
+        fn set<'a>(&'a self, key: &'a str, value: Vec<u8>) -> StoreFuture<'a, ()> {
+            let key = key.to_owned();
+            Box::pin(self.update(move |entries| {
+                entries.insert(key, value);
+            }))
+        }
+
+        fn remove<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+            let key = key.to_owned();
+            Box::pin(self.update(move |entries| {
+                entries.remove(&key);
+            }))
+        }
+    }
+
+    async fn blocking<T: Send + 'static>(
+        work: impl FnOnce() -> Result<T, SignalFishError> + Send + 'static,
+    ) -> Result<T, SignalFishError> {
+        tokio::task::spawn_blocking(work)
+            .await
+            .map_err(|error| SignalFishError::Io(std::io::Error::other(error)))?
+    }
+
+    /// The file's entries; a missing file is empty and a corrupt one is
+    /// logged and treated as empty.
+    fn read_entries(path: &Path) -> Result<BTreeMap<String, Vec<u8>>, SignalFishError> {
+        let contents = match std::fs::read(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(BTreeMap::new());
+            }
+            Err(error) => return Err(error.into()),
+        };
+        match serde_json::from_slice(&contents) {
+            Ok(entries) => Ok(entries),
+            Err(error) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    "session store file is corrupt; starting fresh: {error}"
+                );
+                Ok(BTreeMap::new())
+            }
+        }
+    }
+
+    fn write_entries(
+        path: &Path,
+        entries: &BTreeMap<String, Vec<u8>>,
+    ) -> Result<(), SignalFishError> {
+        let contents = serde_json::to_vec(entries)?;
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        std::fs::write(&temporary, contents)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "store-file"))]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("signal-fish-store-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("scratch dir must be creatable");
+        dir.join(name)
+    }
+
+    #[tokio::test]
+    async fn file_store_round_trips_across_instances() {
+        let path = scratch_path("store.json");
+        let store = FileSessionStore::new(&path);
+        store.set("a", b"one".to_vec()).await.unwrap();
+        store.set("b", b"two".to_vec()).await.unwrap();
+        store.remove("b").await.unwrap();
+
+        let reopened = FileSessionStore::new(&path);
+        assert_eq!(reopened.get("a").await.unwrap(), Some(b"one".to_vec()));
+        assert_eq!(reopened.get("b").await.unwrap(), None);
+        assert!(
+            !path.with_extension("json.tmp").exists(),
+            "the temporary file is renamed into place"
+        );
+    }
+
+    #[tokio::test]
+    async fn file_store_starts_fresh_from_a_corrupt_file() {
+        let path = scratch_path("store.json");
+        std::fs::write(&path, b"{ not json").expect("scratch file must be writable");
+        let store = FileSessionStore::new(&path);
+
+        assert_eq!(store.get("a").await.unwrap(), None);
+        store.set("a", b"one".to_vec()).await.unwrap();
+        assert_eq!(store.get("a").await.unwrap(), Some(b"one".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn file_store_reads_a_missing_file_as_empty() {
+        let store = FileSessionStore::new(scratch_path("absent.json"));
+        assert_eq!(store.get("a").await.unwrap(), None);
+        store.remove("a").await.unwrap();
+    }
+
+    #[test]
+    fn ticket_debug_redacts_the_token() {
+        let ticket = SessionTicket {
+            player_id: uuid::Uuid::from_u128(1),
+            room_id: uuid::Uuid::from_u128(2),
+            room_code: "ABC123".into(),
+            reconnection_token: "secret".into(),
+        };
+        assert!(!format!("{ticket:?}").contains("secret"));
+    }
+}
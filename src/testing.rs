@@ -0,0 +1,750 @@
+//! Fixture builders for roster and room payloads in downstream tests.
+//!
+//! Filling in every [`PlayerInfo`] field by hand is verbose and breaks each
+//! time the protocol grows a field. These builders start from stable
+//! defaults — an id derived from the name, [`FIXTURE_TIMESTAMP`] for
+//! `connected_at`, no v3 stamps — so a test only states what it cares about.
+//!
+//! ```rust
+//! use signal_fish_client::protocol::ServerMessage;
+//! use signal_fish_client::testing;
+//!
+//! let host = testing::player("Alice").authority().ready();
+//! let joined = testing::room_joined("ABC123")
+//!     .players([host, testing::player("Bob")])
+//!     .spectators([testing::spectator("Carol")]);
+//!
+//! let payload = joined.payload();
+//! assert_eq!(payload.current_players.len(), 2);
+//! assert_eq!(payload.ready_players, vec![testing::player_id("Alice")]);
+//!
+//! let wire: ServerMessage = serde_json::from_str(&joined.json()).unwrap();
+//! assert!(matches!(wire, ServerMessage::RoomJoined(_)));
+//! ```
+//!
+//! [`SentLog`] records what a scripted test transport sent, keeping only the
+//! newest frames so a soak test can run for hours in bounded memory.
+//! [`EmissionHook`] observes the async client's event fan-out, for asserting
+//! the cross-subscriber ordering guarantee. [`SlowConsumer`] reads an event
+//! receiver — the primary one, a fan-out subscriber, or a broadcast
+//! subscriber — with a controlled per-event delay, for exercising backpressure
+//! and channel sizing without sleeps scattered through the test.
+//!
+//! Requires the `testing` feature.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::protocol::{
+    ConnectionInfo, LobbyState, PlayerId, PlayerInfo, RoomId, RoomJoinedPayload, ServerMessage,
+    SpectatorInfo,
+};
+use crate::SignalFishEvent;
+
+/// `connected_at` used by every fixture unless overridden.
+pub const FIXTURE_TIMESTAMP: &str = "2026-01-01T00:00:00Z";
+
+/// The default id of a fixture named `name`.
+///
+/// Stable across runs and distinct per name, so a test can refer to a player
+/// by name without threading ids around.
+pub fn player_id(name: &str) -> PlayerId {
+    // FNV-1a: deterministic and dependency-free.
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    uuid::Uuid::from_u128(u128::from(hash))
+}
+
+/// Start a [`PlayerInfo`] fixture: not authority, not ready, no connection info.
+pub fn player(name: impl Into<String>) -> PlayerBuilder {
+    let name = name.into();
+    PlayerBuilder {
+        info: PlayerInfo {
+            id: player_id(&name),
+            name,
+            is_authority: false,
+            is_ready: false,
+            connected_at: FIXTURE_TIMESTAMP.into(),
+            connection_info: None,
+            epoch: None,
+            seq: None,
+        },
+    }
+}
+
+/// Start a [`SpectatorInfo`] fixture.
+pub fn spectator(name: impl Into<String>) -> SpectatorBuilder {
+    let name = name.into();
+    SpectatorBuilder {
+        info: SpectatorInfo {
+            id: player_id(&name),
+            name,
+            connected_at: FIXTURE_TIMESTAMP.into(),
+        },
+    }
+}
+
+/// Start a `RoomJoined` fixture: an empty four-player `Waiting` room that
+/// supports authority, joined by a local player named `"self"`.
+pub fn room_joined(room_code: impl Into<String>) -> RoomJoinedBuilder {
+    RoomJoinedBuilder {
+        payload: RoomJoinedPayload {
+            room_id: RoomId::nil(),
+            room_code: room_code.into(),
+            player_id: player_id("self"),
+            game_name: "test-game".into(),
+            max_players: 4,
+            supports_authority: true,
+            current_players: Vec::new(),
+            is_authority: false,
+            lobby_state: LobbyState::Waiting,
+            ready_players: Vec::new(),
+            relay_type: "auto".into(),
+            current_spectators: Vec::new(),
+            ice_servers: Vec::new(),
+            reconnection_token: None,
+        },
+    }
+}
+
+/// Builder for a [`PlayerInfo`] fixture; see [`player`].
+#[derive(Debug, Clone)]
+pub struct PlayerBuilder {
+    info: PlayerInfo,
+}
+
+impl PlayerBuilder {
+    /// Override the name-derived id.
+    #[must_use]
+    pub fn id(mut self, id: PlayerId) -> Self {
+        self.info.id = id;
+        self
+    }
+
+    /// Mark the player as the room authority.
+    #[must_use]
+    pub fn authority(mut self) -> Self {
+        self.info.is_authority = true;
+        self
+    }
+
+    /// Mark the player as ready.
+    #[must_use]
+    pub fn ready(mut self) -> Self {
+        self.info.is_ready = true;
+        self
+    }
+
+    /// Override the [`FIXTURE_TIMESTAMP`] `connected_at`.
+    #[must_use]
+    pub fn connected_at(mut self, timestamp: impl Into<String>) -> Self {
+        self.info.connected_at = timestamp.into();
+        self
+    }
+
+    /// Attach P2P connection info.
+    #[must_use]
+    pub fn connection_info(mut self, info: ConnectionInfo) -> Self {
+        self.info.connection_info = Some(info);
+        self
+    }
+
+    /// Set the protocol-v3 incarnation epoch and relay tail.
+    #[must_use]
+    pub fn stamped(mut self, epoch: u32, seq: u64) -> Self {
+        self.info.epoch = Some(epoch);
+        self.info.seq = Some(seq);
+        self
+    }
+
+    /// The finished [`PlayerInfo`].
+    pub fn build(self) -> PlayerInfo {
+        self.info
+    }
+}
+
+impl From<PlayerBuilder> for PlayerInfo {
+    fn from(builder: PlayerBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builder for a [`SpectatorInfo`] fixture; see [`spectator`].
+#[derive(Debug, Clone)]
+pub struct SpectatorBuilder {
+    info: SpectatorInfo,
+}
+
+impl SpectatorBuilder {
+    /// Override the name-derived id.
+    #[must_use]
+    pub fn id(mut self, id: PlayerId) -> Self {
+        self.info.id = id;
+        self
+    }
+
+    /// Override the [`FIXTURE_TIMESTAMP`] `connected_at`.
+    #[must_use]
+    pub fn connected_at(mut self, timestamp: impl Into<String>) -> Self {
+        self.info.connected_at = timestamp.into();
+        self
+    }
+
+    /// The finished [`SpectatorInfo`].
+    pub fn build(self) -> SpectatorInfo {
+        self.info
+    }
+}
+
+impl From<SpectatorBuilder> for SpectatorInfo {
+    fn from(builder: SpectatorBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builder for a `RoomJoined` fixture; see [`room_joined`].
+///
+/// `ready_players` is derived from the roster, so it always agrees with each
+/// player's `is_ready`.
+#[derive(Debug, Clone)]
+pub struct RoomJoinedBuilder {
+    payload: RoomJoinedPayload,
+}
+
+impl RoomJoinedBuilder {
+    /// Replace the roster.
+    #[must_use]
+    pub fn players<P: Into<PlayerInfo>>(mut self, players: impl IntoIterator<Item = P>) -> Self {
+        self.payload.current_players = players.into_iter().map(Into::into).collect();
+        self.payload.ready_players = self
+            .payload
+            .current_players
+            .iter()
+            .filter(|player| player.is_ready)
+            .map(|player| player.id)
+            .collect();
+        self
+    }
+
+    /// Replace the spectator list.
+    #[must_use]
+    pub fn spectators<S: Into<SpectatorInfo>>(
+        mut self,
+        spectators: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.payload.current_spectators = spectators.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the local player's id (defaults to `player_id("self")`).
+    #[must_use]
+    pub fn player_id(mut self, id: PlayerId) -> Self {
+        self.payload.player_id = id;
+        self
+    }
+
+    /// Set the room id (defaults to the nil UUID).
+    #[must_use]
+    pub fn room_id(mut self, id: RoomId) -> Self {
+        self.payload.room_id = id;
+        self
+    }
+
+    /// Set the game name (defaults to `"test-game"`).
+    #[must_use]
+    pub fn game_name(mut self, name: impl Into<String>) -> Self {
+        self.payload.game_name = name.into();
+        self
+    }
+
+    /// Set the room capacity (defaults to 4).
+    #[must_use]
+    pub fn max_players(mut self, max_players: u8) -> Self {
+        self.payload.max_players = max_players;
+        self
+    }
+
+    /// Make the local player the room authority.
+    #[must_use]
+    pub fn authority(mut self) -> Self {
+        self.payload.is_authority = true;
+        self
+    }
+
+    /// Set the lobby state (defaults to `Waiting`).
+    #[must_use]
+    pub fn lobby_state(mut self, state: LobbyState) -> Self {
+        self.payload.lobby_state = state;
+        self
+    }
+
+    /// Set the relay transport label (defaults to `"auto"`).
+    #[must_use]
+    pub fn relay_type(mut self, relay_type: impl Into<String>) -> Self {
+        self.payload.relay_type = relay_type.into();
+        self
+    }
+
+    /// Attach a reconnection token.
+    #[must_use]
+    pub fn reconnection_token(mut self, token: impl Into<String>) -> Self {
+        self.payload.reconnection_token = Some(token.into());
+        self
+    }
+
+    /// The typed payload.
+    pub fn payload(&self) -> RoomJoinedPayload {
+        self.payload.clone()
+    }
+
+    /// The payload wrapped as a server message.
+    pub fn message(&self) -> ServerMessage {
+        ServerMessage::RoomJoined(Box::new(self.payload()))
+    }
+
+    /// The server message as a JSON text frame.
+    pub fn json(&self) -> String {
+        // Every field serializes infallibly (string keys, no custom impls).
+        serde_json::to_string(&self.message()).unwrap_or_default()
+    }
+}
+
+/// Shared record of the frames a test transport sent.
+///
+/// Clones share one log: hand one to the transport, which calls
+/// [`record`](Self::record) for each outbound frame, and keep one to inspect.
+/// A bounded log (the default, [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY)
+/// frames) drops the oldest frame once full, while [`total`](Self::total)
+/// keeps counting everything ever recorded and
+/// [`overflow_count`](Self::overflow_count) the frames dropped. Query with
+/// [`latest`](Self::latest) and [`matching`](Self::matching), which clone
+/// only the frames they return.
+///
+/// ```rust
+/// use signal_fish_client::testing::SentLog;
+///
+/// let sent = SentLog::bounded(2);
+/// for frame in ["a", "b", "c"] {
+///     sent.record(frame);
+/// }
+/// assert_eq!(sent.total(), 3);
+/// assert_eq!(sent.overflow_count(), 1);
+/// assert_eq!(sent.latest(5), vec!["b", "c"]);
+/// assert_eq!(sent.matching(|frame| frame == "c"), vec!["c"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SentLog {
+    inner: Arc<Mutex<SentLogInner>>,
+}
+
+#[derive(Debug)]
+struct SentLogInner {
+    frames: VecDeque<String>,
+    capacity: Option<usize>,
+    total: u64,
+    overflowed: u64,
+}
+
+impl SentLog {
+    /// Frames kept by [`SentLog::new`] and [`SentLog::default`].
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// A log keeping the newest [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY)
+    /// frames.
+    pub fn new() -> Self {
+        Self::bounded(Self::DEFAULT_CAPACITY)
+    }
+
+    /// A log keeping the newest `capacity` frames. Values below 1 are clamped
+    /// to 1.
+    pub fn bounded(capacity: usize) -> Self {
+        Self::with_capacity(Some(capacity.max(1)))
+    }
+
+    /// A log keeping every frame, for short tests that inspect the full
+    /// history.
+    pub fn unbounded() -> Self {
+        Self::with_capacity(None)
+    }
+
+    fn with_capacity(capacity: Option<usize>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SentLogInner {
+                frames: VecDeque::new(),
+                capacity,
+                total: 0,
+                overflowed: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SentLogInner> {
+        // A panicking test thread must not hide the log from the assertions.
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Record one sent frame, evicting the oldest if the log is full.
+    pub fn record(&self, frame: impl Into<String>) {
+        let mut inner = self.lock();
+        if inner.capacity == Some(inner.frames.len()) {
+            inner.frames.pop_front();
+            inner.overflowed = inner.overflowed.saturating_add(1);
+        }
+        inner.frames.push_back(frame.into());
+        inner.total = inner.total.saturating_add(1);
+    }
+
+    /// Frames ever recorded, including evicted ones.
+    pub fn total(&self) -> u64 {
+        self.lock().total
+    }
+
+    /// Frames evicted because the log was full. Frames dropped by
+    /// [`clear`](Self::clear) are not counted.
+    pub fn overflow_count(&self) -> u64 {
+        self.lock().overflowed
+    }
+
+    /// Frames currently retained; never more than the capacity.
+    pub fn len(&self) -> usize {
+        self.lock().frames.len()
+    }
+
+    /// Whether no frame is retained.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The retention limit; `None` for an unbounded log.
+    pub fn capacity(&self) -> Option<usize> {
+        self.lock().capacity
+    }
+
+    /// Up to the `n` newest retained frames, oldest first.
+    pub fn latest(&self, n: usize) -> Vec<String> {
+        let inner = self.lock();
+        let skip = inner.frames.len().saturating_sub(n);
+        inner.frames.iter().skip(skip).cloned().collect()
+    }
+
+    /// Retained frames for which `predicate` holds, oldest first.
+    pub fn matching(&self, mut predicate: impl FnMut(&str) -> bool) -> Vec<String> {
+        self.lock()
+            .frames
+            .iter()
+            .filter(|frame| predicate(frame))
+            .cloned()
+            .collect()
+    }
+
+    /// Drop every retained frame. [`total`](Self::total) is unaffected.
+    pub fn clear(&self) {
+        self.lock().frames.clear();
+    }
+}
+
+impl Default for SentLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Observer of every event the async client hands to a subscriber channel,
+/// installed with
+/// [`SignalFishConfig::with_emission_hook`](crate::SignalFishConfig::with_emission_hook).
+///
+/// Called with the subscriber's index (its position in the vector from
+/// [`start_with_subscribers`](crate::SignalFishClient::start_with_subscribers),
+/// `0` for [`start`](crate::SignalFishClient::start)) at the moment the event
+/// is enqueued, so a shared recorder sees one global emission sequence. The
+/// call happens on the transport loop: keep it short and never block.
+#[derive(Clone)]
+pub struct EmissionHook {
+    hook: Arc<EmissionFn>,
+}
+
+type EmissionFn = dyn Fn(usize, &SignalFishEvent) + Send + Sync;
+
+impl EmissionHook {
+    /// Wrap a closure called with the subscriber index and event of each
+    /// delivery.
+    pub fn new(hook: impl Fn(usize, &SignalFishEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            hook: Arc::new(hook),
+        }
+    }
+
+    /// Report that `event` was enqueued for `subscriber`.
+    pub fn observe(&self, subscriber: usize, event: &SignalFishEvent) {
+        (self.hook)(subscriber, event);
+    }
+}
+
+impl fmt::Debug for EmissionHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmissionHook").finish_non_exhaustive()
+    }
+}
+
+/// A deliberately slow reader of an async client's event receiver.
+///
+/// Each [`recv`](Self::recv) sleeps for the configured delay after taking an
+/// event, as a handler doing that much work would, so the transport loop
+/// meets a full channel. The sleep uses Tokio's clock: under
+/// `#[tokio::test(start_paused = true)]` a long run of slow handling takes no
+/// wall time.
+///
+/// It reads any [`SlowSource`]: the receivers from
+/// [`start`](crate::SignalFishClient::start),
+/// [`start_with_subscribers`](crate::SignalFishClient::start_with_subscribers),
+/// and [`subscribe`](crate::SignalFishClient::subscribe), or one from
+/// [`subscribe_broadcast`](crate::SignalFishClient::subscribe_broadcast). The
+/// client never drops an event for a slow `mpsc` reader — it stops reading
+/// the transport until there is room — so [`SlowConsumerReport`] counts what
+/// was buffered rather than lost. A slow broadcast reader loses the oldest
+/// events instead, counted in [`SlowConsumerReport::lagged`].
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use signal_fish_client::testing::SlowConsumer;
+/// use signal_fish_client::SignalFishEvent;
+///
+/// let (tx, rx) = tokio::sync::mpsc::channel(4);
+/// for _ in 0..3 {
+///     tx.send(SignalFishEvent::Pong).await.unwrap();
+/// }
+/// drop(tx);
+///
+/// let mut consumer = SlowConsumer::new(rx).with_artificial_delay(Duration::from_millis(1));
+/// assert_eq!(consumer.drain().await.len(), 3);
+/// let report = consumer.report();
+/// assert_eq!(report.received, 3);
+/// assert_eq!(report.peak_backlog, 3);
+/// assert_eq!(report.delayed, Duration::from_millis(3));
+/// # }
+/// ```
+#[cfg(feature = "tokio-runtime")]
+pub struct SlowConsumer<R = tokio::sync::mpsc::Receiver<SignalFishEvent>> {
+    events: R,
+    delay: Box<DelayFn>,
+    report: SlowConsumerReport,
+}
+
+#[cfg(feature = "tokio-runtime")]
+type DelayFn = dyn FnMut(&SignalFishEvent) -> std::time::Duration + Send;
+
+/// An event receiver a [`SlowConsumer`] can read.
+#[cfg(feature = "tokio-runtime")]
+pub trait SlowSource: Send {
+    /// Events already waiting to be read.
+    fn backlog(&self) -> usize;
+
+    /// The next event, adding to `lagged` any the receiver skipped because it
+    /// fell behind; `None` once the channel is closed and empty.
+    fn next_event(
+        &mut self,
+        lagged: &mut u64,
+    ) -> impl std::future::Future<Output = Option<SignalFishEvent>> + Send;
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl SlowSource for tokio::sync::mpsc::Receiver<SignalFishEvent> {
+    fn backlog(&self) -> usize {
+        self.len()
+    }
+
+    async fn next_event(&mut self, _lagged: &mut u64) -> Option<SignalFishEvent> {
+        self.recv().await
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl SlowSource for tokio::sync::broadcast::Receiver<SignalFishEvent> {
+    fn backlog(&self) -> usize {
+        self.len()
+    }
+
+    async fn next_event(&mut self, lagged: &mut u64) -> Option<SignalFishEvent> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        loop {
+            match self.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(missed)) => *lagged = lagged.saturating_add(missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// What a [`SlowConsumer`] observed so far.
+#[cfg(feature = "tokio-runtime")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlowConsumerReport {
+    /// Events taken from the receiver.
+    pub received: u64,
+    /// Most events already waiting when the consumer came back for the next
+    /// one; at most the channel's capacity for an `mpsc` receiver. A
+    /// broadcast receiver's count includes events it is about to skip.
+    pub peak_backlog: usize,
+    /// Total artificial delay applied.
+    pub delayed: std::time::Duration,
+    /// Events a broadcast receiver skipped by falling a full channel behind.
+    /// Always `0` for an `mpsc` receiver, which never loses events.
+    pub lagged: u64,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<R: SlowSource> SlowConsumer<R> {
+    /// Wrap `events` with no delay; add one with
+    /// [`with_artificial_delay`](Self::with_artificial_delay) or
+    /// [`with_delay_fn`](Self::with_delay_fn).
+    pub fn new(events: R) -> Self {
+        Self {
+            events,
+            delay: Box::new(|_| std::time::Duration::ZERO),
+            report: SlowConsumerReport::default(),
+        }
+    }
+
+    /// Spend `delay` handling every event.
+    #[must_use]
+    pub fn with_artificial_delay(self, delay: std::time::Duration) -> Self {
+        self.with_delay_fn(move |_| delay)
+    }
+
+    /// Spend what `delay` returns handling each event, for example only on
+    /// game data.
+    #[must_use]
+    pub fn with_delay_fn(
+        mut self,
+        delay: impl FnMut(&SignalFishEvent) -> std::time::Duration + Send + 'static,
+    ) -> Self {
+        self.delay = Box::new(delay);
+        self
+    }
+
+    /// The next event, after its handling delay; `None` once the channel is
+    /// closed and empty.
+    pub async fn recv(&mut self) -> Option<SignalFishEvent> {
+        let backlog = self.events.backlog();
+        let event = self.events.next_event(&mut self.report.lagged).await?;
+        self.report.received = self.report.received.saturating_add(1);
+        self.report.peak_backlog = self.report.peak_backlog.max(backlog);
+        let delay = (self.delay)(&event);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+            self.report.delayed = self.report.delayed.saturating_add(delay);
+        }
+        Some(event)
+    }
+
+    /// Every remaining event, read at the configured pace until the channel
+    /// closes.
+    pub async fn drain(&mut self) -> Vec<SignalFishEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = self.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    /// Counters for the run so far.
+    pub fn report(&self) -> SlowConsumerReport {
+        self.report
+    }
+
+    /// The wrapped receiver, to continue at full speed.
+    pub fn into_inner(self) -> R {
+        self.events
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<R> fmt::Debug for SlowConsumer<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowConsumer")
+            .field("report", &self.report)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_stable_and_distinct_per_name() {
+        let alice = player("Alice").build();
+        assert_eq!(alice.id, player_id("Alice"));
+        assert_ne!(alice.id, player_id("Bob"));
+        assert_eq!(alice.connected_at, FIXTURE_TIMESTAMP);
+        assert!(!alice.is_authority && !alice.is_ready);
+        assert_eq!(spectator("Alice").build().id, alice.id);
+    }
+
+    #[test]
+    fn room_joined_json_round_trips_and_derives_ready_players() {
+        let id = uuid::Uuid::from_u128(9);
+        let joined = room_joined("ROOM42")
+            .players([player("Alice").authority().ready().id(id), player("Bob")])
+            .authority();
+        let payload = joined.payload();
+        assert_eq!(payload.ready_players, vec![id]);
+
+        let ServerMessage::RoomJoined(decoded) = serde_json::from_str(&joined.json()).unwrap()
+        else {
+            panic!("RoomJoined fixture decoded as another message");
+        };
+        assert_eq!(decoded.room_code, "ROOM42");
+        assert!(decoded.is_authority);
+        assert_eq!(decoded.current_players[0].id, id);
+        assert!(decoded.current_players[0].is_authority);
+        assert_eq!(decoded.current_players[1].name, "Bob");
+    }
+
+    #[test]
+    fn bounded_sent_log_never_exceeds_capacity_while_counting_everything() {
+        let sent = SentLog::bounded(3);
+        for index in 0..100_000 {
+            sent.record(format!("frame {index}"));
+            assert!(sent.len() <= 3);
+        }
+        assert_eq!(sent.total(), 100_000);
+        assert_eq!(sent.overflow_count(), 100_000 - 3);
+        assert_eq!(sent.latest(2), vec!["frame 99998", "frame 99999"]);
+        assert_eq!(
+            sent.matching(|frame| frame.ends_with('7')),
+            vec!["frame 99997"]
+        );
+        sent.clear();
+        assert!(sent.is_empty());
+        assert_eq!(sent.total(), 100_000);
+        assert_eq!(sent.overflow_count(), 100_000 - 3);
+    }
+
+    #[test]
+    fn unbounded_sent_log_keeps_every_frame() {
+        let sent = SentLog::unbounded();
+        let clone = sent.clone();
+        for index in 0..5_000 {
+            clone.record(index.to_string());
+        }
+        assert_eq!(sent.capacity(), None);
+        assert_eq!(sent.len(), 5_000);
+        assert_eq!(sent.overflow_count(), 0);
+        assert_eq!(
+            sent.latest(usize::MAX).first().map(String::as_str),
+            Some("0")
+        );
+        assert_eq!(SentLog::bounded(0).capacity(), Some(1));
+    }
+}
@@ -0,0 +1,23 @@
+//! Clock types that work on every supported target.
+//!
+//! `std::time::Instant::now()` and `SystemTime::now()` panic on
+//! `wasm32-unknown-unknown`, which has no operating-system clock. There the
+//! `web-time` crate supplies the same API backed by the browser's
+//! `performance.now()` and `Date.now()`; everywhere else these are the
+//! standard library's types.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
+#[cfg(all(
+    any(feature = "tokio-runtime", feature = "polling-client"),
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+pub(crate) use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(all(
+    any(feature = "tokio-runtime", feature = "polling-client"),
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
+pub(crate) use web_time::{SystemTime, UNIX_EPOCH};
@@ -0,0 +1,146 @@
+//! Server clock synchronization for lockstep games.
+//!
+//! A `TimeSyncRequest` carries the client's send time; the server answers with
+//! a `TimeSyncResponse` echoing it beside its own clock reading. Assuming the
+//! server read its clock halfway through the round trip (the NTP half-RTT
+//! estimate), the pair yields a [`TimeSyncResult`]. Asymmetric paths bias the
+//! offset by half the difference between the two directions, which is why
+//! short round trips are the most trustworthy: the clients keep the last few
+//! samples and report the one with the smallest RTT as their
+//! `server_time_estimate()`.
+
+use std::time::Duration;
+
+/// Samples kept for the best-of-N server-time estimate unless configured
+/// with [`SignalFishConfig::with_time_sync_samples`](crate::SignalFishConfig::with_time_sync_samples).
+pub const DEFAULT_TIME_SYNC_SAMPLES: usize = 8;
+
+/// One clock comparison with the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeSyncResult {
+    /// Estimated server clock minus local clock, in milliseconds. Add it to a
+    /// local Unix-epoch millisecond timestamp to read the server clock.
+    pub offset: i64,
+    /// Round-trip time of the exchange.
+    pub rtt: Duration,
+}
+
+impl TimeSyncResult {
+    /// Estimate the offset from one exchange: the request left at
+    /// `client_sent_at_ms`, the server stamped `server_time_ms`, and the
+    /// response arrived at `received_at_ms` (all Unix-epoch milliseconds).
+    ///
+    /// A response that appears to arrive before its request was sent counts
+    /// as a zero round trip.
+    #[must_use]
+    pub fn from_exchange(client_sent_at_ms: u64, server_time_ms: u64, received_at_ms: u64) -> Self {
+        let rtt_ms = received_at_ms.saturating_sub(client_sent_at_ms);
+        let midpoint = i128::from(client_sent_at_ms) + i128::from(rtt_ms / 2);
+        let offset = i128::from(server_time_ms) - midpoint;
+        Self {
+            offset: i64::try_from(offset).unwrap_or(if offset < 0 { i64::MIN } else { i64::MAX }),
+            rtt: Duration::from_millis(rtt_ms),
+        }
+    }
+
+    /// The server clock at local Unix-epoch time `local_ms`, by this estimate.
+    #[must_use]
+    pub fn server_time_ms(&self, local_ms: u64) -> u64 {
+        local_ms.saturating_add_signed(self.offset)
+    }
+}
+
+/// Rolling window of the most recent samples.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+#[derive(Debug)]
+pub(crate) struct TimeSyncSampler {
+    samples: std::collections::VecDeque<TimeSyncResult>,
+    capacity: usize,
+}
+
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+impl TimeSyncSampler {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: std::collections::VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub(crate) fn record(&mut self, sample: TimeSyncResult) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// The retained sample with the smallest round trip; the newest wins a tie.
+    pub(crate) fn best(&self) -> Option<TimeSyncResult> {
+        self.samples
+            .iter()
+            .rev()
+            .min_by_key(|sample| sample.rtt)
+            .copied()
+    }
+}
+
+/// Milliseconds since the Unix epoch by the system clock, `0` if it reads
+/// before the epoch.
+#[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+pub(crate) fn unix_time_ms() -> u64 {
+    crate::time::SystemTime::now()
+        .duration_since(crate::time::UNIX_EPOCH)
+        .map_or(0, |since| {
+            u64::try_from(since.as_millis()).unwrap_or(u64::MAX)
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_paths_recover_the_true_offset() {
+        // Server runs 5 s ahead; 40 ms each way.
+        let sample = TimeSyncResult::from_exchange(1_000, 1_000 + 5_000 + 40, 1_080);
+        assert_eq!(sample.rtt, Duration::from_millis(80));
+        assert_eq!(sample.offset, 5_000);
+        assert_eq!(sample.server_time_ms(2_000), 7_000);
+    }
+
+    #[test]
+    fn asymmetric_paths_bias_the_offset_by_half_the_difference() {
+        // Server runs 5 s behind; 10 ms up, 90 ms down.
+        let sample = TimeSyncResult::from_exchange(10_000, 10_000 - 5_000 + 10, 10_100);
+        assert_eq!(sample.rtt, Duration::from_millis(100));
+        assert_eq!(sample.offset, -5_000 + (10 - 90) / 2);
+        assert_eq!(sample.server_time_ms(10), 0, "saturates at the epoch");
+    }
+
+    #[test]
+    fn a_response_from_the_past_counts_as_a_zero_round_trip() {
+        let sample = TimeSyncResult::from_exchange(500, 700, 400);
+        assert_eq!(sample.rtt, Duration::ZERO);
+        assert_eq!(sample.offset, 200);
+    }
+
+    #[cfg(any(feature = "tokio-runtime", feature = "polling-client"))]
+    #[test]
+    fn sampler_keeps_the_lowest_rtt_of_the_last_n() {
+        let sample = |offset, rtt_ms| TimeSyncResult {
+            offset,
+            rtt: Duration::from_millis(rtt_ms),
+        };
+        let mut sampler = TimeSyncSampler::new(3);
+        assert_eq!(sampler.best(), None);
+        sampler.record(sample(1, 10));
+        sampler.record(sample(2, 50));
+        sampler.record(sample(3, 30));
+        assert_eq!(sampler.best(), Some(sample(1, 10)));
+        sampler.record(sample(4, 30));
+        assert_eq!(sampler.best(), Some(sample(4, 30)), "oldest evicted");
+        assert!(TimeSyncSampler::new(0).capacity >= 1);
+    }
+}
@@ -4,11 +4,14 @@
 //! same implementation usable by an async runtime driver and by a main-thread
 //! game-loop driver without requiring `Send`.
 
+#[cfg(feature = "client")]
 use std::task::{Context, Poll};
 
+#[cfg(feature = "client")]
 use crate::error::SignalFishError;
 
 /// One complete signaling transport frame.
+#[cfg(feature = "client")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransportFrame {
     /// JSON protocol message.
@@ -34,6 +37,7 @@ pub struct TransportCloseInfo {
 ///
 /// Counters are cumulative and saturating. Byte values describe backend-owned
 /// buffering, not the polling client's command queue or peer delivery.
+#[cfg(feature = "client")]
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct TransportDiagnostics {
     /// Bytes currently buffered by the transport backend.
@@ -72,6 +76,18 @@ pub struct TransportDiagnostics {
 ///
 /// `poll_close` is idempotent and may require multiple polls. Once it returns
 /// `Ready(Ok(()))`, later calls must also succeed without sending another close.
+///
+/// # One session per instance
+///
+/// A transport carries exactly one client session, and so exactly one
+/// `Authenticate`. Both clients take it by value, send their own
+/// `Authenticate` first, and close it when they stop, so connect a new
+/// transport for every client and every reconnect. A custom transport that is
+/// a handle onto shared connection state must not be handed to two sessions;
+/// the server would see interleaved handshakes on one connection. Such a
+/// handle should share one used-flag across its copies and report it through
+/// [`claim_session`](Self::claim_session).
+#[cfg(feature = "client")]
 pub trait Transport {
     /// Mark the start of one caller-driven polling cycle.
     ///
@@ -99,6 +115,29 @@ pub trait Transport {
     /// The default preserves source compatibility for existing implementors.
     fn abort(&mut self) {}
 
+    /// Claim this transport for one client session, just before the client
+    /// sends its `Authenticate`. Returns `false` if a session already claimed
+    /// it.
+    ///
+    /// The async client refuses a transport that cannot be claimed: it logs
+    /// the refusal at error level, sends nothing, leaves the transport open
+    /// for its other owner, and ends with
+    /// [`DisconnectReason::HandshakeRefused`](crate::DisconnectReason::HandshakeRefused)
+    /// naming [`SignalFishError::TransportReused`].
+    ///
+    /// Each client takes its transport by value and claims it once, and no
+    /// public API sends a second `Authenticate` on a transport. A refusal
+    /// therefore means the connection reached a second session some other
+    /// way: a custom handle onto shared connection state, or a wrapper that
+    /// claimed it before handing it on.
+    /// [`WebSocketTransport`](crate::WebSocketTransport) and
+    /// [`TcpTransport`](crate::TcpTransport) keep a used-flag. The default
+    /// always succeeds, which suits a transport that owns its connection
+    /// outright.
+    fn claim_session(&mut self) -> bool {
+        true
+    }
+
     /// Whether the connection handshake has completed.
     fn is_ready(&self) -> bool {
         true
@@ -109,6 +148,17 @@ pub trait Transport {
         None
     }
 
+    /// When the transport last saw the connection alive, including traffic
+    /// the client never receives as a frame (WebSocket pongs, keepalive acks).
+    ///
+    /// The async client's heartbeat skips its `Ping` while this falls within
+    /// [`ping_interval`](crate::SignalFishConfig::ping_interval). Read it from
+    /// the clock the client runs on (Tokio's, under paused test time). The
+    /// default `None` leaves the heartbeat unchanged.
+    fn last_activity_hint(&self) -> Option<crate::time::Instant> {
+        None
+    }
+
     /// Return transport-owned buffering and admission diagnostics.
     fn diagnostics(&self) -> TransportDiagnostics {
         TransportDiagnostics::default()
@@ -117,7 +167,18 @@ pub trait Transport {
 
 /// Gate a synchronous backend send without transferring caller ownership until
 /// the backend accepts the borrowed frame.
-#[cfg(any(test, target_os = "emscripten"))]
+#[cfg(all(
+    feature = "client",
+    any(
+        test,
+        target_os = "emscripten",
+        all(
+            feature = "transport-websocket-wasm",
+            target_arch = "wasm32",
+            target_os = "unknown"
+        )
+    )
+))]
 pub(crate) fn poll_accept_frame<F>(
     ready: bool,
     frame: &mut Option<TransportFrame>,
@@ -167,7 +228,7 @@ pub(crate) async fn close_transport<T: Transport + ?Sized>(
     std::future::poll_fn(|cx| transport.poll_close(cx)).await
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "client"))]
 mod tests {
     use std::cell::Cell;
     use std::task::Poll;
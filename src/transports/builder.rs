@@ -0,0 +1,550 @@
+//! Handshake customization for [`WebSocketTransport`].
+//!
+//! Servers that authenticate the WebSocket upgrade itself expect more than a
+//! URL: an `Authorization` header, a tenant or region query parameter, a
+//! cookie. [`WebSocketTransportBuilder`], returned by
+//! [`WebSocketTransport::builder`], adds them to the handshake request that
+//! tungstenite sends. Header names and values are checked as they are added,
+//! so a malformed one fails with [`SignalFishError::InvalidRequest`] before
+//! anything is dialed.
+//!
+//! The builder also bounds the initial dial, which otherwise waits out the
+//! operating system's TCP timeout when the server is unreachable:
+//! [`with_connect_timeout`](WebSocketTransportBuilder::with_connect_timeout)
+//! caps each attempt and [`with_retry`](WebSocketTransportBuilder::with_retry)
+//! makes more than one.
+
+use std::fmt;
+use std::time::Duration;
+
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use super::connector::ProgressFn;
+use super::websocket::{WebSocketConnectOptions, WebSocketTransport, WsStream};
+use super::{ConnectProgress, WebSocketConnector};
+use crate::error::SignalFishError;
+
+/// Builder for a [`WebSocketTransport`] whose handshake carries extra headers
+/// or query parameters.
+///
+/// ```rust,no_run
+/// # async fn example(token: &str) -> Result<(), signal_fish_client::SignalFishError> {
+/// use signal_fish_client::WebSocketTransport;
+///
+/// let transport = WebSocketTransport::builder("wss://signal.example.com/ws")
+///     .with_header("Authorization", format!("Bearer {token}"))
+///     .with_query_param("tenant", "acme")
+///     .connect()
+///     .await?;
+/// # let _ = transport;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A header replaces the one tungstenite would generate under the same name;
+/// adding a name twice sends both values. Query parameters are
+/// percent-encoded and appended to any query the URL already has. `Debug`
+/// output shows header names and query keys but not their values, which
+/// often hold credentials.
+#[derive(Clone)]
+pub struct WebSocketTransportBuilder {
+    url: String,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    query: Vec<(String, String)>,
+    /// The first header that failed to parse, reported when the request is
+    /// built.
+    invalid: Option<String>,
+    options: WebSocketConnectOptions,
+    connect_timeout: Option<Duration>,
+    attempts: u32,
+    backoff: Duration,
+}
+
+impl fmt::Debug for WebSocketTransportBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketTransportBuilder")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "query",
+                &self.query.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            )
+            .field("invalid", &self.invalid)
+            .field("options", &self.options)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("attempts", &self.attempts)
+            .field("backoff", &self.backoff)
+            .finish()
+    }
+}
+
+impl WebSocketTransportBuilder {
+    pub(crate) fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            query: Vec::new(),
+            invalid: None,
+            options: WebSocketConnectOptions::default(),
+            connect_timeout: None,
+            attempts: 1,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// Send `name: value` with the handshake.
+    ///
+    /// An invalid name or value is remembered and returned as
+    /// [`SignalFishError::InvalidRequest`] by [`request`](Self::request) and
+    /// the connect methods.
+    #[must_use]
+    pub fn with_header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        let parsed = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|error| format!("header name {name:?}: {error}"))
+            .and_then(|parsed| {
+                HeaderValue::from_str(value.as_ref())
+                    .map(|value| (parsed, value))
+                    .map_err(|error| format!("value of header {name:?}: {error}"))
+            });
+        match parsed {
+            Ok((name, mut value)) => {
+                value.set_sensitive(true);
+                self.headers.push((name, value));
+            }
+            Err(error) => {
+                self.invalid.get_or_insert(error);
+            }
+        }
+        self
+    }
+
+    /// Append `key=value` to the URL's query, percent-encoding both.
+    #[must_use]
+    pub fn with_query_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the socket options for [`connect`](Self::connect).
+    ///
+    /// Defaults to [`WebSocketConnectOptions::default`].
+    /// [`connect_with`](Self::connect_with) uses the connector's options
+    /// instead.
+    #[must_use]
+    pub fn with_options(mut self, options: WebSocketConnectOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Give up on an attempt that has not connected within `timeout`, with
+    /// [`SignalFishError::ConnectTimeout`].
+    ///
+    /// The timeout covers one attempt — DNS, TCP, TLS, and the upgrade — not
+    /// the retries of [`with_retry`](Self::with_retry). Defaults to none: an
+    /// unreachable host then takes the operating system's TCP timeout, often
+    /// more than a minute, to fail.
+    #[must_use]
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Make up to `attempts` attempts at the initial connection, waiting
+    /// `backoff` before the second and twice as long before each one after.
+    ///
+    /// Only failures that may pass are retried: I/O errors, a closed
+    /// transport, and [`SignalFishError::ConnectTimeout`]. An invalid
+    /// request or a failed TLS handshake is returned at once. Once the
+    /// attempts run out, the last one's error is returned. Defaults to a
+    /// single attempt; `0` counts as `1`.
+    ///
+    /// This covers the first dial only. To redial after an established
+    /// connection drops, start the client with
+    /// [`SignalFishClient::start_with_reconnect`](crate::SignalFishClient::start_with_reconnect).
+    #[must_use]
+    pub fn with_retry(mut self, attempts: u32, backoff: Duration) -> Self {
+        self.attempts = attempts.max(1);
+        self.backoff = backoff;
+        self
+    }
+
+    /// The handshake request the connect methods send.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::InvalidRequest`] if a header name or value
+    /// is invalid, or the URL with its query parameters is not a valid
+    /// WebSocket URL.
+    pub fn request(&self) -> Result<Request, SignalFishError> {
+        if let Some(invalid) = &self.invalid {
+            return Err(SignalFishError::InvalidRequest(invalid.clone()));
+        }
+        let mut request = self
+            .url_with_query()
+            .into_client_request()
+            .map_err(|error| SignalFishError::InvalidRequest(error.to_string()))?;
+        let mut custom = HeaderMap::new();
+        for (name, value) in &self.headers {
+            custom.append(name.clone(), value.clone());
+        }
+        request.headers_mut().extend(custom);
+        Ok(request)
+    }
+
+    /// Connect with the custom handshake, resolving the host afresh.
+    ///
+    /// Dropping the returned future stops connecting, including any retry
+    /// still waiting out its backoff.
+    ///
+    /// # Errors
+    ///
+    /// [`SignalFishError::InvalidRequest`] as [`request`](Self::request),
+    /// before anything is dialed; [`SignalFishError::ConnectTimeout`] if the
+    /// last attempt timed out; otherwise the errors of
+    /// [`WebSocketTransport::connect`].
+    pub async fn connect(self) -> Result<WebSocketTransport, SignalFishError> {
+        self.connect_with_progress(|_| {}).await
+    }
+
+    /// [`connect`](Self::connect), reporting each setup step to
+    /// `on_progress` as
+    /// [`WebSocketTransport::connect_with_progress`] does, plus a
+    /// [`ConnectProgress::RetryScheduled`] before each retry.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with_progress(
+        self,
+        mut on_progress: impl FnMut(ConnectProgress) + Send,
+    ) -> Result<WebSocketTransport, SignalFishError> {
+        let connector = WebSocketConnector::shared().reusing(self.options);
+        self.open(&connector, &mut on_progress).await
+    }
+
+    /// Connect with the custom handshake through a shared
+    /// [`WebSocketConnector`], reusing its DNS cache and (with the `tls`
+    /// feature) its TLS configuration.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with(
+        self,
+        connector: &WebSocketConnector,
+    ) -> Result<WebSocketTransport, SignalFishError> {
+        self.open(connector, &mut |_| {}).await
+    }
+
+    async fn open(
+        &self,
+        connector: &WebSocketConnector,
+        progress: &mut ProgressFn<'_>,
+    ) -> Result<WebSocketTransport, SignalFishError> {
+        // Checked up front so an invalid request is never retried.
+        self.request()?;
+        tracing::debug!(url = %self.url, "connecting to WebSocket server with a custom handshake");
+        let mut attempt = 1;
+        let stream = loop {
+            match self.attempt(connector, progress).await {
+                Err(error) if attempt < self.attempts && retryable(&error) => {
+                    let delay = self.backoff.saturating_mul(1 << (attempt - 1).min(31));
+                    attempt += 1;
+                    tracing::debug!(url = %self.url, attempt, ?delay, %error, "retrying WebSocket connect");
+                    progress(ConnectProgress::RetryScheduled { attempt, delay });
+                    tokio::time::sleep(delay).await;
+                }
+                result => break result?,
+            }
+        };
+        tracing::info!(url = %self.url, "WebSocket connection established");
+        Ok(WebSocketTransport::from_stream(stream))
+    }
+
+    /// One attempt, with a fresh request (and so a fresh
+    /// `Sec-WebSocket-Key`) each time.
+    async fn attempt(
+        &self,
+        connector: &WebSocketConnector,
+        progress: &mut ProgressFn<'_>,
+    ) -> Result<WsStream, SignalFishError> {
+        let request = self.request()?;
+        match self.connect_timeout {
+            Some(timeout) => {
+                connector
+                    .open_request_within(request, timeout, progress)
+                    .await
+            }
+            None => connector.open_request(request, progress).await,
+        }
+    }
+
+    /// The URL with the query parameters appended ahead of any fragment.
+    fn url_with_query(&self) -> String {
+        if self.query.is_empty() {
+            return self.url.clone();
+        }
+        let (base, fragment) = match self.url.split_once('#') {
+            Some((base, fragment)) => (base, Some(fragment)),
+            None => (self.url.as_str(), None),
+        };
+        let mut url = base.to_owned();
+        match base.find('?') {
+            None => url.push('?'),
+            Some(at) if at + 1 < base.len() && !base.ends_with('&') => url.push('&'),
+            Some(_) => {}
+        }
+        for (index, (key, value)) in self.query.iter().enumerate() {
+            if index > 0 {
+                url.push('&');
+            }
+            percent_encode(&mut url, key);
+            url.push('=');
+            percent_encode(&mut url, value);
+        }
+        if let Some(fragment) = fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        url
+    }
+}
+
+/// Whether a failed attempt may succeed if made again.
+fn retryable(error: &SignalFishError) -> bool {
+    matches!(
+        error,
+        SignalFishError::Io(_)
+            | SignalFishError::TransportClosed
+            | SignalFishError::ConnectTimeout { .. }
+    )
+}
+
+/// Append `text` to `out`, percent-encoding every byte outside RFC 3986's
+/// unreserved set.
+fn percent_encode(out: &mut String, text: &str) {
+    use std::fmt::Write as _;
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::transports::ConnectStage;
+
+    #[test]
+    fn headers_and_query_land_on_the_request() {
+        let request = WebSocketTransport::builder("wss://signal.example.com/ws")
+            .with_header("Authorization", "Bearer secret-token")
+            .with_header("X-Trace", "a")
+            .with_header("X-Trace", "b")
+            .with_query_param("tenant", "acme")
+            .with_query_param("name", "Zoë & co")
+            .request()
+            .unwrap();
+
+        assert_eq!(
+            request.uri().to_string(),
+            "wss://signal.example.com/ws?tenant=acme&name=Zo%C3%AB%20%26%20co"
+        );
+        let headers = request.headers();
+        assert_eq!(headers["authorization"], "Bearer secret-token");
+        let traces: Vec<_> = headers.get_all("x-trace").iter().collect();
+        assert_eq!(traces, ["a", "b"]);
+        // tungstenite's own upgrade headers are still there.
+        assert_eq!(headers["host"], "signal.example.com");
+        assert!(headers.contains_key("sec-websocket-key"));
+    }
+
+    #[test]
+    fn a_custom_header_replaces_the_generated_one() {
+        let request = WebSocketTransport::builder("ws://localhost:3536/ws")
+            .with_header("Host", "game.example.com")
+            .request()
+            .unwrap();
+
+        let hosts: Vec<_> = request.headers().get_all("host").iter().collect();
+        assert_eq!(hosts, ["game.example.com"]);
+    }
+
+    #[test]
+    fn query_params_extend_an_existing_query() {
+        let url = |base: &str| {
+            WebSocketTransport::builder(base)
+                .with_query_param("tenant", "acme")
+                .url_with_query()
+        };
+        assert_eq!(url("ws://h/ws?v=3"), "ws://h/ws?v=3&tenant=acme");
+        assert_eq!(url("ws://h/ws?"), "ws://h/ws?tenant=acme");
+        assert_eq!(url("ws://h/ws?v=3&"), "ws://h/ws?v=3&tenant=acme");
+        assert_eq!(url("ws://h/ws#top"), "ws://h/ws?tenant=acme#top");
+    }
+
+    #[test]
+    fn invalid_headers_fail_before_dialing() {
+        let bad_name = WebSocketTransport::builder("ws://localhost:3536/ws")
+            .with_header("Bad Header", "value")
+            .request()
+            .unwrap_err();
+        assert!(
+            matches!(&bad_name, SignalFishError::InvalidRequest(detail) if detail.contains("Bad Header")),
+            "{bad_name:?}"
+        );
+
+        let bad_value = WebSocketTransport::builder("ws://localhost:3536/ws")
+            .with_header("Authorization", "Bearer line\nbreak")
+            .request()
+            .unwrap_err();
+        assert!(
+            matches!(&bad_value, SignalFishError::InvalidRequest(detail) if !detail.contains("line")),
+            "the value stays out of the error: {bad_value:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn connect_reports_an_invalid_request_without_dialing() {
+        // Nothing listens here; only the request check can answer.
+        let error = WebSocketTransport::builder("ws://127.0.0.1:1/ws")
+            .with_header("Bad Header", "value")
+            .connect()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, SignalFishError::InvalidRequest(_)),
+            "{error:?}"
+        );
+    }
+
+    /// A server that accepts each connection and drops it at once, counting
+    /// the connections.
+    async fn closing_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                drop(tcp);
+            }
+        });
+        (url, accepted)
+    }
+
+    #[tokio::test]
+    async fn retries_back_off_until_the_attempts_run_out() {
+        let (url, accepted) = closing_server().await;
+        let mut steps = Vec::new();
+
+        let error = WebSocketTransport::builder(url)
+            .with_retry(3, Duration::from_millis(10))
+            .connect_with_progress(|step| steps.push(step))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, SignalFishError::Io(_)), "{error:?}");
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+        let retries: Vec<_> = steps
+            .iter()
+            .filter_map(|step| match step {
+                ConnectProgress::RetryScheduled { attempt, delay } => Some((*attempt, *delay)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            retries,
+            [
+                (2, Duration::from_millis(10)),
+                (3, Duration::from_millis(20))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn connect_timeout_abandons_a_silent_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        // Accept, then never answer the upgrade.
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((tcp, _)) = listener.accept().await {
+                held.push(tcp);
+            }
+        });
+        let mut steps = Vec::new();
+        let started = std::time::Instant::now();
+
+        let error = WebSocketTransport::builder(url)
+            .with_connect_timeout(Duration::from_millis(50))
+            .connect_with_progress(|step| steps.push(step))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, SignalFishError::ConnectTimeout { elapsed }
+                if elapsed >= Duration::from_millis(50)),
+            "{error:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(
+            matches!(
+                steps.last(),
+                Some(ConnectProgress::Failed {
+                    stage: ConnectStage::Upgrade,
+                    ..
+                })
+            ),
+            "{steps:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn dropping_the_connect_future_stops_retrying() {
+        let (url, accepted) = closing_server().await;
+
+        let connect = WebSocketTransport::builder(url)
+            .with_retry(10, Duration::from_millis(100))
+            .connect();
+        let outcome = tokio::time::timeout(Duration::from_millis(30), connect).await;
+        assert!(
+            outcome.is_err(),
+            "the connect future must still be backing off"
+        );
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn debug_output_hides_header_and_query_values() {
+        let builder = WebSocketTransport::builder("ws://localhost:3536/ws")
+            .with_header("Authorization", "Bearer secret-token")
+            .with_query_param("key", "secret-key");
+
+        let debug = format!("{builder:?}");
+        assert!(debug.contains("authorization"), "{debug}");
+        assert!(!debug.contains("secret"), "{debug}");
+    }
+}
@@ -0,0 +1,1034 @@
+//! Reusable connection setup shared by many [`WebSocketTransport`]s.
+//!
+//! A process that opens many connections — a bot swarm, a load tester, a
+//! server embedding one client per room — otherwise pays a DNS lookup and a
+//! full TLS handshake per connection. A [`WebSocketConnector`] caches resolved
+//! addresses for a configurable TTL and (with the `tls` feature) holds one
+//! rustls `ClientConfig`, so TLS session resumption works across connections.
+//! [`TlsRoots`] picks which certificate authorities that config trusts: the
+//! bundled webpki roots, the platform store, both, or a custom set.
+//!
+//! The helpers that take no connector, such as
+//! [`WebSocketTransport::connect`](super::WebSocketTransport::connect), share
+//! one process-wide [`WebSocketConnector::shared`], so even plain connects
+//! reuse DNS answers and TLS sessions.
+//!
+//! Connection setup runs in explicit stages — resolve, TCP connect, TLS, and
+//! WebSocket upgrade — each reported as a [`ConnectProgress`] to callers that
+//! ask for it via
+//! [`WebSocketTransport::connect_with_progress`](super::WebSocketTransport::connect_with_progress).
+//!
+//! [`WebSocketTransport`]: super::WebSocketTransport
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+
+use super::websocket::{websocket_error_to_io, WebSocketConnectOptions, WsStream};
+use crate::error::SignalFishError;
+
+/// Future returned by [`DnsResolver::resolve`].
+pub type ResolveFuture<'a> = Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'a>>;
+
+/// Hostname resolution used by a [`WebSocketConnector`].
+///
+/// The default, [`SystemResolver`], uses the operating system resolver via
+/// `tokio::net::lookup_host`. Supply your own to route lookups through a
+/// custom DNS client or to observe them in tests.
+pub trait DnsResolver: Send + Sync + fmt::Debug {
+    /// Resolve `host` to the socket addresses to try, in order, for `port`.
+    fn resolve<'a>(&'a self, host: &'a str, port: u16) -> ResolveFuture<'a>;
+}
+
+/// [`DnsResolver`] backed by the operating system resolver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl DnsResolver for SystemResolver {
+    fn resolve<'a>(&'a self, host: &'a str, port: u16) -> ResolveFuture<'a> {
+        Box::pin(async move { Ok(tokio::net::lookup_host((host, port)).await?.collect()) })
+    }
+}
+
+/// A step of connection setup, as reported by [`ConnectProgress::Failed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectStage {
+    /// Parsing the URL and resolving its host to socket addresses.
+    Resolve,
+    /// Opening the TCP connection.
+    Tcp,
+    /// The TLS handshake (`wss://` only).
+    Tls,
+    /// The WebSocket HTTP upgrade handshake.
+    Upgrade,
+}
+
+/// Progress of a connection attempt, in the order the steps happen.
+///
+/// A successful `ws://` connect reports `DnsResolved`, `TcpConnected`, and
+/// `UpgradeCompleted`; `wss://` adds `TlsEstablished` before the upgrade. A
+/// failed attempt ends with a single `Failed` naming the step that failed.
+/// Helpers that retry emit `RetryScheduled` before each new attempt and then
+/// start the sequence over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectProgress {
+    /// The host resolved to these addresses (a single entry for IP literals).
+    DnsResolved(Vec<SocketAddr>),
+    /// A TCP connection to this address is open.
+    TcpConnected(SocketAddr),
+    /// The TLS handshake completed.
+    TlsEstablished,
+    /// The WebSocket upgrade completed; the connection is ready.
+    UpgradeCompleted,
+    /// Another attempt will start after `delay`.
+    RetryScheduled {
+        /// 1-based number of the attempt that is about to start.
+        attempt: u32,
+        /// How long the helper waits before starting it.
+        delay: Duration,
+    },
+    /// The attempt failed during `stage`.
+    Failed {
+        /// The step that failed.
+        stage: ConnectStage,
+        /// Display text of the underlying error.
+        error: String,
+    },
+}
+
+/// Callback type the staged connect reports [`ConnectProgress`] through.
+pub(crate) type ProgressFn<'a> = dyn FnMut(ConnectProgress) + Send + 'a;
+
+/// Report a failure at `stage` and pass the error through.
+fn fail(
+    progress: &mut ProgressFn<'_>,
+    stage: ConnectStage,
+    error: SignalFishError,
+) -> SignalFishError {
+    progress(ConnectProgress::Failed {
+        stage,
+        error: error.to_string(),
+    });
+    error
+}
+
+/// Default lifetime of a cached DNS answer.
+pub const DEFAULT_DNS_TTL: Duration = Duration::from_secs(60);
+
+struct CachedAddrs {
+    addrs: Vec<SocketAddr>,
+    expires_at: Instant,
+}
+
+type DnsCache = Mutex<HashMap<(String, u16), CachedAddrs>>;
+
+/// Backs [`WebSocketConnector::shared`].
+static SHARED: OnceLock<Arc<WebSocketConnector>> = OnceLock::new();
+
+/// Shared, reusable connection setup for [`WebSocketTransport`](super::WebSocketTransport).
+///
+/// Build one, share it by reference (or inside an `Arc`), and connect with
+/// [`WebSocketTransport::connect_with`](super::WebSocketTransport::connect_with):
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), signal_fish_client::SignalFishError> {
+/// use signal_fish_client::{WebSocketConnector, WebSocketTransport};
+///
+/// let connector = WebSocketConnector::new();
+/// let mut bots = Vec::new();
+/// for _ in 0..200 {
+///     bots.push(WebSocketTransport::connect_with(&connector, "ws://localhost:3536/ws").await?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebSocketConnector {
+    options: WebSocketConnectOptions,
+    dns_ttl: Duration,
+    resolver: Arc<dyn DnsResolver>,
+    dns_cache: Arc<DnsCache>,
+    #[cfg(feature = "tls")]
+    tls_config: Arc<rustls::ClientConfig>,
+}
+
+impl fmt::Debug for WebSocketConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketConnector")
+            .field("options", &self.options)
+            .field("dns_ttl", &self.dns_ttl)
+            .field("resolver", &self.resolver)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for WebSocketConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebSocketConnector {
+    /// Create a connector with default [`WebSocketConnectOptions`], the
+    /// [`SystemResolver`], a [`DEFAULT_DNS_TTL`] cache, and (with `tls`) a
+    /// rustls config using the bundled webpki roots with session resumption
+    /// enabled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            options: WebSocketConnectOptions::default(),
+            dns_ttl: DEFAULT_DNS_TTL,
+            resolver: Arc::new(SystemResolver),
+            dns_cache: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "tls")]
+            tls_config: default_tls_config(),
+        }
+    }
+
+    /// The process-wide connector, built with [`new`](Self::new) on first use.
+    ///
+    /// [`WebSocketTransport::connect`](super::WebSocketTransport::connect) and
+    /// the other helpers that take no connector go through it, as do
+    /// [`WebSocketDialer::new`](super::WebSocketDialer::new) and
+    /// [`WebSocketTransportBuilder::connect`](super::WebSocketTransportBuilder::connect).
+    /// Those asking for other socket options or another TLS config still share
+    /// its DNS cache. Build your own connector to change the TTL or resolver.
+    #[must_use]
+    pub fn shared() -> Arc<Self> {
+        Arc::clone(SHARED.get_or_init(|| Arc::new(Self::new())))
+    }
+
+    /// A connector applying `options` that shares this one's DNS cache,
+    /// resolver, and TLS config.
+    pub(crate) fn reusing(&self, options: WebSocketConnectOptions) -> Self {
+        Self {
+            options,
+            dns_ttl: self.dns_ttl,
+            resolver: Arc::clone(&self.resolver),
+            dns_cache: Arc::clone(&self.dns_cache),
+            #[cfg(feature = "tls")]
+            tls_config: Arc::clone(&self.tls_config),
+        }
+    }
+
+    /// Set the socket options applied to every connection.
+    #[must_use]
+    pub fn with_options(mut self, options: WebSocketConnectOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set how long a resolved address list is reused.
+    ///
+    /// Defaults to [`DEFAULT_DNS_TTL`]. `Duration::ZERO` disables caching.
+    #[must_use]
+    pub fn with_dns_ttl(mut self, ttl: Duration) -> Self {
+        self.dns_ttl = ttl;
+        self
+    }
+
+    /// Replace the hostname resolver.
+    #[must_use]
+    pub fn with_resolver(mut self, resolver: impl DnsResolver + 'static) -> Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+
+    /// Replace the shared rustls client configuration (for example to add a
+    /// private root CA).
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn with_tls_config(mut self, config: Arc<rustls::ClientConfig>) -> Self {
+        self.tls_config = config;
+        self
+    }
+
+    /// Trust `roots` for every `wss://` connection, replacing the shared
+    /// rustls configuration (session resumption stays enabled).
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), signal_fish_client::SignalFishError> {
+    /// use signal_fish_client::{TlsRoots, WebSocketConnector, WebSocketTransport};
+    ///
+    /// // Trust a TLS-inspecting corporate proxy whose CA lives in the OS store.
+    /// let connector = WebSocketConnector::new().with_tls_roots(TlsRoots::Both)?;
+    /// let transport = WebSocketTransport::connect_with(&connector, "wss://example.com/ws").await?;
+    /// # let _ = transport;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As [`TlsRoots::load`].
+    #[cfg(feature = "tls")]
+    pub fn with_tls_roots(self, roots: TlsRoots) -> Result<Self, SignalFishError> {
+        Ok(self.with_tls_config(tls_config_with_roots(roots.load()?)))
+    }
+
+    /// The rustls configuration every `wss://` connection shares.
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn tls_config(&self) -> &Arc<rustls::ClientConfig> {
+        &self.tls_config
+    }
+
+    /// Socket options applied to every connection.
+    #[must_use]
+    pub fn options(&self) -> WebSocketConnectOptions {
+        self.options
+    }
+
+    /// Drop every cached DNS answer.
+    pub fn clear_dns_cache(&self) {
+        self.lock_cache().clear();
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<(String, u16), CachedAddrs>> {
+        self.dns_cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![SocketAddr::new(ip, port)]);
+        }
+        let key = (host.to_owned(), port);
+        if let Some(cached) = self.lock_cache().get(&key) {
+            if cached.expires_at > Instant::now() {
+                return Ok(cached.addrs.clone());
+            }
+        }
+        let addrs = self.resolver.resolve(host, port).await?;
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no addresses found for {host}"),
+            ));
+        }
+        if !self.dns_ttl.is_zero() {
+            self.lock_cache().insert(
+                key,
+                CachedAddrs {
+                    addrs: addrs.clone(),
+                    expires_at: Instant::now() + self.dns_ttl,
+                },
+            );
+        }
+        Ok(addrs)
+    }
+
+    /// Resolve, connect, and complete the WebSocket (and TLS) handshake.
+    pub(crate) async fn open(&self, url: &str) -> Result<WsStream, SignalFishError> {
+        self.open_with_progress(url, &mut |_| {}).await
+    }
+
+    /// [`open`](Self::open), reporting each completed or failed step to `progress`.
+    pub(crate) async fn open_with_progress(
+        &self,
+        url: &str,
+        progress: &mut ProgressFn<'_>,
+    ) -> Result<WsStream, SignalFishError> {
+        let request = url
+            .into_client_request()
+            .map_err(|e| SignalFishError::Io(websocket_error_to_io(e)))
+            .map_err(|e| fail(progress, ConnectStage::Resolve, e))?;
+        self.open_request(request, progress).await
+    }
+
+    /// [`open`](Self::open) within `timeout`, as
+    /// [`open_request_within`](Self::open_request_within).
+    pub(crate) async fn open_within(
+        &self,
+        url: &str,
+        timeout: Duration,
+    ) -> Result<WsStream, SignalFishError> {
+        let request = url
+            .into_client_request()
+            .map_err(|e| SignalFishError::Io(websocket_error_to_io(e)))?;
+        self.open_request_within(request, timeout, &mut |_| {})
+            .await
+    }
+
+    /// [`open_with_progress`](Self::open_with_progress) for a handshake
+    /// request built by the caller, headers and all.
+    pub(crate) async fn open_request(
+        &self,
+        request: Request,
+        progress: &mut ProgressFn<'_>,
+    ) -> Result<WsStream, SignalFishError> {
+        let uri = request.uri();
+        let secure = uri.scheme_str() == Some("wss");
+        let host = uri
+            .host()
+            .map(|host| {
+                host.trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_owned()
+            })
+            .ok_or_else(|| {
+                SignalFishError::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "WebSocket URL has no host",
+                ))
+            })
+            .map_err(|e| fail(progress, ConnectStage::Resolve, e))?;
+        if secure && !cfg!(feature = "tls") {
+            return Err(fail(progress, ConnectStage::Resolve, tls_unavailable()));
+        }
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+        let addrs = self
+            .resolve(&host, port)
+            .await
+            .map_err(|e| fail(progress, ConnectStage::Resolve, e.into()))?;
+        progress(ConnectProgress::DnsResolved(addrs.clone()));
+
+        let tcp = tokio::net::TcpStream::connect(addrs.as_slice())
+            .await
+            .map_err(|e| fail(progress, ConnectStage::Tcp, e.into()))?;
+        if self.options.disable_nagle {
+            tcp.set_nodelay(true)
+                .map_err(|e| fail(progress, ConnectStage::Tcp, e.into()))?;
+        }
+        let peer = tcp
+            .peer_addr()
+            .map_err(|e| fail(progress, ConnectStage::Tcp, e.into()))?;
+        progress(ConnectProgress::TcpConnected(peer));
+
+        let stream = if secure {
+            let tls = self
+                .tls_handshake(&host, tcp)
+                .await
+                .map_err(|e| fail(progress, ConnectStage::Tls, e))?;
+            progress(ConnectProgress::TlsEstablished);
+            tls
+        } else {
+            tokio_tungstenite::MaybeTlsStream::Plain(tcp)
+        };
+
+        let (stream, _response) =
+            tokio_tungstenite::client_async_with_config(request, stream, None)
+                .await
+                .map_err(|e| SignalFishError::Io(websocket_error_to_io(e)))
+                .map_err(|e| fail(progress, ConnectStage::Upgrade, e))?;
+        progress(ConnectProgress::UpgradeCompleted);
+        Ok(stream)
+    }
+
+    /// [`open_request`](Self::open_request), abandoned with
+    /// [`SignalFishError::ConnectTimeout`] if it has not finished within
+    /// `timeout`. The timeout is reported as `Failed` at the step that was
+    /// still running.
+    pub(crate) async fn open_request_within(
+        &self,
+        request: Request,
+        timeout: Duration,
+        progress: &mut ProgressFn<'_>,
+    ) -> Result<WsStream, SignalFishError> {
+        let secure = request.uri().scheme_str() == Some("wss");
+        let started = tokio::time::Instant::now();
+        let mut stage = ConnectStage::Resolve;
+        let mut track = |step: ConnectProgress| {
+            stage = match step {
+                ConnectProgress::DnsResolved(_) => ConnectStage::Tcp,
+                ConnectProgress::TcpConnected(_) if secure => ConnectStage::Tls,
+                ConnectProgress::TcpConnected(_) | ConnectProgress::TlsEstablished => {
+                    ConnectStage::Upgrade
+                }
+                _ => stage,
+            };
+            progress(step);
+        };
+        match tokio::time::timeout(timeout, self.open_request(request, &mut track)).await {
+            Ok(result) => result,
+            Err(_) => {
+                let elapsed = started.elapsed();
+                tracing::debug!(?elapsed, ?stage, "WebSocket connect timed out");
+                Err(fail(
+                    progress,
+                    stage,
+                    SignalFishError::ConnectTimeout { elapsed },
+                ))
+            }
+        }
+    }
+
+    #[cfg(feature = "tls")]
+    async fn tls_handshake(
+        &self,
+        host: &str,
+        tcp: tokio::net::TcpStream,
+    ) -> Result<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>, SignalFishError> {
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_owned())
+            .map_err(|e| SignalFishError::Io(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+        let tls = tokio_rustls::TlsConnector::from(Arc::clone(&self.tls_config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(SignalFishError::TlsHandshake)?;
+        Ok(tokio_tungstenite::MaybeTlsStream::Rustls(tls))
+    }
+
+    #[cfg(not(feature = "tls"))]
+    #[allow(clippy::unused_async)]
+    async fn tls_handshake(
+        &self,
+        _host: &str,
+        _tcp: tokio::net::TcpStream,
+    ) -> Result<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>, SignalFishError> {
+        Err(tls_unavailable())
+    }
+}
+
+/// The error for a `wss://` URL in a build without the `tls` feature.
+fn tls_unavailable() -> SignalFishError {
+    SignalFishError::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "wss:// URLs need the `tls` (or `transport-websocket-rustls`) feature of signal-fish-client",
+    ))
+}
+
+/// The certificate authorities a `wss://` connection trusts.
+///
+/// Players behind a TLS-inspecting proxy (common on managed Windows machines)
+/// see certificates signed by a CA that exists only in the machine's store;
+/// [`Native`](Self::Native) or [`Both`](Self::Both) let them connect. Apply
+/// with [`WebSocketConnector::with_tls_roots`].
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Default)]
+pub enum TlsRoots {
+    /// The bundled Mozilla roots (`webpki-roots`): the same on every machine.
+    #[default]
+    WebPki,
+    /// The operating system's store: the Windows certificate store, the macOS
+    /// keychain, or the OpenSSL paths on other Unixes. `SSL_CERT_FILE` and
+    /// `SSL_CERT_DIR` override the platform lookup.
+    Native,
+    /// The bundled roots plus the operating system's store.
+    Both,
+    /// Exactly these roots, for example a private CA.
+    Custom(rustls::RootCertStore),
+}
+
+#[cfg(feature = "tls")]
+impl TlsRoots {
+    /// Build the root store.
+    ///
+    /// [`Native`](Self::Native) and [`Both`](Self::Both) read the platform
+    /// store on every call, which can take a while on some systems; build a
+    /// connector once and share it. Unreadable parts of a store that still
+    /// yields certificates are logged and skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Io`] when the platform store cannot be read
+    /// or holds no usable certificate. `Both` fails too rather than quietly
+    /// falling back to the bundled roots.
+    pub fn load(&self) -> Result<rustls::RootCertStore, SignalFishError> {
+        Ok(match self {
+            Self::WebPki => webpki_roots(),
+            Self::Native => native_roots()?,
+            Self::Both => {
+                let mut roots = webpki_roots();
+                roots.roots.extend(native_roots()?.roots);
+                roots
+            }
+            Self::Custom(roots) => roots.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "tls")]
+fn webpki_roots() -> rustls::RootCertStore {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    roots
+}
+
+#[cfg(feature = "tls")]
+fn native_roots() -> Result<rustls::RootCertStore, SignalFishError> {
+    let loaded = rustls_native_certs::load_native_certs();
+    let mut roots = rustls::RootCertStore::empty();
+    let (added, ignored) = roots.add_parsable_certificates(loaded.certs);
+    if added == 0 {
+        let detail = if !loaded.errors.is_empty() {
+            loaded
+                .errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        } else if ignored > 0 {
+            format!("none of its {ignored} certificates could be parsed")
+        } else {
+            "it holds no certificates".to_owned()
+        };
+        return Err(SignalFishError::Io(io::Error::other(format!(
+            "cannot read the platform certificate store: {detail}"
+        ))));
+    }
+    for error in &loaded.errors {
+        tracing::warn!(%error, "skipped part of the platform certificate store");
+    }
+    if ignored > 0 {
+        tracing::warn!(ignored, "skipped unparsable platform root certificates");
+    }
+    Ok(roots)
+}
+
+#[cfg(feature = "tls")]
+fn default_tls_config() -> Arc<rustls::ClientConfig> {
+    tls_config_with_roots(webpki_roots())
+}
+
+#[cfg(feature = "tls")]
+fn tls_config_with_roots(roots: rustls::RootCertStore) -> Arc<rustls::ClientConfig> {
+    super::websocket::ensure_crypto_provider();
+    let mut config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.resumption = rustls::client::Resumption::in_memory_sessions(256);
+    Arc::new(config)
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+mod tests {
+    use super::*;
+    use crate::transports::WebSocketTransport;
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::TcpListener;
+
+    #[derive(Debug)]
+    struct CountingResolver {
+        target: SocketAddr,
+        lookups: Arc<AtomicUsize>,
+    }
+
+    impl DnsResolver for CountingResolver {
+        fn resolve<'a>(&'a self, _host: &'a str, _port: u16) -> ResolveFuture<'a> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            let target = self.target;
+            Box::pin(async move { Ok(vec![target]) })
+        }
+    }
+
+    /// Accept `connections` WebSocket handshakes and hold each one open.
+    async fn start_server(connections: usize) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..connections {
+                let (tcp, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                    while let Some(Ok(_)) = ws.next().await {}
+                });
+            }
+        });
+        addr
+    }
+
+    fn counting_connector(
+        target: SocketAddr,
+        ttl: Duration,
+    ) -> (WebSocketConnector, Arc<AtomicUsize>) {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let connector =
+            WebSocketConnector::new()
+                .with_dns_ttl(ttl)
+                .with_resolver(CountingResolver {
+                    target,
+                    lookups: Arc::clone(&lookups),
+                });
+        (connector, lookups)
+    }
+
+    #[tokio::test]
+    async fn dns_answers_are_cached_across_connects() {
+        let addr = start_server(2).await;
+        let (connector, lookups) = counting_connector(addr, DEFAULT_DNS_TTL);
+        let url = format!("ws://signal-fish.test:{}/ws", addr.port());
+
+        let _first = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+        let _second = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn plain_connects_share_the_process_wide_cache() {
+        let addr = start_server(3).await;
+        assert!(Arc::ptr_eq(
+            &WebSocketConnector::shared(),
+            &WebSocketConnector::shared()
+        ));
+        let url = format!("ws://localhost:{}/ws", addr.port());
+        let cached = || {
+            WebSocketConnector::shared()
+                .lock_cache()
+                .get(&("localhost".to_owned(), addr.port()))
+                .map(|cached| cached.expires_at)
+        };
+
+        let _first = WebSocketTransport::connect(&url).await.unwrap();
+        let expires_at = cached().expect("the answer is cached by the shared connector");
+        let _second = WebSocketTransport::connect(&url).await.unwrap();
+        let nagle = WebSocketConnectOptions {
+            disable_nagle: false,
+        };
+        let _third = WebSocketTransport::connect_with_options(&url, nagle)
+            .await
+            .unwrap();
+
+        assert_eq!(cached(), Some(expires_at), "later connects hit the cache");
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_resolves_every_time() {
+        let addr = start_server(2).await;
+        let (connector, lookups) = counting_connector(addr, Duration::ZERO);
+        let url = format!("ws://signal-fish.test:{}/ws", addr.port());
+
+        let _first = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+        let _second = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn ip_literals_bypass_the_resolver() {
+        let addr = start_server(1).await;
+        let (connector, lookups) = counting_connector(addr, DEFAULT_DNS_TTL);
+
+        let _transport = WebSocketTransport::connect_with(&connector, &format!("ws://{addr}/ws"))
+            .await
+            .unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 0);
+    }
+
+    /// Connect to `url`, returning the result and every progress step reported.
+    async fn connect_recording(
+        url: &str,
+    ) -> (
+        Result<WebSocketTransport, SignalFishError>,
+        Vec<ConnectProgress>,
+    ) {
+        let mut steps = Vec::new();
+        let result = WebSocketTransport::connect_with_progress(url, |step| steps.push(step)).await;
+        (result, steps)
+    }
+
+    #[tokio::test]
+    async fn progress_reports_each_step_in_order() {
+        let addr = start_server(1).await;
+
+        let (result, steps) = connect_recording(&format!("ws://{addr}/ws")).await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            steps,
+            vec![
+                ConnectProgress::DnsResolved(vec![addr]),
+                ConnectProgress::TcpConnected(addr),
+                ConnectProgress::UpgradeCompleted,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_through_a_connector_uses_its_resolver() {
+        let addr = start_server(1).await;
+        let (connector, lookups) = counting_connector(addr, DEFAULT_DNS_TTL);
+        let url = format!("ws://signal-fish.test:{}/ws", addr.port());
+        let mut steps = Vec::new();
+
+        let result =
+            WebSocketTransport::connect_with_connector_progress(&connector, &url, |step| {
+                steps.push(step);
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            steps.first(),
+            Some(&ConnectProgress::DnsResolved(vec![addr]))
+        );
+        assert_eq!(steps.last(), Some(&ConnectProgress::UpgradeCompleted));
+    }
+
+    #[tokio::test]
+    async fn refused_port_reports_tcp_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let (result, steps) = connect_recording(&format!("ws://{addr}/ws")).await;
+
+        assert!(result.is_err());
+        assert_eq!(steps.len(), 2, "{steps:?}");
+        assert_eq!(steps[0], ConnectProgress::DnsResolved(vec![addr]));
+        assert!(
+            matches!(
+                &steps[1],
+                ConnectProgress::Failed {
+                    stage: ConnectStage::Tcp,
+                    ..
+                }
+            ),
+            "{steps:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_upgrade_reports_upgrade_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            drop(tcp);
+        });
+
+        let (result, steps) = connect_recording(&format!("ws://{addr}/ws")).await;
+
+        assert!(result.is_err());
+        assert_eq!(steps[1], ConnectProgress::TcpConnected(addr));
+        assert!(
+            matches!(
+                steps.last(),
+                Some(ConnectProgress::Failed {
+                    stage: ConnectStage::Upgrade,
+                    ..
+                })
+            ),
+            "{steps:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_url_reports_resolve_failure() {
+        let (result, steps) = connect_recording("not a url").await;
+
+        assert!(result.is_err());
+        assert!(
+            matches!(
+                steps.as_slice(),
+                [ConnectProgress::Failed {
+                    stage: ConnectStage::Resolve,
+                    ..
+                }]
+            ),
+            "{steps:?}"
+        );
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn wss_to_plain_server_reports_tls_failure() {
+        let addr = start_server(1).await;
+
+        let (result, steps) = connect_recording(&format!("wss://{addr}/ws")).await;
+
+        assert!(result.is_err());
+        assert_eq!(steps[1], ConnectProgress::TcpConnected(addr));
+        assert!(
+            matches!(
+                steps.last(),
+                Some(ConnectProgress::Failed {
+                    stage: ConnectStage::Tls,
+                    ..
+                })
+            ),
+            "{steps:?}"
+        );
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn connects_share_one_tls_config() {
+        let addr = start_server(2).await;
+        let connector = WebSocketConnector::new();
+        let shared = Arc::clone(connector.tls_config());
+        let url = format!("ws://{addr}/ws");
+
+        let _first = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+        let _second = WebSocketTransport::connect_with(&connector, &url)
+            .await
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&shared, connector.tls_config()));
+        assert_eq!(
+            Arc::strong_count(&shared),
+            2,
+            "no per-connect config copies"
+        );
+    }
+
+    /// A self-signed `localhost` certificate; see `tests/tls-fixtures`.
+    #[cfg(feature = "tls")]
+    const LOCALHOST_CERT: &str = include_str!("../../tests/tls-fixtures/localhost.crt");
+    #[cfg(feature = "tls")]
+    const LOCALHOST_KEY: &str = include_str!("../../tests/tls-fixtures/localhost.key");
+
+    #[cfg(feature = "tls")]
+    fn localhost_roots() -> rustls::RootCertStore {
+        use rustls::pki_types::pem::PemObject;
+        let mut roots = rustls::RootCertStore::empty();
+        roots
+            .add(
+                rustls::pki_types::CertificateDer::from_pem_slice(LOCALHOST_CERT.as_bytes())
+                    .unwrap(),
+            )
+            .unwrap();
+        roots
+    }
+
+    /// Serve `connections` WebSocket handshakes over TLS as `localhost`.
+    #[cfg(feature = "tls")]
+    async fn start_tls_server(connections: usize) -> SocketAddr {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+        crate::transports::websocket::ensure_crypto_provider();
+        let cert = CertificateDer::from_pem_slice(LOCALHOST_CERT.as_bytes()).unwrap();
+        let key = PrivateKeyDer::from_pem_slice(LOCALHOST_KEY.as_bytes()).unwrap();
+        let config = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert], key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..connections {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    // A client that distrusts the certificate aborts the handshake.
+                    let Ok(tls) = acceptor.accept(tcp).await else {
+                        return;
+                    };
+                    let mut ws = tokio_tungstenite::accept_async(tls).await.unwrap();
+                    while let Some(Ok(_)) = ws.next().await {}
+                });
+            }
+        });
+        addr
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn tls_roots_build_the_expected_stores() {
+        assert!(matches!(TlsRoots::default(), TlsRoots::WebPki));
+        assert_eq!(
+            TlsRoots::WebPki.load().unwrap().len(),
+            webpki_roots::TLS_SERVER_ROOTS.len()
+        );
+
+        let custom = localhost_roots();
+        assert_eq!(TlsRoots::Custom(custom.clone()).load().unwrap().len(), 1);
+
+        let connector = WebSocketConnector::new()
+            .with_tls_roots(TlsRoots::Custom(custom))
+            .unwrap();
+        assert!(
+            !Arc::ptr_eq(
+                connector.tls_config(),
+                WebSocketConnector::new().tls_config()
+            ),
+            "with_tls_roots replaces the shared config"
+        );
+    }
+
+    // The only test that reads the platform store, so its environment
+    // overrides cannot race another test.
+    #[cfg(feature = "tls")]
+    #[test]
+    fn native_roots_follow_ssl_cert_file_and_name_unreadable_stores() {
+        let path =
+            std::env::temp_dir().join(format!("signal-fish-roots-{}.pem", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        std::env::remove_var("SSL_CERT_DIR");
+        std::env::set_var("SSL_CERT_FILE", &path);
+
+        for roots in [TlsRoots::Native, TlsRoots::Both] {
+            let error = roots.clone().load().unwrap_err();
+            assert!(matches!(error, SignalFishError::Io(_)), "{error:?}");
+            assert!(
+                error
+                    .to_string()
+                    .contains("cannot read the platform certificate store"),
+                "{error}"
+            );
+            assert!(WebSocketConnector::new().with_tls_roots(roots).is_err());
+        }
+
+        std::fs::write(&path, "no certificates here")
+            .unwrap_or_else(|e| panic!("write {}: {e}", path.display()));
+        let error = TlsRoots::Native.load().unwrap_err();
+        assert!(
+            error.to_string().ends_with("it holds no certificates"),
+            "{error}"
+        );
+
+        std::fs::write(&path, LOCALHOST_CERT)
+            .unwrap_or_else(|e| panic!("write {}: {e}", path.display()));
+        assert_eq!(TlsRoots::Native.load().unwrap().len(), 1);
+        assert_eq!(
+            TlsRoots::Both.load().unwrap().len(),
+            webpki_roots::TLS_SERVER_ROOTS.len() + 1
+        );
+
+        std::env::remove_var("SSL_CERT_FILE");
+        std::fs::remove_file(&path).unwrap_or_else(|e| panic!("remove {}: {e}", path.display()));
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn custom_roots_trust_a_self_signed_server() {
+        let addr = start_tls_server(2).await;
+        let url = format!("wss://localhost:{}/ws", addr.port());
+
+        let (bundled, _) = counting_connector(addr, DEFAULT_DNS_TTL);
+        let error = WebSocketTransport::connect_with(&bundled, &url)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, SignalFishError::TlsHandshake(_)),
+            "{error:?}"
+        );
+
+        let (trusting, _) = counting_connector(addr, DEFAULT_DNS_TTL);
+        let trusting = trusting
+            .with_tls_roots(TlsRoots::Custom(localhost_roots()))
+            .unwrap();
+        WebSocketTransport::connect_with(&trusting, &url)
+            .await
+            .unwrap();
+    }
+}
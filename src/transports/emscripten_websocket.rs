@@ -435,6 +435,19 @@ unsafe fn copy_event_payload(data: *const u8, len: usize) -> Vec<u8> {
     unsafe { std::slice::from_raw_parts(data, len).to_vec() }
 }
 
+/// The longest valid UTF-8 prefix of `bytes`. The browser truncates a close
+/// reason to the event's fixed buffer, which can cut a character in half;
+/// the rest of the reason is still worth keeping.
+fn utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => bytes
+            .get(..error.valid_up_to())
+            .and_then(|valid| std::str::from_utf8(valid).ok())
+            .unwrap_or_default(),
+    }
+}
+
 // SAFETY: See the callback SAFETY block comment above for pointer guarantees.
 extern "C" fn on_open_callback(
     _event_type: c_int,
@@ -520,7 +533,7 @@ extern "C" fn on_close_callback(
     let reason = event
         .reason
         .get(..reason_len)
-        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .map(utf8_prefix)
         .filter(|reason| !reason.is_empty())
         .map(str::to_owned);
     let _ = state.tx.send(IncomingEvent::Close {
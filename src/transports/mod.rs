@@ -7,7 +7,9 @@
 //! | Feature                | Transport              |
 //! |------------------------|------------------------|
 //! | `transport-websocket`  | [`WebSocketTransport`] |
+//! | `transport-tcp`        | [`TcpTransport`]       |
 //! | `transport-websocket-emscripten` | `EmscriptenWebSocketTransport` |
+//! | `transport-websocket-wasm` | `WasmWebSocketTransport` |
 //!
 //! # Example
 //!
@@ -27,11 +29,29 @@
 //! # }
 //! ```
 
+#[cfg(feature = "transport-websocket")]
+pub mod builder;
+#[cfg(feature = "transport-websocket")]
+pub mod connector;
 #[cfg(feature = "transport-websocket")]
 pub mod websocket;
 
+#[cfg(feature = "transport-tcp")]
+pub mod tcp;
+
+#[cfg(feature = "transport-websocket")]
+pub use builder::WebSocketTransportBuilder;
+#[cfg(feature = "tls")]
+pub use connector::TlsRoots;
 #[cfg(feature = "transport-websocket")]
-pub use websocket::{WebSocketConnectOptions, WebSocketTransport};
+pub use connector::{
+    ConnectProgress, ConnectStage, DnsResolver, ResolveFuture, SystemResolver, WebSocketConnector,
+};
+#[cfg(feature = "transport-websocket")]
+pub use websocket::{WebSocketConnectOptions, WebSocketDialer, WebSocketTransport};
+
+#[cfg(feature = "transport-tcp")]
+pub use tcp::TcpTransport;
 
 // Gated on both feature and target: this module uses Emscripten's C WebSocket API,
 // which only exists on wasm32-unknown-emscripten. The dual gate keeps `--all-features`
@@ -43,3 +63,25 @@ pub mod emscripten_websocket;
 #[cfg(all(feature = "transport-websocket-emscripten", target_os = "emscripten"))]
 #[allow(deprecated)]
 pub use emscripten_websocket::EmscriptenWebSocketTransport;
+
+// Gated on feature and target like the Emscripten transport: web-sys is a
+// wasm32-unknown-unknown dependency only. The event translation it builds on
+// holds no JavaScript values, so host test builds compile and test it too.
+#[cfg(all(
+    feature = "transport-websocket-wasm",
+    target_arch = "wasm32",
+    target_os = "unknown"
+))]
+pub mod wasm_websocket;
+#[cfg(all(
+    feature = "transport-websocket-wasm",
+    any(test, all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod wasm_websocket_state;
+
+#[cfg(all(
+    feature = "transport-websocket-wasm",
+    target_arch = "wasm32",
+    target_os = "unknown"
+))]
+pub use wasm_websocket::WasmWebSocketTransport;
@@ -0,0 +1,574 @@
+//! Newline-delimited JSON over a plain TCP connection.
+//!
+//! [`TcpTransport`] carries each protocol message as one line of JSON ending
+//! in `\n`, with no WebSocket framing, for servers and relays listening on a
+//! raw TCP port. It is also handy for local tooling, where a line protocol
+//! can be driven with `nc`.
+//!
+//! # Feature gate
+//!
+//! Available with the `transport-tcp` feature.
+//!
+//! # Framing
+//!
+//! Inbound bytes are split on `\n` however TCP happens to segment them; a line
+//! split across several reads is reassembled, and several lines in one read
+//! come out as separate frames. A trailing `\r` is stripped and blank lines are
+//! skipped. Lines may be at most
+//! [`with_max_line_length`](TcpTransport::with_max_line_length) bytes, so a
+//! peer that never sends a newline cannot grow the buffer without bound.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), signal_fish_client::SignalFishError> {
+//! use signal_fish_client::{SignalFishClient, SignalFishConfig, TcpTransport};
+//!
+//! let transport = TcpTransport::connect("127.0.0.1:3537").await?;
+//! let (client, events) = SignalFishClient::start(transport, SignalFishConfig::new("mb_app_abc123"));
+//! # drop((client, events));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+use crate::error::SignalFishError;
+use crate::transport::{Transport, TransportCloseInfo, TransportDiagnostics, TransportFrame};
+
+/// Default longest line, in bytes, that [`TcpTransport`] sends or receives:
+/// 1 MiB.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1024 * 1024;
+
+/// Bytes read from the socket per attempt.
+const READ_CHUNK: usize = 8 * 1024;
+
+/// A [`Transport`] that sends each frame as one line of JSON over TCP.
+///
+/// Only text frames can be sent: a [`TransportFrame::Binary`] frame, or text
+/// containing `\n`, would break the framing and is refused with
+/// [`SignalFishError::TransportSend`]. A frame or inbound line longer than the
+/// [maximum line length](Self::with_max_line_length) is refused with
+/// [`SignalFishError::MessageTooLarge`]; for an inbound line that also ends the
+/// stream, since the rest of it cannot be skipped reliably.
+///
+/// [`poll_close`](Transport::poll_close) shuts down the write half, so the
+/// server reads end-of-stream; frames the server sends before closing its own
+/// end can still be received.
+#[derive(Debug)]
+pub struct TcpTransport {
+    stream: Option<TcpStream>,
+    max_line_length: usize,
+    /// Received bytes not yet returned as a frame.
+    inbound: Vec<u8>,
+    /// Length of the prefix of `inbound` already searched for a newline.
+    scanned: usize,
+    /// The accepted frame and its newline, until fully written.
+    outbound: Vec<u8>,
+    /// Bytes of `outbound` already written.
+    written: usize,
+    /// The read half reached end-of-stream or failed.
+    read_done: bool,
+    /// The write half has been shut down.
+    shut_down: bool,
+    close_info: Option<TransportCloseInfo>,
+    /// When the last bytes arrived.
+    last_activity: Option<std::time::Instant>,
+    /// A client session has claimed the transport.
+    session_claimed: bool,
+}
+
+impl TcpTransport {
+    /// Connect to `addr`, trying each resolved address in turn.
+    ///
+    /// Nagle's algorithm is disabled (`TCP_NODELAY`) so small, latency-sensitive
+    /// game messages are sent without delay. Use
+    /// [`from_stream`](Self::from_stream) to choose socket options yourself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Io`] if no address could be resolved or
+    /// connected to.
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self, SignalFishError> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        tracing::info!(peer = ?stream.peer_addr().ok(), "TCP connection established");
+        Ok(Self::from_stream(stream))
+    }
+
+    /// Wrap an already-connected stream, leaving its socket options as they
+    /// are.
+    pub fn from_stream(stream: TcpStream) -> Self {
+        Self {
+            stream: Some(stream),
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            inbound: Vec::new(),
+            scanned: 0,
+            outbound: Vec::new(),
+            written: 0,
+            read_done: false,
+            shut_down: false,
+            close_info: None,
+            last_activity: None,
+            session_claimed: false,
+        }
+    }
+
+    /// Set the longest line, in bytes and excluding the line ending, that is
+    /// sent or received. Defaults to [`DEFAULT_MAX_LINE_LENGTH`].
+    #[must_use]
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Take the next complete, non-blank line out of `inbound`.
+    fn take_line(&mut self) -> Option<Result<TransportFrame, SignalFishError>> {
+        loop {
+            let Some(offset) = self
+                .inbound
+                .get(self.scanned..)
+                .and_then(|unscanned| unscanned.iter().position(|byte| *byte == b'\n'))
+            else {
+                self.scanned = self.inbound.len();
+                return None;
+            };
+            let end = self.scanned + offset;
+            let mut line: Vec<u8> = self.inbound.drain(..=end).collect();
+            self.scanned = 0;
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if line.len() > self.max_line_length {
+                return Some(Err(self.line_too_long()));
+            }
+            if line.is_empty() {
+                continue;
+            }
+            return Some(
+                String::from_utf8(line)
+                    .map(TransportFrame::Text)
+                    .map_err(|error| {
+                        SignalFishError::TransportReceive(format!("line is not UTF-8: {error}"))
+                    }),
+            );
+        }
+    }
+
+    /// End the read half after an oversized line.
+    fn line_too_long(&mut self) -> SignalFishError {
+        tracing::warn!(
+            limit = self.max_line_length,
+            "inbound line exceeds the limit; ending the TCP stream"
+        );
+        self.inbound = Vec::new();
+        self.scanned = 0;
+        self.read_done = true;
+        SignalFishError::MessageTooLarge {
+            limit: self.max_line_length,
+        }
+    }
+
+    /// Write out the rest of `outbound`.
+    fn poll_write_outbound(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SignalFishError>> {
+        while let Some(rest) = self
+            .outbound
+            .get(self.written..)
+            .filter(|rest| !rest.is_empty())
+        {
+            let Some(stream) = self.stream.as_mut() else {
+                return Poll::Ready(Err(SignalFishError::TransportClosed));
+            };
+            let error = match Pin::new(stream).poll_write(cx, rest) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => "connection closed while writing".to_string(),
+                Poll::Ready(Ok(written)) => {
+                    self.written += written;
+                    continue;
+                }
+                Poll::Ready(Err(error)) => error.to_string(),
+            };
+            self.outbound.clear();
+            self.written = 0;
+            return Poll::Ready(Err(SignalFishError::TransportSend(error)));
+        }
+        self.outbound.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Transport for TcpTransport {
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> Poll<Result<(), SignalFishError>> {
+        if self.shut_down || self.stream.is_none() {
+            return Poll::Ready(Err(SignalFishError::TransportClosed));
+        }
+        // Finish the frame accepted by an earlier call before taking another.
+        match self.poll_write_outbound(cx) {
+            Poll::Ready(Ok(())) => {}
+            other => return other,
+        }
+        let text = match frame.as_ref() {
+            None => return Poll::Ready(Ok(())),
+            Some(TransportFrame::Text(text)) => text,
+            Some(TransportFrame::Binary(_)) => {
+                return Poll::Ready(Err(SignalFishError::TransportSend(
+                    "binary frames cannot be sent over newline-delimited TCP".into(),
+                )))
+            }
+        };
+        if text.contains('\n') {
+            return Poll::Ready(Err(SignalFishError::TransportSend(
+                "text frame contains a newline".into(),
+            )));
+        }
+        if text.len() > self.max_line_length {
+            return Poll::Ready(Err(SignalFishError::MessageTooLarge {
+                limit: self.max_line_length,
+            }));
+        }
+        if let Some(TransportFrame::Text(text)) = frame.take() {
+            self.outbound = text.into_bytes();
+            self.outbound.push(b'\n');
+        }
+        self.poll_write_outbound(cx)
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        loop {
+            if let Some(line) = self.take_line() {
+                return Poll::Ready(Some(line));
+            }
+            if self.read_done {
+                return Poll::Ready(None);
+            }
+            // Room for a full line plus a `\r` still waiting for its `\n`.
+            if self.inbound.len() > self.max_line_length.saturating_add(1) {
+                return Poll::Ready(Some(Err(self.line_too_long())));
+            }
+            let Some(stream) = self.stream.as_mut() else {
+                self.read_done = true;
+                return Poll::Ready(None);
+            };
+            let mut chunk = [0; READ_CHUNK];
+            let mut buf = ReadBuf::new(&mut chunk);
+            match Pin::new(stream).poll_read(cx, &mut buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(error)) => {
+                    self.read_done = true;
+                    return Poll::Ready(Some(Err(SignalFishError::TransportReceive(
+                        error.to_string(),
+                    ))));
+                }
+                Poll::Ready(Ok(())) if buf.filled().is_empty() => {
+                    if !self.inbound.is_empty() {
+                        tracing::debug!(
+                            bytes = self.inbound.len(),
+                            "TCP stream ended inside a line; discarding it"
+                        );
+                        self.inbound = Vec::new();
+                    }
+                    self.read_done = true;
+                    self.close_info = Some(TransportCloseInfo {
+                        initiated_by_peer: !self.shut_down,
+                        ..TransportCloseInfo::default()
+                    });
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Ok(())) => {
+                    self.inbound.extend_from_slice(buf.filled());
+                    self.last_activity = Some(tokio::time::Instant::now().into_std());
+                }
+            }
+        }
+    }
+
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SignalFishError>> {
+        if self.shut_down {
+            return Poll::Ready(Ok(()));
+        }
+        if let Poll::Ready(Err(error)) = self.poll_write_outbound(cx) {
+            tracing::debug!(%error, "dropping a partly written frame to close");
+        } else if !self.outbound.is_empty() {
+            return Poll::Pending;
+        }
+        let Some(stream) = self.stream.as_mut() else {
+            self.shut_down = true;
+            return Poll::Ready(Ok(()));
+        };
+        match Pin::new(stream).poll_shutdown(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                self.shut_down = true;
+                match result {
+                    // The peer already reset the connection; nothing to shut.
+                    Err(error) if error.kind() != std::io::ErrorKind::NotConnected => {
+                        Poll::Ready(Err(SignalFishError::Io(error)))
+                    }
+                    _ => Poll::Ready(Ok(())),
+                }
+            }
+        }
+    }
+
+    fn abort(&mut self) {
+        self.stream = None;
+        self.outbound = Vec::new();
+        self.written = 0;
+        self.read_done = true;
+        self.shut_down = true;
+    }
+
+    fn close_info(&self) -> Option<TransportCloseInfo> {
+        self.close_info.clone()
+    }
+
+    fn last_activity_hint(&self) -> Option<std::time::Instant> {
+        self.last_activity
+    }
+
+    fn claim_session(&mut self) -> bool {
+        !std::mem::replace(&mut self.session_claimed, true)
+    }
+
+    fn diagnostics(&self) -> TransportDiagnostics {
+        let buffered = self.outbound.len().saturating_sub(self.written) as u64;
+        TransportDiagnostics {
+            current_buffered_bytes: buffered,
+            peak_buffered_bytes: buffered,
+            ..TransportDiagnostics::default()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::protocol::{ClientMessage, ServerMessage};
+    use crate::transport::{close_transport, recv_frame, send_frame};
+    use crate::{SignalFishClient, SignalFishConfig, SignalFishEvent};
+
+    async fn listener() -> (TcpListener, std::net::SocketAddr) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        (listener, addr)
+    }
+
+    fn text(frame: Option<Result<TransportFrame, SignalFishError>>) -> String {
+        match frame {
+            Some(Ok(TransportFrame::Text(text))) => text,
+            other => panic!("expected a text frame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tcp_transport_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<TcpTransport>();
+    }
+
+    #[tokio::test]
+    async fn a_transport_is_claimed_by_one_session() {
+        let (listener, addr) = listener().await;
+        let mut transport = TcpTransport::connect(addr).await.unwrap();
+        let _accepted = listener.accept().await.unwrap();
+        assert!(transport.claim_session());
+        assert!(!transport.claim_session());
+    }
+
+    #[tokio::test]
+    async fn protocol_messages_round_trip_across_segment_boundaries() {
+        let (listener, addr) = listener().await;
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let ping = lines.next_line().await.unwrap().unwrap();
+            assert!(matches!(
+                serde_json::from_str::<ClientMessage>(&ping).unwrap(),
+                ClientMessage::Ping
+            ));
+
+            let pong = serde_json::to_string(&ServerMessage::Pong).unwrap();
+            let (head, tail) = pong.split_at(5);
+            write.write_all(head.as_bytes()).await.unwrap();
+            write.flush().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            // The rest of the first line, a blank line, and a CRLF line in one
+            // segment.
+            let rest = format!("{tail}\n\n{pong}\r\n");
+            write.write_all(rest.as_bytes()).await.unwrap();
+
+            // The client's shutdown reads as end-of-stream.
+            assert_eq!(lines.next_line().await.unwrap(), None);
+        });
+
+        let mut transport = TcpTransport::connect(addr).await.unwrap();
+        let ping = serde_json::to_string(&ClientMessage::Ping).unwrap();
+        send_frame(&mut transport, TransportFrame::Text(ping))
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let line = text(recv_frame(&mut transport).await);
+            assert!(matches!(
+                serde_json::from_str::<ServerMessage>(&line).unwrap(),
+                ServerMessage::Pong
+            ));
+        }
+        assert!(transport.last_activity_hint().is_some());
+        close_transport(&mut transport).await.unwrap();
+        close_transport(&mut transport).await.unwrap();
+        server.await.unwrap();
+
+        // The server closed its end after the client's shutdown.
+        assert!(recv_frame(&mut transport).await.is_none());
+        assert!(!transport.close_info().unwrap().initiated_by_peer);
+    }
+
+    #[tokio::test]
+    async fn the_client_authenticates_over_tcp() {
+        let (listener, addr) = listener().await;
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let authenticate = lines.next_line().await.unwrap().unwrap();
+            assert!(matches!(
+                serde_json::from_str::<ClientMessage>(&authenticate).unwrap(),
+                ClientMessage::Authenticate { .. }
+            ));
+            let authenticated = serde_json::json!({
+                "type": "Authenticated",
+                "data": {
+                    "app_name": "tcp-test",
+                    "rate_limits": { "per_minute": 60, "per_hour": 600, "per_day": 6000 },
+                },
+            });
+            write
+                .write_all(format!("{authenticated}\n").as_bytes())
+                .await
+                .unwrap();
+            // Hold the connection open until the client leaves.
+            while lines.next_line().await.unwrap().is_some() {}
+        });
+
+        let transport = TcpTransport::connect(addr).await.unwrap();
+        let (mut client, mut events) =
+            SignalFishClient::start(transport, SignalFishConfig::new("mb_app_test"));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            SignalFishEvent::Connected
+        ));
+        let event = events.recv().await.unwrap();
+        assert!(
+            matches!(&event, SignalFishEvent::Authenticated { app_name, .. } if app_name == "tcp-test"),
+            "got {event:?}"
+        );
+        client.shutdown().await;
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn an_endless_line_is_too_large_and_ends_the_stream() {
+        let (listener, addr) = listener().await;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"{\"type\":\"Pong\"}\n").await.unwrap();
+            socket.write_all(&[b'x'; 64]).await.unwrap();
+            let mut rest = Vec::new();
+            socket.read_to_end(&mut rest).await.unwrap();
+        });
+
+        let mut transport = TcpTransport::connect(addr)
+            .await
+            .unwrap()
+            .with_max_line_length(16);
+        assert_eq!(text(recv_frame(&mut transport).await), r#"{"type":"Pong"}"#);
+        assert!(matches!(
+            recv_frame(&mut transport).await,
+            Some(Err(SignalFishError::MessageTooLarge { limit: 16 }))
+        ));
+        assert!(recv_frame(&mut transport).await.is_none());
+        close_transport(&mut transport).await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_long_line_arriving_whole_is_too_large() {
+        let (listener, addr) = listener().await;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"0123456789abcdef!\n").await.unwrap();
+        });
+
+        let mut transport = TcpTransport::connect(addr)
+            .await
+            .unwrap()
+            .with_max_line_length(16);
+        assert!(matches!(
+            recv_frame(&mut transport).await,
+            Some(Err(SignalFishError::MessageTooLarge { limit: 16 }))
+        ));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn frames_that_would_break_the_framing_are_refused_and_kept() {
+        let (listener, addr) = listener().await;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = String::new();
+            socket.read_to_string(&mut received).await.unwrap();
+            received
+        });
+
+        let mut transport = TcpTransport::connect(addr)
+            .await
+            .unwrap()
+            .with_max_line_length(16);
+        let refused = [
+            (TransportFrame::Binary(vec![1, 2, 3]), "binary"),
+            (TransportFrame::Text("a\nb".into()), "newline"),
+            (TransportFrame::Text("x".repeat(17)), "too long"),
+        ];
+        for (frame, why) in refused {
+            let mut slot = Some(frame);
+            let result = std::future::poll_fn(|cx| transport.poll_send(cx, &mut slot)).await;
+            assert!(result.is_err(), "{why} frame should be refused");
+            assert!(slot.is_some(), "{why} frame should stay with the caller");
+        }
+        send_frame(&mut transport, TransportFrame::Text("x".repeat(16)))
+            .await
+            .unwrap();
+        close_transport(&mut transport).await.unwrap();
+        assert!(matches!(
+            send_frame(&mut transport, TransportFrame::Text("late".into())).await,
+            Err(SignalFishError::TransportClosed)
+        ));
+
+        assert_eq!(server.await.unwrap(), format!("{}\n", "x".repeat(16)));
+    }
+}
@@ -0,0 +1,246 @@
+//! Browser WebSocket transport for `wasm32-unknown-unknown`.
+//!
+//! [`WasmWebSocketTransport`] drives the browser's own `WebSocket` through
+//! `web-sys`, for games built with `wasm-bindgen` or `wasm-pack`, where
+//! `tokio-tungstenite` cannot open TCP sockets.
+//!
+//! # Feature gate
+//!
+//! Available with the `transport-websocket-wasm` feature on
+//! `wasm32-unknown-unknown`. The gating is applied in `transports/mod.rs`; on
+//! other targets the feature compiles to nothing.
+//!
+//! # Driving the client
+//!
+//! Browsers have no Tokio runtime, so
+//! [`SignalFishClient::start`](crate::SignalFishClient::start) is unavailable.
+//! Drive the transport with
+//! [`SignalFishPollingClient`](crate::SignalFishPollingClient), calling
+//! [`poll`](crate::SignalFishPollingClient::poll) once per animation frame.
+//! Unlike the Emscripten transport, the socket callbacks wake the waker of a
+//! pending `poll_recv` or `poll_send`, so the transport also works under a
+//! wake-driven executor such as `wasm-bindgen-futures`.
+//!
+//! # Threading
+//!
+//! The socket callbacks run on the JavaScript event loop of the thread that
+//! created the socket, and share state with the transport through an
+//! `Rc<RefCell<_>>`. The transport is therefore not `Send`: create it and
+//! poll it on the same thread.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use signal_fish_client::{SignalFishConfig, SignalFishPollingClient, WasmWebSocketTransport};
+//!
+//! let transport = WasmWebSocketTransport::connect("wss://signal.example.com/ws")?;
+//! let mut client = SignalFishPollingClient::new(transport, SignalFishConfig::new("mb_app_abc123"));
+//! // Each requestAnimationFrame:
+//! for event in client.poll() {
+//!     // ...
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use super::wasm_websocket_state::{SocketEvent, SocketState};
+use crate::error::SignalFishError;
+use crate::transport::{poll_accept_frame, Transport, TransportCloseInfo, TransportFrame};
+
+/// A [`Transport`] backed by the browser's `WebSocket` API.
+///
+/// Created with [`connect`](Self::connect), which returns at once; the
+/// handshake completes on the browser's event loop. Frames handed to
+/// `poll_send` before then stay with the caller until the socket opens.
+/// Binary frames are received as `ArrayBuffer`s.
+///
+/// Dropping the transport closes the socket and detaches its callbacks.
+pub struct WasmWebSocketTransport {
+    socket: web_sys::WebSocket,
+    state: Rc<RefCell<SocketState>>,
+    // Held so the callbacks outlive the socket's references to them.
+    _on_open: Closure<dyn FnMut(web_sys::Event)>,
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    _on_error: Closure<dyn FnMut(web_sys::Event)>,
+    _on_close: Closure<dyn FnMut(web_sys::CloseEvent)>,
+}
+
+impl std::fmt::Debug for WasmWebSocketTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmWebSocketTransport")
+            .field("url", &self.socket.url())
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmWebSocketTransport {
+    /// Open a WebSocket to `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignalFishError::Io`] if the browser refuses the URL, for
+    /// example one that is not `ws://` or `wss://`, or a `ws://` URL from an
+    /// `https://` page. A server that cannot be reached is reported later, as
+    /// a receive error followed by the end of the stream.
+    pub fn connect(url: &str) -> Result<Self, SignalFishError> {
+        let socket = web_sys::WebSocket::new(url).map_err(|error| {
+            SignalFishError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("cannot open WebSocket to {url}: {}", describe(&error)),
+            ))
+        })?;
+        socket.set_binary_type(web_sys::BinaryType::Arraybuffer);
+        let state = Rc::new(RefCell::new(SocketState::default()));
+
+        let on_open = {
+            let state = Rc::clone(&state);
+            Closure::<dyn FnMut(web_sys::Event)>::new(move |_: web_sys::Event| {
+                deliver(&state, SocketEvent::Open);
+            })
+        };
+        let on_message = {
+            let state = Rc::clone(&state);
+            Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+                match message_frame(&event.data()) {
+                    Some(frame) => deliver(&state, SocketEvent::Message(frame)),
+                    None => tracing::warn!(
+                        "ignoring a WebSocket message that is neither text nor ArrayBuffer"
+                    ),
+                }
+            })
+        };
+        let on_error = {
+            let state = Rc::clone(&state);
+            Closure::<dyn FnMut(web_sys::Event)>::new(move |_: web_sys::Event| {
+                deliver(&state, SocketEvent::Error);
+            })
+        };
+        let on_close = {
+            let state = Rc::clone(&state);
+            Closure::<dyn FnMut(web_sys::CloseEvent)>::new(move |event: web_sys::CloseEvent| {
+                deliver(
+                    &state,
+                    SocketEvent::Close {
+                        code: event.code(),
+                        reason: event.reason(),
+                        was_clean: event.was_clean(),
+                    },
+                );
+            })
+        };
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            state,
+            _on_open: on_open,
+            _on_message: on_message,
+            _on_error: on_error,
+            _on_close: on_close,
+        })
+    }
+
+    /// Start the closing handshake once; later calls do nothing.
+    fn close_socket(&self) {
+        if self.state.borrow_mut().begin_close() {
+            if let Err(error) = self.socket.close_with_code(1000) {
+                tracing::warn!("closing the WebSocket failed: {}", describe(&error));
+            }
+        }
+    }
+}
+
+/// Record `event` and wake whoever waits on it, outside the borrow.
+fn deliver(state: &RefCell<SocketState>, event: SocketEvent) {
+    let wakers = state.borrow_mut().push(event);
+    for waker in wakers.into_iter().flatten() {
+        waker.wake();
+    }
+}
+
+/// A message event's payload as a frame: a string is text, an
+/// `ArrayBuffer` binary.
+fn message_frame(data: &JsValue) -> Option<TransportFrame> {
+    if let Some(text) = data.as_string() {
+        return Some(TransportFrame::Text(text));
+    }
+    data.dyn_ref::<js_sys::ArrayBuffer>()
+        .map(|buffer| TransportFrame::Binary(js_sys::Uint8Array::new(buffer).to_vec()))
+}
+
+/// Display text for a JavaScript exception.
+fn describe(error: &JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| format!("{error:?}"))
+}
+
+impl Transport for WasmWebSocketTransport {
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> Poll<Result<(), SignalFishError>> {
+        if frame.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        let writable = match self.state.borrow_mut().poll_writable(cx) {
+            Poll::Ready(Ok(())) => true,
+            Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+            Poll::Pending => false,
+        };
+        poll_accept_frame(writable, frame, |frame| {
+            let sent = match frame {
+                TransportFrame::Text(text) => self.socket.send_with_str(text),
+                TransportFrame::Binary(bytes) => self.socket.send_with_u8_array(bytes),
+            };
+            sent.map_err(|error| SignalFishError::TransportSend(describe(&error)))
+        })
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.state.borrow_mut().poll_recv(cx)
+    }
+
+    fn poll_close(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), SignalFishError>> {
+        self.close_socket();
+        Poll::Ready(Ok(()))
+    }
+
+    fn abort(&mut self) {
+        self.close_socket();
+    }
+
+    fn is_ready(&self) -> bool {
+        self.state.borrow().is_open()
+    }
+
+    fn close_info(&self) -> Option<TransportCloseInfo> {
+        self.state.borrow().close_info()
+    }
+}
+
+impl Drop for WasmWebSocketTransport {
+    fn drop(&mut self) {
+        self.close_socket();
+        // Detach the callbacks before their closures are freed.
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onerror(None);
+        self.socket.set_onclose(None);
+    }
+}
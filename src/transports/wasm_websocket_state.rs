@@ -0,0 +1,289 @@
+//! Browser WebSocket events translated into transport results.
+//!
+//! The browser reports a WebSocket's life through `onopen`, `onmessage`,
+//! `onerror`, and `onclose` callbacks that run on the JavaScript event loop.
+//! [`SocketState`] is what those callbacks share with
+//! [`WasmWebSocketTransport`](super::WasmWebSocketTransport): they push
+//! [`SocketEvent`]s into it, and the transport's `poll_*` methods read it back
+//! out. It holds no JavaScript values, so the translation is unit-tested on
+//! the host.
+
+use std::collections::VecDeque;
+use std::task::{Context, Poll, Waker};
+
+use crate::error::SignalFishError;
+use crate::transport::{TransportCloseInfo, TransportFrame};
+
+/// What a browser WebSocket callback reported.
+#[derive(Debug)]
+pub(crate) enum SocketEvent {
+    /// `onopen`: the handshake completed.
+    Open,
+    /// `onmessage` with a text or `ArrayBuffer` payload.
+    Message(TransportFrame),
+    /// `onerror`. The browser gives no detail and always follows it with
+    /// `onclose`.
+    Error,
+    /// `onclose`.
+    Close {
+        /// The close code; `1006` when the connection dropped without one.
+        code: u16,
+        /// The close reason; empty when none was sent.
+        reason: String,
+        /// Whether the closing handshake completed.
+        was_clean: bool,
+    },
+}
+
+/// Connection state shared by the socket callbacks and the transport.
+#[derive(Debug, Default)]
+pub(crate) struct SocketState {
+    opened: bool,
+    /// Frames and errors not yet returned by `poll_recv`, in arrival order.
+    incoming: VecDeque<Result<TransportFrame, SignalFishError>>,
+    /// An error or close arrived; `poll_recv` ends once `incoming` drains.
+    ended: bool,
+    /// The transport started the close, so a later `onclose` is not the
+    /// peer's.
+    closing: bool,
+    close_info: Option<TransportCloseInfo>,
+    recv_waker: Option<Waker>,
+    /// Woken when the socket opens or ends, for a send waiting on the
+    /// handshake.
+    send_waker: Option<Waker>,
+}
+
+impl SocketState {
+    /// Record `event` and return the wakers to wake once the state is no
+    /// longer borrowed.
+    pub(crate) fn push(&mut self, event: SocketEvent) -> [Option<Waker>; 2] {
+        match event {
+            SocketEvent::Open => {
+                self.opened = true;
+                return [self.send_waker.take(), None];
+            }
+            SocketEvent::Message(frame) => {
+                if self.ended {
+                    return [None, None];
+                }
+                self.incoming.push_back(Ok(frame));
+                return [self.recv_waker.take(), None];
+            }
+            SocketEvent::Error => {
+                if !self.ended && !self.closing {
+                    self.incoming
+                        .push_back(Err(SignalFishError::TransportReceive(
+                            "WebSocket error".into(),
+                        )));
+                }
+                self.ended = true;
+            }
+            SocketEvent::Close {
+                code,
+                reason,
+                was_clean,
+            } => {
+                self.ended = true;
+                self.close_info = Some(TransportCloseInfo {
+                    code: Some(code),
+                    reason: Some(reason).filter(|reason| !reason.is_empty()),
+                    clean: Some(was_clean),
+                    initiated_by_peer: !self.closing,
+                });
+            }
+        }
+        [self.recv_waker.take(), self.send_waker.take()]
+    }
+
+    /// Whether the handshake has completed.
+    pub(crate) fn is_open(&self) -> bool {
+        self.opened
+    }
+
+    /// `Ready(Ok)` once the socket is open and can take a frame,
+    /// `Ready(Err)` once it can no longer open or send.
+    pub(crate) fn poll_writable(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SignalFishError>> {
+        if self.ended || self.closing {
+            return Poll::Ready(Err(SignalFishError::TransportClosed));
+        }
+        if self.opened {
+            return Poll::Ready(Ok(()));
+        }
+        self.send_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// The next frame or error, `None` once the socket has ended and every
+    /// earlier frame was returned.
+    pub(crate) fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        if let Some(next) = self.incoming.pop_front() {
+            return Poll::Ready(Some(next));
+        }
+        if self.ended || self.closing {
+            return Poll::Ready(None);
+        }
+        self.recv_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Mark the close as the transport's own. Returns `false` if it was
+    /// already closing or ended, so the socket is closed only once.
+    pub(crate) fn begin_close(&mut self) -> bool {
+        let first = !self.closing && !self.ended;
+        self.closing = true;
+        first
+    }
+
+    /// How the socket closed, once `onclose` has fired.
+    pub(crate) fn close_info(&self) -> Option<TransportCloseInfo> {
+        self.close_info.clone()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn wake(wakers: [Option<Waker>; 2]) {
+        for waker in wakers.into_iter().flatten() {
+            waker.wake();
+        }
+    }
+
+    fn text(text: &str) -> SocketEvent {
+        SocketEvent::Message(TransportFrame::Text(text.into()))
+    }
+
+    #[test]
+    fn sends_wait_for_the_socket_to_open() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+        let mut state = SocketState::default();
+
+        assert!(state.poll_writable(&mut cx).is_pending());
+        wake(state.push(SocketEvent::Open));
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(state.is_open());
+        assert!(matches!(state.poll_writable(&mut cx), Poll::Ready(Ok(()))));
+    }
+
+    #[test]
+    fn messages_arrive_in_order_and_wake_the_receiver() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+        let mut state = SocketState::default();
+        state.push(SocketEvent::Open);
+
+        assert!(state.poll_recv(&mut cx).is_pending());
+        wake(state.push(text("first")));
+        wake(state.push(SocketEvent::Message(TransportFrame::Binary(vec![1, 2]))));
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        let Poll::Ready(Some(Ok(first))) = state.poll_recv(&mut cx) else {
+            panic!("expected the text frame");
+        };
+        assert_eq!(first, TransportFrame::Text("first".into()));
+        let Poll::Ready(Some(Ok(second))) = state.poll_recv(&mut cx) else {
+            panic!("expected the binary frame");
+        };
+        assert_eq!(second, TransportFrame::Binary(vec![1, 2]));
+        assert!(state.poll_recv(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn a_peer_close_ends_receiving_after_earlier_frames() {
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        let mut state = SocketState::default();
+        state.push(SocketEvent::Open);
+        state.push(text("last words"));
+        state.push(SocketEvent::Close {
+            code: 4001,
+            reason: "kicked".into(),
+            was_clean: true,
+        });
+
+        assert!(matches!(state.poll_recv(&mut cx), Poll::Ready(Some(Ok(_)))));
+        assert!(matches!(state.poll_recv(&mut cx), Poll::Ready(None)));
+        assert_eq!(
+            state.close_info(),
+            Some(TransportCloseInfo {
+                code: Some(4001),
+                reason: Some("kicked".into()),
+                clean: Some(true),
+                initiated_by_peer: true,
+            })
+        );
+        assert!(matches!(
+            state.poll_writable(&mut cx),
+            Poll::Ready(Err(SignalFishError::TransportClosed))
+        ));
+    }
+
+    #[test]
+    fn an_error_is_reported_once_before_the_close() {
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        let mut state = SocketState::default();
+
+        // A connection refused before opening: error, then an unclean close.
+        state.push(SocketEvent::Error);
+        state.push(SocketEvent::Close {
+            code: 1006,
+            reason: String::new(),
+            was_clean: false,
+        });
+
+        assert!(matches!(
+            state.poll_recv(&mut cx),
+            Poll::Ready(Some(Err(SignalFishError::TransportReceive(_))))
+        ));
+        assert!(matches!(state.poll_recv(&mut cx), Poll::Ready(None)));
+        let info = state.close_info().unwrap();
+        assert_eq!(info.code, Some(1006));
+        assert_eq!(info.reason, None, "an empty reason is no reason");
+        assert_eq!(info.clean, Some(false));
+    }
+
+    #[test]
+    fn a_close_the_transport_started_is_not_the_peers() {
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        let mut state = SocketState::default();
+        state.push(SocketEvent::Open);
+
+        assert!(state.begin_close());
+        assert!(!state.begin_close(), "the socket is closed only once");
+        assert!(matches!(state.poll_recv(&mut cx), Poll::Ready(None)));
+        state.push(SocketEvent::Close {
+            code: 1000,
+            reason: String::new(),
+            was_clean: true,
+        });
+
+        assert!(!state.close_info().unwrap().initiated_by_peer);
+    }
+}
@@ -6,6 +6,14 @@
 //! the ring provider and bundled webpki roots), after which TLS is handled
 //! transparently via [`MaybeTlsStream`](tokio_tungstenite::MaybeTlsStream).
 //!
+//! | Features | `ws://` | `wss://` |
+//! |---|---|---|
+//! | `transport-websocket` (default) | Yes | Refused with [`SignalFishError::Io`] ([`ErrorKind::Unsupported`](std::io::ErrorKind::Unsupported)) before dialing |
+//! | `tls` or its alias `transport-websocket-rustls` | Yes | rustls, trusting the bundled webpki roots; [`connect_tls_with`](WebSocketTransport::connect_tls_with) or a [`WebSocketConnector`](super::WebSocketConnector) take custom roots or client certificates |
+//!
+//! A failed TLS handshake, such as an untrusted certificate, is reported as
+//! [`SignalFishError::TlsHandshake`] rather than a connect error.
+//!
 //! Connections disable Nagle's algorithm (`TCP_NODELAY`) by default for low
 //! latency; see [`WebSocketConnectOptions`] to override.
 //!
@@ -118,8 +126,10 @@ impl WebSocketConnectOptions {
 /// # }
 /// ```
 ///
-/// For advanced use-cases (custom TLS, proxy, headers) construct the stream
-/// yourself and use [`WebSocketTransport::from_stream`].
+/// For handshake headers or query parameters use
+/// [`WebSocketTransport::builder`]. For other advanced setups (a proxy, a
+/// custom stream) construct the stream yourself and use
+/// [`WebSocketTransport::from_stream`].
 ///
 /// # Polling Safety
 ///
@@ -133,24 +143,37 @@ pub struct WebSocketTransport {
     send_started: bool,
     control_flush_pending: bool,
     peer_close_pending: bool,
+    /// When the last frame of any kind, pongs included, arrived.
+    last_activity: Option<std::time::Instant>,
+    /// A client session has claimed the transport.
+    session_claimed: bool,
 }
 
 impl WebSocketTransport {
     /// Establish a new WebSocket connection to the given URL.
     ///
     /// `ws://` is always supported. `wss://` requires the optional `tls` feature;
-    /// without it a `wss://` URL fails with [`SignalFishError::Io`].
+    /// without it a `wss://` URL fails with [`SignalFishError::Io`] of kind
+    /// [`Unsupported`](std::io::ErrorKind::Unsupported) before anything is
+    /// dialed.
     ///
     /// Nagle's algorithm is **disabled by default** (`TCP_NODELAY`) so small,
     /// latency-sensitive game messages are sent without delay. Use
     /// [`connect_with_options`](Self::connect_with_options) to override that.
     ///
+    /// Every call goes through the process-wide
+    /// [`WebSocketConnector::shared`](super::WebSocketConnector::shared), so
+    /// repeated connects reuse its cached DNS answers and (with `tls`) its TLS
+    /// sessions.
+    ///
     /// # Errors
     ///
-    /// Returns [`SignalFishError::Io`] if the URL is invalid or the connection
-    /// cannot be established. When the underlying error is an I/O error its
-    /// [`ErrorKind`](std::io::ErrorKind) is preserved; all other errors are
-    /// mapped to [`ErrorKind::Other`](std::io::ErrorKind::Other).
+    /// Returns [`SignalFishError::TlsHandshake`] if the TLS handshake of a
+    /// `wss://` URL fails, and [`SignalFishError::Io`] if the URL is invalid
+    /// or the connection cannot be established otherwise. When the underlying
+    /// error is an I/O error its [`ErrorKind`](std::io::ErrorKind) is
+    /// preserved; all other errors are mapped to
+    /// [`ErrorKind::Other`](std::io::ErrorKind::Other).
     pub async fn connect(url: &str) -> Result<Self, SignalFishError> {
         Self::connect_with_options(url, WebSocketConnectOptions::default()).await
     }
@@ -163,57 +186,167 @@ impl WebSocketTransport {
     /// (`TCP_NODELAY`). The option is applied to the raw socket before any TLS
     /// handshake, so it covers both `ws://` and `wss://`.
     ///
+    /// `wss://` here trusts the bundled webpki roots. To trust the platform
+    /// certificate store or a private CA, connect through a
+    /// [`WebSocketConnector`](super::WebSocketConnector) built with
+    /// `with_tls_roots` (feature `tls`), or use
+    /// [`connect_tls_with`](Self::connect_tls_with).
+    ///
     /// # Errors
     ///
-    /// Returns [`SignalFishError::Io`] if the URL is invalid or the connection
-    /// cannot be established. When the underlying error is an I/O error its
-    /// [`ErrorKind`](std::io::ErrorKind) is preserved; all other errors are
-    /// mapped to [`ErrorKind::Other`](std::io::ErrorKind::Other).
+    /// Same as [`connect`](Self::connect).
     pub async fn connect_with_options(
         url: &str,
         options: WebSocketConnectOptions,
     ) -> Result<Self, SignalFishError> {
-        #[cfg(feature = "tls")]
-        {
-            // Ensure a rustls crypto provider is installed process-wide so the
-            // wss:// path below cannot panic when the dependency graph enables
-            // both `ring` and `aws_lc_rs` (rustls' auto-detection panics on that
-            // ambiguity). Idempotent and first-wins: yields to any provider the
-            // application already installed.
-            use std::sync::Once;
-            static INSTALL: Once = Once::new();
-            INSTALL.call_once(|| {
-                let _ = rustls::crypto::ring::default_provider().install_default();
-            });
-        }
-
         tracing::debug!(
             url = %url,
             disable_nagle = options.disable_nagle,
             "connecting to WebSocket server"
         );
+        let connector = super::WebSocketConnector::shared().reusing(options);
+        let stream = connector.open(url).await?;
+        tracing::info!(url = %url, "WebSocket connection established");
+        Ok(Self::from_stream(stream))
+    }
 
-        let (stream, _response) =
-            tokio_tungstenite::connect_async_with_config(url, None, options.disable_nagle)
-                .await
-                .map_err(|e| {
-                    let kind = match &e {
-                        tokio_tungstenite::tungstenite::Error::Io(io) => io.kind(),
-                        _ => std::io::ErrorKind::Other,
-                    };
-                    SignalFishError::Io(std::io::Error::new(kind, e))
-                })?;
+    /// Establish a `wss://` connection whose TLS handshake uses `config`.
+    ///
+    /// For a private CA, a pinned root store, or a client certificate the
+    /// server demands, build a rustls `ClientConfig` and pass it here:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     roots: rustls::RootCertStore,
+    /// #     chain: Vec<rustls::pki_types::CertificateDer<'static>>,
+    /// #     key: rustls::pki_types::PrivateKeyDer<'static>,
+    /// # ) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::sync::Arc;
+    /// use signal_fish_client::WebSocketTransport;
+    ///
+    /// let config = rustls::ClientConfig::builder()
+    ///     .with_root_certificates(roots)
+    ///     .with_client_auth_cert(chain, key)?;
+    /// let transport =
+    ///     WebSocketTransport::connect_tls_with("wss://example.com/ws", Arc::new(config)).await?;
+    /// # let _ = transport;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The default [`WebSocketConnectOptions`] apply. To share `config`, and
+    /// its TLS sessions, across many connections, hand it to a
+    /// [`WebSocketConnector`](super::WebSocketConnector) with
+    /// `with_tls_config` instead.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls_with(
+        url: &str,
+        config: std::sync::Arc<rustls::ClientConfig>,
+    ) -> Result<Self, SignalFishError> {
+        tracing::debug!(url = %url, "connecting to WebSocket server with a custom TLS config");
+        let connector = super::WebSocketConnector::shared()
+            .reusing(WebSocketConnectOptions::default())
+            .with_tls_config(config);
+        let stream = connector.open(url).await?;
+        tracing::info!(url = %url, "WebSocket connection established");
+        Ok(Self::from_stream(stream))
+    }
 
+    /// Establish a new WebSocket connection through a shared
+    /// [`WebSocketConnector`](super::WebSocketConnector).
+    ///
+    /// The connector supplies the socket options, a cached DNS answer when one
+    /// is fresh, and (with the `tls` feature) its shared rustls configuration,
+    /// so many connections from one process reuse lookups and TLS sessions.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with(
+        connector: &super::WebSocketConnector,
+        url: &str,
+    ) -> Result<Self, SignalFishError> {
+        tracing::debug!(url = %url, "connecting to WebSocket server via shared connector");
+        let stream = connector.open(url).await?;
         tracing::info!(url = %url, "WebSocket connection established");
+        Ok(Self::from_stream(stream))
+    }
 
-        Ok(Self {
-            stream: Some(stream),
-            closed: false,
-            close_info: None,
-            send_started: false,
-            control_flush_pending: false,
-            peer_close_pending: false,
-        })
+    /// Establish a new WebSocket connection, reporting each setup step to
+    /// `on_progress` as it completes.
+    ///
+    /// Intended for launcher and lobby UIs that show "resolving…",
+    /// "connecting…", "TLS handshake…" while the connection comes up. Steps are
+    /// reported in order as [`ConnectProgress`](super::ConnectProgress) values;
+    /// a failure reports [`ConnectProgress::Failed`](super::ConnectProgress::Failed)
+    /// with the [`ConnectStage`](super::ConnectStage) that failed before the
+    /// error is returned. The callback runs inline on the connecting task, so
+    /// keep it cheap (forward into a channel for anything heavier).
+    ///
+    /// Goes through the process-wide
+    /// [`WebSocketConnector::shared`](super::WebSocketConnector::shared), as
+    /// [`connect`](Self::connect) does. To report progress while connecting
+    /// through a connector of your own, use
+    /// [`connect_with_connector_progress`](Self::connect_with_connector_progress).
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), signal_fish_client::SignalFishError> {
+    /// use signal_fish_client::{ConnectProgress, WebSocketTransport};
+    ///
+    /// let transport = WebSocketTransport::connect_with_progress(
+    ///     "ws://localhost:3536/ws",
+    ///     |step| match step {
+    ///         ConnectProgress::DnsResolved(_) => println!("connecting…"),
+    ///         ConnectProgress::TcpConnected(_) => println!("handshaking…"),
+    ///         other => println!("{other:?}"),
+    ///     },
+    /// )
+    /// .await?;
+    /// # let _ = transport;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with_progress(
+        url: &str,
+        on_progress: impl FnMut(super::ConnectProgress) + Send,
+    ) -> Result<Self, SignalFishError> {
+        let connector = super::WebSocketConnector::shared();
+        Self::connect_with_connector_progress(&connector, url, on_progress).await
+    }
+
+    /// [`connect_with`](Self::connect_with), reporting each setup step to
+    /// `on_progress` as [`connect_with_progress`](Self::connect_with_progress)
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with_connector_progress(
+        connector: &super::WebSocketConnector,
+        url: &str,
+        mut on_progress: impl FnMut(super::ConnectProgress) + Send,
+    ) -> Result<Self, SignalFishError> {
+        tracing::debug!(url = %url, "connecting to WebSocket server with progress reporting");
+        let stream = connector.open_with_progress(url, &mut on_progress).await?;
+        tracing::info!(url = %url, "WebSocket connection established");
+        Ok(Self::from_stream(stream))
+    }
+
+    /// Start building a connection to `url` whose handshake carries extra
+    /// headers or query parameters, such as an `Authorization` token.
+    ///
+    /// See [`WebSocketTransportBuilder`](super::WebSocketTransportBuilder).
+    #[must_use]
+    pub fn builder(url: impl Into<String>) -> super::WebSocketTransportBuilder {
+        super::WebSocketTransportBuilder::new(url)
     }
 
     /// Create a [`WebSocketTransport`] from an already-established WebSocket stream.
@@ -232,33 +365,64 @@ impl WebSocketTransport {
             send_started: false,
             control_flush_pending: false,
             peer_close_pending: false,
+            last_activity: None,
+            session_claimed: false,
         }
     }
 
     /// Establish a new WebSocket connection with a timeout.
     ///
-    /// Behaves identically to [`connect`](Self::connect) but fails with
-    /// [`SignalFishError::Timeout`] if the connection is not established within
-    /// the given duration.
+    /// Behaves identically to [`connect`](Self::connect) but gives up with
+    /// [`SignalFishError::ConnectTimeout`] if the connection is not
+    /// established within the given duration, instead of waiting out the
+    /// operating system's TCP timeout for an unreachable host.
     ///
-    /// To pair a timeout with custom [`WebSocketConnectOptions`], wrap
-    /// [`connect_with_options`](Self::connect_with_options), e.g.
-    /// `tokio::time::timeout(dur, WebSocketTransport::connect_with_options(url, opts))`.
+    /// To pair a timeout with retries, headers, or custom
+    /// [`WebSocketConnectOptions`], use [`builder`](Self::builder) and
+    /// [`with_connect_timeout`](super::WebSocketTransportBuilder::with_connect_timeout).
     ///
     /// # Errors
     ///
-    /// Returns [`SignalFishError::Timeout`] if the deadline elapses, or any
-    /// error that [`connect`](Self::connect) may return.
+    /// Returns [`SignalFishError::ConnectTimeout`] if the deadline elapses,
+    /// or any error that [`connect`](Self::connect) may return.
     pub async fn connect_with_timeout(
         url: &str,
         timeout: std::time::Duration,
     ) -> Result<Self, SignalFishError> {
-        tokio::time::timeout(timeout, Self::connect(url))
-            .await
-            .map_err(|_| SignalFishError::Timeout)?
+        tracing::debug!(url = %url, ?timeout, "connecting to WebSocket server with a timeout");
+        let stream = super::WebSocketConnector::shared()
+            .open_within(url, timeout)
+            .await?;
+        tracing::info!(url = %url, "WebSocket connection established");
+        Ok(Self::from_stream(stream))
     }
 }
 
+/// Ensure a rustls crypto provider is installed process-wide so the wss://
+/// path cannot panic when the dependency graph enables both `ring` and
+/// `aws_lc_rs` (rustls' auto-detection panics on that ambiguity). Idempotent
+/// and first-wins: yields to any provider the application already installed.
+#[cfg(feature = "tls")]
+pub(crate) fn ensure_crypto_provider() {
+    use std::sync::Once;
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    });
+}
+
+/// Map a tungstenite error to `io::Error`, preserving the
+/// [`ErrorKind`](std::io::ErrorKind) of underlying I/O failures.
+pub(crate) fn websocket_error_to_io(
+    error: tokio_tungstenite::tungstenite::Error,
+) -> std::io::Error {
+    let kind = match &error {
+        tokio_tungstenite::tungstenite::Error::Io(io) => io.kind(),
+        _ => std::io::ErrorKind::Other,
+    };
+    std::io::Error::new(kind, error)
+}
+
 impl Transport for WebSocketTransport {
     fn poll_send(
         &mut self,
@@ -350,6 +514,7 @@ impl Transport for WebSocketTransport {
                     None => return Poll::Ready(None),
                 },
             };
+            self.last_activity = Some(tokio::time::Instant::now().into_std());
 
             match msg {
                 // `Utf8Bytes::to_string()` copies the payload into a new `String`
@@ -389,8 +554,8 @@ impl Transport for WebSocketTransport {
                     self.control_flush_pending = true;
                 }
                 Message::Pong(_) => {
-                    tracing::debug!("received WebSocket pong (ignored)");
-                    // Continue the loop.
+                    tracing::debug!("received WebSocket pong");
+                    // Recorded as activity above; continue the loop.
                 }
                 Message::Frame(_) => {
                     // This variant is never produced by the read half of the stream;
@@ -445,6 +610,14 @@ impl Transport for WebSocketTransport {
         self.close_info.clone()
     }
 
+    fn last_activity_hint(&self) -> Option<std::time::Instant> {
+        self.last_activity
+    }
+
+    fn claim_session(&mut self) -> bool {
+        !std::mem::replace(&mut self.session_claimed, true)
+    }
+
     fn abort(&mut self) {
         self.stream = None;
         self.closed = true;
@@ -454,6 +627,67 @@ impl Transport for WebSocketTransport {
     }
 }
 
+/// [`TransportFactory`](crate::TransportFactory) that dials a fresh
+/// [`WebSocketTransport`] to one URL, for
+/// [`SignalFishClient::start_with_reconnect`](crate::SignalFishClient::start_with_reconnect).
+///
+/// Every dial goes through one shared
+/// [`WebSocketConnector`](super::WebSocketConnector), so redials reuse its
+/// cached DNS answer and (with `tls`) its TLS sessions.
+///
+/// ```rust,no_run
+/// # async fn example() {
+/// use signal_fish_client::{SignalFishClient, SignalFishConfig, WebSocketDialer};
+///
+/// let (client, events) = SignalFishClient::start_with_reconnect(
+///     WebSocketDialer::new("ws://localhost:3536/ws"),
+///     SignalFishConfig::new("mb_app_abc123"),
+/// );
+/// # drop((client, events));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebSocketDialer {
+    url: String,
+    connector: std::sync::Arc<super::WebSocketConnector>,
+}
+
+impl WebSocketDialer {
+    /// Dial `url` through the process-wide
+    /// [`WebSocketConnector::shared`](super::WebSocketConnector::shared).
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_connector(url, super::WebSocketConnector::shared())
+    }
+
+    /// Dial `url` through `connector`, which other dialers and transports
+    /// may share.
+    #[must_use]
+    pub fn with_connector(
+        url: impl Into<String>,
+        connector: std::sync::Arc<super::WebSocketConnector>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            connector,
+        }
+    }
+
+    /// The URL each dial connects to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl crate::TransportFactory for WebSocketDialer {
+    type Transport = WebSocketTransport;
+
+    fn connect(&mut self) -> crate::ConnectFuture<'_, WebSocketTransport> {
+        Box::pin(WebSocketTransport::connect_with(&self.connector, &self.url))
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "transport-websocket")]
 #[allow(
@@ -547,6 +781,33 @@ mod tests {
 
     // ── Mock-stream tests ────────────────────────────────────────────────
 
+    #[tokio::test]
+    async fn dialer_opens_a_new_connection_per_call() {
+        use crate::TransportFactory;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                ws.send(Message::Text("hello".into())).await.unwrap();
+            }
+        });
+
+        let mut dialer = WebSocketDialer::new(format!("ws://{addr}"));
+        assert_eq!(dialer.url(), format!("ws://{addr}"));
+        for _ in 0..2 {
+            let mut transport = dialer.connect().await.unwrap();
+            let frame = std::future::poll_fn(|cx| transport.poll_recv(cx)).await;
+            assert_eq!(
+                frame.unwrap().unwrap(),
+                TransportFrame::Text("hello".into())
+            );
+        }
+        accepted.await.unwrap();
+    }
+
     #[tokio::test]
     async fn connect_disables_nagle_by_default() {
         let url = start_mock_server(|mut ws| async move {
@@ -609,12 +870,50 @@ mod tests {
 
         let result = WebSocketTransport::connect(&format!("wss://{addr}")).await;
         assert!(
-            result.is_err(),
-            "wss:// to a non-TLS peer must fail via a TLS/IO error (proving the provider is wired), \
-             not succeed"
+            matches!(result, Err(SignalFishError::TlsHandshake(_))),
+            "wss:// to a non-TLS peer must fail the TLS handshake (proving the provider is wired), \
+             got {result:?}"
         );
     }
 
+    #[cfg(not(feature = "tls"))]
+    #[tokio::test]
+    async fn wss_without_tls_is_refused_before_dialing() {
+        // Nothing listens here; the refusal must come from the scheme alone.
+        let result = WebSocketTransport::connect("wss://127.0.0.1:1/ws").await;
+
+        let Err(SignalFishError::Io(error)) = result else {
+            panic!("expected an I/O error, got {result:?}");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+        assert!(error.to_string().contains("`tls`"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn received_pongs_are_reported_as_activity() {
+        let url = start_mock_server(|mut ws| async move {
+            ws.send(Message::Pong(Vec::new().into()))
+                .await
+                .expect("server must send a pong");
+            while let Some(Ok(_)) = ws.next().await {}
+        })
+        .await;
+
+        let mut transport = WebSocketTransport::connect(&url)
+            .await
+            .expect("WebSocket connect must succeed");
+        assert_eq!(transport.last_activity_hint(), None);
+
+        // The pong never surfaces as a frame, but it is seen while polling.
+        let recv = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            crate::transport::recv_frame(&mut transport),
+        )
+        .await;
+        assert!(recv.is_err(), "a pong is not delivered as a frame");
+        assert!(transport.last_activity_hint().is_some());
+    }
+
     #[tokio::test]
     async fn recv_receives_text_messages() {
         let url = start_mock_server(|mut ws| async move {
@@ -834,17 +1133,76 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn builder_headers_and_query_reach_the_server() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("TcpListener must bind to localhost");
+        let addr = listener
+            .local_addr()
+            .expect("TcpListener must have a local address");
+        let (seen_tx, seen_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (tcp, _) = listener
+                .accept()
+                .await
+                .expect("TcpListener must accept a connection");
+            // tungstenite's handshake callback fixes the error type.
+            #[allow(clippy::result_large_err)]
+            let record = |request: &tokio_tungstenite::tungstenite::handshake::server::Request,
+                          response| {
+                let _ = seen_tx.send((
+                    request.uri().to_string(),
+                    request.headers().get("authorization").cloned(),
+                ));
+                Ok(response)
+            };
+            let _ws = tokio_tungstenite::accept_hdr_async(tcp, record)
+                .await
+                .expect("WebSocket handshake must succeed");
+        });
+
+        let _transport = WebSocketTransport::builder(format!("ws://{addr}/ws"))
+            .with_header("Authorization", "Bearer token")
+            .with_query_param("tenant", "acme")
+            .connect()
+            .await
+            .expect("builder connect must succeed");
+
+        let (uri, authorization) = seen_rx.await.expect("server must see the handshake");
+        assert_eq!(uri, "/ws?tenant=acme");
+        assert_eq!(authorization.unwrap(), "Bearer token");
+    }
+
     #[tokio::test]
     async fn connect_with_timeout_times_out() {
-        // Use a non-routable address to guarantee a timeout.
-        let result = WebSocketTransport::connect_with_timeout(
-            "ws://192.0.2.1:1",
-            std::time::Duration::from_millis(50),
-        )
-        .await;
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("TcpListener must bind to localhost");
+        let url = format!(
+            "ws://{}/ws",
+            listener
+                .local_addr()
+                .expect("listener must have an address")
+        );
+        // Accept, then never answer the upgrade.
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((tcp, _)) = listener.accept().await {
+                held.push(tcp);
+            }
+        });
+
+        let result =
+            WebSocketTransport::connect_with_timeout(&url, std::time::Duration::from_millis(50))
+                .await;
 
         let err = result.unwrap_err();
-        assert!(matches!(err, SignalFishError::Timeout));
+        assert!(
+            matches!(err, SignalFishError::ConnectTimeout { elapsed }
+                if elapsed >= std::time::Duration::from_millis(50)),
+            "{err:?}"
+        );
     }
 
     #[tokio::test]
@@ -993,7 +993,7 @@ mod tests {
             relay_type: "auto".into(),
             current_spectators: vec![],
             ice_servers: vec![],
-            missed_events: missed,
+            missed_events: missed.into_iter().map(Into::into).collect(),
             replay: None,
             sender_watermarks: vec![],
             reconnection_token: None,
@@ -0,0 +1,19 @@
+//! Compile-time guarantees of the public API, checked with trybuild.
+//!
+//! Each `tests/ui/exhaustive_*.rs` matches every variant of a
+//! `#[non_exhaustive]` enum without a wildcard arm. Its `.stderr` snapshot
+//! pins that the only complaint is the missing `_`, so a variant added
+//! without updating the case fails here too. Each `tests/ui/wildcard_*.rs`
+//! must compile. After adding a variant, list it in the matching case and
+//! regenerate the snapshots:
+//!
+//! ```text
+//! TRYBUILD=overwrite cargo test --test api_stability_tests
+//! ```
+
+#[test]
+fn non_exhaustive_enums_need_a_wildcard_arm() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/exhaustive_*.rs");
+    cases.pass("tests/ui/wildcard_*.rs");
+}
@@ -1320,6 +1320,7 @@ mod ci_workflow_policy {
         "spell-check",
         "clippy",
         "test",
+        "protocol-only",
         "msrv",
         "doc",
         "deny",
@@ -3005,6 +3006,66 @@ mod dependency_policy {
         }
     }
 
+    /// Without `client` the crate is protocol types only, so tokio must stay
+    /// optional and every feature that builds a client must enable `client`.
+    #[test]
+    fn async_dependencies_are_reachable_only_through_client() {
+        let parsed = cargo_toml();
+        let tokio = parsed
+            .get("dependencies")
+            .and_then(|deps| deps.get("tokio"))
+            .expect("Cargo.toml must define [dependencies].tokio");
+        assert_eq!(
+            tokio.get("optional").and_then(toml::Value::as_bool),
+            Some(true),
+            "[dependencies].tokio must be optional so the protocol builds without it."
+        );
+
+        let features = parsed
+            .get("features")
+            .and_then(toml::Value::as_table)
+            .expect("Cargo.toml must define [features]");
+        assert!(
+            features.contains_key("client"),
+            "Cargo.toml must define the `client` feature."
+        );
+        let enables = |feature: &str| -> Vec<String> {
+            features
+                .get(feature)
+                .and_then(toml::Value::as_array)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(toml::Value::as_str)
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        for feature in ["tokio-runtime", "polling-client"] {
+            assert!(
+                enables(feature).iter().any(|value| value == "client"),
+                "the `{feature}` feature builds a client and must enable `client`."
+            );
+        }
+        for (feature, values) in features {
+            let Some(values) = values.as_array() else {
+                continue;
+            };
+            for value in values.iter().filter_map(toml::Value::as_str) {
+                if value == "dep:tokio" || value.starts_with("tokio/") {
+                    assert!(
+                        enables(feature).iter().any(|value| value == "client")
+                            || enables(feature)
+                                .iter()
+                                .any(|value| value == "tokio-runtime"),
+                        "`{feature}` enables `{value}` without `client`."
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn uuid_dependency_enables_v4_and_serde_features() {
         let parsed = cargo_toml();
@@ -5310,7 +5371,10 @@ mod panic_script_cfg_handling {
                                 .to_string();
                             for line in content.lines() {
                                 let trimmed = line.trim();
-                                if trimmed.starts_with("#[cfg(") && trimmed.contains("test") {
+                                // `test` inside a string (`feature = "testing"`)
+                                // is not the `test` cfg predicate.
+                                let unquoted: String = trimmed.split('"').step_by(2).collect();
+                                if trimmed.starts_with("#[cfg(") && unquoted.contains("test") {
                                     out.push((relative.clone(), trimmed.to_string()));
                                 }
                             }
@@ -18,26 +18,33 @@ mod common;
 use std::collections::VecDeque;
 
 use signal_fish_client::protocol::{
-    ClientMessage, ConnectionInfo, GameDataEncoding, RelayTransport, ServerMessage, TransportKind,
+    ClientMessage, ConnectionInfo, GameDataEncoding, RateLimitInfo, RelayTransport, ServerLimits,
+    ServerMessage, TransportKind,
 };
+use signal_fish_client::testing::{SentLog, SlowConsumer};
 use signal_fish_client::transport::TransportFrame;
 use signal_fish_client::{
-    ErrorCode, JoinRoomParams, PeerSignal, SignalFishClient, SignalFishConfig, SignalFishError,
-    SignalFishEvent, Transport,
+    ClientMessageKind, DecodeErrorKind, DecodeFailureHook, DisconnectReason, ErrorCode,
+    HealthThresholds, HealthVerdict, JoinRoomParams, MemorySessionStore, OutOfRangeAction,
+    PeerSignal, PromotionError, ReconnectPolicy, ResumePolicy, SdkVersion, SdkVersionPolicy,
+    SelfRemovalPolicy, SessionInconsistencyPolicy, SessionState, SessionStore, SessionTicket,
+    SessionTrigger, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent,
+    StateTransition, Transport, SESSION_TICKET_KEY,
 };
 
 type StartedClient = (
     SignalFishClient,
     tokio::sync::mpsc::Receiver<SignalFishEvent>,
-    std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    SentLog,
     std::sync::Arc<std::sync::atomic::AtomicBool>,
 );
 
 use common::{
-    authenticated_json, authority_response_json, error_json, game_data_json, new_peer_json,
-    peer_transport_status_json, player_left_json, pong_json, protocol_info_json, reconnected_json,
-    reconnected_with_protocol_info_json, room_joined_json, room_left_json, session_plan_json,
-    signal_json, spectator_joined_json, spectator_left_json, wait_for_sent_len, MockTransport,
+    announcement_json, authenticated_json, authority_response_json, error_json, game_data_json,
+    new_peer_json, peer_transport_status_json, player_left_json, pong_json, protocol_info_json,
+    protocol_info_payload, reconnected_json, reconnected_with_protocol_info_json, room_joined_json,
+    room_joined_json_with, room_left_json, session_plan_json, signal_json, spectator_joined_json,
+    spectator_left_json, suite_config, time_sync_response_json, wait_for_sent_len, MockTransport,
 };
 
 // ════════════════════════════════════════════════════════════════════
@@ -56,7 +63,7 @@ fn start_client_with_config(
     config: SignalFishConfig,
 ) -> StartedClient {
     let (transport, sent, closed) = MockTransport::new(incoming);
-    let (client, events) = SignalFishClient::start(transport, config);
+    let (client, events) = SignalFishClient::start(transport, suite_config(config));
     (client, events, sent, closed)
 }
 
@@ -75,6 +82,14 @@ async fn drain_until_authenticated(rx: &mut tokio::sync::mpsc::Receiver<SignalFi
     );
 }
 
+/// [`SentLog::matching`] predicate for outgoing `kind` messages.
+fn of_type(kind: &'static str) -> impl Fn(&str) -> bool {
+    move |frame| {
+        serde_json::from_str::<serde_json::Value>(frame)
+            .is_ok_and(|message| message["type"] == kind)
+    }
+}
+
 fn v3_room_baseline_json(peer: uuid::Uuid) -> String {
     let message =
         ServerMessage::RoomJoined(Box::new(signal_fish_client::protocol::RoomJoinedPayload {
@@ -147,6 +162,109 @@ impl Transport for HangingCloseTransport {
     }
 }
 
+/// Transport that accepts `successes` sends, then fails every later one.
+struct SendFailsAfterTransport {
+    successes: usize,
+    incoming: VecDeque<Option<Result<String, SignalFishError>>>,
+}
+
+impl SendFailsAfterTransport {
+    fn new(successes: usize, incoming: Vec<Option<Result<String, SignalFishError>>>) -> Self {
+        Self {
+            successes,
+            incoming: VecDeque::from(incoming),
+        }
+    }
+}
+
+impl Transport for SendFailsAfterTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        frame.take();
+        if self.successes == 0 {
+            return std::task::Poll::Ready(Err(SignalFishError::TransportSend(
+                "write failed".into(),
+            )));
+        }
+        self.successes -= 1;
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        if let Some(item) = self.incoming.pop_front() {
+            std::task::Poll::Ready(item.map(|result| result.map(TransportFrame::Text)))
+        } else {
+            std::task::Poll::Pending
+        }
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+type Feed = tokio::sync::mpsc::UnboundedSender<Result<String, SignalFishError>>;
+
+/// Transport fed from a channel, so a test decides when each frame arrives.
+/// Dropping the sender closes the transport.
+struct FeedTransport {
+    feed: tokio::sync::mpsc::UnboundedReceiver<Result<String, SignalFishError>>,
+    sent: SentLog,
+}
+
+impl FeedTransport {
+    fn new() -> (Self, Feed, SentLog) {
+        let (tx, feed) = tokio::sync::mpsc::unbounded_channel();
+        let sent = SentLog::new();
+        (
+            Self {
+                feed,
+                sent: sent.clone(),
+            },
+            tx,
+            sent,
+        )
+    }
+}
+
+impl Transport for FeedTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if let Some(TransportFrame::Text(message)) = frame.take() {
+            self.sent.record(message);
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.feed
+            .poll_recv(cx)
+            .map(|item| item.map(|result| result.map(TransportFrame::Text)))
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
 // ════════════════════════════════════════════════════════════════════
 // Auth flow lifecycle
 // ════════════════════════════════════════════════════════════════════
@@ -179,8 +297,8 @@ async fn auth_flow_connected_then_authenticated() {
 
     // Verify the Authenticate message was sent.
     {
-        let messages = sent.lock().unwrap();
-        assert!(!messages.is_empty());
+        let messages = sent.matching(of_type("Authenticate"));
+        assert_eq!(messages.len(), 1);
         let first: ClientMessage = serde_json::from_str(&messages[0]).expect("parse auth message");
         if let ClientMessage::Authenticate { app_id, .. } = first {
             assert_eq!(app_id, "mb_test_integration");
@@ -192,6 +310,88 @@ async fn auth_flow_connected_then_authenticated() {
     client.shutdown().await;
 }
 
+#[tokio::test]
+async fn wait_authenticated_resolves_when_the_server_confirms() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+
+    let (info, ()) = tokio::join!(
+        client.wait_authenticated(std::time::Duration::from_secs(5)),
+        async {
+            feed.send(Ok(authenticated_json())).unwrap();
+        }
+    );
+
+    let info = info.expect("authentication succeeds");
+    assert_eq!(info.app_name, "test-app");
+    assert_eq!(info.organization, None);
+    assert_eq!(info.rate_limits.per_minute, 60);
+    drain_until_authenticated(&mut events).await;
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn wait_authenticated_resolves_at_once_after_authentication() {
+    let (mut client, _events, _feed, _sent) = start_fed().await;
+
+    let info = client
+        .wait_authenticated(std::time::Duration::ZERO)
+        .await
+        .expect("already authenticated");
+    assert_eq!(info.app_name, "test-app");
+    assert_eq!(info.rate_limits.per_day, 10000);
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn wait_authenticated_reports_an_authentication_error_with_its_code() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, _events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+
+    let (result, ()) = tokio::join!(
+        client.wait_authenticated(std::time::Duration::from_secs(5)),
+        async {
+            feed.send(Ok(auth_failure_json())).unwrap();
+        }
+    );
+
+    let error = result.expect_err("authentication fails");
+    assert!(
+        matches!(
+            &error,
+            SignalFishError::AuthenticationFailed {
+                error,
+                error_code: ErrorCode::InvalidAppId,
+            } if error == "app revoked"
+        ),
+        "{error:?}"
+    );
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_authenticated_times_out_without_an_answer() {
+    let (transport, _feed, _sent) = FeedTransport::new();
+    let (mut client, _events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+
+    let error = client
+        .wait_authenticated(std::time::Duration::from_secs(2))
+        .await
+        .expect_err("no answer arrives");
+    assert!(matches!(error, SignalFishError::Timeout), "{error:?}");
+    assert!(!client.is_authenticated());
+    client.shutdown().await;
+}
+
 // ════════════════════════════════════════════════════════════════════
 // Room join → leave → rejoin flow
 // ════════════════════════════════════════════════════════════════════
@@ -245,2139 +445,8678 @@ async fn room_join_leave_rejoin_flow() {
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// Reconnection flow
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn reconnection_flow_updates_state() {
-    let (mut client, mut events, sent, _closed) = start_client(vec![
+async fn state_history_records_room_lifecycle_transitions() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(reconnected_json())),
+        Some(Ok(room_joined_json())),
+        Some(Ok(room_left_json())),
+        // RoomLeft while not in a room is rejected and must not be logged.
+        Some(Ok(room_left_json())),
     ]);
 
+    assert_eq!(client.session_state(), SessionState::Connected);
     drain_until_authenticated(&mut events).await;
-
-    // Issue a reconnect.
-    let pid = uuid::Uuid::from_u128(200);
-    let rid = uuid::Uuid::from_u128(100);
-    client
-        .reconnect(pid, rid, "auth-tok".into())
-        .expect("reconnect");
-
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::Reconnected {
-        room_code,
-        player_id,
-        is_authority,
-        ..
-    } = ev
-    {
-        assert_eq!(room_code, "RECON1");
-        assert_eq!(player_id, pid);
-        assert!(is_authority);
-    } else {
-        panic!("expected Reconnected, got {ev:?}");
+    for _ in 0..3 {
+        events.recv().await.expect("event");
     }
+    assert_eq!(client.session_state(), SessionState::Authenticated);
 
-    // State should be updated.
-    assert_eq!(client.current_room_code().await.as_deref(), Some("RECON1"));
-    assert_eq!(client.current_player_id().await, Some(pid));
+    client.shutdown().await;
+    assert_eq!(client.session_state(), SessionState::Disconnected);
 
-    // Verify the Reconnect message was sent.
-    wait_for_sent_len(&sent, 2).await;
-    {
-        let messages = sent.lock().unwrap();
-        // The reconnect message might be any position after authenticate.
-        // We just check at least one Reconnect was sent.
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::Reconnect { .. }))
-                .unwrap_or(false)
+    let transition = |from, to, trigger| StateTransition { from, to, trigger };
+    assert_eq!(
+        client.state_history(),
+        vec![
+            transition(
+                SessionState::Connected,
+                SessionState::Authenticated,
+                SessionTrigger::Authenticated
+            ),
+            transition(
+                SessionState::Authenticated,
+                SessionState::InRoom,
+                SessionTrigger::JoinedRoom
+            ),
+            transition(
+                SessionState::InRoom,
+                SessionState::Authenticated,
+                SessionTrigger::LeftRoom
+            ),
+            transition(
+                SessionState::Authenticated,
+                SessionState::Disconnected,
+                SessionTrigger::TransportClosed
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn health_verdict_tracks_silence_and_disconnect() {
+    let config =
+        SignalFishConfig::new("mb_test_integration").with_health_thresholds(HealthThresholds {
+            degraded_after: std::time::Duration::from_millis(50),
+            unhealthy_after: std::time::Duration::from_secs(60),
+            degraded_utilization: 0.8,
+            degraded_rtt: None,
         });
-        assert!(
-            found,
-            "expected a Reconnect message to be sent, but messages were: {messages:?}"
-        );
-    }
+    let (mut client, mut events, _sent, _closed) =
+        start_client_with_config(vec![Some(Ok(authenticated_json()))], config);
+
+    drain_until_authenticated(&mut events).await;
+    let health = client.health();
+    assert!(health.authenticated);
+    assert_eq!(health.verdict, HealthVerdict::Healthy);
+    assert!(health.channel_utilization < 0.8);
+
+    tokio::time::sleep(std::time::Duration::from_millis(80)).await;
+    let health = client.health();
+    assert!(health.last_recv_age >= std::time::Duration::from_millis(50));
+    assert_eq!(health.verdict, HealthVerdict::Degraded, "quiet too long");
 
     client.shutdown().await;
+    assert_eq!(client.health().verdict, HealthVerdict::Unhealthy);
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Spectator flow
+// leave_room_and_wait
 // ════════════════════════════════════════════════════════════════════
 
+/// An authenticated client in room `ABC123`, fed by the test.
+async fn start_in_room() -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+    SentLog,
+) {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(room_joined_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    (client, events, feed, sent)
+}
+
 #[tokio::test]
-async fn spectator_join_and_leave_flow() {
-    // NOTE: Scripted messages are consumed immediately, so we avoid
-    // asserting intermediate state between SpectatorJoined and SpectatorLeft.
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(spectator_joined_json())),
-        Some(Ok(spectator_left_json())),
-    ]);
+async fn leave_room_and_wait_resolves_on_room_left() {
+    let (mut client, mut events, feed, sent) = start_in_room().await;
+
+    let (result, ()) = tokio::join!(
+        client.leave_room_and_wait(std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            feed.send(Ok(room_left_json())).unwrap();
+        }
+    );
 
-    drain_until_authenticated(&mut events).await;
+    result.expect("the leave is confirmed");
+    assert_eq!(sent.matching(of_type("LeaveRoom")).len(), 1);
+    assert_eq!(client.session_state(), SessionState::Authenticated);
+    let snapshot = client.snapshot();
+    assert_eq!(snapshot.room_id, None);
+    assert_eq!(snapshot.room_code, None);
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomLeft), "{ev:?}");
+    client.shutdown().await;
+}
 
-    // Join as spectator.
-    client
-        .join_as_spectator("spec-game".into(), "SPEC1".into(), "Watcher".into())
-        .expect("join_as_spectator");
+#[tokio::test]
+async fn leave_room_and_wait_treats_not_in_room_as_left() {
+    let (mut client, mut events, feed, sent) = start_in_room().await;
+
+    let (result, ()) = tokio::join!(
+        client.leave_room_and_wait(std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            feed.send(Ok(error_json("not in a room", Some(ErrorCode::NotInRoom))))
+                .unwrap();
+        }
+    );
 
+    result.expect("NOT_IN_ROOM ends in the same state");
+    assert_eq!(client.session_state(), SessionState::Authenticated);
+    assert_eq!(client.snapshot().room_code, None);
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::SpectatorJoined {
-        room_code,
-        spectator_id,
-        game_name,
-        ..
-    } = ev
-    {
-        assert_eq!(room_code, "SPEC1");
-        assert_eq!(spectator_id, uuid::Uuid::from_u128(400));
-        assert_eq!(game_name, "spec-game");
-    } else {
-        panic!("expected SpectatorJoined, got {ev:?}");
-    }
+    assert!(matches!(ev, SignalFishEvent::Error { .. }), "{ev:?}");
 
-    // Leave spectator.
-    client.leave_spectator().expect("leave_spectator");
+    // The room is cleared once: a late RoomLeft is not applied again.
+    feed.send(Ok(room_left_json())).unwrap();
+    feed.send(Ok(pong_json())).unwrap();
     let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::SpectatorLeft { .. }));
+    assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+    client.shutdown().await;
+}
 
-    // After SpectatorLeft, room state should be cleared.
-    assert!(client.current_room_id().await.is_none());
-    assert!(client.current_room_code().await.is_none());
+#[tokio::test]
+async fn leave_room_and_wait_outside_a_room_returns_at_once() {
+    let (mut client, _events, _feed, sent) = start_fed().await;
 
+    client
+        .leave_room_and_wait(std::time::Duration::ZERO)
+        .await
+        .expect("nothing to leave");
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    assert!(sent.matching(of_type("LeaveRoom")).is_empty());
     client.shutdown().await;
 }
 
+#[tokio::test]
+async fn leave_room_and_wait_fails_when_the_connection_ends() {
+    let (client, _events, feed, sent) = start_in_room().await;
+
+    let (result, ()) = tokio::join!(
+        client.leave_room_and_wait(std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            drop(feed);
+        }
+    );
+
+    let error = result.expect_err("the connection ends first");
+    assert!(matches!(error, SignalFishError::NotConnected), "{error:?}");
+}
+
 // ════════════════════════════════════════════════════════════════════
-// Authority request/response flow
+// Player roster
 // ════════════════════════════════════════════════════════════════════
 
+fn roster(players: &[signal_fish_client::protocol::PlayerInfo]) -> Vec<(String, bool, bool)> {
+    players
+        .iter()
+        .map(|p| (p.name.clone(), p.is_ready, p.is_authority))
+        .collect()
+}
+
 #[tokio::test]
-async fn authority_request_granted() {
-    let (mut client, mut events, sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(authority_response_json(true, None))),
-    ]);
+async fn current_players_tracks_the_room_roster() {
+    use signal_fish_client::testing;
 
-    drain_until_authenticated(&mut events).await;
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let alice = testing::player_id("Alice");
+    let bob = testing::player_id("Bob");
+    let cara = testing::player_id("Cara");
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("ROSTER")
+        .player_id(alice)
+        .players([
+            testing::player("Alice").authority(),
+            testing::player("Bob").ready(),
+        ])
+        .json()))
+        .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    assert_eq!(
+        roster(&client.current_players().await),
+        vec![("Alice".into(), false, true), ("Bob".into(), true, false)]
+    );
 
-    client.request_authority(true).expect("request_authority");
+    feed.send(Ok(common::player_joined_json("Cara", cara)))
+        .unwrap();
+    feed.send(Ok(lobby_json_for(&[alice, cara]))).unwrap();
+    feed.send(Ok(serde_json::to_string(
+        &ServerMessage::AuthorityChanged {
+            authority_player: Some(cara),
+            you_are_authority: false,
+        },
+    )
+    .unwrap()))
+        .unwrap();
+    feed.send(Ok(player_left_json(bob))).unwrap();
+    for _ in 0..4 {
+        events.recv().await.expect("event");
+    }
+    assert_eq!(
+        roster(&client.current_players().await),
+        vec![("Alice".into(), true, false), ("Cara".into(), true, true)]
+    );
 
+    feed.send(Ok(room_left_json())).unwrap();
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::AuthorityResponse {
-        granted,
-        reason,
-        error_code,
-    } = ev
-    {
-        assert!(granted);
-        assert!(reason.is_none());
-        assert!(error_code.is_none());
-    } else {
-        panic!("expected AuthorityResponse, got {ev:?}");
-    }
+    assert!(matches!(ev, SignalFishEvent::RoomLeft), "{ev:?}");
+    assert!(client.current_players().await.is_empty());
+    client.shutdown().await;
+}
 
-    // Verify the AuthorityRequest message was sent.
-    wait_for_sent_len(&sent, 2).await;
-    {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| {
-                    matches!(
-                        cm,
-                        ClientMessage::AuthorityRequest {
-                            become_authority: true
-                        }
-                    )
-                })
-                .unwrap_or(false)
-        });
-        assert!(found, "expected AuthorityRequest message");
-    }
+#[tokio::test]
+async fn current_players_follows_a_spectated_room() {
+    use signal_fish_client::protocol::{LobbyState, SpectatorJoinedPayload};
+    use signal_fish_client::testing;
 
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    client
+        .join_as_spectator("spec-game".into(), "SPEC1".into(), "Watcher".into())
+        .expect("join_as_spectator");
+    let payload = SpectatorJoinedPayload {
+        room_id: uuid::Uuid::from_u128(300),
+        room_code: "SPEC1".into(),
+        spectator_id: uuid::Uuid::from_u128(400),
+        game_name: "spec-game".into(),
+        current_players: vec![testing::player("Alice").build()],
+        current_spectators: vec![],
+        lobby_state: LobbyState::Waiting,
+        reason: None,
+    };
+    feed.send(Ok(serde_json::to_string(&ServerMessage::SpectatorJoined(
+        Box::new(payload),
+    ))
+    .unwrap()))
+        .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::SpectatorJoined { .. }),
+        "{ev:?}"
+    );
+    assert_eq!(
+        roster(&client.current_players().await),
+        vec![("Alice".into(), false, false)]
+    );
+
+    feed.send(Ok(spectator_left_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::SpectatorLeft { .. }),
+        "{ev:?}"
+    );
+    assert!(client.current_players().await.is_empty());
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn authority_request_denied() {
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(authority_response_json(false, Some("not allowed")))),
-    ]);
-
-    drain_until_authenticated(&mut events).await;
-
-    client.request_authority(true).expect("request_authority");
+async fn current_players_is_cleared_on_disconnect() {
+    use signal_fish_client::testing;
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::AuthorityResponse {
-        granted, reason, ..
-    } = ev
-    {
-        assert!(!granted);
-        assert_eq!(reason.as_deref(), Some("not allowed"));
-    } else {
-        panic!("expected AuthorityResponse, got {ev:?}");
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("ROSTER")
+        .players([testing::player("Bob")])
+        .json()))
+        .unwrap();
+    events.recv().await.expect("event");
+    assert_eq!(client.current_players().await.len(), 1);
+
+    drop(feed);
+    loop {
+        let ev = events.recv().await.expect("event");
+        if matches!(ev, SignalFishEvent::Disconnected { .. }) {
+            break;
+        }
     }
-
+    assert!(client.current_players().await.is_empty());
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// ProvideConnectionInfo flow
-// ════════════════════════════════════════════════════════════════════
+fn lobby_json_for(ready_players: &[uuid::Uuid]) -> String {
+    serde_json::to_string(&ServerMessage::LobbyStateChanged {
+        lobby_state: signal_fish_client::protocol::LobbyState::Lobby,
+        ready_players: ready_players.to_vec(),
+        all_ready: true,
+    })
+    .unwrap()
+}
 
 #[tokio::test]
-async fn provide_connection_info_sends_correct_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+async fn lobby_state_follows_the_room_and_notifies_watchers() {
+    use signal_fish_client::protocol::LobbyState;
 
-    drain_until_authenticated(&mut events).await;
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let mut watch = client.lobby_state_watch();
+    assert_eq!(client.lobby_state(), LobbyState::Waiting);
 
-    let conn_info = ConnectionInfo::Direct {
-        host: "192.168.0.1".into(),
-        port: 7777,
-    };
     client
-        .provide_connection_info(conn_info)
-        .expect("provide_connection_info");
-
-    wait_for_sent_len(&sent, 2).await;
-
-    {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::ProvideConnectionInfo { .. }))
-                .unwrap_or(false)
-        });
-        assert!(found, "expected ProvideConnectionInfo message");
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(signal_fish_client::testing::room_joined("LOBBY1")
+        .lobby_state(LobbyState::Lobby)
+        .json()))
+        .unwrap();
+    watch.changed().await.expect("joined");
+    assert_eq!(*watch.borrow_and_update(), LobbyState::Lobby);
+    assert_eq!(client.lobby_state(), LobbyState::Lobby);
+
+    feed.send(Ok(serde_json::to_string(
+        &ServerMessage::LobbyStateChanged {
+            lobby_state: LobbyState::Finalized,
+            ready_players: vec![],
+            all_ready: true,
+        },
+    )
+    .unwrap()))
+        .unwrap();
+    watch.changed().await.expect("finalized");
+    assert_eq!(*watch.borrow_and_update(), LobbyState::Finalized);
+
+    feed.send(Ok(room_left_json())).unwrap();
+    watch.changed().await.expect("left");
+    assert_eq!(*watch.borrow_and_update(), LobbyState::Waiting);
+    assert_eq!(client.lobby_state(), LobbyState::Waiting);
+    drop(events.recv().await);
+    client.shutdown().await;
+}
 
-        // Verify the actual content.
-        let pci_msg = messages.iter().find_map(|m| {
-            let cm: ClientMessage = serde_json::from_str(m).ok()?;
-            if let ClientMessage::ProvideConnectionInfo { connection_info } = cm {
-                Some(connection_info)
-            } else {
-                None
-            }
-        });
-        let ci = pci_msg.expect("ProvideConnectionInfo not found");
-        if let ConnectionInfo::Direct { host, port } = ci {
-            assert_eq!(host, "192.168.0.1");
-            assert_eq!(port, 7777);
-        } else {
-            panic!("expected Direct connection info");
-        }
-    }
+#[tokio::test]
+async fn lobby_state_resets_on_disconnect() {
+    use signal_fish_client::protocol::LobbyState;
 
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(signal_fish_client::testing::room_joined("LOBBY1")
+        .lobby_state(LobbyState::Finalized)
+        .json()))
+        .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    let mut watch = client.lobby_state_watch();
+    assert_eq!(*watch.borrow_and_update(), LobbyState::Finalized);
+
+    drop(feed);
+    watch.changed().await.expect("reset");
+    assert_eq!(*watch.borrow(), LobbyState::Waiting);
+    assert_eq!(client.lobby_state(), LobbyState::Waiting);
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn provide_relay_connection_info() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+async fn room_snapshot_reads_the_whole_room_at_once() {
+    use signal_fish_client::protocol::{LobbyState, SpectatorInfo};
+    use signal_fish_client::testing;
 
-    drain_until_authenticated(&mut events).await;
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    assert!(client.room_snapshot().is_none());
 
-    let conn_info = ConnectionInfo::Relay {
-        host: "relay.example.com".into(),
-        port: 3000,
-        transport: RelayTransport::Tcp,
-        allocation_id: "room-abc".into(),
-        token: "tok-xyz".into(),
-        client_id: Some(5),
-    };
+    let alice = testing::player_id("Alice");
+    let bob = testing::player_id("Bob");
     client
-        .provide_connection_info(conn_info)
-        .expect("provide_connection_info");
-
-    wait_for_sent_len(&sent, 2).await;
-
-    {
-        let messages = sent.lock().unwrap();
-        let pci_msg = messages.iter().find_map(|m| {
-            let cm: ClientMessage = serde_json::from_str(m).ok()?;
-            if let ClientMessage::ProvideConnectionInfo { connection_info } = cm {
-                Some(connection_info)
-            } else {
-                None
-            }
-        });
-        let ci = pci_msg.expect("ProvideConnectionInfo not found");
-        if let ConnectionInfo::Relay {
-            host,
-            port,
-            transport,
-            token,
-            client_id,
-            ..
-        } = ci
-        {
-            assert_eq!(host, "relay.example.com");
-            assert_eq!(port, 3000);
-            assert!(matches!(transport, RelayTransport::Tcp));
-            assert_eq!(token, "tok-xyz");
-            assert_eq!(client_id, Some(5));
-        } else {
-            panic!("expected Relay connection info");
-        }
+        .join_room(JoinRoomParams::new("arena", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("SNAP1")
+        .game_name("arena")
+        .max_players(6)
+        .player_id(alice)
+        .authority()
+        .lobby_state(LobbyState::Lobby)
+        .players([testing::player("Alice").authority(), testing::player("Bob")])
+        .spectators([testing::spectator("Sam")])
+        .json()))
+        .unwrap();
+    feed.send(Ok(serde_json::to_string(
+        &ServerMessage::AuthorityChanged {
+            authority_player: Some(bob),
+            you_are_authority: false,
+        },
+    )
+    .unwrap()))
+        .unwrap();
+    feed.send(Ok(serde_json::to_string(
+        &ServerMessage::NewSpectatorJoined {
+            spectator: testing::spectator("Tia").build(),
+            current_spectators: vec![],
+            reason: None,
+        },
+    )
+    .unwrap()))
+        .unwrap();
+    for _ in 0..3 {
+        events.recv().await.expect("event");
     }
 
+    let room = client.room_snapshot().expect("in a room");
+    assert_eq!(room.room_code.as_deref(), Some("SNAP1"));
+    assert_eq!(room.room_id, client.snapshot().room_id);
+    assert_eq!(room.player_id, Some(alice));
+    assert_eq!(room.game_name.as_deref(), Some("arena"));
+    assert_eq!(room.max_players, 6);
+    assert_eq!(room.lobby_state, LobbyState::Lobby);
+    assert_eq!(room.authority, Some(bob));
+    assert!(!room.is_authority);
+    assert_eq!(
+        roster(&room.players),
+        vec![("Alice".into(), false, false), ("Bob".into(), false, true)]
+    );
+    let spectators: Vec<&str> = room
+        .spectators
+        .iter()
+        .map(|s: &SpectatorInfo| s.name.as_str())
+        .collect();
+    assert_eq!(spectators, ["Sam", "Tia"]);
+
+    feed.send(Ok(room_left_json())).unwrap();
+    events.recv().await.expect("RoomLeft");
+    assert!(client.room_snapshot().is_none());
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// JoinAsSpectator + LeaveSpectator message verification
-// ════════════════════════════════════════════════════════════════════
-
-#[tokio::test]
-async fn join_as_spectator_sends_correct_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+#[tokio::test(start_paused = true)]
+async fn room_history_records_each_ended_room_session() {
+    use signal_fish_client::testing;
+    use signal_fish_client::RoomLeaveReason;
 
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration").with_room_history(8)),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
     drain_until_authenticated(&mut events).await;
+    let secs = std::time::Duration::from_secs;
 
+    // Voluntary leave after a third player shows up.
     client
-        .join_as_spectator("game1".into(), "CODE1".into(), "Viewer".into())
-        .expect("join_as_spectator");
-
-    wait_for_sent_len(&sent, 2).await;
+        .join_room(JoinRoomParams::new("arena", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("HIST1")
+        .game_name("arena")
+        .players([testing::player("Alice"), testing::player("Bob")])
+        .json()))
+        .unwrap();
+    feed.send(Ok(common::player_joined_json(
+        "Cara",
+        testing::player_id("Cara"),
+    )))
+    .unwrap();
+    for _ in 0..2 {
+        events.recv().await.expect("RoomJoined, then PlayerJoined");
+    }
+    tokio::time::advance(secs(5)).await;
+    client.leave_room().expect("leave_room");
+    tokio::time::timeout(secs(1), async {
+        while client
+            .outgoing_breakdown()
+            .sent(ClientMessageKind::LeaveRoom)
+            == 0
+        {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("the leave goes out");
+    feed.send(Ok(room_left_json())).unwrap();
+    events.recv().await.expect("RoomLeft");
 
-    {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().find_map(|m| {
-            let cm: ClientMessage = serde_json::from_str(m).ok()?;
-            if let ClientMessage::JoinAsSpectator {
-                game_name,
-                room_code,
-                spectator_name,
-            } = cm
-            {
-                Some((game_name, room_code, spectator_name))
-            } else {
-                None
-            }
-        });
-        let (gn, rc, sn) = found.expect("JoinAsSpectator not found");
-        assert_eq!(gn, "game1");
-        assert_eq!(rc, "CODE1");
-        assert_eq!(sn, "Viewer");
+    // The server ends the next room unasked.
+    client
+        .join_room(JoinRoomParams::new("duel", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("HIST2")
+        .game_name("duel")
+        .players([testing::player("Alice")])
+        .json()))
+        .unwrap();
+    events.recv().await.expect("RoomJoined");
+    tokio::time::advance(secs(2)).await;
+    feed.send(Ok(room_left_json())).unwrap();
+    events.recv().await.expect("RoomLeft");
+
+    // The connection drops in the third.
+    client
+        .join_room(JoinRoomParams::new("arena", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(testing::room_joined("HIST3")
+        .game_name("arena")
+        .players([testing::player("Alice"), testing::player("Bob")])
+        .json()))
+        .unwrap();
+    events.recv().await.expect("RoomJoined");
+    tokio::time::advance(secs(1)).await;
+    drop(feed);
+    loop {
+        let ev = events.recv().await.expect("Disconnected");
+        if matches!(ev, SignalFishEvent::Disconnected { .. }) {
+            break;
+        }
     }
 
+    let history: Vec<_> = client
+        .room_history()
+        .into_iter()
+        .map(|r| {
+            (
+                r.room_code,
+                r.game_name,
+                r.peak_players,
+                r.duration,
+                r.reason_left,
+            )
+        })
+        .collect();
+    assert_eq!(
+        history,
+        vec![
+            (
+                "HIST1".into(),
+                "arena".into(),
+                3,
+                secs(5),
+                RoomLeaveReason::Left
+            ),
+            (
+                "HIST2".into(),
+                "duel".into(),
+                1,
+                secs(2),
+                RoomLeaveReason::Removed
+            ),
+            (
+                "HIST3".into(),
+                "arena".into(),
+                2,
+                secs(1),
+                RoomLeaveReason::Disconnected
+            ),
+        ]
+    );
+
+    let record = client.room_history().into_iter().next().expect("a record");
+    assert_eq!(
+        record.left_at_ms - record.joined_at_ms,
+        5_000,
+        "wall-clock stamps follow the client clock"
+    );
+    let upload = serde_json::to_value(&record).unwrap();
+    assert_eq!(upload["reason_left"], "left");
+    assert_eq!(upload["room_code"], "HIST1");
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn leave_spectator_sends_correct_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
-
-    drain_until_authenticated(&mut events).await;
-
-    client.leave_spectator().expect("leave_spectator");
-
-    wait_for_sent_len(&sent, 2).await;
-
-    {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::LeaveSpectator))
-                .unwrap_or(false)
-        });
-        assert!(found, "expected LeaveSpectator message");
-    }
-
+async fn room_history_is_off_by_default() {
+    let (mut client, mut events, feed, _sent) = start_in_room().await;
+    feed.send(Ok(room_left_json())).unwrap();
+    events.recv().await.expect("RoomLeft");
+    assert!(client.room_history().is_empty());
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Error event handling
+// Reconnection flow
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn server_error_event_received() {
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
+async fn reconnection_flow_updates_state() {
+    let (mut client, mut events, sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(error_json(
-            "something went wrong",
-            Some(ErrorCode::InternalError),
-        ))),
+        Some(Ok(reconnected_json())),
     ]);
 
     drain_until_authenticated(&mut events).await;
 
+    // Issue a reconnect.
+    let pid = uuid::Uuid::from_u128(200);
+    let rid = uuid::Uuid::from_u128(100);
+    client
+        .reconnect(pid, rid, "auth-tok".into())
+        .expect("reconnect");
+
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::Error {
-        message,
-        error_code,
+    if let SignalFishEvent::Reconnected {
+        room_code,
+        player_id,
+        is_authority,
+        ..
     } = ev
     {
-        assert_eq!(message, "something went wrong");
-        assert_eq!(error_code, Some(ErrorCode::InternalError));
+        assert_eq!(room_code, "RECON1");
+        assert_eq!(player_id, pid);
+        assert!(is_authority);
     } else {
-        panic!("expected Error event, got {ev:?}");
+        panic!("expected Reconnected, got {ev:?}");
     }
 
-    client.shutdown().await;
-}
-
-#[tokio::test]
-async fn server_error_event_without_error_code() {
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(error_json("generic error", None))),
-    ]);
-
-    drain_until_authenticated(&mut events).await;
+    // State should be updated.
+    assert_eq!(client.current_room_code().await.as_deref(), Some("RECON1"));
+    assert_eq!(client.current_player_id().await, Some(pid));
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::Error {
-        message,
-        error_code,
-    } = ev
+    // Verify the Reconnect message was sent.
+    wait_for_sent_len(&sent, 2).await;
     {
-        assert_eq!(message, "generic error");
-        assert!(error_code.is_none());
-    } else {
-        panic!("expected Error event, got {ev:?}");
+        // The reconnect message might be any position after authenticate.
+        // We just check at least one Reconnect was sent.
+        assert!(
+            !sent.matching(of_type("Reconnect")).is_empty(),
+            "expected a Reconnect message to be sent, but the latest were: {:?}",
+            sent.latest(5)
+        );
     }
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// Disconnect handling
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn disconnect_on_transport_close() {
+async fn reconnect_with_sequence_gaps_emits_missed_events_gap() {
+    let ServerMessage::Reconnected(mut payload) =
+        serde_json::from_str(&reconnected_json()).expect("decode fixture")
+    else {
+        panic!("fixture is not Reconnected");
+    };
+    payload.missed_events = [1, 2, 5, 9]
+        .into_iter()
+        .map(|seq| signal_fish_client::MissedEvent {
+            message: ServerMessage::Pong,
+            seq: Some(seq),
+        })
+        .collect();
+    let gapped = serde_json::to_string(&ServerMessage::Reconnected(payload)).expect("serialize");
     let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), None]);
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(gapped))]);
 
     drain_until_authenticated(&mut events).await;
 
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
-    assert!(!client.is_connected());
+    let ev = events.recv().await.expect("Reconnected");
+    assert!(
+        matches!(&ev, SignalFishEvent::Reconnected { missed_events, .. } if missed_events.len() == 4),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("first gap");
+    assert!(
+        matches!(ev, SignalFishEvent::MissedEventsGap { from: 3, to: 4 }),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("second gap");
+    assert!(
+        matches!(ev, SignalFishEvent::MissedEventsGap { from: 6, to: 8 }),
+        "{ev:?}"
+    );
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn disconnect_on_transport_error() {
-    let (mut client, mut events, _sent, _closed) = start_client(vec![Some(Err(
-        SignalFishError::TransportReceive("network failure".into()),
-    ))]);
+async fn reconnect_snapshot_wins_over_conflicting_missed_events() {
+    use signal_fish_client::testing;
+
+    let alice = testing::player("Alice").build();
+    let bob = testing::player("Bob").build();
+    let cara = testing::player("Cara").build();
+    let ServerMessage::Reconnected(mut payload) =
+        serde_json::from_str(&reconnected_json()).expect("decode fixture")
+    else {
+        panic!("fixture is not Reconnected");
+    };
+    // Bob dropped and rejoined, and Cara came and went, while we were away;
+    // the snapshot is the state after all of it.
+    payload.current_players = vec![alice.clone(), bob.clone()];
+    payload.missed_events = vec![
+        ServerMessage::PlayerLeft {
+            player_id: bob.id,
+            epoch: None,
+            final_seq: None,
+        },
+        ServerMessage::PlayerJoined {
+            player: cara.clone(),
+        },
+        ServerMessage::PlayerJoined {
+            player: bob.clone(),
+        },
+        ServerMessage::PlayerLeft {
+            player_id: cara.id,
+            epoch: None,
+            final_seq: None,
+        },
+    ]
+    .into_iter()
+    .map(Into::into)
+    .collect();
+    let conflicting =
+        serde_json::to_string(&ServerMessage::Reconnected(payload)).expect("serialize");
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(conflicting))]);
 
-    // Connected might still be emitted before the error is processed.
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Connected));
+    drain_until_authenticated(&mut events).await;
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::Disconnected { reason, .. } = ev {
-        let r = reason.expect("reason should be present");
-        assert!(r.contains("network failure"), "reason was: {r}");
-    } else {
-        panic!("expected Disconnected, got {ev:?}");
-    }
+    let Some(SignalFishEvent::Reconnected { missed_events, .. }) = events.recv().await else {
+        panic!("expected Reconnected");
+    };
+    assert!(matches!(
+        missed_events.as_slice(),
+        [
+            SignalFishEvent::PlayerLeft { .. },
+            SignalFishEvent::PlayerJoined { .. },
+            SignalFishEvent::PlayerJoined { .. },
+            SignalFishEvent::PlayerLeft { .. },
+        ]
+    ));
+    let roster: Vec<_> = client
+        .current_players()
+        .await
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+    assert_eq!(roster, [alice.id, bob.id]);
 
-    assert!(!client.is_connected());
     client.shutdown().await;
 }
 
-#[tokio::test]
-async fn operations_fail_after_disconnect() {
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), None]);
+// ════════════════════════════════════════════════════════════════════
+// Single-flight reconnect
+// ════════════════════════════════════════════════════════════════════
 
-    drain_until_authenticated(&mut events).await;
+/// The player and room of [`reconnected_json`].
+fn reconnect_target() -> (uuid::Uuid, uuid::Uuid) {
+    (uuid::Uuid::from_u128(200), uuid::Uuid::from_u128(100))
+}
 
-    // Wait for Disconnected.
-    let _ev = events.recv().await;
+fn reconnection_failed_json() -> String {
+    serde_json::to_string(&ServerMessage::ReconnectionFailed {
+        reason: "expired".into(),
+        error_code: ErrorCode::ReconnectionExpired,
+    })
+    .expect("serialize")
+}
 
-    let result = client.ping();
+#[tokio::test]
+async fn second_reconnect_is_refused_until_the_first_is_answered() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    let (player_id, room_id) = reconnect_target();
+
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("the first reconnect is sent");
+    let error = client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect_err("a second one waits for the answer");
     assert!(
-        matches!(result, Err(SignalFishError::NotConnected)),
-        "expected NotConnected error, got {result:?}"
+        matches!(error, SignalFishError::ReconnectInFlight),
+        "{error:?}"
+    );
+    let error = client
+        .reconnect(player_id, uuid::Uuid::from_u128(101), "tok".into())
+        .expect_err("whatever room it names");
+    assert!(
+        matches!(error, SignalFishError::ReconnectInFlight),
+        "{error:?}"
     );
 
+    feed.send(Ok(reconnected_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Reconnected { .. })
+    ));
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("answered, the next reconnect goes out");
+    wait_for_sent_len(&sent, 3).await;
+    assert_eq!(sent.matching(of_type("Reconnect")).len(), 2);
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// GameData and GameDataBinary event handling
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn game_data_event_received() {
-    let player = uuid::Uuid::from_u128(42);
-    let data = serde_json::json!({"score": 100, "level": 5});
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(game_data_json(player, data.clone()))),
-    ]);
-
-    drain_until_authenticated(&mut events).await;
+async fn reconnect_and_wait_joins_the_attempt_in_flight() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    let (player_id, room_id) = reconnect_target();
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("reconnect");
+    let timeout = std::time::Duration::from_secs(5);
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::GameData {
-        from_player,
-        data: d,
-        seq,
-        epoch,
-        class,
-        key,
-    } = ev
-    {
-        assert_eq!(from_player, player);
-        assert_eq!(d["score"], 100);
-        assert_eq!(d["level"], 5);
-        assert!(seq.is_none());
-        assert!(epoch.is_none());
-        assert!(class.is_none());
-        assert!(key.is_none());
-    } else {
-        panic!("expected GameData event, got {ev:?}");
-    }
+    let other_room = client
+        .reconnect_and_wait(player_id, uuid::Uuid::from_u128(101), "tok".into(), timeout)
+        .await
+        .expect_err("an attempt for another room is not joined");
+    assert!(
+        matches!(other_room, SignalFishError::ReconnectInFlight),
+        "{other_room:?}"
+    );
 
+    let (first, second, ()) = tokio::join!(
+        client.reconnect_and_wait(player_id, room_id, "tok".into(), timeout),
+        client.reconnect_and_wait(player_id, room_id, "tok".into(), timeout),
+        async {
+            feed.send(Ok(reconnected_json())).unwrap();
+        }
+    );
+    assert_eq!(first.expect("first waiter").room_code, "RECON1");
+    assert_eq!(second.expect("second waiter").room_code, "RECON1");
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Reconnected { .. })
+    ));
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(
+        sent.matching(of_type("Reconnect")).len(),
+        1,
+        "the waiters shared the one Reconnect"
+    );
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn game_data_binary_event_received() {
-    let player = uuid::Uuid::from_u128(99);
-    let payload = vec![0xCA, 0xFE, 0xBA, 0xBE];
-    let binary = signal_fish_client::protocol::V3BinaryGameDataFrame {
-        from_player: player,
-        encoding: GameDataEncoding::MessagePack,
-        payload: payload.clone(),
-        seq: 1,
-        epoch: 1,
-    };
-    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
-        Some(Ok(TransportFrame::Text(authenticated_json()))),
-        Some(Ok(TransportFrame::Text(protocol_info_json(Some(3))))),
-        Some(Ok(TransportFrame::Text(v3_room_baseline_json(player)))),
-        Some(Ok(TransportFrame::Binary(
-            rmp_serde::to_vec_named(&binary).expect("serialize binary frame"),
-        ))),
-    ]);
-    let mut config = SignalFishConfig::new("mb_test_integration").enable_v3();
-    config.game_data_format = Some(GameDataEncoding::MessagePack);
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
-
-    drain_until_authenticated(&mut events).await;
+async fn reconnection_failed_clears_the_attempt_and_fails_its_waiters() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    let (player_id, room_id) = reconnect_target();
+
+    let (result, ()) = tokio::join!(
+        client.reconnect_and_wait(
+            player_id,
+            room_id,
+            "tok".into(),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(reconnection_failed_json())).unwrap();
+        }
+    );
+    let error = result.expect_err("the server refused");
+    assert!(
+        matches!(
+            &error,
+            SignalFishError::ServerError {
+                message,
+                error_code: Some(ErrorCode::ReconnectionExpired),
+            } if message == "expired"
+        ),
+        "{error:?}"
+    );
     assert!(matches!(
         events.recv().await,
-        Some(SignalFishEvent::ProtocolInfo(_))
+        Some(SignalFishEvent::ReconnectionFailed { .. })
     ));
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("a failed attempt is no longer in flight");
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn reconnect_and_wait_fails_when_the_connection_ends() {
+    let (client, mut events, feed, sent) = start_fed().await;
+    let (player_id, room_id) = reconnect_target();
+
+    let (result, ()) = tokio::join!(
+        client.reconnect_and_wait(
+            player_id,
+            room_id,
+            "tok".into(),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            drop(feed);
+        }
+    );
+    assert!(
+        matches!(result, Err(SignalFishError::NotConnected)),
+        "{result:?}"
+    );
     assert!(matches!(
         events.recv().await,
-        Some(SignalFishEvent::RoomJoined { .. })
+        Some(SignalFishEvent::Disconnected { .. })
     ));
+}
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::GameDataBinary {
-        from_player,
-        encoding,
-        payload: p,
-        seq,
-        epoch,
-    } = ev
-    {
-        assert_eq!(from_player, player);
-        assert!(matches!(encoding, GameDataEncoding::MessagePack));
-        assert_eq!(p, payload);
-        assert_eq!(seq, Some(1));
-        assert_eq!(epoch, Some(1));
-    } else {
-        panic!("expected GameDataBinary event, got {ev:?}");
-    }
+#[tokio::test(start_paused = true)]
+async fn an_unanswered_reconnect_stops_counting_after_the_timeout() {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(
+            SignalFishConfig::new("mb_test_integration")
+                .with_reconnect_answer_timeout(std::time::Duration::from_secs(2)),
+        ),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    let (player_id, room_id) = reconnect_target();
 
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("reconnect");
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    assert!(matches!(
+        client.reconnect(player_id, room_id, "tok".into()),
+        Err(SignalFishError::ReconnectInFlight)
+    ));
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("the unanswered attempt is taken as lost");
+    wait_for_sent_len(&sent, 3).await;
+    assert_eq!(sent.matching(of_type("Reconnect")).len(), 2);
     client.shutdown().await;
 }
 
-#[tokio::test]
-async fn v2_message_pack_binary_event_received() {
-    let player = uuid::Uuid::from_u128(98);
-    let frame = signal_fish_client::protocol::V2BinaryGameDataFrame {
-        from_player: player,
-        encoding: GameDataEncoding::MessagePack,
-        payload: vec![4, 5, 6],
-    };
-    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
-        Some(Ok(TransportFrame::Text(authenticated_json()))),
-        Some(Ok(TransportFrame::Text(protocol_info_json(None)))),
-        Some(Ok(TransportFrame::Binary(
-            rmp_serde::to_vec_named(&frame).expect("serialize v2 binary frame"),
-        ))),
-    ]);
-    let mut config = SignalFishConfig::new("mb_test_integration");
-    config.game_data_format = Some(GameDataEncoding::MessagePack);
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
-    drain_until_authenticated(&mut events).await;
+#[tokio::test(start_paused = true)]
+async fn a_dropped_connection_clears_the_attempt_in_flight() {
+    let (mut client, mut events, dials) = start_reconnecting(ReconnectPolicy::default());
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
     assert!(matches!(
         events.recv().await,
-        Some(SignalFishEvent::ProtocolInfo(_))
+        Some(SignalFishEvent::Authenticated { .. })
     ));
+    let (player_id, room_id) = reconnect_target();
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("reconnect");
+    wait_for_sent_len(&sent, 2).await;
+
+    drop(feed);
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+    client
+        .reconnect(player_id, room_id, "tok".into())
+        .expect("the attempt ended with its connection; this one is held for the next");
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
     assert!(matches!(
         events.recv().await,
-        Some(SignalFishEvent::GameDataBinary {
-            from_player,
-            seq: None,
-            epoch: None,
-            ..
-        }) if from_player == player
+        Some(SignalFishEvent::Authenticated { .. })
     ));
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(sent.matching(of_type("Reconnect")).len(), 1);
     client.shutdown().await;
 }
 
+// ════════════════════════════════════════════════════════════════════
+// Spectator flow
+// ════════════════════════════════════════════════════════════════════
+
 #[tokio::test]
-async fn json_mode_physical_binary_policy_matches_polling_client() {
-    for (policy, connected, quarantined) in [
-        (
-            signal_fish_client::ProtocolViolationPolicy::Quarantine,
-            true,
-            true,
-        ),
-        (
-            signal_fish_client::ProtocolViolationPolicy::Disconnect,
-            false,
-            false,
-        ),
-        (
-            signal_fish_client::ProtocolViolationPolicy::Observe,
-            true,
-            false,
-        ),
-    ] {
-        let (transport, _sent, _closed) =
-            MockTransport::new_frames(vec![Some(Ok(TransportFrame::Binary(vec![0xff, 0x00])))]);
-        let config =
-            SignalFishConfig::new("mb_test_integration").with_protocol_violation_policy(policy);
-        let (mut client, mut events) = SignalFishClient::start(transport, config);
-        assert!(matches!(
-            events.recv().await,
-            Some(SignalFishEvent::Connected)
-        ));
-        assert!(matches!(
-            events.recv().await,
-            Some(SignalFishEvent::ProtocolViolation { .. })
-        ));
-        if policy == signal_fish_client::ProtocolViolationPolicy::Disconnect {
-            assert!(matches!(
-                events.recv().await,
-                Some(SignalFishEvent::Disconnected { .. })
-            ));
-        }
-        assert_eq!(client.is_connected(), connected);
-        assert_eq!(client.snapshot().quarantined, quarantined);
-        client.shutdown().await;
+async fn spectator_join_and_leave_flow() {
+    // NOTE: Scripted messages are consumed immediately, so we avoid
+    // asserting intermediate state between SpectatorJoined and SpectatorLeft.
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(spectator_joined_json())),
+        Some(Ok(spectator_left_json())),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    // Join as spectator.
+    client
+        .join_as_spectator("spec-game".into(), "SPEC1".into(), "Watcher".into())
+        .expect("join_as_spectator");
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::SpectatorJoined {
+        room_code,
+        spectator_id,
+        game_name,
+        ..
+    } = ev
+    {
+        assert_eq!(room_code, "SPEC1");
+        assert_eq!(spectator_id, uuid::Uuid::from_u128(400));
+        assert_eq!(game_name, "spec-game");
+    } else {
+        panic!("expected SpectatorJoined, got {ev:?}");
+    }
+
+    // Leave spectator.
+    client.leave_spectator().expect("leave_spectator");
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::SpectatorLeft { .. }));
+
+    // After SpectatorLeft, room state should be cleared.
+    assert!(client.current_room_id().await.is_none());
+    assert!(client.current_room_code().await.is_none());
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Spectator → player promotion
+// ════════════════════════════════════════════════════════════════════
+
+/// A client spectating room `SPEC1` of `spec-game`, fed by the test.
+async fn start_spectating() -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+    SentLog,
+) {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    client
+        .join_as_spectator("spec-game".into(), "SPEC1".into(), "Watcher".into())
+        .expect("join_as_spectator");
+    feed.send(Ok(spectator_joined_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::SpectatorJoined { .. }),
+        "expected SpectatorJoined, got {ev:?}"
+    );
+    (client, events, feed, sent)
+}
+
+/// Wait for the promotion's `LeaveSpectator` (the third frame sent) and
+/// check that nothing follows it before the server answers.
+async fn expect_leave_spectator_alone(sent: &SentLog) {
+    wait_for_sent_len(sent, 3).await;
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
     }
+    assert_eq!(sent.total(), 3, "JoinRoom went out before SpectatorLeft");
+    assert_eq!(sent.matching(of_type("LeaveSpectator")).len(), 1);
+}
+
+fn room_join_failed_json(reason: &str, error_code: ErrorCode) -> String {
+    serde_json::to_string(&ServerMessage::RoomJoinFailed {
+        reason: reason.into(),
+        error_code: Some(error_code),
+    })
+    .expect("room_join_failed_json serialization")
 }
 
 #[tokio::test]
-async fn async_observe_advances_valid_wrong_representation_sequence() {
-    let player = uuid::Uuid::from_u128(97);
-    let binary = signal_fish_client::protocol::V3BinaryGameDataFrame {
-        from_player: player,
-        encoding: GameDataEncoding::MessagePack,
-        payload: vec![1, 2, 3],
-        seq: 1,
-        epoch: 1,
+async fn promote_to_player_joins_the_spectated_room_after_spectator_left() {
+    let (mut client, mut events, feed, sent) = start_spectating().await;
+    let player_id = uuid::Uuid::from_u128(500);
+
+    let (result, ()) = tokio::join!(
+        client.promote_to_player("Alice", |params| params
+            .with_max_players(4)
+            .with_room_code("ELSEWHERE")),
+        async {
+            expect_leave_spectator_alone(&sent).await;
+            feed.send(Ok(spectator_left_json())).unwrap();
+            wait_for_sent_len(&sent, 4).await;
+            feed.send(Ok(room_joined_json_with("SPEC1", "spec-game", player_id)))
+                .unwrap();
+        }
+    );
+
+    let room = result.expect("promotion succeeds");
+    assert_eq!(room.room_code, "SPEC1");
+    assert_eq!(room.player_id, player_id);
+    let joins = sent.matching(of_type("JoinRoom"));
+    let [join] = joins.as_slice() else {
+        panic!("expected one JoinRoom, got {joins:?}");
     };
-    let following = ServerMessage::GameData {
-        from_player: player,
-        data: serde_json::json!({"seq": 2}),
-        seq: Some(2),
-        epoch: Some(1),
-        class: Some(signal_fish_client::protocol::DeliveryClass::Reliable),
-        key: None,
+    let ClientMessage::JoinRoom {
+        game_name,
+        room_code,
+        player_name,
+        max_players,
+        ..
+    } = serde_json::from_str(join).expect("JoinRoom parses")
+    else {
+        panic!("expected JoinRoom, got {join}");
     };
-    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
-        Some(Ok(TransportFrame::Text(authenticated_json()))),
-        Some(Ok(TransportFrame::Text(protocol_info_json(Some(3))))),
-        Some(Ok(TransportFrame::Text(v3_room_baseline_json(player)))),
-        Some(Ok(TransportFrame::Binary(
-            rmp_serde::to_vec_named(&binary).expect("serialize binary fixture"),
-        ))),
-        Some(Ok(TransportFrame::Text(
-            serde_json::to_string(&following).expect("serialize following JSON fixture"),
-        ))),
-    ]);
-    let config = SignalFishConfig::new("mb_test_integration")
-        .enable_v3()
-        .with_protocol_violation_policy(signal_fish_client::ProtocolViolationPolicy::Observe);
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
-    drain_until_authenticated(&mut events).await;
-    assert!(matches!(
-        events.recv().await,
-        Some(SignalFishEvent::ProtocolInfo(_))
-    ));
-    assert!(matches!(
-        events.recv().await,
-        Some(SignalFishEvent::RoomJoined { .. })
-    ));
-    assert!(matches!(
-        events.recv().await,
-        Some(SignalFishEvent::ProtocolViolation { .. })
-    ));
-    assert!(matches!(
-        events.recv().await,
-        Some(SignalFishEvent::GameDataBinary { seq: Some(1), .. })
-    ));
-    assert!(matches!(
-        events.recv().await,
-        Some(SignalFishEvent::GameData { seq: Some(2), .. })
-    ));
+    assert_eq!(game_name, "spec-game");
+    assert_eq!(room_code.as_deref(), Some("SPEC1"), "the room code is kept");
+    assert_eq!(player_name, "Alice");
+    assert_eq!(max_players, Some(4));
+
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::SpectatorLeft { .. }),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    assert_eq!(client.session_state(), SessionState::InRoom);
+    assert_eq!(client.current_player_id().await, Some(player_id));
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// send_game_data API method
-// ════════════════════════════════════════════════════════════════════
+#[tokio::test]
+async fn promote_to_player_reports_a_failed_leave() {
+    let (mut client, _events, feed, sent) = start_spectating().await;
+
+    let (result, ()) = tokio::join!(client.promote_to_player("Alice", |params| params), async {
+        expect_leave_spectator_alone(&sent).await;
+        feed.send(Ok(error_json(
+            "spectator seat is locked",
+            Some(ErrorCode::InvalidRoomState),
+        )))
+        .unwrap();
+    });
+
+    let error = result.expect_err("the leave is refused");
+    assert!(
+        matches!(
+            &error,
+            PromotionError::Leave(SignalFishError::ServerError {
+                error_code: Some(ErrorCode::InvalidRoomState),
+                ..
+            })
+        ),
+        "{error:?}"
+    );
+    assert!(sent.matching(of_type("JoinRoom")).is_empty());
+    assert_eq!(client.session_state(), SessionState::Spectating);
+    assert_eq!(client.current_room_code().await.as_deref(), Some("SPEC1"));
+    client.shutdown().await;
+}
 
 #[tokio::test]
-async fn send_game_data_produces_correct_json() {
+async fn promote_to_player_reports_a_room_that_filled_in_the_gap() {
+    let (mut client, _events, feed, sent) = start_spectating().await;
+
+    let (result, ()) = tokio::join!(client.promote_to_player("Alice", |params| params), async {
+        expect_leave_spectator_alone(&sent).await;
+        feed.send(Ok(spectator_left_json())).unwrap();
+        wait_for_sent_len(&sent, 4).await;
+        feed.send(Ok(room_join_failed_json(
+            "room is full",
+            ErrorCode::RoomFull,
+        )))
+        .unwrap();
+    });
+
+    let error = result.expect_err("the join is refused");
+    let PromotionError::Join { room_code, error } = error else {
+        panic!("expected a join failure, got {error:?}");
+    };
+    assert_eq!(room_code, "SPEC1");
+    assert!(
+        matches!(
+            error,
+            SignalFishError::ServerError {
+                error_code: Some(ErrorCode::RoomFull),
+                ..
+            }
+        ),
+        "{error:?}"
+    );
+    assert_eq!(client.session_state(), SessionState::Authenticated);
+    assert!(client.current_room_code().await.is_none());
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn promote_to_player_requires_a_spectator_session() {
     let (mut client, mut events, sent, _closed) =
         start_client(vec![Some(Ok(authenticated_json()))]);
-
     drain_until_authenticated(&mut events).await;
 
-    let data = serde_json::json!({"type": "chat", "msg": "hello"});
-    client.send_game_data(data.clone()).expect("send_game_data");
-
-    wait_for_sent_len(&sent, 2).await;
-
-    {
-        let messages = sent.lock().unwrap();
-        let gd_msg = messages.iter().find_map(|m| {
-            let cm: ClientMessage = serde_json::from_str(m).ok()?;
-            if let ClientMessage::GameData {
-                data: d,
-                class,
-                key,
-            } = cm
-            {
-                Some((d, class, key))
-            } else {
-                None
-            }
-        });
-        let (d, class, key) = gd_msg.expect("GameData message not found");
-        assert_eq!(d["type"], "chat");
-        assert_eq!(d["msg"], "hello");
-        assert!(class.is_none());
-        assert!(key.is_none());
-    }
+    let error = client
+        .promote_to_player("Alice", |params| params)
+        .await
+        .expect_err("not spectating");
 
+    assert!(matches!(error, PromotionError::NotSpectating), "{error:?}");
+    assert_eq!(sent.total(), 1, "only Authenticate was sent");
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// set_ready and ping API methods
+// Authority request/response flow
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn set_ready_sends_player_ready_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+async fn authority_request_granted() {
+    let (mut client, mut events, sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(authority_response_json(true, None))),
+    ]);
 
     drain_until_authenticated(&mut events).await;
 
-    client.set_ready().expect("set_ready");
+    client.request_authority(true).expect("request_authority");
 
-    wait_for_sent_len(&sent, 2).await;
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::AuthorityResponse {
+        granted,
+        reason,
+        error_code,
+    } = ev
+    {
+        assert!(granted);
+        assert!(reason.is_none());
+        assert!(error_code.is_none());
+    } else {
+        panic!("expected AuthorityResponse, got {ev:?}");
+    }
 
+    // Verify the AuthorityRequest message was sent.
+    wait_for_sent_len(&sent, 2).await;
     {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
+        let found = sent.matching(of_type("AuthorityRequest")).iter().any(|m| {
             serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::PlayerReady))
+                .map(|cm| {
+                    matches!(
+                        cm,
+                        ClientMessage::AuthorityRequest {
+                            become_authority: true
+                        }
+                    )
+                })
                 .unwrap_or(false)
         });
-        assert!(found, "expected PlayerReady message");
+        assert!(found, "expected AuthorityRequest message");
     }
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn ping_and_pong_flow() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pong_json()))]);
+async fn authority_request_denied() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(authority_response_json(false, Some("not allowed")))),
+    ]);
 
     drain_until_authenticated(&mut events).await;
 
-    client.ping().expect("ping");
+    client.request_authority(true).expect("request_authority");
 
     let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Pong));
-
-    wait_for_sent_len(&sent, 2).await;
+    if let SignalFishEvent::AuthorityResponse {
+        granted, reason, ..
+    } = ev
     {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::Ping))
-                .unwrap_or(false)
-        });
-        assert!(found, "expected Ping message");
+        assert!(!granted);
+        assert_eq!(reason.as_deref(), Some("not allowed"));
+    } else {
+        panic!("expected AuthorityResponse, got {ev:?}");
     }
 
     client.shutdown().await;
 }
 
+fn authority_denied_json(reason: &str, error_code: ErrorCode) -> String {
+    serde_json::to_string(&ServerMessage::AuthorityResponse {
+        granted: false,
+        reason: Some(reason.into()),
+        error_code: Some(error_code),
+    })
+    .expect("authority_denied_json serialization")
+}
+
+#[tokio::test]
+async fn request_authority_and_wait_resolves_with_the_response() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+
+    let (grant, ()) = tokio::join!(
+        client.request_authority_and_wait(true, std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(authority_response_json(true, None))).unwrap();
+        }
+    );
+
+    let grant = grant.expect("the server answers");
+    assert!(grant.granted);
+    assert_eq!(grant.reason, None);
+    assert_eq!(grant.error_code, None);
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::AuthorityResponse { granted: true, .. }),
+        "the event is still delivered: {ev:?}"
+    );
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn request_authority_and_wait_matches_responses_to_requests_in_order() {
+    let (mut client, _events, feed, sent) = start_fed().await;
+
+    client.request_authority(true).expect("request_authority");
+    let (first, second, ()) = tokio::join!(
+        client.request_authority_and_wait(true, std::time::Duration::from_secs(5)),
+        client.request_authority_and_wait(false, std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 4).await;
+            feed.send(Ok(authority_response_json(true, Some("plain"))))
+                .unwrap();
+            feed.send(Ok(authority_denied_json(
+                "another player holds authority",
+                ErrorCode::AuthorityConflict,
+            )))
+            .unwrap();
+            feed.send(Ok(authority_response_json(true, Some("released"))))
+                .unwrap();
+        }
+    );
+
+    let first = first.expect("the first waiter is answered");
+    assert!(!first.granted, "the plain request's answer is skipped");
+    assert_eq!(
+        first.reason.as_deref(),
+        Some("another player holds authority")
+    );
+    assert_eq!(first.error_code, Some(ErrorCode::AuthorityConflict));
+    let second = second.expect("the second waiter is answered");
+    assert_eq!(second.reason.as_deref(), Some("released"));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn request_authority_and_wait_times_out_without_desyncing_later_requests() {
+    let (mut client, _events, feed, _sent) = start_fed().await;
+
+    let error = client
+        .request_authority_and_wait(true, std::time::Duration::from_secs(2))
+        .await
+        .expect_err("no answer arrives");
+    assert!(matches!(error, SignalFishError::Timeout), "{error:?}");
+
+    let (grant, ()) = tokio::join!(
+        client.request_authority_and_wait(true, std::time::Duration::from_secs(5)),
+        async {
+            feed.send(Ok(authority_response_json(false, Some("late"))))
+                .unwrap();
+            feed.send(Ok(authority_response_json(true, Some("current"))))
+                .unwrap();
+        }
+    );
+    let grant = grant.expect("the second request is answered");
+    assert_eq!(grant.reason.as_deref(), Some("current"));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn request_authority_and_wait_fails_when_the_connection_ends() {
+    let (client, _events, feed, sent) = start_fed().await;
+
+    let (result, ()) = tokio::join!(
+        client.request_authority_and_wait(true, std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            drop(feed);
+        }
+    );
+
+    let error = result.expect_err("the connection ends first");
+    assert!(matches!(error, SignalFishError::NotConnected), "{error:?}");
+}
+
 // ════════════════════════════════════════════════════════════════════
-// JoinRoom with builder options
+// ProvideConnectionInfo flow
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn join_room_with_all_options_sends_correct_message() {
+async fn provide_connection_info_sends_correct_message() {
     let (mut client, mut events, sent, _closed) =
         start_client(vec![Some(Ok(authenticated_json()))]);
 
     drain_until_authenticated(&mut events).await;
 
-    let params = JoinRoomParams::new("arena", "Alice")
-        .with_room_code("ROOM42")
-        .with_max_players(8)
-        .with_supports_authority(true)
-        .with_relay_transport(RelayTransport::Udp);
-
-    client.join_room(params).expect("join_room");
+    let conn_info = ConnectionInfo::Direct {
+        host: "192.168.0.1".into(),
+        port: 7777,
+    };
+    client
+        .provide_connection_info(conn_info)
+        .expect("provide_connection_info");
 
     wait_for_sent_len(&sent, 2).await;
 
     {
-        let messages = sent.lock().unwrap();
-        let jr_msg = messages.iter().find_map(|m| {
+        let messages = sent.matching(of_type("ProvideConnectionInfo"));
+        assert!(
+            !messages.is_empty(),
+            "expected ProvideConnectionInfo message"
+        );
+
+        // Verify the actual content.
+        let pci_msg = messages.iter().find_map(|m| {
             let cm: ClientMessage = serde_json::from_str(m).ok()?;
-            if let ClientMessage::JoinRoom {
-                game_name,
-                room_code,
-                player_name,
-                max_players,
-                supports_authority,
-                relay_transport,
-            } = cm
-            {
-                Some((
-                    game_name,
-                    room_code,
-                    player_name,
-                    max_players,
-                    supports_authority,
-                    relay_transport,
-                ))
+            if let ClientMessage::ProvideConnectionInfo { connection_info } = cm {
+                Some(connection_info)
             } else {
                 None
             }
         });
-        let (gn, rc, pn, mp, sa, rt) = jr_msg.expect("JoinRoom message not found");
-        assert_eq!(gn, "arena");
-        assert_eq!(rc.as_deref(), Some("ROOM42"));
-        assert_eq!(pn, "Alice");
-        assert_eq!(mp, Some(8));
-        assert_eq!(sa, Some(true));
-        assert!(matches!(rt, Some(RelayTransport::Udp)));
+        let ci = pci_msg.expect("ProvideConnectionInfo not found");
+        if let ConnectionInfo::Direct { host, port } = ci {
+            assert_eq!(host, "192.168.0.1");
+            assert_eq!(port, 7777);
+        } else {
+            panic!("expected Direct connection info");
+        }
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn provide_relay_connection_info() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let conn_info = ConnectionInfo::Relay {
+        host: "relay.example.com".into(),
+        port: 3000,
+        transport: RelayTransport::Tcp,
+        allocation_id: "room-abc".into(),
+        token: "tok-xyz".into(),
+        client_id: Some(5),
+    };
+    client
+        .provide_connection_info(conn_info)
+        .expect("provide_connection_info");
+
+    wait_for_sent_len(&sent, 2).await;
+
+    {
+        let pci_msg = sent
+            .matching(of_type("ProvideConnectionInfo"))
+            .iter()
+            .find_map(|m| {
+                let cm: ClientMessage = serde_json::from_str(m).ok()?;
+                if let ClientMessage::ProvideConnectionInfo { connection_info } = cm {
+                    Some(connection_info)
+                } else {
+                    None
+                }
+            });
+        let ci = pci_msg.expect("ProvideConnectionInfo not found");
+        if let ConnectionInfo::Relay {
+            host,
+            port,
+            transport,
+            token,
+            client_id,
+            ..
+        } = ci
+        {
+            assert_eq!(host, "relay.example.com");
+            assert_eq!(port, 3000);
+            assert!(matches!(transport, RelayTransport::Tcp));
+            assert_eq!(token, "tok-xyz");
+            assert_eq!(client_id, Some(5));
+        } else {
+            panic!("expected Relay connection info");
+        }
     }
 
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Multiple sequential operations
+// JoinAsSpectator + LeaveSpectator message verification
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn multiple_sequential_operations() {
-    let player = uuid::Uuid::from_u128(42);
-    let data_msg = game_data_json(player, serde_json::json!({"tick": 1}));
-    let (mut client, mut events, sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(room_joined_json())),
-        Some(Ok(data_msg)),
-        Some(Ok(pong_json())),
-        Some(Ok(room_left_json())),
-    ]);
+async fn join_as_spectator_sends_correct_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
 
     drain_until_authenticated(&mut events).await;
 
-    // Join room.
     client
-        .join_room(JoinRoomParams::new("game", "Player1"))
-        .expect("join");
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
+        .join_as_spectator("game1".into(), "CODE1".into(), "Viewer".into())
+        .expect("join_as_spectator");
 
-    // Send game data.
-    client
-        .send_game_data(serde_json::json!({"action": "jump"}))
-        .expect("send_game_data");
+    wait_for_sent_len(&sent, 2).await;
 
-    // Receive server game data.
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::GameData { .. }));
+    {
+        let found = sent
+            .matching(of_type("JoinAsSpectator"))
+            .iter()
+            .find_map(|m| {
+                let cm: ClientMessage = serde_json::from_str(m).ok()?;
+                if let ClientMessage::JoinAsSpectator {
+                    game_name,
+                    room_code,
+                    spectator_name,
+                } = cm
+                {
+                    Some((game_name, room_code, spectator_name))
+                } else {
+                    None
+                }
+            });
+        let (gn, rc, sn) = found.expect("JoinAsSpectator not found");
+        assert_eq!(gn, "game1");
+        assert_eq!(rc, "CODE1");
+        assert_eq!(sn, "Viewer");
+    }
 
-    // Ping.
-    client.ping().expect("ping");
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Pong));
+    client.shutdown().await;
+}
 
-    // Leave room.
-    client.leave_room().expect("leave");
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::RoomLeft));
+#[tokio::test]
+async fn leave_spectator_sends_correct_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    client.leave_spectator().expect("leave_spectator");
+
+    wait_for_sent_len(&sent, 2).await;
 
-    // Verify all expected messages were sent.
-    wait_for_sent_len(&sent, 5).await;
     {
-        let messages = sent.lock().unwrap();
-        // Should have: Authenticate, JoinRoom, GameData, Ping, LeaveRoom
-        assert!(
-            messages.len() >= 5,
-            "expected at least 5 messages, got {}",
-            messages.len()
-        );
+        let found = !sent.matching(of_type("LeaveSpectator")).is_empty();
+        assert!(found, "expected LeaveSpectator message");
     }
 
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// PlayerJoined / PlayerLeft events
+// Error event handling
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn player_joined_event_received() {
-    let new_player = uuid::Uuid::from_u128(555);
+async fn server_error_event_received() {
     let (mut client, mut events, _sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(room_joined_json())),
-        Some(Ok(common::player_joined_json("Bob", new_player))),
+        Some(Ok(error_json(
+            "something went wrong",
+            Some(ErrorCode::InternalError),
+        ))),
     ]);
 
     drain_until_authenticated(&mut events).await;
-    let _rj = events.recv().await; // RoomJoined
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::PlayerJoined { player } = ev {
-        assert_eq!(player.name, "Bob");
-        assert_eq!(player.id, new_player);
+    if let SignalFishEvent::Error {
+        message,
+        error_code,
+    } = ev
+    {
+        assert_eq!(message, "something went wrong");
+        assert_eq!(error_code, Some(ErrorCode::InternalError));
     } else {
-        panic!("expected PlayerJoined event, got {ev:?}");
+        panic!("expected Error event, got {ev:?}");
     }
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn player_left_event_received() {
-    let left_player = uuid::Uuid::from_u128(666);
-
+async fn server_error_event_without_error_code() {
     let (mut client, mut events, _sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(room_joined_json())),
-        Some(Ok(player_left_json(left_player))),
+        Some(Ok(error_json("generic error", None))),
     ]);
 
     drain_until_authenticated(&mut events).await;
-    let _rj = events.recv().await; // RoomJoined
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::PlayerLeft {
-        player_id,
-        epoch,
-        final_seq,
+    if let SignalFishEvent::Error {
+        message,
+        error_code,
     } = ev
     {
-        assert_eq!(player_id, left_player);
-        assert!(epoch.is_none());
-        assert!(final_seq.is_none());
+        assert_eq!(message, "generic error");
+        assert!(error_code.is_none());
     } else {
-        panic!("expected PlayerLeft event, got {ev:?}");
+        panic!("expected Error event, got {ev:?}");
     }
 
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// LobbyStateChanged and GameStarting events
+// Announcements
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn lobby_state_changed_event() {
-    let p1 = uuid::Uuid::from_u128(1);
-    let lobby_json = serde_json::to_string(&ServerMessage::LobbyStateChanged {
-        lobby_state: signal_fish_client::protocol::LobbyState::Finalized,
-        ready_players: vec![p1],
-        all_ready: true,
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(lobby_json))]);
+async fn announcement_event_received() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(announcement_json(
+            "Maintenance at noon",
+            Some("warning"),
+        ))),
+    ]);
 
     drain_until_authenticated(&mut events).await;
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::LobbyStateChanged {
-        lobby_state,
-        ready_players,
-        all_ready,
+    if let SignalFishEvent::Announcement {
+        message,
+        severity,
+        metadata,
     } = ev
     {
-        assert!(matches!(
-            lobby_state,
-            signal_fish_client::protocol::LobbyState::Finalized
-        ));
-        assert_eq!(ready_players.len(), 1);
-        assert!(all_ready);
+        assert_eq!(message, "Maintenance at noon");
+        assert_eq!(severity.as_deref(), Some("warning"));
+        assert!(metadata.is_none());
     } else {
-        panic!("expected LobbyStateChanged event, got {ev:?}");
+        panic!("expected Announcement event, got {ev:?}");
     }
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn game_starting_event() {
-    use signal_fish_client::protocol::PeerConnectionInfo;
-
-    let gs_json = serde_json::to_string(&ServerMessage::GameStarting {
-        peer_connections: vec![PeerConnectionInfo {
-            player_id: uuid::Uuid::from_u128(10),
-            player_name: "Peer".into(),
-            is_authority: true,
-            relay_type: "auto".into(),
-            connection_info: None,
-        }],
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(gs_json))]);
+async fn suppressed_announcements_are_not_delivered() {
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(announcement_json("Welcome!", None))),
+            Some(Ok(error_json("after the announcement", None))),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_suppress_announcements(true),
+    );
 
     drain_until_authenticated(&mut events).await;
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::GameStarting { peer_connections } = ev {
-        assert_eq!(peer_connections.len(), 1);
-        assert_eq!(peer_connections[0].player_name, "Peer");
-    } else {
-        panic!("expected GameStarting event, got {ev:?}");
-    }
+    assert!(
+        matches!(&ev, SignalFishEvent::Error { message, .. } if message == "after the announcement"),
+        "expected the announcement to be dropped, got {ev:?}"
+    );
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// AuthorityChanged event
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn authority_changed_event() {
-    let auth_player = uuid::Uuid::from_u128(77);
-    let ac_json = serde_json::to_string(&ServerMessage::AuthorityChanged {
-        authority_player: Some(auth_player),
-        you_are_authority: true,
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(ac_json))]);
+async fn legacy_system_player_game_data_maps_to_announcements() {
+    let system = uuid::Uuid::nil();
+    let player = uuid::Uuid::from_u128(42);
+    let legacy = serde_json::json!({
+        "message": "Finals start in 10 minutes",
+        "severity": "info",
+        "metadata": { "round": 3 },
+    });
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(game_data_json(system, legacy.clone()))),
+            // Same shape from a real player, and another shape from the
+            // system player, both stay game data.
+            Some(Ok(game_data_json(player, legacy))),
+            Some(Ok(game_data_json(system, serde_json::json!({"tick": 1})))),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_legacy_announcement_sender(system),
+    );
 
     drain_until_authenticated(&mut events).await;
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::AuthorityChanged {
-        authority_player,
-        you_are_authority,
+    if let SignalFishEvent::Announcement {
+        message,
+        severity,
+        metadata,
     } = ev
     {
-        assert_eq!(authority_player, Some(auth_player));
-        assert!(you_are_authority);
+        assert_eq!(message, "Finals start in 10 minutes");
+        assert_eq!(severity.as_deref(), Some("info"));
+        assert_eq!(metadata, Some(serde_json::json!({ "round": 3 })));
     } else {
-        panic!("expected AuthorityChanged event, got {ev:?}");
+        panic!("expected Announcement event, got {ev:?}");
+    }
+    for expected_sender in [player, system] {
+        let ev = events.recv().await.expect("event");
+        assert!(
+            matches!(ev, SignalFishEvent::GameData { from_player, .. } if from_player == expected_sender),
+            "expected GameData from {expected_sender}, got {ev:?}"
+        );
     }
 
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// NewSpectatorJoined and SpectatorDisconnected events
+// Disconnect handling
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn new_spectator_joined_event() {
-    let nsj_json = serde_json::to_string(&ServerMessage::NewSpectatorJoined {
-        spectator: signal_fish_client::protocol::SpectatorInfo {
-            id: uuid::Uuid::from_u128(500),
-            name: "NewViewer".into(),
-            connected_at: "2026-01-01T00:00:00Z".into(),
-        },
-        current_spectators: vec![],
-        reason: Some(signal_fish_client::protocol::SpectatorStateChangeReason::Joined),
-    })
-    .expect("serialize");
+async fn describe_error_code_uses_the_configured_formatter() {
+    let formatter = signal_fish_client::ErrorCodeFormatter::new(|code| {
+        (*code == ErrorCode::RoomFull).then(|| "Raum ist voll.".to_string())
+    });
+    let config = SignalFishConfig::new("mb_test").with_error_code_formatter(formatter);
+    let (mut client, _events, _sent, _closed) =
+        start_client_with_config(vec![Some(Ok(authenticated_json()))], config);
+    assert_eq!(
+        client.describe_error_code(&ErrorCode::RoomFull),
+        "Raum ist voll."
+    );
+    assert_eq!(
+        client.describe_error_code(&ErrorCode::RoomNotFound),
+        ErrorCode::RoomNotFound.description()
+    );
+    client.shutdown().await;
+
+    let (mut plain, _events, _sent, _closed) = start_client(vec![]);
+    assert_eq!(
+        plain.describe_error_code(&ErrorCode::RoomFull),
+        ErrorCode::RoomFull.description()
+    );
+    plain.shutdown().await;
+}
 
+#[tokio::test]
+async fn disconnect_on_transport_close() {
     let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(nsj_json))]);
+        start_client(vec![Some(Ok(authenticated_json())), None]);
 
     drain_until_authenticated(&mut events).await;
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::NewSpectatorJoined {
-        spectator, reason, ..
-    } = ev
-    {
-        assert_eq!(spectator.name, "NewViewer");
-        assert!(matches!(
-            reason,
-            Some(signal_fish_client::protocol::SpectatorStateChangeReason::Joined)
-        ));
-    } else {
-        panic!("expected NewSpectatorJoined event, got {ev:?}");
-    }
+    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
+    assert!(!client.is_connected());
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn spectator_disconnected_event() {
-    let sd_json = serde_json::to_string(&ServerMessage::SpectatorDisconnected {
-        spectator_id: uuid::Uuid::from_u128(600),
-        reason: Some(signal_fish_client::protocol::SpectatorStateChangeReason::Disconnected),
-        current_spectators: vec![],
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(sd_json))]);
+async fn disconnect_on_transport_error() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![Some(Err(
+        SignalFishError::TransportReceive("network failure".into()),
+    ))]);
 
-    drain_until_authenticated(&mut events).await;
+    // Connected might still be emitted before the error is processed.
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Connected));
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::SpectatorDisconnected {
-        spectator_id,
-        reason,
-        ..
-    } = ev
-    {
-        assert_eq!(spectator_id, uuid::Uuid::from_u128(600));
-        assert!(matches!(
-            reason,
-            Some(signal_fish_client::protocol::SpectatorStateChangeReason::Disconnected)
-        ));
+    if let SignalFishEvent::Disconnected { reason, .. } = ev {
+        let r = reason.to_string();
+        assert!(r.contains("network failure"), "reason was: {r}");
     } else {
-        panic!("expected SpectatorDisconnected event, got {ev:?}");
+        panic!("expected Disconnected, got {ev:?}");
     }
 
+    assert!(!client.is_connected());
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// ReconnectionFailed and PlayerReconnected events
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn reconnection_failed_event() {
-    let rf_json = serde_json::to_string(&ServerMessage::ReconnectionFailed {
-        reason: "expired".into(),
-        error_code: ErrorCode::ReconnectionExpired,
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(rf_json))]);
+async fn failed_authenticate_send_is_flagged_as_handshake_failure() {
+    let transport = SendFailsAfterTransport::new(0, vec![]);
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
 
-    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Connected));
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::ReconnectionFailed { reason, error_code } = ev {
-        assert_eq!(reason, "expired");
-        assert_eq!(error_code, ErrorCode::ReconnectionExpired);
-    } else {
-        panic!("expected ReconnectionFailed event, got {ev:?}");
-    }
+    let SignalFishEvent::Disconnected { reason, .. } = ev else {
+        panic!("expected Disconnected, got {ev:?}");
+    };
+    let reason = reason.to_string();
+    assert!(reason.contains("during handshake"), "reason was: {reason}");
+    assert!(reason.contains("write failed"), "reason was: {reason}");
+    assert!(client.handshake_failed());
+    assert!(client.snapshot().handshake_failed);
+    assert!(!client.is_connected());
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn player_reconnected_event() {
-    let pr_json = serde_json::to_string(&ServerMessage::PlayerReconnected {
-        player_id: uuid::Uuid::from_u128(700),
-        epoch: None,
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pr_json))]);
+async fn send_failure_after_handshake_is_not_a_handshake_failure() {
+    let transport = SendFailsAfterTransport::new(1, vec![Some(Ok(authenticated_json()))]);
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
 
     drain_until_authenticated(&mut events).await;
+    client.ping().expect("ping queued");
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::PlayerReconnected { player_id, epoch } = ev {
-        assert_eq!(player_id, uuid::Uuid::from_u128(700));
-        assert!(epoch.is_none());
-    } else {
-        panic!("expected PlayerReconnected event, got {ev:?}");
-    }
+    let SignalFishEvent::Disconnected { reason, .. } = ev else {
+        panic!("expected Disconnected, got {ev:?}");
+    };
+    let reason = reason.to_string();
+    assert!(
+        reason.contains("transport send error"),
+        "reason was: {reason}"
+    );
+    assert!(!reason.contains("handshake"), "reason was: {reason}");
+    assert!(!client.handshake_failed());
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// AuthenticationError event
-// ════════════════════════════════════════════════════════════════════
-
-#[tokio::test]
-async fn authentication_error_event() {
-    let ae_json = serde_json::to_string(&ServerMessage::AuthenticationError {
-        error: "bad credentials".into(),
-        error_code: ErrorCode::InvalidAppId,
-    })
-    .expect("serialize");
+// ── SDK version ─────────────────────────────────────────────────────
 
-    let (mut client, mut events, _sent, _closed) = start_client(vec![Some(Ok(ae_json))]);
+fn sent_sdk_version(sent: &SentLog) -> Option<String> {
+    let frames = sent.matching(of_type("Authenticate"));
+    let [frame] = frames.as_slice() else {
+        panic!("expected one Authenticate, got {frames:?}");
+    };
+    match serde_json::from_str(frame).expect("Authenticate decodes") {
+        ClientMessage::Authenticate { sdk_version, .. } => sdk_version,
+        other => panic!("expected Authenticate, got {other:?}"),
+    }
+}
 
-    // Connected is first.
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Connected));
+#[tokio::test]
+async fn sdk_version_with_build_metadata_is_sent_in_authenticate() {
+    let version = SdkVersion::crate_default()
+        .with_build_metadata("gabc123")
+        .expect("valid metadata")
+        .with_channel("beta")
+        .expect("valid channel");
+    let (mut client, mut events, sent, _closed) = start_client_with_config(
+        vec![Some(Ok(authenticated_json()))],
+        SignalFishConfig::new("mb_test_integration").with_sdk_version(version),
+    );
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::AuthenticationError { error, error_code } = ev {
-        assert_eq!(error, "bad credentials");
-        assert_eq!(error_code, ErrorCode::InvalidAppId);
-    } else {
-        panic!("expected AuthenticationError event, got {ev:?}");
-    }
+    drain_until_authenticated(&mut events).await;
+    assert_eq!(
+        sent_sdk_version(&sent),
+        Some(format!("{}+beta.gabc123", env!("CARGO_PKG_VERSION")))
+    );
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// RoomJoinFailed and SpectatorJoinFailed events
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn room_join_failed_event() {
-    let rjf_json = serde_json::to_string(&ServerMessage::RoomJoinFailed {
-        reason: "room full".into(),
-        error_code: Some(ErrorCode::RoomFull),
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(rjf_json))]);
+async fn non_semver_sdk_version_is_sent_as_is_by_default() {
+    let (mut client, mut events, sent, _closed) = start_client_with_config(
+        vec![Some(Ok(authenticated_json()))],
+        SignalFishConfig::new("mb_test_integration").with_sdk_version("build 42 (beta)"),
+    );
 
     drain_until_authenticated(&mut events).await;
-
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::RoomJoinFailed { reason, error_code } = ev {
-        assert_eq!(reason, "room full");
-        assert_eq!(error_code, Some(ErrorCode::RoomFull));
-    } else {
-        panic!("expected RoomJoinFailed event, got {ev:?}");
-    }
+    assert_eq!(sent_sdk_version(&sent).as_deref(), Some("build 42 (beta)"));
 
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn spectator_join_failed_event() {
-    let sjf_json = serde_json::to_string(&ServerMessage::SpectatorJoinFailed {
-        reason: "spectators disabled".into(),
-        error_code: Some(ErrorCode::SpectatorNotAllowed),
-    })
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(sjf_json))]);
-
-    drain_until_authenticated(&mut events).await;
+async fn rejected_sdk_version_ends_the_connection_before_authenticate() {
+    let (mut client, mut events, sent, _closed) = start_client_with_config(
+        vec![Some(Ok(authenticated_json()))],
+        SignalFishConfig::new("mb_test_integration")
+            .with_sdk_version("1.2")
+            .with_sdk_version_policy(SdkVersionPolicy::Reject),
+    );
 
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::SpectatorJoinFailed { reason, error_code } = ev {
-        assert_eq!(reason, "spectators disabled");
-        assert_eq!(error_code, Some(ErrorCode::SpectatorNotAllowed));
-    } else {
-        panic!("expected SpectatorJoinFailed event, got {ev:?}");
-    }
+    assert!(matches!(ev, SignalFishEvent::Connected));
+    let ev = events.recv().await.expect("event");
+    let SignalFishEvent::Disconnected { reason, .. } = ev else {
+        panic!("expected Disconnected, got {ev:?}");
+    };
+    assert_eq!(
+        reason,
+        DisconnectReason::HandshakeRefused {
+            detail: "`1.2` is not a valid SDK version: expected MAJOR.MINOR.PATCH".into()
+        }
+    );
+    assert!(client.handshake_failed());
+    assert!(!client.is_connected());
+    assert_eq!(sent.total(), 0, "nothing reached the wire");
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// ProtocolInfo event
-// ════════════════════════════════════════════════════════════════════
-
-#[tokio::test]
-async fn protocol_info_event() {
-    let pi_json = serde_json::to_string(&ServerMessage::ProtocolInfo(
-        signal_fish_client::protocol::ProtocolInfoPayload {
-            platform: Some("unity".into()),
-            sdk_version: Some("1.0.0".into()),
-            minimum_version: None,
-            recommended_version: None,
-            capabilities: vec!["spectator".into()],
-            notes: None,
-            game_data_formats: vec![GameDataEncoding::Json],
-            player_name_rules: None,
-            protocol_version: None,
-            min_protocol_version: None,
-            max_protocol_version: None,
-            transports: None,
-        },
-    ))
-    .expect("serialize");
-
-    let (mut client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pi_json))]);
+#[tokio::test(start_paused = true)]
+async fn activity_reports_time_since_last_send_and_receive() {
+    use std::time::Duration;
 
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
     drain_until_authenticated(&mut events).await;
-
+    let fresh = client.activity();
+    assert_eq!(fresh.since_last_send, Some(Duration::ZERO));
+    assert_eq!(fresh.since_last_recv, Some(Duration::ZERO));
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    client.ping().expect("ping queued");
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let activity = client.activity();
+    assert_eq!(activity.since_last_send, Some(Duration::from_secs(2)));
+    assert_eq!(activity.since_last_recv, Some(Duration::from_secs(7)));
+
+    // The connection then dies: the reason says which direction went quiet.
+    feed.send(Err(SignalFishError::TransportReceive(
+        "connection reset".into(),
+    )))
+    .unwrap();
     let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::ProtocolInfo(payload) = ev {
-        assert_eq!(payload.platform.as_deref(), Some("unity"));
-        assert_eq!(payload.capabilities, vec!["spectator"]);
-    } else {
-        panic!("expected ProtocolInfo event, got {ev:?}");
-    }
+    let SignalFishEvent::Disconnected { reason, .. } = ev else {
+        panic!("expected Disconnected, got {ev:?}");
+    };
+    let reason = reason.to_string();
+    assert!(
+        reason.ends_with("connection reset (last send 2s ago, last receive 7s ago)"),
+        "{reason}"
+    );
 
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// Shutdown behavior
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn shutdown_closes_transport() {
-    let (mut client, mut events, _sent, closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+async fn operations_fail_after_disconnect() {
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), None]);
 
     drain_until_authenticated(&mut events).await;
 
-    client.shutdown().await;
+    // Wait for Disconnected.
+    let _ev = events.recv().await;
 
-    // The Disconnected event should be emitted.
-    let ev = events.recv().await.expect("event");
-    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
+    let result = client.ping();
+    assert!(
+        matches!(result, Err(SignalFishError::NotConnected)),
+        "expected NotConnected error, got {result:?}"
+    );
 
-    assert!(closed.load(std::sync::atomic::Ordering::Relaxed));
+    client.shutdown().await;
 }
 
+// ════════════════════════════════════════════════════════════════════
+// GameData and GameDataBinary event handling
+// ════════════════════════════════════════════════════════════════════
+
 #[tokio::test]
-async fn shutdown_timeout_clears_state_even_when_disconnected_event_is_skipped() {
-    let transport = HangingCloseTransport::new(vec![
+async fn game_data_event_received() {
+    let player = uuid::Uuid::from_u128(42);
+    let data = serde_json::json!({"score": 100, "level": 5});
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(room_joined_json())),
+        Some(Ok(game_data_json(player, data.clone()))),
     ]);
-    let config = SignalFishConfig::new("mb_test_integration")
-        .with_shutdown_timeout(std::time::Duration::from_millis(1));
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
 
     drain_until_authenticated(&mut events).await;
-    let ev = events.recv().await.expect("expected RoomJoined event");
-    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
 
-    assert!(client.is_authenticated());
-    assert!(client.current_player_id().await.is_some());
-    assert!(client.current_room_id().await.is_some());
-    assert_eq!(client.current_room_code().await.as_deref(), Some("ABC123"));
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::GameData {
+        from_player,
+        data: d,
+        seq,
+        epoch,
+        class,
+        key,
+    } = ev
+    {
+        assert_eq!(from_player, player);
+        assert_eq!(d["score"], 100);
+        assert_eq!(d["level"], 5);
+        assert!(seq.is_none());
+        assert!(epoch.is_none());
+        assert!(class.is_none());
+        assert!(key.is_none());
+    } else {
+        panic!("expected GameData event, got {ev:?}");
+    }
 
     client.shutdown().await;
-
-    assert!(!client.is_connected());
-    assert!(!client.is_authenticated());
-    assert!(client.current_player_id().await.is_none());
-    assert!(client.current_room_id().await.is_none());
-    assert!(client.current_room_code().await.is_none());
 }
 
 #[tokio::test]
-async fn leave_room_sends_leave_room_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
+async fn decode_game_data_reports_failures_to_the_hook() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Score {
+        score: u32,
+    }
 
+    let player = uuid::Uuid::from_u128(42);
+    let failures = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = std::sync::Arc::clone(&failures);
+    let config = SignalFishConfig::new("mb_test_integration").with_decode_failure_hook(
+        DecodeFailureHook::new(move |from, kind| sink.lock().unwrap().push((from, kind))),
+    );
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(game_data_json(player, serde_json::json!({"score": 7})))),
+            Some(Ok(game_data_json(
+                player,
+                serde_json::json!({"score": "x"}),
+            ))),
+        ],
+        config,
+    );
     drain_until_authenticated(&mut events).await;
 
-    client.leave_room().expect("leave_room");
-
-    wait_for_sent_len(&sent, 2).await;
-
-    {
-        let messages = sent.lock().unwrap();
-        let found = messages.iter().any(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .map(|cm| matches!(cm, ClientMessage::LeaveRoom))
-                .unwrap_or(false)
-        });
-        assert!(found, "expected LeaveRoom message");
-    }
-
+    let valid = events.recv().await.expect("event");
+    let score: Score = client.decode_game_data(&valid).expect("valid payload");
+    assert_eq!(score.score, 7);
+    let mismatched = events.recv().await.expect("event");
+    assert!(client.try_decode_game_data::<Score>(&mismatched).is_none());
+    let error = client
+        .decode_game_data::<Score>(&mismatched)
+        .expect_err("mismatched payload");
+    assert_eq!(error.kind(), DecodeErrorKind::Mismatch);
+    // Not game data: an error, but nothing for the hook to attribute.
+    assert!(client
+        .try_decode_game_data::<Score>(&SignalFishEvent::Connected)
+        .is_none());
+
+    assert_eq!(
+        *failures.lock().unwrap(),
+        vec![
+            (player, DecodeErrorKind::Mismatch),
+            (player, DecodeErrorKind::Mismatch)
+        ]
+    );
     client.shutdown().await;
 }
 
-// ════════════════════════════════════════════════════════════════════
-// Malformed JSON resilience
-// ════════════════════════════════════════════════════════════════════
-
 #[tokio::test]
-async fn malformed_json_emits_decode_failed_then_next_message_arrives() {
-    // Send garbled text followed by a valid message. The transport loop
-    // surfaces the invalid frame as a DecodeFailed event (never a silent
-    // drop) and continues.
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok("{{not valid json at all!!!".into())),
-        Some(Ok(pong_json())),
+async fn game_data_binary_event_received() {
+    let player = uuid::Uuid::from_u128(99);
+    let payload = vec![0xCA, 0xFE, 0xBA, 0xBE];
+    let binary = signal_fish_client::protocol::V3BinaryGameDataFrame {
+        from_player: player,
+        encoding: GameDataEncoding::MessagePack,
+        payload: payload.clone(),
+        seq: 1,
+        epoch: 1,
+    };
+    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
+        Some(Ok(TransportFrame::Text(authenticated_json()))),
+        Some(Ok(TransportFrame::Text(protocol_info_json(Some(3))))),
+        Some(Ok(TransportFrame::Text(v3_room_baseline_json(player)))),
+        Some(Ok(TransportFrame::Binary(
+            rmp_serde::to_vec_named(&binary).expect("serialize binary frame"),
+        ))),
     ]);
+    let mut config = SignalFishConfig::new("mb_test_integration").enable_v3();
+    config.game_data_format = Some(GameDataEncoding::MessagePack);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
 
     drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ProtocolInfo(_))
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomJoined { .. })
+    ));
 
-    let ev = events
-        .recv()
-        .await
-        .expect("expected DecodeFailed after malformed JSON");
-    match ev {
-        SignalFishEvent::DecodeFailed {
-            message_type,
-            raw_prefix,
-            ..
-        } => {
-            // Not valid JSON at all → no wire `type` tag recoverable.
-            assert_eq!(message_type.as_deref(), None);
-            assert_eq!(raw_prefix, "{{not valid json at all!!!");
-        }
-        other => panic!("expected DecodeFailed, got {other:?}"),
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::GameDataBinary {
+        from_player,
+        encoding,
+        payload: p,
+        seq,
+        epoch,
+    } = ev
+    {
+        assert_eq!(from_player, player);
+        assert!(matches!(encoding, GameDataEncoding::MessagePack));
+        assert_eq!(p, payload);
+        assert_eq!(seq, Some(1));
+        assert_eq!(epoch, Some(1));
+    } else {
+        panic!("expected GameDataBinary event, got {ev:?}");
     }
 
-    let ev = events
-        .recv()
-        .await
-        .expect("expected Pong after DecodeFailed");
-    assert!(
-        matches!(ev, SignalFishEvent::Pong),
-        "expected Pong event after malformed JSON, got {ev:?}"
-    );
-    assert_eq!(client.stats().messages_undecodable, 1);
-
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn unknown_error_code_string_surfaces_decode_failed_not_silent_drop() {
-    // The core #131-follow-up regression: a server newer than this SDK sends
-    // an Error frame with an error_code string the exhaustive ErrorCode enum
-    // does not know. The whole ServerMessage fails to parse — before 0.7.0 it
-    // was silently dropped (warn! only); now it must surface as DecodeFailed
-    // carrying the wire tag, and the connection must stay open.
-    let unknown_code_frame =
-        r#"{"type":"Error","data":{"message":"evicted","error_code":"FUTURE_CODE_XYZ"}}"#;
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(unknown_code_frame.to_string())),
-        Some(Ok(pong_json())),
-    ]);
-
-    drain_until_authenticated(&mut events).await;
-
-    let ev = events.recv().await.expect("event after unknown error code");
-    match ev {
-        SignalFishEvent::DecodeFailed {
-            message_type,
-            error,
-            raw_prefix,
-        } => {
-            assert_eq!(
-                message_type.as_deref(),
-                Some("Error"),
-                "the wire tag must identify which message type failed"
-            );
-            assert!(
-                error.contains("FUTURE_CODE_XYZ") || error.contains("variant"),
-                "serde error should mention the unknown token: {error}"
-            );
-            assert_eq!(raw_prefix, unknown_code_frame);
+async fn v2_message_pack_binary_event_received() {
+    let player = uuid::Uuid::from_u128(98);
+    let frame = signal_fish_client::protocol::V2BinaryGameDataFrame {
+        from_player: player,
+        encoding: GameDataEncoding::MessagePack,
+        payload: vec![4, 5, 6],
+    };
+    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
+        Some(Ok(TransportFrame::Text(authenticated_json()))),
+        Some(Ok(TransportFrame::Text(protocol_info_json(None)))),
+        Some(Ok(TransportFrame::Binary(
+            rmp_serde::to_vec_named(&frame).expect("serialize v2 binary frame"),
+        ))),
+    ]);
+    let mut config = SignalFishConfig::new("mb_test_integration");
+    config.game_data_format = Some(GameDataEncoding::MessagePack);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ProtocolInfo(_))
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::GameDataBinary {
+            from_player,
+            seq: None,
+            epoch: None,
+            ..
+        }) if from_player == player
+    ));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_mode_physical_binary_policy_matches_polling_client() {
+    for (policy, connected, quarantined) in [
+        (
+            signal_fish_client::ProtocolViolationPolicy::Quarantine,
+            true,
+            true,
+        ),
+        (
+            signal_fish_client::ProtocolViolationPolicy::Disconnect,
+            false,
+            false,
+        ),
+        (
+            signal_fish_client::ProtocolViolationPolicy::Observe,
+            true,
+            false,
+        ),
+    ] {
+        let (transport, _sent, _closed) =
+            MockTransport::new_frames(vec![Some(Ok(TransportFrame::Binary(vec![0xff, 0x00])))]);
+        let config =
+            SignalFishConfig::new("mb_test_integration").with_protocol_violation_policy(policy);
+        let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::Connected)
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(SignalFishEvent::ProtocolViolation { .. })
+        ));
+        if policy == signal_fish_client::ProtocolViolationPolicy::Disconnect {
+            assert!(matches!(
+                events.recv().await,
+                Some(SignalFishEvent::Disconnected { .. })
+            ));
         }
-        other => panic!("expected DecodeFailed, got {other:?}"),
+        assert_eq!(client.is_connected(), connected);
+        assert_eq!(client.snapshot().quarantined, quarantined);
+        client.shutdown().await;
     }
+}
 
-    // Connection unaffected: the next frame still arrives.
-    let ev = events.recv().await.expect("Pong after DecodeFailed");
-    assert!(matches!(ev, SignalFishEvent::Pong));
-    assert!(client.is_connected());
-    assert_eq!(client.stats().messages_undecodable, 1);
-
+#[tokio::test]
+async fn async_observe_advances_valid_wrong_representation_sequence() {
+    let player = uuid::Uuid::from_u128(97);
+    let binary = signal_fish_client::protocol::V3BinaryGameDataFrame {
+        from_player: player,
+        encoding: GameDataEncoding::MessagePack,
+        payload: vec![1, 2, 3],
+        seq: 1,
+        epoch: 1,
+    };
+    let following = ServerMessage::GameData {
+        from_player: player,
+        data: serde_json::json!({"seq": 2}),
+        seq: Some(2),
+        epoch: Some(1),
+        class: Some(signal_fish_client::protocol::DeliveryClass::Reliable),
+        key: None,
+    };
+    let (transport, _sent, _closed) = MockTransport::new_frames(vec![
+        Some(Ok(TransportFrame::Text(authenticated_json()))),
+        Some(Ok(TransportFrame::Text(protocol_info_json(Some(3))))),
+        Some(Ok(TransportFrame::Text(v3_room_baseline_json(player)))),
+        Some(Ok(TransportFrame::Binary(
+            rmp_serde::to_vec_named(&binary).expect("serialize binary fixture"),
+        ))),
+        Some(Ok(TransportFrame::Text(
+            serde_json::to_string(&following).expect("serialize following JSON fixture"),
+        ))),
+    ]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .enable_v3()
+        .with_protocol_violation_policy(signal_fish_client::ProtocolViolationPolicy::Observe);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ProtocolInfo(_))
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomJoined { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ProtocolViolation { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::GameDataBinary { seq: Some(1), .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::GameData { seq: Some(2), .. })
+    ));
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn decode_failed_raw_prefix_is_capped_on_utf8_boundary() {
-    use signal_fish_client::DECODE_FAILED_RAW_PREFIX_MAX;
-
-    // Garbage longer than the cap, with multi-byte characters positioned so a
-    // naive byte cut would split one.
-    let garbage = format!("ここは{}", "é".repeat(600));
-    assert!(garbage.len() > DECODE_FAILED_RAW_PREFIX_MAX);
-
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
+async fn binary_game_data_is_sent_as_a_binary_frame() {
+    let (transport, _sent, _closed) = MockTransport::new(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(garbage.clone())),
+        Some(Ok(protocol_info_json(Some(3)))),
     ]);
+    let sent_binary = std::sync::Arc::clone(&transport.sent_binary);
+    let mut config = SignalFishConfig::new("mb_test_integration").enable_v3();
+    config.game_data_format = Some(GameDataEncoding::MessagePack);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
     drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ProtocolInfo(_))
+    ));
 
-    let ev = events.recv().await.expect("DecodeFailed for garbage");
-    match ev {
-        SignalFishEvent::DecodeFailed { raw_prefix, .. } => {
-            assert!(raw_prefix.len() <= DECODE_FAILED_RAW_PREFIX_MAX);
-            assert!(
-                garbage.starts_with(&raw_prefix),
-                "prefix must be a true prefix of the input"
-            );
-            // String integrity (valid UTF-8) is implied by the type; the cut
-            // landing on a boundary is what this asserts.
-            assert!(!raw_prefix.is_empty());
+    let payload = vec![0x92, 0x01, 0xa2, b'h', b'i'];
+    client.send_binary_game_data(payload.clone()).unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(2), async {
+        while sent_binary.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
         }
-        other => panic!("expected DecodeFailed, got {other:?}"),
-    }
+    })
+    .await
+    .expect("binary frame was never sent");
+
+    assert_eq!(*sent_binary.lock().unwrap(), vec![payload]);
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Disconnected enrichment: last_server_error
+// send_game_data API method
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn disconnected_carries_last_server_error_after_server_close() {
-    // The server's slow-consumer eviction shape: a best-effort Error farewell
-    // followed by a close. The terminal Disconnected must carry the farewell
-    // so the disconnect can be attributed.
-    let (_client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(error_json(
-            "Disconnected as a slow consumer",
-            Some(ErrorCode::SlowConsumer),
-        ))),
-        None, // server closes
-    ]);
+async fn send_game_data_produces_correct_json() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
 
     drain_until_authenticated(&mut events).await;
 
-    let ev = events.recv().await.expect("Error event");
-    assert!(matches!(ev, SignalFishEvent::Error { .. }));
+    let data = serde_json::json!({"type": "chat", "msg": "hello"});
+    client.send_game_data(data.clone()).expect("send_game_data");
 
-    let ev = events.recv().await.expect("Disconnected event");
-    match ev {
-        SignalFishEvent::Disconnected {
-            last_server_error, ..
-        } => {
-            let info = last_server_error.expect("farewell must be attributed");
-            assert_eq!(info.error_code, Some(ErrorCode::SlowConsumer));
-            assert!(info.message.contains("slow consumer"));
-        }
-        other => panic!("expected Disconnected, got {other:?}"),
+    wait_for_sent_len(&sent, 2).await;
+
+    {
+        let gd_msg = sent.matching(of_type("GameData")).iter().find_map(|m| {
+            let cm: ClientMessage = serde_json::from_str(m).ok()?;
+            if let ClientMessage::GameData {
+                data: d,
+                class,
+                key,
+            } = cm
+            {
+                Some((d, class, key))
+            } else {
+                None
+            }
+        });
+        let (d, class, key) = gd_msg.expect("GameData message not found");
+        assert_eq!(d["type"], "chat");
+        assert_eq!(d["msg"], "hello");
+        assert!(class.is_none());
+        assert!(key.is_none());
     }
+
+    client.shutdown().await;
 }
 
 #[tokio::test]
-async fn disconnected_last_server_error_is_none_without_prior_error() {
-    let (_client, mut events, _sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json())), None]);
+async fn outgoing_breakdown_counts_each_kind_when_queued_and_when_sent() {
+    let (mut client, _events, _feed, _sent) = start_fed().await;
+
+    client.ping().expect("ping");
+    for seq in 0..3 {
+        client
+            .send_game_data(serde_json::json!({ "seq": seq }))
+            .expect("send_game_data");
+    }
+    client
+        .join_room(JoinRoomParams::new("arena", "Alice"))
+        .expect("join_room");
+
+    let expected = std::collections::BTreeMap::from([
+        (ClientMessageKind::Authenticate, 1),
+        (ClientMessageKind::JoinRoom, 1),
+        (ClientMessageKind::GameData, 3),
+        (ClientMessageKind::Ping, 1),
+    ]);
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        while client.outgoing_breakdown().sent != expected {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("every queued message is sent");
+    let breakdown = client.outgoing_breakdown();
+    assert_eq!(breakdown.enqueued, expected);
+    assert_eq!(breakdown.sent, expected);
+    assert_eq!(breakdown.sent(ClientMessageKind::LeaveRoom), 0);
+    client.shutdown().await;
+}
 
+#[tokio::test]
+async fn outgoing_breakdown_shows_messages_queued_but_never_sent() {
+    let transport = SendFailsAfterTransport::new(1, vec![Some(Ok(authenticated_json()))]);
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
     drain_until_authenticated(&mut events).await;
 
-    let ev = events.recv().await.expect("Disconnected event");
-    match ev {
-        SignalFishEvent::Disconnected {
-            reason,
-            last_server_error,
-        } => {
-            assert_eq!(reason, None, "bare close has no reason");
-            assert_eq!(last_server_error, None);
+    client
+        .send_game_data(serde_json::json!({ "seq": 0 }))
+        .expect("send_game_data");
+    while let Some(ev) = events.recv().await {
+        if matches!(ev, SignalFishEvent::Disconnected { .. }) {
+            break;
         }
-        other => panic!("expected Disconnected, got {other:?}"),
     }
+
+    let breakdown = client.outgoing_breakdown();
+    assert_eq!(breakdown.enqueued(ClientMessageKind::GameData), 1);
+    assert_eq!(breakdown.sent(ClientMessageKind::GameData), 0);
+    assert_eq!(breakdown.sent(ClientMessageKind::Authenticate), 1);
+    client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Wedged-consumer shutdown: graceful close instead of abort-only
+// set_ready and ping API methods
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn shutdown_completes_gracefully_with_wedged_consumer() {
-    // A consumer that stops draining wedges the transport loop in the event
-    // send. Pre-0.7.0 the shutdown oneshot was starved too, so shutdown()
-    // could only abort the task, leaving the transport unclosed. Now the
-    // event send races the shutdown signal: the loop unblocks, closes the
-    // transport, and shutdown() completes without reaching the abort.
-    let (transport, _sent, closed) = MockTransport::new(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(pong_json())),
-        Some(Ok(pong_json())),
-    ]);
-    let config = SignalFishConfig::new("mb_test_integration")
-        .with_event_channel_capacity(1)
-        .with_shutdown_timeout(std::time::Duration::from_secs(5));
-    let (mut client, events) = SignalFishClient::start(transport, config);
+async fn set_ready_sends_player_ready_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
 
-    // Never drain `events`; give the loop time to wedge on a full channel.
-    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    drain_until_authenticated(&mut events).await;
 
-    let started = std::time::Instant::now();
-    client.shutdown().await;
-    let elapsed = started.elapsed();
+    client.set_ready().expect("set_ready");
 
-    assert!(
-        closed.load(std::sync::atomic::Ordering::Relaxed),
-        "transport must be closed gracefully even with a wedged consumer"
-    );
-    assert!(
-        elapsed < std::time::Duration::from_secs(4),
-        "shutdown must not need the timeout/abort path; took {elapsed:?}"
-    );
-    drop(events);
+    wait_for_sent_len(&sent, 2).await;
+
+    {
+        let found = !sent.matching(of_type("PlayerReady")).is_empty();
+        assert!(found, "expected PlayerReady message");
+    }
+
+    client.shutdown().await;
 }
 
 #[tokio::test]
-async fn wedged_consumer_events_before_shutdown_are_not_lost_when_drained() {
-    // Guards against over-eager abandonment: a consumer that resumes draining
-    // BEFORE shutdown still receives every event, and shutdown then delivers
-    // the terminal Disconnected.
-    let (transport, _sent, closed) =
-        MockTransport::new(vec![Some(Ok(authenticated_json())), Some(Ok(pong_json()))]);
-    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(1);
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
+async fn ping_and_pong_flow() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pong_json()))]);
 
-    // Let the loop wedge against the capacity-1 channel.
-    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    drain_until_authenticated(&mut events).await;
 
-    // Resume draining: everything must arrive in order.
-    let ev = events.recv().await.expect("Connected");
-    assert!(matches!(ev, SignalFishEvent::Connected));
-    let ev = events.recv().await.expect("Authenticated");
-    assert!(matches!(ev, SignalFishEvent::Authenticated { .. }));
-    let ev = events.recv().await.expect("Pong");
+    client.ping().expect("ping");
+
+    let ev = events.recv().await.expect("event");
     assert!(matches!(ev, SignalFishEvent::Pong));
 
+    wait_for_sent_len(&sent, 2).await;
+    {
+        let found = !sent.matching(of_type("Ping")).is_empty();
+        assert!(found, "expected Ping message");
+    }
+
     client.shutdown().await;
-    let ev = events.recv().await.expect("Disconnected");
-    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
-    assert!(closed.load(std::sync::atomic::Ordering::Relaxed));
 }
 
-#[tokio::test]
-async fn shutdown_races_wedged_terminal_disconnect() {
-    // The *terminal* Disconnected — emitted on a transport error, a clean
-    // server close, or a dropped handle — must race the shutdown signal too,
-    // not just the normal per-message event path. The four break-paths share
-    // one helper; this exercises it via a transport receive error that fires
-    // while the event channel is full (cap 1, undrained Connected), so the
-    // terminal delivery blocks. Pre-fix those paths used a blocking emit that
-    // ignored shutdown, pinning shutdown() to its full timeout/abort. A
-    // generous timeout makes the racing path (ms) unmistakable vs blocking (s).
-    let (transport, _sent, closed) = MockTransport::new(vec![Some(Err(
-        SignalFishError::TransportReceive("boom".into()),
-    ))]);
-    let config = SignalFishConfig::new("mb_test_integration")
-        .with_event_channel_capacity(1)
-        .with_shutdown_timeout(std::time::Duration::from_secs(10));
-    // `_events` is bound (not `_`) so the channel stays open and full — that
-    // is what wedges the terminal delivery.
-    let (mut client, _events) = SignalFishClient::start(transport, config);
+#[tokio::test(start_paused = true)]
+async fn latency_stats_summarize_scripted_round_trips() {
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    assert_eq!(client.latency_stats(), None);
 
-    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    for (pings, rtt_ms) in (1..).zip([40, 10, 30, 20, 100]) {
+        client.ping().expect("ping");
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            while client.outgoing_breakdown().sent(ClientMessageKind::Ping) < pings {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("the ping goes out");
+        tokio::time::advance(std::time::Duration::from_millis(rtt_ms)).await;
+        feed.send(Ok(pong_json())).unwrap();
+        let ev = events.recv().await.expect("event");
+        assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+    }
 
-    let started = std::time::Instant::now();
+    let stats = client.latency_stats().expect("five round trips");
+    let ms = std::time::Duration::from_millis;
+    assert_eq!(stats.samples, 5);
+    assert_eq!(stats.last, ms(100));
+    assert_eq!(stats.min, ms(10));
+    assert_eq!(stats.p50, ms(30));
+    assert_eq!(stats.p95, ms(100));
+    assert_eq!(stats.max, ms(100));
+    assert_eq!(stats.jitter, ms(24), "mean absolute deviation from 40 ms");
+    assert_eq!(client.health().rtt_p95, Some(ms(100)));
     client.shutdown().await;
-    let elapsed = started.elapsed();
+}
+
+/// Wait until `pings` `Ping` frames have gone out.
+async fn wait_for_pings_sent(client: &SignalFishClient, pings: u64) {
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        while client.outgoing_breakdown().sent(ClientMessageKind::Ping) < pings {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("the ping goes out");
+}
+
+#[tokio::test(start_paused = true)]
+async fn ping_rtt_returns_the_round_trip_and_still_emits_pong() {
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let ms = std::time::Duration::from_millis;
+
+    let (rtt, ()) = tokio::join!(client.ping_rtt(ms(500)), async {
+        wait_for_pings_sent(&client, 1).await;
+        tokio::time::advance(ms(25)).await;
+        feed.send(Ok(pong_json())).unwrap();
+    });
+
+    assert_eq!(rtt.expect("the pong answers"), ms(25));
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+    assert_eq!(client.snapshot().last_rtt, Some(ms(25)));
+    assert_eq!(client.latency_stats().map(|stats| stats.last), Some(ms(25)));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn concurrent_ping_rtt_callers_each_get_their_own_round_trip() {
+    let (mut client, _events, feed, _sent) = start_fed().await;
+    let ms = std::time::Duration::from_millis;
+
+    // A plain ping goes out first; its pong must not resolve either caller.
+    client.ping().expect("ping");
+    wait_for_pings_sent(&client, 1).await;
+    tokio::time::advance(ms(5)).await;
+
+    let first = client.ping_rtt(ms(500));
+    let second = async {
+        wait_for_pings_sent(&client, 2).await;
+        tokio::time::advance(ms(10)).await;
+        client.ping_rtt(ms(500)).await
+    };
+    let script = async {
+        wait_for_pings_sent(&client, 3).await;
+        tokio::time::advance(ms(20)).await;
+        feed.send(Ok(pong_json())).unwrap();
+        feed.send(Ok(pong_json())).unwrap();
+        tokio::time::sleep(ms(5)).await;
+        feed.send(Ok(pong_json())).unwrap();
+    };
+    let (first, second, ()) = tokio::join!(first, second, script);
+
+    assert_eq!(first.expect("first pong"), ms(30));
+    assert_eq!(second.expect("second pong"), ms(25));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn ping_rtt_times_out_without_a_pong_and_fails_when_the_transport_closes() {
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let ms = std::time::Duration::from_millis;
+
+    let unanswered = client.ping_rtt(ms(100)).await;
     assert!(
-        elapsed < std::time::Duration::from_secs(3),
-        "terminal Disconnected must race shutdown, not block until the abort \
-         timeout; took {elapsed:?}"
+        matches!(unanswered, Err(SignalFishError::Timeout)),
+        "{unanswered:?}"
     );
-    // Graceful shutdown always releases the connection: the transport must be
-    // closed even when shutdown wins the race against the wedged delivery.
+
+    let (closed, ()) = tokio::join!(client.ping_rtt(ms(500)), async {
+        wait_for_pings_sent(&client, 2).await;
+        drop(feed);
+    });
     assert!(
-        closed.load(std::sync::atomic::Ordering::Relaxed),
-        "the transport must be closed on the terminal break-path, not left \
-         open until the task is dropped"
+        matches!(closed, Err(SignalFishError::NotConnected)),
+        "{closed:?}"
     );
+    while events.recv().await.is_some() {}
+    assert!(matches!(
+        client.ping_rtt(ms(100)).await,
+        Err(SignalFishError::NotConnected)
+    ));
+    client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// PlayerJoined with ConnectionInfo::Direct
+// JoinRoom with builder options
 // ════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
-async fn player_joined_with_connection_info_direct() {
-    use signal_fish_client::protocol::PlayerInfo;
+async fn join_room_with_all_options_sends_correct_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
 
-    let new_player = uuid::Uuid::from_u128(777);
-    let pj_json = serde_json::to_string(&ServerMessage::PlayerJoined {
-        player: PlayerInfo {
-            id: new_player,
-            name: "ConnectedPlayer".into(),
-            is_authority: true,
-            is_ready: true,
-            connected_at: "2026-02-20T12:00:00Z".into(),
-            connection_info: Some(ConnectionInfo::Direct {
-                host: "10.0.0.5".into(),
-                port: 5555,
-            }),
-            epoch: None,
-            seq: None,
-        },
-    })
-    .expect("serialize");
+    drain_until_authenticated(&mut events).await;
 
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(room_joined_json())),
-        Some(Ok(pj_json)),
-    ]);
+    let params = JoinRoomParams::new("arena", "Alice")
+        .with_room_code("ROOM42")
+        .with_max_players(8)
+        .with_supports_authority(true)
+        .with_relay_transport(RelayTransport::Udp);
 
-    drain_until_authenticated(&mut events).await;
-    let _rj = events.recv().await; // RoomJoined
+    client.join_room(params).expect("join_room");
 
-    let ev = events.recv().await.expect("event");
-    if let SignalFishEvent::PlayerJoined { player } = ev {
-        assert_eq!(player.name, "ConnectedPlayer");
-        assert_eq!(player.id, new_player);
-        assert!(player.is_authority);
-        assert!(player.is_ready);
-        if let Some(ConnectionInfo::Direct { host, port }) = player.connection_info {
-            assert_eq!(host, "10.0.0.5");
-            assert_eq!(port, 5555);
-        } else {
-            panic!(
-                "expected Direct connection_info, got {:?}",
-                player.connection_info
-            );
-        }
-    } else {
-        panic!("expected PlayerJoined event, got {ev:?}");
+    wait_for_sent_len(&sent, 2).await;
+
+    {
+        let jr_msg = sent.matching(of_type("JoinRoom")).iter().find_map(|m| {
+            let cm: ClientMessage = serde_json::from_str(m).ok()?;
+            if let ClientMessage::JoinRoom {
+                game_name,
+                room_code,
+                player_name,
+                max_players,
+                supports_authority,
+                relay_transport,
+            } = cm
+            {
+                Some((
+                    game_name,
+                    room_code,
+                    player_name,
+                    max_players,
+                    supports_authority,
+                    relay_transport,
+                ))
+            } else {
+                None
+            }
+        });
+        let (gn, rc, pn, mp, sa, rt) = jr_msg.expect("JoinRoom message not found");
+        assert_eq!(gn, "arena");
+        assert_eq!(rc.as_deref(), Some("ROOM42"));
+        assert_eq!(pn, "Alice");
+        assert_eq!(mp, Some(8));
+        assert_eq!(sa, Some(true));
+        assert!(matches!(rt, Some(RelayTransport::Udp)));
     }
 
     client.shutdown().await;
 }
 
 // ════════════════════════════════════════════════════════════════════
-// Protocol v2/v3: start_game, mesh signaling, and the negotiation guard
+// join_room_and_wait
 // ════════════════════════════════════════════════════════════════════
 
-/// Consume events until a `ProtocolInfo` event is observed (which proves the
-/// client processed the negotiation and updated its state).
-async fn drain_until_protocol_info(rx: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) {
-    loop {
-        let ev = rx.recv().await.expect("expected ProtocolInfo event");
-        if matches!(ev, SignalFishEvent::ProtocolInfo(_)) {
-            return;
-        }
-    }
+/// An authenticated client fed by the test.
+async fn start_fed() -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+    SentLog,
+) {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    (client, events, feed, sent)
 }
 
-/// Parse all currently-recorded outgoing messages into `ClientMessage`s.
-///
-/// Every captured frame MUST deserialize cleanly: silently dropping
-/// unparsable frames would let a malformed or unexpected wire shape pass
-/// assertions like "no v3 message reached the wire" that depend on *seeing*
-/// every outgoing message. A parse failure here is a real bug in the client,
-/// so we surface it loudly instead of hiding it.
-fn sent_messages(sent: &std::sync::Arc<std::sync::Mutex<Vec<String>>>) -> Vec<ClientMessage> {
-    sent.lock()
-        .unwrap()
-        .iter()
-        .map(|m| {
-            serde_json::from_str::<ClientMessage>(m)
-                .unwrap_or_else(|e| panic!("outgoing client message must deserialize: {e}\n{m}"))
-        })
-        .collect()
+#[tokio::test]
+async fn join_room_and_wait_resolves_with_the_room_joined_payload() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    let player_id = uuid::Uuid::from_u128(700);
+
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice").with_room_code("WAIT1"),
+            std::time::Duration::from_secs(5),
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(room_joined_json_with("WAIT1", "arena", player_id)))
+                .unwrap();
+        }
+    );
+
+    let room = result.expect("join succeeds");
+    assert_eq!(room.room_code, "WAIT1");
+    assert_eq!(room.player_id, player_id);
+    assert_eq!(sent.matching(of_type("JoinRoom")).len(), 1);
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(&ev, SignalFishEvent::RoomJoined { room_code, .. } if room_code == "WAIT1"),
+        "the event is still delivered: {ev:?}"
+    );
+    assert_eq!(client.session_state(), SessionState::InRoom);
+    client.shutdown().await;
 }
 
 #[tokio::test]
-async fn start_game_sends_start_game_message() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
-    drain_until_authenticated(&mut events).await;
+async fn join_room_and_wait_reports_a_refused_join_with_its_code() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
 
-    client.start_game().expect("start_game");
-    wait_for_sent_len(&sent, 2).await;
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(room_join_failed_json(
+                "room is full",
+                ErrorCode::RoomFull,
+            )))
+            .unwrap();
+        }
+    );
 
-    assert!(sent_messages(&sent)
-        .iter()
-        .any(|m| matches!(m, ClientMessage::StartGame)));
+    let error = result.expect_err("the join is refused");
+    assert!(
+        matches!(
+            &error,
+            SignalFishError::RoomJoinFailed {
+                reason,
+                error_code: Some(ErrorCode::RoomFull),
+            } if reason == "room is full"
+        ),
+        "{error:?}"
+    );
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::RoomJoinFailed { .. }),
+        "{ev:?}"
+    );
+    assert_eq!(client.session_state(), SessionState::Authenticated);
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn start_game_available_on_relay_floor() {
-    // start_game is the universal v2 change — NOT gated behind v3 negotiation.
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
-    drain_until_authenticated(&mut events).await;
-    assert!(client.negotiated_protocol_version().is_none());
+async fn join_room_and_wait_reports_an_unrecognized_code_as_unknown() {
+    let (mut client, _events, feed, sent) = start_fed().await;
 
-    client
-        .start_game()
-        .expect("start_game must work on the relay floor");
-    wait_for_sent_len(&sent, 2).await;
-    assert!(sent_messages(&sent)
-        .iter()
-        .any(|m| matches!(m, ClientMessage::StartGame)));
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(r#"{"type":"RoomJoinFailed","data":{"reason":"region closed","error_code":"SOME_FUTURE_CODE"}}"#.to_string()))
+                .unwrap();
+        }
+    );
+
+    let error = result.expect_err("the join is refused");
+    assert!(
+        matches!(
+            &error,
+            SignalFishError::RoomJoinFailed {
+                reason,
+                error_code: Some(ErrorCode::Unknown(code)),
+            } if reason == "region closed" && code == "SOME_FUTURE_CODE"
+        ),
+        "{error:?}"
+    );
+    assert_eq!(client.stats().messages_undecodable, 0);
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn send_signal_before_v3_returns_protocol_unsupported() {
-    let (mut client, mut events, sent, _closed) =
-        start_client(vec![Some(Ok(authenticated_json()))]);
-    drain_until_authenticated(&mut events).await;
+async fn join_room_and_wait_reports_a_server_error() {
+    let (mut client, _events, feed, sent) = start_fed().await;
 
-    // Authenticated but no `ProtocolInfo` yet → negotiation is still in flight,
-    // so the guard reports "pre-negotiation" (NOT "relay-only", which is
-    // reserved for a `ProtocolInfo` that resolved at the v2 floor — see
-    // `v2_protocol_info_keeps_relay_floor_guard`).
-    let err = client
-        .send_signal(uuid::Uuid::from_u128(2), PeerSignal::Offer("sdp".into()))
-        .expect_err("send_signal must fail before negotiation completes");
-    assert!(matches!(
-        err,
-        SignalFishError::ProtocolUnsupported {
-            mode: "pre-negotiation"
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            feed.send(Ok(error_json(
+                "bad game name",
+                Some(ErrorCode::InvalidInput),
+            )))
+            .unwrap();
         }
-    ));
-    // report_transport_status fails fast too.
-    assert!(matches!(
-        client.report_transport_status(TransportKind::WebRtc, true),
-        Err(SignalFishError::ProtocolUnsupported { .. })
-    ));
+    );
 
-    // No v3 message ever reached the wire.
-    assert!(!sent_messages(&sent).iter().any(|m| matches!(
-        m,
-        ClientMessage::Signal { .. } | ClientMessage::TransportStatus { .. }
-    )));
+    let error = result.expect_err("the server errors");
+    assert!(
+        matches!(
+            &error,
+            SignalFishError::ServerError {
+                error_code: Some(ErrorCode::InvalidInput),
+                ..
+            }
+        ),
+        "{error:?}"
+    );
     client.shutdown().await;
 }
 
-#[tokio::test]
-async fn send_signal_after_v3_negotiation_is_sent() {
-    let peer = uuid::Uuid::from_u128(2);
-    let (mut client, mut events, sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(Some(3)))),
-    ]);
-    drain_until_authenticated(&mut events).await;
-    drain_until_protocol_info(&mut events).await;
-    assert_eq!(client.negotiated_protocol_version(), Some(3));
-
-    client.send_offer(peer, "the-sdp").expect("send_offer");
-    wait_for_sent_len(&sent, 2).await;
+#[tokio::test(start_paused = true)]
+async fn join_room_and_wait_times_out_and_a_late_answer_still_applies() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
 
-    let signal = sent_messages(&sent).into_iter().find_map(|m| match m {
-        ClientMessage::Signal { to, signal } if to == peer => Some(signal),
-        _ => None,
-    });
-    assert_eq!(signal, Some(serde_json::json!({ "Offer": "the-sdp" })));
+    let error = client
+        .join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(2),
+        )
+        .await
+        .expect_err("no answer arrives");
+    assert!(matches!(error, SignalFishError::Timeout), "{error:?}");
+    assert_eq!(sent.matching(of_type("JoinRoom")).len(), 1);
+
+    feed.send(Ok(room_joined_json_with(
+        "LATE1",
+        "arena",
+        uuid::Uuid::from_u128(701),
+    )))
+    .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    assert_eq!(client.session_state(), SessionState::InRoom);
+    assert_eq!(client.current_room_code().await.as_deref(), Some("LATE1"));
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn report_transport_status_after_v3_is_sent() {
-    let (mut client, mut events, sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(Some(3)))),
-    ]);
-    drain_until_authenticated(&mut events).await;
-    drain_until_protocol_info(&mut events).await;
+async fn join_room_and_wait_fails_when_the_connection_ends() {
+    let (client, _events, feed, sent) = start_fed().await;
 
-    client
-        .report_transport_status(TransportKind::WebRtc, true)
-        .expect("report_transport_status");
-    wait_for_sent_len(&sent, 2).await;
-    assert!(sent_messages(&sent).iter().any(|m| matches!(
-        m,
-        ClientMessage::TransportStatus {
-            transport: TransportKind::WebRtc,
-            connected: true
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(5)
+        ),
+        async {
+            wait_for_sent_len(&sent, 2).await;
+            drop(feed);
         }
-    )));
-    client.shutdown().await;
+    );
+
+    let error = result.expect_err("the connection ends first");
+    assert!(matches!(error, SignalFishError::NotConnected), "{error:?}");
 }
 
+// ════════════════════════════════════════════════════════════════════
+// Cancelling awaitable calls
+// ════════════════════════════════════════════════════════════════════
+
 #[tokio::test]
-async fn v2_protocol_info_keeps_relay_floor_guard() {
-    // A v2 ProtocolInfo (no version field) leaves negotiated version None, so v3
-    // sends still fail fast — and because a `ProtocolInfo` *did* arrive, the
-    // guard reports the terminal "relay-only" mode (contrast
-    // `send_signal_before_v3_returns_protocol_unsupported`, which is
-    // "pre-negotiation" because no `ProtocolInfo` has arrived).
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(None))),
-    ]);
-    drain_until_authenticated(&mut events).await;
-    drain_until_protocol_info(&mut events).await;
-    assert!(client.negotiated_protocol_version().is_none());
-    assert!(matches!(
-        client.send_offer(uuid::Uuid::from_u128(2), "x"),
-        Err(SignalFishError::ProtocolUnsupported { mode: "relay-only" })
+async fn join_room_and_wait_dropped_after_its_answer_lets_a_later_call_wait() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+
+    let mut join = Box::pin(client.join_room_and_wait(
+        JoinRoomParams::new("arena", "Alice"),
+        std::time::Duration::from_secs(5),
     ));
+    tokio::select! {
+        biased;
+        result = &mut join => panic!("no answer yet: {result:?}"),
+        () = wait_for_sent_len(&sent, 2) => {}
+    }
+    feed.send(Ok(room_join_failed_json(
+        "room is full",
+        ErrorCode::RoomFull,
+    )))
+    .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::RoomJoinFailed { .. }),
+        "{ev:?}"
+    );
+    // The answer reached the waiter, but the caller never reads it.
+    drop(join);
+
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice").with_room_code("NEXT1"),
+            std::time::Duration::from_secs(5),
+        ),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            feed.send(Ok(room_joined_json_with(
+                "NEXT1",
+                "arena",
+                uuid::Uuid::from_u128(710),
+            )))
+            .unwrap();
+        }
+    );
+    assert_eq!(result.expect("the later join succeeds").room_code, "NEXT1");
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn v3_session_plan_and_signal_events_are_emitted() {
-    let peer = uuid::Uuid::from_u128(7);
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(Some(3)))),
-        Some(Ok(session_plan_json(peer, true))),
-        Some(Ok(signal_json(
-            peer,
-            serde_json::json!({ "Offer": "remote-sdp" }),
-        ))),
-    ]);
-    drain_until_authenticated(&mut events).await;
+async fn join_room_and_wait_dropped_before_its_answer_lets_a_later_call_wait() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+
+    tokio::select! {
+        result = client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice"),
+            std::time::Duration::from_secs(5),
+        ) => panic!("no answer yet: {result:?}"),
+        () = wait_for_sent_len(&sent, 2) => {}
+    }
+    // Arrives with no one waiting: applied and delivered as an event only.
+    feed.send(Ok(room_join_failed_json(
+        "room is full",
+        ErrorCode::RoomFull,
+    )))
+    .unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(ev, SignalFishEvent::RoomJoinFailed { .. }),
+        "{ev:?}"
+    );
 
-    let mut saw_plan = false;
-    let mut saw_signal = false;
-    while !(saw_plan && saw_signal) {
-        match events.recv().await.expect("event") {
-            SignalFishEvent::SessionPlan {
-                topology,
-                transport,
-                peers,
-                fallback,
-                ..
-            } => {
-                assert!(matches!(topology, signal_fish_client::Topology::Mesh));
-                assert!(matches!(transport, TransportKind::WebRtc));
-                assert!(matches!(fallback, TransportKind::Relay));
-                assert_eq!(peers.len(), 1);
-                assert_eq!(peers[0].player_id, peer);
-                assert!(peers[0].initiate);
-                saw_plan = true;
-            }
-            SignalFishEvent::SignalReceived { from, signal } => {
-                assert_eq!(from, peer);
-                assert_eq!(
-                    PeerSignal::try_from(&signal).expect("typed signal"),
-                    PeerSignal::Offer("remote-sdp".into())
-                );
-                saw_signal = true;
-            }
-            _ => {}
+    let (result, ()) = tokio::join!(
+        client.join_room_and_wait(
+            JoinRoomParams::new("arena", "Alice").with_room_code("NEXT2"),
+            std::time::Duration::from_secs(5),
+        ),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            feed.send(Ok(room_joined_json_with(
+                "NEXT2",
+                "arena",
+                uuid::Uuid::from_u128(711),
+            )))
+            .unwrap();
         }
-    }
+    );
+    assert_eq!(result.expect("the later join succeeds").room_code, "NEXT2");
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn new_peer_and_peer_transport_status_events_are_emitted() {
-    let peer = uuid::Uuid::from_u128(8);
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(new_peer_json(peer, true))),
-        Some(Ok(peer_transport_status_json(peer, true))),
-    ]);
-    drain_until_authenticated(&mut events).await;
+async fn request_authority_and_wait_dropped_mid_wait_keeps_later_requests_in_line() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
 
-    let mut saw_new_peer = false;
-    let mut saw_status = false;
-    while !(saw_new_peer && saw_status) {
-        match events.recv().await.expect("event") {
-            SignalFishEvent::NewPeer {
-                peer_id,
-                you_initiate,
-            } => {
-                assert_eq!(peer_id, peer);
-                assert!(you_initiate);
-                saw_new_peer = true;
-            }
-            SignalFishEvent::PeerTransportStatus {
-                peer_id,
-                transport,
-                connected,
-            } => {
-                assert_eq!(peer_id, peer);
-                assert!(matches!(transport, TransportKind::WebRtc));
-                assert!(connected);
-                saw_status = true;
-            }
-            _ => {}
+    tokio::select! {
+        grant = client.request_authority_and_wait(true, std::time::Duration::from_secs(5)) => {
+            panic!("no answer yet: {grant:?}")
         }
+        () = wait_for_sent_len(&sent, 2) => {}
     }
+    let (grant, ()) = tokio::join!(
+        client.request_authority_and_wait(true, std::time::Duration::from_secs(5)),
+        async {
+            wait_for_sent_len(&sent, 3).await;
+            feed.send(Ok(authority_response_json(false, Some("dropped"))))
+                .unwrap();
+            feed.send(Ok(authority_response_json(true, Some("current"))))
+                .unwrap();
+        }
+    );
+
+    let grant = grant.expect("the later request is answered");
+    assert_eq!(grant.reason.as_deref(), Some("current"));
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(&ev, SignalFishEvent::AuthorityResponse { reason, .. } if reason.as_deref() == Some("dropped")),
+        "the abandoned answer is still delivered: {ev:?}"
+    );
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn unknown_server_message_type_surfaces_decode_failed_then_next_arrives() {
-    // Forward-compat: a well-formed but unknown `type` surfaces as a
-    // DecodeFailed event carrying the wire tag, and the following valid
-    // message still arrives.
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
-        Some(Ok(authenticated_json())),
-        Some(Ok(r#"{"type":"SomeFutureV4Message","data":{}}"#.to_string())),
-        Some(Ok(pong_json())),
-    ]);
+async fn reliable_send_dropped_mid_select_queues_nothing() {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(
+            SignalFishConfig::new("mb_test_integration")
+                .with_event_channel_capacity(1)
+                .with_command_channel_capacity(1),
+        ),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
     drain_until_authenticated(&mut events).await;
-    let ev = events.recv().await.expect("event after unknown type");
-    match ev {
-        SignalFishEvent::DecodeFailed { message_type, .. } => {
-            assert_eq!(message_type.as_deref(), Some("SomeFutureV4Message"));
+
+    // Stall the loop on a full event channel until the command queue fills.
+    let from = uuid::Uuid::from_u128(720);
+    for n in 0..2 {
+        feed.send(Ok(game_data_json(from, serde_json::json!({ "n": n }))))
+            .unwrap();
+    }
+    let mut queued = 0;
+    while client
+        .send_game_data(serde_json::json!({ "queued": queued }))
+        .is_ok()
+    {
+        queued += 1;
+        assert!(queued < 1000, "the command queue never filled");
+        tokio::task::yield_now().await;
+    }
+
+    tokio::select! {
+        result = client.send_game_data_reliable(serde_json::json!({ "dropped": true })) => {
+            panic!("the queue is full: {result:?}")
         }
-        other => panic!("expected DecodeFailed, got {other:?}"),
+        () = tokio::time::sleep(std::time::Duration::from_millis(20)) => {}
     }
-    let ev = events.recv().await.expect("event after DecodeFailed");
-    assert!(matches!(ev, SignalFishEvent::Pong));
-    assert!(client.is_connected());
+
+    for _ in 0..2 {
+        let ev = events.recv().await.expect("event");
+        assert!(matches!(ev, SignalFishEvent::GameData { .. }), "{ev:?}");
+    }
+    client
+        .send_game_data_reliable(serde_json::json!({ "last": true }))
+        .await
+        .expect("the queue drains");
+    wait_for_sent_len(&sent, 1 + queued + 1).await;
+
+    let game_data = sent.matching(of_type("GameData"));
+    assert!(
+        game_data.iter().all(|frame| !frame.contains("dropped")),
+        "{game_data:?}"
+    );
+    assert!(
+        game_data.last().is_some_and(|frame| frame.contains("last")),
+        "{game_data:?}"
+    );
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn send_signal_before_authentication_is_pre_negotiation() {
-    // The `mode: "pre-negotiation"` branch of the guard: no auth scripted, so the
-    // client is connected but has not authenticated/negotiated.
-    let (mut client, _events, sent, _closed) = start_client(vec![]);
-
-    let err = client
-        .send_offer(uuid::Uuid::from_u128(2), "sdp")
-        .expect_err("send before negotiation must fail");
-    assert!(matches!(
-        err,
-        SignalFishError::ProtocolUnsupported {
-            mode: "pre-negotiation"
+async fn loop_loses_no_command_or_frame_when_its_select_arms_race() {
+    let (mut client, mut events, feed, sent) = start_fed().await;
+    let from = uuid::Uuid::from_u128(721);
+
+    let sends = async {
+        for n in 0..100 {
+            feed.send(Ok(game_data_json(from, serde_json::json!({ "n": n }))))
+                .unwrap();
+            client
+                .send_game_data_reliable(serde_json::json!({ "n": n }))
+                .await
+                .expect("send");
         }
-    ));
-    assert!(!client.supports_mesh());
-    assert!(sent_messages(&sent)
+    };
+    let receives = async {
+        let mut received = Vec::new();
+        while received.len() < 100 {
+            if let SignalFishEvent::GameData { data, .. } = events.recv().await.expect("event") {
+                received.push(data["n"].as_u64().expect("n"));
+            }
+        }
+        received
+    };
+    let ((), received) = tokio::join!(sends, receives);
+
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+    wait_for_sent_len(&sent, 101).await;
+    let sent_n: Vec<_> = sent
+        .matching(of_type("GameData"))
         .iter()
-        .all(|m| !matches!(m, ClientMessage::Signal { .. })));
+        .map(|frame| {
+            let message: serde_json::Value = serde_json::from_str(frame).expect("json");
+            message["data"]["data"]["n"].as_u64().expect("n")
+        })
+        .collect();
+    assert_eq!(sent_n, (0..100).collect::<Vec<_>>());
     client.shutdown().await;
 }
 
+// ════════════════════════════════════════════════════════════════════
+// Multiple sequential operations
+// ════════════════════════════════════════════════════════════════════
+
 #[tokio::test]
-async fn negotiated_version_resets_on_disconnect() {
-    // Note: the scripted clean-close races the mid-stream state, so we assert the
-    // post-disconnect state only. The full negotiate-then-reset cycle is proven
-    // deterministically by the polling client's synchronous equivalent.
-    let (mut client, mut events, _sent, _closed) = start_client(vec![
+async fn multiple_sequential_operations() {
+    let player = uuid::Uuid::from_u128(42);
+    let data_msg = game_data_json(player, serde_json::json!({"tick": 1}));
+    let (mut client, mut events, sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(Some(3)))),
-        None, // clean close
+        Some(Ok(room_joined_json())),
+        Some(Ok(data_msg)),
+        Some(Ok(pong_json())),
+        Some(Ok(room_left_json())),
     ]);
+
     drain_until_authenticated(&mut events).await;
 
-    // Drain until the transport closes (clear_session_state runs before the
-    // Disconnected event is delivered).
-    loop {
-        match events.recv().await {
-            Some(SignalFishEvent::Disconnected { .. }) | None => break,
-            _ => {}
-        }
+    // Join room.
+    client
+        .join_room(JoinRoomParams::new("game", "Player1"))
+        .expect("join");
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
+
+    // Send game data.
+    client
+        .send_game_data(serde_json::json!({"action": "jump"}))
+        .expect("send_game_data");
+
+    // Receive server game data.
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::GameData { .. }));
+
+    // Ping.
+    client.ping().expect("ping");
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Pong));
+
+    // Leave room.
+    client.leave_room().expect("leave");
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomLeft));
+
+    // Verify all expected messages were sent.
+    wait_for_sent_len(&sent, 5).await;
+    for kind in ["Authenticate", "JoinRoom", "GameData", "Ping", "LeaveRoom"] {
+        assert!(
+            !sent.matching(of_type(kind)).is_empty(),
+            "expected a {kind} message, the latest were: {:?}",
+            sent.latest(5)
+        );
     }
-    assert_eq!(client.negotiated_protocol_version(), None);
-    assert!(!client.supports_mesh());
-    assert!(client.send_offer(uuid::Uuid::from_u128(2), "x").is_err());
+
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn reconnect_restores_negotiated_version_from_missed_events() {
-    let peer = uuid::Uuid::from_u128(2);
-    let (mut client, mut events, sent, _closed) = start_client_with_config(
-        vec![
-            Some(Ok(authenticated_json())),
-            Some(Ok(reconnected_with_protocol_info_json(Some(3)))),
-        ],
-        SignalFishConfig::new("mb_test_integration").enable_mesh(),
-    );
+async fn long_running_client_keeps_the_sent_log_bounded() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
     drain_until_authenticated(&mut events).await;
-    // Drain until the Reconnected event (state updated by then).
-    loop {
-        if matches!(
-            events.recv().await.expect("event"),
-            SignalFishEvent::Reconnected { .. }
-        ) {
-            break;
-        }
+
+    // Four times the capacity: the log must evict rather than grow.
+    let pings: u64 = 4096;
+    for count in 1..=pings {
+        client.ping().expect("ping");
+        wait_for_sent_len(&sent, count + 1).await;
+        assert!(sent.len() <= SentLog::DEFAULT_CAPACITY);
     }
-    assert_eq!(client.negotiated_protocol_version(), Some(3));
-    assert!(client.supports_mesh());
+    assert_eq!(sent.total(), pings + 1);
+    assert_eq!(sent.len(), SentLog::DEFAULT_CAPACITY);
+    assert!(
+        sent.matching(of_type("Authenticate")).is_empty(),
+        "the oldest frame should have been evicted"
+    );
+    assert_eq!(sent.latest(3).len(), 3);
 
-    client
-        .send_offer(peer, "sdp")
-        .expect("send_offer after reconnect");
-    wait_for_sent_len(&sent, 2).await;
-    assert!(sent_messages(&sent)
-        .iter()
-        .any(|m| matches!(m, ClientMessage::Signal { .. })));
     client.shutdown().await;
 }
 
+// ════════════════════════════════════════════════════════════════════
+// PlayerJoined / PlayerLeft events
+// ════════════════════════════════════════════════════════════════════
+
 #[tokio::test]
-async fn v4_negotiation_still_enables_mesh() {
-    // `>= 3` (not `== 3`) semantics: a future v4 negotiation must still enable mesh.
-    let (mut client, mut events, _sent, _closed) = start_client_with_config(
-        vec![
-            Some(Ok(authenticated_json())),
-            Some(Ok(protocol_info_json(Some(4)))),
-        ],
-        SignalFishConfig::new("mb_test_integration").enable_mesh(),
-    );
+async fn player_joined_event_received() {
+    let new_player = uuid::Uuid::from_u128(555);
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_json())),
+        Some(Ok(common::player_joined_json("Bob", new_player))),
+    ]);
+
     drain_until_authenticated(&mut events).await;
-    drain_until_protocol_info(&mut events).await;
-    assert_eq!(client.negotiated_protocol_version(), Some(4));
-    assert!(client.supports_mesh());
-    client
-        .send_offer(uuid::Uuid::from_u128(2), "sdp")
-        .expect("v4 must enable mesh");
+    let _rj = events.recv().await; // RoomJoined
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::PlayerJoined { player } = ev {
+        assert_eq!(player.name, "Bob");
+        assert_eq!(player.id, new_player);
+    } else {
+        panic!("expected PlayerJoined event, got {ev:?}");
+    }
+
     client.shutdown().await;
 }
 
 #[tokio::test]
-async fn send_answer_ice_and_raw_signal_wire_shapes() {
-    let peer = uuid::Uuid::from_u128(5);
-    let (mut client, mut events, sent, _closed) = start_client(vec![
+async fn player_left_event_received() {
+    let left_player = uuid::Uuid::from_u128(666);
+
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
         Some(Ok(authenticated_json())),
-        Some(Ok(protocol_info_json(Some(3)))),
+        Some(Ok(room_joined_json())),
+        Some(Ok(player_left_json(left_player))),
     ]);
+
     drain_until_authenticated(&mut events).await;
-    drain_until_protocol_info(&mut events).await;
+    let _rj = events.recv().await; // RoomJoined
 
-    client.send_answer(peer, "ans").expect("send_answer");
-    client
-        .send_ice_candidate(peer, "cand")
-        .expect("send_ice_candidate");
-    client
-        .send_raw_signal(peer, serde_json::json!({ "Renegotiate": true }))
-        .expect("send_raw_signal");
-    wait_for_sent_len(&sent, 4).await;
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::PlayerLeft {
+        player_id,
+        epoch,
+        final_seq,
+    } = ev
+    {
+        assert_eq!(player_id, left_player);
+        assert!(epoch.is_none());
+        assert!(final_seq.is_none());
+    } else {
+        panic!("expected PlayerLeft event, got {ev:?}");
+    }
 
-    let signals: Vec<serde_json::Value> = sent_messages(&sent)
-        .into_iter()
-        .filter_map(|m| match m {
-            ClientMessage::Signal { to, signal } if to == peer => Some(signal),
-            _ => None,
-        })
-        .collect();
-    assert!(signals.contains(&serde_json::json!({ "Answer": "ans" })));
-    assert!(signals.contains(&serde_json::json!({ "IceCandidate": "cand" })));
-    // The raw escape hatch forwards an opaque value verbatim.
-    assert!(signals.contains(&serde_json::json!({ "Renegotiate": true })));
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Server-initiated removal: PlayerLeft naming this client
+// ════════════════════════════════════════════════════════════════════
+
+/// `room_joined_json()` joins as player 42.
+fn own_player_left_json() -> String {
+    player_left_json(uuid::Uuid::from_u128(42))
+}
+
+fn start_self_removal_client(policy: SelfRemovalPolicy, tail: Vec<String>) -> StartedClient {
+    let incoming = [
+        authenticated_json(),
+        room_joined_json(),
+        own_player_left_json(),
+    ]
+    .into_iter()
+    .chain(tail)
+    .map(|json| Some(Ok(json)))
+    .collect();
+    start_client_with_config(
+        incoming,
+        SignalFishConfig::new("mb_test_integration").with_self_removal_policy(policy),
+    )
+}
+
+#[tokio::test(start_paused = true)]
+async fn own_player_left_clears_room_after_grace_by_default() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_json())),
+        Some(Ok(own_player_left_json())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let _rj = events.recv().await; // RoomJoined
+    let ev = events.recv().await.expect("PlayerLeft");
+    assert!(matches!(ev, SignalFishEvent::PlayerLeft { .. }), "{ev:?}");
+    let left_at = tokio::time::Instant::now();
+
+    let ev = events.recv().await.expect("RemovedFromRoom");
+    match ev {
+        SignalFishEvent::RemovedFromRoom { room_id, room_code } => {
+            assert_eq!(room_id, uuid::Uuid::nil());
+            assert_eq!(room_code, "ABC123");
+        }
+        other => panic!("expected RemovedFromRoom, got {other:?}"),
+    }
+    assert!(
+        left_at.elapsed() >= std::time::Duration::from_millis(500),
+        "waited for the default grace period"
+    );
+    assert_eq!(client.current_room_id().await, None);
+    assert_eq!(client.current_room_code().await, None);
+    assert_eq!(client.session_state(), SessionState::Authenticated);
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn own_player_left_then_room_left_is_reported_once() {
+    let (mut client, mut events, _sent, _closed) = start_self_removal_client(
+        SelfRemovalPolicy::default(),
+        vec![room_left_json(), pong_json()],
+    );
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "PlayerLeft",
+            "RoomLeft"
+        ]
+    );
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    assert!(
+        events.try_recv().is_err(),
+        "no RemovedFromRoom after RoomLeft"
+    );
+    assert_eq!(client.current_room_id().await, None);
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn own_player_left_with_zero_grace_swallows_a_late_room_left() {
+    let (mut client, mut events, _sent, _closed) = start_self_removal_client(
+        SelfRemovalPolicy::ClearAfter {
+            grace: std::time::Duration::ZERO,
+        },
+        vec![room_left_json(), pong_json()],
+    );
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "PlayerLeft",
+            "RemovedFromRoom"
+        ]
+    );
+    assert_eq!(client.current_room_code().await, None);
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn await_room_left_policy_warns_and_keeps_room_until_room_left() {
+    let (mut client, mut events, _sent, _closed) =
+        start_self_removal_client(SelfRemovalPolicy::AwaitRoomLeft, vec![pong_json()]);
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "PlayerLeft",
+            "SelfRemovalPending"
+        ]
+    );
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    assert!(events.try_recv().is_err(), "nothing cleared on a timer");
+    assert_eq!(client.current_room_code().await.as_deref(), Some("ABC123"));
+    assert_eq!(client.session_state(), SessionState::InRoom);
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn await_room_left_policy_clears_when_room_left_arrives() {
+    let (mut client, mut events, _sent, _closed) = start_self_removal_client(
+        SelfRemovalPolicy::AwaitRoomLeft,
+        vec![room_left_json(), pong_json()],
+    );
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "PlayerLeft",
+            "SelfRemovalPending",
+            "RoomLeft"
+        ]
+    );
+    assert_eq!(client.current_room_id().await, None);
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Room-scoped messages naming another room
+// ════════════════════════════════════════════════════════════════════
+
+/// Joined `ABC123`, then told about leaving spectator mode in `SPEC1`.
+fn start_inconsistency_client(policy: SessionInconsistencyPolicy) -> StartedClient {
+    let incoming = [
+        authenticated_json(),
+        room_joined_json(),
+        spectator_left_json(),
+        pong_json(),
+    ]
+    .into_iter()
+    .map(|json| Some(Ok(json)))
+    .collect();
+    start_client_with_config(
+        incoming,
+        SignalFishConfig::new("mb_test_integration").with_session_inconsistency_policy(policy),
+    )
+}
+
+#[tokio::test]
+async fn mismatched_spectator_left_warns_then_applies_by_default() {
+    let (mut client, mut events, _sent, _closed) =
+        start_inconsistency_client(SessionInconsistencyPolicy::default());
+    drain_until_authenticated(&mut events).await;
+    let _rj = events.recv().await; // RoomJoined
+    match events.recv().await.expect("SessionInconsistency") {
+        SignalFishEvent::SessionInconsistency {
+            expected,
+            got,
+            message_kind,
+        } => {
+            assert_eq!(expected.room_id, Some(uuid::Uuid::nil()));
+            assert_eq!(expected.room_code.as_deref(), Some("ABC123"));
+            assert_eq!(got.room_id, Some(uuid::Uuid::from_u128(300)));
+            assert_eq!(got.room_code.as_deref(), Some("SPEC1"));
+            assert_eq!(message_kind, "SpectatorLeft");
+        }
+        other => panic!("expected SessionInconsistency, got {other:?}"),
+    }
+    assert_eq!(recv_until_pong(&mut events).await, ["SpectatorLeft"]);
+    assert_eq!(client.current_room_id().await, None);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn ignore_policy_drops_the_mismatched_message() {
+    let (mut client, mut events, _sent, _closed) =
+        start_inconsistency_client(SessionInconsistencyPolicy::Ignore);
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "SessionInconsistency"
+        ]
+    );
+    assert_eq!(client.current_room_code().await.as_deref(), Some("ABC123"));
+    assert_eq!(client.session_state(), SessionState::InRoom);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn resync_policy_closes_the_connection() {
+    let (_client, mut events, _sent, closed) =
+        start_inconsistency_client(SessionInconsistencyPolicy::Resync);
+    let mut names = Vec::new();
+    let mut reason = None;
+    while let Some(event) = events.recv().await {
+        names.push(format!("{event:?}"));
+        if let SignalFishEvent::Disconnected { reason: r, .. } = event {
+            reason = Some(r);
+            break;
+        }
+    }
+    assert_eq!(
+        names,
+        [
+            "Connected",
+            "Authenticated",
+            "RoomJoined",
+            "SessionInconsistency",
+            "Disconnected"
+        ]
+    );
+    assert_eq!(reason, Some(DisconnectReason::SessionInconsistency));
+    assert!(closed.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Organization check on Authenticated
+// ════════════════════════════════════════════════════════════════════
+
+/// Expects organization `acme`; the server authenticates under `organization`.
+fn start_organization_client(
+    organization: &str,
+    policy: signal_fish_client::OrganizationMismatchPolicy,
+) -> StartedClient {
+    let authenticated = serde_json::to_string(&ServerMessage::Authenticated {
+        app_name: "test-app".into(),
+        organization: Some(organization.into()),
+        rate_limits: RateLimitInfo {
+            per_minute: 60,
+            per_hour: 1000,
+            per_day: 10000,
+        },
+    })
+    .expect("serialize Authenticated");
+    start_client_with_config(
+        vec![Some(Ok(authenticated)), Some(Ok(pong_json()))],
+        SignalFishConfig::new("mb_test_integration")
+            .with_expected_organization("acme")
+            .with_organization_mismatch_policy(policy),
+    )
+}
+
+#[tokio::test]
+async fn expected_organization_authenticates_without_a_warning() {
+    let (mut client, mut events, _sent, _closed) = start_organization_client(
+        "acme",
+        signal_fish_client::OrganizationMismatchPolicy::Disconnect,
+    );
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        ["Connected", "Authenticated"]
+    );
+    let info = client.authenticated_info().expect("authenticated");
+    assert_eq!(info.organization.as_deref(), Some("acme"));
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn unexpected_organization_warns_then_authenticates_by_default() {
+    let (mut client, mut events, _sent, _closed) = start_organization_client(
+        "globex",
+        signal_fish_client::OrganizationMismatchPolicy::default(),
+    );
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    match events.recv().await.expect("OrganizationMismatch") {
+        SignalFishEvent::OrganizationMismatch { expected, actual } => {
+            assert_eq!(expected, "acme");
+            assert_eq!(actual.as_deref(), Some("globex"));
+        }
+        other => panic!("expected OrganizationMismatch, got {other:?}"),
+    }
+    assert_eq!(recv_until_pong(&mut events).await, ["Authenticated"]);
+    assert!(client.is_authenticated());
+    let info = client.authenticated_info().expect("authenticated");
+    assert_eq!(info.organization.as_deref(), Some("globex"));
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn disconnect_policy_rejects_an_unexpected_organization() {
+    let (client, mut events, _sent, closed) = start_organization_client(
+        "globex",
+        signal_fish_client::OrganizationMismatchPolicy::Disconnect,
+    );
+    let mut names = Vec::new();
+    let mut reason = None;
+    while let Some(event) = events.recv().await {
+        names.push(format!("{event:?}"));
+        if let SignalFishEvent::Disconnected { reason: r, .. } = event {
+            reason = Some(r);
+            break;
+        }
+    }
+    assert_eq!(names, ["Connected", "OrganizationMismatch", "Disconnected"]);
+    assert_eq!(reason, Some(DisconnectReason::OrganizationMismatch));
+    assert!(closed.load(std::sync::atomic::Ordering::SeqCst));
+    assert!(!client.is_authenticated());
+    assert_eq!(client.authenticated_info(), None);
+}
+
+// ════════════════════════════════════════════════════════════════════
+// LobbyStateChanged and GameStarting events
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn lobby_state_changed_event() {
+    let p1 = uuid::Uuid::from_u128(1);
+    let lobby_json = serde_json::to_string(&ServerMessage::LobbyStateChanged {
+        lobby_state: signal_fish_client::protocol::LobbyState::Finalized,
+        ready_players: vec![p1],
+        all_ready: true,
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(lobby_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::LobbyStateChanged {
+        lobby_state,
+        ready_players,
+        all_ready,
+    } = ev
+    {
+        assert!(matches!(
+            lobby_state,
+            signal_fish_client::protocol::LobbyState::Finalized
+        ));
+        assert_eq!(ready_players.len(), 1);
+        assert!(all_ready);
+    } else {
+        panic!("expected LobbyStateChanged event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+fn lobby_json(ready_players: &[u128], all_ready: bool) -> String {
+    serde_json::to_string(&ServerMessage::LobbyStateChanged {
+        lobby_state: signal_fish_client::protocol::LobbyState::Lobby,
+        ready_players: ready_players
+            .iter()
+            .copied()
+            .map(uuid::Uuid::from_u128)
+            .collect(),
+        all_ready,
+    })
+    .expect("serialize")
+}
+
+fn ready_count(event: &SignalFishEvent) -> (usize, bool) {
+    match event {
+        SignalFishEvent::LobbyStateChanged {
+            ready_players,
+            all_ready,
+            ..
+        } => (ready_players.len(), *all_ready),
+        other => panic!("expected LobbyStateChanged, got {other:?}"),
+    }
+}
+
+const LOBBY_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+
+fn start_debounced_client(incoming: Vec<Option<Result<String, SignalFishError>>>) -> StartedClient {
+    start_client_with_config(
+        incoming,
+        SignalFishConfig::new("mb_test_integration").with_lobby_state_debounce(LOBBY_DEBOUNCE),
+    )
+}
+
+#[tokio::test(start_paused = true)]
+async fn lobby_state_debounce_coalesces_a_burst_into_the_latest() {
+    let (mut client, mut events, _sent, _closed) = start_debounced_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(lobby_json(&[1], false))),
+        Some(Ok(lobby_json(&[], false))),
+        Some(Ok(lobby_json(&[2], false))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let burst_at = tokio::time::Instant::now();
+
+    let ev = events.recv().await.expect("coalesced event");
+    assert_eq!(ready_count(&ev), (1, false));
+    if let SignalFishEvent::LobbyStateChanged { ready_players, .. } = &ev {
+        assert_eq!(
+            ready_players,
+            &[uuid::Uuid::from_u128(2)],
+            "latest state wins"
+        );
+    }
+    assert!(
+        burst_at.elapsed() >= LOBBY_DEBOUNCE,
+        "emitted after the window"
+    );
+
+    tokio::time::sleep(LOBBY_DEBOUNCE * 5).await;
+    assert!(
+        events.try_recv().is_err(),
+        "burst produced exactly one event"
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn lobby_state_debounce_flushes_all_ready_immediately() {
+    let (mut client, mut events, _sent, _closed) = start_debounced_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(lobby_json(&[1], false))),
+        Some(Ok(lobby_json(&[1, 2], true))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let burst_at = tokio::time::Instant::now();
+
+    let ev = events.recv().await.expect("all-ready event");
+    assert_eq!(ready_count(&ev), (2, true));
+    assert_eq!(burst_at.elapsed(), std::time::Duration::ZERO, "not delayed");
+
+    tokio::time::sleep(LOBBY_DEBOUNCE * 5).await;
+    assert!(
+        events.try_recv().is_err(),
+        "the held state was superseded, not emitted late"
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn lobby_state_debounce_flushes_before_other_events() {
+    let (mut client, mut events, _sent, _closed) = start_debounced_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(lobby_json(&[1], false))),
+        Some(Ok(pong_json())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let burst_at = tokio::time::Instant::now();
+
+    let ev = events.recv().await.expect("lobby event");
+    assert_eq!(ready_count(&ev), (1, false));
+    let ev = events.recv().await.expect("pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    assert_eq!(burst_at.elapsed(), std::time::Duration::ZERO);
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn lobby_state_without_debounce_emits_every_change() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(lobby_json(&[1], false))),
+        Some(Ok(lobby_json(&[], false))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    assert_eq!(
+        ready_count(&events.recv().await.expect("first")),
+        (1, false)
+    );
+    assert_eq!(
+        ready_count(&events.recv().await.expect("second")),
+        (0, false)
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn game_starting_event() {
+    use signal_fish_client::protocol::PeerConnectionInfo;
+
+    let gs_json = serde_json::to_string(&ServerMessage::GameStarting {
+        peer_connections: vec![PeerConnectionInfo {
+            player_id: uuid::Uuid::from_u128(10),
+            player_name: "Peer".into(),
+            is_authority: true,
+            relay_type: "auto".into(),
+            connection_info: None,
+        }],
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(gs_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::GameStarting { peer_connections } = ev {
+        assert_eq!(peer_connections.len(), 1);
+        assert_eq!(peer_connections[0].player_name, "Peer");
+    } else {
+        panic!("expected GameStarting event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// AuthorityChanged event
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn authority_changed_event() {
+    let auth_player = uuid::Uuid::from_u128(77);
+    let ac_json = serde_json::to_string(&ServerMessage::AuthorityChanged {
+        authority_player: Some(auth_player),
+        you_are_authority: true,
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(ac_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::AuthorityChanged {
+        authority_player,
+        you_are_authority,
+    } = ev
+    {
+        assert_eq!(authority_player, Some(auth_player));
+        assert!(you_are_authority);
+    } else {
+        panic!("expected AuthorityChanged event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// NewSpectatorJoined and SpectatorDisconnected events
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn new_spectator_joined_event() {
+    let nsj_json = serde_json::to_string(&ServerMessage::NewSpectatorJoined {
+        spectator: signal_fish_client::protocol::SpectatorInfo {
+            id: uuid::Uuid::from_u128(500),
+            name: "NewViewer".into(),
+            connected_at: "2026-01-01T00:00:00Z".into(),
+        },
+        current_spectators: vec![],
+        reason: Some(signal_fish_client::protocol::SpectatorStateChangeReason::Joined),
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(nsj_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::NewSpectatorJoined {
+        spectator, reason, ..
+    } = ev
+    {
+        assert_eq!(spectator.name, "NewViewer");
+        assert!(matches!(
+            reason,
+            Some(signal_fish_client::protocol::SpectatorStateChangeReason::Joined)
+        ));
+    } else {
+        panic!("expected NewSpectatorJoined event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn spectator_disconnected_event() {
+    let sd_json = serde_json::to_string(&ServerMessage::SpectatorDisconnected {
+        spectator_id: uuid::Uuid::from_u128(600),
+        reason: Some(signal_fish_client::protocol::SpectatorStateChangeReason::Disconnected),
+        current_spectators: vec![],
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(sd_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::SpectatorDisconnected {
+        spectator_id,
+        reason,
+        ..
+    } = ev
+    {
+        assert_eq!(spectator_id, uuid::Uuid::from_u128(600));
+        assert!(matches!(
+            reason,
+            Some(signal_fish_client::protocol::SpectatorStateChangeReason::Disconnected)
+        ));
+    } else {
+        panic!("expected SpectatorDisconnected event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// ReconnectionFailed and PlayerReconnected events
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn reconnection_failed_event() {
+    let rf_json = serde_json::to_string(&ServerMessage::ReconnectionFailed {
+        reason: "expired".into(),
+        error_code: ErrorCode::ReconnectionExpired,
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(rf_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::ReconnectionFailed { reason, error_code } = ev {
+        assert_eq!(reason, "expired");
+        assert_eq!(error_code, ErrorCode::ReconnectionExpired);
+    } else {
+        panic!("expected ReconnectionFailed event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn player_reconnected_event() {
+    let pr_json = serde_json::to_string(&ServerMessage::PlayerReconnected {
+        player_id: uuid::Uuid::from_u128(700),
+        epoch: None,
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pr_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::PlayerReconnected { player_id, epoch } = ev {
+        assert_eq!(player_id, uuid::Uuid::from_u128(700));
+        assert!(epoch.is_none());
+    } else {
+        panic!("expected PlayerReconnected event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// AuthenticationError event
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn authentication_error_event() {
+    let ae_json = serde_json::to_string(&ServerMessage::AuthenticationError {
+        error: "bad credentials".into(),
+        error_code: ErrorCode::InvalidAppId,
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) = start_client(vec![Some(Ok(ae_json))]);
+
+    // Connected is first.
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Connected));
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::AuthenticationError { error, error_code } = ev {
+        assert_eq!(error, "bad credentials");
+        assert_eq!(error_code, ErrorCode::InvalidAppId);
+    } else {
+        panic!("expected AuthenticationError event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+fn auth_failure_json() -> String {
+    serde_json::to_string(&ServerMessage::AuthenticationError {
+        error: "app revoked".into(),
+        error_code: ErrorCode::InvalidAppId,
+    })
+    .expect("serialize")
+}
+
+#[tokio::test]
+async fn auth_failure_after_reconnect_invalidates_session_by_default() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(reconnected_json())),
+        Some(Ok(auth_failure_json())),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("Reconnected");
+    assert!(matches!(ev, SignalFishEvent::Reconnected { .. }), "{ev:?}");
+
+    let ev = events.recv().await.expect("AuthenticationError");
+    assert!(
+        matches!(ev, SignalFishEvent::AuthenticationError { .. }),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("SessionInvalidated");
+    match ev {
+        SignalFishEvent::SessionInvalidated { reason } => assert_eq!(reason, "app revoked"),
+        other => panic!("expected SessionInvalidated, got {other:?}"),
+    }
+
+    let snapshot = client.snapshot();
+    assert!(snapshot.connected);
+    assert!(!snapshot.authenticated);
+    assert_eq!(snapshot.room_id, None);
+    assert_eq!(snapshot.room_code, None);
+    assert_eq!(snapshot.player_id, None);
+    assert_eq!(snapshot.reconnection_token, None);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn auth_failure_keeps_session_when_clearing_is_disabled() {
+    let config =
+        SignalFishConfig::new("mb_test_integration").with_clear_session_on_auth_failure(false);
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(reconnected_json())),
+            Some(Ok(auth_failure_json())),
+            Some(Ok(pong_json())),
+        ],
+        config,
+    );
+
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("Reconnected");
+    assert!(matches!(ev, SignalFishEvent::Reconnected { .. }), "{ev:?}");
+    let ev = events.recv().await.expect("AuthenticationError");
+    assert!(
+        matches!(ev, SignalFishEvent::AuthenticationError { .. }),
+        "{ev:?}"
+    );
+    // No SessionInvalidated: the next event is the scripted Pong.
+    let ev = events.recv().await.expect("Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+
+    let snapshot = client.snapshot();
+    assert!(!snapshot.authenticated);
+    assert_eq!(snapshot.room_id, Some(uuid::Uuid::from_u128(100)));
+    assert_eq!(snapshot.room_code.as_deref(), Some("RECON1"));
+    assert_eq!(snapshot.player_id, Some(uuid::Uuid::from_u128(200)));
+
+    client.shutdown().await;
+}
+
+async fn recv_until_pong(events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) -> Vec<String> {
+    let mut names = Vec::new();
+    while let Some(event) = events.recv().await {
+        if matches!(event, SignalFishEvent::Pong) {
+            return names;
+        }
+        names.push(format!("{event:?}"));
+    }
+    panic!("event channel closed before Pong; saw {names:?}");
+}
+
+#[tokio::test]
+async fn transparent_reauth_keeps_session_epoch_and_reemits_by_default() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(reconnected_json())),
+        Some(Ok(authenticated_json())),
+        Some(Ok(pong_json())),
+    ]);
+
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        ["Connected", "Authenticated", "Reconnected", "Authenticated"]
+    );
+    let snapshot = client.snapshot();
+    assert_eq!(snapshot.session_epoch, 0);
+    assert_eq!(snapshot.room_code.as_deref(), Some("RECON1"));
+
+    client.shutdown().await;
+    assert_eq!(client.snapshot().session_epoch, 1);
+}
+
+#[tokio::test]
+async fn transparent_reauth_is_suppressed_when_reemit_is_disabled() {
+    let config = SignalFishConfig::new("mb_test_integration").with_reemit_session_events(false);
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(reconnected_json())),
+            Some(Ok(authenticated_json())),
+            Some(Ok(pong_json())),
+        ],
+        config,
+    );
+
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        ["Connected", "Authenticated", "Reconnected"]
+    );
+    assert_eq!(client.snapshot().session_epoch, 0);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn hard_session_reset_bumps_epoch_and_always_emits_authenticated() {
+    let config = SignalFishConfig::new("mb_test_integration").with_reemit_session_events(false);
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(reconnected_json())),
+            Some(Ok(auth_failure_json())),
+            Some(Ok(authenticated_json())),
+            Some(Ok(pong_json())),
+        ],
+        config,
+    );
+
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        [
+            "Connected",
+            "Authenticated",
+            "Reconnected",
+            "AuthenticationError",
+            "SessionInvalidated",
+            "Authenticated"
+        ]
+    );
+    let snapshot = client.snapshot();
+    assert_eq!(snapshot.session_epoch, 1);
+    assert!(snapshot.authenticated);
+    assert_eq!(snapshot.room_id, None);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn auth_failure_without_a_session_emits_no_invalidation() {
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(auth_failure_json())), Some(Ok(pong_json()))]);
+
+    let ev = events.recv().await.expect("Connected");
+    assert!(matches!(ev, SignalFishEvent::Connected));
+    let ev = events.recv().await.expect("AuthenticationError");
+    assert!(
+        matches!(ev, SignalFishEvent::AuthenticationError { .. }),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// RoomJoinFailed and SpectatorJoinFailed events
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn room_join_failed_event() {
+    let rjf_json = serde_json::to_string(&ServerMessage::RoomJoinFailed {
+        reason: "room full".into(),
+        error_code: Some(ErrorCode::RoomFull),
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(rjf_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::RoomJoinFailed {
+        reason,
+        error_code,
+        requested_room_code,
+    } = ev
+    {
+        assert_eq!(reason, "room full");
+        assert_eq!(error_code, Some(ErrorCode::RoomFull));
+        assert_eq!(requested_room_code, None);
+    } else {
+        panic!("expected RoomJoinFailed event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn spectator_join_failed_event() {
+    let sjf_json = serde_json::to_string(&ServerMessage::SpectatorJoinFailed {
+        reason: "spectators disabled".into(),
+        error_code: Some(ErrorCode::SpectatorNotAllowed),
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(sjf_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::SpectatorJoinFailed { reason, error_code } = ev {
+        assert_eq!(reason, "spectators disabled");
+        assert_eq!(error_code, Some(ErrorCode::SpectatorNotAllowed));
+    } else {
+        panic!("expected SpectatorJoinFailed event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// ProtocolInfo event
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn protocol_info_event() {
+    let pi_json = serde_json::to_string(&ServerMessage::ProtocolInfo(
+        signal_fish_client::protocol::ProtocolInfoPayload {
+            platform: Some("unity".into()),
+            sdk_version: Some("1.0.0".into()),
+            minimum_version: None,
+            recommended_version: None,
+            capabilities: vec!["spectator".into()],
+            notes: None,
+            game_data_formats: vec![GameDataEncoding::Json],
+            player_name_rules: None,
+            protocol_version: None,
+            min_protocol_version: None,
+            max_protocol_version: None,
+            transports: None,
+            limits: None,
+        },
+    ))
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pi_json))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::ProtocolInfo(payload) = ev {
+        assert_eq!(payload.platform.as_deref(), Some("unity"));
+        assert_eq!(payload.capabilities, vec!["spectator"]);
+    } else {
+        panic!("expected ProtocolInfo event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn server_limits_cap_max_players_and_explain_limit_errors() {
+    let limits = ServerLimits {
+        max_rooms_per_game: Some(50),
+        max_players_per_room: Some(8),
+        max_spectators: None,
+    };
+    let mut payload = protocol_info_payload(None);
+    payload.limits = Some(limits);
+    let pi_json = serde_json::to_string(&ServerMessage::ProtocolInfo(payload)).expect("serialize");
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), Some(Ok(pi_json))]);
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::ProtocolInfo(_)), "got {ev:?}");
+    assert_eq!(client.server_limits(), Some(limits));
+
+    let result = client.join_room(JoinRoomParams::new("g", "Alice").with_max_players(9));
+    assert!(
+        matches!(
+            result,
+            Err(SignalFishError::MaxPlayersExceeded {
+                requested: 9,
+                limit: 8
+            })
+        ),
+        "got {result:?}"
+    );
+    assert!(sent.matching(of_type("JoinRoom")).is_empty());
+    client
+        .join_room(JoinRoomParams::new("g", "Alice").with_max_players(8))
+        .expect("max_players within the limit");
+    wait_for_sent_len(&sent, 2).await;
+
+    assert_eq!(
+        client.describe_error_code(&ErrorCode::MaxRoomsPerGameExceeded),
+        format!(
+            "{} (server limit: 50 rooms per game)",
+            ErrorCode::MaxRoomsPerGameExceeded.description()
+        )
+    );
+    assert_eq!(
+        client.describe_error_code(&ErrorCode::TooManySpectators),
+        ErrorCode::TooManySpectators.description(),
+        "no advertised spectator cap"
+    );
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn server_without_limits_leaves_join_params_unchecked() {
+    let (mut client, mut events, sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(None))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let _ = events.recv().await; // ProtocolInfo
+    assert!(client.server_limits().is_none());
+    client
+        .join_room(JoinRoomParams::new("g", "Alice").with_max_players(200))
+        .expect("no advertised cap");
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(
+        client.describe_error_code(&ErrorCode::MaxRoomsPerGameExceeded),
+        ErrorCode::MaxRoomsPerGameExceeded.description()
+    );
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Server time synchronization
+// ════════════════════════════════════════════════════════════════════
+
+/// `client_sent_at_ms` of every `TimeSyncRequest` sent so far.
+fn time_sync_requests(sent: &SentLog) -> Vec<u64> {
+    sent.matching(of_type("TimeSyncRequest"))
+        .iter()
+        .map(|frame| match serde_json::from_str(frame).unwrap() {
+            ClientMessage::TimeSyncRequest { client_sent_at_ms } => client_sent_at_ms,
+            other => panic!("expected TimeSyncRequest, got {other:?}"),
+        })
+        .collect()
+}
+
+/// Answer the `nth` `TimeSyncRequest` as a server whose clock runs
+/// `offset_ms` ahead, with `up_ms` and `down_ms` of one-way latency. Call it
+/// at the instant the request goes out so the latencies are exact.
+async fn answer_time_sync(
+    sent: &SentLog,
+    feed: &Feed,
+    nth: usize,
+    offset_ms: i64,
+    (up_ms, down_ms): (u64, u64),
+) {
+    use std::time::Duration;
+
+    let client_sent_at_ms = loop {
+        if let Some(&sent_at) = time_sync_requests(sent).get(nth - 1) {
+            break sent_at;
+        }
+        tokio::task::yield_now().await;
+    };
+    tokio::time::sleep(Duration::from_millis(up_ms)).await;
+    let server_time_ms = (client_sent_at_ms + up_ms).saturating_add_signed(offset_ms);
+    tokio::time::sleep(Duration::from_millis(down_ms)).await;
+    feed.send(Ok(time_sync_response_json(
+        client_sent_at_ms,
+        server_time_ms,
+    )))
+    .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn sync_time_applies_the_half_rtt_estimate_to_asymmetric_paths() {
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert_eq!(client.server_time_estimate(), None);
+
+    // Server 5 s ahead; 30 ms up, 70 ms down.
+    let (result, ()) = tokio::join!(
+        client.sync_time(),
+        answer_time_sync(&sent, &feed, 1, 5_000, (30, 70))
+    );
+    let result = result.expect("time sync completes");
+    assert_eq!(result.rtt, Duration::from_millis(100));
+    assert_eq!(result.offset, 5_000 + (30 - 70) / 2);
+    assert_eq!(client.server_time_estimate(), Some(result));
+
+    let ev = events.recv().await.expect("event");
+    let SignalFishEvent::TimeSyncResponse { sample, .. } = ev else {
+        panic!("expected TimeSyncResponse, got {ev:?}");
+    };
+    assert_eq!(sample, Some(result));
+
+    // A slower exchange does not displace the better estimate.
+    let (slow, ()) = tokio::join!(
+        client.sync_time(),
+        answer_time_sync(&sent, &feed, 2, 5_000, (200, 20))
+    );
+    let slow = slow.expect("time sync completes");
+    assert_eq!(slow.rtt, Duration::from_millis(220));
+    assert_eq!(slow.offset, 5_000 + (200 - 20) / 2);
+    assert_eq!(client.server_time_estimate(), Some(result));
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn sync_time_fails_when_the_connection_drops_mid_exchange() {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    let (result, ()) = tokio::join!(client.sync_time(), async move {
+        wait_for_sent_len(&sent, 2).await;
+        drop(feed);
+    });
+    let err = result.expect_err("no response arrives");
+    assert!(matches!(err, SignalFishError::NotConnected), "{err:?}");
+    assert_eq!(client.server_time_estimate(), None);
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn time_sync_interval_keeps_the_estimate_fresh() {
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_time_sync_interval(Duration::from_secs(10))
+        .with_time_sync_samples(2);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+
+    // The 10 s tick passes unused before authentication.
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    assert!(time_sync_requests(&sent).is_empty());
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    // Ticks land at 20 s, 30 s and 40 s; each answer starts on its tick.
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    answer_time_sync(&sent, &feed, 1, -2_000, (10, 10)).await;
+    events.recv().await.expect("TimeSyncResponse");
+    let first = client.server_time_estimate().expect("first sample");
+    assert_eq!(first.offset, -2_000);
+    assert_eq!(first.rtt, Duration::from_millis(20));
+
+    // Two slower samples later, the fast one has left the window.
+    tokio::time::sleep(Duration::from_millis(9_980)).await;
+    answer_time_sync(&sent, &feed, 2, -2_000, (60, 0)).await;
+    events.recv().await.expect("TimeSyncResponse");
+    tokio::time::sleep(Duration::from_millis(9_940)).await;
+    answer_time_sync(&sent, &feed, 3, -2_000, (60, 0)).await;
+    events.recv().await.expect("TimeSyncResponse");
+    assert_eq!(time_sync_requests(&sent).len(), 3);
+    let latest = client.server_time_estimate().expect("estimate");
+    assert_eq!(latest.rtt, Duration::from_millis(60));
+    assert_eq!(latest.offset, -2_000 + 30);
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Shutdown behavior
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn shutdown_closes_transport() {
+    let (mut client, mut events, _sent, closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    client.shutdown().await;
+
+    // The Disconnected event should be emitted.
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
+
+    assert!(closed.load(std::sync::atomic::Ordering::Relaxed));
+}
+
+/// Transport whose sends after the first `fast_sends` and whose close each
+/// take `delay`, recording whether the close finished or was aborted.
+struct SlowTransport {
+    delay: std::time::Duration,
+    fast_sends: usize,
+    sending: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+    closing: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+    incoming: VecDeque<Option<Result<String, SignalFishError>>>,
+    closed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    aborted: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl SlowTransport {
+    fn new(
+        delay: std::time::Duration,
+        fast_sends: usize,
+        incoming: Vec<Option<Result<String, SignalFishError>>>,
+    ) -> Self {
+        Self {
+            delay,
+            fast_sends,
+            sending: None,
+            closing: None,
+            incoming: VecDeque::from(incoming),
+            closed: std::sync::Arc::default(),
+            aborted: std::sync::Arc::default(),
+        }
+    }
+}
+
+impl Transport for SlowTransport {
+    fn poll_send(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if frame.is_none() {
+            return std::task::Poll::Ready(Ok(()));
+        }
+        if self.fast_sends > 0 {
+            self.fast_sends -= 1;
+        } else {
+            let delay = self.delay;
+            let sending = self
+                .sending
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(delay)));
+            std::task::ready!(std::future::Future::poll(sending.as_mut(), cx));
+            self.sending = None;
+        }
+        frame.take();
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        if let Some(item) = self.incoming.pop_front() {
+            std::task::Poll::Ready(item.map(|result| result.map(TransportFrame::Text)))
+        } else {
+            std::task::Poll::Pending
+        }
+    }
+
+    fn poll_close(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        let delay = self.delay;
+        let closing = self
+            .closing
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(delay)));
+        std::task::ready!(std::future::Future::poll(closing.as_mut(), cx));
+        self.closed.store(true, std::sync::atomic::Ordering::SeqCst);
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn abort(&mut self) {
+        self.aborted
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_slow_shutdown_flush_still_closes_the_transport_gracefully() {
+    let delay = std::time::Duration::from_millis(300);
+    let transport = SlowTransport::new(delay, 1, vec![Some(Ok(authenticated_json()))]);
+    let (closed, aborted) = (transport.closed.clone(), transport.aborted.clone());
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_shutdown_timeout(std::time::Duration::from_secs(1));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    drain_until_authenticated(&mut events).await;
+
+    // Five sends take longer than the flush's window.
+    for tick in 0..5 {
+        client.send_game_data(serde_json::json!(tick)).unwrap();
+    }
+    let started = tokio::time::Instant::now();
+    client.shutdown().await;
+
+    assert!(
+        closed.load(std::sync::atomic::Ordering::SeqCst),
+        "the close gets its own window after the flush"
+    );
+    assert!(!aborted.load(std::sync::atomic::Ordering::SeqCst));
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected {
+            reason: DisconnectReason::ClientShutdown,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn shutdown_timeout_clears_state_even_when_disconnected_event_is_skipped() {
+    let transport = HangingCloseTransport::new(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_json())),
+    ]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_shutdown_timeout(std::time::Duration::from_millis(1));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("expected RoomJoined event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
+
+    assert!(client.is_authenticated());
+    assert!(client.current_player_id().await.is_some());
+    assert!(client.current_room_id().await.is_some());
+    assert_eq!(client.current_room_code().await.as_deref(), Some("ABC123"));
+
+    client.shutdown().await;
+
+    assert!(!client.is_connected());
+    assert!(!client.is_authenticated());
+    assert!(client.current_player_id().await.is_none());
+    assert!(client.current_room_id().await.is_none());
+    assert!(client.current_room_code().await.is_none());
+}
+
+#[tokio::test]
+async fn leave_room_sends_leave_room_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+
+    drain_until_authenticated(&mut events).await;
+
+    client.leave_room().expect("leave_room");
+
+    wait_for_sent_len(&sent, 2).await;
+
+    {
+        let found = !sent.matching(of_type("LeaveRoom")).is_empty();
+        assert!(found, "expected LeaveRoom message");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Draining
+// ════════════════════════════════════════════════════════════════════
+
+/// Wait for the terminal `Disconnected`, skipping anything before it.
+async fn disconnect_reason(
+    events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>,
+) -> DisconnectReason {
+    loop {
+        match events.recv().await.expect("event") {
+            SignalFishEvent::Disconnected { reason, .. } => return reason,
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+async fn drain_in_room_finishes_the_room_then_shuts_down() {
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(room_joined_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
+
+    client.begin_drain().await.expect("begin_drain");
+    assert!(client.snapshot().draining);
+    assert!(matches!(
+        client.join_room(JoinRoomParams::new("test-game", "Alice")),
+        Err(SignalFishError::Draining)
+    ));
+    assert!(matches!(
+        client.join_as_spectator("test-game".into(), "ABC123".into(), "Watcher".into()),
+        Err(SignalFishError::Draining)
+    ));
+
+    // The current room keeps working.
+    client
+        .send_game_data(serde_json::json!({"turn": 1}))
+        .expect("send_game_data");
+    client.set_ready().expect("set_ready");
+    client.leave_room().expect("leave_room");
+    wait_for_sent_len(&sent, 5).await;
+    assert_eq!(sent.matching(of_type("JoinRoom")).len(), 1);
+    assert_eq!(sent.matching(of_type("GameData")).len(), 1);
+    assert_eq!(sent.matching(of_type("PlayerReady")).len(), 1);
+    assert_eq!(sent.matching(of_type("LeaveRoom")).len(), 1);
+    assert!(client.is_connected(), "the drain waits for the room to end");
+
+    feed.send(Ok(room_left_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomLeft));
+    assert_eq!(
+        disconnect_reason(&mut events).await,
+        DisconnectReason::Drained
+    );
+    client.drained().await;
+    assert!(!client.is_connected());
+    assert!(client.snapshot().draining);
+}
+
+#[tokio::test]
+async fn drain_while_idle_shuts_down_immediately() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    let drained = client.drained();
+    client.begin_drain().await.expect("begin_drain");
+    drained.await;
+
+    assert_eq!(
+        disconnect_reason(&mut events).await,
+        DisconnectReason::Drained
+    );
+    assert!(events.recv().await.is_none());
+    assert!(!client.is_connected());
+    assert!(matches!(
+        client.begin_drain().await,
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn drain_timeout_shuts_down_a_room_that_never_ends() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(
+            SignalFishConfig::new("mb_test_integration")
+                .with_drain_timeout(std::time::Duration::from_secs(30)),
+        ),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    client
+        .join_room(JoinRoomParams::new("test-game", "Alice"))
+        .expect("join_room");
+    feed.send(Ok(room_joined_json())).unwrap();
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }));
+
+    let started = tokio::time::Instant::now();
+    client.begin_drain().await.expect("begin_drain");
+    client.drained().await;
+
+    assert_eq!(started.elapsed(), std::time::Duration::from_secs(30));
+    assert_eq!(
+        disconnect_reason(&mut events).await,
+        DisconnectReason::DrainTimedOut
+    );
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Malformed JSON resilience
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn malformed_json_emits_decode_failed_then_next_message_arrives() {
+    // Send garbled text followed by a valid message. The transport loop
+    // surfaces the invalid frame as a DecodeFailed event (never a silent
+    // drop) and continues.
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok("{{not valid json at all!!!".into())),
+        Some(Ok(pong_json())),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events
+        .recv()
+        .await
+        .expect("expected DecodeFailed after malformed JSON");
+    match ev {
+        SignalFishEvent::DecodeFailed {
+            message_type,
+            raw_prefix,
+            ..
+        } => {
+            // Not valid JSON at all → no wire `type` tag recoverable.
+            assert_eq!(message_type.as_deref(), None);
+            assert_eq!(raw_prefix, "{{not valid json at all!!!");
+        }
+        other => panic!("expected DecodeFailed, got {other:?}"),
+    }
+
+    let ev = events
+        .recv()
+        .await
+        .expect("expected Pong after DecodeFailed");
+    assert!(
+        matches!(ev, SignalFishEvent::Pong),
+        "expected Pong event after malformed JSON, got {ev:?}"
+    );
+    assert_eq!(client.stats().messages_undecodable, 1);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn blank_keepalive_frames_are_skipped_and_counted() {
+    // A reverse proxy's empty keepalives produce no event; a valid frame with
+    // a trailing newline still parses.
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(String::new())),
+        Some(Ok(" \n\t".into())),
+        Some(Ok(format!("{}\n", pong_json()))),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("expected Pong after keepalives");
+    assert!(
+        matches!(ev, SignalFishEvent::Pong),
+        "blank frames must not surface as events, got {ev:?}"
+    );
+    let stats = client.stats();
+    assert_eq!(stats.blank_frames_skipped, 2);
+    assert_eq!(stats.messages_undecodable, 0);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn strict_blank_frames_surface_decode_failed() {
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![Some(Ok(authenticated_json())), Some(Ok(String::new()))],
+        SignalFishConfig::new("mb_test_integration").with_strict_blank_frames(true),
+    );
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("expected DecodeFailed");
+    assert!(
+        matches!(ev, SignalFishEvent::DecodeFailed { .. }),
+        "strict mode reports blank frames, got {ev:?}"
+    );
+    assert_eq!(client.stats().messages_undecodable, 1);
+    assert_eq!(client.stats().blank_frames_skipped, 0);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn concatenated_messages_in_one_frame_each_surface() {
+    // A coalescing proxy packed two messages into one text frame.
+    let left_player = uuid::Uuid::from_u128(666);
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_json())),
+        Some(Ok(format!(
+            "{}{}",
+            pong_json(),
+            player_left_json(left_player)
+        ))),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+    let _rj = events.recv().await; // RoomJoined
+
+    let ev = events.recv().await.expect("expected Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    let ev = events.recv().await.expect("expected PlayerLeft");
+    assert!(
+        matches!(ev, SignalFishEvent::PlayerLeft { player_id, .. } if player_id == left_player),
+        "got {ev:?}"
+    );
+    let stats = client.stats();
+    assert_eq!(stats.multi_message_frames, 1);
+    assert_eq!(stats.messages_undecodable, 0);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn truncated_trailing_message_is_reported_once_after_the_rest() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(format!("{} {{\"type\":\"Pong\"", pong_json()))),
+        Some(Ok(pong_json())),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("expected Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    let ev = events.recv().await.expect("expected DecodeFailed");
+    assert!(
+        matches!(ev, SignalFishEvent::DecodeFailed { .. }),
+        "the truncated remainder must be reported, got {ev:?}"
+    );
+    let ev = events.recv().await.expect("expected the next frame's Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    let stats = client.stats();
+    assert_eq!(stats.messages_undecodable, 1);
+    assert_eq!(stats.multi_message_frames, 1);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn strict_message_framing_rejects_concatenated_messages() {
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(format!("{}{}", pong_json(), pong_json()))),
+            Some(Ok(pong_json())),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_strict_message_framing(true),
+    );
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("expected DecodeFailed");
+    assert!(
+        matches!(ev, SignalFishEvent::DecodeFailed { .. }),
+        "strict mode reports the whole frame, got {ev:?}"
+    );
+    let ev = events.recv().await.expect("expected the next frame's Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    let stats = client.stats();
+    assert_eq!(stats.messages_undecodable, 1);
+    assert_eq!(stats.multi_message_frames, 0);
+
+    client.shutdown().await;
+}
+
+/// Run a quick leave and rejoin whose frame also carries a `PlayerJoined`
+/// broadcast from the room being left, returning the events after the first
+/// `RoomJoined`.
+async fn leave_rejoin_race(drop_cross_epoch_events: bool) -> (Vec<SignalFishEvent>, u64) {
+    let stale = uuid::Uuid::from_u128(901);
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(room_joined_json())),
+            Some(Ok(format!(
+                "{}{}{}",
+                room_left_json(),
+                common::player_joined_json("Stale", stale),
+                room_joined_json_with("NEW1", "test-game", uuid::Uuid::from_u128(42))
+            ))),
+        ],
+        SignalFishConfig::new("mb_test_integration")
+            .with_drop_cross_epoch_events(drop_cross_epoch_events),
+    );
+
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    let mut delivered = Vec::new();
+    loop {
+        let ev = events.recv().await.expect("event");
+        let rejoined = matches!(ev, SignalFishEvent::RoomJoined { .. });
+        delivered.push(ev);
+        if rejoined {
+            break;
+        }
+    }
+    let dropped = client.stats().cross_epoch_events_dropped;
+    client.shutdown().await;
+    (delivered, dropped)
+}
+
+#[tokio::test]
+async fn drop_cross_epoch_events_suppresses_a_stale_player_joined() {
+    let (delivered, dropped) = leave_rejoin_race(true).await;
+
+    assert!(
+        matches!(
+            delivered.as_slice(),
+            [SignalFishEvent::RoomLeft, SignalFishEvent::RoomJoined { room_code, .. }]
+                if room_code == "NEW1"
+        ),
+        "lifecycle events arrive, the stale PlayerJoined does not: {delivered:?}"
+    );
+    assert_eq!(dropped, 1);
+}
+
+#[tokio::test]
+async fn cross_epoch_events_are_delivered_by_default() {
+    let (delivered, dropped) = leave_rejoin_race(false).await;
+
+    assert!(
+        matches!(
+            delivered.as_slice(),
+            [
+                SignalFishEvent::RoomLeft,
+                SignalFishEvent::PlayerJoined { player },
+                SignalFishEvent::RoomJoined { .. },
+            ] if player.name == "Stale"
+        ),
+        "{delivered:?}"
+    );
+    assert_eq!(dropped, 0);
+}
+
+#[tokio::test]
+async fn unknown_error_code_string_arrives_as_error_code_unknown() {
+    // A server newer than this SDK sends an Error frame with an error_code
+    // string the SDK does not know. Before 0.7.0 the frame was silently
+    // dropped, later it surfaced as DecodeFailed; now the message decodes
+    // and the code arrives as ErrorCode::Unknown with the wire token.
+    let unknown_code_frame =
+        r#"{"type":"Error","data":{"message":"evicted","error_code":"FUTURE_CODE_XYZ"}}"#;
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(unknown_code_frame.to_string())),
+        Some(Ok(pong_json())),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event after unknown error code");
+    match ev {
+        SignalFishEvent::Error {
+            message,
+            error_code,
+        } => {
+            assert_eq!(message, "evicted");
+            assert_eq!(
+                error_code,
+                Some(ErrorCode::Unknown("FUTURE_CODE_XYZ".to_string()))
+            );
+        }
+        other => panic!("expected Error, got {other:?}"),
+    }
+
+    let ev = events.recv().await.expect("Pong after Error");
+    assert!(matches!(ev, SignalFishEvent::Pong));
+    assert!(client.is_connected());
+    assert_eq!(client.stats().messages_undecodable, 0);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn decode_failed_raw_prefix_is_capped_on_utf8_boundary() {
+    use signal_fish_client::DECODE_FAILED_RAW_PREFIX_MAX;
+
+    // Garbage longer than the cap, with multi-byte characters positioned so a
+    // naive byte cut would split one.
+    let garbage = format!("ここは{}", "é".repeat(600));
+    assert!(garbage.len() > DECODE_FAILED_RAW_PREFIX_MAX);
+
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(garbage.clone())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("DecodeFailed for garbage");
+    match ev {
+        SignalFishEvent::DecodeFailed { raw_prefix, .. } => {
+            assert!(raw_prefix.len() <= DECODE_FAILED_RAW_PREFIX_MAX);
+            assert!(
+                garbage.starts_with(&raw_prefix),
+                "prefix must be a true prefix of the input"
+            );
+            // String integrity (valid UTF-8) is implied by the type; the cut
+            // landing on a boundary is what this asserts.
+            assert!(!raw_prefix.is_empty());
+        }
+        other => panic!("expected DecodeFailed, got {other:?}"),
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn forwarded_unknown_messages_arrive_whole_in_place_of_decode_failed() {
+    let future = r#"{"type":"SomeFutureMessage","data":{"shiny":true}}"#;
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(future.into())),
+            Some(Ok("{{not valid json at all!!!".into())),
+            Some(Ok(pong_json())),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_forward_unknown_messages(true),
+    );
+    drain_until_authenticated(&mut events).await;
+
+    match events.recv().await {
+        Some(SignalFishEvent::UnknownMessage {
+            message_type, raw, ..
+        }) => {
+            assert_eq!(message_type, "SomeFutureMessage");
+            assert_eq!(raw, future);
+        }
+        other => panic!("expected UnknownMessage, got {other:?}"),
+    }
+    // Without a type tag there is nothing to forward.
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::DecodeFailed { .. })
+    ));
+    assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+    assert_eq!(client.stats().messages_undecodable, 2);
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Out-of-range numeric fields
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn out_of_range_numbers_warn_and_keep_the_message() {
+    let authenticated = r#"{"type":"Authenticated","data":{"app_name":"test-app","rate_limits":{"per_minute":-1,"per_hour":1000,"per_day":10000}}}"#;
+    let room_joined = signal_fish_client::testing::room_joined("ABC123")
+        .max_players(8)
+        .json()
+        .replace(r#""max_players":8"#, r#""max_players":300"#);
+    let player_joined = format!(
+        r#"{{"type":"PlayerJoined","data":{{"player":{{"id":"{}","name":"Bob","is_authority":false,"is_ready":true,"connected_at":"2026-01-01T00:00:00Z","connection_info":{{"type":"direct","host":"10.0.0.1","port":0}}}}}}}}"#,
+        uuid::Uuid::from_u128(7),
+    );
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated.into())),
+        Some(Ok(room_joined)),
+        Some(Ok(player_joined)),
+    ]);
+
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Connected));
+    let mut received = Vec::new();
+    for _ in 0..6 {
+        received.push(events.recv().await.expect("event"));
+    }
+    let mut received = received.into_iter();
+
+    let Some(SignalFishEvent::FieldOutOfRange {
+        field,
+        value,
+        action,
+    }) = received.next()
+    else {
+        panic!("expected FieldOutOfRange before Authenticated");
+    };
+    assert_eq!(
+        (field, value.as_str(), action),
+        ("rate_limits.per_minute", "-1", OutOfRangeAction::Unlimited)
+    );
+    let Some(SignalFishEvent::Authenticated { rate_limits, .. }) = received.next() else {
+        panic!("expected Authenticated");
+    };
+    assert_eq!(rate_limits.per_minute, RateLimitInfo::UNLIMITED);
+    assert_eq!(rate_limits.per_hour, 1000);
+
+    let Some(SignalFishEvent::FieldOutOfRange {
+        field,
+        value,
+        action,
+    }) = received.next()
+    else {
+        panic!("expected FieldOutOfRange before RoomJoined");
+    };
+    assert_eq!(
+        (field, value.as_str(), action),
+        ("max_players", "300", OutOfRangeAction::Clamped { to: 255 })
+    );
+    let Some(SignalFishEvent::RoomJoined {
+        max_players,
+        room_code,
+        ..
+    }) = received.next()
+    else {
+        panic!("expected RoomJoined");
+    };
+    assert_eq!((max_players, room_code.as_str()), (255, "ABC123"));
+
+    let Some(SignalFishEvent::FieldOutOfRange {
+        field,
+        value,
+        action,
+    }) = received.next()
+    else {
+        panic!("expected FieldOutOfRange before PlayerJoined");
+    };
+    assert_eq!(
+        (field, value.as_str(), action),
+        ("connection_info.port", "0", OutOfRangeAction::Dropped)
+    );
+    let Some(SignalFishEvent::PlayerJoined { player }) = received.next() else {
+        panic!("expected PlayerJoined");
+    };
+    assert_eq!(player.name, "Bob");
+    assert!(player.connection_info.is_none());
+
+    assert_eq!(client.current_room_code().await.as_deref(), Some("ABC123"));
+    assert_eq!(client.stats().messages_undecodable, 0);
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Salvaged room payloads
+// ════════════════════════════════════════════════════════════════════
+
+/// A `RoomJoined` for Alice, Bob and Carol whose Bob has a `connection_info`
+/// of the wrong shape.
+fn room_joined_with_corrupt_player() -> String {
+    use signal_fish_client::testing::{player, room_joined};
+    let json = room_joined("SALV01")
+        .players([player("Alice"), player("Bob"), player("Carol")])
+        .json();
+    let mut message: serde_json::Value = serde_json::from_str(&json).unwrap();
+    message["data"]["current_players"][1]["connection_info"] =
+        serde_json::json!({ "type": "direct", "host": 5 });
+    message.to_string()
+}
+
+#[tokio::test]
+async fn room_joined_with_a_corrupt_player_is_salvaged_with_a_warning() {
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_with_corrupt_player())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    let SignalFishEvent::PayloadSalvaged {
+        message_type,
+        field,
+        error,
+    } = ev
+    else {
+        panic!("expected PayloadSalvaged before RoomJoined, got {ev:?}");
+    };
+    assert_eq!(
+        (message_type, field.as_str()),
+        ("RoomJoined", "current_players[1]")
+    );
+    assert!(!error.is_empty());
+
+    let ev = events.recv().await.expect("event");
+    let SignalFishEvent::RoomJoined {
+        room_code,
+        current_players,
+        ..
+    } = ev
+    else {
+        panic!("expected RoomJoined, got {ev:?}");
+    };
+    assert_eq!(room_code, "SALV01");
+    let names: Vec<_> = current_players.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Alice", "Carol"]);
+    assert_eq!(client.current_room_code().await.as_deref(), Some("SALV01"));
+    assert_eq!(client.stats().messages_undecodable, 0);
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn room_joined_salvage_defaults_a_malformed_optional_field() {
+    let json = signal_fish_client::testing::room_joined("SALV02").json();
+    let mut message: serde_json::Value = serde_json::from_str(&json).unwrap();
+    message["data"]["reconnection_token"] = serde_json::json!(42);
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(message.to_string())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    assert!(
+        matches!(
+            &ev,
+            SignalFishEvent::PayloadSalvaged { field, .. } if field == "reconnection_token"
+        ),
+        "{ev:?}"
+    );
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::RoomJoined { .. }), "{ev:?}");
+    assert_eq!(client.snapshot().reconnection_token, None);
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn strict_payloads_report_a_corrupt_room_joined_as_undecodable() {
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(room_joined_with_corrupt_player())),
+            Some(Ok(pong_json())),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_strict_payloads(true),
+    );
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::DecodeFailed { .. }), "{ev:?}");
+    let ev = events.recv().await.expect("event");
+    assert!(matches!(ev, SignalFishEvent::Pong), "{ev:?}");
+    assert_eq!(client.current_room_code().await, None);
+    assert_eq!(client.stats().messages_undecodable, 1);
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Disconnected enrichment: last_server_error
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn disconnected_carries_last_server_error_after_server_close() {
+    // The server's slow-consumer eviction shape: a best-effort Error farewell
+    // followed by a close. The terminal Disconnected must carry the farewell
+    // so the disconnect can be attributed.
+    let (_client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(error_json(
+            "Disconnected as a slow consumer",
+            Some(ErrorCode::SlowConsumer),
+        ))),
+        None, // server closes
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("Error event");
+    assert!(matches!(ev, SignalFishEvent::Error { .. }));
+
+    let ev = events.recv().await.expect("Disconnected event");
+    match ev {
+        SignalFishEvent::Disconnected {
+            last_server_error, ..
+        } => {
+            let info = last_server_error.expect("farewell must be attributed");
+            assert_eq!(info.error_code, Some(ErrorCode::SlowConsumer));
+            assert!(info.message.contains("slow consumer"));
+        }
+        other => panic!("expected Disconnected, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn disconnected_last_server_error_is_none_without_prior_error() {
+    let (_client, mut events, _sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json())), None]);
+
+    drain_until_authenticated(&mut events).await;
+
+    let ev = events.recv().await.expect("Disconnected event");
+    match ev {
+        SignalFishEvent::Disconnected {
+            reason,
+            last_server_error,
+        } => {
+            assert!(
+                matches!(reason, DisconnectReason::ServerClosed { close: None, .. }),
+                "bare close has no Close frame: {reason:?}"
+            );
+            assert_eq!(last_server_error, None);
+        }
+        other => panic!("expected Disconnected, got {other:?}"),
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Wedged-consumer shutdown: graceful close instead of abort-only
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn shutdown_completes_gracefully_with_wedged_consumer() {
+    // A consumer that stops draining wedges the transport loop in the event
+    // send. Pre-0.7.0 the shutdown oneshot was starved too, so shutdown()
+    // could only abort the task, leaving the transport unclosed. Now the
+    // event send races the shutdown signal: the loop unblocks, closes the
+    // transport, and shutdown() completes without reaching the abort.
+    let (transport, _sent, closed) = MockTransport::new(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(pong_json())),
+        Some(Ok(pong_json())),
+    ]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_event_channel_capacity(1)
+        .with_shutdown_timeout(std::time::Duration::from_secs(5));
+    let (mut client, events) = SignalFishClient::start(transport, suite_config(config));
+
+    // Never drain `events`; give the loop time to wedge on a full channel.
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let started = std::time::Instant::now();
+    client.shutdown().await;
+    let elapsed = started.elapsed();
+
+    assert!(
+        closed.load(std::sync::atomic::Ordering::Relaxed),
+        "transport must be closed gracefully even with a wedged consumer"
+    );
+    assert!(
+        elapsed < std::time::Duration::from_secs(4),
+        "shutdown must not need the timeout/abort path; took {elapsed:?}"
+    );
+    drop(events);
+}
+
+#[tokio::test]
+async fn wedged_consumer_events_before_shutdown_are_not_lost_when_drained() {
+    // Guards against over-eager abandonment: a consumer that resumes draining
+    // BEFORE shutdown still receives every event, and shutdown then delivers
+    // the terminal Disconnected.
+    let (transport, _sent, closed) =
+        MockTransport::new(vec![Some(Ok(authenticated_json())), Some(Ok(pong_json()))]);
+    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(1);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+
+    // Let the loop wedge against the capacity-1 channel.
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    // Resume draining: everything must arrive in order.
+    let ev = events.recv().await.expect("Connected");
+    assert!(matches!(ev, SignalFishEvent::Connected));
+    let ev = events.recv().await.expect("Authenticated");
+    assert!(matches!(ev, SignalFishEvent::Authenticated { .. }));
+    let ev = events.recv().await.expect("Pong");
+    assert!(matches!(ev, SignalFishEvent::Pong));
+
+    client.shutdown().await;
+    let ev = events.recv().await.expect("Disconnected");
+    assert!(matches!(ev, SignalFishEvent::Disconnected { .. }));
+    assert!(closed.load(std::sync::atomic::Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn shutdown_races_wedged_terminal_disconnect() {
+    // The *terminal* Disconnected — emitted on a transport error, a clean
+    // server close, or a dropped handle — must race the shutdown signal too,
+    // not just the normal per-message event path. The four break-paths share
+    // one helper; this exercises it via a transport receive error that fires
+    // while the event channel is full (cap 1, undrained Connected), so the
+    // terminal delivery blocks. Pre-fix those paths used a blocking emit that
+    // ignored shutdown, pinning shutdown() to its full timeout/abort. A
+    // generous timeout makes the racing path (ms) unmistakable vs blocking (s).
+    let (transport, _sent, closed) = MockTransport::new(vec![Some(Err(
+        SignalFishError::TransportReceive("boom".into()),
+    ))]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_event_channel_capacity(1)
+        .with_shutdown_timeout(std::time::Duration::from_secs(10));
+    // `_events` is bound (not `_`) so the channel stays open and full — that
+    // is what wedges the terminal delivery.
+    let (mut client, _events) = SignalFishClient::start(transport, suite_config(config));
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let started = std::time::Instant::now();
+    client.shutdown().await;
+    let elapsed = started.elapsed();
+    assert!(
+        elapsed < std::time::Duration::from_secs(3),
+        "terminal Disconnected must race shutdown, not block until the abort \
+         timeout; took {elapsed:?}"
+    );
+    // Graceful shutdown always releases the connection: the transport must be
+    // closed even when shutdown wins the race against the wedged delivery.
+    assert!(
+        closed.load(std::sync::atomic::Ordering::Relaxed),
+        "the transport must be closed on the terminal break-path, not left \
+         open until the task is dropped"
+    );
+}
+
+/// Drain `events` until the channel closes.
+async fn drain_to_close(
+    events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>,
+) -> Vec<SignalFishEvent> {
+    let mut tail = Vec::new();
+    while let Some(event) = events.recv().await {
+        tail.push(event);
+    }
+    tail
+}
+
+/// Asserts the stream ended with exactly one `Disconnected` and returns its reason.
+fn terminal_reason(tail: &[SignalFishEvent]) -> DisconnectReason {
+    let disconnected = tail
+        .iter()
+        .filter(|event| matches!(event, SignalFishEvent::Disconnected { .. }))
+        .count();
+    assert_eq!(disconnected, 1, "exactly one Disconnected: {tail:?}");
+    match tail.last() {
+        Some(SignalFishEvent::Disconnected { reason, .. }) => reason.clone(),
+        _ => panic!("Disconnected must be the last event before None: {tail:?}"),
+    }
+}
+
+/// Transport whose server side closes once `server_closed` is set, counting
+/// `close()` calls.
+struct RacingCloseTransport {
+    incoming: VecDeque<String>,
+    server_closed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    close_calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl Transport for RacingCloseTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        frame.take();
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        if let Some(text) = self.incoming.pop_front() {
+            std::task::Poll::Ready(Some(Ok(TransportFrame::Text(text))))
+        } else if self
+            .server_closed
+            .load(std::sync::atomic::Ordering::Acquire)
+        {
+            std::task::Poll::Ready(None)
+        } else {
+            std::task::Poll::Pending
+        }
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.close_calls
+            .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn close_info(&self) -> Option<signal_fish_client::TransportCloseInfo> {
+        self.server_closed
+            .load(std::sync::atomic::Ordering::Acquire)
+            .then(|| signal_fish_client::TransportCloseInfo {
+                code: Some(1001),
+                reason: Some("going away".into()),
+                clean: Some(true),
+                initiated_by_peer: true,
+            })
+    }
+}
+
+#[tokio::test]
+async fn server_close_racing_shutdown_closes_once_and_reports_the_server() {
+    // The server's close is already pending when shutdown fires, so both
+    // loop branches are ready at once. Whichever `select!` picks, the server
+    // came first: its reason must win, and the transport is closed once.
+    for run in 0..200 {
+        let server_closed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let close_calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let transport = RacingCloseTransport {
+            incoming: VecDeque::from([authenticated_json()]),
+            server_closed: std::sync::Arc::clone(&server_closed),
+            close_calls: std::sync::Arc::clone(&close_calls),
+        };
+        let (mut client, mut events) = SignalFishClient::start(
+            transport,
+            suite_config(SignalFishConfig::new("mb_test_integration")),
+        );
+        drain_until_authenticated(&mut events).await;
+
+        server_closed.store(true, std::sync::atomic::Ordering::Release);
+        client.shutdown().await;
+
+        let reason = terminal_reason(&drain_to_close(&mut events).await).to_string();
+        assert!(
+            reason.starts_with("closed by server: code=Some(1001)"),
+            "run {run}: expected the server's close reason, got {reason:?}"
+        );
+        assert_eq!(
+            close_calls.load(std::sync::atomic::Ordering::Acquire),
+            1,
+            "run {run}: transport closed more than once"
+        );
+    }
+}
+
+#[tokio::test]
+async fn stream_ends_with_disconnected_when_shutdown_wins_a_full_channel() {
+    // The terminal delivery is wedged on a full channel when shutdown
+    // arrives. Shutdown must still return promptly, and the receiver must
+    // still see Disconnected — after the buffered Connected, before None.
+    let (transport, _sent, _closed) = MockTransport::new(vec![Some(Err(
+        SignalFishError::TransportReceive("boom".into()),
+    ))]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_event_channel_capacity(1)
+        .with_shutdown_timeout(std::time::Duration::from_secs(10));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let started = std::time::Instant::now();
+    client.shutdown().await;
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+
+    let tail = drain_to_close(&mut events).await;
+    assert!(matches!(tail[0], SignalFishEvent::Connected), "{tail:?}");
+    let reason = terminal_reason(&tail).to_string();
+    assert!(reason.contains("boom"), "{reason}");
+}
+
+#[tokio::test]
+async fn stream_ends_with_disconnected_after_shutdown_timeout_abort() {
+    let transport = HangingCloseTransport::new(vec![Some(Ok(authenticated_json()))]);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_shutdown_timeout(std::time::Duration::from_millis(1));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    drain_until_authenticated(&mut events).await;
+
+    client.shutdown().await;
+
+    let tail = drain_to_close(&mut events).await;
+    assert_eq!(terminal_reason(&tail), DisconnectReason::ClientShutdown);
+}
+
+#[tokio::test]
+async fn stream_ends_with_disconnected_when_dropped_in_runtime() {
+    let (transport, _sent, _closed) = MockTransport::new(vec![Some(Ok(authenticated_json()))]);
+    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(2);
+    let (client, mut receivers) =
+        SignalFishClient::start_with_subscribers(transport, suite_config(config), 2);
+    let mut drained = receivers.remove(0);
+    drain_until_authenticated(&mut drained).await;
+    // The second subscriber never drained: its channel is full, so its
+    // Disconnected has to wait for capacity.
+    drop(client);
+
+    let tail = drain_to_close(&mut drained).await;
+    assert_eq!(terminal_reason(&tail), DisconnectReason::ClientShutdown);
+
+    let tail = drain_to_close(&mut receivers[0]).await;
+    assert_eq!(
+        tail.len(),
+        3,
+        "Connected, Authenticated, Disconnected: {tail:?}"
+    );
+    assert_eq!(terminal_reason(&tail), DisconnectReason::ClientShutdown);
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Slow consumers: backpressure instead of loss
+// ════════════════════════════════════════════════════════════════════
+
+/// Read `consumer` up to and including the terminal `Disconnected`.
+async fn slow_drain_to_disconnect(consumer: &mut SlowConsumer) -> Vec<SignalFishEvent> {
+    let mut events = Vec::new();
+    while let Some(event) = consumer.recv().await {
+        let terminal = matches!(event, SignalFishEvent::Disconnected { .. });
+        events.push(event);
+        if terminal {
+            return events;
+        }
+    }
+    panic!("channel closed without Disconnected: {events:?}");
+}
+
+fn game_data_sequence(events: &[SignalFishEvent]) -> Vec<u64> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            SignalFishEvent::GameData { data, .. } => data["n"].as_u64(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn sustained_slow_consumption_loses_no_event_and_bounds_the_backlog() {
+    let sender = uuid::Uuid::from_u128(7);
+    let mut script = vec![Some(Ok(authenticated_json()))];
+    script.extend((0..40).map(|n| Some(Ok(game_data_json(sender, serde_json::json!({ "n": n }))))));
+    script.push(None);
+    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(4);
+    let (mut client, events, _sent, _closed) = start_client_with_config(script, config);
+
+    let mut consumer =
+        SlowConsumer::new(events).with_artificial_delay(std::time::Duration::from_millis(5));
+    let events = slow_drain_to_disconnect(&mut consumer).await;
+
+    assert_eq!(game_data_sequence(&events), (0..40).collect::<Vec<_>>());
+    assert!(matches!(events[0], SignalFishEvent::Connected));
+    let report = consumer.report();
+    assert_eq!(
+        report.received, 43,
+        "Connected, Authenticated, 40 GameData, Disconnected"
+    );
+    assert_eq!(report.peak_backlog, 4, "the loop waited on a full channel");
+    assert_eq!(report.delayed, std::time::Duration::from_millis(5 * 43));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_game_data_handling_still_ends_with_the_terminal_disconnect() {
+    let sender = uuid::Uuid::from_u128(7);
+    let mut script = vec![Some(Ok(authenticated_json()))];
+    script.extend((0..10).map(|n| Some(Ok(game_data_json(sender, serde_json::json!({ "n": n }))))));
+    script.push(Some(Err(SignalFishError::TransportReceive("reset".into()))));
+    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(2);
+    let (mut client, events, _sent, _closed) = start_client_with_config(script, config);
+
+    let mut consumer = SlowConsumer::new(events).with_delay_fn(|event| match event {
+        SignalFishEvent::GameData { .. } => std::time::Duration::from_millis(20),
+        _ => std::time::Duration::ZERO,
+    });
+    let events = slow_drain_to_disconnect(&mut consumer).await;
+
+    assert_eq!(game_data_sequence(&events), (0..10).collect::<Vec<_>>());
+    let Some(SignalFishEvent::Disconnected { reason, .. }) = events.last() else {
+        panic!("expected a terminal Disconnected: {events:?}");
+    };
+    assert!(reason.to_string().contains("reset"), "{reason:?}");
+    let report = consumer.report();
+    assert_eq!(report.delayed, std::time::Duration::from_millis(20 * 10));
+    assert!(report.peak_backlog <= 2, "{report:?}");
+    client.shutdown().await;
+}
+
+/// Authentication, game data numbered `0..count`, then a clean close.
+fn numbered_game_data_script(count: u64) -> Vec<Option<Result<String, SignalFishError>>> {
+    let sender = uuid::Uuid::from_u128(7);
+    let mut script = vec![Some(Ok(authenticated_json()))];
+    script.extend(
+        (0..count).map(|n| Some(Ok(game_data_json(sender, serde_json::json!({ "n": n }))))),
+    );
+    script.push(None);
+    script
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_slow_fan_out_subscriber_holds_back_every_receiver_without_loss() {
+    let (transport, _sent, _closed) = MockTransport::new(numbered_game_data_script(30));
+    let config = SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(3);
+    let (mut client, mut receivers) =
+        SignalFishClient::start_with_subscribers(transport, suite_config(config), 2);
+    let mut slow = SlowConsumer::new(receivers.remove(1))
+        .with_artificial_delay(std::time::Duration::from_millis(10));
+    let mut fast = receivers.remove(0);
+    let fast_reader = tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = fast.recv().await {
+            let terminal = matches!(event, SignalFishEvent::Disconnected { .. });
+            events.push((tokio::time::Instant::now(), event));
+            if terminal {
+                break;
+            }
+        }
+        events
+    });
+
+    let started = tokio::time::Instant::now();
+    let slow_events = slow_drain_to_disconnect(&mut slow).await;
+    let fast_events = fast_reader.await.unwrap();
+
+    assert_eq!(
+        game_data_sequence(&slow_events),
+        (0..30).collect::<Vec<_>>()
+    );
+    let fast_only: Vec<SignalFishEvent> =
+        fast_events.iter().map(|(_, event)| event.clone()).collect();
+    assert_eq!(game_data_sequence(&fast_only), (0..30).collect::<Vec<_>>());
+    let report = slow.report();
+    assert_eq!(report.received, 33, "{report:?}");
+    assert_eq!(report.lagged, 0, "a fan-out subscriber never loses events");
+    assert_eq!(
+        report.peak_backlog, 3,
+        "the loop waited on its full channel"
+    );
+    // The fan-out delivers in lockstep, so the fast receiver saw its
+    // terminal event only once the slow one had worked through nearly all
+    // of its 330ms of handling.
+    let (fast_done, _) = fast_events.last().unwrap();
+    assert!(
+        *fast_done - started >= std::time::Duration::from_millis(250),
+        "the slow subscriber paced the fast one: {:?}",
+        *fast_done - started
+    );
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_slow_broadcast_subscriber_lags_without_holding_back_the_receiver() {
+    let (transport, _sent, _closed) = MockTransport::new(numbered_game_data_script(30));
+    let config = SignalFishConfig::new("mb_test_integration").with_broadcast_events(4);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    let mut slow = SlowConsumer::new(client.subscribe_broadcast().expect("broadcast is enabled"))
+        .with_artificial_delay(std::time::Duration::from_millis(10));
+
+    let started = tokio::time::Instant::now();
+    let fast = drain_to_close(&mut events).await;
+    assert_eq!(game_data_sequence(&fast), (0..30).collect::<Vec<_>>());
+    assert_eq!(
+        tokio::time::Instant::now(),
+        started,
+        "the receiver never waited on the broadcast subscriber"
+    );
+
+    let slow_events = slow.drain().await;
+    assert!(matches!(
+        slow_events.last(),
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    let seen = game_data_sequence(&slow_events);
+    assert!(seen.windows(2).all(|pair| pair[0] < pair[1]), "{seen:?}");
+    let report = slow.report();
+    assert!(report.lagged > 0, "{report:?}");
+    assert_eq!(report.received + report.lagged, 33, "{report:?}");
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// PlayerJoined with ConnectionInfo::Direct
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn player_joined_with_connection_info_direct() {
+    use signal_fish_client::protocol::PlayerInfo;
+
+    let new_player = uuid::Uuid::from_u128(777);
+    let pj_json = serde_json::to_string(&ServerMessage::PlayerJoined {
+        player: PlayerInfo {
+            id: new_player,
+            name: "ConnectedPlayer".into(),
+            is_authority: true,
+            is_ready: true,
+            connected_at: "2026-02-20T12:00:00Z".into(),
+            connection_info: Some(ConnectionInfo::Direct {
+                host: "10.0.0.5".into(),
+                port: 5555,
+            }),
+            epoch: None,
+            seq: None,
+        },
+    })
+    .expect("serialize");
+
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(room_joined_json())),
+        Some(Ok(pj_json)),
+    ]);
+
+    drain_until_authenticated(&mut events).await;
+    let _rj = events.recv().await; // RoomJoined
+
+    let ev = events.recv().await.expect("event");
+    if let SignalFishEvent::PlayerJoined { player } = ev {
+        assert_eq!(player.name, "ConnectedPlayer");
+        assert_eq!(player.id, new_player);
+        assert!(player.is_authority);
+        assert!(player.is_ready);
+        if let Some(ConnectionInfo::Direct { host, port }) = player.connection_info {
+            assert_eq!(host, "10.0.0.5");
+            assert_eq!(port, 5555);
+        } else {
+            panic!(
+                "expected Direct connection_info, got {:?}",
+                player.connection_info
+            );
+        }
+    } else {
+        panic!("expected PlayerJoined event, got {ev:?}");
+    }
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Protocol v2/v3: start_game, mesh signaling, and the negotiation guard
+// ════════════════════════════════════════════════════════════════════
+
+/// Consume events until a `ProtocolInfo` event is observed (which proves the
+/// client processed the negotiation and updated its state).
+async fn drain_until_protocol_info(rx: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) {
+    loop {
+        let ev = rx.recv().await.expect("expected ProtocolInfo event");
+        if matches!(ev, SignalFishEvent::ProtocolInfo(_)) {
+            return;
+        }
+    }
+}
+
+/// Parse all retained outgoing messages into `ClientMessage`s.
+///
+/// Every captured frame MUST deserialize cleanly: silently dropping
+/// unparsable frames would let a malformed or unexpected wire shape pass
+/// assertions like "no v3 message reached the wire" that depend on *seeing*
+/// every outgoing message. A parse failure here is a real bug in the client,
+/// so we surface it loudly instead of hiding it.
+fn sent_messages(sent: &SentLog) -> Vec<ClientMessage> {
+    sent.latest(sent.len())
+        .iter()
+        .map(|m| {
+            serde_json::from_str::<ClientMessage>(m)
+                .unwrap_or_else(|e| panic!("outgoing client message must deserialize: {e}\n{m}"))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn start_game_sends_start_game_message() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+    drain_until_authenticated(&mut events).await;
+
+    client.start_game().expect("start_game");
+    wait_for_sent_len(&sent, 2).await;
+
+    assert!(sent_messages(&sent)
+        .iter()
+        .any(|m| matches!(m, ClientMessage::StartGame)));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn start_game_available_on_relay_floor() {
+    // start_game is the universal v2 change — NOT gated behind v3 negotiation.
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+    drain_until_authenticated(&mut events).await;
+    assert!(client.negotiated_protocol_version().is_none());
+
+    client
+        .start_game()
+        .expect("start_game must work on the relay floor");
+    wait_for_sent_len(&sent, 2).await;
+    assert!(sent_messages(&sent)
+        .iter()
+        .any(|m| matches!(m, ClientMessage::StartGame)));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn send_signal_before_v3_returns_protocol_unsupported() {
+    let (mut client, mut events, sent, _closed) =
+        start_client(vec![Some(Ok(authenticated_json()))]);
+    drain_until_authenticated(&mut events).await;
+
+    // Authenticated but no `ProtocolInfo` yet → negotiation is still in flight,
+    // so the guard reports "pre-negotiation" (NOT "relay-only", which is
+    // reserved for a `ProtocolInfo` that resolved at the v2 floor — see
+    // `v2_protocol_info_keeps_relay_floor_guard`).
+    let err = client
+        .send_signal(uuid::Uuid::from_u128(2), PeerSignal::Offer("sdp".into()))
+        .expect_err("send_signal must fail before negotiation completes");
+    assert!(matches!(
+        err,
+        SignalFishError::ProtocolUnsupported {
+            mode: "pre-negotiation"
+        }
+    ));
+    // report_transport_status fails fast too.
+    assert!(matches!(
+        client.report_transport_status(TransportKind::WebRtc, true),
+        Err(SignalFishError::ProtocolUnsupported { .. })
+    ));
+
+    // No v3 message ever reached the wire.
+    assert!(!sent_messages(&sent).iter().any(|m| matches!(
+        m,
+        ClientMessage::Signal { .. } | ClientMessage::TransportStatus { .. }
+    )));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn send_signal_after_v3_negotiation_is_sent() {
+    let peer = uuid::Uuid::from_u128(2);
+    let (mut client, mut events, sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(Some(3)))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    drain_until_protocol_info(&mut events).await;
+    assert_eq!(client.negotiated_protocol_version(), Some(3));
+
+    client.send_offer(peer, "the-sdp").expect("send_offer");
+    wait_for_sent_len(&sent, 2).await;
+
+    let signal = sent_messages(&sent).into_iter().find_map(|m| match m {
+        ClientMessage::Signal { to, signal } if to == peer => Some(signal),
+        _ => None,
+    });
+    assert_eq!(signal, Some(serde_json::json!({ "Offer": "the-sdp" })));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn report_transport_status_after_v3_is_sent() {
+    let (mut client, mut events, sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(Some(3)))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    drain_until_protocol_info(&mut events).await;
+
+    client
+        .report_transport_status(TransportKind::WebRtc, true)
+        .expect("report_transport_status");
+    wait_for_sent_len(&sent, 2).await;
+    assert!(sent_messages(&sent).iter().any(|m| matches!(
+        m,
+        ClientMessage::TransportStatus {
+            transport: TransportKind::WebRtc,
+            connected: true
+        }
+    )));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn v2_protocol_info_keeps_relay_floor_guard() {
+    // A v2 ProtocolInfo (no version field) leaves negotiated version None, so v3
+    // sends still fail fast — and because a `ProtocolInfo` *did* arrive, the
+    // guard reports the terminal "relay-only" mode (contrast
+    // `send_signal_before_v3_returns_protocol_unsupported`, which is
+    // "pre-negotiation" because no `ProtocolInfo` has arrived).
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(None))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    drain_until_protocol_info(&mut events).await;
+    assert!(client.negotiated_protocol_version().is_none());
+    assert!(matches!(
+        client.send_offer(uuid::Uuid::from_u128(2), "x"),
+        Err(SignalFishError::ProtocolUnsupported { mode: "relay-only" })
+    ));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn v3_session_plan_and_signal_events_are_emitted() {
+    let peer = uuid::Uuid::from_u128(7);
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(Some(3)))),
+        Some(Ok(session_plan_json(peer, true))),
+        Some(Ok(signal_json(
+            peer,
+            serde_json::json!({ "Offer": "remote-sdp" }),
+        ))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    let mut saw_plan = false;
+    let mut saw_signal = false;
+    while !(saw_plan && saw_signal) {
+        match events.recv().await.expect("event") {
+            SignalFishEvent::SessionPlan {
+                topology,
+                transport,
+                peers,
+                fallback,
+                ..
+            } => {
+                assert!(matches!(topology, signal_fish_client::Topology::Mesh));
+                assert!(matches!(transport, TransportKind::WebRtc));
+                assert!(matches!(fallback, TransportKind::Relay));
+                assert_eq!(peers.len(), 1);
+                assert_eq!(peers[0].player_id, peer);
+                assert!(peers[0].initiate);
+                saw_plan = true;
+            }
+            SignalFishEvent::SignalReceived { from, signal } => {
+                assert_eq!(from, peer);
+                assert_eq!(
+                    PeerSignal::try_from(&signal).expect("typed signal"),
+                    PeerSignal::Offer("remote-sdp".into())
+                );
+                saw_signal = true;
+            }
+            _ => {}
+        }
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn new_peer_and_peer_transport_status_events_are_emitted() {
+    let peer = uuid::Uuid::from_u128(8);
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(new_peer_json(peer, true))),
+        Some(Ok(peer_transport_status_json(peer, true))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    let mut saw_new_peer = false;
+    let mut saw_status = false;
+    while !(saw_new_peer && saw_status) {
+        match events.recv().await.expect("event") {
+            SignalFishEvent::NewPeer {
+                peer_id,
+                you_initiate,
+            } => {
+                assert_eq!(peer_id, peer);
+                assert!(you_initiate);
+                saw_new_peer = true;
+            }
+            SignalFishEvent::PeerTransportStatus {
+                peer_id,
+                transport,
+                connected,
+            } => {
+                assert_eq!(peer_id, peer);
+                assert!(matches!(transport, TransportKind::WebRtc));
+                assert!(connected);
+                saw_status = true;
+            }
+            _ => {}
+        }
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn unknown_server_message_type_surfaces_decode_failed_then_next_arrives() {
+    // Forward-compat: a well-formed but unknown `type` surfaces as a
+    // DecodeFailed event carrying the wire tag, and the following valid
+    // message still arrives.
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(r#"{"type":"SomeFutureV4Message","data":{}}"#.to_string())),
+        Some(Ok(pong_json())),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    let ev = events.recv().await.expect("event after unknown type");
+    match ev {
+        SignalFishEvent::DecodeFailed { message_type, .. } => {
+            assert_eq!(message_type.as_deref(), Some("SomeFutureV4Message"));
+        }
+        other => panic!("expected DecodeFailed, got {other:?}"),
+    }
+    let ev = events.recv().await.expect("event after DecodeFailed");
+    assert!(matches!(ev, SignalFishEvent::Pong));
+    assert!(client.is_connected());
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn send_signal_before_authentication_is_pre_negotiation() {
+    // The `mode: "pre-negotiation"` branch of the guard: no auth scripted, so the
+    // client is connected but has not authenticated/negotiated.
+    let (mut client, _events, sent, _closed) = start_client(vec![]);
+
+    let err = client
+        .send_offer(uuid::Uuid::from_u128(2), "sdp")
+        .expect_err("send before negotiation must fail");
+    assert!(matches!(
+        err,
+        SignalFishError::ProtocolUnsupported {
+            mode: "pre-negotiation"
+        }
+    ));
+    assert!(!client.supports_mesh());
+    assert!(sent_messages(&sent)
+        .iter()
+        .all(|m| !matches!(m, ClientMessage::Signal { .. })));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn negotiated_version_resets_on_disconnect() {
+    // Note: the scripted clean-close races the mid-stream state, so we assert the
+    // post-disconnect state only. The full negotiate-then-reset cycle is proven
+    // deterministically by the polling client's synchronous equivalent.
+    let (mut client, mut events, _sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(Some(3)))),
+        None, // clean close
+    ]);
+    drain_until_authenticated(&mut events).await;
+
+    // Drain until the transport closes (clear_session_state runs before the
+    // Disconnected event is delivered).
+    loop {
+        match events.recv().await {
+            Some(SignalFishEvent::Disconnected { .. }) | None => break,
+            _ => {}
+        }
+    }
+    assert_eq!(client.negotiated_protocol_version(), None);
+    assert!(!client.supports_mesh());
+    assert!(client.send_offer(uuid::Uuid::from_u128(2), "x").is_err());
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn reconnect_restores_negotiated_version_from_missed_events() {
+    let peer = uuid::Uuid::from_u128(2);
+    let (mut client, mut events, sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(reconnected_with_protocol_info_json(Some(3)))),
+        ],
+        SignalFishConfig::new("mb_test_integration").enable_mesh(),
+    );
+    drain_until_authenticated(&mut events).await;
+    // Drain until the Reconnected event (state updated by then).
+    loop {
+        if matches!(
+            events.recv().await.expect("event"),
+            SignalFishEvent::Reconnected { .. }
+        ) {
+            break;
+        }
+    }
+    assert_eq!(client.negotiated_protocol_version(), Some(3));
+    assert!(client.supports_mesh());
+
+    client
+        .send_offer(peer, "sdp")
+        .expect("send_offer after reconnect");
+    wait_for_sent_len(&sent, 2).await;
+    assert!(sent_messages(&sent)
+        .iter()
+        .any(|m| matches!(m, ClientMessage::Signal { .. })));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn v4_negotiation_still_enables_mesh() {
+    // `>= 3` (not `== 3`) semantics: a future v4 negotiation must still enable mesh.
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(protocol_info_json(Some(4)))),
+        ],
+        SignalFishConfig::new("mb_test_integration").enable_mesh(),
+    );
+    drain_until_authenticated(&mut events).await;
+    drain_until_protocol_info(&mut events).await;
+    assert_eq!(client.negotiated_protocol_version(), Some(4));
+    assert!(client.supports_mesh());
+    client
+        .send_offer(uuid::Uuid::from_u128(2), "sdp")
+        .expect("v4 must enable mesh");
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn send_answer_ice_and_raw_signal_wire_shapes() {
+    let peer = uuid::Uuid::from_u128(5);
+    let (mut client, mut events, sent, _closed) = start_client(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(protocol_info_json(Some(3)))),
+    ]);
+    drain_until_authenticated(&mut events).await;
+    drain_until_protocol_info(&mut events).await;
+
+    client.send_answer(peer, "ans").expect("send_answer");
+    client
+        .send_ice_candidate(peer, "cand")
+        .expect("send_ice_candidate");
+    client
+        .send_raw_signal(peer, serde_json::json!({ "Renegotiate": true }))
+        .expect("send_raw_signal");
+    wait_for_sent_len(&sent, 4).await;
+
+    let signals: Vec<serde_json::Value> = sent_messages(&sent)
+        .into_iter()
+        .filter_map(|m| match m {
+            ClientMessage::Signal { to, signal } if to == peer => Some(signal),
+            _ => None,
+        })
+        .collect();
+    assert!(signals.contains(&serde_json::json!({ "Answer": "ans" })));
+    assert!(signals.contains(&serde_json::json!({ "IceCandidate": "cand" })));
+    // The raw escape hatch forwards an opaque value verbatim.
+    assert!(signals.contains(&serde_json::json!({ "Renegotiate": true })));
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Effective configuration
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn effective_config_tracks_the_source_of_each_value() {
+    use signal_fish_client::protocol::RateLimitInfo;
+    use signal_fish_client::{ConfigSource, ErrorCode};
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let mut config = SignalFishConfig::new("mb_test_integration")
+        .with_event_channel_capacity(64)
+        .with_protocol_version(3);
+    config.game_data_format = Some(GameDataEncoding::MessagePack);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+
+    let before = client.effective_config();
+    assert_eq!(before.event_channel_capacity.value, 64);
+    assert_eq!(before.event_channel_capacity.source, ConfigSource::Config);
+    assert_eq!(before.command_channel_capacity.value, 1024);
+    assert_eq!(
+        before.command_channel_capacity.source,
+        ConfigSource::Default
+    );
+    assert_eq!(before.game_data_encoding.source, ConfigSource::Config);
+    assert_eq!(before.protocol_version.value, Some(3));
+    assert_eq!(before.protocol_version.source, ConfigSource::Config);
+    assert_eq!(before.rate_limits.value, None);
+    assert_eq!(before.rate_limits.source, ConfigSource::Default);
+
+    feed.send(Ok(authenticated_json())).unwrap();
+    // The server settles on the v2 floor and rejects MessagePack.
+    feed.send(Ok(protocol_info_json(None))).unwrap();
+    feed.send(Ok(error_json(
+        "unsupported format",
+        Some(ErrorCode::UnsupportedGameDataFormat),
+    )))
+    .unwrap();
+    drain_until_authenticated(&mut events).await;
+    for _ in 0..2 {
+        events.recv().await.expect("ProtocolInfo, then Error");
+    }
+
+    let after = client.effective_config();
+    assert_eq!(after.game_data_encoding.value, GameDataEncoding::Json);
+    assert_eq!(after.game_data_encoding.source, ConfigSource::Server);
+    assert_eq!(after.protocol_version.value, None);
+    assert_eq!(after.protocol_version.source, ConfigSource::Server);
+    assert_eq!(
+        after.rate_limits.value,
+        Some(RateLimitInfo {
+            per_minute: 60,
+            per_hour: 1000,
+            per_day: 10000,
+        })
+    );
+    assert_eq!(after.rate_limits.source, ConfigSource::Server);
+    assert_eq!(after.event_channel_capacity, before.event_channel_capacity);
+
+    let report = serde_json::to_value(&after).unwrap();
+    assert_eq!(
+        report["event_channel_capacity"],
+        serde_json::json!({ "value": 64, "source": "Config" })
+    );
+    assert_eq!(
+        report["game_data_encoding"],
+        serde_json::json!({ "value": "json", "source": "Server" })
+    );
+    assert_eq!(report["command_channel_capacity"]["source"], "Default");
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn rate_limits_last_for_the_connection() {
+    use signal_fish_client::protocol::RateLimitInfo;
+
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    assert_eq!(
+        client.rate_limits(),
+        Some(RateLimitInfo {
+            per_minute: 60,
+            per_hour: 1000,
+            per_day: 10000,
+        })
+    );
+
+    drop(feed);
+    loop {
+        let ev = events.recv().await.expect("Disconnected");
+        if matches!(ev, SignalFishEvent::Disconnected { .. }) {
+            break;
+        }
+    }
+    assert_eq!(client.rate_limits(), None);
+    assert_eq!(client.effective_config().rate_limits.value, None);
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Runtime configuration
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test(start_paused = true)]
+async fn runtime_config_retunes_the_ping_interval_mid_session() {
+    use signal_fish_client::{ConfigSource, RuntimeConfigPatch};
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config =
+        SignalFishConfig::new("mb_test_integration").with_ping_interval(Duration::from_secs(10));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config.clone()));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    let pings = || sent.matching(of_type("Ping")).len();
+
+    // Ticks at 10 s and 20 s.
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(pings(), 2);
+
+    let faster = config.clone().with_ping_interval(Duration::from_secs(3));
+    client
+        .update_runtime_config(RuntimeConfigPatch::between(&config, &faster))
+        .expect("ping_interval is tunable");
+
+    // The old 30 s tick is gone; the new cadence counts from 25 s.
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(pings(), 2 + 3, "ticks at 28 s, 31 s and 34 s");
+
+    let effective = client.effective_config();
+    assert_eq!(effective.ping_interval.value, Some(Duration::from_secs(3)));
+    assert_eq!(effective.ping_interval.source, ConfigSource::Runtime);
+    assert_eq!(effective.time_sync_interval.source, ConfigSource::Default);
+
+    // Stopping the pings works the same way.
+    client
+        .update_runtime_config(RuntimeConfigPatch::new().with_ping_interval(None))
+        .expect("ping_interval is tunable");
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(pings(), 5);
+
+    // Same session throughout.
+    assert!(client.is_authenticated());
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+    assert!(matches!(
+        events.try_recv(),
+        Err(tokio::sync::mpsc::error::TryRecvError::Empty)
+    ));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn runtime_config_rejects_patches_to_fixed_fields() {
+    use signal_fish_client::{ConfigSource, RuntimeConfigPatch};
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config =
+        SignalFishConfig::new("mb_test_integration").with_ping_interval(Duration::from_secs(10));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config.clone()));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    let mut edited = config
+        .clone()
+        .with_ping_interval(Duration::from_secs(1))
+        .with_event_channel_capacity(8);
+    edited.app_id = "mb_other".into();
+    let err = client
+        .update_runtime_config(RuntimeConfigPatch::between(&config, &edited))
+        .expect_err("app_id and event_channel_capacity are fixed");
+    let SignalFishError::NotRuntimeTunable { fields } = &err else {
+        panic!("expected NotRuntimeTunable, got {err:?}");
+    };
+    assert_eq!(fields, &["app_id", "event_channel_capacity"]);
+    assert!(err.to_string().contains("app_id, event_channel_capacity"));
+
+    // Nothing was applied, including the tunable part.
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    assert_eq!(sent.matching(of_type("Ping")).len(), 1);
+    let effective = client.effective_config();
+    assert_eq!(effective.ping_interval.value, Some(Duration::from_secs(10)));
+    assert_eq!(effective.ping_interval.source, ConfigSource::Config);
+
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Transport liveness hints
+// ════════════════════════════════════════════════════════════════════
+
+type Hint = std::sync::Arc<std::sync::Mutex<Option<std::time::Instant>>>;
+
+/// [`FeedTransport`] that reports whatever liveness hint the test sets.
+struct HintTransport {
+    inner: FeedTransport,
+    hint: Hint,
+}
+
+impl Transport for HintTransport {
+    fn poll_send(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.inner.poll_send(cx, frame)
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.inner.poll_recv(cx)
+    }
+
+    fn poll_close(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.inner.poll_close(cx)
+    }
+
+    fn last_activity_hint(&self) -> Option<std::time::Instant> {
+        *self.hint.lock().unwrap()
+    }
+}
+
+async fn start_hint_client(
+    ping_interval: std::time::Duration,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+    SentLog,
+    Hint,
+) {
+    let (inner, feed, sent) = FeedTransport::new();
+    let hint = Hint::default();
+    let transport = HintTransport {
+        inner,
+        hint: hint.clone(),
+    };
+    let config = SignalFishConfig::new("mb_test_integration").with_ping_interval(ping_interval);
+    let (client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    (client, events, feed, sent, hint)
+}
+
+fn now_std() -> std::time::Instant {
+    tokio::time::Instant::now().into_std()
+}
+
+#[tokio::test(start_paused = true)]
+async fn fresh_transport_liveness_suppresses_heartbeat_pings() {
+    use std::time::Duration;
+
+    let (mut client, _events, _feed, sent, hint) = start_hint_client(Duration::from_secs(10)).await;
+    let pings = || sent.matching(of_type("Ping")).len();
+
+    // Without a hint the heartbeat is unchanged: ticks at 10 s and 20 s.
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    assert_eq!(pings(), 2);
+
+    // Transport-level traffic every 4 s keeps every later tick quiet.
+    for _ in 0..15 {
+        *hint.lock().unwrap() = Some(now_std());
+        tokio::time::sleep(Duration::from_secs(4)).await;
+    }
+    assert_eq!(pings(), 2, "no Ping while the transport saw the peer");
+    let since_recv = client.activity().since_last_recv.unwrap();
+    assert!(
+        since_recv <= Duration::from_secs(10),
+        "transport activity counts as inbound: {since_recv:?}"
+    );
+    assert_eq!(client.health().verdict, HealthVerdict::Healthy);
+
+    // Once the hint goes stale, pings resume one interval after it.
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(
+        pings(),
+        4,
+        "Pings at 91 s and 101 s, after the 81 s activity"
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn skipped_heartbeat_is_rescheduled_from_the_last_transport_activity() {
+    use std::time::Duration;
+
+    let (mut client, _events, _feed, sent, hint) = start_hint_client(Duration::from_secs(10)).await;
+    let pings = || sent.matching(of_type("Ping")).len();
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    *hint.lock().unwrap() = Some(now_std());
+
+    // The 10 s tick sees activity 5 s old and moves to 15 s.
+    tokio::time::sleep(Duration::from_secs(9)).await;
+    assert_eq!(pings(), 0);
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(pings(), 1, "Ping at 15 s, one interval after the activity");
+
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_missing_pong_closes_the_connection_with_heartbeat_timeout() {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_heartbeat(Duration::from_secs(10), Duration::from_secs(5));
+    let (client, mut events, sent, closed) =
+        start_client_with_config(vec![Some(Ok(authenticated_json()))], config);
+    drain_until_authenticated(&mut events).await;
+
+    tokio::time::sleep(Duration::from_secs(14)).await;
+    assert_eq!(sent.matching(of_type("Ping")).len(), 1, "Ping at 10 s");
+    assert!(client.is_connected(), "the Pong is not overdue yet");
+
+    match events.recv().await {
+        Some(SignalFishEvent::Disconnected { reason, .. }) => {
+            assert_eq!(reason, DisconnectReason::HeartbeatTimeout);
+        }
+        other => panic!("expected Disconnected, got {other:?}"),
+    }
+    assert!(closed.load(Ordering::Relaxed), "the transport was closed");
+    assert!(!client.is_connected());
+    assert_eq!(
+        client.effective_config().pong_timeout.value,
+        Some(Duration::from_secs(5))
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn answered_heartbeats_keep_the_connection_open() {
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_heartbeat(Duration::from_secs(10), Duration::from_secs(5));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    // Ticks at 10 s, 20 s, ...; each check lands 1 s after one.
+    tokio::time::sleep(Duration::from_secs(11)).await;
+    for pings in 1..=6 {
+        assert_eq!(sent.matching(of_type("Ping")).len(), pings);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        feed.send(Ok(pong_json())).unwrap();
+        assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert!(client.is_connected(), "past the 5 s timeout, but answered");
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn application_pings_push_back_the_heartbeat() {
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_heartbeat(Duration::from_secs(10), Duration::from_secs(5));
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    let pings = || sent.matching(of_type("Ping")).len();
+
+    tokio::time::sleep(Duration::from_secs(8)).await;
+    client.ping().unwrap();
+    wait_for_sent_len(&sent, 2).await;
+    feed.send(Ok(pong_json())).unwrap();
+
+    tokio::time::sleep(Duration::from_secs(9)).await;
+    assert_eq!(pings(), 1, "no heartbeat at 10 s after the 8 s ping");
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(pings(), 2, "heartbeat at 18 s");
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Off-loop decoding
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn decode_pipeline_applies_queued_frames_before_the_transport_error() {
+    let peer = uuid::Uuid::new_v4();
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(room_joined_json())),
+            Some(Ok(game_data_json(peer, serde_json::json!(1)))),
+            Some(Ok(game_data_json(peer, serde_json::json!(2)))),
+            Some(Ok("{ not json".into())),
+            Some(Err(SignalFishError::TransportReceive(
+                "network failure".into(),
+            ))),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_decode_pipeline(1),
+    );
+
+    let mut received = Vec::new();
+    while let Some(ev) = events.recv().await {
+        received.push(ev);
+    }
+    let [SignalFishEvent::Connected, SignalFishEvent::Authenticated { .. }, SignalFishEvent::RoomJoined { .. }, SignalFishEvent::GameData { data: first, .. }, SignalFishEvent::GameData { data: second, .. }, SignalFishEvent::DecodeFailed { .. }, SignalFishEvent::Disconnected { reason, .. }] =
+        received.as_slice()
+    else {
+        panic!("frames out of order: {received:#?}");
+    };
+    assert_eq!(
+        (first, second),
+        (&serde_json::json!(1), &serde_json::json!(2))
+    );
+    let reason = reason.to_string();
+    assert!(reason.contains("network failure"), "reason was: {reason}");
+    assert_eq!(
+        client.effective_config().decode_pipeline.value,
+        Some(1),
+        "depth is reported"
+    );
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn decode_pipeline_shutdown_abandons_a_large_queued_frame() {
+    let peer = uuid::Uuid::new_v4();
+    let bulky = serde_json::json!({ "blob": "x".repeat(5 * 1024 * 1024) });
+    let (mut client, mut events, _sent, _closed) = start_client_with_config(
+        vec![
+            Some(Ok(authenticated_json())),
+            Some(Ok(game_data_json(peer, bulky))),
+            Some(Ok(game_data_json(peer, serde_json::json!("after")))),
+        ],
+        SignalFishConfig::new("mb_test_integration").with_decode_pipeline(1),
+    );
+    drain_until_authenticated(&mut events).await;
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), client.shutdown())
+        .await
+        .expect("shutdown does not wait for the decode");
+    let kinds = drain_all(&mut events).await;
+    assert_eq!(kinds.last(), Some(&"Disconnected"));
+    assert!(
+        kinds.len() <= 3,
+        "at most the two game-data events precede Disconnected: {kinds:?}"
+    );
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Multiple subscribers
+// ════════════════════════════════════════════════════════════════════
+
+async fn drain_all(rx: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) -> Vec<&'static str> {
+    let mut kinds = Vec::new();
+    while let Some(ev) = rx.recv().await {
+        kinds.push(match ev {
+            SignalFishEvent::Connected => "Connected",
+            SignalFishEvent::Authenticated { .. } => "Authenticated",
+            SignalFishEvent::Pong => "Pong",
+            SignalFishEvent::GameData { .. } => "GameData",
+            SignalFishEvent::Disconnected { .. } => "Disconnected",
+            other => panic!("unexpected event {other:?}"),
+        });
+    }
+    kinds
+}
+
+#[tokio::test]
+async fn every_subscriber_sees_every_event_including_disconnected() {
+    let (transport, _sent, _closed) = MockTransport::new(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(pong_json())),
+        None,
+    ]);
+    let (_client, mut receivers) = SignalFishClient::start_with_subscribers(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test")),
+        2,
+    );
+    assert_eq!(receivers.len(), 2);
+
+    let expected = ["Connected", "Authenticated", "Pong", "Disconnected"];
+    for rx in &mut receivers {
+        assert_eq!(drain_all(rx).await, expected);
+    }
+}
+
+#[tokio::test]
+async fn dropping_a_stalled_subscriber_unblocks_the_others() {
+    // Capacity 1 makes the undrained subscriber stall the loop until dropped.
+    let (transport, _sent, _closed) = MockTransport::new(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(pong_json())),
+        Some(Ok(pong_json())),
+        None,
+    ]);
+    let config = SignalFishConfig::new("mb_test").with_event_channel_capacity(1);
+    let (_client, mut receivers) =
+        SignalFishClient::start_with_subscribers(transport, suite_config(config), 2);
+    let mut dropped = receivers.pop().expect("second receiver");
+    let mut kept = receivers.pop().expect("first receiver");
+
+    assert!(matches!(
+        kept.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    assert!(matches!(
+        dropped.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    drop(dropped);
+
+    assert_eq!(
+        drain_all(&mut kept).await,
+        ["Authenticated", "Pong", "Pong", "Disconnected"]
+    );
+}
+
+#[tokio::test]
+async fn zero_subscribers_is_clamped_to_one() {
+    let (transport, _sent, _closed) = MockTransport::new(vec![Some(Ok(authenticated_json()))]);
+    let (mut client, mut receivers) = SignalFishClient::start_with_subscribers(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test")),
+        0,
+    );
+    assert_eq!(receivers.len(), 1);
+    drain_until_authenticated(&mut receivers[0]).await;
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Late subscribers and dropped receivers
+// ════════════════════════════════════════════════════════════════════
+
+#[tokio::test(start_paused = true)]
+async fn subscribe_attaches_a_receiver_mid_session() {
+    use std::time::Duration;
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut first) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut first).await;
+
+    let mut second = client.subscribe().expect("subscribe while connected");
+    // Let the loop handle the subscription before the next frame arrives.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    feed.send(Ok(pong_json())).unwrap();
+    for rx in [&mut first, &mut second] {
+        let ev = rx.recv().await.expect("expected Pong");
+        assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    }
+
+    // A subscription still queued when the loop stops gets the end too.
+    let mut late = client.subscribe().expect("subscribe while connected");
+    client.shutdown().await;
+    for rx in [&mut first, &mut second, &mut late] {
+        let ev = rx.recv().await.expect("expected Disconnected");
+        assert!(
+            matches!(ev, SignalFishEvent::Disconnected { .. }),
+            "got {ev:?}"
+        );
+        assert!(rx.recv().await.is_none());
+    }
+    assert!(matches!(
+        client.subscribe(),
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+// ════════════════════════════════════════════════════════════════════
+// wait_for
+// ════════════════════════════════════════════════════════════════════
+
+/// Matches an `Announcement`, returning its text.
+fn announcement(event: &SignalFishEvent) -> Option<String> {
+    match event {
+        SignalFishEvent::Announcement { message, .. } => Some(message.clone()),
+        _ => None,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_for_returns_the_first_match_without_consuming_events() {
+    use std::time::Duration;
+
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let mut seen = 0;
+    let waiting = client.wait_for(Duration::from_secs(5), |event| {
+        seen += 1;
+        announcement(event)
+    });
+    // Let the loop register the waiter before the frames arrive.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    feed.send(Ok(pong_json())).unwrap();
+    feed.send(Ok(announcement_json("first", None))).unwrap();
+    feed.send(Ok(announcement_json("second", None))).unwrap();
+    assert_eq!(waiting.await.unwrap(), "first");
+    assert_eq!(seen, 2, "the matcher stops at the first match");
+
+    // Matched and unmatched events alike still reach the receiver, in order.
+    assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+    for expected in ["first", "second"] {
+        let event = events.recv().await.expect("expected Announcement");
+        assert_eq!(announcement(&event).as_deref(), Some(expected));
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_for_registers_when_called_so_a_later_action_cannot_race_it() {
+    use std::time::Duration;
+
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    // Nothing sleeps between the call and the frame; the waiter was queued
+    // first, so the loop has it before it reads the answer.
+    let waiting = client.wait_for(Duration::from_secs(5), |event| {
+        matches!(event, SignalFishEvent::Pong).then_some(())
+    });
+    client.ping().unwrap();
+    tokio::task::yield_now().await;
+    feed.send(Ok(pong_json())).unwrap();
+    waiting.await.unwrap();
+    assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_for_times_out_and_the_loop_keeps_running() {
+    use std::time::Duration;
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration").with_event_channel_capacity(4)),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    let waiting = client.wait_for(Duration::from_secs(1), announcement);
+    assert!(matches!(waiting.await, Err(SignalFishError::Timeout)));
+
+    // The expired waiter no longer holds the loop up, even past its
+    // channel's capacity.
+    for index in 0..10 {
+        feed.send(Ok(announcement_json(&format!("a{index}"), None)))
+            .unwrap();
+    }
+    for index in 0..10 {
+        let event = events.recv().await.expect("expected Announcement");
+        assert_eq!(announcement(&event), Some(format!("a{index}")));
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_for_aborts_on_disconnected_unless_it_matches_it() {
+    use std::time::Duration;
+
+    let (mut client, _events, _feed, _sent) = start_fed().await;
+    let aborted = client.wait_for(Duration::from_secs(5), announcement);
+    let ended = client.wait_for(Duration::from_secs(5), |event| match event {
+        SignalFishEvent::Disconnected { reason, .. } => Some(reason.clone()),
+        _ => None,
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    client.shutdown().await;
+    assert!(matches!(aborted.await, Err(SignalFishError::NotConnected)));
+    assert_eq!(ended.await.unwrap(), DisconnectReason::ClientShutdown);
+
+    assert!(matches!(
+        client.wait_for(Duration::from_secs(5), announcement).await,
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Broadcast events
+// ════════════════════════════════════════════════════════════════════
+
+/// A client fed by the test that also publishes to a broadcast channel of
+/// `capacity`.
+async fn start_broadcasting(
+    capacity: usize,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+) {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration").with_broadcast_events(capacity)),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    (client, events, feed)
+}
+
+#[tokio::test(start_paused = true)]
+async fn broadcast_subscribers_see_every_event_beside_the_receiver() {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let (mut client, mut events, feed) = start_broadcasting(16).await;
+    let mut lobby = client.subscribe_broadcast().expect("broadcast is enabled");
+    let mut netcode = client.subscribe_broadcast().expect("broadcast is enabled");
+    feed.send(Ok(pong_json())).unwrap();
+    feed.send(Ok(announcement_json("hello", None))).unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    client.shutdown().await;
+
+    for _ in 0..2 {
+        let event = format!("{:?}", events.recv().await.unwrap());
+        assert_eq!(event, format!("{:?}", lobby.recv().await.unwrap()));
+        assert_eq!(event, format!("{:?}", netcode.recv().await.unwrap()));
+    }
+    for rx in [&mut lobby, &mut netcode] {
+        assert!(matches!(
+            rx.recv().await,
+            Ok(SignalFishEvent::Disconnected {
+                reason: DisconnectReason::ClientShutdown,
+                ..
+            })
+        ));
+        assert!(matches!(rx.recv().await, Err(RecvError::Closed)));
+    }
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    assert!(client.subscribe_broadcast().is_none(), "the loop has ended");
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_lagging_broadcast_subscriber_never_holds_the_loop_up() {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let (mut client, mut events, feed) = start_broadcasting(2).await;
+    let mut lagging = client.subscribe_broadcast().expect("broadcast is enabled");
+    for index in 0..10 {
+        feed.send(Ok(announcement_json(&format!("a{index}"), None)))
+            .unwrap();
+    }
+    // The ordinary receiver gets all ten while nothing reads `lagging`.
+    for index in 0..10 {
+        let event = events.recv().await.expect("expected Announcement");
+        assert_eq!(announcement(&event), Some(format!("a{index}")));
+    }
+
+    assert!(matches!(lagging.recv().await, Err(RecvError::Lagged(8))));
+    for expected in ["a8", "a9"] {
+        let event = lagging.recv().await.unwrap();
+        assert_eq!(announcement(&event).as_deref(), Some(expected));
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn broadcast_delivery_is_off_by_default() {
+    let (mut client, _events, _feed, _sent) = start_fed().await;
+    assert!(client.subscribe_broadcast().is_none());
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_broadcast_channel_keeps_a_client_without_receivers_running() {
+    use signal_fish_client::OnReceiverDropped;
+    use std::time::Duration;
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (client, events) = SignalFishClient::start(
+        transport,
+        suite_config(
+            SignalFishConfig::new("mb_test_integration")
+                .with_broadcast_events(8)
+                .with_on_receiver_dropped(OnReceiverDropped::ShutdownClient),
+        ),
+    );
+    let mut observer = client.subscribe_broadcast().expect("broadcast is enabled");
+    drop(events);
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(pong_json())).unwrap();
+    loop {
+        match tokio::time::timeout(Duration::from_secs(5), observer.recv())
+            .await
+            .expect("the client keeps running")
+            .unwrap()
+        {
+            SignalFishEvent::Pong => break,
+            SignalFishEvent::Disconnected { .. } => panic!("the client shut down"),
+            _ => continue,
+        }
+    }
+    assert!(client.is_connected());
+}
+
+#[tokio::test(start_paused = true)]
+async fn on_event_handlers_see_events_until_their_guard_drops() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let handler = |name: &'static str| {
+        let seen = Arc::clone(&seen);
+        move |event: &SignalFishEvent| seen.lock().unwrap().push(format!("{name}:{event:?}"))
+    };
+    let first = client
+        .on_event(handler("first"))
+        .expect("register while connected");
+    let _second = client
+        .on_event(handler("second"))
+        .expect("register while connected");
+    // Let the loop handle both registrations before the next frame arrives.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    feed.send(Ok(pong_json())).unwrap();
+    assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    drop(first);
+    feed.send(Ok(room_left_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomLeft)
+    ));
+    client.shutdown().await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(
+        seen,
+        [
+            "first:Pong",
+            "second:Disconnected",
+            "second:Pong",
+            "second:RoomLeft"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn on_event_fails_once_the_client_has_stopped() {
+    let (mut client, _events, _feed, _sent) = start_fed().await;
+    client.shutdown().await;
+    assert!(matches!(
+        client.on_event(|_| {}),
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn enveloped_events_are_numbered_in_emission_order() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start_enveloped(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(pong_json())).unwrap();
+
+    let mut seen = Vec::new();
+    while let Some(envelope) = events.recv().await {
+        let pong = matches!(envelope.event, SignalFishEvent::Pong);
+        seen.push(envelope);
+        if pong {
+            break;
+        }
+    }
+    let seqs: Vec<u64> = seen.iter().map(|envelope| envelope.seq).collect();
+    assert_eq!(seqs, (1..=seen.len() as u64).collect::<Vec<_>>());
+    assert!(matches!(seen[0].event, SignalFishEvent::Connected));
+    assert!(seen
+        .windows(2)
+        .all(|pair| pair[0].received_at <= pair[1].received_at));
+    assert_eq!(client.last_event_seq(), seen.len() as u64);
+
+    client.shutdown().await;
+    let last = events.recv().await.expect("expected Disconnected");
+    assert!(matches!(last.event, SignalFishEvent::Disconnected { .. }));
+    assert_eq!(last.seq, seen.len() as u64 + 1);
+    assert_eq!(client.last_event_seq(), last.seq);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_late_enveloped_subscriber_sees_the_gap_before_it_joined() {
+    use std::time::Duration;
+
+    let (mut client, mut events, feed, _sent) = start_fed().await;
+    let before = client.last_event_seq();
+    assert!(before >= 2, "Connected and Authenticated were emitted");
+
+    let mut enveloped = client
+        .subscribe_enveloped()
+        .expect("subscribe while connected");
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    feed.send(Ok(pong_json())).unwrap();
+    let envelope = enveloped.recv().await.expect("expected Pong");
+    assert!(matches!(envelope.event, SignalFishEvent::Pong));
+    assert_eq!(envelope.seq, before + 1);
+    assert!(matches!(events.recv().await, Some(SignalFishEvent::Pong)));
+
+    client.shutdown().await;
+    assert!(matches!(
+        client.subscribe_enveloped(),
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_receivers_keep_the_client_running_by_default() {
+    use std::time::Duration;
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    drop(events);
+
+    feed.send(Ok(room_joined_json())).unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    assert!(client.is_connected());
+    assert!(
+        client.snapshot().room_id.is_some(),
+        "frames are still applied"
+    );
+    assert_eq!(client.stats().paused_frames_skipped, 0);
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_receivers_shut_the_client_down_on_request() {
+    use signal_fish_client::{ConfigSource, OnReceiverDropped};
+    use std::time::Duration;
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_on_receiver_dropped(OnReceiverDropped::ShutdownClient);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    drop(events);
+
+    // No frame arrives; the periodic check notices.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    assert!(!client.is_connected());
+    assert!(matches!(client.ping(), Err(SignalFishError::NotConnected)));
+    assert!(matches!(
+        client.subscribe(),
+        Err(SignalFishError::NotConnected)
+    ));
+    let effective = client.effective_config();
+    assert_eq!(
+        effective.on_receiver_dropped.value,
+        OnReceiverDropped::ShutdownClient
+    );
+    assert_eq!(effective.on_receiver_dropped.source, ConfigSource::Config);
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_receivers_pause_parsing_until_a_new_subscriber() {
+    use signal_fish_client::OnReceiverDropped;
+    use std::time::Duration;
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_ping_interval(Duration::from_secs(1))
+        .with_on_receiver_dropped(OnReceiverDropped::PauseParsing);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    drop(events);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    feed.send(Ok(room_joined_json())).unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    assert!(client.is_connected());
+    assert!(
+        client.snapshot().room_id.is_none(),
+        "paused frames are not applied"
+    );
+    assert_eq!(client.stats().paused_frames_skipped, 1);
+    assert_eq!(
+        sent.matching(of_type("Ping")).len(),
+        2,
+        "heartbeats continue at 1 s and 2 s"
+    );
+
+    let mut events = client.subscribe().expect("subscribe resumes parsing");
+    // Frames the loop reads before handling the subscription are skipped.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    feed.send(Ok(room_joined_json())).unwrap();
+    let ev = events.recv().await.expect("expected RoomJoined");
+    assert!(
+        matches!(ev, SignalFishEvent::RoomJoined { .. }),
+        "got {ev:?}"
+    );
+    assert!(client.snapshot().room_id.is_some());
+    assert_eq!(client.stats().paused_frames_skipped, 1);
+
+    client.shutdown().await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Automatic reconnection
+// ════════════════════════════════════════════════════════════════════
+
+type Dial = Result<FeedTransport, SignalFishError>;
+
+/// Start a reconnecting client whose factory hands out whatever the returned
+/// sender supplies, one dial per item.
+fn start_reconnecting(
+    policy: ReconnectPolicy,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    tokio::sync::mpsc::UnboundedSender<Dial>,
+) {
+    start_reconnecting_with(
+        SignalFishConfig::new("mb_test_integration").with_reconnect_policy(policy),
+    )
+}
+
+fn start_reconnecting_with(
+    config: SignalFishConfig,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    tokio::sync::mpsc::UnboundedSender<Dial>,
+) {
+    let (dial_tx, dial_rx) = tokio::sync::mpsc::unbounded_channel::<Dial>();
+    let dials = std::sync::Arc::new(tokio::sync::Mutex::new(dial_rx));
+    let factory = move || {
+        let dials = std::sync::Arc::clone(&dials);
+        async move {
+            dials
+                .lock()
+                .await
+                .recv()
+                .await
+                .unwrap_or(Err(SignalFishError::TransportClosed))
+        }
+    };
+    let (client, events) = SignalFishClient::start_with_reconnect(factory, suite_config(config));
+    (client, events, dial_tx)
+}
+
+/// Dial a fresh transport through `dials` and wait for its `Connected`.
+async fn redial(
+    dials: &tokio::sync::mpsc::UnboundedSender<Dial>,
+    events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>,
+) -> (Feed, SentLog) {
+    let (transport, feed, sent) = FeedTransport::new();
+    dials.send(Ok(transport)).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    (feed, sent)
+}
+
+async fn next_reconnecting(events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) -> u32 {
+    match events.recv().await {
+        Some(SignalFishEvent::Reconnecting { attempt, delay }) => {
+            assert!(delay <= std::time::Duration::from_millis(500) * 2u32.pow(attempt - 1));
+            attempt
+        }
+        other => panic!("expected Reconnecting, got {other:?}"),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn reconnect_redials_and_reauthenticates_after_the_transport_drops() {
+    let (mut client, mut events, dials) = start_reconnecting(ReconnectPolicy::default());
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+
+    drop(feed);
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+    assert!(!client.is_connected());
+    assert!(!client.is_authenticated());
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 1).await;
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    let triggers: Vec<_> = client
+        .state_history()
+        .iter()
+        .map(|transition| transition.trigger)
+        .collect();
+    assert_eq!(
+        triggers,
+        [
+            SessionTrigger::Authenticated,
+            SessionTrigger::TransportClosed,
+            SessionTrigger::TransportReopened,
+            SessionTrigger::Authenticated,
+        ]
+    );
+
+    client.shutdown().await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    assert!(events.recv().await.is_none());
+}
+
+/// Start a reconnecting client with `config`, authenticate its first
+/// connection, and drop it.
+async fn lose_first_connection(
+    config: SignalFishConfig,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    tokio::sync::mpsc::UnboundedSender<Dial>,
+) {
+    let (client, mut events, dials) = start_reconnecting_with(config);
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    expect_authenticated(&mut events).await;
+    feed.send(Err(SignalFishError::TransportReceive("reset".into())))
+        .unwrap();
+    next_reconnecting(&mut events).await;
+    (client, events, dials)
+}
+
+async fn expect_authenticated(events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>) {
+    match events.recv().await {
+        Some(SignalFishEvent::Authenticated { .. }) => {}
+        other => panic!("expected Authenticated, got {other:?}"),
+    }
+}
+
+/// The `tick` of each `GameData` in `sent`, oldest first.
+fn sent_ticks(sent: &SentLog) -> Vec<serde_json::Value> {
+    sent.matching(of_type("GameData"))
+        .iter()
+        .map(|frame| match serde_json::from_str(frame).unwrap() {
+            ClientMessage::GameData { data, .. } => data["tick"].clone(),
+            other => panic!("expected GameData, got {other:?}"),
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn commands_sent_during_an_outage_go_out_in_order_once_authenticated() {
+    let (mut client, mut events, dials) =
+        lose_first_connection(SignalFishConfig::new("mb_test_integration")).await;
+
+    client
+        .send_game_data(serde_json::json!({"tick": 1}))
+        .unwrap();
+    client.ping().unwrap();
+    client.set_ready().unwrap();
+    client
+        .send_game_data(serde_json::json!({"tick": 2}))
+        .unwrap();
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 1).await;
+    client
+        .send_game_data(serde_json::json!({"tick": 3}))
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    assert_eq!(
+        sent_types(&sent, sent.len()),
+        ["Authenticate"],
+        "held until Authenticated"
+    );
+
+    feed.send(Ok(authenticated_json())).unwrap();
+    expect_authenticated(&mut events).await;
+    wait_for_sent_len(&sent, 5).await;
+    assert_eq!(
+        sent_types(&sent, sent.len()),
+        [
+            "Authenticate",
+            "GameData",
+            "PlayerReady",
+            "GameData",
+            "GameData"
+        ],
+        "the ping is dropped, not held"
+    );
+    assert_eq!(sent_ticks(&sent), [1, 2, 3]);
+    assert_eq!(client.stats().offline_commands_evicted, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_full_offline_queue_evicts_its_oldest_command() {
+    let (mut client, mut events, dials) = lose_first_connection(
+        SignalFishConfig::new("mb_test_integration").with_offline_queue_capacity(2),
+    )
+    .await;
+
+    for tick in 1..=4 {
+        client
+            .send_game_data(serde_json::json!({"tick": tick}))
+            .unwrap();
+    }
+    assert_eq!(client.stats().offline_commands_evicted, 0);
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    expect_authenticated(&mut events).await;
+    wait_for_sent_len(&sent, 3).await;
+    assert_eq!(sent_ticks(&sent), [3, 4]);
+    assert_eq!(client.stats().offline_commands_evicted, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_zero_offline_queue_refuses_sends_during_an_outage() {
+    let (mut client, _events, _dials) = lose_first_connection(
+        SignalFishConfig::new("mb_test_integration").with_offline_queue_capacity(0),
+    )
+    .await;
+
+    assert!(matches!(
+        client.set_ready(),
+        Err(SignalFishError::NotConnected)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_failed_first_dial_backs_off_and_authenticates_once() {
+    let (_client, mut events, dials) = start_reconnecting(ReconnectPolicy::default());
+    dials.send(Err(SignalFishError::TransportClosed)).unwrap();
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+    dials.send(Err(SignalFishError::TransportClosed)).unwrap();
+    assert_eq!(next_reconnecting(&mut events).await, 2);
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn exhausted_attempts_end_the_client_with_disconnected() {
+    let policy = ReconnectPolicy {
+        max_attempts: Some(2),
+        ..ReconnectPolicy::default()
+    };
+    let (mut client, mut events, dials) = start_reconnecting(policy);
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    drop(feed);
+    for attempt in 1..=2 {
+        assert_eq!(next_reconnecting(&mut events).await, attempt);
+        dials.send(Err(SignalFishError::TransportClosed)).unwrap();
+    }
+
+    match events.recv().await {
+        Some(SignalFishEvent::Disconnected {
+            reason:
+                DisconnectReason::ReconnectExhausted {
+                    attempts,
+                    last_failure,
+                },
+            ..
+        }) => {
+            assert_eq!(attempts, 2);
+            assert!(
+                last_failure.to_string().starts_with("connect failed"),
+                "{last_failure}"
+            );
+        }
+        other => panic!("expected Disconnected, got {other:?}"),
+    }
+    assert!(events.recv().await.is_none());
+    assert!(matches!(client.ping(), Err(SignalFishError::NotConnected)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_rejected_authentication_is_not_retried() {
+    let (_client, mut events, dials) = start_reconnecting(ReconnectPolicy::default());
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    let rejected = serde_json::to_string(&ServerMessage::AuthenticationError {
+        error: "bad app".into(),
+        error_code: ErrorCode::InvalidAppId,
+    })
+    .unwrap();
+    feed.send(Ok(rejected)).unwrap();
+    drop(feed);
+
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::AuthenticationError { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    assert!(events.recv().await.is_none());
+}
+
+/// A cloneable handle onto one scripted connection, claimable once across
+/// all its clones. Each `None` in the script ends a receive.
+#[derive(Clone)]
+struct SharedConnection {
+    script: std::sync::Arc<std::sync::Mutex<VecDeque<Option<String>>>>,
+    claimed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    closes: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    sent: SentLog,
+}
+
+impl SharedConnection {
+    fn new(script: Vec<Option<String>>) -> Self {
+        Self {
+            script: std::sync::Arc::new(std::sync::Mutex::new(script.into())),
+            claimed: std::sync::Arc::default(),
+            closes: std::sync::Arc::default(),
+            sent: SentLog::new(),
+        }
+    }
+
+    fn closes(&self) -> usize {
+        self.closes.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+impl Transport for SharedConnection {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if let Some(TransportFrame::Text(message)) = frame.take() {
+            self.sent.record(message);
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        match self.script.lock().unwrap().pop_front() {
+            Some(Some(text)) => std::task::Poll::Ready(Some(Ok(TransportFrame::Text(text)))),
+            Some(None) => std::task::Poll::Ready(None),
+            None => std::task::Poll::Pending,
+        }
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.closes
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn claim_session(&mut self) -> bool {
+        !self.claimed.swap(true, std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+fn assert_transport_reused(event: Option<SignalFishEvent>) {
+    match event {
+        Some(SignalFishEvent::Disconnected {
+            reason: DisconnectReason::HandshakeRefused { detail },
+            ..
+        }) => assert_eq!(detail, SignalFishError::TransportReused.to_string()),
+        other => panic!("expected a refused handshake, got {other:?}"),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_factory_handing_back_a_used_transport_ends_the_client() {
+    let connection = SharedConnection::new(vec![Some(authenticated_json()), None]);
+    let factory = {
+        let connection = connection.clone();
+        move || std::future::ready(Ok(connection.clone()))
+    };
+    let (client, mut events) = SignalFishClient::start_with_reconnect(
+        factory,
+        suite_config(SignalFishConfig::new("mb_test_integration")),
+    );
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    expect_authenticated(&mut events).await;
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+
+    // The second dial returns the same connection: refused, not retried.
+    assert_transport_reused(events.recv().await);
+    assert!(events.recv().await.is_none());
+    assert!(client.handshake_failed());
+    assert_eq!(
+        connection.sent.matching(of_type("Authenticate")).len(),
+        1,
+        "one Authenticate per transport"
+    );
+    assert_eq!(connection.closes(), 1, "only the first session closed it");
+}
+
+#[tokio::test]
+async fn a_second_client_on_a_claimed_transport_sends_nothing() {
+    let connection = SharedConnection::new(vec![Some(authenticated_json())]);
+    let config = || suite_config(SignalFishConfig::new("mb_test_integration"));
+    let (mut first, mut first_events) = SignalFishClient::start(connection.clone(), config());
+    drain_until_authenticated(&mut first_events).await;
+
+    let (second, mut second_events) = SignalFishClient::start(connection.clone(), config());
+    assert_transport_reused(second_events.recv().await);
+    assert!(second_events.recv().await.is_none());
+    assert!(second.handshake_failed());
+
+    assert_eq!(connection.sent.len(), 1, "{:?}", connection.sent.latest(4));
+    assert_eq!(connection.closes(), 0);
+    assert!(first.is_authenticated());
+    first.shutdown().await;
+    assert_eq!(connection.closes(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_heartbeat_timeout_redials_a_reconnecting_client() {
+    use std::time::Duration;
+
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_heartbeat(Duration::from_secs(10), Duration::from_secs(5));
+    let (client, mut events, dials) = start_reconnecting_with(config);
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+    assert!(!client.is_connected());
+    drop(feed);
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_during_an_outage_ends_the_client() {
+    let (mut client, mut events, dials) = start_reconnecting(ReconnectPolicy::default());
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    drop(feed);
+    next_reconnecting(&mut events).await;
+
+    client.shutdown().await;
+    match events.recv().await {
+        Some(SignalFishEvent::Disconnected { reason, .. }) => {
+            assert_eq!(reason, DisconnectReason::ClientShutdown);
+        }
+        other => panic!("expected Disconnected, got {other:?}"),
+    }
+    assert!(events.recv().await.is_none());
+}
+
+/// Start a reconnecting client under `policy`, authenticate it, and join a
+/// room whose `RoomJoined` carries a reconnection token, then drop the
+/// transport. Returns once `Reconnecting` arrives.
+async fn lose_connection_in_room(
+    policy: ResumePolicy,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    tokio::sync::mpsc::UnboundedSender<Dial>,
+) {
+    lose_connection_in_room_with(
+        SignalFishConfig::new("mb_test_integration").with_resume_policy(policy),
+    )
+    .await
+}
+
+async fn lose_connection_in_room_with(
+    config: SignalFishConfig,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    tokio::sync::mpsc::UnboundedSender<Dial>,
+) {
+    let (client, mut events, dials) = start_reconnecting_with(config);
+    let (feed, _sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    let joined = signal_fish_client::testing::room_joined("RECON1")
+        .room_id(uuid::Uuid::from_u128(100))
+        .player_id(uuid::Uuid::from_u128(200))
+        .reconnection_token("resume-token")
+        .json();
+    feed.send(Ok(joined)).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomJoined { .. })
+    ));
+    drop(feed);
+    next_reconnecting(&mut events).await;
+    assert_eq!(client.snapshot().room_id, None);
+    (client, events, dials)
+}
+
+fn sent_types(sent: &SentLog, n: usize) -> Vec<String> {
+    sent.latest(n)
+        .iter()
+        .map(|frame| {
+            serde_json::from_str::<serde_json::Value>(frame).unwrap()["type"]
+                .as_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn auto_resume_reconnects_to_the_room_after_reauthenticating() {
+    let (mut client, mut events, dials) = lose_connection_in_room(ResumePolicy::Auto).await;
+    client.set_ready().unwrap();
+
+    let (feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(sent_types(&sent, 2), ["Authenticate", "Reconnect"]);
+    let reconnect: serde_json::Value =
+        serde_json::from_str(&sent.matching(of_type("Reconnect"))[0]).unwrap();
+    assert_eq!(reconnect["data"]["auth_token"], "resume-token");
+    assert_eq!(
+        reconnect["data"]["room_id"],
+        uuid::Uuid::from_u128(100).to_string()
+    );
+    assert_eq!(
+        reconnect["data"]["player_id"],
+        uuid::Uuid::from_u128(200).to_string()
+    );
+
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(reconnected_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Reconnected { .. })
+    ));
+    assert_eq!(client.snapshot().room_id, Some(uuid::Uuid::from_u128(100)));
+    assert_eq!(client.session_state(), SessionState::InRoom);
+    // Commands held during the outage follow the `Reconnect`.
+    wait_for_sent_len(&sent, 3).await;
+    assert_eq!(
+        sent_types(&sent, 3),
+        ["Authenticate", "Reconnect", "PlayerReady"]
+    );
+}
+
+/// Dial a second transport for a client that lost its first in a room,
+/// answer `Authenticate` and the automatic `Reconnect`, then a `Pong`.
+/// Returns the events before the `Pong`, and the feed keeping the transport
+/// up.
+async fn resume_on_a_second_transport(
+    dials: &tokio::sync::mpsc::UnboundedSender<Dial>,
+    events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>,
+) -> (Vec<String>, Feed) {
+    let (transport, feed, sent) = FeedTransport::new();
+    dials.send(Ok(transport)).unwrap();
+    // Let the reconnect backoff pass; `wait_for_sent_len` never idles.
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(sent_types(&sent, 2), ["Authenticate", "Reconnect"]);
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(reconnected_json())).unwrap();
+    feed.send(Ok(pong_json())).unwrap();
+    (recv_until_pong(events).await, feed)
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_resumed_outage_keeps_the_session_epoch_and_reemits_by_default() {
+    let (client, mut events, dials) = lose_connection_in_room(ResumePolicy::Auto).await;
+    assert_eq!(client.snapshot().session_epoch, 0);
+
+    let (seen, _feed) = resume_on_a_second_transport(&dials, &mut events).await;
+    assert_eq!(seen, ["Connected", "Authenticated", "Reconnected"]);
+    let snapshot = client.snapshot();
+    assert_eq!(snapshot.session_epoch, 0);
+    assert_eq!(snapshot.room_id, Some(uuid::Uuid::from_u128(100)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_resumed_outage_suppresses_repeated_session_events_when_reemit_is_disabled() {
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_resume_policy(ResumePolicy::Auto)
+        .with_reemit_session_events(false);
+    let (client, mut events, dials) = lose_connection_in_room_with(config).await;
+    let waiting = client.wait_authenticated(std::time::Duration::from_secs(30));
+
+    let (authenticated, (seen, _feed)) =
+        tokio::join!(waiting, resume_on_a_second_transport(&dials, &mut events));
+    assert_eq!(seen, ["Reconnected"]);
+    authenticated.expect("the suppressed Authenticated still answers waiters");
+    assert!(client.is_authenticated());
+    assert_eq!(client.snapshot().session_epoch, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn an_outage_that_cannot_resume_the_room_starts_a_new_session() {
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_resume_policy(ResumePolicy::Never)
+        .with_reemit_session_events(false);
+    let (client, mut events, dials) = lose_connection_in_room_with(config).await;
+    assert_eq!(client.snapshot().session_epoch, 1);
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    dials.send(Ok(transport)).unwrap();
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(pong_json())).unwrap();
+    assert_eq!(
+        recv_until_pong(&mut events).await,
+        ["Connected", "Authenticated"],
+        "a new session announces itself"
+    );
+    assert_eq!(client.snapshot().session_epoch, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn giving_up_reconnecting_ends_the_kept_session() {
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_resume_policy(ResumePolicy::Auto)
+        .with_reconnect_policy(ReconnectPolicy {
+            max_attempts: Some(1),
+            ..ReconnectPolicy::default()
+        });
+    let (client, mut events, dials) = lose_connection_in_room_with(config).await;
+    assert_eq!(client.snapshot().session_epoch, 0);
+
+    dials.send(Err(SignalFishError::TransportClosed)).unwrap();
+    loop {
+        match events.recv().await.expect("a terminal Disconnected") {
+            SignalFishEvent::Disconnected { .. } => break,
+            _ => continue,
+        }
+    }
+    assert_eq!(client.snapshot().session_epoch, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_failed_auto_resume_leaves_the_client_outside_any_room() {
+    let (client, mut events, dials) = lose_connection_in_room(ResumePolicy::Auto).await;
+    let (feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 2).await;
+    let failed = serde_json::to_string(&ServerMessage::ReconnectionFailed {
+        reason: "expired".into(),
+        error_code: ErrorCode::ReconnectionExpired,
+    })
+    .unwrap();
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(failed)).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::ReconnectionFailed { .. })
+    ));
+    assert_eq!(client.snapshot().room_id, None);
+    assert_eq!(client.session_state(), SessionState::Authenticated);
+    assert_eq!(
+        client.snapshot().session_epoch,
+        1,
+        "the room the session was kept for is gone"
+    );
+
+    // The failed room is not tried again after the next outage.
+    drop(feed);
+    next_reconnecting(&mut events).await;
+    let (_feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 1).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(sent_types(&sent, sent.len()), ["Authenticate"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn manual_resume_sends_reconnect_only_when_asked() {
+    let (mut client, mut events, dials) = lose_connection_in_room(ResumePolicy::Manual).await;
+    let (feed, sent) = redial(&dials, &mut events).await;
+    feed.send(Ok(authenticated_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert_eq!(sent_types(&sent, sent.len()), ["Authenticate"]);
+
+    client.resume().unwrap();
+    wait_for_sent_len(&sent, 2).await;
+    let reconnect: serde_json::Value =
+        serde_json::from_str(&sent.matching(of_type("Reconnect"))[0]).unwrap();
+    assert_eq!(reconnect["data"]["auth_token"], "resume-token");
+    feed.send(Ok(reconnected_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Reconnected { .. })
+    ));
+    assert!(matches!(client.resume(), Err(SignalFishError::NotInRoom)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn resume_without_a_held_room_fails_with_not_in_room() {
+    let (mut client, mut events, dials) = lose_connection_in_room(ResumePolicy::Never).await;
+    assert!(matches!(client.resume(), Err(SignalFishError::NotInRoom)));
+    let (_feed, sent) = redial(&dials, &mut events).await;
+    wait_for_sent_len(&sent, 1).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(sent_types(&sent, sent.len()), ["Authenticate"]);
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Session store
+// ════════════════════════════════════════════════════════════════════
+
+/// Wait until `store` holds a session ticket (`present`) or holds none.
+async fn wait_for_stored_ticket(store: &MemorySessionStore, present: bool) -> Option<Vec<u8>> {
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        loop {
+            let stored = store.get(SESSION_TICKET_KEY).await.unwrap();
+            if stored.is_some() == present {
+                return stored;
+            }
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("the session store never caught up")
+}
+
+#[tokio::test]
+async fn a_stored_ticket_resumes_the_room_on_start() {
+    let ticket = SessionTicket {
+        player_id: uuid::Uuid::from_u128(200),
+        room_id: uuid::Uuid::from_u128(100),
+        room_code: "RECON1".into(),
+        reconnection_token: "stored-token".into(),
+    };
+    let store = std::sync::Arc::new(MemorySessionStore::new());
+    store
+        .set(SESSION_TICKET_KEY, serde_json::to_vec(&ticket).unwrap())
+        .await
+        .unwrap();
+    let (transport, feed, sent) = FeedTransport::new();
+    let (client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration"))
+            .with_session_store(store.clone())
+            .with_resume_policy(ResumePolicy::Auto),
+    );
+
+    wait_for_sent_len(&sent, 2).await;
+    assert_eq!(sent_types(&sent, 2), ["Authenticate", "Reconnect"]);
+    let reconnect: serde_json::Value =
+        serde_json::from_str(&sent.matching(of_type("Reconnect"))[0]).unwrap();
+    assert_eq!(reconnect["data"]["auth_token"], "stored-token");
+
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(reconnected_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Reconnected { .. })
+    ));
+    assert_eq!(client.session_state(), SessionState::InRoom);
+    drop(client);
+}
+
+#[tokio::test]
+async fn a_stored_ticket_waits_for_resume_under_the_manual_policy() {
+    let ticket = SessionTicket {
+        player_id: uuid::Uuid::from_u128(200),
+        room_id: uuid::Uuid::from_u128(100),
+        room_code: "RECON1".into(),
+        reconnection_token: "stored-token".into(),
+    };
+    let store = std::sync::Arc::new(MemorySessionStore::new());
+    store
+        .set(SESSION_TICKET_KEY, serde_json::to_vec(&ticket).unwrap())
+        .await
+        .unwrap();
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration"))
+            .with_session_store(store)
+            .with_resume_policy(ResumePolicy::Manual),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert_eq!(sent_types(&sent, sent.len()), ["Authenticate"]);
+
+    client.resume().unwrap();
+    wait_for_sent_len(&sent, 2).await;
+    let reconnect: serde_json::Value =
+        serde_json::from_str(&sent.matching(of_type("Reconnect"))[0]).unwrap();
+    assert_eq!(reconnect["data"]["auth_token"], "stored-token");
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn a_corrupt_stored_ticket_is_discarded_and_the_client_starts_fresh() {
+    let store = std::sync::Arc::new(MemorySessionStore::new());
+    store
+        .set(SESSION_TICKET_KEY, b"{ not a ticket".to_vec())
+        .await
+        .unwrap();
+    let (transport, feed, sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration"))
+            .with_session_store(store.clone())
+            .with_resume_policy(ResumePolicy::Auto),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    assert_eq!(sent_types(&sent, sent.len()), ["Authenticate"]);
+    assert_eq!(wait_for_stored_ticket(&store, false).await, None);
+    assert!(matches!(client.resume(), Err(SignalFishError::NotInRoom)));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn the_stored_ticket_outlives_a_dropped_transport_but_not_a_leave() {
+    let store = std::sync::Arc::new(MemorySessionStore::new());
+    let joined = signal_fish_client::testing::room_joined("RECON1")
+        .room_id(uuid::Uuid::from_u128(100))
+        .player_id(uuid::Uuid::from_u128(200))
+        .reconnection_token("resume-token")
+        .json();
+
+    // A dropped transport leaves the ticket for the next process.
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (_client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration"))
+            .with_session_store(store.clone()),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(joined.clone())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomJoined { .. })
+    ));
+    let stored = wait_for_stored_ticket(&store, true).await.unwrap();
+    let ticket: SessionTicket = serde_json::from_slice(&stored).unwrap();
+    assert_eq!(ticket.reconnection_token, "resume-token");
+    assert_eq!(ticket.room_code, "RECON1");
+    drop(feed);
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    assert!(store.get(SESSION_TICKET_KEY).await.unwrap().is_some());
+
+    // Leaving ends the room for good.
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (mut client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_test_integration"))
+            .with_session_store(store.clone()),
+    );
+    feed.send(Ok(authenticated_json())).unwrap();
+    feed.send(Ok(joined)).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomJoined { .. })
+    ));
+    client.leave_room().unwrap();
+    feed.send(Ok(room_left_json())).unwrap();
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::RoomLeft)
+    ));
+    assert_eq!(wait_for_stored_ticket(&store, false).await, None);
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Cross-subscriber emission order
+// ════════════════════════════════════════════════════════════════════
+
+/// One scripted server frame in the randomized ordering mix.
+#[derive(Debug, Clone, Copy)]
+enum MixFrame {
+    Pong,
+    GameData,
+    Error,
+    Announcement,
+    Malformed,
+}
+
+impl MixFrame {
+    fn json(self, index: usize) -> String {
+        match self {
+            Self::Pong => pong_json(),
+            Self::GameData => game_data_json(
+                uuid::Uuid::from_u128(7),
+                serde_json::json!({ "index": index }),
+            ),
+            Self::Error => error_json(&format!("error {index}"), None),
+            Self::Announcement => announcement_json(&format!("notice {index}"), None),
+            Self::Malformed => format!("{{not json {index}"),
+        }
+    }
+}
+
+/// Distinguishes events of the same variant by their scripted payload.
+fn emission_label(event: &SignalFishEvent) -> String {
+    match event {
+        SignalFishEvent::GameData { data, .. } => format!("GameData {data}"),
+        SignalFishEvent::Error { message, .. } => format!("Error {message}"),
+        SignalFishEvent::Announcement { message, .. } => format!("Announcement {message}"),
+        other => format!("{other:?}"),
+    }
+}
+
+/// Script `frames` through a client with `subscribers` receivers of
+/// `capacity`, draining each receiver on its own task (yielding between
+/// events where `slow` says so), and check the fan-out order against the
+/// global sequence recorded by an [`EmissionHook`].
+///
+/// [`EmissionHook`]: signal_fish_client::testing::EmissionHook
+fn check_emission_order(frames: &[MixFrame], subscribers: usize, capacity: usize, slow: &[bool]) {
+    use std::sync::{Arc, Mutex};
+
+    use signal_fish_client::testing::EmissionHook;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let log: Arc<Mutex<Vec<(usize, String)>>> = Arc::default();
+        let recorder = Arc::clone(&log);
+        let config = SignalFishConfig::new("mb_test")
+            .with_event_channel_capacity(capacity)
+            .with_emission_hook(EmissionHook::new(move |subscriber, event| {
+                recorder
+                    .lock()
+                    .unwrap()
+                    .push((subscriber, emission_label(event)));
+            }));
+        let mut incoming = vec![Some(Ok(authenticated_json()))];
+        incoming.extend(
+            frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| Some(Ok(frame.json(index)))),
+        );
+        incoming.push(None);
+        let (transport, _sent, _closed) = MockTransport::new(incoming);
+        let (_client, receivers) =
+            SignalFishClient::start_with_subscribers(transport, suite_config(config), subscribers);
+
+        let drains: Vec<_> = receivers
+            .into_iter()
+            .enumerate()
+            .map(|(subscriber, mut rx)| {
+                let log = Arc::clone(&log);
+                let slow = slow.get(subscriber).copied().unwrap_or(false);
+                tokio::spawn(async move {
+                    let mut received = Vec::new();
+                    while let Some(event) = rx.recv().await {
+                        // Holding event N means every subscriber was handed
+                        // every earlier event, and every lower-indexed
+                        // subscriber was handed N itself.
+                        let position = received.len() * subscribers + subscriber;
+                        let emitted = log.lock().unwrap().len();
+                        assert!(
+                            emitted > position,
+                            "subscriber {subscriber} got event {} with only {emitted} emissions recorded",
+                            received.len()
+                        );
+                        received.push(emission_label(&event));
+                        if slow {
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                    received
+                })
+            })
+            .collect();
+        let mut received = Vec::new();
+        for drain in drains {
+            received.push(drain.await.unwrap());
+        }
+
+        let log = log.lock().unwrap().clone();
+        assert_eq!(log.len() % subscribers, 0, "{log:?}");
+        let sequence: Vec<String> = log
+            .chunks(subscribers)
+            .map(|run| {
+                for (subscriber, (index, label)) in run.iter().enumerate() {
+                    assert_eq!(*index, subscriber, "index order within an event: {run:?}");
+                    assert_eq!(label, &run[0].1, "one event per run: {run:?}");
+                }
+                run[0].1.clone()
+            })
+            .collect();
+        assert_eq!(sequence.first().map(String::as_str), Some("Connected"));
+        assert_eq!(sequence.last().map(String::as_str), Some("Disconnected"));
+        assert!(
+            sequence.len() >= frames.len() + 3,
+            "every scripted frame surfaces an event: {sequence:?}"
+        );
+        for events in received {
+            assert_eq!(events, sequence);
+        }
+    });
+}
+
+#[test]
+fn emission_order_holds_for_a_fixed_mix() {
+    use MixFrame::{Announcement, Error, GameData, Malformed, Pong};
+
+    check_emission_order(
+        &[
+            GameData,
+            Error,
+            Pong,
+            Announcement,
+            Malformed,
+            GameData,
+            Error,
+        ],
+        3,
+        1,
+        &[false, true, false],
+    );
+}
+
+mod emission_order {
+    use proptest::prelude::*;
+
+    use super::{check_emission_order, MixFrame};
+
+    fn frames() -> impl Strategy<Value = Vec<MixFrame>> {
+        prop::collection::vec(
+            prop_oneof![
+                Just(MixFrame::Pong),
+                Just(MixFrame::GameData),
+                Just(MixFrame::Error),
+                Just(MixFrame::Announcement),
+                Just(MixFrame::Malformed),
+            ],
+            0..24,
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        #[test]
+        fn emission_order_holds_for_random_mixes(
+            frames in frames(),
+            subscribers in 1usize..=4,
+            capacity in 1usize..=3,
+            slow in prop::collection::vec(any::<bool>(), 4),
+        ) {
+            check_emission_order(&frames, subscribers, capacity, &slow);
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Loop watchdog
+// ════════════════════════════════════════════════════════════════════
+
+/// Passes everything through to `inner` until `blocked` is set, then never
+/// completes another send — a stand-in for an interceptor that deadlocks.
+struct BlockingSends<T> {
+    inner: T,
+    blocked: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl<T> BlockingSends<T> {
+    fn new(inner: T) -> (Self, std::sync::Arc<std::sync::atomic::AtomicBool>) {
+        let blocked = std::sync::Arc::default();
+        (
+            Self {
+                inner,
+                blocked: std::sync::Arc::clone(&blocked),
+            },
+            blocked,
+        )
+    }
+}
+
+impl<T: Transport> Transport for BlockingSends<T> {
+    fn poll_send(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if self.blocked.load(std::sync::atomic::Ordering::Relaxed) {
+            return std::task::Poll::Pending;
+        }
+        self.inner.poll_send(cx, frame)
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.inner.poll_recv(cx)
+    }
+
+    fn poll_close(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.inner.poll_close(cx)
+    }
+}
+
+fn watched_config() -> SignalFishConfig {
+    SignalFishConfig::new("mb_test_integration")
+        .with_loop_watchdog(std::time::Duration::from_secs(1))
+}
+
+/// Block sends, queue a ping into the block, and return when it happened.
+fn stall(
+    client: &mut SignalFishClient,
+    blocked: &std::sync::atomic::AtomicBool,
+) -> tokio::time::Instant {
+    blocked.store(true, std::sync::atomic::Ordering::Relaxed);
+    client.ping().expect("ping queues");
+    tokio::time::Instant::now()
+}
+
+async fn expect_stalled(
+    events: &mut tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    stalled_at: tokio::time::Instant,
+) {
+    let ev = events.recv().await.expect("expected ClientStalled");
+    let SignalFishEvent::ClientStalled { since } = ev else {
+        panic!("expected ClientStalled, got {ev:?}");
+    };
+    let since = tokio::time::Instant::from_std(since);
+    let threshold = std::time::Duration::from_secs(1);
+    assert!(since <= stalled_at, "the loop last ticked before the stall");
+    assert!(
+        stalled_at - since <= threshold / 2,
+        "an idle loop ticks every half threshold"
+    );
+    let detected = tokio::time::Instant::now() - since;
+    assert!(
+        detected >= threshold && detected <= threshold + threshold / 4,
+        "detected after {detected:?}"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn loop_watchdog_reports_a_loop_stuck_in_a_send_once() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (transport, blocked) = BlockingSends::new(transport);
+    let (mut client, mut events) =
+        SignalFishClient::start(transport, suite_config(watched_config()));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    // An idle loop is not a stalled one.
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    assert!(events.try_recv().is_err());
+
+    let stalled_at = stall(&mut client, &blocked);
+    expect_stalled(&mut events, stalled_at).await;
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .is_err(),
+        "one report per stall"
+    );
+
+    // The stuck loop misses the shutdown signal and is aborted.
+    client.shutdown().await;
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Disconnected { .. })
+    ));
+    assert!(events.recv().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn loop_watchdog_does_not_count_a_full_receiver_as_a_stall() {
+    let mut incoming = vec![Some(Ok(authenticated_json()))];
+    incoming.extend((0..4).map(|_| Some(Ok(pong_json()))));
+    let (transport, _sent, _closed) = MockTransport::new(incoming);
+    let config = watched_config().with_event_channel_capacity(1);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+
+    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    drain_until_authenticated(&mut events).await;
+    for _ in 0..4 {
+        let ev = events.recv().await.expect("expected Pong");
+        assert!(matches!(ev, SignalFishEvent::Pong), "got {ev:?}");
+    }
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn loop_watchdog_restart_ends_a_stalled_connection() {
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (transport, blocked) = BlockingSends::new(transport);
+    let config = watched_config().with_restart_stalled_loop(true);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    let stalled_at = stall(&mut client, &blocked);
+    expect_stalled(&mut events, stalled_at).await;
+    let ev = events.recv().await.expect("expected Disconnected");
+    let SignalFishEvent::Disconnected { reason, .. } = ev else {
+        panic!("expected Disconnected, got {ev:?}");
+    };
+    assert_eq!(reason, DisconnectReason::LoopStalled);
+    assert!(events.recv().await.is_none());
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn loop_watchdog_restart_redials_a_reconnecting_client() {
+    type WatchedDial = Result<BlockingSends<FeedTransport>, SignalFishError>;
+    let (dial_tx, dial_rx) = tokio::sync::mpsc::unbounded_channel::<WatchedDial>();
+    let dials = std::sync::Arc::new(tokio::sync::Mutex::new(dial_rx));
+    let factory = move || {
+        let dials = std::sync::Arc::clone(&dials);
+        async move {
+            dials
+                .lock()
+                .await
+                .recv()
+                .await
+                .unwrap_or(Err(SignalFishError::TransportClosed))
+        }
+    };
+    let config = watched_config().with_restart_stalled_loop(true);
+    let (mut client, mut events) =
+        SignalFishClient::start_with_reconnect(factory, suite_config(config));
+
+    let (transport, feed, _sent) = FeedTransport::new();
+    let (transport, blocked) = BlockingSends::new(transport);
+    dial_tx.send(Ok(transport)).unwrap();
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+
+    let stalled_at = stall(&mut client, &blocked);
+    expect_stalled(&mut events, stalled_at).await;
+    assert_eq!(next_reconnecting(&mut events).await, 1);
+
+    let (transport, feed, sent) = FeedTransport::new();
+    let (transport, _blocked) = BlockingSends::new(transport);
+    dial_tx.send(Ok(transport)).unwrap();
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+    client.shutdown().await;
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Message deadlines
+// ════════════════════════════════════════════════════════════════════
+
+/// Holds every send while `gate` is shut, waking the loop when it opens — a
+/// transport stalled behind a congested link.
+struct GatedSends<T> {
+    inner: T,
+    gate: std::sync::Arc<SendGate>,
+}
+
+#[derive(Default)]
+struct SendGate {
+    shut: std::sync::atomic::AtomicBool,
+    waker: std::sync::Mutex<Option<std::task::Waker>>,
+}
+
+impl SendGate {
+    fn shut(&self) {
+        self.shut.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn open(&self) {
+        self.shut.store(false, std::sync::atomic::Ordering::SeqCst);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Transport> Transport for GatedSends<T> {
+    fn poll_send(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        if self.gate.shut.load(std::sync::atomic::Ordering::SeqCst) {
+            *self.gate.waker.lock().unwrap() = Some(cx.waker().clone());
+            return std::task::Poll::Pending;
+        }
+        self.inner.poll_send(cx, frame)
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.inner.poll_recv(cx)
+    }
+
+    fn poll_close(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.inner.poll_close(cx)
+    }
+}
+
+/// An authenticated client whose sends stall behind the returned gate.
+async fn start_gated(
+    config: SignalFishConfig,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    Feed,
+    SentLog,
+    std::sync::Arc<SendGate>,
+) {
+    let (inner, feed, sent) = FeedTransport::new();
+    let gate = std::sync::Arc::<SendGate>::default();
+    let transport = GatedSends {
+        inner,
+        gate: std::sync::Arc::clone(&gate),
+    };
+    let (client, mut events) = SignalFishClient::start(transport, suite_config(config));
+    feed.send(Ok(authenticated_json())).unwrap();
+    drain_until_authenticated(&mut events).await;
+    (client, events, feed, sent, gate)
+}
+
+fn game_data_values(sent: &SentLog) -> Vec<serde_json::Value> {
+    sent.matching(of_type("GameData"))
+        .iter()
+        .map(|frame| {
+            let frame: serde_json::Value = serde_json::from_str(frame).unwrap();
+            frame["data"]["data"].clone()
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn queued_messages_past_their_deadline_expire_at_dequeue() {
+    let config = SignalFishConfig::new("mb_test_integration").with_message_deadline(
+        ClientMessageKind::GameData,
+        std::time::Duration::from_millis(100),
+    );
+    let (mut client, mut events, _feed, sent, gate) = start_gated(config).await;
+
+    gate.shut();
+    client.send_game_data(serde_json::json!(0)).unwrap();
+    // The loop takes the first message and stalls sending it.
+    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    client.send_game_data(serde_json::json!(1)).unwrap();
+    client
+        .send_game_data_with_deadline(serde_json::json!(2), std::time::Duration::from_secs(10))
+        .unwrap();
+    client
+        .send_game_data_with_deadline(serde_json::json!(3), std::time::Duration::from_millis(50))
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    gate.open();
+
+    for _ in 0..2 {
+        let ev = events.recv().await.expect("expected MessageExpired");
+        assert!(
+            matches!(
+                ev,
+                SignalFishEvent::MessageExpired {
+                    kind: ClientMessageKind::GameData
+                }
+            ),
+            "got {ev:?}"
+        );
+    }
+    client.ping().unwrap();
+    wait_for_sent_len(&sent, 4).await;
+    assert_eq!(
+        game_data_values(&sent),
+        [serde_json::json!(0), serde_json::json!(2)],
+        "a dequeued message is sent late; an expired one never"
+    );
+    let stats = client.stats();
+    assert_eq!(stats.messages_expired, 2);
+    assert_eq!(stats.game_data_sent, 2);
+    client.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn authenticate_ping_and_leave_never_expire() {
+    let deadline = std::time::Duration::from_millis(1);
+    let config = SignalFishConfig::new("mb_test_integration")
+        .with_message_deadline(ClientMessageKind::Authenticate, deadline)
+        .with_message_deadline(ClientMessageKind::Ping, deadline)
+        .with_message_deadline(ClientMessageKind::LeaveRoom, deadline)
+        .with_message_deadline(ClientMessageKind::PlayerReady, deadline);
+    let (mut client, mut events, feed, sent, gate) = start_gated(config).await;
+    client
+        .join_room(JoinRoomParams::new("game", "player"))
+        .unwrap();
+    feed.send(Ok(room_joined_json())).unwrap();
+    loop {
+        match events.recv().await.expect("expected RoomJoined") {
+            SignalFishEvent::RoomJoined { .. } => break,
+            _ => continue,
+        }
+    }
+
+    gate.shut();
+    client.ping().unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    client.set_ready().unwrap();
+    client.ping().unwrap();
+    client.leave_room().unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    gate.open();
+
+    let ev = events.recv().await.expect("expected MessageExpired");
+    assert!(
+        matches!(
+            ev,
+            SignalFishEvent::MessageExpired {
+                kind: ClientMessageKind::PlayerReady
+            }
+        ),
+        "got {ev:?}"
+    );
+    wait_for_sent_len(&sent, 5).await;
+    assert_eq!(sent.matching(of_type("Authenticate")).len(), 1);
+    assert_eq!(sent.matching(of_type("Ping")).len(), 2);
+    assert_eq!(sent.matching(of_type("LeaveRoom")).len(), 1);
+    assert!(sent.matching(of_type("PlayerReady")).is_empty());
+    assert_eq!(client.stats().messages_expired, 1);
     client.shutdown().await;
 }
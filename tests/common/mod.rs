@@ -9,31 +9,36 @@
 //! Shared test utilities for Signal Fish Client integration tests.
 //!
 //! Provides a channel-based [`MockTransport`] and helper functions for
-//! constructing common server response JSON strings.
+//! constructing common server response JSON strings, built on the crate's
+//! `testing` fixture builders where one exists.
 
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex as StdMutex};
+use std::sync::Arc;
 
 use signal_fish_client::protocol::SpectatorJoinedPayload;
 use signal_fish_client::protocol::{
-    LobbyState, PlayerId, PlayerInfo, ProtocolInfoPayload, RateLimitInfo, ReconnectedPayload,
-    RoomJoinedPayload, ServerMessage, SessionPeer, SessionPlanPayload, Topology, TransportKind,
+    LobbyState, PlayerId, ProtocolInfoPayload, RateLimitInfo, ReconnectedPayload, ServerMessage,
+    SessionPeer, SessionPlanPayload, Topology, TransportKind,
 };
+use signal_fish_client::testing::{self, SentLog};
 use signal_fish_client::transport::TransportFrame;
-use signal_fish_client::{SignalFishError, Transport};
+use signal_fish_client::{SignalFishConfig, SignalFishError, Transport};
 
 // ── MockTransport ───────────────────────────────────────────────────
 
 /// A channel-based mock transport for integration testing.
 ///
 /// Scripted server responses are consumed in order by `recv()`.
-/// All messages sent by the client are recorded in `sent`.
+/// Messages sent by the client are recorded in `sent`, a bounded [`SentLog`];
+/// binary frames are recorded in `sent_binary`.
 pub struct MockTransport {
     /// Scripted server responses (consumed in order by `recv`).
     incoming: VecDeque<Option<Result<TransportFrame, SignalFishError>>>,
     /// Recorded outgoing messages from the client.
-    pub sent: Arc<StdMutex<Vec<String>>>,
+    pub sent: SentLog,
+    /// Recorded outgoing binary frames from the client.
+    pub sent_binary: Arc<std::sync::Mutex<Vec<Vec<u8>>>>,
     /// Whether `close()` has been called.
     pub closed: Arc<AtomicBool>,
 }
@@ -45,15 +50,16 @@ impl MockTransport {
     /// and whether close was called.
     pub fn new(
         incoming: Vec<Option<Result<String, SignalFishError>>>,
-    ) -> (Self, Arc<StdMutex<Vec<String>>>, Arc<AtomicBool>) {
-        let sent = Arc::new(StdMutex::new(Vec::new()));
+    ) -> (Self, SentLog, Arc<AtomicBool>) {
+        let sent = SentLog::new();
         let closed = Arc::new(AtomicBool::new(false));
         let transport = Self {
             incoming: incoming
                 .into_iter()
                 .map(|item| item.map(|result| result.map(TransportFrame::Text)))
                 .collect(),
-            sent: Arc::clone(&sent),
+            sent: sent.clone(),
+            sent_binary: Arc::default(),
             closed: Arc::clone(&closed),
         };
         (transport, sent, closed)
@@ -62,13 +68,14 @@ impl MockTransport {
     /// Create a mock transport from physical text/binary frames.
     pub fn new_frames(
         incoming: Vec<Option<Result<TransportFrame, SignalFishError>>>,
-    ) -> (Self, Arc<StdMutex<Vec<String>>>, Arc<AtomicBool>) {
-        let sent = Arc::new(StdMutex::new(Vec::new()));
+    ) -> (Self, SentLog, Arc<AtomicBool>) {
+        let sent = SentLog::new();
         let closed = Arc::new(AtomicBool::new(false));
         (
             Self {
                 incoming: VecDeque::from(incoming),
-                sent: Arc::clone(&sent),
+                sent: sent.clone(),
+                sent_binary: Arc::default(),
                 closed: Arc::clone(&closed),
             },
             sent,
@@ -83,11 +90,14 @@ impl Transport for MockTransport {
         _cx: &mut std::task::Context<'_>,
         frame: &mut Option<TransportFrame>,
     ) -> std::task::Poll<Result<(), SignalFishError>> {
-        if let Some(frame) = frame.take() {
-            let TransportFrame::Text(message) = frame else {
-                panic!("test mock expected an outbound text frame");
-            };
-            self.sent.lock().unwrap().push(message);
+        match frame.take() {
+            Some(TransportFrame::Text(message)) => self.sent.record(message),
+            Some(TransportFrame::Binary(payload)) => self
+                .sent_binary
+                .lock()
+                .expect("sent_binary mutex poisoned")
+                .push(payload),
+            None => {}
         }
         std::task::Poll::Ready(Ok(()))
     }
@@ -135,24 +145,10 @@ pub fn room_joined_json() -> String {
 
 /// Returns the JSON string for a `RoomJoined` server message with custom values.
 pub fn room_joined_json_with(room_code: &str, game_name: &str, player_id: uuid::Uuid) -> String {
-    let payload = RoomJoinedPayload {
-        room_id: uuid::Uuid::nil(),
-        room_code: room_code.into(),
-        player_id,
-        game_name: game_name.into(),
-        max_players: 4,
-        supports_authority: true,
-        current_players: vec![],
-        is_authority: false,
-        lobby_state: LobbyState::Waiting,
-        ready_players: vec![],
-        relay_type: "auto".into(),
-        current_spectators: vec![],
-        ice_servers: vec![],
-        reconnection_token: None,
-    };
-    serde_json::to_string(&ServerMessage::RoomJoined(Box::new(payload)))
-        .expect("room_joined_json serialization")
+    testing::room_joined(room_code)
+        .game_name(game_name)
+        .player_id(player_id)
+        .json()
 }
 
 /// Returns the JSON string for a `RoomLeft` server message.
@@ -217,19 +213,19 @@ pub fn pong_json() -> String {
     serde_json::to_string(&ServerMessage::Pong).expect("pong_json serialization")
 }
 
+/// Returns the JSON string for a `TimeSyncResponse` server message.
+pub fn time_sync_response_json(client_sent_at_ms: u64, server_time_ms: u64) -> String {
+    serde_json::to_string(&ServerMessage::TimeSyncResponse {
+        client_sent_at_ms,
+        server_time_ms,
+    })
+    .expect("time_sync_response_json serialization")
+}
+
 /// Returns the JSON string for a `PlayerJoined` server message.
 pub fn player_joined_json(name: &str, player_id: uuid::Uuid) -> String {
     serde_json::to_string(&ServerMessage::PlayerJoined {
-        player: PlayerInfo {
-            id: player_id,
-            name: name.into(),
-            is_authority: false,
-            is_ready: false,
-            connected_at: "2026-01-01T00:00:00Z".into(),
-            connection_info: None,
-            epoch: None,
-            seq: None,
-        },
+        player: testing::player(name).id(player_id).build(),
     })
     .expect("player_joined_json serialization")
 }
@@ -253,6 +249,16 @@ pub fn error_json(message: &str, error_code: Option<signal_fish_client::ErrorCod
     .expect("error_json serialization")
 }
 
+/// Returns the JSON string for an `Announcement` server message.
+pub fn announcement_json(message: &str, severity: Option<&str>) -> String {
+    serde_json::to_string(&ServerMessage::Announcement {
+        message: message.into(),
+        severity: severity.map(Into::into),
+        metadata: None,
+    })
+    .expect("announcement_json serialization")
+}
+
 /// Returns the JSON string for an `AuthorityResponse` server message.
 pub fn authority_response_json(granted: bool, reason: Option<&str>) -> String {
     serde_json::to_string(&ServerMessage::AuthorityResponse {
@@ -294,6 +300,7 @@ pub fn protocol_info_payload(protocol_version: Option<u16>) -> ProtocolInfoPaylo
         min_protocol_version: protocol_version.map(|_| 2),
         max_protocol_version: protocol_version.map(|_| 3),
         transports: None,
+        limits: None,
     }
 }
 
@@ -324,9 +331,9 @@ pub fn reconnected_with_protocol_info_json(protocol_version: Option<u16>) -> Str
         relay_type: "tcp".into(),
         current_spectators: vec![],
         ice_servers: vec![],
-        missed_events: vec![ServerMessage::ProtocolInfo(protocol_info_payload(
-            protocol_version,
-        ))],
+        missed_events: vec![
+            ServerMessage::ProtocolInfo(protocol_info_payload(protocol_version)).into(),
+        ],
         replay: None,
         sender_watermarks: vec![],
         reconnection_token: None,
@@ -335,12 +342,12 @@ pub fn reconnected_with_protocol_info_json(protocol_version: Option<u16>) -> Str
         .expect("reconnected_with_protocol_info_json serialization")
 }
 
-/// Wait until the mock transport records at least `expected_len` outgoing
-/// messages. This avoids fixed sleeps when testing queued async sends.
-pub async fn wait_for_sent_len(sent: &Arc<StdMutex<Vec<String>>>, expected_len: usize) {
+/// Wait until the mock transport has recorded at least `expected_len` outgoing
+/// messages in total. This avoids fixed sleeps when testing queued async sends.
+pub async fn wait_for_sent_len(sent: &SentLog, expected_len: u64) {
     tokio::time::timeout(std::time::Duration::from_secs(1), async {
         loop {
-            if sent.lock().unwrap().len() >= expected_len {
+            if sent.total() >= expected_len {
                 break;
             }
             tokio::task::yield_now().await;
@@ -350,7 +357,7 @@ pub async fn wait_for_sent_len(sent: &Arc<StdMutex<Vec<String>>>, expected_len:
     .unwrap_or_else(|_| {
         panic!(
             "timed out waiting for {expected_len} sent message(s); got {}",
-            sent.lock().unwrap().len()
+            sent.total()
         )
     });
 }
@@ -398,3 +405,22 @@ pub fn peer_transport_status_json(peer_id: PlayerId, connected: bool) -> String
     })
     .expect("peer_transport_status_json serialization")
 }
+
+// ── Suite modes ─────────────────────────────────────────────────────
+
+/// Set to a queue depth to re-run the async-client suites with off-loop
+/// decoding: `SIGNAL_FISH_TEST_DECODE_PIPELINE=4 cargo test`.
+pub const DECODE_PIPELINE_ENV: &str = "SIGNAL_FISH_TEST_DECODE_PIPELINE";
+
+/// `config` with the suite mode selected by the environment applied. A test
+/// that picks its own pipeline depth keeps it.
+pub fn suite_config(config: SignalFishConfig) -> SignalFishConfig {
+    match std::env::var(DECODE_PIPELINE_ENV) {
+        Ok(depth) if config.decode_pipeline.is_none() => config.with_decode_pipeline(
+            depth
+                .parse()
+                .unwrap_or_else(|_| panic!("{DECODE_PIPELINE_ENV} must be a queue depth")),
+        ),
+        _ => config,
+    }
+}
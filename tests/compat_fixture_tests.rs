@@ -0,0 +1,278 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::todo,
+    clippy::unimplemented,
+    clippy::indexing_slicing
+)]
+//! Backward-compatibility tests against frozen protocol fixture snapshots.
+//!
+//! `tests/compat-fixtures/v2.0/` holds complete v2 messages as the 0.9 client
+//! parsed them, next to the structure it parsed them into (re-serialized, one
+//! line per fixture). The current types must still parse every fixture into
+//! the recorded structure: a recorded field may not change or disappear (a
+//! recorded `null` and an absent key both mean `None`), while fields added
+//! since the snapshot are allowed. The snapshot is frozen — never edit it to
+//! make a test pass; add a new versioned directory instead. `v2.1/` records
+//! the messages added since (`Announcement`), and `v2.2/` `ProtocolInfo` with
+//! and without the optional `limits` block.
+//!
+//! The suite also enforces the pattern that keeps this true: every `Option`
+//! field in the protocol types carries `#[serde(default)]`, so a server that
+//! omits a newer optional field still parses.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use signal_fish_client::protocol::{ClientMessage, ServerMessage};
+
+const V2_0_SERVER: &str = include_str!("compat-fixtures/v2.0/server-messages.jsonl");
+const V2_0_SERVER_EXPECTED: &str = include_str!("compat-fixtures/v2.0/server-expected.jsonl");
+const V2_0_CLIENT: &str = include_str!("compat-fixtures/v2.0/client-messages.jsonl");
+const V2_0_CLIENT_EXPECTED: &str = include_str!("compat-fixtures/v2.0/client-expected.jsonl");
+const V2_1_SERVER: &str = include_str!("compat-fixtures/v2.1/server-messages.jsonl");
+const V2_1_SERVER_EXPECTED: &str = include_str!("compat-fixtures/v2.1/server-expected.jsonl");
+const V2_2_SERVER: &str = include_str!("compat-fixtures/v2.2/server-messages.jsonl");
+const V2_2_SERVER_EXPECTED: &str = include_str!("compat-fixtures/v2.2/server-expected.jsonl");
+
+const PROTOCOL_SOURCES: &[(&str, &str)] = &[
+    ("src/protocol.rs", include_str!("../src/protocol.rs")),
+    (
+        "src/protocol/binary.rs",
+        include_str!("../src/protocol/binary.rs"),
+    ),
+];
+
+fn lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// The first place `got` no longer holds what `recorded` captured, if any.
+fn first_difference(recorded: &Value, got: &Value, path: &str) -> Option<String> {
+    match (recorded, got) {
+        (Value::Object(recorded), Value::Object(got)) => recorded.iter().find_map(|(key, want)| {
+            let path = format!("{path}.{key}");
+            match got.get(key) {
+                Some(have) => first_difference(want, have, &path),
+                None if want.is_null() => None,
+                None => Some(format!("{path}: recorded {want}, now missing")),
+            }
+        }),
+        (Value::Array(recorded), Value::Array(got)) if recorded.len() == got.len() => recorded
+            .iter()
+            .zip(got)
+            .enumerate()
+            .find_map(|(index, (want, have))| {
+                first_difference(want, have, &format!("{path}[{index}]"))
+            }),
+        _ if recorded == got => None,
+        _ => Some(format!("{path}: recorded {recorded}, now {got}")),
+    }
+}
+
+/// Parse every fixture with today's types and compare against the recorded structure.
+fn assert_parses_as_recorded<T: Serialize + DeserializeOwned>(
+    name: &str,
+    fixtures: &str,
+    expected: &str,
+) {
+    let fixtures: Vec<_> = lines(fixtures).collect();
+    let expected: Vec<_> = lines(expected).collect();
+    assert_eq!(
+        fixtures.len(),
+        expected.len(),
+        "{name}: every fixture needs exactly one recorded expectation"
+    );
+    assert!(!fixtures.is_empty(), "{name}: empty fixture set");
+
+    for (index, (fixture, recorded)) in fixtures.iter().zip(&expected).enumerate() {
+        let line = index + 1;
+        let parsed: T = serde_json::from_str(fixture).unwrap_or_else(|error| {
+            panic!("{name}:{line}: frozen fixture no longer parses: {error}\n  {fixture}")
+        });
+        let got = serde_json::to_value(&parsed).expect("serialize parsed fixture");
+        let recorded: Value = serde_json::from_str(recorded).expect("recorded expectation");
+        if let Some(difference) = first_difference(&recorded, &got, "$") {
+            panic!("{name}:{line}: parses differently than recorded: {difference}\n  {fixture}");
+        }
+    }
+}
+
+#[test]
+fn v2_0_server_fixtures_parse_as_recorded() {
+    assert_parses_as_recorded::<ServerMessage>(
+        "v2.0/server-messages",
+        V2_0_SERVER,
+        V2_0_SERVER_EXPECTED,
+    );
+}
+
+#[test]
+fn v2_0_client_fixtures_parse_as_recorded() {
+    assert_parses_as_recorded::<ClientMessage>(
+        "v2.0/client-messages",
+        V2_0_CLIENT,
+        V2_0_CLIENT_EXPECTED,
+    );
+}
+
+#[test]
+fn v2_1_server_fixtures_parse_as_recorded() {
+    assert_parses_as_recorded::<ServerMessage>(
+        "v2.1/server-messages",
+        V2_1_SERVER,
+        V2_1_SERVER_EXPECTED,
+    );
+}
+
+#[test]
+fn v2_2_server_fixtures_parse_as_recorded() {
+    assert_parses_as_recorded::<ServerMessage>(
+        "v2.2/server-messages",
+        V2_2_SERVER,
+        V2_2_SERVER_EXPECTED,
+    );
+}
+
+#[test]
+fn server_fixtures_tolerate_unknown_additive_fields() {
+    // A newer server adds fields the snapshot has never seen; the recorded
+    // structure must survive them.
+    for (name, fixtures, expected) in [
+        ("v2.0", V2_0_SERVER, V2_0_SERVER_EXPECTED),
+        ("v2.1", V2_1_SERVER, V2_1_SERVER_EXPECTED),
+        ("v2.2", V2_2_SERVER, V2_2_SERVER_EXPECTED),
+    ] {
+        let expected: Vec<_> = lines(expected).collect();
+        for (index, fixture) in lines(fixtures).enumerate() {
+            let line = index + 1;
+            let mut value: Value = serde_json::from_str(fixture).expect("fixture JSON");
+            if let Some(Value::Object(data)) = value.get_mut("data") {
+                data.insert("future_field".into(), serde_json::json!({ "v": 4 }));
+            }
+            let parsed: ServerMessage = serde_json::from_value(value).unwrap_or_else(|error| {
+                panic!("{name}:{line}: additive field broke parsing: {error}")
+            });
+            let recorded: Value = serde_json::from_str(expected[index]).expect("recorded");
+            let got = serde_json::to_value(&parsed).expect("serialize");
+            assert_eq!(
+                first_difference(&recorded, &got, "$"),
+                None,
+                "{name}:{line}"
+            );
+        }
+    }
+}
+
+#[test]
+fn unknown_message_types_fail_as_data_errors() {
+    // The transport loop turns a `Data` error into a `DecodeFailed` event and
+    // keeps running; a new variant must never look like a syntax failure or panic.
+    for json in [
+        r#"{"type":"SomeFutureV4Message","data":{"x":1}}"#,
+        r#"{"type":"SomeFutureV4Message"}"#,
+    ] {
+        let error = serde_json::from_str::<ServerMessage>(json).unwrap_err();
+        assert_eq!(
+            error.classify(),
+            serde_json::error::Category::Data,
+            "{json}"
+        );
+    }
+}
+
+/// Indices of the attribute and doc-comment lines directly above `field`.
+fn attribute_block(source: &[&str], field: usize) -> Vec<usize> {
+    let mut block = Vec::new();
+    let mut index = field;
+    while index > 0 {
+        index -= 1;
+        let line = source[index].trim();
+        if line == ")]" {
+            // Multi-line attribute: walk back to its opening `#[`.
+            while !source[index].trim().starts_with("#[") {
+                block.push(index);
+                index -= 1;
+            }
+            block.push(index);
+        } else if line.starts_with("#[") || line.starts_with("///") {
+            block.push(index);
+        } else {
+            break;
+        }
+    }
+    block
+}
+
+/// Whether `line` declares a named field of type `Option<…>`.
+fn is_option_field(line: &str) -> bool {
+    let line = line.trim_start();
+    let line = line
+        .strip_prefix("pub(crate) ")
+        .or_else(|| line.strip_prefix("pub "))
+        .unwrap_or(line);
+    line.split_once(": Option<").is_some_and(|(name, _)| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    })
+}
+
+#[test]
+fn every_optional_protocol_field_has_serde_default() {
+    let mut missing = Vec::new();
+    for (path, source) in PROTOCOL_SOURCES {
+        let source: Vec<_> = source
+            .lines()
+            .take_while(|line| !line.starts_with("#[cfg(test)]"))
+            .collect();
+        for (index, line) in source.iter().enumerate() {
+            if !is_option_field(line) {
+                continue;
+            }
+            let has_default = attribute_block(&source, index)
+                .into_iter()
+                .map(|i| source[i].trim())
+                .filter(|attr| !attr.starts_with("///"))
+                .any(|attr| attr.contains("default"));
+            if !has_default {
+                missing.push(format!("{path}:{}: {}", index + 1, line.trim()));
+            }
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "Option fields in protocol types must carry #[serde(default)] so older \
+         servers that omit them still parse:\n  {}",
+        missing.join("\n  ")
+    );
+}
+
+#[test]
+fn option_field_scanner_detects_a_missing_default() {
+    let source = [
+        "pub struct Example {",
+        "    /// Present.",
+        "    #[serde(",
+        "        default,",
+        "        skip_serializing_if = \"Option::is_none\"",
+        "    )]",
+        "    pub covered: Option<u8>,",
+        "    #[serde(skip_serializing_if = \"Option::is_none\")]",
+        "    pub uncovered: Option<u8>,",
+        "}",
+    ];
+    assert!(is_option_field(source[6]) && is_option_field(source[8]));
+    assert!(!is_option_field(source[0]));
+    let has_default = |field: usize| {
+        attribute_block(&source, field)
+            .into_iter()
+            .any(|i| source[i].contains("default"))
+    };
+    assert!(has_default(6));
+    assert!(!has_default(8));
+}
@@ -0,0 +1,263 @@
+//! The read-only debug endpoint, driven over raw TCP.
+//!
+//! A scripted session authenticates and joins a room; each test then talks
+//! to [`SignalFishClient::serve_debug`] with newline-delimited JSON and
+//! checks the answers against that session.
+#![cfg(feature = "debug-server")]
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+
+#[allow(dead_code)]
+mod common;
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+
+use signal_fish_client::{
+    testing, DebugServer, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent,
+};
+
+use common::{authenticated_json, suite_config, MockTransport};
+
+/// A client that has authenticated and joined room `DBG123` with Ada and
+/// Grace, serving its debug endpoint on a loopback port.
+async fn start_serving() -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+    DebugServer,
+) {
+    let joined = testing::room_joined("DBG123")
+        .game_name("chess")
+        .player_id(testing::player_id("Ada"))
+        .players([testing::player("Ada"), testing::player("Grace")])
+        .json();
+    let (transport, _sent, _closed) =
+        MockTransport::new(vec![Some(Ok(authenticated_json())), Some(Ok(joined))]);
+    let (client, mut events) = SignalFishClient::start(
+        transport,
+        suite_config(SignalFishConfig::new("mb_debug").with_event_channel_capacity(64)),
+    );
+    loop {
+        match events.recv().await.expect("the client to join the room") {
+            SignalFishEvent::RoomJoined { .. } => break,
+            _ => continue,
+        }
+    }
+    let server = client.serve_debug("127.0.0.1:0").await.unwrap();
+    (client, events, server)
+}
+
+struct Connection {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    write: OwnedWriteHalf,
+}
+
+impl Connection {
+    async fn open(server: &DebugServer) -> Self {
+        let (read, write) = TcpStream::connect(server.local_addr())
+            .await
+            .unwrap()
+            .into_split();
+        Self {
+            lines: BufReader::new(read).lines(),
+            write,
+        }
+    }
+
+    async fn send(&mut self, line: &str) -> Option<Value> {
+        self.write
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .unwrap();
+        self.next().await
+    }
+
+    async fn next(&mut self) -> Option<Value> {
+        let line = tokio::time::timeout(Duration::from_secs(5), self.lines.next_line())
+            .await
+            .expect("an answer within five seconds")
+            .unwrap()?;
+        Some(serde_json::from_str(&line).unwrap())
+    }
+
+    /// The data of `route`, asked with the server's token.
+    async fn get(&mut self, server: &DebugServer, route: &str) -> Value {
+        let request = json!({ "token": server.token(), "route": route }).to_string();
+        let answer = self.send(&request).await.expect("an answer");
+        assert_eq!(answer["route"], route, "{answer}");
+        answer["data"].clone()
+    }
+}
+
+#[tokio::test]
+async fn routes_reflect_the_scripted_session() {
+    let (mut client, _events, server) = start_serving().await;
+    let mut connection = Connection::open(&server).await;
+
+    let snapshot = connection.get(&server, "snapshot").await;
+    assert_eq!(snapshot["connected"], true);
+    assert_eq!(snapshot["authenticated"], true);
+    assert_eq!(snapshot["room_code"], "DBG123");
+    assert_eq!(
+        snapshot["player_id"],
+        json!(testing::player_id("Ada").to_string())
+    );
+    assert_eq!(snapshot["session_state"], "InRoom");
+    assert_eq!(snapshot["session_epoch"], 0);
+
+    let roster = connection.get(&server, "roster").await;
+    assert_eq!(roster["in_room"], true);
+    assert_eq!(roster["game_name"], "chess");
+    let names: Vec<&str> = roster["players"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|player| player["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Ada", "Grace"]);
+
+    let stats = connection.get(&server, "stats").await;
+    assert_eq!(stats["game_data_sent"], 0);
+    assert_eq!(stats["messages_undecodable"], 0);
+    assert_eq!(stats["sent"]["Authenticate"], 1);
+
+    let history = connection.get(&server, "history").await;
+    let transitions = history["transitions"].as_array().unwrap();
+    assert_eq!(
+        transitions.last().unwrap()["to"],
+        "InRoom",
+        "{transitions:?}"
+    );
+    assert!(history["rooms"].is_array());
+
+    let config = connection.get(&server, "config").await;
+    assert_eq!(config["event_channel_capacity"]["value"], 64);
+    assert_eq!(config["event_channel_capacity"]["source"], "Config");
+
+    // Nothing in the answers gives away the reconnection token.
+    assert!(snapshot.get("reconnection_token").is_none());
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn traffic_follows_what_the_session_sent_and_received() {
+    let (mut client, _events, server) = start_serving().await;
+    let mut connection = Connection::open(&server).await;
+
+    let traffic = connection.get(&server, "traffic").await;
+    assert!(traffic["since_last_send_ms"].is_f64(), "{traffic}");
+    assert!(traffic["since_last_recv_ms"].is_f64(), "{traffic}");
+    assert_eq!(traffic["latency"], Value::Null, "no Ping was answered");
+    assert_eq!(traffic["game_data_sent"], 0);
+    assert_eq!(traffic["game_data_received"], 0);
+
+    client.send_game_data(json!({ "move": "e4" })).unwrap();
+    let sent = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let traffic = connection.get(&server, "traffic").await;
+            if traffic["game_data_sent"] == 1 {
+                break traffic;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the game data to be sent");
+    assert_eq!(sent["game_data_received"], 0);
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn requests_need_the_token_and_a_known_route() {
+    let (mut client, _events, server) = start_serving().await;
+    let mut connection = Connection::open(&server).await;
+
+    let answer = connection
+        .send(&json!({ "token": "guess", "route": "snapshot" }).to_string())
+        .await
+        .unwrap();
+    assert_eq!(answer, json!({ "error": "unauthorized" }));
+
+    let answer = connection.send("snapshot please").await.unwrap();
+    assert!(
+        answer["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("malformed request"),
+        "{answer}"
+    );
+
+    let answer = connection
+        .send(&json!({ "token": server.token(), "route": "shutdown" }).to_string())
+        .await
+        .unwrap();
+    assert_eq!(answer["error"], "unknown route \"shutdown\"");
+    assert_eq!(
+        answer["routes"],
+        json!(["snapshot", "roster", "stats", "history", "traffic", "config"])
+    );
+
+    // The connection survives refused requests.
+    assert_eq!(
+        connection.get(&server, "snapshot").await["authenticated"],
+        true
+    );
+
+    let answer = connection.send(&"x".repeat(5000)).await.unwrap();
+    assert_eq!(answer["error"], "request line too long");
+    assert!(connection.next().await.is_none(), "then it closes");
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn each_server_has_its_own_random_token() {
+    let (mut client, _events, first) = start_serving().await;
+    let second = client.serve_debug("127.0.0.1:0").await.unwrap();
+    assert_eq!(first.token().len(), 32);
+    assert_ne!(first.token(), second.token());
+
+    let mut connection = Connection::open(&second).await;
+    let answer = connection
+        .send(&json!({ "token": first.token(), "route": "stats" }).to_string())
+        .await
+        .unwrap();
+    assert_eq!(answer, json!({ "error": "unauthorized" }));
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn the_endpoint_closes_with_the_client() {
+    let (mut client, _events, server) = start_serving().await;
+    let mut connection = Connection::open(&server).await;
+    assert_eq!(connection.get(&server, "roster").await["in_room"], true);
+
+    client.shutdown().await;
+    assert!(
+        connection.next().await.is_none(),
+        "open connections are closed"
+    );
+    let refused = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match TcpStream::connect(server.local_addr()).await {
+                Err(_) => break,
+                // The listener may still be closing.
+                Ok(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await;
+    assert!(refused.is_ok(), "the listener is closed");
+
+    assert!(matches!(
+        client.serve_debug("127.0.0.1:0").await,
+        Err(SignalFishError::NotConnected)
+    ));
+}
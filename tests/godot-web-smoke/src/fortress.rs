@@ -493,7 +493,7 @@ impl FortressScenario {
             SignalFishEvent::Disconnected { reason, .. }
                 if !self.completed && !self.closing_success =>
             {
-                self.fail(format!("unexpected disconnect: {reason:?}"));
+                self.fail(format!("unexpected disconnect: {reason}"));
             }
             _ => {}
         }
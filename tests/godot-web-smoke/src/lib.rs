@@ -289,7 +289,7 @@ impl SmokePair {
                 self.record_load_receive(&data, false);
             }
             SignalFishEvent::Disconnected { reason, .. } => {
-                self.handle_disconnect("first", reason.as_deref());
+                self.handle_disconnect("first", &reason.to_string());
             }
             _ => {}
         }
@@ -344,7 +344,7 @@ impl SmokePair {
                 }
             }
             SignalFishEvent::Disconnected { reason, .. } => {
-                self.handle_disconnect("second", reason.as_deref());
+                self.handle_disconnect("second", &reason.to_string());
             }
             _ => {}
         }
@@ -680,13 +680,11 @@ impl SmokePair {
         self.load_finished_at = Some(Instant::now());
     }
 
-    fn handle_disconnect(&mut self, peer: &str, reason: Option<&str>) {
+    fn handle_disconnect(&mut self, peer: &str, reason: &str) {
         let label = self.kind.label();
         if self.kind == PairKind::Binary
             && self.relay_received
-            && reason.is_some_and(|reason| {
-                reason.contains("code=Some(4000)") || reason.contains("code=4000")
-            })
+            && (reason.contains("code=Some(4000)") || reason.contains("code=4000"))
         {
             godot_print!("SIGNAL_FISH_SMOKE close-attribution-ok {peer}");
             self.close_attributed = true;
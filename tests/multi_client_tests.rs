@@ -0,0 +1,624 @@
+//! Several real clients talking through one in-process server.
+//!
+//! [`FakeServer`] keeps actual rooms: it authenticates each connection,
+//! negotiates protocol v2 or v3 per connection, tracks players and spectators
+//! per room code, announces arrivals to the members already there, and relays
+//! game data to everyone in the room but the sender, stamped with the
+//! sender's player id. Like the real server it encodes game data per
+//! recipient: a JSON recipient gets a `GameData` text message and a
+//! MessagePack recipient a binary envelope, and a v3 recipient gets each
+//! sender's epoch and sequence number. The tests prove that what one client
+//! sends is what the others receive, field for field.
+#![cfg(feature = "tokio-runtime")]
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+
+#[allow(dead_code)]
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use signal_fish_client::protocol::{
+    ClientMessage, GameDataEncoding, LobbyState, PlayerId, PlayerInfo, RoomId, ServerMessage,
+    SpectatorInfo, SpectatorJoinedPayload, V2BinaryGameDataFrame, V3BinaryGameDataFrame,
+};
+use signal_fish_client::testing;
+use signal_fish_client::transport::TransportFrame;
+use signal_fish_client::{
+    JoinRoomParams, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent, Transport,
+};
+use tokio::sync::mpsc;
+
+use common::{authenticated_json, protocol_info_json};
+
+const GAME: &str = "duel";
+const ROOM_CODE: &str = "DUEL01";
+
+// ── Fake server ─────────────────────────────────────────────────────
+
+/// The client half of an in-process connection to [`FakeServer`].
+struct ServerLink {
+    to_server: Option<mpsc::UnboundedSender<TransportFrame>>,
+    from_server: mpsc::UnboundedReceiver<TransportFrame>,
+}
+
+impl Transport for ServerLink {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        let Some(frame) = frame.take() else {
+            return std::task::Poll::Ready(Ok(()));
+        };
+        let sent = self
+            .to_server
+            .as_ref()
+            .is_some_and(|server| server.send(frame).is_ok());
+        std::task::Poll::Ready(if sent {
+            Ok(())
+        } else {
+            Err(SignalFishError::TransportClosed)
+        })
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.from_server.poll_recv(cx).map(|frame| frame.map(Ok))
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.to_server = None;
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// Every sender stays in its first epoch; nobody here reconnects.
+const EPOCH: u32 = 1;
+
+/// What the server agreed with one connection at authentication.
+#[derive(Clone, Copy)]
+struct Session {
+    encoding: GameDataEncoding,
+    v3: bool,
+}
+
+/// One connection's place in a room.
+struct Member {
+    id: PlayerId,
+    name: String,
+    spectator: bool,
+    session: Session,
+    /// Game data messages this member has sent; its v3 sequence number.
+    sent: u64,
+    outbox: mpsc::UnboundedSender<TransportFrame>,
+}
+
+impl Member {
+    /// This member as a roster entry for `recipient`, with its delivery
+    /// baseline when the recipient speaks v3.
+    fn player_info(&self, recipient: Session) -> PlayerInfo {
+        let player = testing::player(&self.name).id(self.id);
+        if recipient.v3 {
+            player.stamped(EPOCH, self.sent).into()
+        } else {
+            player.into()
+        }
+    }
+
+    fn spectator_info(&self) -> SpectatorInfo {
+        testing::spectator(&self.name).id(self.id).into()
+    }
+
+    fn send(&self, message: &ServerMessage) {
+        let json = serde_json::to_string(message).expect("server messages serialize");
+        let _ = self.outbox.send(TransportFrame::Text(json));
+    }
+
+    /// Deliver the `seq`th game data from `from_player` in this member's
+    /// encoding.
+    fn relay(&self, from_player: PlayerId, seq: u64, data: &RelayedData) {
+        match (self.session.encoding, data) {
+            (GameDataEncoding::MessagePack, RelayedData::Json(data)) => {
+                let payload = rmp_serde::to_vec_named(data).expect("JSON encodes as MessagePack");
+                self.relay_binary(from_player, seq, payload);
+            }
+            (GameDataEncoding::MessagePack, RelayedData::Binary(payload)) => {
+                self.relay_binary(from_player, seq, payload.clone());
+            }
+            (_, RelayedData::Json(data)) => self.relay_json(from_player, seq, data.clone()),
+            (_, RelayedData::Binary(payload)) => {
+                let data = rmp_serde::from_slice(payload).expect("binary game data is MessagePack");
+                self.relay_json(from_player, seq, data);
+            }
+        }
+    }
+
+    fn relay_json(&self, from_player: PlayerId, seq: u64, data: serde_json::Value) {
+        let v3 = self.session.v3;
+        self.send(&ServerMessage::GameData {
+            from_player,
+            data,
+            seq: v3.then_some(seq),
+            epoch: v3.then_some(EPOCH),
+            class: None,
+            key: None,
+        });
+    }
+
+    fn relay_binary(&self, from_player: PlayerId, seq: u64, payload: Vec<u8>) {
+        let encoding = GameDataEncoding::MessagePack;
+        let wire = if self.session.v3 {
+            rmp_serde::to_vec_named(&V3BinaryGameDataFrame {
+                from_player,
+                encoding,
+                payload,
+                seq,
+                epoch: EPOCH,
+            })
+        } else {
+            rmp_serde::to_vec_named(&V2BinaryGameDataFrame {
+                from_player,
+                encoding,
+                payload,
+            })
+        };
+        let wire = wire.expect("the envelope encodes");
+        let _ = self.outbox.send(TransportFrame::Binary(wire));
+    }
+}
+
+/// Game data as a client sent it.
+enum RelayedData {
+    Json(serde_json::Value),
+    Binary(Vec<u8>),
+}
+
+struct Room {
+    room_id: RoomId,
+    game_name: String,
+    members: Vec<Member>,
+}
+
+impl Room {
+    fn players(&self, recipient: Session) -> Vec<PlayerInfo> {
+        self.members
+            .iter()
+            .filter(|member| !member.spectator)
+            .map(|member| member.player_info(recipient))
+            .collect()
+    }
+
+    fn spectators(&self) -> Vec<SpectatorInfo> {
+        self.members
+            .iter()
+            .filter(|member| member.spectator)
+            .map(Member::spectator_info)
+            .collect()
+    }
+}
+
+/// An in-process signaling server with real rooms and relaying.
+#[derive(Clone, Default)]
+struct FakeServer {
+    rooms: Arc<Mutex<BTreeMap<String, Room>>>,
+}
+
+impl FakeServer {
+    /// Open a connection and start a client named `name` on it.
+    fn start_client(
+        &self,
+        name: &str,
+        config: SignalFishConfig,
+    ) -> (SignalFishClient, mpsc::Receiver<SignalFishEvent>) {
+        let (to_server, from_client) = mpsc::unbounded_channel();
+        let (to_client, from_server) = mpsc::unbounded_channel();
+        tokio::spawn(self.clone().serve(
+            testing::player_id(name),
+            name.to_owned(),
+            from_client,
+            to_client,
+        ));
+        let link = ServerLink {
+            to_server: Some(to_server),
+            from_server,
+        };
+        SignalFishClient::start(link, common::suite_config(config))
+    }
+
+    /// Handle one connection until its client hangs up.
+    async fn serve(
+        self,
+        id: PlayerId,
+        name: String,
+        mut from_client: mpsc::UnboundedReceiver<TransportFrame>,
+        outbox: mpsc::UnboundedSender<TransportFrame>,
+    ) {
+        let mut session = Session {
+            encoding: GameDataEncoding::Json,
+            v3: false,
+        };
+        let mut room_code: Option<String> = None;
+        while let Some(frame) = from_client.recv().await {
+            let message = match frame {
+                TransportFrame::Text(json) => {
+                    serde_json::from_str(&json).unwrap_or_else(|error| panic!("{json}: {error}"))
+                }
+                TransportFrame::Binary(payload) => {
+                    if let Some(code) = &room_code {
+                        self.broadcast(code, id, &RelayedData::Binary(payload));
+                    }
+                    continue;
+                }
+            };
+            match message {
+                ClientMessage::Authenticate {
+                    game_data_format,
+                    protocol_version,
+                    ..
+                } => {
+                    session = Session {
+                        encoding: game_data_format.unwrap_or_default(),
+                        v3: protocol_version.is_some_and(|version| version >= 3),
+                    };
+                    let version = session.v3.then_some(3);
+                    let _ = outbox.send(TransportFrame::Text(authenticated_json()));
+                    let _ = outbox.send(TransportFrame::Text(protocol_info_json(version)));
+                }
+                ClientMessage::JoinRoom {
+                    game_name,
+                    room_code: code,
+                    ..
+                } => {
+                    let code = code.unwrap_or_else(|| ROOM_CODE.to_owned());
+                    self.join(
+                        &code,
+                        &game_name,
+                        Member {
+                            id,
+                            name: name.clone(),
+                            spectator: false,
+                            session,
+                            sent: 0,
+                            outbox: outbox.clone(),
+                        },
+                    );
+                    room_code = Some(code);
+                }
+                ClientMessage::JoinAsSpectator {
+                    game_name,
+                    room_code: code,
+                    ..
+                } => {
+                    self.join(
+                        &code,
+                        &game_name,
+                        Member {
+                            id,
+                            name: name.clone(),
+                            spectator: true,
+                            session,
+                            sent: 0,
+                            outbox: outbox.clone(),
+                        },
+                    );
+                    room_code = Some(code);
+                }
+                ClientMessage::GameData { data, .. } => {
+                    if let Some(code) = &room_code {
+                        self.broadcast(code, id, &RelayedData::Json(data));
+                    }
+                }
+                ClientMessage::Ping => {
+                    let _ = outbox.send(TransportFrame::Text(
+                        serde_json::to_string(&ServerMessage::Pong).unwrap(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        if let Some(code) = room_code {
+            if let Some(room) = self.rooms.lock().unwrap().get_mut(&code) {
+                room.members.retain(|member| member.id != id);
+            }
+        }
+    }
+
+    /// Seat `member` in the room `code`, creating it, and tell the members
+    /// already there.
+    fn join(&self, code: &str, game_name: &str, member: Member) {
+        let mut rooms = self.rooms.lock().unwrap();
+        let room = rooms.entry(code.to_owned()).or_insert_with(|| Room {
+            room_id: uuid::Uuid::new_v4(),
+            game_name: game_name.to_owned(),
+            members: Vec::new(),
+        });
+        room.members.push(member);
+        let Some(joined) = room.members.last() else {
+            return;
+        };
+        if joined.spectator {
+            joined.send(&ServerMessage::SpectatorJoined(Box::new(
+                SpectatorJoinedPayload {
+                    room_id: room.room_id,
+                    room_code: code.to_owned(),
+                    spectator_id: joined.id,
+                    game_name: room.game_name.clone(),
+                    current_players: room.players(joined.session),
+                    current_spectators: room.spectators(),
+                    lobby_state: LobbyState::Waiting,
+                    reason: None,
+                },
+            )));
+        } else {
+            let payload = testing::room_joined(code)
+                .room_id(room.room_id)
+                .player_id(joined.id)
+                .game_name(room.game_name.clone())
+                .players(room.players(joined.session))
+                .spectators(room.spectators())
+                .payload();
+            joined.send(&ServerMessage::RoomJoined(Box::new(payload)));
+        }
+        for member in &room.members {
+            if member.id == joined.id {
+                continue;
+            }
+            member.send(&if joined.spectator {
+                ServerMessage::NewSpectatorJoined {
+                    spectator: joined.spectator_info(),
+                    current_spectators: room.spectators(),
+                    reason: None,
+                }
+            } else {
+                ServerMessage::PlayerJoined {
+                    player: joined.player_info(member.session),
+                }
+            });
+        }
+    }
+
+    /// Relay game data from `sender` to everyone else in the room `code`.
+    fn broadcast(&self, code: &str, sender: PlayerId, data: &RelayedData) {
+        let mut rooms = self.rooms.lock().unwrap();
+        let Some(room) = rooms.get_mut(code) else {
+            return;
+        };
+        let Some(from) = room.members.iter_mut().find(|member| member.id == sender) else {
+            return;
+        };
+        from.sent += 1;
+        let seq = from.sent;
+        for member in room.members.iter().filter(|member| member.id != sender) {
+            member.relay(sender, seq, data);
+        }
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+/// A client config asking for `encoding` game data.
+fn config(encoding: GameDataEncoding) -> SignalFishConfig {
+    let mut config = SignalFishConfig::new("mb_multi_client");
+    config.game_data_format = Some(encoding);
+    config
+}
+
+/// The next event that is not a roster or lobby update, within a second.
+async fn next_event(events: &mut mpsc::Receiver<SignalFishEvent>) -> SignalFishEvent {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            let event = events.recv().await.expect("the client is running");
+            if !matches!(
+                event,
+                SignalFishEvent::PlayerJoined { .. }
+                    | SignalFishEvent::NewSpectatorJoined { .. }
+                    | SignalFishEvent::LobbyStateChanged { .. }
+            ) {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("an event arrives")
+}
+
+/// Start a client and wait until it is authenticated and has negotiated.
+async fn connect(
+    server: &FakeServer,
+    name: &str,
+    config: SignalFishConfig,
+) -> (SignalFishClient, mpsc::Receiver<SignalFishEvent>) {
+    let (client, mut events) = server.start_client(name, config);
+    assert!(matches!(
+        next_event(&mut events).await,
+        SignalFishEvent::Connected
+    ));
+    let ev = next_event(&mut events).await;
+    assert!(
+        matches!(ev, SignalFishEvent::Authenticated { .. }),
+        "{ev:?}"
+    );
+    let ev = next_event(&mut events).await;
+    assert!(matches!(ev, SignalFishEvent::ProtocolInfo(_)), "{ev:?}");
+    (client, events)
+}
+
+/// Connect `name` and join the shared room as a player.
+async fn join_player(
+    server: &FakeServer,
+    name: &str,
+    config: SignalFishConfig,
+) -> (SignalFishClient, mpsc::Receiver<SignalFishEvent>) {
+    let (mut client, mut events) = connect(server, name, config).await;
+    client
+        .join_room(JoinRoomParams::new(GAME, name).with_room_code(ROOM_CODE))
+        .expect("join_room");
+    let ev = next_event(&mut events).await;
+    let SignalFishEvent::RoomJoined { player_id, .. } = ev else {
+        panic!("expected RoomJoined, got {ev:?}");
+    };
+    assert_eq!(player_id, testing::player_id(name));
+    (client, events)
+}
+
+/// Connect `name` and watch the shared room.
+async fn join_spectator(
+    server: &FakeServer,
+    name: &str,
+    config: SignalFishConfig,
+) -> (SignalFishClient, mpsc::Receiver<SignalFishEvent>) {
+    let (mut client, mut events) = connect(server, name, config).await;
+    client
+        .join_as_spectator(GAME.into(), ROOM_CODE.into(), name.into())
+        .expect("join_as_spectator");
+    let ev = next_event(&mut events).await;
+    assert!(
+        matches!(ev, SignalFishEvent::SpectatorJoined { .. }),
+        "{ev:?}"
+    );
+    (client, events)
+}
+
+fn expect_json(event: SignalFishEvent) -> (PlayerId, serde_json::Value) {
+    match event {
+        SignalFishEvent::GameData {
+            from_player, data, ..
+        } => (from_player, data),
+        other => panic!("expected GameData, got {other:?}"),
+    }
+}
+
+fn expect_binary(event: SignalFishEvent) -> (PlayerId, Vec<u8>) {
+    match event {
+        SignalFishEvent::GameDataBinary {
+            from_player,
+            encoding,
+            payload,
+            ..
+        } => {
+            assert_eq!(encoding, GameDataEncoding::MessagePack);
+            (from_player, payload)
+        }
+        other => panic!("expected GameDataBinary, got {other:?}"),
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn two_players_exchange_json_game_data_and_a_spectator_sees_both() {
+    let server = FakeServer::default();
+    let (mut alice, mut alice_events) =
+        join_player(&server, "Alice", config(GameDataEncoding::Json)).await;
+    let (mut bob, mut bob_events) =
+        join_player(&server, "Bob", config(GameDataEncoding::Json)).await;
+    let (mut carol, mut carol_events) =
+        join_spectator(&server, "Carol", config(GameDataEncoding::Json)).await;
+    assert_eq!(alice.room_snapshot().expect("in the room").players.len(), 2);
+    assert_eq!(bob.room_snapshot().expect("in the room").players.len(), 2);
+
+    let move_a = serde_json::json!({ "move": "e4", "clock": [300, 299.5], "note": "ünïcödé" });
+    alice.send_game_data(move_a.clone()).expect("alice sends");
+    assert_eq!(
+        expect_json(next_event(&mut bob_events).await),
+        (testing::player_id("Alice"), move_a.clone())
+    );
+
+    let move_b = serde_json::json!({ "move": "e5", "resign": false, "extra": null });
+    bob.send_game_data(move_b.clone()).expect("bob sends");
+    assert_eq!(
+        expect_json(next_event(&mut alice_events).await),
+        (testing::player_id("Bob"), move_b.clone())
+    );
+
+    assert_eq!(
+        expect_json(next_event(&mut carol_events).await),
+        (testing::player_id("Alice"), move_a)
+    );
+    assert_eq!(
+        expect_json(next_event(&mut carol_events).await),
+        (testing::player_id("Bob"), move_b)
+    );
+
+    // Nobody hears their own data back.
+    alice.ping().expect("ping");
+    assert!(matches!(
+        next_event(&mut alice_events).await,
+        SignalFishEvent::Pong
+    ));
+    bob.ping().expect("ping");
+    assert!(matches!(
+        next_event(&mut bob_events).await,
+        SignalFishEvent::Pong
+    ));
+
+    for client in [&mut alice, &mut bob, &mut carol] {
+        client.shutdown().await;
+    }
+}
+
+#[tokio::test]
+async fn two_players_exchange_binary_game_data_and_a_json_spectator_sees_both() {
+    let server = FakeServer::default();
+    let (mut alice, mut alice_events) = join_player(
+        &server,
+        "Alice",
+        config(GameDataEncoding::MessagePack).enable_v3(),
+    )
+    .await;
+    let (mut bob, mut bob_events) = join_player(
+        &server,
+        "Bob",
+        config(GameDataEncoding::MessagePack).enable_v3(),
+    )
+    .await;
+    let (mut carol, mut carol_events) =
+        join_spectator(&server, "Carol", config(GameDataEncoding::Json)).await;
+
+    let state = serde_json::json!({ "x": 12, "y": -3, "tag": "bin" });
+    let payload = rmp_serde::to_vec_named(&state).unwrap();
+    alice
+        .send_binary_game_data(payload.clone())
+        .expect("alice sends binary");
+    assert_eq!(
+        expect_binary(next_event(&mut bob_events).await),
+        (testing::player_id("Alice"), payload),
+        "the payload arrives byte for byte"
+    );
+
+    // JSON from a MessagePack client reaches a MessagePack peer as binary.
+    let chat = serde_json::json!({ "chat": "gg" });
+    bob.send_game_data(chat.clone()).expect("bob sends JSON");
+    let (from_player, payload) = expect_binary(next_event(&mut alice_events).await);
+    assert_eq!(from_player, testing::player_id("Bob"));
+    assert_eq!(
+        rmp_serde::from_slice::<serde_json::Value>(&payload).unwrap(),
+        chat
+    );
+
+    // The JSON spectator gets both as JSON, attributed to their senders.
+    assert_eq!(
+        expect_json(next_event(&mut carol_events).await),
+        (testing::player_id("Alice"), state)
+    );
+    assert_eq!(
+        expect_json(next_event(&mut carol_events).await),
+        (testing::player_id("Bob"), chat)
+    );
+
+    for client in [&mut alice, &mut bob, &mut carol] {
+        client.shutdown().await;
+    }
+}
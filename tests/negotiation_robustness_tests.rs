@@ -18,9 +18,10 @@ mod common;
 
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex as StdMutex};
+use std::sync::Arc;
 
 use signal_fish_client::protocol::{LobbyState, ReconnectedPayload, ServerMessage};
+use signal_fish_client::testing::SentLog;
 use signal_fish_client::transport::TransportFrame;
 use signal_fish_client::{
     SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent, Transport,
@@ -28,7 +29,7 @@ use signal_fish_client::{
 
 use common::{
     authenticated_json, game_data_json, protocol_info_json, protocol_info_payload,
-    room_joined_json, wait_for_sent_len,
+    room_joined_json, suite_config, wait_for_sent_len,
 };
 
 fn start_client(
@@ -36,12 +37,12 @@ fn start_client(
 ) -> (
     SignalFishClient,
     tokio::sync::mpsc::Receiver<SignalFishEvent>,
-    Arc<StdMutex<Vec<String>>>,
+    SentLog,
     Arc<AtomicBool>,
 ) {
     let (transport, sent, closed) = common::MockTransport::new(incoming);
     let config = SignalFishConfig::new("mb_audit").enable_mesh();
-    let (client, events) = SignalFishClient::start(transport, config);
+    let (client, events) = SignalFishClient::start(transport, suite_config(config));
     (client, events, sent, closed)
 }
 
@@ -94,7 +95,7 @@ fn reconnected_with_missed(missed: Vec<ServerMessage>) -> String {
         relay_type: "tcp".into(),
         current_spectators: vec![],
         ice_servers: vec![],
-        missed_events: missed,
+        missed_events: missed.into_iter().map(Into::into).collect(),
         replay: None,
         sender_watermarks: vec![],
         reconnection_token: None,
@@ -207,7 +208,7 @@ async fn reconnect_without_protocol_info_preserves_prior_v3() {
         .expect("mesh must survive a reconnect that omits ProtocolInfo");
 
     wait_for_sent_len(&sent, 2).await;
-    let signal_sent = sent.lock().unwrap().iter().any(|m| m.contains("Signal"));
+    let signal_sent = !sent.matching(|m| m.contains("Signal")).is_empty();
     assert!(signal_sent, "Signal should have reached the wire");
     client.shutdown().await;
 }
@@ -219,7 +220,7 @@ async fn reconnect_without_protocol_info_preserves_prior_v3() {
 /// A transport that errors on the Nth `send()` call (1-indexed).
 struct SendErrorTransport {
     incoming: VecDeque<Option<Result<String, SignalFishError>>>,
-    sent: Arc<StdMutex<Vec<String>>>,
+    sent: SentLog,
     closed: Arc<AtomicBool>,
     send_count: usize,
     error_on_send: usize,
@@ -229,12 +230,12 @@ impl SendErrorTransport {
     fn new(
         incoming: Vec<Option<Result<String, SignalFishError>>>,
         error_on_send: usize,
-    ) -> (Self, Arc<StdMutex<Vec<String>>>, Arc<AtomicBool>) {
-        let sent = Arc::new(StdMutex::new(Vec::new()));
+    ) -> (Self, SentLog, Arc<AtomicBool>) {
+        let sent = SentLog::new();
         let closed = Arc::new(AtomicBool::new(false));
         let t = Self {
             incoming: VecDeque::from(incoming),
-            sent: Arc::clone(&sent),
+            sent: sent.clone(),
             closed: Arc::clone(&closed),
             send_count: 0,
             error_on_send,
@@ -257,7 +258,7 @@ impl Transport for SendErrorTransport {
             let TransportFrame::Text(message) = frame else {
                 panic!("test mock expected an outbound text frame");
             };
-            self.sent.lock().unwrap().push(message);
+            self.sent.record(message);
         }
         std::task::Poll::Ready(Ok(()))
     }
@@ -291,7 +292,7 @@ async fn send_error_midflight_disconnects_and_clears_state() {
         2,
     );
     let config = SignalFishConfig::new("mb_audit").enable_mesh();
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
 
     drain_until_authenticated(&mut events).await;
     loop {
@@ -312,7 +313,7 @@ async fn send_error_midflight_disconnects_and_clears_state() {
         match events.recv().await {
             Some(SignalFishEvent::Disconnected { reason, .. }) => {
                 assert!(
-                    reason.as_deref().unwrap_or("").contains("send"),
+                    reason.to_string().contains("send"),
                     "reason should mention the send error: {reason:?}"
                 );
                 saw_disconnect = true;
@@ -344,7 +345,7 @@ async fn v3_send_after_disconnect_does_not_panic() {
         2,
     );
     let config = SignalFishConfig::new("mb_audit").enable_mesh();
-    let (mut client, mut events) = SignalFishClient::start(transport, config);
+    let (mut client, mut events) = SignalFishClient::start(transport, suite_config(config));
 
     drain_until_authenticated(&mut events).await;
     drain_until_protocol_info(&mut events).await;
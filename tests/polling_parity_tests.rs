@@ -16,10 +16,13 @@
     clippy::type_complexity
 )]
 
+#[allow(dead_code)]
+mod common;
+
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
-use signal_fish_client::client::{SignalFishClient, SignalFishConfig};
+use signal_fish_client::client::{SessionInconsistencyPolicy, SignalFishClient, SignalFishConfig};
 use signal_fish_client::error::SignalFishError;
 use signal_fish_client::polling_client::SignalFishPollingClient;
 use signal_fish_client::protocol::{
@@ -27,12 +30,16 @@ use signal_fish_client::protocol::{
     ReconnectedPayload, RoomJoinedPayload, ServerMessage, TransportKind, V2BinaryGameDataFrame,
     V3BinaryGameDataFrame,
 };
+use signal_fish_client::testing::SentLog;
 use signal_fish_client::transport::TransportFrame;
 use signal_fish_client::ProtocolViolationPolicy;
 use signal_fish_client::{
-    GameDataDelivery, JoinRoomParams, PeerSignal, SignalFishClientApi, SignalFishEvent, Transport,
+    DisconnectReason, GameDataDelivery, JoinRoomParams, PeerSignal, SignalFishClientApi,
+    SignalFishEvent, Transport,
 };
 
+use common::suite_config;
+
 fn assert_common_api_is_object_safe(_client: &mut dyn signal_fish_client::SignalFishClientApi) {}
 
 #[derive(Clone)]
@@ -201,7 +208,9 @@ const PI_V2: &str = r#"{"type":"ProtocolInfo","data":{"capabilities":[],"game_da
 #[derive(Clone)]
 struct SharedMock {
     incoming: Arc<Mutex<VecDeque<Option<Result<String, SignalFishError>>>>>,
-    sent: Arc<Mutex<Vec<String>>>,
+    sent: SentLog,
+    /// The receive that found `incoming` empty, woken by [`deliver`](Self::deliver).
+    waker: Arc<Mutex<Option<std::task::Waker>>>,
 }
 
 impl SharedMock {
@@ -210,13 +219,23 @@ impl SharedMock {
             incoming: Arc::new(Mutex::new(
                 msgs.into_iter().map(|m| Some(Ok(m.to_string()))).collect(),
             )),
-            sent: Arc::new(Mutex::new(Vec::new())),
+            sent: SentLog::new(),
+            waker: Arc::default(),
         }
     }
     fn from_msgs(msgs: Vec<Option<Result<String, SignalFishError>>>) -> Self {
         Self {
             incoming: Arc::new(Mutex::new(msgs.into())),
-            sent: Arc::new(Mutex::new(Vec::new())),
+            sent: SentLog::new(),
+            waker: Arc::default(),
+        }
+    }
+
+    /// Queue `item` and wake a receive waiting for it.
+    fn deliver(&self, item: Result<String, SignalFishError>) {
+        self.incoming.lock().unwrap().push_back(Some(item));
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
         }
     }
 }
@@ -231,20 +250,23 @@ impl Transport for SharedMock {
             let TransportFrame::Text(message) = frame else {
                 panic!("parity mock expected an outbound text frame");
             };
-            self.sent.lock().unwrap().push(message);
+            self.sent.record(message);
         }
         std::task::Poll::Ready(Ok(()))
     }
     fn poll_recv(
         &mut self,
-        _cx: &mut std::task::Context<'_>,
+        cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
         let item = self.incoming.lock().unwrap().pop_front();
         match item {
             Some(inner) => {
                 std::task::Poll::Ready(inner.map(|result| result.map(TransportFrame::Text)))
             }
-            None => std::task::Poll::Pending,
+            None => {
+                *self.waker.lock().unwrap() = Some(cx.waker().clone());
+                std::task::Poll::Pending
+            }
         }
     }
     fn poll_close(
@@ -269,6 +291,7 @@ fn pi_v3_payload() -> ProtocolInfoPayload {
         min_protocol_version: Some(2),
         max_protocol_version: Some(3),
         transports: None,
+        limits: None,
     }
 }
 
@@ -295,7 +318,7 @@ fn reconnected_with_missed(missed: Vec<ServerMessage>) -> String {
         relay_type: "tcp".into(),
         current_spectators: vec![],
         ice_servers: vec![],
-        missed_events: missed,
+        missed_events: missed.into_iter().map(Into::into).collect(),
         replay: None,
         sender_watermarks: vec![],
         reconnection_token: None,
@@ -303,10 +326,15 @@ fn reconnected_with_missed(missed: Vec<ServerMessage>) -> String {
     serde_json::to_string(&ServerMessage::Reconnected(Box::new(payload))).unwrap()
 }
 
-async fn wait_for_sent_len(mock: &SharedMock, expected_len: usize) {
+/// [`SentLog::matching`] predicate for the `Authenticate` frame.
+fn is_authenticate(frame: &str) -> bool {
+    frame.starts_with(r#"{"type":"Authenticate""#)
+}
+
+async fn wait_for_sent_len(mock: &SharedMock, expected_len: u64) {
     tokio::time::timeout(std::time::Duration::from_secs(1), async {
         loop {
-            if mock.sent.lock().unwrap().len() >= expected_len {
+            if mock.sent.total() >= expected_len {
                 break;
             }
             tokio::task::yield_now().await;
@@ -316,7 +344,7 @@ async fn wait_for_sent_len(mock: &SharedMock, expected_len: usize) {
     .unwrap_or_else(|_| {
         panic!(
             "timed out waiting for {expected_len} sent message(s); got {}",
-            mock.sent.lock().unwrap().len()
+            mock.sent.total()
         )
     });
 }
@@ -388,15 +416,59 @@ fn canonical_event(event: &SignalFishEvent) -> String {
         SignalFishEvent::Disconnected {
             reason,
             last_server_error,
-        } => event_fields!("Disconnected", reason, last_server_error),
+        } => {
+            // Activity timings differ run to run; the close itself must match.
+            let reason = match reason {
+                DisconnectReason::ServerClosed { close, .. } => format!("ServerClosed {close:?}"),
+                other => format!("{other:?}"),
+            };
+            event_fields!("Disconnected", reason, last_server_error)
+        }
+        SignalFishEvent::Reconnecting { attempt, delay } => {
+            event_fields!("Reconnecting", attempt, delay)
+        }
+        SignalFishEvent::ClientStalled { since } => event_fields!("ClientStalled", since),
+        SignalFishEvent::MessageExpired { kind } => event_fields!("MessageExpired", kind),
         SignalFishEvent::DecodeFailed {
             message_type,
             error,
             raw_prefix,
         } => event_fields!("DecodeFailed", message_type, error, raw_prefix),
+        SignalFishEvent::UnknownMessage {
+            message_type,
+            raw,
+            error,
+        } => event_fields!("UnknownMessage", message_type, raw, error),
         SignalFishEvent::ProtocolViolation { kind, diagnostic } => {
             event_fields!("ProtocolViolation", kind, diagnostic)
         }
+        SignalFishEvent::SessionInvalidated { reason } => {
+            event_fields!("SessionInvalidated", reason)
+        }
+        SignalFishEvent::RemovedFromRoom { room_id, room_code } => {
+            event_fields!("RemovedFromRoom", room_id, room_code)
+        }
+        SignalFishEvent::SelfRemovalPending { room_id } => {
+            event_fields!("SelfRemovalPending", room_id)
+        }
+        SignalFishEvent::SessionInconsistency {
+            expected,
+            got,
+            message_kind,
+        } => event_fields!("SessionInconsistency", expected, got, message_kind),
+        SignalFishEvent::OrganizationMismatch { expected, actual } => {
+            event_fields!("OrganizationMismatch", expected, actual)
+        }
+        SignalFishEvent::FieldOutOfRange {
+            field,
+            value,
+            action,
+        } => event_fields!("FieldOutOfRange", field, value, action),
+        SignalFishEvent::PayloadSalvaged {
+            message_type,
+            field,
+            error,
+        } => event_fields!("PayloadSalvaged", message_type, field, error),
         SignalFishEvent::Authenticated {
             app_name,
             organization,
@@ -438,9 +510,11 @@ fn canonical_event(event: &SignalFishEvent) -> String {
             ice_servers,
             reconnection_token
         ),
-        SignalFishEvent::RoomJoinFailed { reason, error_code } => {
-            event_fields!("RoomJoinFailed", reason, error_code)
-        }
+        SignalFishEvent::RoomJoinFailed {
+            reason,
+            error_code,
+            requested_room_code,
+        } => event_fields!("RoomJoinFailed", reason, error_code, requested_room_code),
         SignalFishEvent::RoomLeft => event_fields!("RoomLeft"),
         SignalFishEvent::PlayerJoined { player } => event_fields!("PlayerJoined", player),
         SignalFishEvent::PlayerLeft {
@@ -570,6 +644,7 @@ fn canonical_event(event: &SignalFishEvent) -> String {
                 reconnection_token
             )
         }
+        SignalFishEvent::MissedEventsGap { from, to } => event_fields!("MissedEventsGap", from, to),
         SignalFishEvent::ReconnectionFailed { reason, error_code } => {
             event_fields!("ReconnectionFailed", reason, error_code)
         }
@@ -626,10 +701,28 @@ fn canonical_event(event: &SignalFishEvent) -> String {
             reason,
             current_spectators
         ),
+        SignalFishEvent::TimeSyncResponse {
+            client_sent_at_ms,
+            server_time_ms,
+            sample,
+        } => event_fields!(
+            "TimeSyncResponse",
+            client_sent_at_ms,
+            server_time_ms,
+            sample
+        ),
+        SignalFishEvent::Announcement {
+            message,
+            severity,
+            metadata,
+        } => event_fields!("Announcement", message, severity, metadata),
         SignalFishEvent::Error {
             message,
             error_code,
         } => event_fields!("Error", message, error_code),
+        // `SignalFishEvent` is non-exhaustive outside the crate, so a variant
+        // without a projection fails here at run time, not at compile time.
+        other => panic!("no parity projection for {other:?}"),
     }
 }
 
@@ -637,7 +730,8 @@ async fn assert_frame_trace_parity(frames: Vec<TransportFrame>, config: SignalFi
     let make_mock = || TraceMock::new(frames.clone());
 
     let async_mock = make_mock();
-    let (async_client, mut async_rx) = SignalFishClient::start(async_mock, config.clone());
+    let (async_client, mut async_rx) =
+        SignalFishClient::start(async_mock, suite_config(config.clone()));
     let mut async_events = Vec::new();
     tokio::time::timeout(std::time::Duration::from_secs(1), async {
         while let Some(event) = async_rx.recv().await {
@@ -664,6 +758,7 @@ async fn assert_frame_trace_parity(frames: Vec<TransportFrame>, config: SignalFi
     assert_eq!(async_events, polling_events);
     assert_eq!(async_client.snapshot(), polling_client.snapshot());
     assert_eq!(async_client.stats(), polling_client.stats());
+    assert_eq!(async_client.state_history(), polling_client.state_history());
 }
 
 async fn assert_server_trace_parity(lines: &str, config: SignalFishConfig) {
@@ -680,16 +775,18 @@ async fn assert_server_trace_parity(lines: &str, config: SignalFishConfig) {
 #[tokio::test]
 async fn parity_relay_floor_authenticate_is_byte_identical() {
     let async_mock = SharedMock::new(vec![]);
-    let (_client, _events) =
-        SignalFishClient::start(async_mock.clone(), SignalFishConfig::new("app"));
+    let (_client, _events) = SignalFishClient::start(
+        async_mock.clone(),
+        suite_config(SignalFishConfig::new("app")),
+    );
     wait_for_sent_len(&async_mock, 1).await;
-    let async_sent = async_mock.sent.lock().unwrap().clone();
+    let async_sent = async_mock.sent.matching(is_authenticate);
 
     let poll_mock = SharedMock::new(vec![]);
     let mut poll_client =
         SignalFishPollingClient::new(poll_mock.clone(), SignalFishConfig::new("app"));
     poll_client.poll();
-    let poll_sent = poll_mock.sent.lock().unwrap().clone();
+    let poll_sent = poll_mock.sent.matching(is_authenticate);
 
     assert!(!async_sent.is_empty());
     assert!(!poll_sent.is_empty());
@@ -707,7 +804,7 @@ async fn parity_relay_floor_authenticate_is_byte_identical() {
 async fn both_drivers_implement_the_object_safe_common_api() {
     let async_mock = SharedMock::new(vec![]);
     let (mut async_client, _events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     assert_common_api_is_object_safe(&mut async_client);
 
     let poll_mock = SharedMock::new(vec![]);
@@ -739,6 +836,26 @@ async fn vendored_v2_and_v3_server_message_traces_have_complete_parity() {
     }
 }
 
+#[tokio::test]
+async fn announcement_filter_and_legacy_mapping_have_parity() {
+    let system = uuid::Uuid::nil();
+    let trace = [
+        r#"{"type":"Authenticated","data":{"app_name":"app","rate_limits":{"per_minute":60,"per_hour":3600,"per_day":86400}}}"#,
+        r#"{"type":"Announcement","data":{"message":"Welcome!","severity":"info"}}"#,
+        r#"{"type":"GameData","data":{"from_player":"00000000-0000-0000-0000-000000000000","data":{"message":"Legacy notice"}}}"#,
+        r#"{"type":"GameData","data":{"from_player":"00000000-0000-0000-0000-000000000000","data":{"tick":1}}}"#,
+    ]
+    .join("\n");
+    let legacy = SignalFishConfig::new("app").with_legacy_announcement_sender(system);
+    for config in [
+        SignalFishConfig::new("app"),
+        legacy.clone(),
+        legacy.with_suppress_announcements(true),
+    ] {
+        assert_server_trace_parity(&trace, config).await;
+    }
+}
+
 fn binary_accountability_prefix(player_id: PlayerId) -> Vec<TransportFrame> {
     let room_joined = ServerMessage::RoomJoined(Box::new(RoomJoinedPayload {
         room_id: uuid::Uuid::from_u128(200),
@@ -849,7 +966,7 @@ async fn every_common_command_produces_identical_physical_frames() {
         let async_mock = FrameMock::v3();
         let async_sent = Arc::clone(&async_mock.sent);
         let (mut async_client, mut async_events) =
-            SignalFishClient::start(async_mock, config.clone());
+            SignalFishClient::start(async_mock, suite_config(config.clone()));
         for _ in 0..2 {
             tokio::time::timeout(std::time::Duration::from_secs(1), async_events.recv())
                 .await
@@ -905,7 +1022,8 @@ async fn pending_transport_queue_capacity_and_errors_match() {
 
     let async_mock = NeverSendMock::new();
     let attempted = Arc::clone(&async_mock.attempted);
-    let (mut async_client, _events) = SignalFishClient::start(async_mock, config.clone());
+    let (mut async_client, _events) =
+        SignalFishClient::start(async_mock, suite_config(config.clone()));
     tokio::time::timeout(std::time::Duration::from_secs(1), async {
         while !attempted.load(std::sync::atomic::Ordering::Acquire) {
             tokio::task::yield_now().await;
@@ -941,7 +1059,7 @@ async fn pending_transport_queue_capacity_and_errors_match() {
 async fn disconnected_common_commands_consistently_return_not_connected() {
     let async_mock = SharedMock::new(vec![]);
     let (mut async_client, _events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     async_client.shutdown().await;
 
     let polling_mock = SharedMock::new(vec![]);
@@ -989,7 +1107,8 @@ async fn parity_ensure_v3_pre_negotiation_mode() {
     let peer: PlayerId = PEER_UUID.parse().unwrap();
 
     let async_mock = SharedMock::new(vec![]);
-    let (mut client, _events) = SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+    let (mut client, _events) =
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     let async_err = client.send_offer(peer, "sdp").unwrap_err();
 
     let poll_mock = SharedMock::new(vec![]);
@@ -1020,7 +1139,7 @@ async fn parity_ensure_v3_relay_only_mode_after_v2_negotiation() {
 
     let async_mock = SharedMock::new(vec![AUTH, PI_V2]);
     let (mut client, mut events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     // Drain until the v2 ProtocolInfo has been processed into client state.
     loop {
         match events.recv().await {
@@ -1050,7 +1169,8 @@ async fn parity_ensure_v3_relay_only_mode_after_v2_negotiation() {
 #[tokio::test]
 async fn parity_negotiated_version_after_v3() {
     let async_mock = SharedMock::new(vec![AUTH, PI_V3]);
-    let (client, mut events) = SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+    let (client, mut events) =
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     for _ in 0..3 {
         let _ = tokio::time::timeout(std::time::Duration::from_millis(100), events.recv()).await;
     }
@@ -1071,8 +1191,10 @@ async fn parity_reconnect_replay_restores_v3_from_missed_events() {
     let recon = reconnected_with_missed(vec![ServerMessage::ProtocolInfo(pi_v3_payload())]);
 
     let async_mock = SharedMock::new(vec![AUTH, &recon]);
-    let (client, mut events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app").enable_mesh());
+    let (client, mut events) = SignalFishClient::start(
+        async_mock,
+        suite_config(SignalFishConfig::new("app").enable_mesh()),
+    );
     for _ in 0..4 {
         let _ = tokio::time::timeout(std::time::Duration::from_millis(100), events.recv()).await;
     }
@@ -1099,7 +1221,8 @@ async fn parity_reconnect_v2_missed_events_does_not_downgrade_active_v3() {
     let recon_v2 = reconnected_with_missed(vec![ServerMessage::ProtocolInfo(pi_v2_payload())]);
 
     let async_mock = SharedMock::new(vec![AUTH, PI_V3, &recon_v2]);
-    let (client, mut events) = SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+    let (client, mut events) =
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     for _ in 0..5 {
         let _ = tokio::time::timeout(std::time::Duration::from_millis(100), events.recv()).await;
     }
@@ -1126,10 +1249,10 @@ async fn parity_enable_mesh_authenticate_is_byte_identical() {
     let async_mock = SharedMock::new(vec![]);
     let (_client, _events) = SignalFishClient::start(
         async_mock.clone(),
-        SignalFishConfig::new("app").enable_mesh(),
+        suite_config(SignalFishConfig::new("app").enable_mesh()),
     );
     wait_for_sent_len(&async_mock, 1).await;
-    let async_sent = async_mock.sent.lock().unwrap().clone();
+    let async_sent = async_mock.sent.matching(is_authenticate);
 
     let poll_mock = SharedMock::new(vec![]);
     let mut poll_client = SignalFishPollingClient::new(
@@ -1137,7 +1260,7 @@ async fn parity_enable_mesh_authenticate_is_byte_identical() {
         SignalFishConfig::new("app").enable_mesh(),
     );
     poll_client.poll();
-    let poll_sent = poll_mock.sent.lock().unwrap().clone();
+    let poll_sent = poll_mock.sent.matching(is_authenticate);
 
     assert_eq!(
         async_sent[0], poll_sent[0],
@@ -1162,8 +1285,10 @@ async fn parity_disconnect_resets_negotiated_version() {
         Some(Ok(PI_V3.to_string())),
         Some(Err(SignalFishError::TransportReceive("reset".into()))),
     ]);
-    let (client, mut events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app").enable_mesh());
+    let (client, mut events) = SignalFishClient::start(
+        async_mock,
+        suite_config(SignalFishConfig::new("app").enable_mesh()),
+    );
     let mut saw_disconnect = false;
     for _ in 0..6 {
         match tokio::time::timeout(std::time::Duration::from_millis(150), events.recv()).await {
@@ -1183,13 +1308,12 @@ async fn parity_disconnect_resets_negotiated_version() {
 
 #[tokio::test]
 async fn parity_decode_failed_async_vs_polling() {
-    const BAD_FRAME: &str =
-        r#"{"type":"Error","data":{"message":"x","error_code":"FUTURE_CODE_XYZ"}}"#;
+    const BAD_FRAME: &str = r#"{"type":"Error","data":{"message":"x","error_code":404}}"#;
 
     // Async client.
     let async_mock = SharedMock::new(vec![AUTH, BAD_FRAME]);
     let (async_client, mut events) =
-        SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     let mut async_decode_failed = None;
     for _ in 0..6 {
         match tokio::time::timeout(std::time::Duration::from_millis(150), events.recv()).await {
@@ -1248,7 +1372,8 @@ async fn parity_disconnected_carries_last_server_error() {
         Some(Ok(FAREWELL.to_string())),
         None,
     ]);
-    let (_client, mut events) = SignalFishClient::start(async_mock, SignalFishConfig::new("app"));
+    let (_client, mut events) =
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
     let mut async_info = None;
     for _ in 0..8 {
         match tokio::time::timeout(std::time::Duration::from_millis(150), events.recv()).await {
@@ -1286,3 +1411,449 @@ async fn parity_disconnected_carries_last_server_error() {
         Some(signal_fish_client::ErrorCode::SlowConsumer)
     );
 }
+
+// ── PARITY 11: advertised server limits gate join_room in both ────────
+
+const PI_LIMITS: &str = r#"{"type":"ProtocolInfo","data":{"capabilities":[],"game_data_formats":[],"limits":{"max_rooms_per_game":20,"max_players_per_room":4}}}"#;
+
+#[tokio::test]
+async fn parity_server_limits_reject_oversized_rooms() {
+    let async_mock = SharedMock::new(vec![AUTH, PI_LIMITS]);
+    let (mut client, mut events) =
+        SignalFishClient::start(async_mock, suite_config(SignalFishConfig::new("app")));
+    loop {
+        match events.recv().await {
+            Some(SignalFishEvent::ProtocolInfo(_)) | None => break,
+            _ => {}
+        }
+    }
+
+    let poll_mock = SharedMock::new(vec![AUTH, PI_LIMITS]);
+    let mut poll_client = SignalFishPollingClient::new(poll_mock, SignalFishConfig::new("app"));
+    poll_client.poll();
+
+    assert_eq!(client.server_limits(), poll_client.server_limits());
+    assert_eq!(
+        poll_client
+            .server_limits()
+            .and_then(|limits| limits.max_players_per_room),
+        Some(4)
+    );
+    let params = JoinRoomParams::new("g", "alice").with_max_players(5);
+    for error in [
+        client.join_room(params.clone()).unwrap_err(),
+        poll_client.join_room(params).unwrap_err(),
+    ] {
+        assert!(
+            matches!(
+                error,
+                SignalFishError::MaxPlayersExceeded {
+                    requested: 5,
+                    limit: 4
+                }
+            ),
+            "{error:?}"
+        );
+    }
+    let code = signal_fish_client::ErrorCode::MaxRoomsPerGameExceeded;
+    assert_eq!(
+        client.describe_error_code(&code),
+        poll_client.describe_error_code(&code)
+    );
+    assert!(poll_client
+        .describe_error_code(&code)
+        .ends_with("(server limit: 20 rooms per game)"));
+}
+
+// ── PARITY 12: room-scope inconsistencies follow the same policy ─────
+
+#[tokio::test]
+async fn parity_session_inconsistency_policies() {
+    let foreign_spectator_left = serde_json::to_string(&ServerMessage::SpectatorLeft {
+        room_id: Some(uuid::Uuid::from_u128(999)),
+        room_code: Some("ELSEWH".into()),
+        reason: None,
+        current_spectators: vec![],
+    })
+    .unwrap();
+    let trace = [AUTH, &open_room_joined_json(), &foreign_spectator_left].join("\n");
+    for policy in [
+        SessionInconsistencyPolicy::Apply,
+        SessionInconsistencyPolicy::Ignore,
+        SessionInconsistencyPolicy::Resync,
+    ] {
+        assert_server_trace_parity(
+            &trace,
+            SignalFishConfig::new("app").with_session_inconsistency_policy(policy),
+        )
+        .await;
+    }
+}
+
+// ── PARITY 13: out-of-range numbers are adjusted identically ─────────
+
+#[tokio::test]
+async fn parity_out_of_range_numbers() {
+    let authenticated = r#"{"type":"Authenticated","data":{"app_name":"a","rate_limits":{"per_minute":-1,"per_hour":5000000000,"per_day":10}}}"#;
+    let room_joined = open_room_joined_json().replace(r#""max_players":4"#, r#""max_players":300"#);
+    let trace = [authenticated, &room_joined].join("\n");
+    assert_server_trace_parity(&trace, SignalFishConfig::new("app")).await;
+}
+
+fn room_join_failed_json() -> String {
+    serde_json::to_string(&ServerMessage::RoomJoinFailed {
+        reason: "room full".into(),
+        error_code: Some(signal_fish_client::ErrorCode::RoomFull),
+    })
+    .unwrap()
+}
+
+fn open_room_joined_json() -> String {
+    serde_json::to_string(&ServerMessage::RoomJoined(Box::new(RoomJoinedPayload {
+        room_id: uuid::Uuid::from_u128(300),
+        room_code: "OPEN01".into(),
+        player_id: uuid::Uuid::from_u128(301),
+        game_name: "g".into(),
+        max_players: 4,
+        supports_authority: false,
+        current_players: vec![],
+        is_authority: false,
+        lobby_state: LobbyState::Waiting,
+        ready_players: vec![],
+        relay_type: "websocket".into(),
+        current_spectators: vec![],
+        ice_servers: vec![],
+        reconnection_token: None,
+    })))
+    .unwrap()
+}
+
+fn assert_no_room(snapshot: &signal_fish_client::ClientSnapshot) {
+    assert_eq!(snapshot.room_id, None, "failed join left a room id behind");
+    assert_eq!(
+        snapshot.room_code, None,
+        "failed join left a room code behind"
+    );
+    assert_eq!(snapshot.player_id, None);
+}
+
+#[tokio::test]
+async fn parity_failed_join_leaves_no_partial_room_state() {
+    use signal_fish_client::SessionState;
+
+    // Async: join FULL01 → failure, then quick-match → success.
+    let async_mock = SharedMock::new(vec![AUTH]);
+    let (mut async_client, mut events) = SignalFishClient::start(
+        async_mock.clone(),
+        suite_config(SignalFishConfig::new("app")),
+    );
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+
+    async_mock
+        .incoming
+        .lock()
+        .unwrap()
+        .push_back(Some(Ok(room_join_failed_json())));
+    async_client
+        .join_room(JoinRoomParams::new("g", "alice").with_room_code("FULL01"))
+        .unwrap();
+    let async_failed = events.recv().await.expect("RoomJoinFailed");
+    assert_no_room(&async_client.snapshot());
+    assert_eq!(async_client.session_state(), SessionState::Authenticated);
+
+    async_mock
+        .incoming
+        .lock()
+        .unwrap()
+        .push_back(Some(Ok(open_room_joined_json())));
+    async_client
+        .join_room(JoinRoomParams::new("g", "alice"))
+        .unwrap();
+    let async_joined = events.recv().await.expect("RoomJoined");
+    let async_snapshot = async_client.snapshot();
+    async_client.shutdown().await;
+
+    // Polling: the same script, one poll per server answer.
+    let poll_mock = SharedMock::new(vec![AUTH]);
+    let mut poll_client =
+        SignalFishPollingClient::new(poll_mock.clone(), SignalFishConfig::new("app"));
+    poll_client.poll();
+    poll_client
+        .join_room(JoinRoomParams::new("g", "alice").with_room_code("FULL01"))
+        .unwrap();
+    poll_mock
+        .incoming
+        .lock()
+        .unwrap()
+        .push_back(Some(Ok(room_join_failed_json())));
+    let poll_failed = poll_client.poll();
+    assert_no_room(&poll_client.snapshot());
+    assert_eq!(poll_client.session_state(), SessionState::Authenticated);
+
+    poll_client
+        .join_room(JoinRoomParams::new("g", "alice"))
+        .unwrap();
+    poll_mock
+        .incoming
+        .lock()
+        .unwrap()
+        .push_back(Some(Ok(open_room_joined_json())));
+    let poll_joined = poll_client.poll();
+
+    match &async_failed {
+        SignalFishEvent::RoomJoinFailed {
+            requested_room_code,
+            error_code,
+            ..
+        } => {
+            assert_eq!(requested_room_code.as_deref(), Some("FULL01"));
+            assert_eq!(*error_code, Some(signal_fish_client::ErrorCode::RoomFull));
+        }
+        other => panic!("expected RoomJoinFailed, got {other:?}"),
+    }
+    assert_eq!(poll_failed.len(), 1, "{poll_failed:?}");
+    assert_eq!(
+        canonical_event(&async_failed),
+        canonical_event(&poll_failed[0])
+    );
+    assert_eq!(poll_joined.len(), 1, "{poll_joined:?}");
+    assert_eq!(
+        canonical_event(&async_joined),
+        canonical_event(&poll_joined[0])
+    );
+    assert_eq!(async_snapshot.room_code.as_deref(), Some("OPEN01"));
+    assert_eq!(
+        format!("{async_snapshot:?}"),
+        format!("{:?}", poll_client.snapshot())
+    );
+    assert_eq!(poll_client.session_state(), SessionState::InRoom);
+}
+
+// ── is_authority() follows the same messages in both drivers ─────────
+
+/// One step of the authority script: a server message, or a local
+/// `request_authority` call.
+enum AuthorityStep {
+    Server(String),
+    Ask(bool),
+}
+
+fn authority_script() -> Vec<(AuthorityStep, bool)> {
+    let local = uuid::Uuid::from_u128(301);
+    let other = uuid::Uuid::from_u128(302);
+    let joined: ServerMessage = serde_json::from_str(&open_room_joined_json()).unwrap();
+    let ServerMessage::RoomJoined(mut joined) = joined else {
+        panic!("expected RoomJoined");
+    };
+    joined.is_authority = true;
+    joined.current_players = [(local, "alice", true), (other, "bob", false)]
+        .into_iter()
+        .map(|(id, name, is_authority)| PlayerInfo {
+            id,
+            name: name.into(),
+            is_authority,
+            is_ready: false,
+            connected_at: "2026-01-01T00:00:00Z".into(),
+            connection_info: None,
+            epoch: None,
+            seq: None,
+        })
+        .collect();
+    let reconnected: ServerMessage =
+        serde_json::from_str(&reconnected_with_missed(vec![])).unwrap();
+    let ServerMessage::Reconnected(mut reconnected) = reconnected else {
+        panic!("expected Reconnected");
+    };
+    reconnected.is_authority = true;
+    let server =
+        |message: ServerMessage| AuthorityStep::Server(serde_json::to_string(&message).unwrap());
+    let response = |granted| {
+        server(ServerMessage::AuthorityResponse {
+            granted,
+            reason: None,
+            error_code: None,
+        })
+    };
+    vec![
+        (server(ServerMessage::RoomJoined(joined)), true),
+        (
+            server(ServerMessage::AuthorityChanged {
+                authority_player: Some(other),
+                you_are_authority: false,
+            }),
+            false,
+        ),
+        (AuthorityStep::Ask(true), false),
+        (response(false), false),
+        (AuthorityStep::Ask(true), false),
+        (response(true), true),
+        (AuthorityStep::Ask(false), true),
+        (response(true), false),
+        (
+            server(ServerMessage::AuthorityChanged {
+                authority_player: Some(local),
+                you_are_authority: true,
+            }),
+            true,
+        ),
+        (server(ServerMessage::RoomLeft), false),
+        (server(ServerMessage::Reconnected(reconnected)), true),
+    ]
+}
+
+#[tokio::test]
+async fn parity_is_authority_tracks_joins_changes_grants_and_departures() {
+    let async_mock = SharedMock::new(vec![AUTH]);
+    let (mut async_client, mut events) = SignalFishClient::start(
+        async_mock.clone(),
+        suite_config(SignalFishConfig::new("app")),
+    );
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Connected)
+    ));
+    assert!(matches!(
+        events.recv().await,
+        Some(SignalFishEvent::Authenticated { .. })
+    ));
+    assert!(!async_client.is_authority());
+
+    let poll_mock = SharedMock::new(vec![AUTH]);
+    let mut poll_client =
+        SignalFishPollingClient::new(poll_mock.clone(), SignalFishConfig::new("app"));
+    poll_client.poll();
+    assert!(!poll_client.is_authority());
+
+    async_client
+        .join_room(JoinRoomParams::new("g", "alice"))
+        .unwrap();
+    poll_client
+        .join_room(JoinRoomParams::new("g", "alice"))
+        .unwrap();
+    for (index, (step, expected)) in authority_script().into_iter().enumerate() {
+        match step {
+            AuthorityStep::Server(frame) => {
+                async_mock.deliver(Ok(frame.clone()));
+                tokio::time::timeout(std::time::Duration::from_secs(1), events.recv())
+                    .await
+                    .unwrap_or_else(|_| panic!("no event for step {index}"))
+                    .expect("the scripted message's event");
+                poll_mock.deliver(Ok(frame));
+                poll_client.poll();
+            }
+            AuthorityStep::Ask(become_authority) => {
+                async_client.request_authority(become_authority).unwrap();
+                poll_client.request_authority(become_authority).unwrap();
+                poll_client.poll();
+            }
+        }
+        assert_eq!(async_client.is_authority(), expected, "async, step {index}");
+        assert_eq!(
+            poll_client.is_authority(),
+            expected,
+            "polling, step {index}"
+        );
+    }
+
+    async_mock.deliver(Err(SignalFishError::TransportReceive("reset".into())));
+    loop {
+        match events.recv().await {
+            Some(SignalFishEvent::Disconnected { .. }) | None => break,
+            Some(_) => {}
+        }
+    }
+    assert!(!async_client.is_authority(), "async: reset on Disconnected");
+    poll_client.close();
+    assert!(!poll_client.is_authority(), "polling: reset on close");
+}
+
+// ── PARITY: typed game data serializes before it is queued ───────────
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum GameMsg {
+    Move { x: i32, y: i32 },
+    Chat(String),
+}
+
+#[tokio::test]
+async fn parity_send_game_data_as_serializes_or_refuses() {
+    let async_mock = SharedMock::new(vec![AUTH]);
+    let (mut async_client, mut events) = SignalFishClient::start(
+        async_mock.clone(),
+        suite_config(SignalFishConfig::new("app")),
+    );
+    loop {
+        match events.recv().await {
+            Some(SignalFishEvent::Authenticated { .. }) | None => break,
+            _ => {}
+        }
+    }
+    let poll_mock = SharedMock::new(vec![AUTH]);
+    let mut poll_client =
+        SignalFishPollingClient::new(poll_mock.clone(), SignalFishConfig::new("app"));
+    poll_client.poll();
+
+    // JSON object keys must be strings.
+    let unserializable = std::collections::BTreeMap::from([((1, 2), "tile")]);
+    let messages = [GameMsg::Move { x: 3, y: -1 }, GameMsg::Chat("gg".into())];
+    for message in &messages {
+        async_client.send_game_data_as(message).unwrap();
+        poll_client.send_game_data_as(message).unwrap();
+    }
+    for error in [
+        async_client.send_game_data_as(&unserializable).unwrap_err(),
+        poll_client.send_game_data_as(&unserializable).unwrap_err(),
+    ] {
+        assert!(
+            matches!(error, SignalFishError::Serialization(_)),
+            "{error:?}"
+        );
+    }
+    poll_client.poll();
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        while async_mock
+            .sent
+            .matching(|frame| frame.contains("GameData"))
+            .len()
+            < 2
+        {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("both messages sent");
+
+    let async_sent = async_mock.sent.matching(|frame| frame.contains("GameData"));
+    assert_eq!(
+        async_sent,
+        poll_mock.sent.matching(|frame| frame.contains("GameData"))
+    );
+    let received: Vec<GameMsg> = async_sent
+        .iter()
+        .map(|frame| {
+            let frame: serde_json::Value = serde_json::from_str(frame).unwrap();
+            SignalFishEvent::GameData {
+                from_player: PlayerId::nil(),
+                data: frame["data"]["data"].clone(),
+                seq: None,
+                epoch: None,
+                class: None,
+                key: None,
+            }
+            .game_data_as()
+            .unwrap()
+            .unwrap()
+        })
+        .collect();
+    assert_eq!(received, messages);
+    assert_eq!(async_client.stats().game_data_sent, 2);
+    assert_eq!(poll_client.stats().game_data_sent, 2);
+    async_client.shutdown().await;
+}
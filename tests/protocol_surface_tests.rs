@@ -0,0 +1,786 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::todo,
+    clippy::unimplemented,
+    clippy::indexing_slicing
+)]
+//! Snapshot of the protocol module's wire surface.
+//!
+//! Every `ClientMessage` / `ServerMessage` variant is serialized from a
+//! canonical instance with every optional field present and one element in
+//! every list. The JSON is reduced to its structure — keys sorted, values
+//! replaced by their JSON type, `type` tags kept verbatim — and compared with
+//! `tests/protocol-surface.snapshot`, together with the wire names of every
+//! protocol enum. A renamed field, a changed tag, or a new variant therefore
+//! fails here until the snapshot is regenerated in the same change:
+//!
+//! ```text
+//! SIGNAL_FISH_UPDATE_SNAPSHOTS=1 cargo test --test protocol_surface_tests
+//! ```
+//!
+//! The snapshot carries a digest of the surface, and `CHANGELOG.md` must
+//! quote it, so a wire change cannot land without a changelog entry. The
+//! `ErrorCode` value space is checked against the server spec in
+//! `error_code_conformance_tests.rs`, not here.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use signal_fish_client::protocol::{
+    ClientMessage, ConnectionInfo, DeliveryClass, DeliveryCountersByClass, DeliveryGap,
+    DeliveryGapReason, DeliveryReportPayload, GameDataEncoding, IceServer, LobbyState,
+    MessageTransport, MissedEvent, PeerConnectionInfo, PlayerNameRulesPayload, ProtocolInfoPayload,
+    RateLimitInfo, ReconnectedPayload, RelayTransport, ReplayStatus, RoomJoinedPayload,
+    SenderWatermark, ServerLimits, ServerMessage, SessionPeer, SessionPlanPayload,
+    SpectatorJoinedPayload, SpectatorStateChangeReason, Topology, TransportKind,
+};
+use signal_fish_client::{testing, ErrorCode};
+
+/// Environment variable that rewrites the snapshot instead of comparing.
+const UPDATE_ENV: &str = "SIGNAL_FISH_UPDATE_SNAPSHOTS";
+const SNAPSHOT: &str = "tests/protocol-surface.snapshot";
+const HEADER: &str = "\
+# Wire surface of `signal_fish_client::protocol`, checked by
+# tests/protocol_surface_tests.rs. Do not edit by hand; regenerate with
+#   SIGNAL_FISH_UPDATE_SNAPSHOTS=1 cargo test --test protocol_surface_tests
+# and quote the new digest in the CHANGELOG.md entry for the wire change.
+";
+
+fn snapshot_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SNAPSHOT)
+}
+
+// ── Canonicalization ────────────────────────────────────────────────
+
+/// `value` with every leaf replaced by its JSON type. String `type` tags stay
+/// verbatim because they are the wire discriminants; list elements collapse
+/// to their distinct shapes.
+fn erase(value: &Value) -> Value {
+    match value {
+        Value::Null => json!("null"),
+        Value::Bool(_) => json!("bool"),
+        Value::Number(number) if number.is_f64() => json!("number"),
+        Value::Number(_) => json!("integer"),
+        Value::String(_) => json!("string"),
+        Value::Array(items) => {
+            let mut shapes: Vec<Value> = Vec::new();
+            for shape in items.iter().map(erase) {
+                if !shapes.contains(&shape) {
+                    shapes.push(shape);
+                }
+            }
+            shapes.sort_by_key(render);
+            Value::Array(shapes)
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| {
+                    let shape = match field {
+                        Value::String(tag) if key == "type" => Value::String(tag.clone()),
+                        other => erase(other),
+                    };
+                    (key.clone(), shape)
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Compact JSON with object keys sorted, independent of `serde_json`'s map
+/// ordering features.
+fn render(value: &Value) -> String {
+    let mut out = String::new();
+    render_into(value, &mut out);
+    out
+}
+
+fn render_into(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                render_into(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                render_into(&fields[key], out);
+            }
+            out.push('}');
+        }
+        leaf => out.push_str(&leaf.to_string()),
+    }
+}
+
+/// The wire names serde accepts for `T`, read back from the error it gives
+/// for an unknown variant. `probe` wraps the unknown name in `T`'s
+/// representation.
+fn wire_names<T: DeserializeOwned + std::fmt::Debug>(probe: Value) -> Vec<String> {
+    let error = serde_json::from_value::<T>(probe).expect_err("probe variant must be unknown");
+    let message = error.to_string();
+    let (_, expected) = message
+        .split_once("expected one of ")
+        .or_else(|| message.split_once("expected "))
+        .unwrap_or_else(|| panic!("unexpected serde error for an unknown variant: {message}"));
+    expected
+        .split(", ")
+        .filter_map(|name| name.split('`').nth(1))
+        .map(str::to_owned)
+        .collect()
+}
+
+fn tag_probe() -> Value {
+    json!({ "type": "__surface_probe__" })
+}
+
+fn string_probe() -> Value {
+    json!("__surface_probe__")
+}
+
+// ── Canonical instances ─────────────────────────────────────────────
+
+fn id(n: u128) -> uuid::Uuid {
+    uuid::Uuid::from_u128(n)
+}
+
+fn connection_infos() -> Vec<ConnectionInfo> {
+    vec![
+        ConnectionInfo::Direct {
+            host: "10.0.0.1".into(),
+            port: 7777,
+        },
+        ConnectionInfo::UnityRelay {
+            allocation_id: "alloc".into(),
+            connection_data: "data".into(),
+            key: "key".into(),
+        },
+        ConnectionInfo::Relay {
+            host: "relay.example.com".into(),
+            port: 9999,
+            transport: RelayTransport::Udp,
+            allocation_id: "alloc".into(),
+            token: "token".into(),
+            client_id: Some(7),
+        },
+        ConnectionInfo::WebRTC {
+            sdp: Some("v=0".into()),
+            ice_candidates: vec!["candidate:1".into()],
+        },
+        ConnectionInfo::Custom { data: json!({}) },
+    ]
+}
+
+fn direct() -> ConnectionInfo {
+    ConnectionInfo::Direct {
+        host: "10.0.0.1".into(),
+        port: 7777,
+    }
+}
+
+fn ice_server() -> IceServer {
+    IceServer {
+        urls: vec!["turn:turn.example.com".into()],
+        username: Some("user".into()),
+        credential: Some("secret".into()),
+    }
+}
+
+fn player() -> signal_fish_client::protocol::PlayerInfo {
+    testing::player("alice")
+        .id(id(1))
+        .authority()
+        .ready()
+        .connection_info(direct())
+        .stamped(1, 2)
+        .build()
+}
+
+fn spectator() -> signal_fish_client::protocol::SpectatorInfo {
+    testing::spectator("watcher").id(id(2)).build()
+}
+
+fn room_joined() -> RoomJoinedPayload {
+    RoomJoinedPayload {
+        room_id: id(10),
+        room_code: "ROOM01".into(),
+        player_id: id(1),
+        game_name: "game".into(),
+        max_players: 4,
+        supports_authority: true,
+        current_players: vec![player()],
+        is_authority: true,
+        lobby_state: LobbyState::Lobby,
+        ready_players: vec![id(1)],
+        relay_type: "websocket".into(),
+        current_spectators: vec![spectator()],
+        ice_servers: vec![ice_server()],
+        reconnection_token: Some("token".into()),
+    }
+}
+
+fn reconnected() -> ReconnectedPayload {
+    ReconnectedPayload {
+        room_id: id(10),
+        room_code: "ROOM01".into(),
+        player_id: id(1),
+        game_name: "game".into(),
+        max_players: 4,
+        supports_authority: true,
+        current_players: vec![player()],
+        is_authority: true,
+        lobby_state: LobbyState::Lobby,
+        ready_players: vec![id(1)],
+        relay_type: "websocket".into(),
+        current_spectators: vec![spectator()],
+        ice_servers: vec![ice_server()],
+        missed_events: vec![MissedEvent {
+            message: ServerMessage::PlayerReconnected {
+                player_id: id(3),
+                epoch: Some(1),
+            },
+            seq: Some(5),
+        }],
+        replay: Some(ReplayStatus::Complete),
+        sender_watermarks: vec![SenderWatermark {
+            player_id: id(3),
+            epoch: 1,
+            seq: 5,
+        }],
+        reconnection_token: Some("token".into()),
+    }
+}
+
+fn protocol_info() -> ProtocolInfoPayload {
+    ProtocolInfoPayload {
+        platform: Some("rust".into()),
+        sdk_version: Some("0.9.0".into()),
+        minimum_version: Some("0.8.0".into()),
+        recommended_version: Some("0.9.0".into()),
+        capabilities: vec!["reconnect".into()],
+        notes: Some("notes".into()),
+        game_data_formats: vec![GameDataEncoding::MessagePack],
+        player_name_rules: Some(PlayerNameRulesPayload {
+            max_length: 16,
+            min_length: 1,
+            allow_unicode_alphanumeric: true,
+            allow_spaces: false,
+            allow_leading_trailing_whitespace: false,
+            allowed_symbols: vec!['_'],
+            additional_allowed_characters: Some("-".into()),
+        }),
+        protocol_version: Some(3),
+        min_protocol_version: Some(2),
+        max_protocol_version: Some(3),
+        transports: Some(vec![MessageTransport::Websocket]),
+        limits: Some(ServerLimits {
+            max_rooms_per_game: Some(10),
+            max_players_per_room: Some(8),
+            max_spectators: Some(4),
+        }),
+    }
+}
+
+fn client_messages() -> Vec<(String, ClientMessage)> {
+    let mut messages = vec![
+        ClientMessage::Authenticate {
+            app_id: "mb_app".into(),
+            sdk_version: Some("0.9.0".into()),
+            platform: Some("rust".into()),
+            game_data_format: Some(GameDataEncoding::MessagePack),
+            protocol_version: Some(3),
+            supported_transports: Some(vec![TransportKind::WebRtc]),
+            supported_topologies: Some(vec![Topology::Mesh]),
+        },
+        ClientMessage::JoinRoom {
+            game_name: "game".into(),
+            room_code: Some("ROOM01".into()),
+            player_name: "alice".into(),
+            max_players: Some(4),
+            supports_authority: Some(true),
+            relay_transport: Some(RelayTransport::Tcp),
+        },
+        ClientMessage::LeaveRoom,
+        ClientMessage::GameData {
+            data: json!({}),
+            class: Some(DeliveryClass::Latest),
+            key: Some(1),
+        },
+        ClientMessage::AuthorityRequest {
+            become_authority: true,
+        },
+        ClientMessage::PlayerReady,
+        ClientMessage::Ping,
+        ClientMessage::Reconnect {
+            player_id: id(1),
+            room_id: id(10),
+            auth_token: "token".into(),
+        },
+        ClientMessage::JoinAsSpectator {
+            game_name: "game".into(),
+            room_code: "ROOM01".into(),
+            spectator_name: "watcher".into(),
+        },
+        ClientMessage::LeaveSpectator,
+        ClientMessage::StartGame,
+        ClientMessage::Signal {
+            to: id(3),
+            signal: json!({}),
+        },
+        ClientMessage::TransportStatus {
+            transport: TransportKind::Direct,
+            connected: true,
+        },
+        ClientMessage::TimeSyncRequest {
+            client_sent_at_ms: 1,
+        },
+    ]
+    .into_iter()
+    .map(|message| (String::new(), message))
+    .collect::<Vec<_>>();
+    for connection_info in connection_infos() {
+        let label = serde_json::to_value(&connection_info).unwrap()["type"]
+            .as_str()
+            .map(|tag| format!("/{tag}"))
+            .unwrap();
+        messages.push((
+            label,
+            ClientMessage::ProvideConnectionInfo { connection_info },
+        ));
+    }
+    messages
+}
+
+fn server_messages() -> Vec<ServerMessage> {
+    vec![
+        ServerMessage::Authenticated {
+            app_name: "app".into(),
+            organization: Some("org".into()),
+            rate_limits: RateLimitInfo {
+                per_minute: 60,
+                per_hour: 1000,
+                per_day: 10000,
+            },
+        },
+        ServerMessage::ProtocolInfo(protocol_info()),
+        ServerMessage::AuthenticationError {
+            error: "bad app id".into(),
+            error_code: ErrorCode::InvalidAppId,
+        },
+        ServerMessage::RoomJoined(Box::new(room_joined())),
+        ServerMessage::RoomJoinFailed {
+            reason: "full".into(),
+            error_code: Some(ErrorCode::RoomFull),
+        },
+        ServerMessage::RoomLeft,
+        ServerMessage::PlayerJoined { player: player() },
+        ServerMessage::PlayerLeft {
+            player_id: id(3),
+            epoch: Some(1),
+            final_seq: Some(9),
+        },
+        ServerMessage::GameData {
+            from_player: id(3),
+            data: json!({}),
+            seq: Some(1),
+            epoch: Some(1),
+            class: Some(DeliveryClass::Reliable),
+            key: Some(1),
+        },
+        ServerMessage::GameDataBinary {
+            from_player: id(3),
+            encoding: GameDataEncoding::Rkyv,
+            payload: vec![1, 2],
+            seq: Some(1),
+            epoch: Some(1),
+        },
+        ServerMessage::AuthorityChanged {
+            authority_player: Some(id(1)),
+            you_are_authority: true,
+        },
+        ServerMessage::AuthorityResponse {
+            granted: false,
+            reason: Some("taken".into()),
+            error_code: Some(ErrorCode::AuthorityConflict),
+        },
+        ServerMessage::LobbyStateChanged {
+            lobby_state: LobbyState::Finalized,
+            ready_players: vec![id(1)],
+            all_ready: true,
+        },
+        ServerMessage::GameStarting {
+            peer_connections: vec![PeerConnectionInfo {
+                player_id: id(3),
+                player_name: "bob".into(),
+                is_authority: false,
+                relay_type: "websocket".into(),
+                connection_info: Some(direct()),
+            }],
+        },
+        ServerMessage::Pong,
+        ServerMessage::TimeSyncResponse {
+            client_sent_at_ms: 1,
+            server_time_ms: 2,
+        },
+        ServerMessage::Reconnected(Box::new(reconnected())),
+        ServerMessage::ReconnectionFailed {
+            reason: "expired".into(),
+            error_code: ErrorCode::ReconnectionExpired,
+        },
+        ServerMessage::PlayerReconnected {
+            player_id: id(3),
+            epoch: Some(2),
+        },
+        ServerMessage::SpectatorJoined(Box::new(SpectatorJoinedPayload {
+            room_id: id(10),
+            room_code: "ROOM01".into(),
+            spectator_id: id(2),
+            game_name: "game".into(),
+            current_players: vec![player()],
+            current_spectators: vec![spectator()],
+            lobby_state: LobbyState::Waiting,
+            reason: Some(SpectatorStateChangeReason::Joined),
+        })),
+        ServerMessage::SpectatorJoinFailed {
+            reason: "not allowed".into(),
+            error_code: Some(ErrorCode::SpectatorNotAllowed),
+        },
+        ServerMessage::SpectatorLeft {
+            room_id: Some(id(10)),
+            room_code: Some("ROOM01".into()),
+            reason: Some(SpectatorStateChangeReason::VoluntaryLeave),
+            current_spectators: vec![spectator()],
+        },
+        ServerMessage::NewSpectatorJoined {
+            spectator: spectator(),
+            current_spectators: vec![spectator()],
+            reason: Some(SpectatorStateChangeReason::Joined),
+        },
+        ServerMessage::SpectatorDisconnected {
+            spectator_id: id(2),
+            reason: Some(SpectatorStateChangeReason::Disconnected),
+            current_spectators: vec![spectator()],
+        },
+        ServerMessage::Error {
+            message: "oops".into(),
+            error_code: Some(ErrorCode::InternalError),
+        },
+        ServerMessage::Announcement {
+            message: "hello".into(),
+            severity: Some("info".into()),
+            metadata: Some(json!({})),
+        },
+        ServerMessage::Signal {
+            from: id(3),
+            signal: json!({}),
+        },
+        ServerMessage::NewPeer {
+            peer_id: id(3),
+            you_initiate: true,
+        },
+        ServerMessage::SessionPlan(Box::new(SessionPlanPayload {
+            topology: Topology::Host,
+            transport: TransportKind::WebRtc,
+            host: Some(id(1)),
+            peers: vec![SessionPeer {
+                player_id: id(3),
+                player_name: "bob".into(),
+                is_authority: false,
+                initiate: true,
+            }],
+            ice_servers: vec![ice_server()],
+            fallback: TransportKind::Relay,
+        })),
+        ServerMessage::PeerTransportStatus {
+            peer_id: id(3),
+            transport: TransportKind::WebRtc,
+            connected: true,
+        },
+        ServerMessage::RelayStats {
+            interval_ms: 1000,
+            sent_to_you: 1,
+            dropped_for_you: 0,
+            backpressure_events: 0,
+        },
+        ServerMessage::GoingAway {
+            deadline_ms: 1000,
+            retry_after_secs: Some(5),
+        },
+        ServerMessage::DeliveryReport(Box::new(DeliveryReportPayload {
+            per_class: DeliveryCountersByClass::default(),
+            gaps: vec![DeliveryGap {
+                from_player: id(3),
+                epoch: 1,
+                from_seq: 2,
+                to_seq: 3,
+                reason: DeliveryGapReason::LatestSuperseded,
+            }],
+        })),
+    ]
+}
+
+// ── Snapshot ────────────────────────────────────────────────────────
+
+/// Message lines keyed `Enum::Tag[/detail]`, checked to cover every variant
+/// serde knows for the enum.
+fn message_lines<T: serde::Serialize + DeserializeOwned + std::fmt::Debug>(
+    enum_name: &str,
+    messages: Vec<(String, T)>,
+) -> Vec<String> {
+    let mut covered = BTreeSet::new();
+    let mut lines = Vec::new();
+    for (detail, message) in messages {
+        let value = serde_json::to_value(&message).unwrap();
+        let tag = value["type"].as_str().unwrap().to_owned();
+        lines.push(format!(
+            "{enum_name}::{tag}{detail} {}",
+            render(&erase(&value))
+        ));
+        covered.insert(tag);
+    }
+    let missing: Vec<String> = wire_names::<T>(tag_probe())
+        .into_iter()
+        .filter(|name| !covered.contains(name))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "{enum_name} variants without a canonical instance in {}: {missing:?}",
+        file!()
+    );
+    lines
+}
+
+fn enum_line<T: DeserializeOwned + std::fmt::Debug>(name: &str, probe: Value) -> String {
+    format!("{name} {}", wire_names::<T>(probe).join(" "))
+}
+
+/// The wire names of an enum with a `#[serde(other)]` catch-all, which
+/// accepts any string and so never reports its names: they come from
+/// serializing `named` instead, and `*` marks the catch-all the probe lands on.
+fn catch_all_line<T>(name: &str, named: &[T], other: T) -> String
+where
+    T: serde::Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    assert_eq!(serde_json::from_value::<T>(string_probe()).unwrap(), other);
+    let names: Vec<String> = named
+        .iter()
+        .map(|variant| {
+            serde_json::to_value(variant)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect();
+    format!("{name} {} *", names.join(" "))
+}
+
+/// Every named variant of the catch-all enums. The matches have no wildcard,
+/// so a new variant fails to compile here until it is listed.
+fn relay_transports() -> Vec<RelayTransport> {
+    let all = vec![
+        RelayTransport::Tcp,
+        RelayTransport::Udp,
+        RelayTransport::Websocket,
+        RelayTransport::Auto,
+    ];
+    for variant in &all {
+        match variant {
+            RelayTransport::Tcp
+            | RelayTransport::Udp
+            | RelayTransport::Websocket
+            | RelayTransport::Auto
+            | RelayTransport::Other => {}
+        }
+    }
+    all
+}
+
+fn game_data_encodings() -> Vec<GameDataEncoding> {
+    let all = vec![
+        GameDataEncoding::Json,
+        GameDataEncoding::MessagePack,
+        GameDataEncoding::Rkyv,
+    ];
+    for variant in &all {
+        match variant {
+            GameDataEncoding::Json
+            | GameDataEncoding::MessagePack
+            | GameDataEncoding::Rkyv
+            | GameDataEncoding::Other => {}
+        }
+    }
+    all
+}
+
+fn spectator_state_change_reasons() -> Vec<SpectatorStateChangeReason> {
+    let all = vec![
+        SpectatorStateChangeReason::Joined,
+        SpectatorStateChangeReason::VoluntaryLeave,
+        SpectatorStateChangeReason::Disconnected,
+        SpectatorStateChangeReason::Removed,
+        SpectatorStateChangeReason::RoomClosed,
+    ];
+    for variant in &all {
+        match variant {
+            SpectatorStateChangeReason::Joined
+            | SpectatorStateChangeReason::VoluntaryLeave
+            | SpectatorStateChangeReason::Disconnected
+            | SpectatorStateChangeReason::Removed
+            | SpectatorStateChangeReason::RoomClosed
+            | SpectatorStateChangeReason::Other => {}
+        }
+    }
+    all
+}
+
+fn lobby_states() -> Vec<LobbyState> {
+    let all = vec![
+        LobbyState::Waiting,
+        LobbyState::Lobby,
+        LobbyState::Finalized,
+    ];
+    for variant in &all {
+        match variant {
+            LobbyState::Waiting | LobbyState::Lobby | LobbyState::Finalized | LobbyState::Other => {
+            }
+        }
+    }
+    all
+}
+
+/// The surface lines the digest covers.
+fn surface() -> Vec<String> {
+    let mut lines = vec![
+        enum_line::<ClientMessage>("ClientMessage", tag_probe()),
+        enum_line::<ServerMessage>("ServerMessage", tag_probe()),
+        enum_line::<ConnectionInfo>("ConnectionInfo", tag_probe()),
+        catch_all_line("RelayTransport", &relay_transports(), RelayTransport::Other),
+        catch_all_line(
+            "GameDataEncoding",
+            &game_data_encodings(),
+            GameDataEncoding::Other,
+        ),
+        enum_line::<DeliveryClass>("DeliveryClass", string_probe()),
+        enum_line::<DeliveryGapReason>("DeliveryGapReason", string_probe()),
+        enum_line::<ReplayStatus>("ReplayStatus", string_probe()),
+        enum_line::<Topology>("Topology", string_probe()),
+        enum_line::<TransportKind>("TransportKind", string_probe()),
+        enum_line::<MessageTransport>("MessageTransport", string_probe()),
+        catch_all_line(
+            "SpectatorStateChangeReason",
+            &spectator_state_change_reasons(),
+            SpectatorStateChangeReason::Other,
+        ),
+        catch_all_line("LobbyState", &lobby_states(), LobbyState::Other),
+    ];
+    lines.extend(message_lines("ClientMessage", client_messages()));
+    lines.extend(message_lines(
+        "ServerMessage",
+        server_messages()
+            .into_iter()
+            .map(|message| (String::new(), message))
+            .collect(),
+    ));
+    lines
+}
+
+fn digest(lines: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for line in lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize()[..8]
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+fn rendered_snapshot() -> (String, String) {
+    let lines = surface();
+    let digest = digest(&lines);
+    let mut out = format!("{HEADER}digest {digest}\n\n");
+    for line in &lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+    (out, digest)
+}
+
+#[test]
+fn protocol_surface_matches_snapshot() {
+    let (rendered, _) = rendered_snapshot();
+    let path = snapshot_path();
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::write(&path, &rendered)
+            .unwrap_or_else(|e| panic!("write {}: {e}", path.display()));
+        return;
+    }
+    let recorded =
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {e}", path.display()));
+    if recorded == rendered {
+        return;
+    }
+    let recorded_lines: BTreeSet<&str> = recorded.lines().collect();
+    let rendered_lines: BTreeSet<&str> = rendered.lines().collect();
+    let mut diff = String::new();
+    for line in recorded_lines.difference(&rendered_lines) {
+        let _ = writeln!(diff, "- {line}");
+    }
+    for line in rendered_lines.difference(&recorded_lines) {
+        let _ = writeln!(diff, "+ {line}");
+    }
+    panic!(
+        "the protocol wire surface changed:\n{diff}\n\
+         If the change is intended, regenerate {SNAPSHOT} with \
+         `{UPDATE_ENV}=1 cargo test --test protocol_surface_tests` and quote the \
+         new digest in CHANGELOG.md."
+    );
+}
+
+#[test]
+fn changelog_quotes_the_protocol_surface_digest() {
+    let (_, digest) = rendered_snapshot();
+    let changelog =
+        std::fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("CHANGELOG.md"))
+            .unwrap_or_else(|e| panic!("read CHANGELOG.md: {e}"));
+    assert!(
+        changelog.contains(&format!("`{digest}`")),
+        "CHANGELOG.md does not mention protocol surface digest `{digest}`. A wire \
+         change needs a changelog entry that quotes the digest from {SNAPSHOT}."
+    );
+}
+
+#[test]
+fn erasure_keeps_tags_and_sorts_keys() {
+    let value = json!({
+        "type": "Relay",
+        "b": [1, 2, 3.5, "x"],
+        "a": { "type": 7, "nested": null },
+    });
+    assert_eq!(
+        render(&erase(&value)),
+        r#"{"a":{"nested":"null","type":"integer"},"b":["integer","number","string"],"type":"Relay"}"#
+    );
+}
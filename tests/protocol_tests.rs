@@ -16,8 +16,9 @@ use signal_fish_client::error_codes::ErrorCode;
 use signal_fish_client::protocol::{
     ClientMessage, ConnectionInfo, GameDataEncoding, IceServer, LobbyState, PeerConnectionInfo,
     PlayerInfo, PlayerNameRulesPayload, ProtocolInfoPayload, RateLimitInfo, ReconnectedPayload,
-    RelayTransport, RoomJoinedPayload, ServerMessage, SessionPeer, SessionPlanPayload,
-    SpectatorInfo, SpectatorJoinedPayload, SpectatorStateChangeReason, Topology, TransportKind,
+    RelayTransport, RoomJoinedPayload, ServerLimits, ServerMessage, SessionPeer,
+    SessionPlanPayload, SpectatorInfo, SpectatorJoinedPayload, SpectatorStateChangeReason,
+    Topology, TransportKind,
 };
 use signal_fish_client::PeerSignal;
 
@@ -300,6 +301,7 @@ fn server_message_protocol_info_round_trip() {
         min_protocol_version: None,
         max_protocol_version: None,
         transports: None,
+        limits: None,
     });
     let deser = round_trip(&msg);
     if let ServerMessage::ProtocolInfo(payload) = deser {
@@ -608,7 +610,7 @@ fn server_message_reconnected_round_trip() {
         relay_type: "udp".into(),
         current_spectators: vec![],
         ice_servers: vec![],
-        missed_events: vec![ServerMessage::Pong],
+        missed_events: vec![ServerMessage::Pong.into()],
         replay: None,
         sender_watermarks: vec![],
         reconnection_token: None,
@@ -619,7 +621,7 @@ fn server_message_reconnected_round_trip() {
         assert_eq!(p.room_code, "RECON2");
         assert_eq!(p.max_players, 8);
         assert_eq!(p.missed_events.len(), 1);
-        assert!(matches!(p.missed_events[0], ServerMessage::Pong));
+        assert!(matches!(p.missed_events[0].message, ServerMessage::Pong));
     } else {
         panic!("expected Reconnected variant");
     }
@@ -796,6 +798,73 @@ fn server_message_error_round_trip() {
     }
 }
 
+#[test]
+fn server_message_announcement_round_trip() {
+    let msg = ServerMessage::Announcement {
+        message: "Finals start in 10 minutes".into(),
+        severity: Some("warning".into()),
+        metadata: Some(serde_json::json!({ "tournament": "spring-cup" })),
+    };
+    let deser = round_trip(&msg);
+    if let ServerMessage::Announcement {
+        message,
+        severity,
+        metadata,
+    } = deser
+    {
+        assert_eq!(message, "Finals start in 10 minutes");
+        assert_eq!(severity.as_deref(), Some("warning"));
+        assert_eq!(
+            metadata,
+            Some(serde_json::json!({ "tournament": "spring-cup" }))
+        );
+    } else {
+        panic!("expected Announcement variant");
+    }
+}
+
+#[test]
+fn time_sync_messages_match_the_wire_shape() {
+    let request = ClientMessage::TimeSyncRequest {
+        client_sent_at_ms: 1_700_000_000_123,
+    };
+    assert_eq!(
+        serde_json::to_string(&request).unwrap(),
+        r#"{"type":"TimeSyncRequest","data":{"client_sent_at_ms":1700000000123}}"#
+    );
+
+    let json = r#"{"type":"TimeSyncResponse","data":{"client_sent_at_ms":1700000000123,"server_time_ms":1700000005000}}"#;
+    let msg: ServerMessage = serde_json::from_str(json).expect("deserialize");
+    let ServerMessage::TimeSyncResponse {
+        client_sent_at_ms,
+        server_time_ms,
+    } = msg
+    else {
+        panic!("expected TimeSyncResponse, got {msg:?}");
+    };
+    assert_eq!(client_sent_at_ms, 1_700_000_000_123);
+    assert_eq!(server_time_ms, 1_700_000_005_000);
+    assert_eq!(serde_json::to_string(&msg).unwrap(), json);
+}
+
+#[test]
+fn server_message_announcement_optional_fields_may_be_omitted() {
+    let json = r#"{"type":"Announcement","data":{"message":"Welcome!"}}"#;
+    let msg: ServerMessage = serde_json::from_str(json).expect("deserialize");
+    let ServerMessage::Announcement {
+        message,
+        severity,
+        metadata,
+    } = &msg
+    else {
+        panic!("expected Announcement, got {msg:?}");
+    };
+    assert_eq!(message, "Welcome!");
+    assert_eq!(*severity, None);
+    assert_eq!(*metadata, None);
+    assert_eq!(serde_json::to_string(&msg).unwrap(), json);
+}
+
 // ════════════════════════════════════════════════════════════════════
 // ErrorCode serialization (SCREAMING_SNAKE_CASE)
 // ════════════════════════════════════════════════════════════════════
@@ -928,6 +997,81 @@ fn delivery_liveness_error_codes_serialize_exact_strings() {
     }
 }
 
+#[test]
+fn unknown_error_code_keeps_its_wire_token() {
+    let code: ErrorCode = serde_json::from_str(r#""FUTURE_CODE_XYZ""#).expect("deserialize");
+    assert_eq!(code, ErrorCode::Unknown("FUTURE_CODE_XYZ".to_string()));
+    assert_eq!(code.key(), "FUTURE_CODE_XYZ");
+    assert!(!code.description().is_empty());
+    assert_eq!(
+        serde_json::to_string(&code).expect("serialize"),
+        r#""FUTURE_CODE_XYZ""#
+    );
+    assert_eq!(round_trip(&code), code);
+    assert!(!ErrorCode::all().contains(&code));
+}
+
+#[test]
+fn known_error_codes_never_decode_as_unknown() {
+    for code in ErrorCode::all() {
+        let json = serde_json::to_string(code).expect("serialize");
+        let deser: ErrorCode = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(&deser, code);
+    }
+    assert!(serde_json::from_str::<ErrorCode>("404").is_err());
+}
+
+#[test]
+fn error_with_unknown_code_still_decodes() {
+    let json = r#"{"type":"Error","data":{"message":"evicted","error_code":"FUTURE_CODE_XYZ"}}"#;
+    let msg: ServerMessage = serde_json::from_str(json).expect("deserialize");
+    match msg {
+        ServerMessage::Error {
+            message,
+            error_code,
+        } => {
+            assert_eq!(message, "evicted");
+            assert_eq!(
+                error_code,
+                Some(ErrorCode::Unknown("FUTURE_CODE_XYZ".to_string()))
+            );
+        }
+        other => panic!("expected ServerMessage::Error, got {other:?}"),
+    }
+}
+
+#[test]
+fn fixture_future_error_code_in_every_error_message() {
+    let future = || ErrorCode::Unknown("SOME_FUTURE_CODE".to_string());
+    let fixtures = [
+        r#"{"type":"AuthenticationError","data":{"error":"e","error_code":"SOME_FUTURE_CODE"}}"#,
+        r#"{"type":"RoomJoinFailed","data":{"reason":"r","error_code":"SOME_FUTURE_CODE"}}"#,
+        r#"{"type":"ReconnectionFailed","data":{"reason":"r","error_code":"SOME_FUTURE_CODE"}}"#,
+        r#"{"type":"SpectatorJoinFailed","data":{"reason":"r","error_code":"SOME_FUTURE_CODE"}}"#,
+        r#"{"type":"Error","data":{"message":"m","error_code":"SOME_FUTURE_CODE"}}"#,
+    ];
+    for json in fixtures {
+        let msg: ServerMessage = serde_json::from_str(json)
+            .unwrap_or_else(|error| panic!("{json} must deserialize: {error}"));
+        let code = match &msg {
+            ServerMessage::AuthenticationError { error_code, .. }
+            | ServerMessage::ReconnectionFailed { error_code, .. } => Some(error_code.clone()),
+            ServerMessage::RoomJoinFailed { error_code, .. }
+            | ServerMessage::SpectatorJoinFailed { error_code, .. }
+            | ServerMessage::Error { error_code, .. } => error_code.clone(),
+            other => panic!("unexpected message {other:?}"),
+        };
+        assert_eq!(code, Some(future()), "{json}");
+        assert_eq!(
+            serde_json::to_value(&msg).expect("serialize"),
+            serde_json::from_str::<serde_json::Value>(json).expect("fixture JSON"),
+            "{json} must re-serialize unchanged"
+        );
+    }
+    assert_ne!(future(), ErrorCode::RoomFull);
+    assert!(future().description().contains("does not recognize"));
+}
+
 #[test]
 fn fixture_error_with_slow_consumer_code_from_server() {
     // The exact farewell frame the server (851c446) writes best-effort before
@@ -1155,11 +1299,13 @@ fn reconnected_missed_events_carry_v3_messages() {
             ServerMessage::NewPeer {
                 peer_id: test_uuid(2),
                 you_initiate: true,
-            },
+            }
+            .into(),
             ServerMessage::Signal {
                 from: test_uuid(2),
                 signal: serde_json::json!({ "Offer": "SDP" }),
-            },
+            }
+            .into(),
         ],
         replay: None,
         sender_watermarks: vec![],
@@ -1169,13 +1315,16 @@ fn reconnected_missed_events_carry_v3_messages() {
     if let ServerMessage::Reconnected(p) = round_trip(&msg) {
         assert_eq!(p.missed_events.len(), 2);
         assert!(matches!(
-            p.missed_events[0],
+            p.missed_events[0].message,
             ServerMessage::NewPeer {
                 you_initiate: true,
                 ..
             }
         ));
-        assert!(matches!(p.missed_events[1], ServerMessage::Signal { .. }));
+        assert!(matches!(
+            p.missed_events[1].message,
+            ServerMessage::Signal { .. }
+        ));
     } else {
         panic!("expected Reconnected variant");
     }
@@ -1364,6 +1513,7 @@ fn protocol_info_v2_negotiation_omits_version_fields() {
         min_protocol_version: None,
         max_protocol_version: None,
         transports: None,
+        limits: None,
     };
     let json = serde_json::to_string(&payload).expect("ser");
     assert!(!json.contains("protocol_version"), "{json}");
@@ -1380,6 +1530,32 @@ fn protocol_info_v2_json_without_version_fields_deserializes() {
     assert_eq!(payload.capabilities, vec!["authority".to_string()]);
 }
 
+#[test]
+fn protocol_info_limits_are_optional() {
+    let without = r#"{"capabilities":[],"game_data_formats":["json"]}"#;
+    let payload: ProtocolInfoPayload = serde_json::from_str(without).expect("deser");
+    assert!(payload.limits.is_none());
+    assert!(!serde_json::to_string(&payload)
+        .expect("ser")
+        .contains("limits"));
+
+    let partial = r#"{"capabilities":[],"game_data_formats":["json"],"limits":{"max_players_per_room":8,"max_tables":3}}"#;
+    let payload: ProtocolInfoPayload = serde_json::from_str(partial).expect("deser");
+    assert_eq!(
+        payload.limits,
+        Some(ServerLimits {
+            max_rooms_per_game: None,
+            max_players_per_room: Some(8),
+            max_spectators: None,
+        })
+    );
+    let json = serde_json::to_string(&payload).expect("ser");
+    assert!(
+        json.contains(r#""limits":{"max_players_per_room":8}"#),
+        "{json}"
+    );
+}
+
 #[test]
 fn protocol_info_v3_negotiation_surfaces_versions() {
     let v3 = r#"{"capabilities":[],"game_data_formats":[],"protocol_version":3,"min_protocol_version":2,"max_protocol_version":3}"#;
@@ -1466,6 +1642,180 @@ fn signal_payload_round_trips_as_opaque_value() {
     }
 }
 
+/// Every `ClientMessage` variant with all optional fields unset.
+///
+/// The exhaustive match makes adding a variant a compile error until it is
+/// listed here, so the null-omission audit below can never fall behind.
+fn client_messages_with_unset_options() -> Vec<ClientMessage> {
+    let msgs = vec![
+        ClientMessage::Authenticate {
+            app_id: "mb_app".into(),
+            sdk_version: None,
+            platform: None,
+            game_data_format: None,
+            protocol_version: None,
+            supported_transports: None,
+            supported_topologies: None,
+        },
+        ClientMessage::JoinRoom {
+            game_name: "g".into(),
+            room_code: None,
+            player_name: "p".into(),
+            max_players: None,
+            supports_authority: None,
+            relay_transport: None,
+        },
+        ClientMessage::LeaveRoom,
+        ClientMessage::GameData {
+            data: serde_json::json!({ "x": 1 }),
+            class: None,
+            key: None,
+        },
+        ClientMessage::AuthorityRequest {
+            become_authority: true,
+        },
+        ClientMessage::PlayerReady,
+        ClientMessage::ProvideConnectionInfo {
+            connection_info: ConnectionInfo::WebRTC {
+                sdp: None,
+                ice_candidates: vec![],
+            },
+        },
+        ClientMessage::ProvideConnectionInfo {
+            connection_info: ConnectionInfo::Relay {
+                host: "relay".into(),
+                port: 7777,
+                transport: RelayTransport::Auto,
+                allocation_id: "a".into(),
+                token: "t".into(),
+                client_id: None,
+            },
+        },
+        ClientMessage::Ping,
+        ClientMessage::Reconnect {
+            player_id: nil_uuid(),
+            room_id: nil_uuid(),
+            auth_token: "t".into(),
+        },
+        ClientMessage::JoinAsSpectator {
+            game_name: "g".into(),
+            room_code: "R".into(),
+            spectator_name: "s".into(),
+        },
+        ClientMessage::LeaveSpectator,
+        ClientMessage::StartGame,
+        ClientMessage::Signal {
+            to: nil_uuid(),
+            signal: serde_json::json!({ "Offer": "sdp" }),
+        },
+        ClientMessage::TransportStatus {
+            transport: TransportKind::Relay,
+            connected: false,
+        },
+        ClientMessage::TimeSyncRequest {
+            client_sent_at_ms: 0,
+        },
+    ];
+    for msg in &msgs {
+        match msg {
+            ClientMessage::Authenticate { .. }
+            | ClientMessage::JoinRoom { .. }
+            | ClientMessage::LeaveRoom
+            | ClientMessage::GameData { .. }
+            | ClientMessage::AuthorityRequest { .. }
+            | ClientMessage::PlayerReady
+            | ClientMessage::ProvideConnectionInfo { .. }
+            | ClientMessage::Ping
+            | ClientMessage::Reconnect { .. }
+            | ClientMessage::JoinAsSpectator { .. }
+            | ClientMessage::LeaveSpectator
+            | ClientMessage::StartGame
+            | ClientMessage::Signal { .. }
+            | ClientMessage::TransportStatus { .. }
+            | ClientMessage::TimeSyncRequest { .. } => {}
+        }
+    }
+    msgs
+}
+
+#[test]
+fn client_messages_never_serialize_null_for_unset_options() {
+    for msg in client_messages_with_unset_options() {
+        let json = serde_json::to_string(&msg).expect("ser");
+        assert!(
+            !json.contains("null"),
+            "unset option leaked as null: {json}"
+        );
+    }
+}
+
+#[test]
+fn join_room_omits_unset_options() {
+    let msg = ClientMessage::JoinRoom {
+        game_name: "g".into(),
+        room_code: None,
+        player_name: "p".into(),
+        max_players: None,
+        supports_authority: None,
+        relay_transport: None,
+    };
+    let val: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&msg).expect("ser")).expect("parse");
+    assert_eq!(
+        val["data"],
+        serde_json::json!({ "game_name": "g", "player_name": "p" })
+    );
+}
+
+#[test]
+fn client_messages_accept_explicit_null_options_inbound() {
+    // Older peers and hand-written fixtures send `null`; inbound parsing stays lenient.
+    let json = r#"{"type":"JoinRoom","data":{"game_name":"g","room_code":null,"player_name":"p","max_players":null,"supports_authority":null,"relay_transport":null}}"#;
+    let msg: ClientMessage = serde_json::from_str(json).expect("deser");
+    if let ClientMessage::JoinRoom {
+        room_code,
+        max_players,
+        supports_authority,
+        relay_transport,
+        ..
+    } = msg
+    {
+        assert!(room_code.is_none());
+        assert!(max_players.is_none());
+        assert!(supports_authority.is_none());
+        assert!(relay_transport.is_none());
+    } else {
+        panic!("expected JoinRoom variant");
+    }
+
+    let json = r#"{"type":"Authenticate","data":{"app_id":"mb_app","sdk_version":null,"platform":null,"game_data_format":null}}"#;
+    let msg: ClientMessage = serde_json::from_str(json).expect("deser");
+    assert!(matches!(
+        msg,
+        ClientMessage::Authenticate {
+            sdk_version: None,
+            platform: None,
+            game_data_format: None,
+            ..
+        }
+    ));
+
+    let json = r#"{"type":"webrtc","sdp":null,"ice_candidates":[]}"#;
+    let info: ConnectionInfo = serde_json::from_str(json).expect("deser");
+    assert!(matches!(info, ConnectionInfo::WebRTC { sdp: None, .. }));
+
+    let json =
+        r#"{"type":"relay","host":"h","port":1,"allocation_id":"a","token":"t","client_id":null}"#;
+    let info: ConnectionInfo = serde_json::from_str(json).expect("deser");
+    assert!(matches!(
+        info,
+        ConnectionInfo::Relay {
+            client_id: None,
+            ..
+        }
+    ));
+}
+
 // ════════════════════════════════════════════════════════════════════
 // Server JSON fixture tests (simulate real server JSON)
 // ════════════════════════════════════════════════════════════════════
@@ -1689,7 +2039,7 @@ fn fixture_reconnected_from_server() {
         assert_eq!(p.ready_players.len(), 1);
         assert_eq!(p.missed_events.len(), 1);
         assert!(matches!(
-            &p.missed_events[0],
+            &p.missed_events[0].message,
             ServerMessage::PlayerLeft { player_id: pid, .. } if *pid == missed_player_id
         ));
     } else {
@@ -2122,6 +2472,68 @@ fn spectator_state_change_reason_serde_all() {
     }
 }
 
+// ════════════════════════════════════════════════════════════════════
+// Catch-all variants for values from a newer server
+// ════════════════════════════════════════════════════════════════════
+
+#[test]
+fn unknown_enum_strings_decode_to_other() {
+    let unknown = r#""from_a_newer_server""#;
+    assert_eq!(
+        serde_json::from_str::<LobbyState>(unknown).expect("lobby state"),
+        LobbyState::Other
+    );
+    assert_eq!(
+        serde_json::from_str::<RelayTransport>(unknown).expect("relay transport"),
+        RelayTransport::Other
+    );
+    assert_eq!(
+        serde_json::from_str::<SpectatorStateChangeReason>(unknown).expect("spectator reason"),
+        SpectatorStateChangeReason::Other
+    );
+    assert_eq!(
+        serde_json::from_str::<GameDataEncoding>(unknown).expect("encoding"),
+        GameDataEncoding::Other
+    );
+}
+
+#[test]
+fn catch_all_variants_serialize_as_other_and_round_trip() {
+    assert_eq!(
+        serde_json::to_string(&LobbyState::Other).expect("ser"),
+        r#""other""#
+    );
+    assert_eq!(round_trip(&LobbyState::Other), LobbyState::Other);
+    assert_eq!(round_trip(&RelayTransport::Other), RelayTransport::Other);
+    assert_eq!(
+        round_trip(&SpectatorStateChangeReason::Other),
+        SpectatorStateChangeReason::Other
+    );
+    assert_eq!(
+        round_trip(&GameDataEncoding::Other),
+        GameDataEncoding::Other
+    );
+}
+
+#[test]
+fn catch_all_variants_only_take_strings() {
+    assert!(serde_json::from_str::<LobbyState>("3").is_err());
+    assert!(serde_json::from_str::<RelayTransport>("null").is_err());
+}
+
+#[test]
+fn lobby_state_changed_with_unknown_state_still_decodes() {
+    let json = r#"{"type":"LobbyStateChanged","data":{"lobby_state":"countdown","ready_players":[],"all_ready":false}}"#;
+    let msg: ServerMessage = serde_json::from_str(json).expect("deserialize");
+    assert!(matches!(
+        msg,
+        ServerMessage::LobbyStateChanged {
+            lobby_state: LobbyState::Other,
+            ..
+        }
+    ));
+}
+
 // ════════════════════════════════════════════════════════════════════
 // Struct serde tests: PlayerInfo, SpectatorInfo, PeerConnectionInfo
 // ════════════════════════════════════════════════════════════════════
@@ -2416,6 +2828,7 @@ fn protocol_info_payload_round_trip_minimal() {
         min_protocol_version: None,
         max_protocol_version: None,
         transports: None,
+        limits: None,
     };
     let deser = round_trip(&payload);
     assert!(deser.platform.is_none());
@@ -2459,3 +2872,288 @@ fn error_code_display_returns_description() {
     let display = format!("{code}");
     assert_eq!(display, code.description());
 }
+
+// ════════════════════════════════════════════════════════════════════
+// Number precision in GameData
+// ════════════════════════════════════════════════════════════════════
+
+const PRECISION_PLAYER: &str = "00000000-0000-0000-0000-000000000001";
+
+fn game_data_frame(data: &str, data_first: bool) -> String {
+    let body = format!(r#"{{"from_player":"{PRECISION_PLAYER}","data":{data},"seq":5,"epoch":1}}"#);
+    if data_first {
+        format!(r#"{{"data":{body},"type":"GameData"}}"#)
+    } else {
+        format!(r#"{{"type":"GameData","data":{body}}}"#)
+    }
+}
+
+fn received_game_data(frame: &str) -> serde_json::Value {
+    match serde_json::from_str::<ServerMessage>(frame).expect("decode GameData") {
+        ServerMessage::GameData { data, seq, .. } => {
+            assert_eq!(seq, Some(5), "numeric envelope fields must still decode");
+            data
+        }
+        other => panic!("expected GameData, got {other:?}"),
+    }
+}
+
+#[test]
+fn game_data_preserves_full_64_bit_integers() {
+    let payload = r#"{"id":18446744073709551615,"min":-9223372036854775808}"#;
+    for data_first in [false, true] {
+        let data = received_game_data(&game_data_frame(payload, data_first));
+        assert_eq!(data["id"].as_u64(), Some(u64::MAX));
+        assert_eq!(data["min"].as_i64(), Some(i64::MIN));
+        assert_eq!(serde_json::to_string(&data).unwrap(), payload);
+    }
+}
+
+#[cfg(feature = "arbitrary-precision")]
+#[test]
+fn arbitrary_precision_preserves_wide_integers_and_long_decimals() {
+    let payload = r#"{"big":170141183460469231731687303715884105727,"pi":3.14159265358979323846264338327950288}"#;
+    for data_first in [false, true] {
+        let data = received_game_data(&game_data_frame(payload, data_first));
+        assert_eq!(serde_json::to_string(&data).unwrap(), payload);
+    }
+
+    let outbound = ClientMessage::GameData {
+        data: serde_json::from_str(payload).unwrap(),
+        class: None,
+        key: None,
+    };
+    let wire = serde_json::to_string(&outbound).unwrap();
+    assert!(
+        wire.contains(payload),
+        "outbound GameData lost precision: {wire}"
+    );
+}
+
+#[cfg(feature = "arbitrary-precision")]
+#[test]
+fn arbitrary_precision_keeps_typed_signal_decoding_working() {
+    // Typed helpers decode from the received Value and must keep working when
+    // the feature changes how Value represents numbers.
+    let frame = format!(
+        r#"{{"type":"Signal","data":{{"from":"{PRECISION_PLAYER}","signal":{{"IceCandidate":"candidate:1 1 udp 2130706431 10.0.0.1 5000 typ host"}}}}}}"#
+    );
+    let ServerMessage::Signal { signal, .. } = serde_json::from_str(&frame).unwrap() else {
+        panic!("expected Signal");
+    };
+    assert!(matches!(
+        PeerSignal::try_from(&signal),
+        Ok(PeerSignal::IceCandidate(_))
+    ));
+}
+
+#[cfg(not(feature = "arbitrary-precision"))]
+#[test]
+fn default_build_rounds_decimals_beyond_f64_precision() {
+    // Documented policy: without `arbitrary-precision`, non-integer numbers are
+    // f64. Opt into the feature when game data carries exact decimals.
+    let payload = r#"{"pi":3.14159265358979323846264338327950288}"#;
+    let data = received_game_data(&game_data_frame(payload, false));
+    assert_eq!(
+        serde_json::to_string(&data).unwrap(),
+        r#"{"pi":3.141592653589793}"#
+    );
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Reconnected missed-event sequence numbers
+// ════════════════════════════════════════════════════════════════════
+
+fn reconnected_with_missed_json(missed: &str) -> String {
+    format!(
+        r#"{{"type":"Reconnected","data":{{"room_id":"{PRECISION_PLAYER}","room_code":"SEQ","player_id":"{PRECISION_PLAYER}","game_name":"g","max_players":4,"supports_authority":false,"current_players":[],"is_authority":false,"lobby_state":"waiting","ready_players":[],"relay_type":"tcp","current_spectators":[],"missed_events":{missed}}}}}"#
+    )
+}
+
+fn decode_reconnected(missed: &str) -> ReconnectedPayload {
+    match serde_json::from_str(&reconnected_with_missed_json(missed)).expect("decode") {
+        ServerMessage::Reconnected(payload) => *payload,
+        other => panic!("expected Reconnected, got {other:?}"),
+    }
+}
+
+fn sequenced(seqs: &[u64]) -> ReconnectedPayload {
+    let missed: Vec<String> = seqs
+        .iter()
+        .map(|seq| format!(r#"{{"type":"Pong","seq":{seq}}}"#))
+        .collect();
+    decode_reconnected(&format!("[{}]", missed.join(",")))
+}
+
+#[test]
+fn bare_missed_events_decode_without_seq_and_reserialize_unchanged() {
+    let missed =
+        format!(r#"[{{"type":"PlayerLeft","data":{{"player_id":"{PRECISION_PLAYER}"}}}}]"#);
+    let json = reconnected_with_missed_json(&missed);
+    let payload = decode_reconnected(&missed);
+    assert_eq!(payload.missed_events.len(), 1);
+    assert_eq!(payload.missed_events[0].seq, None);
+    assert!(matches!(
+        payload.missed_events[0].message,
+        ServerMessage::PlayerLeft { .. }
+    ));
+    let msg: ServerMessage = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&msg).unwrap(), json);
+}
+
+#[test]
+fn sequenced_missed_events_decode_with_seq_in_any_position() {
+    let missed = format!(
+        r#"[{{"seq":3,"type":"Pong"}},{{"type":"PlayerLeft","seq":4,"data":{{"player_id":"{PRECISION_PLAYER}"}}}},{{"type":"Pong","data":null,"seq":5}}]"#
+    );
+    let payload = decode_reconnected(&missed);
+    let seqs: Vec<_> = payload.missed_events.iter().map(|e| e.seq).collect();
+    assert_eq!(seqs, [Some(3), Some(4), Some(5)]);
+    assert!(matches!(
+        payload.missed_events[1].message,
+        ServerMessage::PlayerLeft { .. }
+    ));
+}
+
+#[test]
+fn sequenced_missed_events_round_trip_with_seq_beside_the_tag() {
+    let payload = sequenced(&[7]);
+    let wire = serde_json::to_string(&payload.missed_events[0]).unwrap();
+    assert_eq!(wire, r#"{"type":"Pong","seq":7}"#);
+    let msg = ServerMessage::Reconnected(Box::new(payload));
+    let ServerMessage::Reconnected(back) = round_trip(&msg) else {
+        panic!("expected Reconnected");
+    };
+    assert_eq!(back.missed_events[0].seq, Some(7));
+}
+
+#[test]
+fn sequenced_missed_game_data_keeps_full_precision() {
+    let payload = r#"{"id":18446744073709551615}"#;
+    let missed = format!(
+        r#"[{{"type":"GameData","seq":9,"data":{{"from_player":"{PRECISION_PLAYER}","data":{payload}}}}}]"#
+    );
+    let reconnected = decode_reconnected(&missed);
+    let ServerMessage::GameData { data, .. } = &reconnected.missed_events[0].message else {
+        panic!("expected GameData");
+    };
+    assert_eq!(serde_json::to_string(data).unwrap(), payload);
+}
+
+#[test]
+fn missing_ranges_reports_each_gap_inclusively() {
+    assert!(sequenced(&[]).missing_ranges().is_empty());
+    assert!(sequenced(&[4]).missing_ranges().is_empty());
+    assert!(sequenced(&[4, 5, 6]).missing_ranges().is_empty());
+    assert_eq!(sequenced(&[4, 6]).missing_ranges(), vec![5..=5]);
+    assert_eq!(
+        sequenced(&[1, 2, 5, 6, 10]).missing_ranges(),
+        vec![3..=4, 7..=9]
+    );
+}
+
+#[test]
+fn missing_ranges_ignores_unsequenced_duplicate_and_reordered_entries() {
+    assert_eq!(
+        sequenced(&[6, 4, 4, 2]).missing_ranges(),
+        vec![3..=3, 5..=5]
+    );
+
+    let mixed =
+        decode_reconnected(r#"[{"type":"Pong","seq":1},{"type":"Pong"},{"type":"Pong","seq":2}]"#);
+    assert!(mixed.missing_ranges().is_empty());
+
+    assert!(decode_reconnected(r#"[{"type":"Pong"},{"type":"Pong"}]"#)
+        .missing_ranges()
+        .is_empty());
+}
+
+// ════════════════════════════════════════════════════════════════════
+// Out-of-range numeric fields
+// ════════════════════════════════════════════════════════════════════
+
+fn room_joined_with_max_players(max_players: &str) -> String {
+    signal_fish_client::testing::room_joined("BIG")
+        .max_players(8)
+        .json()
+        .replace(
+            r#""max_players":8"#,
+            &format!(r#""max_players":{max_players}"#),
+        )
+}
+
+fn player_joined_with_port(port: &str) -> String {
+    format!(
+        r#"{{"type":"PlayerJoined","data":{{"player":{{"id":"{id}","name":"p","is_authority":false,"is_ready":true,"connected_at":"2026-01-01T00:00:00Z","connection_info":{{"type":"direct","host":"10.0.0.1","port":{port}}}}}}}}}"#,
+        id = test_uuid(3),
+    )
+}
+
+#[test]
+fn max_players_above_u8_is_clamped() {
+    let message: ServerMessage =
+        serde_json::from_str(&room_joined_with_max_players("300")).unwrap();
+    let ServerMessage::RoomJoined(payload) = message else {
+        panic!("expected RoomJoined");
+    };
+    assert_eq!(payload.max_players, u8::MAX);
+    assert_eq!(payload.room_code, "BIG");
+
+    let message: ServerMessage = serde_json::from_str(&room_joined_with_max_players("-3")).unwrap();
+    let ServerMessage::RoomJoined(payload) = message else {
+        panic!("expected RoomJoined");
+    };
+    assert_eq!(payload.max_players, 0);
+}
+
+#[test]
+fn max_players_that_is_not_an_integer_still_fails() {
+    let json = room_joined_with_max_players("4.5");
+    let error = serde_json::from_str::<ServerMessage>(&json).unwrap_err();
+    assert!(error.to_string().contains("an integer"), "{error}");
+}
+
+#[test]
+fn negative_rate_limit_counts_decode_as_unlimited() {
+    let json = r#"{"type":"Authenticated","data":{"app_name":"a","rate_limits":{"per_minute":-1,"per_hour":5000000000,"per_day":10}}}"#;
+    let ServerMessage::Authenticated { rate_limits, .. } = serde_json::from_str(json).unwrap()
+    else {
+        panic!("expected Authenticated");
+    };
+    assert_eq!(
+        rate_limits,
+        RateLimitInfo {
+            per_minute: RateLimitInfo::UNLIMITED,
+            per_hour: u32::MAX,
+            per_day: 10,
+        }
+    );
+    assert_eq!(round_trip(&rate_limits), rate_limits);
+}
+
+#[test]
+fn connection_info_with_an_unusable_port_is_dropped() {
+    for port in ["0", "70000", "-1"] {
+        let message: ServerMessage = serde_json::from_str(&player_joined_with_port(port)).unwrap();
+        let ServerMessage::PlayerJoined { player } = message else {
+            panic!("expected PlayerJoined");
+        };
+        assert!(player.connection_info.is_none(), "port {port}");
+        assert_eq!(player.name, "p");
+    }
+
+    let message: ServerMessage = serde_json::from_str(&player_joined_with_port("7777")).unwrap();
+    let ServerMessage::PlayerJoined { player } = message else {
+        panic!("expected PlayerJoined");
+    };
+    assert!(matches!(
+        player.connection_info,
+        Some(ConnectionInfo::Direct { port: 7777, .. })
+    ));
+}
+
+#[test]
+fn connection_info_with_a_non_numeric_port_still_fails() {
+    let json = player_joined_with_port(r#""7777""#);
+    assert!(serde_json::from_str::<ServerMessage>(&json).is_err());
+}
@@ -0,0 +1,207 @@
+//! The documented quick-start flow, end to end.
+//!
+//! Runs the README sequence — start, `Authenticated`, `join_room`,
+//! `RoomJoined`, `shutdown` — against an in-process server that answers the
+//! client's typed messages, and pins the exact events, the exact wire
+//! messages, the final state, and a clean close. `examples/quickstart.rs` runs
+//! the same scenario, so an API change that breaks the documented flow fails
+//! here first.
+#![cfg(feature = "tokio-runtime")]
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+
+#[allow(dead_code)]
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use signal_fish_client::protocol::ClientMessage;
+use signal_fish_client::transport::TransportFrame;
+use signal_fish_client::{
+    DisconnectReason, JoinRoomParams, SignalFishClient, SignalFishConfig, SignalFishError,
+    SignalFishEvent, Transport,
+};
+use tokio::sync::mpsc;
+
+use common::{authenticated_json, room_joined_json_with};
+
+const APP_ID: &str = "mb_app_abc123";
+const ROOM_CODE: &str = "QS1234";
+const PLAYER_ID: uuid::Uuid = uuid::Uuid::from_u128(7);
+
+/// The client half of an in-process connection.
+struct LoopbackTransport {
+    to_server: Option<mpsc::UnboundedSender<String>>,
+    from_server: mpsc::UnboundedReceiver<String>,
+    closed: Arc<AtomicBool>,
+}
+
+impl Transport for LoopbackTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        let Some(TransportFrame::Text(message)) = frame.take() else {
+            panic!("the quick start sends only text frames");
+        };
+        let sent = self
+            .to_server
+            .as_ref()
+            .is_some_and(|server| server.send(message).is_ok());
+        std::task::Poll::Ready(if sent {
+            Ok(())
+        } else {
+            Err(SignalFishError::TransportClosed)
+        })
+    }
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        self.from_server
+            .poll_recv(cx)
+            .map(|message| message.map(|message| Ok(TransportFrame::Text(message))))
+    }
+
+    fn poll_close(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SignalFishError>> {
+        self.to_server = None;
+        self.closed.store(true, Ordering::Relaxed);
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// Answer `Authenticate` and `JoinRoom` until the client hangs up, returning
+/// every message the client sent, decoded.
+async fn serve(
+    mut from_client: mpsc::UnboundedReceiver<String>,
+    to_client: mpsc::UnboundedSender<String>,
+) -> Vec<ClientMessage> {
+    let mut wire = Vec::new();
+    while let Some(frame) = from_client.recv().await {
+        let message: ClientMessage =
+            serde_json::from_str(&frame).unwrap_or_else(|err| panic!("{frame}: {err}"));
+        let reply = match &message {
+            ClientMessage::Authenticate { .. } => Some(authenticated_json()),
+            ClientMessage::JoinRoom { game_name, .. } => {
+                Some(room_joined_json_with(ROOM_CODE, game_name, PLAYER_ID))
+            }
+            _ => None,
+        };
+        wire.push(message);
+        if let Some(reply) = reply {
+            let _ = to_client.send(reply);
+        }
+    }
+    wire
+}
+
+#[tokio::test]
+async fn quickstart_flow_runs_end_to_end() {
+    let (to_server, from_client) = mpsc::unbounded_channel();
+    let (to_client, from_server) = mpsc::unbounded_channel();
+    let closed = Arc::new(AtomicBool::new(false));
+    let server = tokio::spawn(serve(from_client, to_client));
+    let transport = LoopbackTransport {
+        to_server: Some(to_server),
+        from_server,
+        closed: Arc::clone(&closed),
+    };
+
+    // The README quick start, with `shutdown` once the room is joined.
+    let config = SignalFishConfig::new(APP_ID);
+    let (mut client, mut event_rx) = SignalFishClient::start(transport, config);
+    let mut seen = Vec::new();
+    let mut state_in_room = None;
+    while let Some(event) = event_rx.recv().await {
+        seen.push(format!("{event:?}"));
+        match event {
+            SignalFishEvent::Authenticated { app_name, .. } => {
+                assert_eq!(app_name, "test-app");
+                client
+                    .join_room(JoinRoomParams::new("my-game", "Alice"))
+                    .expect("join_room after Authenticated");
+            }
+            SignalFishEvent::RoomJoined { room_code, .. } => {
+                assert_eq!(room_code, ROOM_CODE);
+                state_in_room = Some((
+                    client.is_authenticated(),
+                    client.current_room_code().await,
+                    client.current_player_id().await,
+                ));
+                client.shutdown().await;
+            }
+            SignalFishEvent::Disconnected {
+                reason,
+                last_server_error,
+            } => {
+                assert_eq!(reason, DisconnectReason::ClientShutdown);
+                assert!(last_server_error.is_none());
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        seen,
+        ["Connected", "Authenticated", "RoomJoined", "Disconnected"]
+    );
+    assert!(
+        event_rx.recv().await.is_none(),
+        "Disconnected is the last event"
+    );
+    assert_eq!(
+        state_in_room,
+        Some((true, Some(ROOM_CODE.to_owned()), Some(PLAYER_ID)))
+    );
+
+    let wire = server.await.expect("server task");
+    let [ClientMessage::Authenticate {
+        app_id,
+        sdk_version,
+        platform,
+        game_data_format,
+        protocol_version,
+        supported_transports,
+        supported_topologies,
+    }, ClientMessage::JoinRoom {
+        game_name,
+        room_code,
+        player_name,
+        max_players,
+        supports_authority,
+        relay_transport,
+    }] = wire.as_slice()
+    else {
+        panic!("expected exactly Authenticate then JoinRoom, got {wire:?}");
+    };
+    assert_eq!(app_id, APP_ID);
+    assert_eq!(sdk_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+    assert_eq!(platform, &None);
+    assert_eq!(game_data_format, &None);
+    assert_eq!(protocol_version, &None);
+    assert!(supported_transports.is_none() && supported_topologies.is_none());
+    assert_eq!(game_name, "my-game");
+    assert_eq!(player_name, "Alice");
+    assert_eq!(room_code, &None);
+    assert_eq!(max_players, &None);
+    assert_eq!(supports_authority, &None);
+    assert!(relay_transport.is_none());
+
+    assert!(closed.load(Ordering::Relaxed), "transport closed");
+    assert!(!client.is_connected());
+    assert!(!client.is_authenticated());
+    assert!(client.current_room_code().await.is_none());
+    assert!(client.task_id().is_none(), "shutdown reaped the loop task");
+    assert_eq!(client.stats().messages_undecodable, 0);
+}
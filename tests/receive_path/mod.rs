@@ -0,0 +1,222 @@
+//! Receive-path harness shared by `tests/receive_path_fuzz_smoke.rs` and the
+//! `fuzz_receive_path` cargo-fuzz target.
+//!
+//! [`run`] feeds a [`SignalFishPollingClient`] the frames encoded in an
+//! arbitrary input, drives it until the transport ends or [`MAX_POLLS`]
+//! cycles pass, and panics if an invariant breaks. The polling client shares
+//! its receive handling with the async client's transport loop, so this
+//! covers both without a runtime.
+//!
+//! Input format: frames separated by `\n`. A frame starting with `0x00` is a
+//! binary frame holding the rest of the line; any other frame is a text
+//! frame, decoded lossily as UTF-8. Serialized JSON never contains a raw
+//! newline, so a corpus file is one fixture per line. The files under
+//! `fuzz/seeds/fuzz_receive_path` are [`seed_corpus`], one input per file.
+
+#![allow(
+    dead_code,
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+
+use std::collections::VecDeque;
+use std::task::{Context, Poll};
+
+use signal_fish_client::protocol::{LobbyState, ServerMessage};
+use signal_fish_client::testing;
+use signal_fish_client::transport::{Transport, TransportFrame};
+use signal_fish_client::{
+    SignalFishConfig, SignalFishError, SignalFishEvent, SignalFishPollingClient,
+    STATE_HISTORY_CAPACITY,
+};
+
+/// Poll cycles before the harness stops driving the client.
+pub const MAX_POLLS: usize = 256;
+
+/// Records kept by the client's room history during a run.
+const ROOM_HISTORY_LIMIT: usize = 4;
+
+/// In-memory transport that replays scripted frames, then reports closed.
+struct ScriptedTransport {
+    incoming: VecDeque<TransportFrame>,
+    sent: usize,
+}
+
+impl Transport for ScriptedTransport {
+    fn poll_send(
+        &mut self,
+        _cx: &mut Context<'_>,
+        frame: &mut Option<TransportFrame>,
+    ) -> Poll<Result<(), SignalFishError>> {
+        if frame.take().is_some() {
+            self.sent += 1;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_recv(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TransportFrame, SignalFishError>>> {
+        Poll::Ready(self.incoming.pop_front().map(Ok))
+    }
+
+    fn poll_close(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), SignalFishError>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Split `input` into transport frames.
+pub fn frames(input: &[u8]) -> Vec<TransportFrame> {
+    input
+        .split(|&byte| byte == b'\n')
+        .map(|line| match line.split_first() {
+            Some((0, payload)) => TransportFrame::Binary(payload.to_vec()),
+            _ => TransportFrame::Text(String::from_utf8_lossy(line).into_owned()),
+        })
+        .collect()
+}
+
+/// Encode `frames` in the harness input format.
+pub fn encode(frames: &[TransportFrame]) -> Vec<u8> {
+    let mut input = Vec::new();
+    for (index, frame) in frames.iter().enumerate() {
+        if index > 0 {
+            input.push(b'\n');
+        }
+        match frame {
+            TransportFrame::Text(text) => input.extend_from_slice(text.as_bytes()),
+            TransportFrame::Binary(payload) => {
+                input.push(0);
+                input.extend_from_slice(payload);
+            }
+        }
+    }
+    input
+}
+
+/// Feed `input` through the receive path and check the invariants.
+pub fn run(input: &[u8]) {
+    let frames = frames(input);
+    let frame_count = frames.len();
+    let transport = ScriptedTransport {
+        incoming: frames.into(),
+        sent: 0,
+    };
+    let config = SignalFishConfig::new("mb_fuzz")
+        .enable_v3()
+        .with_room_history(ROOM_HISTORY_LIMIT);
+    let mut client = SignalFishPollingClient::new(transport, config);
+
+    let mut events = 0usize;
+    let mut disconnects = 0usize;
+    for _ in 0..MAX_POLLS {
+        let batch = client.poll();
+        events += batch.len();
+        disconnects += batch
+            .iter()
+            .filter(|event| matches!(event, SignalFishEvent::Disconnected { .. }))
+            .count();
+        assert!(disconnects <= 1, "Disconnected emitted {disconnects} times");
+        check_state(&client);
+        if disconnects == 1 {
+            assert!(!client.is_connected(), "connected after Disconnected");
+            assert!(
+                !client.is_authenticated(),
+                "authenticated after Disconnected"
+            );
+            assert!(
+                client.room_snapshot().is_none(),
+                "room kept after Disconnected"
+            );
+        }
+        if !client.is_connected() && batch.is_empty() {
+            break;
+        }
+    }
+
+    // Every event stems from a frame or a connection change, and a frame of
+    // n bytes holds at most n messages.
+    assert!(
+        events <= input.len() + 4 * frame_count + 8,
+        "{events} events from {frame_count} frames of {} bytes",
+        input.len()
+    );
+}
+
+fn check_state(client: &SignalFishPollingClient<ScriptedTransport>) {
+    let snapshot = client.snapshot();
+    assert_eq!(
+        snapshot.room_id.is_some(),
+        snapshot.room_code.is_some(),
+        "room_id {:?} with room_code {:?}",
+        snapshot.room_id,
+        snapshot.room_code
+    );
+    if let Some(room) = client.room_snapshot() {
+        assert_eq!(snapshot.room_id, room.room_id);
+        assert_eq!(snapshot.room_code, room.room_code);
+    }
+    if client.is_authenticated() {
+        assert!(client.is_connected(), "authenticated while disconnected");
+    }
+    assert!(client.state_history().len() <= STATE_HISTORY_CAPACITY);
+    assert!(client.room_history().len() <= ROOM_HISTORY_LIMIT);
+}
+
+/// A full session built from the fixture builders, plus each message alone.
+pub fn seed_corpus() -> Vec<Vec<u8>> {
+    let alice = testing::player("Alice").build();
+    let bob = testing::player("Bob").build();
+    let room = testing::room_joined("FUZZ01")
+        .players([alice.clone(), bob.clone()])
+        .lobby_state(LobbyState::Lobby);
+    let text = |message: &ServerMessage| {
+        TransportFrame::Text(serde_json::to_string(message).expect("serialize fixture"))
+    };
+    let authenticated = text(&ServerMessage::Authenticated {
+        app_name: "fuzz".into(),
+        organization: Some("acme".into()),
+        rate_limits: signal_fish_client::protocol::RateLimitInfo {
+            per_minute: 60,
+            per_hour: 1000,
+            per_day: 10000,
+        },
+    });
+    let session = vec![
+        authenticated.clone(),
+        TransportFrame::Text(room.json()),
+        text(&ServerMessage::PlayerJoined {
+            player: testing::player("Cara").build(),
+        }),
+        text(&ServerMessage::GameData {
+            from_player: bob.id,
+            data: serde_json::json!({"x": 1, "y": [2, 3]}),
+            seq: None,
+            epoch: None,
+            class: None,
+            key: None,
+        }),
+        text(&ServerMessage::PlayerLeft {
+            player_id: bob.id,
+            epoch: None,
+            final_seq: None,
+        }),
+        text(&ServerMessage::Pong),
+        text(&ServerMessage::RoomLeft),
+    ];
+    let mut corpus = vec![encode(&session)];
+    corpus.extend(
+        session
+            .iter()
+            .skip(1)
+            .map(|frame| encode(&[authenticated.clone(), frame.clone()])),
+    );
+    corpus.push(encode(&[
+        authenticated,
+        TransportFrame::Binary(vec![0x92, 0x01, 0xa2, b'h', b'i']),
+    ]));
+    corpus
+}
@@ -0,0 +1,87 @@
+//! Smoke run of the `fuzz_receive_path` target under `cargo test`.
+//!
+//! Feeds the seed corpus, then a fixed set of deterministic mutations of it,
+//! through the same harness the cargo-fuzz target uses, so CI without nightly
+//! still exercises the receive path with garbage mixed into valid sessions.
+#![cfg(feature = "polling-client")]
+#![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+mod receive_path;
+
+/// Mutated inputs generated from each seed.
+const MUTATIONS_PER_SEED: usize = 64;
+
+/// xorshift64: deterministic, so a failure reproduces on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        usize::try_from(self.next() % bound.max(1) as u64).unwrap()
+    }
+}
+
+fn mutate(rng: &mut Rng, seed: &[u8], other: &[u8]) -> Vec<u8> {
+    let mut input = seed.to_vec();
+    for _ in 0..=rng.below(4) {
+        let at = rng.below(input.len() + 1);
+        match rng.below(6) {
+            0 if at < input.len() => input[at] ^= 1 << rng.below(8),
+            1 => input.truncate(at),
+            2 => input.insert(at, rng.next() as u8),
+            3 => input.insert(at, b'\n'),
+            4 => {
+                let from = rng.below(other.len() + 1);
+                input.splice(at..at, other[from..].iter().copied());
+            }
+            _ => {
+                let end = (at + rng.below(16)).min(input.len());
+                input.drain(at..end);
+            }
+        }
+    }
+    input
+}
+
+#[test]
+fn seed_corpus_passes_the_receive_path_invariants() {
+    for seed in receive_path::seed_corpus() {
+        receive_path::run(&seed);
+    }
+}
+
+#[test]
+fn mutated_seeds_pass_the_receive_path_invariants() {
+    let corpus = receive_path::seed_corpus();
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for (index, seed) in corpus.iter().enumerate() {
+        let other = &corpus[(index + 1) % corpus.len()];
+        for _ in 0..MUTATIONS_PER_SEED {
+            receive_path::run(&mutate(&mut rng, seed, other));
+        }
+    }
+}
+
+#[test]
+fn arbitrary_bytes_pass_the_receive_path_invariants() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..256 {
+        let len = rng.below(512);
+        let input: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        receive_path::run(&input);
+    }
+}
+
+#[test]
+fn frames_round_trip_through_the_input_format() {
+    for seed in receive_path::seed_corpus() {
+        let frames = receive_path::frames(&seed);
+        assert_eq!(receive_path::encode(&frames), seed);
+    }
+}
@@ -32,7 +32,7 @@ fn both_drivers_delegate_inbound_frames_to_client_core() {
             panic!("failed to read {path}: {error}");
         });
         assert!(
-            source.contains(".process_frame(frame)"),
+            source.contains(".process_frame(frame)") || source.contains(".apply_frame(frame)"),
             "{path} must delegate inbound frames to ClientCore"
         );
         for forbidden in [
@@ -0,0 +1,37 @@
+//! Every `SignalFishError` variant and no wildcard: rejected outside the
+//! crate, and only for the missing `_`.
+
+use signal_fish_client::SignalFishError;
+
+fn retryable(error: &SignalFishError) -> bool {
+    match error {
+        SignalFishError::Timeout => true,
+        SignalFishError::TransportSend(..)
+        | SignalFishError::TransportReceive(..)
+        | SignalFishError::TransportClosed
+        | SignalFishError::Serialization(..)
+        | SignalFishError::NotConnected
+        | SignalFishError::SendBufferFull { .. }
+        | SignalFishError::NotInRoom
+        | SignalFishError::ServerError { .. }
+        | SignalFishError::ProtocolUnsupported { .. }
+        | SignalFishError::BinaryFormatNotNegotiated
+        | SignalFishError::NotRuntimeTunable { .. }
+        | SignalFishError::MaxPlayersExceeded { .. }
+        | SignalFishError::InvalidPlayerName(..)
+        | SignalFishError::Draining
+        | SignalFishError::ReconnectInFlight
+        | SignalFishError::TransportReused
+        | SignalFishError::RoomJoinFailed { .. }
+        | SignalFishError::AuthenticationFailed { .. }
+        | SignalFishError::MessageTooLarge { .. }
+        | SignalFishError::InvalidRequest(..)
+        | SignalFishError::TlsHandshake(..)
+        | SignalFishError::ConnectTimeout { .. }
+        | SignalFishError::Io(..) => false,
+    }
+}
+
+fn main() {
+    let _ = retryable(&SignalFishError::Timeout);
+}
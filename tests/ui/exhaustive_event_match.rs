@@ -0,0 +1,62 @@
+//! Every `SignalFishEvent` variant and no wildcard: rejected outside the
+//! crate, and only for the missing `_`.
+
+use signal_fish_client::SignalFishEvent;
+
+fn is_terminal(event: &SignalFishEvent) -> bool {
+    match event {
+        SignalFishEvent::Disconnected { .. } => true,
+        SignalFishEvent::Connected
+        | SignalFishEvent::Reconnecting { .. }
+        | SignalFishEvent::ClientStalled { .. }
+        | SignalFishEvent::MessageExpired { .. }
+        | SignalFishEvent::DecodeFailed { .. }
+        | SignalFishEvent::UnknownMessage { .. }
+        | SignalFishEvent::ProtocolViolation { .. }
+        | SignalFishEvent::SessionInvalidated { .. }
+        | SignalFishEvent::RemovedFromRoom { .. }
+        | SignalFishEvent::SelfRemovalPending { .. }
+        | SignalFishEvent::SessionInconsistency { .. }
+        | SignalFishEvent::OrganizationMismatch { .. }
+        | SignalFishEvent::FieldOutOfRange { .. }
+        | SignalFishEvent::PayloadSalvaged { .. }
+        | SignalFishEvent::Authenticated { .. }
+        | SignalFishEvent::ProtocolInfo(..)
+        | SignalFishEvent::AuthenticationError { .. }
+        | SignalFishEvent::RoomJoined { .. }
+        | SignalFishEvent::RoomJoinFailed { .. }
+        | SignalFishEvent::RoomLeft
+        | SignalFishEvent::PlayerJoined { .. }
+        | SignalFishEvent::PlayerLeft { .. }
+        | SignalFishEvent::GameData { .. }
+        | SignalFishEvent::GameDataBinary { .. }
+        | SignalFishEvent::AuthorityChanged { .. }
+        | SignalFishEvent::AuthorityResponse { .. }
+        | SignalFishEvent::LobbyStateChanged { .. }
+        | SignalFishEvent::GameStarting { .. }
+        | SignalFishEvent::SessionPlan { .. }
+        | SignalFishEvent::NewPeer { .. }
+        | SignalFishEvent::SignalReceived { .. }
+        | SignalFishEvent::PeerTransportStatus { .. }
+        | SignalFishEvent::RelayStats { .. }
+        | SignalFishEvent::GoingAway { .. }
+        | SignalFishEvent::DeliveryReport(..)
+        | SignalFishEvent::Pong
+        | SignalFishEvent::TimeSyncResponse { .. }
+        | SignalFishEvent::Reconnected { .. }
+        | SignalFishEvent::MissedEventsGap { .. }
+        | SignalFishEvent::ReconnectionFailed { .. }
+        | SignalFishEvent::PlayerReconnected { .. }
+        | SignalFishEvent::SpectatorJoined { .. }
+        | SignalFishEvent::SpectatorJoinFailed { .. }
+        | SignalFishEvent::SpectatorLeft { .. }
+        | SignalFishEvent::NewSpectatorJoined { .. }
+        | SignalFishEvent::SpectatorDisconnected { .. }
+        | SignalFishEvent::Announcement { .. }
+        | SignalFishEvent::Error { .. } => false,
+    }
+}
+
+fn main() {
+    let _ = is_terminal(&SignalFishEvent::Connected);
+}
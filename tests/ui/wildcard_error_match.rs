@@ -0,0 +1,16 @@
+//! A wildcard arm keeps a `SignalFishError` match compiling as variants are
+//! added.
+
+use signal_fish_client::SignalFishError;
+
+fn retryable(error: &SignalFishError) -> bool {
+    match error {
+        SignalFishError::Timeout | SignalFishError::TransportClosed => true,
+        _ => false,
+    }
+}
+
+fn main() {
+    assert!(retryable(&SignalFishError::Timeout));
+    assert!(!retryable(&SignalFishError::NotConnected));
+}
@@ -0,0 +1,15 @@
+//! A wildcard arm keeps a `SignalFishEvent` match compiling as variants are
+//! added.
+
+use signal_fish_client::SignalFishEvent;
+
+fn is_terminal(event: &SignalFishEvent) -> bool {
+    match event {
+        SignalFishEvent::Disconnected { .. } => true,
+        _ => false,
+    }
+}
+
+fn main() {
+    assert!(!is_terminal(&SignalFishEvent::Connected));
+}
@@ -0,0 +1,330 @@
+//! Player and room names outside ASCII, end to end.
+//!
+//! One matrix of names — precomposed and combining accents, CJK, emoji ZWJ
+//! sequences and flags, right-to-left scripts — goes through JSON
+//! serialization, [`PlayerNameRulesPayload::validate`] (which counts `char`s,
+//! not bytes or graphemes), the newline-delimited TCP framing with segments
+//! cut mid-character, and the client's bounded diagnostic text. Every name
+//! must come back byte for byte.
+#![cfg(feature = "tokio-runtime")]
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
+
+#[allow(dead_code)]
+mod common;
+
+use signal_fish_client::protocol::{
+    ClientMessage, PlayerNameRulesPayload, PlayerNameViolation, ServerMessage,
+};
+use signal_fish_client::{
+    testing, JoinRoomParams, SignalFishClient, SignalFishConfig, SignalFishError, SignalFishEvent,
+    DECODE_FAILED_RAW_PREFIX_MAX,
+};
+
+use common::{authenticated_json, protocol_info_payload, suite_config, MockTransport};
+
+/// Each name with its length in `char`s.
+const NAMES: &[(&str, usize)] = &[
+    ("Asa", 3),
+    // Precomposed Å, then A followed by a combining ring above.
+    ("\u{c5}sa", 3),
+    ("A\u{30a}sa", 4),
+    ("小明", 2),
+    ("Zoë 🎮", 5),
+    // Man, woman, girl joined by two zero-width joiners: one grapheme.
+    ("👨\u{200d}👩\u{200d}👧", 5),
+    // Regional indicators S and E: the Swedish flag, one grapheme.
+    ("🇸🇪", 2),
+    ("שלום", 4),
+    ("مرحبا بك", 8),
+    // Right-to-left Hebrew inside left-to-right text.
+    ("abc אבג def", 11),
+    ("नमस्ते", 6),
+];
+
+/// Permissive rules whose length bounds are under test.
+fn rules(min_length: usize, max_length: usize) -> PlayerNameRulesPayload {
+    PlayerNameRulesPayload {
+        max_length,
+        min_length,
+        allow_unicode_alphanumeric: true,
+        allow_spaces: true,
+        allow_leading_trailing_whitespace: false,
+        allowed_symbols: vec![],
+        additional_allowed_characters: Some("\u{30a}\u{200d}👨👩👧🎮🇸🇪\u{94d}\u{947}".into()),
+    }
+}
+
+#[test]
+fn names_round_trip_byte_exact_through_json() {
+    for (name, _) in NAMES {
+        let join = ClientMessage::JoinRoom {
+            game_name: (*name).to_owned(),
+            room_code: Some((*name).to_owned()),
+            player_name: (*name).to_owned(),
+            max_players: None,
+            supports_authority: None,
+            relay_transport: None,
+        };
+        let wire = serde_json::to_string(&join).unwrap();
+        let ClientMessage::JoinRoom {
+            game_name,
+            room_code,
+            player_name,
+            ..
+        } = serde_json::from_str(&wire).unwrap()
+        else {
+            panic!("JoinRoom must decode as JoinRoom");
+        };
+        assert_eq!(player_name.as_bytes(), name.as_bytes(), "{name}");
+        assert_eq!(game_name.as_bytes(), name.as_bytes(), "{name}");
+        assert_eq!(room_code.as_deref(), Some(*name));
+
+        let joined = testing::room_joined(*name)
+            .game_name(*name)
+            .players([testing::player(*name)]);
+        let ServerMessage::RoomJoined(payload) = serde_json::from_str(&joined.json()).unwrap()
+        else {
+            panic!("RoomJoined must decode as RoomJoined");
+        };
+        assert_eq!(payload.room_code.as_bytes(), name.as_bytes());
+        assert_eq!(payload.game_name.as_bytes(), name.as_bytes());
+        assert_eq!(payload.current_players[0].name.as_bytes(), name.as_bytes());
+    }
+}
+
+#[test]
+fn validation_counts_chars_at_the_length_boundaries() {
+    for (name, length) in NAMES {
+        assert_eq!(name.chars().count(), *length, "{name}");
+        assert_eq!(
+            rules(*length, *length).validate(name),
+            Ok(()),
+            "{name} is exactly {length} chars"
+        );
+        assert_eq!(
+            rules(0, length - 1).validate(name),
+            Err(PlayerNameViolation::TooLong {
+                length: *length,
+                max: length - 1,
+            }),
+            "{name} is one char over"
+        );
+        assert_eq!(
+            rules(length + 1, 64).validate(name),
+            Err(PlayerNameViolation::TooShort {
+                length: *length,
+                min: length + 1,
+            }),
+            "{name} is one char under"
+        );
+    }
+}
+
+#[test]
+fn validation_checks_each_char_of_a_grapheme() {
+    let strict = PlayerNameRulesPayload {
+        additional_allowed_characters: None,
+        ..rules(1, 16)
+    };
+    assert_eq!(strict.validate("\u{c5}sa"), Ok(()));
+    assert_eq!(
+        strict.validate("A\u{30a}sa"),
+        Err(PlayerNameViolation::DisallowedCharacter('\u{30a}'))
+    );
+    assert_eq!(
+        strict.validate("👨\u{200d}👩\u{200d}👧"),
+        Err(PlayerNameViolation::DisallowedCharacter('👨'))
+    );
+    assert_eq!(strict.validate("שלום"), Ok(()));
+
+    let ascii = PlayerNameRulesPayload {
+        allow_unicode_alphanumeric: false,
+        ..strict.clone()
+    };
+    assert_eq!(
+        ascii.validate("שלום"),
+        Err(PlayerNameViolation::DisallowedCharacter('ש'))
+    );
+    assert_eq!(
+        strict.validate("\u{3000}小明"),
+        Err(PlayerNameViolation::SurroundingWhitespace),
+        "ideographic space is whitespace"
+    );
+}
+
+#[test]
+fn violation_display_names_the_character() {
+    let error =
+        SignalFishError::InvalidPlayerName(PlayerNameViolation::DisallowedCharacter('\u{200d}'));
+    assert_eq!(
+        error.to_string(),
+        "player name rejected by this server's rules: \
+         name contains the disallowed character '\\u{200d}'"
+    );
+    let too_long = PlayerNameViolation::TooLong { length: 5, max: 4 };
+    assert_eq!(
+        too_long.to_string(),
+        "name is 5 characters, over the maximum of 4"
+    );
+}
+
+/// A client authenticated against a server advertising `name_rules`.
+async fn client_with_rules(
+    name_rules: PlayerNameRulesPayload,
+) -> (
+    SignalFishClient,
+    tokio::sync::mpsc::Receiver<SignalFishEvent>,
+) {
+    let mut protocol_info = protocol_info_payload(None);
+    protocol_info.player_name_rules = Some(name_rules);
+    let (transport, _sent, _closed) = MockTransport::new(vec![
+        Some(Ok(authenticated_json())),
+        Some(Ok(serde_json::to_string(&ServerMessage::ProtocolInfo(
+            protocol_info,
+        ))
+        .unwrap())),
+    ]);
+    let (client, mut events) =
+        SignalFishClient::start(transport, suite_config(SignalFishConfig::new("app")));
+    loop {
+        match events
+            .recv()
+            .await
+            .expect("the client to report ProtocolInfo")
+        {
+            SignalFishEvent::ProtocolInfo(_) => return (client, events),
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+async fn join_room_checks_the_advertised_name_rules() {
+    let (mut client, _events) = client_with_rules(rules(1, 5)).await;
+
+    let error = client
+        .join_room(JoinRoomParams::new("g", "👨\u{200d}👩\u{200d}👧🎮"))
+        .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            SignalFishError::InvalidPlayerName(PlayerNameViolation::TooLong { length: 6, max: 5 })
+        ),
+        "{error}"
+    );
+    client
+        .join_room(JoinRoomParams::new("g", "👨\u{200d}👩\u{200d}👧"))
+        .expect("a five-char name fits five chars, whatever its byte length");
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn decode_failure_prefix_stops_before_a_split_character() {
+    let family = "👨\u{200d}👩\u{200d}👧";
+    // The family starts two bytes before the prefix limit.
+    let frame = format!("{}{family}", "x".repeat(DECODE_FAILED_RAW_PREFIX_MAX - 2));
+    let (transport, _sent, _closed) = MockTransport::new(vec![Some(Ok(frame.clone()))]);
+    let (mut client, mut events) =
+        SignalFishClient::start(transport, suite_config(SignalFishConfig::new("app")));
+
+    let raw_prefix = loop {
+        match events.recv().await.expect("a DecodeFailed event") {
+            SignalFishEvent::DecodeFailed { raw_prefix, .. } => break raw_prefix,
+            _ => continue,
+        }
+    };
+    assert_eq!(raw_prefix.len(), DECODE_FAILED_RAW_PREFIX_MAX - 2);
+    assert!(frame.starts_with(&raw_prefix));
+    client.shutdown().await;
+}
+
+#[cfg(feature = "transport-tcp")]
+#[tokio::test]
+async fn names_survive_tcp_segments_cut_mid_character() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use signal_fish_client::TcpTransport;
+
+    let names: Vec<&str> = NAMES.iter().map(|(name, _)| *name).collect();
+    let local = "👨\u{200d}👩\u{200d}👧";
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let roster = names.clone();
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        socket.set_nodelay(true).unwrap();
+        let (read, mut write) = socket.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let authenticate = lines.next_line().await.unwrap().unwrap();
+        assert!(authenticate.starts_with(r#"{"type":"Authenticate""#));
+        write
+            .write_all(format!("{}\n", authenticated_json()).as_bytes())
+            .await
+            .unwrap();
+
+        let join = lines.next_line().await.unwrap().unwrap();
+        let ClientMessage::JoinRoom { player_name, .. } = serde_json::from_str(&join).unwrap()
+        else {
+            panic!("expected JoinRoom, got {join}");
+        };
+        assert_eq!(player_name.as_bytes(), local.as_bytes());
+
+        let joined = testing::room_joined("小明")
+            .player_id(testing::player_id(&player_name))
+            .players(roster.iter().map(|name| testing::player(*name)))
+            .json();
+        let line = format!("{joined}\n");
+        // Cut a segment inside every multi-byte character.
+        let mut start = 0;
+        for cut in (1..line.len()).filter(|index| !line.is_char_boundary(*index)) {
+            write.write_all(&line.as_bytes()[start..cut]).await.unwrap();
+            write.flush().await.unwrap();
+            start = cut;
+            if cut % 7 == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+        }
+        write.write_all(&line.as_bytes()[start..]).await.unwrap();
+        write.flush().await.unwrap();
+        // Hold the connection until the client closes it.
+        while lines.next_line().await.unwrap().is_some() {}
+    });
+
+    let transport = TcpTransport::connect(addr).await.unwrap();
+    let (mut client, mut events) =
+        SignalFishClient::start(transport, suite_config(SignalFishConfig::new("app")));
+    loop {
+        match events.recv().await.expect("Authenticated") {
+            SignalFishEvent::Authenticated { .. } => break,
+            _ => continue,
+        }
+    }
+    client.join_room(JoinRoomParams::new("g", local)).unwrap();
+    let players = loop {
+        match tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .expect("RoomJoined within five seconds")
+            .expect("RoomJoined")
+        {
+            SignalFishEvent::RoomJoined {
+                room_code,
+                current_players,
+                ..
+            } => {
+                assert_eq!(room_code, "小明");
+                break current_players;
+            }
+            _ => continue,
+        }
+    };
+    let received: Vec<&str> = players.iter().map(|player| player.name.as_str()).collect();
+    assert_eq!(received, names);
+    client.shutdown().await;
+    server.await.unwrap();
+}